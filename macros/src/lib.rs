@@ -0,0 +1,124 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, TokenStream as TokenStream2, TokenTree};
+use quote::{quote, ToTokens};
+use syn::{parse_macro_input, spanned::Spanned, Attribute, FnArg, ImplItem, ItemImpl, Pat, ReturnType, Type, Visibility};
+
+/// Export the public methods of an `impl DataGrid` block to JS through a handle type
+///
+/// `#[js_export(DataGridHandle)]` keeps the block as plain Rust (its `#[wasm_bindgen(...)]`
+/// method attributes move to the exports) and adds a same-named method on the
+/// `#[wasm_bindgen]` handle for every `pub fn`. The handle holds the grid in an
+/// `Rc<RefCell<..>>`; each export borrows it through `borrow_state()` or
+/// `borrow_state_mut()`, which throw instead of aliasing when a JS callback re-enters the
+/// grid. Constructors returning the grid type return the handle instead (via `From`).
+#[proc_macro_attribute]
+pub fn js_export(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let handle = parse_macro_input!(attr as Ident);
+    let mut block = parse_macro_input!(item as ItemImpl);
+    match exports(&handle, &mut block) {
+        Ok(exports) => quote!(#block #exports).into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn exports(handle: &Ident, block: &mut ItemImpl) -> syn::Result<TokenStream2> {
+    let state = &block.self_ty;
+    let Type::Path(state_path) = &**state else {
+        return Err(syn::Error::new(state.span(), "js_export needs a named type"));
+    };
+    let state_name = state_path.path.segments.last().map(|s| s.ident.clone()).unwrap();
+    let block_cfgs: Vec<&Attribute> = block.attrs.iter().filter(|a| a.path().is_ident("cfg")).collect();
+
+    let mut methods = Vec::new();
+    for item in &mut block.items {
+        let ImplItem::Fn(method) = item else { continue };
+        if !matches!(method.vis, Visibility::Public(_)) {
+            continue;
+        }
+        let (bindgen, attrs): (Vec<Attribute>, Vec<Attribute>) =
+            method.attrs.drain(..).partition(|a| a.path().is_ident("wasm_bindgen"));
+        method.attrs = attrs.clone();
+
+        let sig = &method.sig;
+        let name = &sig.ident;
+        let mut receiver = None;
+        let mut params = Vec::new();
+        let mut args = Vec::new();
+        for input in &sig.inputs {
+            match input {
+                FnArg::Receiver(r) => receiver = Some(r.mutability.is_some()),
+                FnArg::Typed(typed) => {
+                    let Pat::Ident(pat) = &*typed.pat else {
+                        return Err(syn::Error::new(typed.pat.span(), "js_export needs plain parameter names"));
+                    };
+                    let (ident, ty) = (&pat.ident, &typed.ty);
+                    params.push(quote!(#ident: #ty));
+                    args.push(ident.clone());
+                }
+            }
+        }
+
+        let output = match &sig.output {
+            ReturnType::Default => quote!(),
+            ReturnType::Type(arrow, ty) => {
+                let ty = replace_state(ty.to_token_stream(), &state_name, handle);
+                quote!(#arrow #ty)
+            }
+        };
+        let call = match receiver {
+            Some(true) => quote!(self.borrow_state_mut().#name(#(#args),*)),
+            Some(false) => quote!(self.borrow_state().#name(#(#args),*)),
+            None => quote!(<#state>::#name(#(#args),*)),
+        };
+        // The grid itself, or a Result/Option holding it, is handed to JS as a handle
+        let body = match &sig.output {
+            ReturnType::Type(_, ty) if is_ident(ty, &state_name) => quote!(#handle::from(#call)),
+            ReturnType::Type(_, ty) if mentions(&ty.to_token_stream(), &state_name) => quote!(#call.map(#handle::from)),
+            _ => call,
+        };
+        let receiver = receiver.map(|_| quote!(&self,));
+        methods.push(quote! {
+            #(#attrs)*
+            #(#bindgen)*
+            pub fn #name(#receiver #(#params),*) #output {
+                #body
+            }
+        });
+    }
+
+    let js_class = state_name.to_string();
+    Ok(quote! {
+        #(#block_cfgs)*
+        #[::wasm_bindgen::prelude::wasm_bindgen(js_class = #js_class)]
+        impl #handle {
+            #(#methods)*
+        }
+    })
+}
+
+fn is_ident(ty: &Type, name: &Ident) -> bool {
+    matches!(ty, Type::Path(path) if path.path.is_ident(name) || path.path.is_ident("Self"))
+}
+
+fn mentions(tokens: &TokenStream2, name: &Ident) -> bool {
+    tokens.clone().into_iter().any(|tree| match tree {
+        TokenTree::Ident(ident) => ident == *name || ident == "Self",
+        TokenTree::Group(group) => mentions(&group.stream(), name),
+        _ => false,
+    })
+}
+
+fn replace_state(tokens: TokenStream2, name: &Ident, handle: &Ident) -> TokenStream2 {
+    tokens
+        .into_iter()
+        .map(|tree| match tree {
+            TokenTree::Ident(ident) if ident == *name || ident == "Self" => TokenTree::Ident(handle.clone()),
+            TokenTree::Group(group) => {
+                let mut replaced = proc_macro2::Group::new(group.delimiter(), replace_state(group.stream(), name, handle));
+                replaced.set_span(group.span());
+                TokenTree::Group(replaced)
+            }
+            other => other,
+        })
+        .collect()
+}
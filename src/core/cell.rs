@@ -9,6 +9,38 @@ pub enum DataType {
     Boolean,
 }
 
+impl DataType {
+    /// Parse a data type name ("text", "number", "date", "boolean"); unknown names are Text
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "number" => DataType::Number,
+            "date" => DataType::Date,
+            "boolean" => DataType::Boolean,
+            _ => DataType::Text,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DataType::Text => "text",
+            DataType::Number => "number",
+            DataType::Date => "date",
+            DataType::Boolean => "boolean",
+        }
+    }
+
+    /// Convert text to a value of this type: numbers that do not parse stay text, and
+    /// booleans are true for "true" or "1"
+    pub fn convert_text(&self, text: &str) -> CellValue {
+        match self {
+            DataType::Number => text.parse::<f64>().map_or_else(|_| CellValue::Text(text.to_string()), CellValue::Number),
+            DataType::Date => CellValue::Date(text.to_string()),
+            DataType::Boolean => CellValue::Boolean(text == "true" || text == "1"),
+            DataType::Text => CellValue::Text(text.to_string()),
+        }
+    }
+}
+
 /// Represents the value stored in a grid cell
 #[derive(Clone, Debug)]
 pub enum CellValue {
@@ -46,6 +78,85 @@ impl CellValue {
     pub fn is_empty(&self) -> bool {
         matches!(self, CellValue::Empty)
     }
+
+    /// Check if text is an ISO 8601 date as stored in Date values:
+    /// "YYYY-MM-DD", optionally followed by " HH:MM[:SS]" or "THH:MM[:SS]"
+    pub fn is_iso_date(text: &str) -> bool {
+        let (date, time) = match text.split_once([' ', 'T']) {
+            Some((date, time)) => (date, Some(time)),
+            None => (text, None),
+        };
+
+        let parts: Vec<&str> = date.split('-').collect();
+        let [year, month, day] = parts[..] else { return false };
+        if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return false;
+        }
+        let (Ok(year), Ok(month), Ok(day)) = (year.parse::<u32>(), month.parse::<u32>(), day.parse::<u32>()) else {
+            return false;
+        };
+        let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return false,
+        };
+        if !(1..=days_in_month).contains(&day) {
+            return false;
+        }
+
+        let Some(time) = time else { return true };
+        let fields: Vec<&str> = time.split(':').collect();
+        let limits = [24, 60, 60];
+        (2..=3).contains(&fields.len())
+            && fields.iter().zip(limits).all(|(field, limit)| {
+                field.len() == 2 && field.parse::<u32>().is_ok_and(|n| n < limit)
+            })
+    }
+
+    /// Convert to a JSON value (Empty becomes null, dates are strings)
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            CellValue::Empty => serde_json::Value::Null,
+            CellValue::Text(s) | CellValue::Date(s) => serde_json::json!(s),
+            CellValue::Number(n) => serde_json::json!(n),
+            CellValue::Boolean(b) => serde_json::json!(b),
+        }
+    }
+
+    /// Convert to a JS value for callbacks (Empty becomes null, dates are strings)
+    pub fn to_js_value(&self) -> wasm_bindgen::JsValue {
+        match self {
+            CellValue::Empty => wasm_bindgen::JsValue::NULL,
+            CellValue::Text(s) | CellValue::Date(s) => wasm_bindgen::JsValue::from_str(s),
+            CellValue::Number(n) => wasm_bindgen::JsValue::from_f64(*n),
+            CellValue::Boolean(b) => wasm_bindgen::JsValue::from_bool(*b),
+        }
+    }
+
+    /// Convert from a JSON value (null becomes Empty; objects and arrays become their JSON text)
+    pub fn from_json(json: &serde_json::Value) -> Self {
+        match json {
+            serde_json::Value::Null => CellValue::Empty,
+            serde_json::Value::String(s) => CellValue::Text(s.clone()),
+            serde_json::Value::Number(n) => CellValue::Number(n.as_f64().unwrap_or(0.0)),
+            serde_json::Value::Bool(b) => CellValue::Boolean(*b),
+            other => CellValue::Text(other.to_string()),
+        }
+    }
+
+    /// Check if a JSON value (as produced by `to_json`) refers to this value
+    /// Numbers compare numerically, so 1 matches 1.0
+    pub fn matches_json(&self, json: &serde_json::Value) -> bool {
+        match self {
+            CellValue::Empty => json.is_null(),
+            CellValue::Text(s) | CellValue::Date(s) => json.as_str() == Some(s.as_str()),
+            CellValue::Number(n) => json.as_f64() == Some(*n),
+            CellValue::Boolean(b) => json.as_bool() == Some(*b),
+        }
+    }
 }
 
 impl Default for CellValue {
@@ -60,6 +171,119 @@ impl fmt::Display for CellValue {
     }
 }
 
+/// Display format for numbers in a column (separators, decimals, currency/unit affixes)
+/// Only affects how values are shown (and copied in "display" mode); stored values stay raw
+#[derive(Clone, Debug, PartialEq)]
+pub struct NumberFormat {
+    pub decimals: Option<usize>,     // Fixed number of decimals (None = as stored)
+    pub thousands_separator: String, // Group separator ("" = no grouping)
+    pub decimal_separator: String,
+    pub prefix: String,              // e.g. "$"
+    pub suffix: String,              // e.g. " €" or " kg"
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            decimals: None,
+            thousands_separator: ",".to_string(),
+            decimal_separator: ".".to_string(),
+            prefix: String::new(),
+            suffix: String::new(),
+        }
+    }
+}
+
+impl NumberFormat {
+    /// Separators used by a locale ("en-US", "de-DE", "fr-FR", "ja-JP", ...)
+    /// Unknown locales use "," and "."
+    pub fn for_locale(locale: &str) -> Self {
+        let language = locale.split(['-', '_']).next().unwrap_or("").to_lowercase();
+        let (thousands, decimal) = match language.as_str() {
+            "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" => (".", ","),
+            "fr" | "ru" | "pl" | "cs" | "sv" | "fi" | "nb" | "uk" => ("\u{a0}", ","),
+            _ => (",", "."),
+        };
+        Self {
+            thousands_separator: thousands.to_string(),
+            decimal_separator: decimal.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Build from JSON: {"decimals", "locale", "thousands_separator", "decimal_separator",
+    /// "prefix", "suffix"}, all optional. "locale" picks the separators; explicit separators override it
+    pub fn from_json(options: &serde_json::Value) -> Self {
+        let mut format = match options["locale"].as_str() {
+            Some(locale) => Self::for_locale(locale),
+            None => Self::default(),
+        };
+        format.decimals = options["decimals"].as_u64().map(|d| d.min(20) as usize);
+        if let Some(separator) = options["thousands_separator"].as_str() {
+            format.thousands_separator = separator.to_string();
+        }
+        if let Some(separator) = options["decimal_separator"].as_str() {
+            format.decimal_separator = separator.to_string();
+        }
+        if let Some(prefix) = options["prefix"].as_str() {
+            format.prefix = prefix.to_string();
+        }
+        if let Some(suffix) = options["suffix"].as_str() {
+            format.suffix = suffix.to_string();
+        }
+        format
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "decimals": self.decimals,
+            "thousands_separator": self.thousands_separator,
+            "decimal_separator": self.decimal_separator,
+            "prefix": self.prefix,
+            "suffix": self.suffix,
+        })
+    }
+
+    /// Format a number for display
+    pub fn format(&self, value: f64) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+
+        let text = match self.decimals {
+            Some(decimals) => format!("{:.*}", decimals, value.abs()),
+            None => value.abs().to_string(),
+        };
+        let (int_part, frac_part) = match text.split_once('.') {
+            Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+            None => (text.as_str(), None),
+        };
+
+        // Group the integer digits in threes
+        let mut grouped = String::with_capacity(int_part.len() + int_part.len() / 3);
+        for (i, digit) in int_part.chars().enumerate() {
+            if i > 0 && (int_part.len() - i) % 3 == 0 {
+                grouped.push_str(&self.thousands_separator);
+            }
+            grouped.push(digit);
+        }
+
+        let negative = value < 0.0 && text.chars().any(|c| c.is_ascii_digit() && c != '0');
+        let mut result = String::new();
+        if negative {
+            result.push('-');
+        }
+        result.push_str(&self.prefix);
+        result.push_str(&grouped);
+        if let Some(frac_part) = frac_part {
+            result.push_str(&self.decimal_separator);
+            result.push_str(frac_part);
+        }
+        result.push_str(&self.suffix);
+        result
+    }
+}
+
 /// Cell border configuration for individual borders
 #[derive(Clone, Debug)]
 pub struct CellBorder {
@@ -82,6 +306,8 @@ pub struct Cell {
     pub value: CellValue,
     pub editable: bool,
     pub modified: bool, // Track if cell has been edited
+    pub original: Option<CellValue>, // Value before the first edit (set with `modified`)
+    pub validation_error: Option<String>, // Message of a failed validation kept on the cell
     pub bg_color: Option<u32>, // RGBA color as u32
     pub fg_color: Option<u32>,
     pub font_bold: bool,
@@ -94,6 +320,8 @@ impl Cell {
             value,
             editable: true,
             modified: false,
+            original: None,
+            validation_error: None,
             bg_color: None,
             fg_color: None,
             font_bold: false,
@@ -112,6 +340,20 @@ impl Cell {
     pub fn empty() -> Self {
         Self::new(CellValue::Empty)
     }
+
+    /// Flag the cell as edited, remembering `old_value` as the original on the first edit
+    pub fn mark_modified(&mut self, old_value: CellValue) {
+        if !self.modified {
+            self.modified = true;
+            self.original = Some(old_value);
+        }
+    }
+
+    /// Accept the current value as the original (clears the modified flag)
+    pub fn clear_modified(&mut self) {
+        self.modified = false;
+        self.original = None;
+    }
 }
 
 impl Default for Cell {
@@ -119,3 +361,21 @@ impl Default for Cell {
         Self::empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_iso_date() {
+        assert!(CellValue::is_iso_date("2024-02-29"));
+        assert!(CellValue::is_iso_date("2024-01-31 23:59:59"));
+        assert!(CellValue::is_iso_date("2024-01-31T08:30"));
+        assert!(!CellValue::is_iso_date("2023-02-29"));
+        assert!(!CellValue::is_iso_date("2024-13-01"));
+        assert!(!CellValue::is_iso_date("2024/01/31"));
+        assert!(!CellValue::is_iso_date("2024-1-31"));
+        assert!(!CellValue::is_iso_date("2024-01-31 24:00"));
+        assert!(!CellValue::is_iso_date("1e5"));
+    }
+}
@@ -0,0 +1,260 @@
+use super::{CellValue, Grid};
+use crate::features::locale;
+use crate::GridError;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use wasm_bindgen::{JsCast, JsValue};
+
+/// How text values of a column are ordered when sorting
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Collation {
+    #[default]
+    Ordinal,         // Code point order ("B" < "a", "item10" < "item2")
+    CaseInsensitive, // Ignore letter case
+    Natural,         // Case-insensitive, digit runs compared by value ("item2" < "item10")
+    Locale,          // Intl.Collator for the column locale (numeric collation)
+}
+
+impl Collation {
+    pub fn parse(name: &str) -> Result<Self, GridError> {
+        match name.to_lowercase().as_str() {
+            "ordinal" | "default" => Ok(Collation::Ordinal),
+            "case_insensitive" | "nocase" => Ok(Collation::CaseInsensitive),
+            "natural" => Ok(Collation::Natural),
+            "locale" => Ok(Collation::Locale),
+            other => Err(GridError::unknown_value("collation", other, &["ordinal", "case_insensitive", "natural", "locale"])),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Collation::Ordinal => "ordinal",
+            Collation::CaseInsensitive => "case_insensitive",
+            Collation::Natural => "natural",
+            Collation::Locale => "locale",
+        }
+    }
+}
+
+/// Text comparison for one sort column, built once per sort
+pub enum TextComparator {
+    Ordinal,
+    CaseInsensitive,
+    Natural,
+    Locale(js_sys::Function), // Bound Intl.Collator.prototype.compare
+}
+
+impl TextComparator {
+    /// Build the comparator for a collation; `locale` (e.g. "de", "ja-JP") is only used by
+    /// Collation::Locale (None = browser default). Fails if Intl.Collator rejects the locale.
+    pub fn new(collation: Collation, locale: Option<&str>) -> Result<Self, GridError> {
+        match collation {
+            Collation::Ordinal => Ok(TextComparator::Ordinal),
+            Collation::CaseInsensitive => Ok(TextComparator::CaseInsensitive),
+            Collation::Natural => Ok(TextComparator::Natural),
+            Collation::Locale => intl_compare(locale).map(TextComparator::Locale).map_err(|e| {
+                GridError::OperationError {
+                    message: locale::format("error.collator", &[("locale", &format!("{:?}", locale)), ("error", &format!("{:?}", e))]),
+                }
+            }),
+        }
+    }
+
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        match self {
+            TextComparator::Ordinal => a.cmp(b),
+            TextComparator::CaseInsensitive => a.to_lowercase().cmp(&b.to_lowercase()).then_with(|| a.cmp(b)),
+            TextComparator::Natural => natural_cmp(a, b),
+            TextComparator::Locale(compare) => {
+                let result = compare
+                    .call2(&JsValue::NULL, &JsValue::from_str(a), &JsValue::from_str(b))
+                    .ok()
+                    .and_then(|r| r.as_f64())
+                    .unwrap_or(0.0);
+                result.partial_cmp(&0.0).unwrap_or(Ordering::Equal)
+            }
+        }
+    }
+}
+
+/// Value comparison for one sort column: the built-in ordering (with the column's text
+/// collation), a user-defined value sequence or a JS comparator callback
+pub enum SortComparator {
+    Builtin(TextComparator),
+    Ordered(HashMap<String, usize>, TextComparator), // Rank of each listed value; others by collation
+    Custom(js_sys::Function), // (a, b) => negative / 0 / positive, like Array.prototype.sort
+}
+
+impl SortComparator {
+    /// Check if the comparator calls into JS (and may not define a total order)
+    pub fn is_custom(&self) -> bool {
+        matches!(self, SortComparator::Custom(_))
+    }
+
+    /// Sort by a list of values (e.g. ["Low", "Medium", "High"]); values not in the list
+    /// come after the listed ones, ordered by `text`
+    pub fn ordered(order: &[String], text: TextComparator) -> Self {
+        let mut ranks = HashMap::new();
+        for (rank, value) in order.iter().enumerate() {
+            ranks.entry(value.clone()).or_insert(rank);
+        }
+        SortComparator::Ordered(ranks, text)
+    }
+
+    /// Compare two values; empty cells never reach a custom callback and sort last
+    /// A callback that throws or returns a non-number treats the values as equal
+    pub fn compare(&self, a: &CellValue, b: &CellValue) -> Ordering {
+        match self {
+            SortComparator::Builtin(text) => Grid::compare_values_with(a, b, text),
+            SortComparator::Ordered(ranks, text) => {
+                match (ranks.get(&a.to_string()), ranks.get(&b.to_string())) {
+                    (Some(ra), Some(rb)) => ra.cmp(rb),
+                    (Some(_), None) if !a.is_empty() => Ordering::Less,
+                    (None, Some(_)) if !b.is_empty() => Ordering::Greater,
+                    _ => Grid::compare_values_with(a, b, text),
+                }
+            }
+            SortComparator::Custom(_) if a.is_empty() || b.is_empty() => Grid::compare_values(a, b),
+            SortComparator::Custom(callback) => {
+                let result = match callback.call2(&JsValue::NULL, &a.to_js_value(), &b.to_js_value()) {
+                    Ok(result) => result.as_f64().unwrap_or(0.0),
+                    Err(e) => {
+                        log::error!("Sort comparator failed: {:?}", e);
+                        0.0
+                    }
+                };
+                result.partial_cmp(&0.0).unwrap_or(Ordering::Equal)
+            }
+        }
+    }
+}
+
+/// Stable sort; with `tolerant` a merge sort is used that accepts comparators which do not
+/// define a total order (the standard sort may panic on those, e.g. on a JS callback that
+/// never returns 0)
+pub fn stable_sort_by<T>(items: &mut Vec<T>, tolerant: bool, mut compare: impl FnMut(&T, &T) -> Ordering) {
+    if tolerant {
+        *items = merge_sort_by(std::mem::take(items), &mut compare);
+    } else {
+        items.sort_by(compare);
+    }
+}
+
+fn merge_sort_by<T>(mut items: Vec<T>, compare: &mut impl FnMut(&T, &T) -> Ordering) -> Vec<T> {
+    if items.len() <= 1 {
+        return items;
+    }
+
+    let right = items.split_off(items.len() / 2);
+    let left = merge_sort_by(items, compare);
+    let mut right = merge_sort_by(right, compare).into_iter().peekable();
+
+    // Right items only move ahead of strictly greater left items (keeps the sort stable)
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    for item in left {
+        while let Some(next) = right.next_if(|r| compare(r, &item) == Ordering::Less) {
+            merged.push(next);
+        }
+        merged.push(item);
+    }
+    merged.extend(right);
+    merged
+}
+
+/// new Intl.Collator(locale, { numeric: true }).compare
+fn intl_compare(locale: Option<&str>) -> Result<js_sys::Function, JsValue> {
+    let intl = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("Intl"))?;
+    let constructor: js_sys::Function = js_sys::Reflect::get(&intl, &JsValue::from_str("Collator"))?.dyn_into()?;
+
+    let locales = js_sys::Array::new();
+    if let Some(locale) = locale {
+        locales.push(&JsValue::from_str(locale));
+    }
+    let options = js_sys::Object::new();
+    js_sys::Reflect::set(&options, &JsValue::from_str("numeric"), &JsValue::TRUE)?;
+
+    let collator = js_sys::Reflect::construct(&constructor, &js_sys::Array::of2(&locales, &options))?;
+    js_sys::Reflect::get(&collator, &JsValue::from_str("compare"))?.dyn_into()
+}
+
+/// Natural order: runs of digits compare by numeric value, other characters
+/// case-insensitively; ties fall back to code point order
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut ia = a.chars().peekable();
+    let mut ib = b.chars().peekable();
+
+    loop {
+        match (ia.peek().copied(), ib.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(ca), Some(cb)) if ca.is_ascii_digit() && cb.is_ascii_digit() => {
+                let da = take_digits(&mut ia);
+                let db = take_digits(&mut ib);
+                let ta = da.trim_start_matches('0');
+                let tb = db.trim_start_matches('0');
+                let cmp = ta.len().cmp(&tb.len()).then_with(|| ta.cmp(tb));
+                if cmp != Ordering::Equal {
+                    return cmp;
+                }
+            }
+            (Some(ca), Some(cb)) => {
+                let cmp = ca.to_lowercase().cmp(cb.to_lowercase());
+                if cmp != Ordering::Equal {
+                    return cmp;
+                }
+                ia.next();
+                ib.next();
+            }
+        }
+    }
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+        digits.push(c);
+    }
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_natural_cmp() {
+        let mut items = vec!["item10", "Item2", "item1", "item02", "item", "apple"];
+        items.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(items, vec!["apple", "item", "item1", "Item2", "item02", "item10"]);
+
+        assert_eq!(natural_cmp("v1.10", "v1.9"), Ordering::Greater);
+        assert_eq!(natural_cmp("a", "a"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_stable_sort_by_tolerant() {
+        let mut items = vec![(3, 'a'), (1, 'b'), (3, 'c'), (2, 'd'), (1, 'e')];
+        stable_sort_by(&mut items, true, |a, b| a.0.cmp(&b.0));
+        assert_eq!(items, vec![(1, 'b'), (1, 'e'), (2, 'd'), (3, 'a'), (3, 'c')]);
+
+        // A comparator that never reports equality must not panic
+        let mut items: Vec<u32> = (0..100).map(|i| i % 7).collect();
+        stable_sort_by(&mut items, true, |a, b| if a < b { Ordering::Less } else { Ordering::Greater });
+        assert!(items.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_ordered_comparator() {
+        let order: Vec<String> = ["Low", "Medium", "High"].iter().map(|s| s.to_string()).collect();
+        let comparator = SortComparator::ordered(&order, TextComparator::Ordinal);
+        let text = |s: &str| CellValue::Text(s.to_string());
+
+        let mut values = vec![text("High"), CellValue::Empty, text("Zzz"), text("Low"), text("Abc"), text("Medium")];
+        values.sort_by(|a, b| comparator.compare(a, b));
+        assert_eq!(
+            values,
+            vec![text("Low"), text("Medium"), text("High"), text("Abc"), text("Zzz"), CellValue::Empty]
+        );
+    }
+}
@@ -1,5 +1,195 @@
-use super::cell::{Cell, CellValue, CellBorders, DataType};
-use std::collections::{HashMap, HashSet};
+use super::cell::{Cell, CellValue, CellBorders, DataType, NumberFormat};
+use super::collation::{self, Collation, SortComparator, TextComparator};
+use crate::features::locale;
+use crate::GridError;
+use std::cell::{Ref, RefCell};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// Row condition that makes cells read-only (set_editable_rule_json)
+#[derive(Clone, Debug, PartialEq)]
+pub struct EditableRule {
+    pub column: usize,                   // Column whose value is tested
+    pub values: Vec<serde_json::Value>,  // Values the test compares against (null = empty)
+    pub negate: bool,                    // Locked when the value matches none of them instead
+    pub columns: Vec<usize>,             // Cells made read-only (empty = the whole row)
+}
+
+impl EditableRule {
+    /// Parse {"column": 3 | "status", "equals" | "not_equals": value | "in" | "not_in": [values],
+    /// "columns": [columns made read-only]} (columns by index or internal name)
+    pub fn from_json(json: &serde_json::Value, grid: &Grid) -> Result<Self, GridError> {
+        let invalid = |message: String| GridError::OperationError { message: locale::format("error.editable_rule", &[("error", &message)]) };
+        let column_of = |value: &serde_json::Value| match value {
+            serde_json::Value::Number(n) => n.as_u64().map(|c| c as usize).filter(|&c| c < grid.col_count()),
+            serde_json::Value::String(name) => grid.get_column_by_name(name),
+            _ => None,
+        };
+
+        let column = column_of(&json["column"]).ok_or_else(|| invalid(format!("unknown column {}", json["column"])))?;
+        let (values, negate) = if let Some(value) = json.get("equals") {
+            (vec![value.clone()], false)
+        } else if let Some(value) = json.get("not_equals") {
+            (vec![value.clone()], true)
+        } else if let Some(values) = json["in"].as_array() {
+            (values.clone(), false)
+        } else if let Some(values) = json["not_in"].as_array() {
+            (values.clone(), true)
+        } else {
+            return Err(invalid("expected equals, not_equals, in or not_in".to_string()));
+        };
+        let columns = match json["columns"].as_array() {
+            Some(columns) => columns
+                .iter()
+                .map(|c| column_of(c).ok_or_else(|| invalid(format!("unknown column {}", c))))
+                .collect::<Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+        Ok(Self { column, values, negate, columns })
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let key = if self.negate { "not_in" } else { "in" };
+        serde_json::json!({ "column": self.column, key: self.values, "columns": self.columns })
+    }
+
+    /// Follow a column insert, delete or move (`remap` gives a column's new index, None if
+    /// it was deleted). Returns false if the rule no longer applies: its tested column, or
+    /// every column it locks, was deleted
+    pub fn remap_columns(&mut self, remap: impl Fn(usize) -> Option<usize>) -> bool {
+        let Some(column) = remap(self.column) else {
+            return false;
+        };
+        self.column = column;
+        if self.columns.is_empty() {
+            return true;
+        }
+        self.columns = self.columns.iter().filter_map(|&col| remap(col)).collect();
+        !self.columns.is_empty()
+    }
+
+    /// Check if the rule makes (row, col) read-only
+    pub fn locks(&self, grid: &Grid, row: usize, col: usize) -> bool {
+        if !self.columns.is_empty() && !self.columns.contains(&col) {
+            return false;
+        }
+        let value = grid.get_value(row, self.column);
+        self.values.iter().any(|v| value.matches_json(v)) != self.negate
+    }
+}
+
+/// Value constraints of a column beyond its regex pattern (set_column_rules_json)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValidationRule {
+    pub required: bool,               // Empty values are invalid
+    pub min: Option<f64>,             // Numeric range (values that are not numbers are left alone)
+    pub max: Option<f64>,
+    pub max_length: Option<usize>,    // Maximum length in characters
+    pub min_date: Option<String>,     // Date range as YYYY-MM-DD (values that are not dates are left alone)
+    pub max_date: Option<String>,
+    pub allowed: Option<Vec<String>>, // The only values accepted
+    pub message: Option<String>,      // Replaces the built-in message of every check
+}
+
+/// Constraint of a ValidationRule that a value breaks
+#[derive(Clone, Debug, PartialEq)]
+pub enum RuleViolation {
+    Required,
+    Min(f64),
+    Max(f64),
+    MaxLength(usize),
+    MinDate(String),
+    MaxDate(String),
+    NotAllowed,
+}
+
+impl ValidationRule {
+    /// Parse {"required": bool, "min": n, "max": n, "max_length": n, "min_date": "YYYY-MM-DD",
+    /// "max_date": "YYYY-MM-DD", "allowed": [values], "message": "..."} (every key optional)
+    pub fn from_json(json: &serde_json::Value) -> Result<Self, GridError> {
+        let invalid = |message: String| GridError::OperationError { message: locale::format("error.validation_rule", &[("error", &message)]) };
+        let Some(object) = json.as_object() else {
+            return Err(invalid("expected a JSON object".to_string()));
+        };
+        let date = |key: &str| match &json[key] {
+            serde_json::Value::Null => Ok(None),
+            serde_json::Value::String(date) if CellValue::is_iso_date(date) && date.len() == 10 => Ok(Some(date.clone())),
+            other => Err(invalid(format!("{} must be a YYYY-MM-DD date, got {}", key, other))),
+        };
+        let number = |key: &str| match &json[key] {
+            serde_json::Value::Null => Ok(None),
+            value => value.as_f64().map(Some).ok_or_else(|| invalid(format!("{} must be a number, got {}", key, value))),
+        };
+
+        let rule = Self {
+            required: json["required"].as_bool().unwrap_or(false),
+            min: number("min")?,
+            max: number("max")?,
+            max_length: number("max_length")?.map(|n| n.max(0.0) as usize),
+            min_date: date("min_date")?,
+            max_date: date("max_date")?,
+            allowed: match &json["allowed"] {
+                serde_json::Value::Null => None,
+                serde_json::Value::Array(values) => {
+                    Some(values.iter().map(|v| v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string())).collect())
+                }
+                other => return Err(invalid(format!("allowed must be an array, got {}", other))),
+            },
+            message: json["message"].as_str().map(str::to_string),
+        };
+        if let Some(key) = object.keys().find(|key| rule.to_json().get(key.as_str()).is_none()) {
+            return Err(invalid(format!("unknown key '{}'", key)));
+        }
+        Ok(rule)
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "required": self.required,
+            "min": self.min,
+            "max": self.max,
+            "max_length": self.max_length,
+            "min_date": self.min_date,
+            "max_date": self.max_date,
+            "allowed": self.allowed,
+            "message": self.message,
+        })
+    }
+
+    /// Check a value as typed; returns the first constraint it breaks
+    pub fn check(&self, text: &str) -> Option<RuleViolation> {
+        if text.is_empty() {
+            return self.required.then_some(RuleViolation::Required);
+        }
+        if let Some(max_length) = self.max_length.filter(|&max| text.chars().count() > max) {
+            return Some(RuleViolation::MaxLength(max_length));
+        }
+        if let Some(allowed) = &self.allowed {
+            if !allowed.iter().any(|value| value == text) {
+                return Some(RuleViolation::NotAllowed);
+            }
+        }
+        if let Ok(number) = text.trim().parse::<f64>() {
+            if let Some(min) = self.min.filter(|&min| number < min) {
+                return Some(RuleViolation::Min(min));
+            }
+            if let Some(max) = self.max.filter(|&max| number > max) {
+                return Some(RuleViolation::Max(max));
+            }
+        }
+        let text = text.trim();
+        if CellValue::is_iso_date(text) {
+            // ISO dates order as text
+            let date = &text[..10];
+            if let Some(min_date) = self.min_date.as_ref().filter(|min| date < min.as_str()) {
+                return Some(RuleViolation::MinDate(min_date.clone()));
+            }
+            if let Some(max_date) = self.max_date.as_ref().filter(|max| date > max.as_str()) {
+                return Some(RuleViolation::MaxDate(max_date.clone()));
+            }
+        }
+        None
+    }
+}
 
 /// Column group for multi-level headers
 #[derive(Clone, Debug)]
@@ -43,6 +233,19 @@ pub struct ColumnConfig {
     pub filterable: bool,           // Can column be filtered
     pub validation_pattern: Option<String>,  // Regex pattern for validation (JavaScript regex syntax)
     pub validation_message: String, // Error message when validation fails
+    pub unique: bool,               // Values must be unique within the column
+    pub number_format: Option<NumberFormat>, // Display format for numbers (None = raw)
+    pub padding: Option<f32>,       // Horizontal text padding (None = grid-wide cell padding)
+    pub collation: Collation,       // Text ordering when sorting
+    pub collation_locale: Option<String>, // Locale for Collation::Locale (None = browser default)
+    pub sort_order: Option<Vec<String>>, // Values in sort order (e.g. Low, Medium, High); others go last
+    pub comparator: Option<js_sys::Function>, // JS sort callback (overrides the collation; not serialized)
+    pub validation_rule: Option<ValidationRule>, // Required/range/length/list constraints
+    pub width_mode: WidthMode,      // Fixed pixels, or a share of the leftover canvas width
+    pub flex: f32,                  // Weight of a flex column's share (default: 1)
+    pub min_width: Option<f32>,     // Narrowest width (None = the grid minimum)
+    pub max_width: Option<f32>,     // Widest width (None = unlimited)
+    pub resizable: bool,            // Can the column be resized by dragging its header edge
 }
 
 impl ColumnConfig {
@@ -58,9 +261,28 @@ impl ColumnConfig {
             filterable: true,
             validation_pattern: None,
             validation_message: String::from("入力値が正しくありません"),
+            unique: false,
+            number_format: None,
+            padding: None,
+            collation: Collation::Ordinal,
+            collation_locale: None,
+            sort_order: None,
+            comparator: None,
+            validation_rule: None,
+            width_mode: WidthMode::Fixed,
+            flex: 1.0,
+            min_width: None,
+            max_width: None,
+            resizable: true,
         }
     }
 
+    /// Width limits (min, max) for layout, never below the grid minimum
+    pub fn width_limits(&self) -> (f32, f32) {
+        let min = self.min_width.unwrap_or(MIN_COL_WIDTH).max(MIN_COL_WIDTH);
+        (min, self.max_width.unwrap_or(f32::INFINITY).max(min))
+    }
+
     pub fn with_width(mut self, width: f32) -> Self {
         self.width = width;
         self
@@ -83,6 +305,270 @@ impl ColumnConfig {
     }
 }
 
+/// Narrowest column width in pixels
+pub const MIN_COL_WIDTH: f32 = 20.0;
+
+/// Lowest row height in pixels
+pub const MIN_ROW_HEIGHT: f32 = 15.0;
+
+/// Height limits of a single row (the row equivalent of a column's min/max width)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RowLimits {
+    pub min_height: Option<f32>, // Lowest height (None = the grid minimum)
+    pub max_height: Option<f32>, // Highest height (None = unlimited)
+    pub resizable: bool,         // Can the row be resized by dragging its header edge
+}
+
+impl Default for RowLimits {
+    fn default() -> Self {
+        Self { min_height: None, max_height: None, resizable: true }
+    }
+}
+
+impl RowLimits {
+    /// Height limits (min, max), never below the grid minimum
+    pub fn height_limits(&self) -> (f32, f32) {
+        let min = self.min_height.unwrap_or(MIN_ROW_HEIGHT).max(MIN_ROW_HEIGHT);
+        (min, self.max_height.unwrap_or(f32::INFINITY).max(min))
+    }
+}
+
+/// How a column's width is determined
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WidthMode {
+    Fixed, // `width` pixels
+    Flex,  // A share (by `flex` weight) of the canvas width left over by the other columns
+}
+
+impl WidthMode {
+    /// Parse mode name ("fixed", "flex")
+    pub fn parse(mode: &str) -> Result<Self, GridError> {
+        match mode {
+            "fixed" | "" => Ok(WidthMode::Fixed),
+            "flex" => Ok(WidthMode::Flex),
+            other => Err(GridError::unknown_value("width_mode", other, &["fixed", "flex"])),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WidthMode::Fixed => "fixed",
+            WidthMode::Flex => "flex",
+        }
+    }
+
+    /// Validate a flex weight (finite and above 0)
+    pub fn flex_weight(weight: f64) -> Result<f32, GridError> {
+        let flex = weight as f32;
+        if flex.is_finite() && flex > 0.0 {
+            Ok(flex)
+        } else {
+            Err(GridError::OperationError { message: locale::format("error.flex_weight", &[("weight", &weight)]) })
+        }
+    }
+}
+
+/// Row sampling mode for previewing huge datasets
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PreviewMode {
+    EveryNth(usize), // Show only every k-th row (0, k, 2k, ...)
+    FirstN(usize),   // Show only the first N rows
+}
+
+/// Density preset: default row height, cell padding and font size together
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Density {
+    Compact,
+    Standard,
+    Comfortable,
+}
+
+impl Density {
+    /// Parse preset name ("compact", "standard", "comfortable")
+    pub fn parse(preset: &str) -> Result<Self, GridError> {
+        match preset.to_lowercase().as_str() {
+            "compact" => Ok(Density::Compact),
+            "standard" | "normal" | "" => Ok(Density::Standard),
+            "comfortable" => Ok(Density::Comfortable),
+            other => Err(GridError::unknown_value("density", other, &["compact", "standard", "comfortable"])),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Density::Compact => "compact",
+            Density::Standard => "standard",
+            Density::Comfortable => "comfortable",
+        }
+    }
+
+    pub fn row_height(&self) -> f32 {
+        match self {
+            Density::Compact => 20.0,
+            Density::Standard => 25.0,
+            Density::Comfortable => 32.0,
+        }
+    }
+
+    pub fn cell_padding(&self) -> f32 {
+        match self {
+            Density::Compact => 3.0,
+            Density::Standard => 5.0,
+            Density::Comfortable => 8.0,
+        }
+    }
+
+    pub fn font_size(&self) -> f32 {
+        match self {
+            Density::Compact => 11.0,
+            Density::Standard => 12.0,
+            Density::Comfortable => 13.0,
+        }
+    }
+}
+
+/// Separator drawn after a column or row to group sections of a wide table
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SectionBreak {
+    Hidden, // No line at all (the grid line and cell border are suppressed)
+    Thin,
+    Medium,
+    Thick,
+    Double,
+}
+
+impl SectionBreak {
+    /// Parse style name ("none", "thin", "medium", "thick", "double")
+    pub fn parse(style: &str) -> Result<Self, GridError> {
+        match style.to_lowercase().as_str() {
+            "none" | "hidden" => Ok(SectionBreak::Hidden),
+            "thin" => Ok(SectionBreak::Thin),
+            "medium" | "" => Ok(SectionBreak::Medium),
+            "thick" => Ok(SectionBreak::Thick),
+            "double" => Ok(SectionBreak::Double),
+            other => Err(GridError::unknown_value("section_break_style", other, &["none", "thin", "medium", "thick", "double"])),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SectionBreak::Hidden => "none",
+            SectionBreak::Thin => "thin",
+            SectionBreak::Medium => "medium",
+            SectionBreak::Thick => "thick",
+            SectionBreak::Double => "double",
+        }
+    }
+
+    /// Strokes making up the separator as (start relative to the cell edge, width) in pixels
+    pub fn strokes(&self) -> &'static [(f32, f32)] {
+        match self {
+            SectionBreak::Hidden => &[],
+            SectionBreak::Thin => &[(0.0, 1.0)],
+            SectionBreak::Medium => &[(-1.0, 2.0)],
+            SectionBreak::Thick => &[(-1.0, 3.0)],
+            SectionBreak::Double => &[(-1.0, 1.0), (1.0, 1.0)],
+        }
+    }
+
+    /// CSS border value for HTML export (spreadsheets map these onto their border styles)
+    pub fn css(&self) -> &'static str {
+        match self {
+            SectionBreak::Hidden => "none",
+            SectionBreak::Thin => "1px solid #404040",
+            SectionBreak::Medium => "2px solid #404040",
+            SectionBreak::Thick => "3px solid #404040",
+            SectionBreak::Double => "3px double #404040",
+        }
+    }
+}
+
+/// What a click on a column header does
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortClick {
+    Header, // Anywhere on the header toggles the sort
+    Arrow,  // Only the arrow zone at the right edge sorts; the rest selects the column
+    Off,    // Clicks select the column; sorting is left to the API
+}
+
+impl SortClick {
+    /// Parse policy name ("header", "arrow", "none")
+    pub fn parse(policy: &str) -> Result<Self, GridError> {
+        match policy {
+            "header" => Ok(SortClick::Header),
+            "arrow" => Ok(SortClick::Arrow),
+            "none" | "off" => Ok(SortClick::Off),
+            other => Err(GridError::unknown_value("sort_click_policy", other, &["header", "arrow", "none"])),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortClick::Header => "header",
+            SortClick::Arrow => "arrow",
+            SortClick::Off => "none",
+        }
+    }
+}
+
+/// Sort state (single-column and multi-column), saved for undo
+#[derive(Clone, Debug, PartialEq)]
+pub struct SortState {
+    pub column: Option<usize>,
+    pub ascending: bool,
+    pub columns: Vec<(usize, bool)>,
+}
+
+impl SortState {
+    /// {"column", "ascending", "columns": [[col, ascending], ...]}
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "column": self.column,
+            "ascending": self.ascending,
+            "columns": self.columns,
+        })
+    }
+}
+
+/// Value of a unique-column cell as compared for duplicates: typed, so 1 and 1.0 match
+/// but the number 1 and the text "1" do not
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UniqueKey {
+    Text(String),
+    Number(u64), // Bits of the value, with -0 folded into 0
+    Boolean(bool),
+    Date(String),
+}
+
+impl UniqueKey {
+    /// Key of a stored value (None for empty cells, which are never duplicates)
+    pub fn of(value: &CellValue) -> Option<Self> {
+        match value {
+            CellValue::Empty => None,
+            CellValue::Text(text) => Some(UniqueKey::Text(text.clone())),
+            CellValue::Number(n) => Some(UniqueKey::Number(if *n == 0.0 { 0.0f64.to_bits() } else { n.to_bits() })),
+            CellValue::Boolean(b) => Some(UniqueKey::Boolean(*b)),
+            CellValue::Date(date) => Some(UniqueKey::Date(date.clone())),
+        }
+    }
+}
+
+/// Rows of a unique column by value; set_value keeps it current, other changes rebuild it
+#[derive(Debug)]
+struct UniqueIndex {
+    data_version: u64, // Grid data_version the index reflects
+    rows: HashMap<UniqueKey, Vec<usize>>, // Ascending rows per value
+}
+
+/// Rows whose extent is not the default row height, by index, so positions are a range
+/// sum over them (custom heights, hidden rows, detail panels and transitions)
+#[derive(Debug)]
+struct RowExtentIndex {
+    key: (u64, u64, u64), // Grid revision, data_version and row_layout_version it reflects
+    deltas: BTreeMap<usize, f64>, // Row -> extent minus the default row height
+    total: f64, // Height of all rows
+}
+
 /// Main grid data structure optimized for sparse data
 pub struct Grid {
     rows: usize,
@@ -103,25 +589,69 @@ pub struct Grid {
     pub header_levels: usize,            // Number of header rows (1 = normal, 2+ = grouped)
     pub header_row_height: f32,          // Height of each header row
 
+    // Section separators after a column / row index (positional, like frozen panes)
+    pub col_section_breaks: BTreeMap<usize, SectionBreak>,
+    pub row_section_breaks: BTreeMap<usize, SectionBreak>,
+
     // Column widths (in pixels)
     col_widths: Vec<f32>,
 
-    // Row heights (in pixels)
-    row_heights: Vec<f32>,
+    // Row heights (in pixels) of rows not at the default height, so a grid of millions
+    // of virtual rows keeps no per-row storage
+    row_heights: BTreeMap<usize, f32>,
+
+    // Stable row IDs: assigned when a row is created and kept through sorts, inserts and
+    // deletes of other rows (row_index_by_id maps each ID back to its current row).
+    // Left empty while row r still has ID r + 1 (until rows are reordered, or inserted
+    // or deleted in the middle), so plain appends need no per-row storage either
+    row_ids: Vec<usize>,
+    row_index_by_id: HashMap<usize, usize>,
+    next_row_id: usize,
+
+    // Master-detail panels: row ID -> height of the detail region reserved below the row
+    row_details: HashMap<usize, f32>,
+
+    // Read-only rows by row ID, so the flag follows the row through sorts and inserts
+    readonly_rows: HashSet<usize>,
+
+    // Rows hidden with set_row_visible, by row ID; they keep their height but take no space
+    hidden_rows: HashSet<usize>,
+
+    // Height limits and resizable flags of single rows, by row ID (absent = defaults)
+    row_limits: HashMap<usize, RowLimits>,
+
+    // Height transitions still animating: row ID -> change to the row's extent
+    // (negative shrinks its detail panel, then the row; positive leaves a gap below it)
+    row_transitions: HashMap<usize, f32>,
+
+    // Index-keyed extents built from the maps above (row_heights and the ID-keyed ones);
+    // row_layout_version counts the changes revision does not (row order, transitions)
+    row_extent_index: RefCell<RowExtentIndex>,
+    row_layout_version: u64,
 
     // Default dimensions
     default_col_width: f32,
     default_row_height: f32,
 
+    // Horizontal text padding inside cells (columns may override it)
+    pub cell_padding: f32,
+    pub density: Density,
+
     // Header dimensions
     pub row_header_width: f32,
     pub col_header_height: f32,          // Total header height (calculated from header_levels * header_row_height)
+    pub column_summary_height: f32,      // Summary band at the bottom of the header (0 = hidden)
+    pub footer_height: f32,              // Aggregate footer band pinned to the canvas bottom (0 = hidden)
     pub show_headers: bool,
+    pub row_label_start: usize,          // Number shown in the first row header (API indices stay 0-based)
 
     // Sort state
     pub sort_column: Option<usize>,
     pub sort_ascending: bool,
     pub sort_columns: Vec<(usize, bool)>, // Multi-column sort: (col, ascending)
+    pub sort_click: SortClick,
+    pub sort_zone_width: f32, // Width of the arrow zone at the right of each header (SortClick::Arrow)
+    pub show_filter_buttons: bool, // Funnel button in the headers of filterable columns
 
     // Freeze state
     pub frozen_rows: usize,
@@ -130,8 +660,35 @@ pub struct Grid {
     // Filter state
     filtered_rows: HashSet<usize>, // Rows that are hidden by filters
 
+    // Preview state (row sampling, hidden rows collapse to zero height)
+    pub preview_mode: Option<PreviewMode>,
+
+    // Ghost entries: virtual trailing row/column used to quickly add data
+    pub show_ghost_row: bool,
+    pub show_ghost_col: bool,
+
+    // Auto-expansion caps (max_rows, max_cols) for paste/fill beyond the grid bounds (None = truncate)
+    pub auto_expand: Option<(usize, usize)>,
+
+    // Changed row ranges per column for column_data_changed events (None = tracking disabled)
+    column_changes: Option<BTreeMap<usize, Vec<(usize, usize)>>>,
+
+    // Incremented on every data or layout change (lets persistence detect unsaved changes)
+    revision: u64,
+
+    // Revision of the last data change per column, and of the last change affecting every
+    // column (row insert/delete/sort), so filters and totals can tell what changed
+    col_revisions: HashMap<usize, u64>,
+    structure_revision: u64,
+    data_version: u64, // Counts cell value and row/column structure changes (not layout)
+    unique_indexes: RefCell<HashMap<usize, UniqueIndex>>, // Built on the first duplicate lookup per column
+
     // Grid-wide options
     pub readonly: bool,              // Read-only mode (no editing)
+    pub unique_strict: bool,         // Reject (instead of flag) duplicates in unique columns
+    pub validation_strict: bool,     // Reject (instead of mark) edits failing column validation
+    pub editable_rules: Vec<EditableRule>,           // Row conditions that lock cells
+    pub editable_callback: Option<js_sys::Function>, // JS predicate (row, col) -> false to lock a cell
     pub enable_context_menu: bool,   // Enable right-click context menu
     pub enable_row_selection: bool,  // Allow row selection
     pub enable_col_selection: bool,  // Allow column selection
@@ -160,22 +717,56 @@ impl Grid {
             cell_borders: HashMap::new(),
             column_configs,
             column_groups: Vec::new(),
+            col_section_breaks: BTreeMap::new(),
+            row_section_breaks: BTreeMap::new(),
             header_levels: 1,
             header_row_height: 30.0,
             col_widths: vec![default_col_width; cols],
-            row_heights: vec![default_row_height; rows],
+            row_heights: BTreeMap::new(),
+            row_ids: Vec::new(),
+            row_index_by_id: HashMap::new(),
+            next_row_id: rows + 1,
+            row_details: HashMap::new(),
+            readonly_rows: HashSet::new(),
+            hidden_rows: HashSet::new(),
+            row_limits: HashMap::new(),
+            row_transitions: HashMap::new(),
+            row_extent_index: RefCell::new(RowExtentIndex { key: (u64::MAX, 0, 0), deltas: BTreeMap::new(), total: 0.0 }),
+            row_layout_version: 0,
             default_col_width,
             default_row_height,
+            cell_padding: Density::Standard.cell_padding(),
+            density: Density::Standard,
             row_header_width: 60.0,
             col_header_height: 30.0,
+            column_summary_height: 0.0,
+            footer_height: 0.0,
+            row_label_start: 1,
             show_headers: true,
             sort_column: None,
             sort_ascending: true,
             sort_columns: Vec::new(),
+            sort_click: SortClick::Header,
+            sort_zone_width: 20.0,
+            show_filter_buttons: false,
             frozen_rows: 0,
             frozen_cols: 0,
             filtered_rows: HashSet::new(),
+            preview_mode: None,
+            show_ghost_row: false,
+            show_ghost_col: false,
+            auto_expand: None,
+            column_changes: None,
+            revision: 0,
+            col_revisions: HashMap::new(),
+            structure_revision: 0,
+            data_version: 0,
+            unique_indexes: RefCell::new(HashMap::new()),
             readonly: false,
+            editable_rules: Vec::new(),
+            editable_callback: None,
+            unique_strict: false,
+            validation_strict: false,
             enable_context_menu: true,
             enable_row_selection: true,
             enable_col_selection: true,
@@ -208,16 +799,55 @@ impl Grid {
     pub fn set_cell(&mut self, row: usize, col: usize, cell: Cell) {
         if row < self.rows && col < self.cols {
             self.cells.insert((row, col), cell);
+            self.mark_changed(col, row, row);
+        }
+    }
+
+    /// Remove cell at position (row, col), returning the previous cell if any
+    pub fn remove_cell(&mut self, row: usize, col: usize) -> Option<Cell> {
+        let removed = self.cells.remove(&(row, col));
+        if removed.is_some() {
+            self.mark_changed(col, row, row);
+        }
+        removed
+    }
+
+    /// Flag a cell as edited (see Cell::mark_modified); `old_value` is its value before the edit
+    pub fn mark_modified(&mut self, row: usize, col: usize, old_value: CellValue) {
+        if let Some(cell) = self.cells.get_mut(&(row, col)) {
+            cell.mark_modified(old_value);
+        }
+    }
+
+    /// Clear the modified flag of every cell, making current values the new originals
+    pub fn clear_modified(&mut self) {
+        for cell in self.cells.values_mut() {
+            cell.clear_modified();
         }
     }
 
     /// Set cell value at position (row, col)
     pub fn set_value(&mut self, row: usize, col: usize, value: CellValue) {
         if row < self.rows && col < self.cols {
-            self.cells
-                .entry((row, col))
-                .or_insert_with(Cell::default)
-                .value = value;
+            let new_key = UniqueKey::of(&value);
+            let cell = self.cells.entry((row, col)).or_insert_with(Cell::default);
+            let old_key = UniqueKey::of(&std::mem::replace(&mut cell.value, value));
+            let version = self.data_version;
+            self.mark_changed(col, row, row);
+
+            // Move the row between values of a current duplicate index instead of rebuilding it
+            if let Some(index) = self.unique_indexes.get_mut().get_mut(&col).filter(|index| index.data_version == version) {
+                if let Some(rows) = old_key.and_then(|key| index.rows.get_mut(&key)) {
+                    rows.retain(|&r| r != row);
+                }
+                if let Some(key) = new_key {
+                    let rows = index.rows.entry(key).or_default();
+                    if let Err(at) = rows.binary_search(&row) {
+                        rows.insert(at, row);
+                    }
+                }
+                index.data_version = self.data_version;
+            }
         }
     }
 
@@ -237,8 +867,65 @@ impl Grid {
             .unwrap_or_default()
     }
 
+    /// Get cell value as displayed (numbers formatted with the column's number format)
+    pub fn get_display_string(&self, row: usize, col: usize) -> String {
+        match (self.cells.get(&(row, col)), self.column_configs.get(col).and_then(|c| c.number_format.as_ref())) {
+            (Some(Cell { value: CellValue::Number(n), .. }), Some(format)) => format.format(*n),
+            (Some(cell), _) => cell.value.to_string(),
+            (None, _) => String::new(),
+        }
+    }
+
+    /// Set (or clear with None) the display format for numbers in a column
+    pub fn set_column_number_format(&mut self, col: usize, format: Option<NumberFormat>) {
+        if let Some(config) = self.column_configs.get_mut(col) {
+            config.number_format = format;
+        }
+    }
+
+    /// Iterate over stored cells (unordered)
+    pub fn iter_cells(&self) -> impl Iterator<Item = ((usize, usize), &Cell)> {
+        self.cells.iter().map(|(pos, cell)| (*pos, cell))
+    }
+
+    /// Iterate over stored cell borders (unordered)
+    pub fn iter_borders(&self) -> impl Iterator<Item = ((usize, usize), &CellBorders)> {
+        self.cell_borders.iter().map(|(pos, borders)| (*pos, borders))
+    }
+
+    /// Default height of new rows
+    pub fn default_row_height(&self) -> f32 {
+        self.default_row_height
+    }
+
+    /// Change the default row height; rows still at the old default are resized too
+    pub fn set_default_row_height(&mut self, height: f32) {
+        let height = height.max(15.0);
+        // Rows at the old default are not stored, so they follow the new one; custom
+        // heights equal to the new default become default rows
+        self.row_heights.retain(|_, h| *h != height);
+        self.default_row_height = height;
+        self.revision += 1;
+    }
+
+    /// Apply a density preset's row height and cell padding
+    /// (font size is applied by the text renderer)
+    pub fn set_density(&mut self, density: Density) {
+        self.density = density;
+        self.cell_padding = density.cell_padding();
+        self.set_default_row_height(density.row_height());
+    }
+
+    /// Horizontal text padding of a column's cells
+    pub fn cell_padding(&self, col: usize) -> f32 {
+        self.column_configs.get(col).and_then(|c| c.padding).unwrap_or(self.cell_padding)
+    }
+
     /// Get column width
     pub fn col_width(&self, col: usize) -> f32 {
+        if self.is_col_hidden(col) {
+            return 0.0;
+        }
         if col < self.cols {
             self.col_widths[col]
         } else {
@@ -246,26 +933,48 @@ impl Grid {
         }
     }
 
+    /// Column width kept while the column is hidden (same as col_width otherwise)
+    pub fn base_col_width(&self, col: usize) -> f32 {
+        self.col_widths.get(col).copied().unwrap_or(self.default_col_width)
+    }
+
     /// Set column width
     pub fn set_col_width(&mut self, col: usize, width: f32) {
         if col < self.cols {
-            self.col_widths[col] = width.max(20.0); // Minimum width
+            self.col_widths[col] = width.max(MIN_COL_WIDTH); // Minimum width
+            self.revision += 1;
         }
     }
 
     /// Get row height
     pub fn row_height(&self, row: usize) -> f32 {
-        if row < self.rows {
-            self.row_heights[row]
-        } else {
-            self.default_row_height
+        if self.is_row_preview_hidden(row) || self.is_row_user_hidden(row) {
+            return 0.0;
         }
+        let height = self.base_row_height(row);
+        let shift = self.row_transition(row);
+        if shift < 0.0 {
+            // Shrunk by a transition once its detail panel is used up
+            return (height + self.panel_height(row) + shift).clamp(0.0, height);
+        }
+        height
+    }
+
+    /// Row height kept while the row is hidden (without detail panels or animations)
+    pub fn base_row_height(&self, row: usize) -> f32 {
+        self.row_heights.get(&row).copied().unwrap_or(self.default_row_height)
     }
 
     /// Set row height
     pub fn set_row_height(&mut self, row: usize, height: f32) {
         if row < self.rows {
-            self.row_heights[row] = height.max(15.0); // Minimum height
+            let height = height.max(MIN_ROW_HEIGHT); // Minimum height
+            if height == self.default_row_height {
+                self.row_heights.remove(&row);
+            } else {
+                self.row_heights.insert(row, height);
+            }
+            self.revision += 1;
         }
     }
 
@@ -274,19 +983,273 @@ impl Grid {
         (0..col).map(|c| self.col_width(c)).sum()
     }
 
-    /// Calculate Y position of row
-    pub fn row_y_position(&self, row: usize) -> f32 {
-        (0..row).map(|r| self.row_height(r)).sum()
+    /// Calculate Y position of row (rows above include their expanded detail panels)
+    /// Kept in f64: past a few million rows f32 can no longer tell adjacent rows apart
+    pub fn row_y_position(&self, row: usize) -> f64 {
+        if self.preview_mode.is_some() {
+            return (0..row).map(|r| self.row_extent(r) as f64).sum();
+        }
+        let deltas: f64 = self.row_extents().deltas.range(..row).map(|(_, &delta)| delta).sum();
+        row as f64 * self.default_row_height as f64 + deltas
+    }
+
+    /// Row whose cells contain a y position (None past the last row or on a detail panel)
+    pub fn row_at_y(&self, y: f64) -> Option<usize> {
+        self.row_extent_at_y(y).filter(|&row| y < self.row_y_position(row) + self.row_height(row) as f64)
+    }
+
+    /// Row whose extent (cells plus detail panel) contains a y position (None past the last row)
+    pub fn row_extent_at_y(&self, y: f64) -> Option<usize> {
+        if y < 0.0 {
+            return None;
+        }
+        if self.preview_mode.is_some() {
+            let mut top = 0.0;
+            for row in 0..self.rows {
+                top += self.row_extent(row) as f64;
+                if y < top {
+                    return Some(row);
+                }
+            }
+            return None;
+        }
+        // Walk the rows off the default only; rows between them take the default height
+        let default = self.default_row_height as f64;
+        let (mut top, mut next) = (0.0, 0);
+        for (&row, &delta) in &self.row_extents().deltas {
+            let gap_end = top + (row - next) as f64 * default;
+            if y < gap_end {
+                break;
+            }
+            if y < gap_end + default + delta {
+                return Some(row);
+            }
+            top = gap_end + default + delta;
+            next = row + 1;
+        }
+        let row = next + ((y - top) / default) as usize;
+        (row < self.rows).then_some(row)
+    }
+
+    /// Helper: The row extent index, rebuilt if the rows or their extents changed since
+    fn row_extents(&self) -> Ref<'_, RowExtentIndex> {
+        let key = (self.revision, self.data_version, self.row_layout_version);
+        if self.row_extent_index.borrow().key != key {
+            let default = self.default_row_height as f64;
+            let ids = self.row_details.keys().chain(&self.hidden_rows).chain(self.row_transitions.keys());
+            let rows: BTreeSet<usize> = self
+                .row_heights
+                .keys()
+                .copied()
+                .chain(ids.filter_map(|&id| self.row_index_by_id(id)))
+                .filter(|&row| row < self.rows)
+                .collect();
+            let deltas: BTreeMap<usize, f64> = rows
+                .into_iter()
+                .map(|row| (row, self.row_extent(row) as f64 - default))
+                .filter(|&(_, delta)| delta != 0.0)
+                .collect();
+            let total = self.rows as f64 * default + deltas.values().sum::<f64>();
+            *self.row_extent_index.borrow_mut() = RowExtentIndex { key, deltas, total };
+        }
+        self.row_extent_index.borrow()
+    }
+
+    /// Height of a row's expanded detail panel (0 when collapsed or the row is hidden)
+    pub fn detail_height(&self, row: usize) -> f32 {
+        let height = self.panel_height(row);
+        (height + self.row_transition(row).min(0.0)).max(0.0)
+    }
+
+    /// Helper: Detail panel height without transitions
+    fn panel_height(&self, row: usize) -> f32 {
+        if self.row_details.is_empty() || self.is_row_hidden(row) {
+            return 0.0;
+        }
+        self.row_id(row).and_then(|id| self.row_details.get(&id)).copied().unwrap_or(0.0)
+    }
+
+    /// Vertical space taken by a row: its height plus its detail panel
+    /// (plus the gap left by a transition still animating)
+    pub fn row_extent(&self, row: usize) -> f32 {
+        self.row_height(row) + self.detail_height(row) + self.row_transition(row).max(0.0)
+    }
+
+    /// Extent change of an animating row (0 when none)
+    pub fn row_transition(&self, row: usize) -> f32 {
+        if self.row_transitions.is_empty() {
+            return 0.0;
+        }
+        self.row_id(row).and_then(|id| self.row_transitions.get(&id)).copied().unwrap_or(0.0)
+    }
+
+    /// Set the extent change of the row with an ID (0 removes it); see row_animation
+    pub fn set_row_transition(&mut self, row_id: usize, shift: f32) {
+        if shift == 0.0 {
+            self.row_transitions.remove(&row_id);
+        } else {
+            self.row_transitions.insert(row_id, shift);
+        }
+        self.row_layout_version += 1;
+    }
+
+    /// Current extent change of the row with an ID
+    pub fn row_transition_by_id(&self, row_id: usize) -> f32 {
+        self.row_transitions.get(&row_id).copied().unwrap_or(0.0)
+    }
+
+    /// Expand a row's detail panel with a height (Some) or collapse it (None)
+    /// Panels follow the row through sorts and inserts (keyed by row ID)
+    pub fn set_row_detail(&mut self, row: usize, height: Option<f32>) {
+        let Some(id) = self.row_id(row) else {
+            return;
+        };
+        match height {
+            Some(height) => self.row_details.insert(id, height.max(0.0)),
+            None => self.row_details.remove(&id),
+        };
+        self.revision += 1;
+    }
+
+    /// Rows with an expanded detail panel, in row order
+    pub fn detail_rows(&self) -> Vec<usize> {
+        let mut rows: Vec<usize> = self.row_details.keys().filter_map(|&id| self.row_index_by_id(id)).collect();
+        rows.sort_unstable();
+        rows
     }
 
     /// Get total grid width
     pub fn total_width(&self) -> f32 {
-        self.col_widths.iter().sum()
+        (0..self.cols).map(|col| self.col_width(col)).sum()
+    }
+
+    /// Check if a column is hidden (ColumnConfig.visible = false); hidden columns keep
+    /// their data and width but take no space
+    pub fn is_col_hidden(&self, col: usize) -> bool {
+        self.column_configs.get(col).is_some_and(|c| !c.visible)
+    }
+
+    /// Show or hide a column; returns false if nothing changed
+    pub fn set_col_visible(&mut self, col: usize, visible: bool) -> bool {
+        match self.column_configs.get_mut(col) {
+            Some(config) if config.visible != visible => {
+                config.visible = visible;
+                self.revision += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Spread the width left over by the other columns in `available` pixels across the
+    /// visible flex columns by weight, within their min/max widths (as CSS flexbox does)
+    /// Returns true if a width changed
+    pub fn distribute_flex_widths(&mut self, available: f32) -> bool {
+        let flex_cols: Vec<usize> = (0..self.cols)
+            .filter(|&col| !self.is_col_hidden(col) && self.column_configs.get(col).is_some_and(|c| c.width_mode == WidthMode::Flex))
+            .collect();
+        if flex_cols.is_empty() {
+            return false;
+        }
+        let fixed: f32 = (0..self.cols).filter(|col| !flex_cols.contains(col)).map(|col| self.col_width(col)).sum();
+        let mut remaining = (available - fixed).max(0.0);
+
+        let mut widths: HashMap<usize, f32> = HashMap::new();
+        let mut open = flex_cols.clone();
+        while !open.is_empty() {
+            let total_weight: f32 = open.iter().map(|&col| self.column_configs[col].flex.max(0.0)).sum();
+            let shares: Vec<(usize, f32, f32)> = open
+                .iter()
+                .map(|&col| {
+                    let config = &self.column_configs[col];
+                    let share = if total_weight > 0.0 { remaining * config.flex.max(0.0) / total_weight } else { 0.0 };
+                    let (min, max) = config.width_limits();
+                    (col, share, share.clamp(min, max))
+                })
+                .collect();
+            // Freeze the columns held at a limit on the side that breaks the total, then
+            // share what is left among the others
+            let violation: f32 = shares.iter().map(|&(_, share, clamped)| clamped - share).sum();
+            let frozen: Vec<(usize, f32)> = shares
+                .iter()
+                .filter(|&&(_, share, clamped)| {
+                    violation == 0.0 || (violation > 0.0 && clamped > share) || (violation < 0.0 && clamped < share)
+                })
+                .map(|&(col, _, clamped)| (col, clamped))
+                .collect();
+            if frozen.is_empty() {
+                break; // No progress (non-finite shares); leave the rest unchanged
+            }
+            for (col, width) in frozen {
+                widths.insert(col, width);
+                remaining = (remaining - width).max(0.0);
+                open.retain(|&c| c != col);
+            }
+        }
+
+        let mut changed = false;
+        for (col, width) in widths {
+            if self.col_widths[col] != width {
+                self.col_widths[col] = width;
+                changed = true;
+            }
+        }
+        if changed {
+            self.revision += 1;
+        }
+        changed
+    }
+
+    /// Columns hidden with set_col_visible, in order
+    pub fn hidden_columns(&self) -> Vec<usize> {
+        (0..self.cols).filter(|&col| self.is_col_hidden(col)).collect()
+    }
+
+    /// Check if a row is hidden with set_row_visible (not by filters or preview mode)
+    pub fn is_row_user_hidden(&self, row: usize) -> bool {
+        !self.hidden_rows.is_empty() && self.row_id(row).is_some_and(|id| self.hidden_rows.contains(&id))
+    }
+
+    /// Show or hide a row (the flag follows the row through sorts and inserts)
+    /// Returns false if nothing changed
+    pub fn set_row_visible(&mut self, row: usize, visible: bool) -> bool {
+        let Some(id) = self.row_id(row) else { return false };
+        let changed = if visible { self.hidden_rows.remove(&id) } else { self.hidden_rows.insert(id) };
+        if changed {
+            self.revision += 1;
+        }
+        changed
+    }
+
+    /// Rows hidden with set_row_visible, in order
+    pub fn hidden_rows(&self) -> Vec<usize> {
+        let mut rows: Vec<usize> = self.hidden_rows.iter().filter_map(|&id| self.row_index_by_id(id)).collect();
+        rows.sort_unstable();
+        rows
     }
 
     /// Get total grid height
-    pub fn total_height(&self) -> f32 {
-        self.row_heights.iter().sum()
+    pub fn total_height(&self) -> f64 {
+        if self.preview_mode.is_some() {
+            return (0..self.rows).map(|r| self.row_extent(r) as f64).sum();
+        }
+        self.row_extents().total
+    }
+
+    /// Height of the ghost row (0 when disabled)
+    pub fn ghost_row_height(&self) -> f32 {
+        if self.show_ghost_row { self.default_row_height } else { 0.0 }
+    }
+
+    /// Width of the ghost column (0 when disabled)
+    pub fn ghost_col_width(&self) -> f32 {
+        if self.show_ghost_col { self.default_col_width } else { 0.0 }
+    }
+
+    /// Check if (row, col) lies in the ghost row (row == row_count) or ghost column (col == col_count)
+    pub fn is_ghost_cell(&self, row: usize, col: usize) -> bool {
+        (self.show_ghost_row && row == self.rows && col < self.cols)
+            || (self.show_ghost_col && col == self.cols && row < self.rows)
     }
 
     /// Resize grid
@@ -297,28 +1260,79 @@ impl Grid {
         // Remove borders outside new bounds
         self.cell_borders.retain(|&(r, c), _| r < rows && c < cols);
 
+        // Keep one column config per column
+        while self.column_configs.len() < cols {
+            let col = self.column_configs.len();
+            self.column_configs.push(ColumnConfig::new(Self::column_index_to_letter(col), format!("col_{}", col)));
+        }
+        self.column_configs.truncate(cols);
+
         // Adjust column widths
         if cols > self.cols {
             self.col_widths.resize(cols, self.default_col_width);
         } else {
             self.col_widths.truncate(cols);
+            self.col_section_breaks.split_off(&cols);
         }
 
-        // Adjust row heights
+        // Adjust row heights and IDs (new rows get fresh IDs)
         if rows > self.rows {
-            self.row_heights.resize(rows, self.default_row_height);
+            if self.row_ids.is_empty() && self.next_row_id == self.rows + 1 {
+                // Appended rows still take ID r + 1
+                self.next_row_id = rows + 1;
+            } else {
+                self.materialize_row_ids();
+                for row in self.rows..rows {
+                    let id = self.allocate_row_id();
+                    self.row_ids.push(id);
+                    self.row_index_by_id.insert(id, row);
+                }
+            }
         } else {
-            self.row_heights.truncate(rows);
+            self.row_heights.split_off(&rows);
+            self.row_section_breaks.split_off(&rows);
+            if self.row_ids.is_empty() {
+                // Implicit IDs: the removed rows are the ones with IDs above `rows`
+                self.row_details.retain(|&id, _| id <= rows);
+                self.readonly_rows.retain(|&id| id <= rows);
+                self.hidden_rows.retain(|&id| id <= rows);
+                self.row_limits.retain(|&id, _| id <= rows);
+            }
+            for id in self.row_ids.drain(rows.min(self.row_ids.len())..) {
+                self.row_index_by_id.remove(&id);
+                self.row_details.remove(&id);
+                self.readonly_rows.remove(&id);
+                self.hidden_rows.remove(&id);
+                self.row_limits.remove(&id);
+            }
         }
 
+        if (rows, cols) != (self.rows, self.cols) {
+            self.data_version += 1;
+        }
         self.rows = rows;
         self.cols = cols;
+        self.revision += 1;
+    }
+
+    /// Size the grid should grow to so that (rows_needed, cols_needed) fit,
+    /// limited by the auto-expansion caps (never smaller than the current size)
+    pub fn auto_expand_size(&self, rows_needed: usize, cols_needed: usize) -> (usize, usize) {
+        match self.auto_expand {
+            Some((max_rows, max_cols)) => (
+                self.rows.max(rows_needed.min(max_rows)),
+                self.cols.max(cols_needed.min(max_cols)),
+            ),
+            None => (self.rows, self.cols),
+        }
     }
 
     /// Clear all cells
     pub fn clear(&mut self) {
         self.cells.clear();
         self.cell_borders.clear();
+        self.readonly_rows.clear();
+        self.mark_all_changed(0);
     }
 
     /// Fill grid with sample data (for testing)
@@ -359,8 +1373,14 @@ impl Grid {
         result
     }
 
-    /// Insert a new row at the specified position
+    /// Insert a new row at the specified position (the row gets a fresh ID)
     pub fn insert_row(&mut self, at_index: usize) {
+        self.insert_row_with_id(at_index, None);
+    }
+
+    /// Insert a new row with a given ID, e.g. when undo restores a deleted row
+    /// A fresh ID is used when `id` is None or already taken
+    pub fn insert_row_with_id(&mut self, at_index: usize, id: Option<usize>) {
         if at_index > self.rows {
             return;
         }
@@ -387,9 +1407,26 @@ impl Grid {
         }
         self.cell_borders = new_borders;
 
-        // Insert new row height
-        self.row_heights.insert(at_index, self.default_row_height);
+        // Insert new row height and ID
+        let moved = self.row_heights.split_off(&at_index);
+        self.row_heights.extend(moved.into_iter().map(|(row, height)| (row + 1, height)));
+        Self::shift_section_breaks(&mut self.row_section_breaks, at_index, true);
+        let id = match id {
+            Some(id) if self.row_index_by_id(id).is_none() => {
+                self.next_row_id = self.next_row_id.max(id + 1);
+                id
+            }
+            _ => self.allocate_row_id(),
+        };
+        if !(self.row_ids.is_empty() && at_index == self.rows && id == self.rows + 1) {
+            self.materialize_row_ids();
+            self.row_ids.insert(at_index, id);
+            self.reindex_row_ids(at_index);
+        }
         self.rows += 1;
+
+        // Rows at and after the insertion point shifted
+        self.mark_all_changed(at_index);
     }
 
     /// Delete a row at the specified position
@@ -428,11 +1465,29 @@ impl Grid {
         }
         self.cell_borders = new_borders;
 
-        // Remove row height
-        if index < self.row_heights.len() {
-            self.row_heights.remove(index);
+        // Remove row height and ID
+        let mut moved = self.row_heights.split_off(&index);
+        moved.remove(&index);
+        self.row_heights.extend(moved.into_iter().map(|(row, height)| (row - 1, height)));
+        Self::shift_section_breaks(&mut self.row_section_breaks, index, false);
+        if index + 1 < self.rows {
+            self.materialize_row_ids();
+        }
+        if let Some(id) = self.row_id(index) {
+            if !self.row_ids.is_empty() {
+                self.row_ids.remove(index);
+                self.row_index_by_id.remove(&id);
+            }
+            self.row_details.remove(&id);
+            self.readonly_rows.remove(&id);
+            self.hidden_rows.remove(&id);
+            self.row_limits.remove(&id);
+            self.reindex_row_ids(index);
         }
         self.rows -= 1;
+
+        // Rows at and after the deleted row shifted
+        self.mark_all_changed(index);
     }
 
     /// Insert a new column at the specified position
@@ -465,7 +1520,14 @@ impl Grid {
 
         // Insert new column width
         self.col_widths.insert(at_index, self.default_col_width);
+        Self::shift_section_breaks(&mut self.col_section_breaks, at_index, true);
         self.cols += 1;
+        self.editable_rules.retain_mut(|rule| rule.remap_columns(|col| Some(if col >= at_index { col + 1 } else { col })));
+
+        // Columns at and after the insertion point shifted
+        for col in at_index..self.cols {
+            self.mark_changed(col, 0, self.rows.saturating_sub(1));
+        }
     }
 
     /// Delete a column at the specified position
@@ -508,13 +1570,27 @@ impl Grid {
         if index < self.col_widths.len() {
             self.col_widths.remove(index);
         }
+        Self::shift_section_breaks(&mut self.col_section_breaks, index, false);
         self.cols -= 1;
+        self.editable_rules.retain_mut(|rule| {
+            rule.remap_columns(|col| match col.cmp(&index) {
+                std::cmp::Ordering::Less => Some(col),
+                std::cmp::Ordering::Equal => None,
+                std::cmp::Ordering::Greater => Some(col - 1),
+            })
+        });
+
+        // Columns at and after the deleted column shifted
+        for col in index..self.cols {
+            self.mark_changed(col, 0, self.rows.saturating_sub(1));
+        }
     }
 
     /// Sort grid by column
-    pub fn sort_by_column(&mut self, col: usize, ascending: bool) {
+    /// Returns the applied row order (see permute_rows), or None if nothing was sorted
+    pub fn sort_by_column(&mut self, col: usize, ascending: bool) -> Option<Vec<usize>> {
         if col >= self.cols {
-            return;
+            return None;
         }
 
         // Store sort state
@@ -530,28 +1606,9 @@ impl Grid {
         }
 
         // Sort rows based on column values
-        row_values.sort_by(|(_, a), (_, b)| {
-            let cmp = match (a, b) {
-                (CellValue::Number(na), CellValue::Number(nb)) => {
-                    na.partial_cmp(nb).unwrap_or(std::cmp::Ordering::Equal)
-                }
-                (CellValue::Boolean(ba), CellValue::Boolean(bb)) => ba.cmp(bb),
-                (CellValue::Text(ta), CellValue::Text(tb)) => ta.cmp(tb),
-                (CellValue::Date(da), CellValue::Date(db)) => da.cmp(db),
-                (CellValue::Empty, CellValue::Empty) => std::cmp::Ordering::Equal,
-                (CellValue::Empty, _) => std::cmp::Ordering::Greater, // Empty values go to the end
-                (_, CellValue::Empty) => std::cmp::Ordering::Less,
-                // Mixed types: Number < Boolean < Date < Text
-                (CellValue::Number(_), _) => std::cmp::Ordering::Less,
-                (_, CellValue::Number(_)) => std::cmp::Ordering::Greater,
-                (CellValue::Boolean(_), CellValue::Date(_)) => std::cmp::Ordering::Less,
-                (CellValue::Boolean(_), CellValue::Text(_)) => std::cmp::Ordering::Less,
-                (CellValue::Date(_), CellValue::Boolean(_)) => std::cmp::Ordering::Greater,
-                (CellValue::Date(_), CellValue::Text(_)) => std::cmp::Ordering::Less,
-                (CellValue::Text(_), CellValue::Boolean(_)) => std::cmp::Ordering::Greater,
-                (CellValue::Text(_), CellValue::Date(_)) => std::cmp::Ordering::Greater,
-            };
-
+        let comparator = self.sort_comparator(col);
+        collation::stable_sort_by(&mut row_values, comparator.is_custom(), |(_, a), (_, b)| {
+            let cmp = comparator.compare(a, b);
             if ascending {
                 cmp
             } else {
@@ -559,38 +1616,19 @@ impl Grid {
             }
         });
 
-        // Create mapping from old row to new row
-        let mut row_mapping: HashMap<usize, usize> = HashMap::new();
-        for (new_row, (old_row, _)) in row_values.iter().enumerate() {
-            row_mapping.insert(*old_row, new_row);
-        }
-
-        // Remap all cells to new row positions
-        let mut new_cells = HashMap::new();
-        for ((old_row, col_idx), cell) in self.cells.drain() {
-            if let Some(&new_row) = row_mapping.get(&old_row) {
-                new_cells.insert((new_row, col_idx), cell);
-            }
-        }
-        self.cells = new_cells;
-
-        // Remap row heights
-        let mut new_row_heights = vec![self.default_row_height; self.rows];
-        for (old_row, new_row) in row_mapping {
-            if old_row < self.row_heights.len() && new_row < new_row_heights.len() {
-                new_row_heights[new_row] = self.row_heights[old_row];
-            }
-        }
-        self.row_heights = new_row_heights;
+        let order: Vec<usize> = row_values.iter().map(|(old_row, _)| *old_row).collect();
+        self.permute_rows(&order);
 
         // Clear multi-column sort when single column sort is used
         self.sort_columns.clear();
+        Some(order)
     }
 
     /// Add column to multi-column sort (for Shift+Click)
-    pub fn add_sort_column(&mut self, col: usize, ascending: bool) {
+    /// Returns the applied row order (see permute_rows), or None if nothing was sorted
+    pub fn add_sort_column(&mut self, col: usize, ascending: bool) -> Option<Vec<usize>> {
         if col >= self.cols {
-            return;
+            return None;
         }
 
         // Check if column already in sort list
@@ -609,13 +1647,14 @@ impl Grid {
         }
 
         // Perform multi-column sort
-        self.sort_by_multiple_columns();
+        self.sort_by_multiple_columns()
     }
 
     /// Sort by multiple columns
-    pub fn sort_by_multiple_columns(&mut self) {
+    /// Returns the applied row order (see permute_rows), or None if nothing was sorted
+    pub fn sort_by_multiple_columns(&mut self) -> Option<Vec<usize>> {
         if self.sort_columns.is_empty() {
-            return;
+            return None;
         }
 
         // Collect all row indices and their values in all sort columns
@@ -630,35 +1669,16 @@ impl Grid {
         }
 
         // Sort rows based on multiple column values
-        row_values.sort_by(|(_, values_a), (_, values_b)| {
+        let comparators: Vec<SortComparator> =
+            self.sort_columns.iter().map(|(col, _)| self.sort_comparator(*col)).collect();
+        let tolerant = comparators.iter().any(SortComparator::is_custom);
+        collation::stable_sort_by(&mut row_values, tolerant, |(_, values_a), (_, values_b)| {
             for (i, (_col, ascending)) in self.sort_columns.iter().enumerate() {
                 if i >= values_a.len() || i >= values_b.len() {
                     break;
                 }
 
-                let a = &values_a[i];
-                let b = &values_b[i];
-
-                let cmp = match (a, b) {
-                    (CellValue::Number(na), CellValue::Number(nb)) => {
-                        na.partial_cmp(nb).unwrap_or(std::cmp::Ordering::Equal)
-                    }
-                    (CellValue::Boolean(ba), CellValue::Boolean(bb)) => ba.cmp(bb),
-                    (CellValue::Text(ta), CellValue::Text(tb)) => ta.cmp(tb),
-                    (CellValue::Date(da), CellValue::Date(db)) => da.cmp(db),
-                    (CellValue::Empty, CellValue::Empty) => std::cmp::Ordering::Equal,
-                    (CellValue::Empty, _) => std::cmp::Ordering::Greater,
-                    (_, CellValue::Empty) => std::cmp::Ordering::Less,
-                    (CellValue::Number(_), _) => std::cmp::Ordering::Less,
-                    (_, CellValue::Number(_)) => std::cmp::Ordering::Greater,
-                    (CellValue::Date(_), CellValue::Text(_)) => std::cmp::Ordering::Less,
-                    (CellValue::Date(_), CellValue::Boolean(_)) => std::cmp::Ordering::Less,
-                    (CellValue::Text(_), CellValue::Date(_)) => std::cmp::Ordering::Greater,
-                    (CellValue::Boolean(_), CellValue::Date(_)) => std::cmp::Ordering::Greater,
-                    (CellValue::Boolean(_), CellValue::Text(_)) => std::cmp::Ordering::Less,
-                    (CellValue::Text(_), CellValue::Boolean(_)) => std::cmp::Ordering::Greater,
-                };
-
+                let cmp = comparators[i].compare(&values_a[i], &values_b[i]);
                 let final_cmp = if *ascending { cmp } else { cmp.reverse() };
 
                 if final_cmp != std::cmp::Ordering::Equal {
@@ -669,9 +1689,30 @@ impl Grid {
             std::cmp::Ordering::Equal
         });
 
+        let order: Vec<usize> = row_values.iter().map(|(old_row, _)| *old_row).collect();
+        self.permute_rows(&order);
+        Some(order)
+    }
+
+    /// Undo permute_rows(order): put every row back where it came from
+    pub fn unpermute_rows(&mut self, order: &[usize]) {
+        let mut inverse = vec![0; order.len()];
+        for (new_row, old_row) in order.iter().enumerate() {
+            inverse[*old_row] = new_row;
+        }
+        self.permute_rows(&inverse);
+    }
+
+    /// Reorder rows: new row i takes the cells and height of old row order[i]
+    /// An empty order leaves the rows unchanged
+    pub fn permute_rows(&mut self, order: &[usize]) {
+        if order.is_empty() {
+            return;
+        }
+
         // Create mapping from old row to new row
         let mut row_mapping: HashMap<usize, usize> = HashMap::new();
-        for (new_row, (old_row, _)) in row_values.iter().enumerate() {
+        for (new_row, old_row) in order.iter().enumerate() {
             row_mapping.insert(*old_row, new_row);
         }
 
@@ -684,14 +1725,259 @@ impl Grid {
         }
         self.cells = new_cells;
 
-        // Remap row heights
-        let mut new_row_heights = vec![self.default_row_height; self.rows];
-        for (old_row, new_row) in row_mapping {
-            if old_row < self.row_heights.len() && new_row < new_row_heights.len() {
-                new_row_heights[new_row] = self.row_heights[old_row];
+        // Remap row heights; section breaks stay after the row they follow
+        self.row_heights = std::mem::take(&mut self.row_heights)
+            .into_iter()
+            .filter_map(|(old_row, height)| row_mapping.get(&old_row).map(|&new_row| (new_row, height)))
+            .collect();
+        self.row_section_breaks = std::mem::take(&mut self.row_section_breaks)
+            .into_iter()
+            .filter_map(|(old_row, style)| row_mapping.get(&old_row).map(|&new_row| (new_row, style)))
+            .collect();
+
+        // Row IDs move with their rows
+        self.materialize_row_ids();
+        let new_row_ids: Vec<usize> = order.iter().filter_map(|&old_row| self.row_ids.get(old_row).copied()).collect();
+        if new_row_ids.len() == self.row_ids.len() {
+            self.row_ids = new_row_ids;
+            self.reindex_row_ids(0);
+        }
+        self.mark_all_changed(0);
+    }
+
+    /// Get the stable ID of a row (None if the row does not exist)
+    pub fn row_id(&self, row: usize) -> Option<usize> {
+        if self.row_ids.is_empty() {
+            return (row < self.rows).then_some(row + 1);
+        }
+        self.row_ids.get(row).copied()
+    }
+
+    /// Get the current index of the row with an ID (None if the row was deleted)
+    pub fn row_index_by_id(&self, id: usize) -> Option<usize> {
+        if self.row_ids.is_empty() {
+            return (1..=self.rows).contains(&id).then(|| id - 1);
+        }
+        self.row_index_by_id.get(&id).copied()
+    }
+
+    /// Replace all row IDs (e.g. when restoring a snapshot)
+    /// Ignored unless there is exactly one unique ID per row
+    pub fn set_row_ids(&mut self, ids: Vec<usize>) {
+        let unique: HashSet<usize> = ids.iter().copied().collect();
+        if ids.len() != self.rows || unique.len() != ids.len() {
+            return;
+        }
+        self.next_row_id = ids.iter().max().map_or(1, |max| max + 1);
+        self.row_details.clear();
+        self.readonly_rows.clear();
+        self.hidden_rows.clear();
+        self.row_limits.clear();
+        self.row_ids = ids;
+        self.row_index_by_id.clear();
+        self.reindex_row_ids(0);
+    }
+
+    /// Helper: Take the next unused row ID
+    fn allocate_row_id(&mut self) -> usize {
+        let id = self.next_row_id;
+        self.next_row_id += 1;
+        id
+    }
+
+    /// Helper: Store the implicit IDs (row r has ID r + 1) before rows are reordered,
+    /// or inserted or deleted in the middle
+    fn materialize_row_ids(&mut self) {
+        if self.row_ids.is_empty() {
+            self.row_ids = (1..=self.rows).collect();
+            self.row_index_by_id = (1..=self.rows).zip(0..self.rows).collect();
+        }
+    }
+
+    /// Helper: Update the ID lookup for rows from `start_row` onward
+    fn reindex_row_ids(&mut self, start_row: usize) {
+        self.row_layout_version += 1;
+        for (row, &id) in self.row_ids.iter().enumerate().skip(start_row) {
+            self.row_index_by_id.insert(id, row);
+        }
+    }
+
+    /// Get the sort state (single and multi-column)
+    pub fn sort_state(&self) -> SortState {
+        SortState {
+            column: self.sort_column,
+            ascending: self.sort_ascending,
+            columns: self.sort_columns.clone(),
+        }
+    }
+
+    /// Restore a sort state without reordering rows
+    pub fn set_sort_state(&mut self, state: &SortState) {
+        self.sort_column = state.column;
+        self.sort_ascending = state.ascending;
+        self.sort_columns = state.columns.clone();
+    }
+
+    /// Move a column (cells, borders, width and config) from one position to another
+    /// Columns in between shift by one. Returns false if either index is out of range.
+    pub fn move_column(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.cols || to >= self.cols {
+            return false;
+        }
+        if from == to {
+            return true;
+        }
+
+        let remap = |col: usize| -> usize {
+            if col == from {
+                to
+            } else if from < to && col > from && col <= to {
+                col - 1
+            } else if to < from && col >= to && col < from {
+                col + 1
+            } else {
+                col
+            }
+        };
+
+        self.cells = self.cells.drain().map(|((row, col), cell)| ((row, remap(col)), cell)).collect();
+        self.cell_borders = self.cell_borders.drain().map(|((row, col), b)| ((row, remap(col)), b)).collect();
+        self.editable_rules.retain_mut(|rule| rule.remap_columns(|col| Some(remap(col))));
+        self.col_section_breaks = std::mem::take(&mut self.col_section_breaks).into_iter().map(|(col, style)| (remap(col), style)).collect();
+
+        let width = self.col_widths.remove(from);
+        self.col_widths.insert(to, width);
+        if from < self.column_configs.len() && to < self.column_configs.len() {
+            let config = self.column_configs.remove(from);
+            self.column_configs.insert(to, config);
+        }
+
+        for col in from.min(to)..=from.max(to) {
+            self.mark_changed(col, 0, self.rows.saturating_sub(1));
+        }
+        true
+    }
+
+    /// Get the active sort keys as (col, ascending) pairs
+    /// Multi-column sort takes precedence over single-column sort
+    pub fn active_sort_keys(&self) -> Vec<(usize, bool)> {
+        if !self.sort_columns.is_empty() {
+            self.sort_columns.clone()
+        } else if let Some(col) = self.sort_column {
+            vec![(col, self.sort_ascending)]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Set how a column's text values are ordered when sorting
+    /// Fails (leaving the column unchanged) if the locale is rejected by Intl.Collator
+    pub fn set_column_collation(&mut self, col: usize, collation: Collation, locale: Option<String>) -> Result<(), GridError> {
+        TextComparator::new(collation, locale.as_deref())?;
+        if let Some(config) = self.column_configs.get_mut(col) {
+            config.collation = collation;
+            config.collation_locale = locale;
+        }
+        Ok(())
+    }
+
+    /// Set (Some) or remove (None) a JS comparator callback used when sorting a column
+    pub fn set_column_comparator(&mut self, col: usize, comparator: Option<js_sys::Function>) {
+        if let Some(config) = self.column_configs.get_mut(col) {
+            config.comparator = comparator;
+        }
+    }
+
+    /// Set (Some) or remove (None) the value sequence a column sorts by
+    pub fn set_column_sort_order(&mut self, col: usize, order: Option<Vec<String>>) {
+        if let Some(config) = self.column_configs.get_mut(col) {
+            config.sort_order = order;
+        }
+    }
+
+    /// Comparator for sorting a column: its JS callback, else its sort order list, else the
+    /// built-in ordering; text uses the column's collation (ordinal when it has none)
+    fn sort_comparator(&self, col: usize) -> SortComparator {
+        let Some(config) = self.column_configs.get(col) else {
+            return SortComparator::Builtin(TextComparator::Ordinal);
+        };
+        if let Some(comparator) = &config.comparator {
+            return SortComparator::Custom(comparator.clone());
+        }
+        let text = TextComparator::new(config.collation, config.collation_locale.as_deref()).unwrap_or_else(|e| {
+            log::warn!("{}; sorting column {} by code point", e, col);
+            TextComparator::Ordinal
+        });
+        match &config.sort_order {
+            Some(order) => SortComparator::ordered(order, text),
+            None => SortComparator::Builtin(text),
+        }
+    }
+
+    /// Compare two cell values using the sort ordering
+    /// (Empty values last, mixed types: Number < Boolean < Date < Text)
+    pub fn compare_values(a: &CellValue, b: &CellValue) -> std::cmp::Ordering {
+        Self::compare_values_with(a, b, &TextComparator::Ordinal)
+    }
+
+    /// Compare two cell values using the sort ordering, with text compared by `text`
+    pub fn compare_values_with(a: &CellValue, b: &CellValue, text: &TextComparator) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+
+        fn type_rank(value: &CellValue) -> u8 {
+            match value {
+                CellValue::Number(_) => 0,
+                CellValue::Boolean(_) => 1,
+                CellValue::Date(_) => 2,
+                CellValue::Text(_) => 3,
+                CellValue::Empty => 4,
+            }
+        }
+
+        match (a, b) {
+            (CellValue::Number(na), CellValue::Number(nb)) => na.partial_cmp(nb).unwrap_or(Ordering::Equal),
+            (CellValue::Boolean(ba), CellValue::Boolean(bb)) => ba.cmp(bb),
+            (CellValue::Text(ta), CellValue::Text(tb)) => text.compare(ta, tb),
+            (CellValue::Date(da), CellValue::Date(db)) => da.cmp(db),
+            _ => type_rank(a).cmp(&type_rank(b)),
+        }
+    }
+
+    /// Find the row index where a row with the given values (indexed by column)
+    /// should be inserted to keep the current sort order (binary search, stable:
+    /// the new row goes after rows with equal keys).
+    /// Returns the row count (append) when no sort is active.
+    pub fn sorted_insert_position(&self, values: &[CellValue]) -> usize {
+        let keys = self.active_sort_keys();
+        if keys.is_empty() {
+            return self.rows;
+        }
+
+        let comparators: Vec<SortComparator> = keys.iter().map(|(col, _)| self.sort_comparator(*col)).collect();
+        let compare_row = |row: usize| -> std::cmp::Ordering {
+            for ((col, ascending), comparator) in keys.iter().zip(&comparators) {
+                let existing = self.get_value(row, *col);
+                let new_value = values.get(*col).cloned().unwrap_or(CellValue::Empty);
+                let cmp = comparator.compare(&existing, &new_value);
+                let cmp = if *ascending { cmp } else { cmp.reverse() };
+                if cmp != std::cmp::Ordering::Equal {
+                    return cmp;
+                }
+            }
+            std::cmp::Ordering::Equal
+        };
+
+        // Upper bound: first row that sorts strictly after the new values
+        let (mut low, mut high) = (0, self.rows);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if compare_row(mid) == std::cmp::Ordering::Greater {
+                high = mid;
+            } else {
+                low = mid + 1;
             }
         }
-        self.row_heights = new_row_heights;
+        low
     }
 
     /// Clear multi-column sort
@@ -705,19 +1991,19 @@ impl Grid {
         &self.sort_columns
     }
 
-    /// Apply filter to a column
-    pub fn apply_column_filter<F>(&mut self, col: usize, predicate: F)
-    where
-        F: Fn(&CellValue) -> bool,
-    {
-        self.filtered_rows.clear();
+    /// Distinct values of a column with their occurrence counts, in sort order
+    pub fn column_value_counts(&self, col: usize) -> Vec<(CellValue, usize)> {
+        let mut values: Vec<CellValue> = (0..self.rows).map(|row| self.get_value(row, col)).collect();
+        values.sort_by(Self::compare_values);
 
-        for row in 0..self.rows {
-            let value = self.get_value(row, col);
-            if !predicate(&value) {
-                self.filtered_rows.insert(row);
+        let mut counts: Vec<(CellValue, usize)> = Vec::new();
+        for value in values {
+            match counts.last_mut() {
+                Some((last, count)) if Self::compare_values(last, &value) == std::cmp::Ordering::Equal => *count += 1,
+                _ => counts.push((value, 1)),
             }
         }
+        counts
     }
 
     /// Clear all filters
@@ -725,11 +2011,44 @@ impl Grid {
         self.filtered_rows.clear();
     }
 
+    /// Get the rows hidden by filters
+    pub fn filtered_rows(&self) -> &HashSet<usize> {
+        &self.filtered_rows
+    }
+
+    /// Replace the rows hidden by filters (e.g. to restore a previous filter)
+    pub fn set_filtered_rows(&mut self, rows: HashSet<usize>) {
+        self.filtered_rows = rows;
+    }
+
     /// Check if a row is filtered (hidden)
     pub fn is_row_filtered(&self, row: usize) -> bool {
         self.filtered_rows.contains(&row)
     }
 
+    /// Check if a row is hidden by preview mode sampling
+    pub fn is_row_preview_hidden(&self, row: usize) -> bool {
+        match self.preview_mode {
+            Some(PreviewMode::EveryNth(k)) => k > 1 && !row.is_multiple_of(k),
+            Some(PreviewMode::FirstN(n)) => row >= n,
+            None => false,
+        }
+    }
+
+    /// Get count of rows hidden by preview mode
+    pub fn preview_hidden_count(&self) -> usize {
+        match self.preview_mode {
+            Some(PreviewMode::EveryNth(k)) if k > 1 => self.rows - self.rows.div_ceil(k),
+            Some(PreviewMode::FirstN(n)) => self.rows.saturating_sub(n),
+            _ => 0,
+        }
+    }
+
+    /// Check if a row is hidden from display (by filters, preview mode or set_row_visible)
+    pub fn is_row_hidden(&self, row: usize) -> bool {
+        self.is_row_filtered(row) || self.is_row_preview_hidden(row) || self.is_row_user_hidden(row)
+    }
+
     /// Get count of visible (non-filtered) rows
     pub fn visible_row_count(&self) -> usize {
         self.rows - self.filtered_rows.len()
@@ -743,7 +2062,7 @@ impl Grid {
 
         let mut y = 0.0;
         for row in 0..self.frozen_rows.min(self.rows) {
-            y += self.row_height(row);
+            y += self.row_extent(row);
         }
         (0.0, y)
     }
@@ -792,6 +2111,7 @@ impl Grid {
     pub fn restore_row_cells(&mut self, row: usize, cells: &[(usize, Cell)]) {
         for (col, cell) in cells {
             self.cells.insert((row, *col), cell.clone());
+            self.mark_changed(*col, row, row);
         }
     }
 
@@ -799,9 +2119,110 @@ impl Grid {
     pub fn restore_column_cells(&mut self, col: usize, cells: &[(usize, Cell)]) {
         for (row, cell) in cells {
             self.cells.insert((*row, col), cell.clone());
+            self.mark_changed(col, *row, *row);
+        }
+    }
+
+    /// Enable or disable tracking of changed rows per column
+    /// Disabling discards any pending changes
+    pub fn set_column_change_tracking(&mut self, enabled: bool) {
+        if enabled {
+            self.column_changes.get_or_insert_with(BTreeMap::new);
+        } else {
+            self.column_changes = None;
         }
     }
 
+    /// Change counter, incremented on every data or layout change
+    /// (frozen panes are plain fields and are not counted)
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Data version, incremented on every change to cell values or to the rows and
+    /// columns (insert, delete, sort, resize); layout and style changes do not count
+    pub fn data_version(&self) -> u64 {
+        self.data_version
+    }
+
+    /// Check if column change tracking is enabled
+    pub fn is_column_change_tracking(&self) -> bool {
+        self.column_changes.is_some()
+    }
+
+    /// Record that rows start..=end of a column changed
+    fn mark_changed(&mut self, col: usize, start: usize, end: usize) {
+        self.revision += 1;
+        self.data_version += 1;
+        self.col_revisions.insert(col, self.revision);
+        if let Some(changes) = self.column_changes.as_mut() {
+            let ranges = changes.entry(col).or_default();
+            // Extend the last range when contiguous (common for sequential loads)
+            if let Some(last) = ranges.last_mut() {
+                if start <= last.1 + 1 && end + 1 >= last.0 {
+                    last.0 = last.0.min(start);
+                    last.1 = last.1.max(end);
+                    return;
+                }
+            }
+            ranges.push((start, end));
+        }
+    }
+
+    /// Record that all rows from start_row onward changed in every column
+    fn mark_all_changed(&mut self, start_row: usize) {
+        self.revision += 1;
+        self.data_version += 1;
+        self.structure_revision = self.revision;
+        if self.column_changes.is_none() || start_row >= self.rows {
+            return;
+        }
+        let end_row = self.rows - 1;
+        for col in 0..self.cols {
+            self.mark_changed(col, start_row, end_row);
+        }
+    }
+
+    /// Check whether data in any of the given columns changed after `revision` (see revision())
+    /// Row inserts, deletes and sorts count for every column
+    pub fn columns_changed_since(&self, cols: &[usize], revision: u64) -> bool {
+        self.structure_changed_since(revision)
+            || cols.iter().any(|col| self.col_revisions.get(col).is_some_and(|&r| r > revision))
+    }
+
+    /// Check whether rows were inserted, deleted or reordered after `revision`
+    pub fn structure_changed_since(&self, revision: u64) -> bool {
+        self.structure_revision > revision
+    }
+
+    /// Check if there are pending column changes
+    pub fn has_column_changes(&self) -> bool {
+        self.column_changes.as_ref().is_some_and(|changes| !changes.is_empty())
+    }
+
+    /// Take pending changes as (col, merged inclusive row ranges), sorted by column
+    pub fn take_column_changes(&mut self) -> Vec<(usize, Vec<(usize, usize)>)> {
+        let Some(changes) = self.column_changes.as_mut() else {
+            return Vec::new();
+        };
+
+        std::mem::take(changes)
+            .into_iter()
+            .filter(|(col, _)| *col < self.cols)
+            .map(|(col, mut ranges)| {
+                ranges.sort_unstable();
+                let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+                for (start, end) in ranges {
+                    match merged.last_mut() {
+                        Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+                        _ => merged.push((start, end)),
+                    }
+                }
+                (col, merged)
+            })
+            .collect()
+    }
+
     /// Convert column index to letter (0 -> A, 1 -> B, ..., 25 -> Z, 26 -> AA, ...)
     pub fn column_index_to_letter(index: usize) -> String {
         let mut result = String::new();
@@ -816,6 +2237,29 @@ impl Grid {
         result
     }
 
+    /// Parse a spreadsheet column letter ("A", "z", "AA") back to its index
+    pub fn column_index_from_letter(letter: &str) -> Option<usize> {
+        if letter.is_empty() || !letter.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        let mut n: usize = 0;
+        for c in letter.chars() {
+            n = n.checked_mul(26)?.checked_add((c.to_ascii_uppercase() as u8 - b'A') as usize + 1)?;
+        }
+        Some(n - 1)
+    }
+
+    /// Label shown in a row's header (row_label_start-based number)
+    pub fn row_label(&self, row: usize) -> String {
+        (row + self.row_label_start).to_string()
+    }
+
+    /// Row index for a row header label (None if not a number or outside the grid)
+    pub fn row_index_from_label(&self, label: &str) -> Option<usize> {
+        let number: usize = label.trim().parse().ok()?;
+        number.checked_sub(self.row_label_start).filter(|&row| row < self.rows)
+    }
+
     /// Set column configuration by index
     pub fn set_column_config(&mut self, col: usize, config: ColumnConfig) {
         if col < self.cols {
@@ -864,14 +2308,71 @@ impl Grid {
         self.header_levels = max_level + 2; // +1 for the level itself (0-indexed), +1 for the column headers row
 
         // Update total header height
-        self.col_header_height = self.header_row_height * self.header_levels as f32;
+        self.col_header_height = self.header_row_height * self.header_levels as f32 + self.column_summary_height;
+    }
+
+    /// Separator after a column (None = plain grid line)
+    pub fn col_section_break(&self, col: usize) -> Option<SectionBreak> {
+        self.col_section_breaks.get(&col).copied()
+    }
+
+    /// Separator after a row (None = plain grid line)
+    pub fn row_section_break(&self, row: usize) -> Option<SectionBreak> {
+        self.row_section_breaks.get(&row).copied()
+    }
+
+    /// Move the breaks after an inserted or deleted row/column at `at`; the break after a
+    /// deleted one moves to the one before it, so the section still ends in the same place
+    fn shift_section_breaks(breaks: &mut BTreeMap<usize, SectionBreak>, at: usize, insert: bool) {
+        let mut moved = breaks.split_off(&at);
+        if insert {
+            breaks.extend(moved.into_iter().map(|(index, style)| (index + 1, style)));
+            return;
+        }
+        let removed = moved.remove(&at);
+        breaks.extend(moved.into_iter().map(|(index, style)| (index - 1, style)));
+        if let Some(style) = removed.filter(|_| at > 0) {
+            breaks.entry(at - 1).or_insert(style);
+        }
+    }
+
+    /// Rectangles (x, y, width, height) of the section separators crossing a region
+    /// The separator on the region's left/top edge is included so each pane draws its half
+    /// y is measured from the top of `row_start`, so far-down rows keep f32 precision
+    pub fn section_break_rects(&self, row_start: usize, row_end: usize, col_start: usize, col_end: usize) -> Vec<[f32; 4]> {
+        let mut rects = Vec::new();
+        let (row_end, col_end) = (row_end.min(self.rows), col_end.min(self.cols));
+        if row_start >= row_end || col_start >= col_end {
+            return rects;
+        }
+
+        let (x_start, x_end) = (self.col_x_position(col_start), self.col_x_position(col_end));
+        let origin = self.row_y_position(row_start);
+        let (y_start, y_end) = (0.0, (self.row_y_position(row_end) - origin) as f32);
+
+        for (&col, section_break) in self.col_section_breaks.range(col_start.saturating_sub(1)..col_end) {
+            let x = self.col_x_position(col + 1);
+            for &(offset, width) in section_break.strokes() {
+                rects.push([x + offset, y_start, width, y_end - y_start]);
+            }
+        }
+        for (&row, section_break) in self.row_section_breaks.range(row_start.saturating_sub(1)..row_end) {
+            if self.is_row_hidden(row) {
+                continue;
+            }
+            let y = (self.row_y_position(row + 1) - origin) as f32;
+            for &(offset, height) in section_break.strokes() {
+                rects.push([x_start, y + offset, x_end - x_start, height]);
+            }
+        }
+        rects
     }
 
     /// Clear all column groups
     pub fn clear_column_groups(&mut self) {
         self.column_groups.clear();
         self.header_levels = 1;
-        self.col_header_height = self.header_row_height;
+        self.col_header_height = self.header_row_height + self.column_summary_height;
     }
 
     /// Get column groups at a specific level
@@ -882,10 +2383,16 @@ impl Grid {
             .collect()
     }
 
+    /// Show (height > 0) or hide the summary band under the column headers
+    pub fn set_column_summary_height(&mut self, height: f32) {
+        self.col_header_height += height - self.column_summary_height;
+        self.column_summary_height = height;
+    }
+
     /// Set header row height (affects total header height)
     pub fn set_header_row_height(&mut self, height: f32) {
         self.header_row_height = height;
-        self.col_header_height = self.header_row_height * self.header_levels as f32;
+        self.col_header_height = self.header_row_height * self.header_levels as f32 + self.column_summary_height;
     }
 
     // ========== Column Validation ==========
@@ -916,6 +2423,99 @@ impl Grid {
         None
     }
 
+    /// Set (or clear with None) the required/range/length/list constraints of a column
+    pub fn set_column_rule(&mut self, col: usize, rule: Option<ValidationRule>) {
+        if let Some(config) = self.column_configs.get_mut(col) {
+            config.validation_rule = rule;
+        }
+    }
+
+    /// Get the required/range/length/list constraints of a column
+    pub fn get_column_rule(&self, col: usize) -> Option<&ValidationRule> {
+        self.column_configs.get(col).and_then(|config| config.validation_rule.as_ref())
+    }
+
+    /// Keep (or clear with None) a validation error message on a cell
+    pub fn set_validation_error(&mut self, row: usize, col: usize, message: Option<String>) {
+        if message.is_none() && self.get_cell(row, col).is_none_or(|cell| cell.validation_error.is_none()) {
+            return;
+        }
+        if row < self.rows && col < self.cols {
+            self.cells.entry((row, col)).or_default().validation_error = message;
+        }
+    }
+
+    /// Cells marked with a validation error, in row then column order
+    pub fn validation_errors(&self) -> Vec<(usize, usize, String)> {
+        let mut errors: Vec<(usize, usize, String)> = self
+            .iter_cells()
+            .filter_map(|((row, col), cell)| cell.validation_error.clone().map(|message| (row, col, message)))
+            .collect();
+        errors.sort_by_key(|&(row, col, _)| (row, col));
+        errors
+    }
+
+    // ========== Unique Constraint ==========
+
+    /// Require values in a column to be unique (empty cells are ignored)
+    pub fn set_column_unique(&mut self, col: usize, unique: bool) {
+        if col < self.column_configs.len() {
+            self.column_configs[col].unique = unique;
+        }
+    }
+
+    /// Check if a column has a unique constraint
+    pub fn is_column_unique(&self, col: usize) -> bool {
+        self.column_configs.get(col).map(|c| c.unique).unwrap_or(false)
+    }
+
+    /// Key a value would be compared with in the column: text (e.g. an editor draft) is
+    /// first converted by the column's data type, as a commit would store it
+    pub fn unique_key(&self, col: usize, value: &CellValue) -> Option<UniqueKey> {
+        match (value, self.column_configs.get(col)) {
+            (CellValue::Text(text), Some(config)) => UniqueKey::of(&config.data_type.convert_text(text)),
+            _ => UniqueKey::of(value),
+        }
+    }
+
+    /// Find another row (not in `except_rows`) in the column holding the same value
+    pub fn find_duplicate_row(&self, col: usize, value: &CellValue, except_rows: &[usize]) -> Option<usize> {
+        let key = self.unique_key(col, value)?;
+        let mut indexes = self.unique_indexes.borrow_mut();
+        if indexes.get(&col).is_none_or(|index| index.data_version != self.data_version) {
+            let mut rows: HashMap<UniqueKey, Vec<usize>> = HashMap::new();
+            for row in 0..self.rows {
+                if let Some(key) = UniqueKey::of(&self.get_value(row, col)) {
+                    rows.entry(key).or_default().push(row);
+                }
+            }
+            indexes.insert(col, UniqueIndex { data_version: self.data_version, rows });
+        }
+        indexes[&col].rows.get(&key)?.iter().copied().find(|row| !except_rows.contains(row))
+    }
+
+    /// Groups of rows sharing a value in the column: (value, rows), ordered by first row
+    pub fn uniqueness_violations(&self, col: usize) -> Vec<(String, Vec<usize>)> {
+        let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+        let mut index: HashMap<UniqueKey, usize> = HashMap::new();
+
+        for row in 0..self.rows {
+            let value = self.get_value(row, col);
+            if let Some(key) = UniqueKey::of(&value) {
+                match index.get(&key) {
+                    Some(&i) => groups[i].1.push(row),
+                    None => {
+                        index.insert(key, groups.len());
+                        groups.push((value.to_string(), vec![row]));
+                    }
+                }
+            }
+        }
+
+        groups.retain(|(_, rows)| rows.len() > 1);
+        groups
+    }
+
     // ========== Column Editable Control ==========
 
     /// Set whether a column is editable
@@ -933,6 +2533,97 @@ impl Grid {
         true // Default to editable if column doesn't exist
     }
 
+    /// Lock (or unlock) a single cell
+    pub fn set_cell_editable(&mut self, row: usize, col: usize, editable: bool) {
+        if row >= self.rows || col >= self.cols || (editable && self.get_cell(row, col).is_none()) {
+            return;
+        }
+        self.cells.entry((row, col)).or_default().editable = editable;
+    }
+
+    /// Lock (or unlock) every cell of a row
+    pub fn set_row_readonly(&mut self, row: usize, readonly: bool) {
+        let Some(id) = self.row_id(row) else { return };
+        if readonly {
+            self.readonly_rows.insert(id);
+        } else {
+            self.readonly_rows.remove(&id);
+        }
+    }
+
+    /// Check if a row is locked with set_row_readonly
+    pub fn is_row_readonly(&self, row: usize) -> bool {
+        !self.readonly_rows.is_empty() && self.row_id(row).is_some_and(|id| self.readonly_rows.contains(&id))
+    }
+
+    /// Height limits and resizable flag of a row
+    pub fn row_limits(&self, row: usize) -> RowLimits {
+        self.row_id(row).and_then(|id| self.row_limits.get(&id)).copied().unwrap_or_default()
+    }
+
+    /// Set a row's height limits and resizable flag (they follow the row through sorts and inserts)
+    pub fn set_row_limits(&mut self, row: usize, limits: RowLimits) {
+        let Some(id) = self.row_id(row) else { return };
+        if limits == RowLimits::default() {
+            self.row_limits.remove(&id);
+        } else {
+            self.row_limits.insert(id, limits);
+        }
+    }
+
+    /// Rows with non-default limits, in order
+    pub fn limited_rows(&self) -> Vec<(usize, RowLimits)> {
+        let mut rows: Vec<(usize, RowLimits)> = self
+            .row_limits
+            .iter()
+            .filter_map(|(&id, &limits)| self.row_index_by_id(id).map(|row| (row, limits)))
+            .collect();
+        rows.sort_unstable_by_key(|&(row, _)| row);
+        rows
+    }
+
+    /// Width limits (min, max) of a column
+    pub fn col_width_limits(&self, col: usize) -> (f32, f32) {
+        self.column_configs.get(col).map_or((MIN_COL_WIDTH, f32::INFINITY), ColumnConfig::width_limits)
+    }
+
+    /// Check if a column can be resized by dragging
+    pub fn is_col_resizable(&self, col: usize) -> bool {
+        self.column_configs.get(col).is_none_or(|config| config.resizable)
+    }
+
+    /// Rows locked with set_row_readonly, in order
+    pub fn readonly_rows(&self) -> Vec<usize> {
+        let mut rows: Vec<usize> = self.readonly_rows.iter().filter_map(|&id| self.row_index_by_id(id)).collect();
+        rows.sort_unstable();
+        rows
+    }
+
+    /// Check if a cell can be changed by editing, paste, Delete or fill: the grid is not
+    /// read-only, neither the column, the row nor the cell is locked, no editable rule
+    /// matches its row and the editable callback (if set) does not return false. Ghost
+    /// cells only check the grid and column. A callback that throws locks the cell.
+    pub fn is_cell_editable(&self, row: usize, col: usize) -> bool {
+        if self.readonly
+            || !self.is_column_editable(col)
+            || self.get_cell(row, col).is_some_and(|cell| !cell.editable)
+            || self.is_row_readonly(row)
+        {
+            return false;
+        }
+        if row >= self.rows {
+            return true;
+        }
+        if self.editable_rules.iter().any(|rule| rule.locks(self, row, col)) {
+            return false;
+        }
+        self.editable_callback.as_ref().is_none_or(|callback| {
+            callback
+                .call2(&wasm_bindgen::JsValue::NULL, &(row as f64).into(), &(col as f64).into())
+                .is_ok_and(|result| result.as_bool() != Some(false))
+        })
+    }
+
     /// Get editable status for all columns
     pub fn get_all_column_editable_status(&self) -> Vec<bool> {
         self.column_configs.iter().map(|c| c.editable).collect()
@@ -967,6 +2658,83 @@ impl Grid {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_hidden_column_layout() {
+        let mut grid = Grid::new(2, 3);
+        grid.set_col_width(1, 80.0);
+        assert!(grid.set_col_visible(1, false));
+        assert!(!grid.set_col_visible(1, false));
+        assert_eq!(grid.col_width(1), 0.0);
+        assert_eq!(grid.col_x_position(2), grid.col_width(0));
+        assert_eq!(grid.total_width(), grid.col_width(0) + grid.col_width(2));
+
+        grid.set_col_visible(1, true);
+        assert_eq!(grid.col_width(1), 80.0); // Width survives hiding
+    }
+
+    #[test]
+    fn test_flex_widths() {
+        let mut grid = Grid::new(1, 4);
+        grid.set_col_width(0, 100.0);
+        for (col, flex) in [(1, 1.0), (2, 2.0), (3, 1.0)] {
+            let config = &mut grid.column_configs[col];
+            config.width_mode = WidthMode::Flex;
+            config.flex = flex;
+        }
+
+        // 400px left over by the fixed column: 100 / 200 / 100
+        assert!(grid.distribute_flex_widths(500.0));
+        assert_eq!((grid.col_width(1), grid.col_width(2), grid.col_width(3)), (100.0, 200.0, 100.0));
+        assert!(!grid.distribute_flex_widths(500.0));
+
+        // A capped column gives its excess to the others
+        grid.column_configs[2].max_width = Some(120.0);
+        grid.distribute_flex_widths(500.0);
+        assert_eq!((grid.col_width(1), grid.col_width(2), grid.col_width(3)), (140.0, 120.0, 140.0));
+
+        // Without room, flex columns shrink to their minimum
+        grid.column_configs[1].min_width = Some(50.0);
+        grid.distribute_flex_widths(100.0);
+        assert_eq!((grid.col_width(1), grid.col_width(3)), (50.0, MIN_COL_WIDTH));
+
+        // Invalid weights are rejected, and a bad one set directly cannot hang the layout
+        assert!(WidthMode::flex_weight(f64::INFINITY).is_err());
+        assert!(WidthMode::flex_weight(0.0).is_err());
+        grid.column_configs[2].flex = f32::INFINITY;
+        grid.distribute_flex_widths(500.0);
+    }
+
+    #[test]
+    fn test_hidden_row_layout() {
+        let mut grid = Grid::new(4, 1);
+        grid.set_row_height(2, 40.0);
+        assert!(grid.set_row_visible(2, false));
+        assert!(!grid.set_row_visible(2, false));
+        assert_eq!(grid.row_height(2), 0.0);
+        assert_eq!(grid.row_y_position(3), 2.0 * grid.default_row_height as f64);
+        assert_eq!(grid.total_height(), 3.0 * grid.default_row_height as f64);
+        assert_eq!(grid.hidden_rows(), vec![2]);
+        assert_eq!(grid.row_extent_at_y(2.0 * grid.default_row_height as f64), Some(3)); // Skips the hidden row
+
+        // The flag follows the row when an earlier row is deleted
+        grid.delete_row(0);
+        assert_eq!(grid.hidden_rows(), vec![1]);
+        grid.set_row_visible(1, true);
+        assert_eq!(grid.row_height(1), 40.0); // Height survives hiding
+
+        // Detail panels and transitions are in the positions, and move with their row on a sort
+        let default = grid.default_row_height as f64;
+        grid.set_row_detail(0, Some(100.0));
+        grid.set_row_transition(grid.row_id(2).unwrap(), 10.0);
+        assert_eq!(grid.total_height(), 2.0 * default + 150.0);
+        assert_eq!(grid.row_at_y(default + 50.0), None); // On the panel
+        grid.permute_rows(&[2, 1, 0]);
+        assert_eq!(grid.row_y_position(1), default + 10.0);
+        assert_eq!(grid.row_y_position(2), default + 50.0);
+        assert_eq!(grid.row_at_y(default + 60.0), Some(2));
+        assert_eq!(grid.total_height(), 2.0 * default + 150.0);
+    }
+
     #[test]
     fn test_grid_creation() {
         let grid = Grid::new(100, 50);
@@ -984,4 +2752,401 @@ mod tests {
         grid.set_value(3, 3, CellValue::Number(42.0));
         assert_eq!(grid.get_value_string(3, 3), "42");
     }
+
+    #[test]
+    fn test_sorted_insert_position() {
+        let mut grid = Grid::new(4, 2);
+        for (row, n) in [1.0, 3.0, 3.0, 7.0].iter().enumerate() {
+            grid.set_value(row, 0, CellValue::Number(*n));
+        }
+
+        // No active sort: append
+        assert_eq!(grid.sorted_insert_position(&[CellValue::Number(2.0)]), 4);
+
+        grid.sort_column = Some(0);
+        grid.sort_ascending = true;
+        assert_eq!(grid.sorted_insert_position(&[CellValue::Number(0.0)]), 0);
+        assert_eq!(grid.sorted_insert_position(&[CellValue::Number(3.0)]), 3);
+        assert_eq!(grid.sorted_insert_position(&[CellValue::Number(9.0)]), 4);
+        // Empty values sort last
+        assert_eq!(grid.sorted_insert_position(&[CellValue::Empty]), 4);
+    }
+
+    #[test]
+    fn test_column_change_tracking() {
+        let mut grid = Grid::new(10, 3);
+        grid.set_value(0, 0, CellValue::Number(1.0));
+        assert!(grid.take_column_changes().is_empty());
+
+        grid.set_column_change_tracking(true);
+        grid.set_value(5, 1, CellValue::Number(1.0));
+        grid.set_value(2, 1, CellValue::Number(1.0));
+        grid.set_value(3, 1, CellValue::Number(1.0));
+        grid.set_value(4, 1, CellValue::Number(1.0));
+        grid.set_value(8, 1, CellValue::Number(1.0));
+        assert_eq!(grid.take_column_changes(), vec![(1, vec![(2, 5), (8, 8)])]);
+        assert!(grid.take_column_changes().is_empty());
+
+        // Structural changes report shifted rows in every column
+        grid.delete_row(7);
+        let changes = grid.take_column_changes();
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0], (0, vec![(7, 8)]));
+    }
+
+    #[test]
+    fn test_preview_mode() {
+        let mut grid = Grid::new(10, 2);
+
+        grid.preview_mode = Some(PreviewMode::EveryNth(3));
+        assert!(!grid.is_row_hidden(3));
+        assert!(grid.is_row_hidden(4));
+        assert_eq!(grid.row_height(4), 0.0);
+        assert_eq!(grid.preview_hidden_count(), 6); // rows 0, 3, 6, 9 shown
+        assert_eq!(grid.total_height(), 4.0 * 25.0);
+
+        grid.preview_mode = Some(PreviewMode::FirstN(4));
+        assert_eq!(grid.preview_hidden_count(), 6);
+
+        grid.preview_mode = None;
+        assert_eq!(grid.total_height(), 10.0 * 25.0);
+    }
+
+    #[test]
+    fn test_auto_expand_size() {
+        let mut grid = Grid::new(3, 2);
+        assert_eq!(grid.auto_expand_size(10, 10), (3, 2));
+
+        grid.auto_expand = Some((5, 4));
+        assert_eq!(grid.auto_expand_size(10, 3), (5, 3));
+        assert_eq!(grid.auto_expand_size(1, 1), (3, 2));
+
+        grid.resize(5, 3);
+        assert_eq!(grid.column_configs.len(), 3);
+        assert_eq!(grid.get_column_config(2).unwrap().internal_name, "col_2");
+    }
+
+    #[test]
+    fn test_uniqueness_violations() {
+        let mut grid = Grid::new(4, 2);
+        grid.set_column_unique(0, true);
+        grid.column_configs[0].data_type = DataType::Number;
+        grid.set_value(0, 0, CellValue::Text("a".to_string()));
+        grid.set_value(1, 0, CellValue::Number(1.0));
+        grid.set_value(2, 0, CellValue::Text("a".to_string()));
+
+        assert!(grid.is_column_unique(0));
+        assert_eq!(grid.uniqueness_violations(0), vec![("a".to_string(), vec![0, 2])]);
+        assert_eq!(grid.find_duplicate_row(0, &CellValue::Text("1.0".to_string()), &[]), Some(1));
+        assert_eq!(grid.find_duplicate_row(0, &CellValue::Text("a".to_string()), &[0, 2]), None);
+        assert_eq!(grid.find_duplicate_row(0, &CellValue::Empty, &[]), None);
+
+        // Edits keep the index current; a number and the same text in a text column differ
+        grid.set_value(1, 0, CellValue::Number(2.0));
+        grid.set_value(3, 0, CellValue::Number(1.0));
+        assert_eq!(grid.find_duplicate_row(0, &CellValue::Number(1.0), &[]), Some(3));
+        grid.set_value(0, 1, CellValue::Number(1.0));
+        assert_eq!(grid.find_duplicate_row(1, &CellValue::Text("1".to_string()), &[]), None);
+    }
+
+    #[test]
+    fn test_display_string_number_format() {
+        let mut grid = Grid::new(3, 2);
+        grid.set_value(0, 0, CellValue::Number(1234567.891));
+        grid.set_value(1, 0, CellValue::Number(-0.5));
+        grid.set_value(2, 0, CellValue::Text("n/a".to_string()));
+        grid.set_value(0, 1, CellValue::Number(1234.5));
+        assert_eq!(grid.get_display_string(0, 0), "1234567.891");
+
+        let format = NumberFormat { decimals: Some(2), prefix: "$".to_string(), ..NumberFormat::default() };
+        grid.set_column_number_format(0, Some(format));
+        assert_eq!(grid.get_display_string(0, 0), "$1,234,567.89");
+        assert_eq!(grid.get_display_string(1, 0), "-$0.50");
+        assert_eq!(grid.get_display_string(2, 0), "n/a");
+
+        let format = NumberFormat { suffix: " €".to_string(), ..NumberFormat::for_locale("de-DE") };
+        grid.set_column_number_format(1, Some(format));
+        assert_eq!(grid.get_display_string(0, 1), "1.234,5 €");
+        assert_eq!(grid.get_value_string(0, 1), "1234.5");
+    }
+
+    #[test]
+    fn test_editable_rules() {
+        let mut grid = Grid::new(3, 3);
+        grid.column_configs[0].internal_name = "status".to_string();
+        grid.set_value(1, 0, CellValue::Text("locked".to_string()));
+        let rule = serde_json::json!({ "column": "status", "equals": "locked", "columns": [2] });
+        grid.editable_rules = vec![EditableRule::from_json(&rule, &grid).unwrap()];
+
+        assert!(!grid.is_cell_editable(1, 2));
+        assert!(grid.is_cell_editable(1, 1)); // Not in "columns"
+        assert!(grid.is_cell_editable(0, 2));
+        assert!(grid.is_cell_editable(3, 2)); // Ghost row: no data to test
+
+        // null matches empty cells
+        let rule = serde_json::json!({ "column": 0, "not_in": [null] });
+        grid.editable_rules = vec![EditableRule::from_json(&rule, &grid).unwrap()];
+        assert!(!grid.is_cell_editable(1, 1));
+        assert!(grid.is_cell_editable(0, 1));
+        assert!(EditableRule::from_json(&serde_json::json!({ "column": "missing", "equals": 1 }), &grid).is_err());
+
+        // Rules follow their columns; deleting the tested column drops the rule
+        let rule = serde_json::json!({ "column": 0, "equals": "locked", "columns": [2] });
+        grid.editable_rules = vec![EditableRule::from_json(&rule, &grid).unwrap()];
+        grid.insert_column(1);
+        assert!(!grid.is_cell_editable(1, 3));
+        assert!(grid.is_cell_editable(1, 2));
+        grid.move_column(0, 3);
+        assert_eq!((grid.editable_rules[0].column, grid.editable_rules[0].columns.clone()), (3, vec![2]));
+        grid.delete_column(3);
+        assert!(grid.editable_rules.is_empty());
+    }
+
+    #[test]
+    fn test_readonly_rows_and_cells() {
+        let mut grid = Grid::new(4, 2);
+        grid.set_row_readonly(1, true);
+        grid.set_cell_editable(2, 1, false);
+        assert!(!grid.is_cell_editable(1, 0));
+        assert!(!grid.is_cell_editable(2, 1));
+        assert!(grid.is_cell_editable(2, 0));
+
+        // The row flag follows its row; deleted rows drop it
+        grid.insert_row(0);
+        assert!(grid.is_row_readonly(2) && !grid.is_row_readonly(1));
+        assert_eq!(grid.readonly_rows(), vec![2]);
+        grid.delete_row(2);
+        assert!(grid.readonly_rows().is_empty());
+
+        grid.set_cell_editable(3, 1, true);
+        assert!(grid.is_cell_editable(3, 1));
+    }
+
+    #[test]
+    fn test_validation_rule() {
+        let json = serde_json::json!({ "required": true, "min": 1, "max": 10, "max_length": 3 });
+        let rule = ValidationRule::from_json(&json).unwrap();
+        assert_eq!(rule.check(""), Some(RuleViolation::Required));
+        assert_eq!(rule.check("0"), Some(RuleViolation::Min(1.0)));
+        assert_eq!(rule.check("11"), Some(RuleViolation::Max(10.0)));
+        assert_eq!(rule.check("abcd"), Some(RuleViolation::MaxLength(3)));
+        assert_eq!(rule.check("5"), None);
+        assert_eq!(rule.check("abc"), None); // Not a number: ranges do not apply
+
+        let json = serde_json::json!({ "min_date": "2024-01-01", "max_date": "2024-12-31", "allowed": null });
+        let rule = ValidationRule::from_json(&json).unwrap();
+        assert_eq!(rule.check("2023-12-31"), Some(RuleViolation::MinDate("2024-01-01".to_string())));
+        assert_eq!(rule.check("2024-12-31 10:00"), None);
+        assert_eq!(ValidationRule::from_json(&rule.to_json()).unwrap(), rule);
+
+        let rule = ValidationRule::from_json(&serde_json::json!({ "allowed": ["A", "B"] })).unwrap();
+        assert_eq!(rule.check("C"), Some(RuleViolation::NotAllowed));
+        assert!(ValidationRule::from_json(&serde_json::json!({ "maximum": 3 })).is_err());
+        assert!(ValidationRule::from_json(&serde_json::json!({ "min_date": "1/2/2024" })).is_err());
+    }
+
+    #[test]
+    fn test_density_keeps_custom_row_heights() {
+        let mut grid = Grid::new(3, 2);
+        grid.set_row_height(1, 60.0);
+        grid.column_configs[1].padding = Some(12.0);
+
+        grid.set_density(Density::Compact);
+        assert_eq!(grid.row_height(0), 20.0);
+        assert_eq!(grid.row_height(1), 60.0);
+        assert_eq!(grid.default_row_height(), 20.0);
+        assert_eq!(grid.cell_padding(0), 3.0);
+        assert_eq!(grid.cell_padding(1), 12.0);
+
+        grid.resize(4, 2);
+        assert_eq!(grid.row_height(3), 20.0);
+    }
+
+    #[test]
+    fn test_row_positions_at_scale() {
+        let mut grid = Grid::new(100_000, 2);
+        let height = grid.default_row_height() as f64;
+        assert_eq!(grid.row_y_position(50_000), 50_000.0 * height);
+        assert_eq!(grid.row_at_y(10.5 * height), Some(10));
+
+        grid.set_row_height(3, grid.default_row_height() * 2.0);
+        assert_eq!(grid.row_y_position(10), 11.0 * height);
+        assert_eq!(grid.row_at_y(4.5 * height), Some(3));
+        assert_eq!(grid.row_at_y(5.5 * height), Some(4));
+        grid.delete_row(3);
+        assert_eq!(grid.total_height(), 99_999.0 * height);
+
+        // Ten million rows keep no per-row storage and distinct row positions
+        let mut grid = Grid::new(10_000_000, 2);
+        assert!(grid.row_ids.is_empty() && grid.row_heights.is_empty());
+        assert_eq!(grid.row_id(9_999_999), Some(10_000_000));
+        let last = grid.row_y_position(9_999_999);
+        assert!(last > grid.row_y_position(9_999_998));
+        assert_eq!(grid.row_at_y(last + 1.0), Some(9_999_999));
+
+        // A hidden row is one indexed extent, not a walk over every row
+        grid.set_row_visible(5, false);
+        assert_eq!(grid.row_extents().deltas.len(), 1);
+        assert_eq!(grid.total_height(), 9_999_999.0 * height);
+        assert_eq!(grid.row_at_y(5.5 * height), Some(6));
+    }
+
+    #[test]
+    fn test_section_break_rects() {
+        let mut grid = Grid::new(4, 3);
+        let (width, height) = (grid.col_width(0), grid.row_height(0));
+        grid.col_section_breaks.insert(0, SectionBreak::Double);
+        grid.row_section_breaks.insert(1, SectionBreak::Hidden);
+        grid.row_section_breaks.insert(2, SectionBreak::Thick);
+
+        let rects = grid.section_break_rects(0, 4, 0, 3);
+        assert_eq!(rects.len(), 3);
+        assert_eq!(rects[0], [width - 1.0, 0.0, 1.0, 4.0 * height]);
+        assert_eq!(rects[2], [0.0, 3.0 * height - 1.0, 3.0 * width, 3.0]);
+
+        // The separator on a pane's left edge is drawn by both panes
+        assert_eq!(grid.section_break_rects(0, 4, 1, 3).len(), 3);
+        assert_eq!(grid.section_break_rects(0, 4, 2, 3).len(), 1);
+        assert_eq!(SectionBreak::parse("THICK").unwrap(), SectionBreak::Thick);
+        assert!(SectionBreak::parse("dotted").is_err());
+
+        // Breaks follow their rows and columns through inserts, deletes and moves
+        grid.insert_row(0);
+        assert_eq!(grid.row_section_breaks.keys().copied().collect::<Vec<_>>(), vec![2, 3]);
+        grid.delete_row(3);
+        assert_eq!(grid.row_section_break(2), Some(SectionBreak::Hidden));
+        grid.move_column(0, 2);
+        assert_eq!(grid.col_section_break(2), Some(SectionBreak::Double));
+    }
+
+    #[test]
+    fn test_column_value_counts() {
+        let mut grid = Grid::new(5, 1);
+        grid.set_value(0, 0, CellValue::Text("b".to_string()));
+        grid.set_value(1, 0, CellValue::Number(2.0));
+        grid.set_value(2, 0, CellValue::Text("b".to_string()));
+        grid.set_value(3, 0, CellValue::Text("a".to_string()));
+
+        let counts = grid.column_value_counts(0);
+        assert_eq!(
+            counts,
+            vec![
+                (CellValue::Number(2.0), 1),
+                (CellValue::Text("a".to_string()), 1),
+                (CellValue::Text("b".to_string()), 2),
+                (CellValue::Empty, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_columns_changed_since() {
+        let mut grid = Grid::new(4, 3);
+        let start = grid.revision();
+        assert!(!grid.columns_changed_since(&[0, 1], start));
+
+        grid.set_value(1, 2, CellValue::Number(1.0));
+        assert!(!grid.columns_changed_since(&[0, 1], start));
+
+        grid.set_value(1, 1, CellValue::Number(1.0));
+        assert!(grid.columns_changed_since(&[0, 1], start));
+        assert!(!grid.columns_changed_since(&[0, 1], grid.revision()));
+
+        let before_delete = grid.revision();
+        grid.delete_row(0);
+        assert!(grid.columns_changed_since(&[0], before_delete));
+        assert!(grid.structure_changed_since(before_delete));
+    }
+
+    #[test]
+    fn test_data_version() {
+        let mut grid = Grid::new(4, 3);
+        let start = grid.data_version();
+        grid.set_col_width(0, 150.0);
+        grid.set_row_height(1, 40.0);
+        assert_eq!(grid.data_version(), start);
+
+        grid.set_value(0, 0, CellValue::Number(1.0));
+        let edited = grid.data_version();
+        assert!(edited > start);
+        grid.delete_row(3);
+        assert!(grid.data_version() > edited);
+
+        let deleted = grid.data_version();
+        grid.resize(3, 3);
+        assert_eq!(grid.data_version(), deleted);
+        grid.resize(5, 3);
+        assert!(grid.data_version() > deleted);
+    }
+
+    #[test]
+    fn test_index_labels() {
+        for col in [0, 25, 26, 701, 702] {
+            assert_eq!(Grid::column_index_from_letter(&Grid::column_index_to_letter(col)), Some(col));
+        }
+        assert_eq!(Grid::column_index_from_letter("ab"), Some(27));
+        assert_eq!(Grid::column_index_from_letter("A1"), None);
+
+        let mut grid = Grid::new(3, 1);
+        assert_eq!(grid.row_label(0), "1");
+        assert_eq!(grid.row_index_from_label("3"), Some(2));
+        assert_eq!(grid.row_index_from_label("0"), None);
+        grid.row_label_start = 0;
+        assert_eq!(grid.row_index_from_label("0"), Some(0));
+        assert_eq!(grid.row_index_from_label("3"), None);
+    }
+
+    #[test]
+    fn test_row_ids_follow_rows() {
+        let mut grid = Grid::new(3, 1);
+        for row in 0..3 {
+            grid.set_value(row, 0, CellValue::Number((3 - row) as f64));
+        }
+        let ids: Vec<usize> = (0..3).map(|row| grid.row_id(row).unwrap()).collect();
+
+        grid.sort_by_column(0, true);
+        assert_eq!(grid.row_index_by_id(ids[0]), Some(2));
+        assert_eq!(grid.row_index_by_id(ids[2]), Some(0));
+
+        grid.insert_row(0);
+        let new_id = grid.row_id(0).unwrap();
+        assert!(!ids.contains(&new_id));
+        assert_eq!(grid.row_index_by_id(ids[2]), Some(1));
+
+        grid.delete_row(1);
+        assert_eq!(grid.row_index_by_id(ids[2]), None);
+        assert_eq!(grid.row_index_by_id(ids[0]), Some(2));
+
+        // Restoring a deleted row keeps its ID
+        grid.insert_row_with_id(1, Some(ids[2]));
+        assert_eq!(grid.row_id(1), Some(ids[2]));
+
+        // Shrinking and growing again never hands out an ID twice
+        let mut grid = Grid::new(3, 1);
+        grid.resize(2, 1);
+        grid.resize(3, 1);
+        assert_eq!(grid.row_id(2), Some(4));
+        assert_eq!(grid.row_index_by_id(3), None);
+    }
+
+    #[test]
+    fn test_modified_keeps_first_original() {
+        let mut grid = Grid::new(3, 1);
+        grid.set_value(0, 0, CellValue::Number(3.0));
+        grid.set_value(1, 0, CellValue::Number(1.0));
+
+        grid.set_value(0, 0, CellValue::Number(5.0));
+        grid.mark_modified(0, 0, CellValue::Number(3.0));
+        grid.set_value(0, 0, CellValue::Number(7.0));
+        grid.mark_modified(0, 0, CellValue::Number(5.0));
+
+        // The original travels with the cell when rows are reordered
+        grid.sort_by_column(0, true);
+        let cell = grid.get_cell(1, 0).unwrap();
+        assert!(cell.modified);
+        assert_eq!(cell.original, Some(CellValue::Number(3.0)));
+
+        grid.clear_modified();
+        assert!(grid.iter_cells().all(|(_, cell)| !cell.modified && cell.original.is_none()));
+    }
 }
@@ -1,7 +1,9 @@
 pub mod cell;
+pub mod collation;
 pub mod grid;
 pub mod viewport;
 
-pub use cell::{Cell, CellBorders, CellValue, DataType};
-pub use grid::{ColumnConfig, Grid};
+pub use cell::{Cell, CellBorders, CellValue, DataType, NumberFormat};
+pub use collation::Collation;
+pub use grid::{ColumnConfig, Density, EditableRule, Grid, PreviewMode, RowLimits, RuleViolation, SectionBreak, SortClick, SortState, UniqueKey, ValidationRule, WidthMode};
 pub use viewport::Viewport;
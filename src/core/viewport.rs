@@ -1,19 +1,36 @@
-use super::grid::Grid;
+use super::grid::{Grid, SortClick};
+
+/// Size (px) of the funnel button in filterable column headers
+pub const FILTER_BUTTON_SIZE: f32 = 14.0;
+
+/// Gap (px) between the filter button and the header's right edge (or sort arrow zone),
+/// wide enough to keep clear of the column resize handle
+const FILTER_BUTTON_MARGIN: f32 = 6.0;
+
+/// Smallest allowed zoom factor
+pub const MIN_ZOOM: f32 = 0.25;
+
+/// Largest allowed zoom factor
+pub const MAX_ZOOM: f32 = 4.0;
 
 /// Viewport manages the visible area of the grid for virtual scrolling
 #[derive(Debug, Clone)]
 pub struct Viewport {
-    /// Canvas width in pixels
+    /// Canvas width in grid pixels (device canvas width divided by zoom)
     pub canvas_width: f32,
 
-    /// Canvas height in pixels
+    /// Canvas height in grid pixels (device canvas height divided by zoom)
     pub canvas_height: f32,
 
+    /// Zoom factor: device pixels per grid pixel (1.0 = 100%)
+    pub zoom: f32,
+
     /// Horizontal scroll offset in pixels
     pub scroll_x: f32,
 
-    /// Vertical scroll offset in pixels
-    pub scroll_y: f32,
+    /// Vertical scroll offset in pixels (f64 like row positions, so very tall grids keep
+    /// sub-pixel precision; subtract row positions before narrowing to f32)
+    pub scroll_y: f64,
 
     /// First visible row index
     pub first_visible_row: usize,
@@ -34,6 +51,7 @@ impl Viewport {
         Self {
             canvas_width,
             canvas_height,
+            zoom: 1.0,
             scroll_x: 0.0,
             scroll_y: 0.0,
             first_visible_row: 0,
@@ -43,56 +61,79 @@ impl Viewport {
         }
     }
 
-    /// Update viewport dimensions
+    /// Update viewport dimensions (device canvas size in pixels)
     pub fn resize(&mut self, width: f32, height: f32) {
-        self.canvas_width = width;
-        self.canvas_height = height;
+        self.canvas_width = width / self.zoom;
+        self.canvas_height = height / self.zoom;
     }
 
-    /// Set scroll position
-    pub fn set_scroll(&mut self, x: f32, y: f32, grid: &Grid) {
-        // Calculate the actual viewport area (excluding headers)
+    /// Set the zoom factor, keeping the grid point under (anchor_x, anchor_y) in place
+    /// The anchor is in device canvas pixels; zoom is clamped to MIN_ZOOM..=MAX_ZOOM
+    pub fn set_zoom(&mut self, zoom: f32, anchor_x: f32, anchor_y: f32, grid: &Grid) {
+        let zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        if !zoom.is_finite() || zoom == self.zoom {
+            return;
+        }
+
         let header_offset_x = if grid.show_headers { grid.row_header_width } else { 0.0 };
         let header_offset_y = if grid.show_headers { grid.col_header_height } else { 0.0 };
 
-        let viewport_width = self.canvas_width - header_offset_x;
-        let viewport_height = self.canvas_height - header_offset_y;
+        // Grid position under the anchor before zooming
+        let grid_x = anchor_x / self.zoom - header_offset_x + self.scroll_x;
+        let grid_y = (anchor_y / self.zoom - header_offset_y) as f64 + self.scroll_y;
+
+        let device_width = self.canvas_width * self.zoom;
+        let device_height = self.canvas_height * self.zoom;
+        self.zoom = zoom;
+        self.canvas_width = device_width / zoom;
+        self.canvas_height = device_height / zoom;
+
+        self.set_scroll(
+            grid_x - (anchor_x / zoom - header_offset_x),
+            grid_y - (anchor_y / zoom - header_offset_y) as f64,
+            grid,
+        );
+    }
 
-        let max_scroll_x = (grid.total_width() - viewport_width).max(0.0);
-        let max_scroll_y = (grid.total_height() - viewport_height).max(0.0);
+    /// Convert device canvas coordinates (e.g. mouse offsetX/offsetY) to grid pixels
+    pub fn to_grid_pixels(&self, x: f32, y: f32) -> (f32, f32) {
+        (x / self.zoom, y / self.zoom)
+    }
 
+    /// Set scroll position
+    pub fn set_scroll(&mut self, x: f32, y: f64, grid: &Grid) {
+        let (max_scroll_x, max_scroll_y) = self.max_scroll(grid);
         self.scroll_x = x.max(0.0).min(max_scroll_x);
         self.scroll_y = y.max(0.0).min(max_scroll_y);
     }
 
+    /// Largest scroll position (x, y) at which the grid still fills the viewport
+    pub fn max_scroll(&self, grid: &Grid) -> (f32, f64) {
+        // Calculate the actual viewport area (excluding headers)
+        let header_offset_x = if grid.show_headers { grid.row_header_width } else { 0.0 };
+        let header_offset_y = if grid.show_headers { grid.col_header_height } else { 0.0 };
+
+        let viewport_width = self.canvas_width - header_offset_x;
+        let viewport_height = self.canvas_height - header_offset_y - grid.footer_height;
+
+        (
+            (grid.total_width() + grid.ghost_col_width() - viewport_width).max(0.0),
+            (grid.total_height() + (grid.ghost_row_height() - viewport_height) as f64).max(0.0),
+        )
+    }
+
     /// Scroll by delta
     pub fn scroll_by(&mut self, dx: f32, dy: f32, grid: &Grid) {
-        self.set_scroll(self.scroll_x + dx, self.scroll_y + dy, grid);
+        self.set_scroll(self.scroll_x + dx, self.scroll_y + dy as f64, grid);
     }
 
     /// Update visible range based on current scroll position
     pub fn update_visible_range(&mut self, grid: &Grid) {
-        // Calculate visible row range
-        let mut y = 0.0;
-        let mut found_first_row = false;
-        self.first_visible_row = 0;
-        self.last_visible_row = grid.row_count().saturating_sub(1);
-
-        for row in 0..grid.row_count() {
-            let row_height = grid.row_height(row);
-
-            if !found_first_row && y + row_height > self.scroll_y {
-                self.first_visible_row = row;
-                found_first_row = true;
-            }
-
-            if y > self.scroll_y + self.canvas_height {
-                self.last_visible_row = row.saturating_sub(1);
-                break;
-            }
-
-            y += row_height;
-        }
+        // Calculate visible row range: look the rows up instead of walking them
+        // (matters for very large grids)
+        let last_row = grid.row_count().saturating_sub(1);
+        self.first_visible_row = grid.row_extent_at_y(self.scroll_y).unwrap_or(last_row);
+        self.last_visible_row = grid.row_extent_at_y(self.scroll_y + self.canvas_height as f64).unwrap_or(last_row);
 
         // Calculate visible column range
         let mut x = 0.0;
@@ -137,24 +178,19 @@ impl Viewport {
 
     /// Convert canvas coordinates to grid cell position
     pub fn canvas_to_cell(&self, canvas_x: f32, canvas_y: f32, grid: &Grid) -> Option<(usize, usize)> {
+        if self.is_in_footer(canvas_y, grid) {
+            return None;
+        }
+
         // Subtract header offset if headers are shown
         let header_offset_x = if grid.show_headers { grid.row_header_width } else { 0.0 };
         let header_offset_y = if grid.show_headers { grid.col_header_height } else { 0.0 };
 
         let grid_x = canvas_x - header_offset_x + self.scroll_x;
-        let grid_y = canvas_y - header_offset_y + self.scroll_y;
-
-        // Find row
-        let mut y = 0.0;
-        let mut row = None;
-        for r in 0..grid.row_count() {
-            let height = grid.row_height(r);
-            if grid_y >= y && grid_y < y + height {
-                row = Some(r);
-                break;
-            }
-            y += height;
-        }
+        let grid_y = (canvas_y - header_offset_y) as f64 + self.scroll_y;
+
+        // Find row (points on an expanded detail panel hit no cell)
+        let row = grid.row_at_y(grid_y);
 
         // Find column
         let mut x = 0.0;
@@ -174,10 +210,53 @@ impl Viewport {
         }
     }
 
+    /// Check if a canvas y coordinate falls on the aggregate footer band
+    pub fn is_in_footer(&self, canvas_y: f32, grid: &Grid) -> bool {
+        grid.footer_height > 0.0 && canvas_y >= self.canvas_height - grid.footer_height
+    }
+
+    /// Convert canvas coordinates to a ghost cell position
+    /// Returns (row_count, col) for the ghost row or (row, col_count) for the ghost column
+    pub fn canvas_to_ghost_cell(&self, canvas_x: f32, canvas_y: f32, grid: &Grid) -> Option<(usize, usize)> {
+        if !grid.show_ghost_row && !grid.show_ghost_col {
+            return None;
+        }
+
+        let header_offset_x = if grid.show_headers { grid.row_header_width } else { 0.0 };
+        let header_offset_y = if grid.show_headers { grid.col_header_height } else { 0.0 };
+        if canvas_x < header_offset_x || canvas_y < header_offset_y || self.is_in_footer(canvas_y, grid) {
+            return None;
+        }
+
+        let grid_x = canvas_x - header_offset_x + self.scroll_x;
+        let grid_y = (canvas_y - header_offset_y) as f64 + self.scroll_y;
+        let total_width = grid.total_width();
+        let total_height = grid.total_height();
+
+        if grid.show_ghost_row && grid_y >= total_height && grid_y < total_height + grid.ghost_row_height() as f64 {
+            let mut x = 0.0;
+            for c in 0..grid.col_count() {
+                let width = grid.col_width(c);
+                if grid_x >= x && grid_x < x + width {
+                    return Some((grid.row_count(), c));
+                }
+                x += width;
+            }
+        }
+
+        if grid.show_ghost_col && grid_x >= total_width && grid_x < total_width + grid.ghost_col_width() {
+            if let Some(r) = grid.row_at_y(grid_y) {
+                return Some((r, grid.col_count()));
+            }
+        }
+
+        None
+    }
+
     /// Convert grid cell position to canvas coordinates
     pub fn cell_to_canvas(&self, row: usize, col: usize, grid: &Grid) -> (f32, f32) {
         let x = grid.col_x_position(col) - self.scroll_x;
-        let y = grid.row_y_position(row) - self.scroll_y;
+        let y = (grid.row_y_position(row) - self.scroll_y) as f32;
         (x, y)
     }
 
@@ -189,6 +268,50 @@ impl Viewport {
             && col <= self.last_visible_col
     }
 
+    /// Get the on-canvas rectangle (x, y, width, height) of a cell, clipped to its pane
+    /// Frozen rows/columns do not scroll; scrollable cells are clipped so they never
+    /// overlap headers or frozen panes. Returns None if no part of the cell is visible.
+    pub fn cell_canvas_rect(&self, row: usize, col: usize, grid: &Grid) -> Option<(f32, f32, f32, f32)> {
+        if row >= grid.row_count() || col >= grid.col_count() {
+            return None;
+        }
+
+        let header_offset_x = if grid.show_headers { grid.row_header_width } else { 0.0 };
+        let header_offset_y = if grid.show_headers { grid.col_header_height } else { 0.0 };
+
+        let frozen_row = row < grid.frozen_rows;
+        let frozen_col = col < grid.frozen_cols;
+
+        let scroll_x = if frozen_col { 0.0 } else { self.scroll_x };
+        let scroll_y = if frozen_row { 0.0 } else { self.scroll_y };
+
+        // Pane bounds: scrollable cells start after the frozen area
+        let min_x = if frozen_col {
+            header_offset_x
+        } else {
+            header_offset_x + grid.col_x_position(grid.frozen_cols.min(grid.col_count()))
+        };
+        let min_y = if frozen_row {
+            header_offset_y
+        } else {
+            header_offset_y + grid.row_y_position(grid.frozen_rows.min(grid.row_count())) as f32
+        };
+
+        let x = grid.col_x_position(col) - scroll_x + header_offset_x;
+        let y = (grid.row_y_position(row) - scroll_y) as f32 + header_offset_y;
+
+        let x1 = x.max(min_x);
+        let y1 = y.max(min_y);
+        let x2 = (x + grid.col_width(col)).min(self.canvas_width);
+        let y2 = (y + grid.row_height(row)).min(self.canvas_height);
+
+        if x2 <= x1 || y2 <= y1 {
+            return None;
+        }
+
+        Some((x1, y1, x2 - x1, y2 - y1))
+    }
+
     /// Check if click is on column header and return column index
     pub fn canvas_to_column_header(&self, canvas_x: f32, canvas_y: f32, grid: &Grid) -> Option<usize> {
         if !grid.show_headers {
@@ -213,6 +336,45 @@ impl Viewport {
         None
     }
 
+    /// Check if a column header click at canvas_x falls in the column's sort arrow zone
+    pub fn in_sort_zone(&self, canvas_x: f32, col: usize, grid: &Grid) -> bool {
+        let grid_x = canvas_x - grid.row_header_width + self.scroll_x;
+        let right = grid.col_x_position(col) + grid.col_width(col);
+        grid_x < right && grid_x >= right - grid.sort_zone_width.min(grid.col_width(col))
+    }
+
+    /// Canvas rect (x, y, width, height) of a column's filter button, right-aligned in the
+    /// bottom header row before the sort arrow zone. None when filter buttons are off or
+    /// the column is not filterable, hidden or too narrow for the button.
+    pub fn filter_button_rect(&self, col: usize, grid: &Grid) -> Option<(f32, f32, f32, f32)> {
+        let config = grid.get_column_config(col)?;
+        if !grid.show_headers || !grid.show_filter_buttons || !config.filterable {
+            return None;
+        }
+        let width = grid.col_width(col);
+        let sort_zone = if grid.sort_click == SortClick::Arrow && config.sortable { grid.sort_zone_width.min(width) } else { 0.0 };
+        if width < sort_zone + FILTER_BUTTON_SIZE + FILTER_BUTTON_MARGIN * 2.0 {
+            return None;
+        }
+
+        let top = if grid.header_levels > 1 && !grid.column_groups.is_empty() {
+            (grid.header_levels - 1) as f32 * grid.header_row_height
+        } else {
+            0.0
+        };
+        let bottom = grid.col_header_height - grid.column_summary_height;
+        let size = FILTER_BUTTON_SIZE.min(bottom - top);
+        let right = grid.col_x_position(col) + width - self.scroll_x + grid.row_header_width - sort_zone - FILTER_BUTTON_MARGIN;
+        Some((right - size, top + (bottom - top - size) / 2.0, size, size))
+    }
+
+    /// Column whose filter button is at canvas point (x, y)
+    pub fn canvas_to_filter_button(&self, canvas_x: f32, canvas_y: f32, grid: &Grid) -> Option<usize> {
+        let col = self.canvas_to_column_header(canvas_x, canvas_y, grid)?;
+        let (x, y, width, height) = self.filter_button_rect(col, grid)?;
+        (canvas_x >= x && canvas_x < x + width && canvas_y >= y && canvas_y < y + height).then_some(col)
+    }
+
     /// Check if click is on row header and return row index
     pub fn canvas_to_row_header(&self, canvas_x: f32, canvas_y: f32, grid: &Grid) -> Option<usize> {
         if !grid.show_headers {
@@ -222,16 +384,8 @@ impl Viewport {
         // Check if click is in row header area
         if canvas_x < grid.row_header_width {
             // Find which row was clicked
-            let grid_y = canvas_y - grid.col_header_height + self.scroll_y;
-
-            let mut y = 0.0;
-            for r in 0..grid.row_count() {
-                let height = grid.row_height(r);
-                if grid_y >= y && grid_y < y + height {
-                    return Some(r);
-                }
-                y += height;
-            }
+            let grid_y = (canvas_y - grid.col_header_height) as f64 + self.scroll_y;
+            return grid.row_at_y(grid_y);
         }
 
         None
@@ -259,4 +413,96 @@ mod tests {
         assert_eq!(viewport.scroll_x, 0.0);
         assert_eq!(viewport.scroll_y, 0.0);
     }
+
+    #[test]
+    fn test_sort_zone_hit() {
+        let mut viewport = Viewport::new(800.0, 600.0);
+        let grid = Grid::new(10, 20);
+        let (header, width) = (grid.row_header_width, grid.col_width(1));
+        let right = header + 2.0 * width; // Right edge of column 1 on the canvas
+        assert!(viewport.in_sort_zone(right - 1.0, 1, &grid));
+        assert!(!viewport.in_sort_zone(right - grid.sort_zone_width - 1.0, 1, &grid));
+        assert!(!viewport.in_sort_zone(right + 1.0, 1, &grid));
+
+        viewport.set_scroll(30.0, 0.0, &grid);
+        assert!(viewport.in_sort_zone(right - 31.0, 1, &grid));
+    }
+
+    #[test]
+    fn test_filter_button_hit() {
+        let viewport = Viewport::new(800.0, 600.0);
+        let mut grid = Grid::new(10, 3);
+        assert_eq!(viewport.filter_button_rect(1, &grid), None); // Off by default
+
+        grid.show_filter_buttons = true;
+        let (x, y, size, _) = viewport.filter_button_rect(1, &grid).unwrap();
+        let right = grid.row_header_width + 2.0 * grid.col_width(1);
+        assert_eq!(x + size, right - FILTER_BUTTON_MARGIN);
+        assert_eq!(viewport.canvas_to_filter_button(x + 1.0, y + 1.0, &grid), Some(1));
+        assert_eq!(viewport.canvas_to_filter_button(x - 1.0, y + 1.0, &grid), None);
+
+        // The arrow sort zone keeps the button to its left; unfilterable columns have none
+        grid.sort_click = SortClick::Arrow;
+        assert_eq!(viewport.filter_button_rect(1, &grid).unwrap().0, x - grid.sort_zone_width);
+        grid.column_configs[1].filterable = false;
+        assert_eq!(viewport.canvas_to_filter_button(x + 1.0, y + 1.0, &grid), None);
+    }
+
+    #[test]
+    fn test_canvas_to_ghost_cell() {
+        let viewport = Viewport::new(800.0, 600.0);
+        let mut grid = Grid::new(3, 2);
+
+        // Ghost row starts right below the last row (header 30px + 3 rows * 25px)
+        assert_eq!(viewport.canvas_to_ghost_cell(70.0, 110.0, &grid), None);
+        grid.show_ghost_row = true;
+        assert_eq!(viewport.canvas_to_ghost_cell(70.0, 110.0, &grid), Some((3, 0)));
+        assert_eq!(viewport.canvas_to_ghost_cell(170.0, 110.0, &grid), Some((3, 1)));
+        assert_eq!(viewport.canvas_to_ghost_cell(70.0, 140.0, &grid), None);
+
+        // Ghost column starts right after the last column (header 60px + 2 cols * 100px)
+        grid.show_ghost_col = true;
+        assert_eq!(viewport.canvas_to_ghost_cell(270.0, 40.0, &grid), Some((0, 2)));
+        assert!(grid.is_ghost_cell(0, 2));
+        assert!(!grid.is_ghost_cell(3, 2));
+    }
+
+    #[test]
+    fn test_cell_canvas_rect() {
+        let mut viewport = Viewport::new(800.0, 600.0);
+        let mut grid = Grid::new(100, 50);
+        grid.frozen_rows = 1;
+
+        // Header offsets: 60px row header, 30px column header
+        assert_eq!(viewport.cell_canvas_rect(0, 0, &grid), Some((60.0, 30.0, 100.0, 25.0)));
+
+        // Scrolled cell is clipped below the frozen row, frozen row stays in place
+        viewport.set_scroll(0.0, 10.0, &grid);
+        assert_eq!(viewport.cell_canvas_rect(1, 0, &grid), Some((60.0, 55.0, 100.0, 15.0)));
+        assert_eq!(viewport.cell_canvas_rect(0, 0, &grid), Some((60.0, 30.0, 100.0, 25.0)));
+
+        // Off-screen and out-of-range cells
+        assert_eq!(viewport.cell_canvas_rect(90, 0, &grid), None);
+        assert_eq!(viewport.cell_canvas_rect(0, 50, &grid), None);
+    }
+
+    #[test]
+    fn test_zoom_keeps_anchor() {
+        let grid = Grid::new(100, 20);
+        let mut viewport = Viewport::new(800.0, 600.0);
+        viewport.set_scroll(200.0, 250.0, &grid);
+        viewport.update_visible_range(&grid);
+        let before = viewport.canvas_to_cell(400.0, 300.0, &grid);
+
+        viewport.set_zoom(2.0, 400.0, 300.0, &grid);
+        assert_eq!(viewport.canvas_width, 400.0);
+        let (x, y) = viewport.to_grid_pixels(400.0, 300.0);
+        assert_eq!(viewport.canvas_to_cell(x, y, &grid), before);
+
+        viewport.resize(1000.0, 600.0);
+        assert_eq!(viewport.canvas_width, 500.0);
+
+        viewport.set_zoom(100.0, 0.0, 0.0, &grid);
+        assert_eq!(viewport.zoom, MAX_ZOOM);
+    }
 }
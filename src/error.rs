@@ -1,3 +1,4 @@
+use crate::features::locale;
 use std::fmt;
 use wasm_bindgen::JsValue;
 
@@ -7,6 +8,12 @@ pub enum GridError {
     /// Cell coordinates out of bounds
     OutOfBounds { row: usize, col: usize },
 
+    /// Row index out of bounds
+    RowOutOfBounds { row: usize },
+
+    /// Column index out of bounds
+    ColumnOutOfBounds { col: usize },
+
     /// Cell is not editable
     CellNotEditable { row: usize, col: usize },
 
@@ -25,33 +32,46 @@ pub enum GridError {
     /// Shader compilation failed
     ShaderError { error: String },
 
+    /// Data changed since the version a batch was prepared against
+    VersionConflict { expected: u64, current: u64 },
+
     /// Generic operation error
     OperationError { message: String },
 }
 
 impl fmt::Display for GridError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Messages come from the locale string table (set_locale_strings_json)
         match self {
             GridError::OutOfBounds { row, col } => {
-                write!(f, "Cell ({}, {}) is out of bounds", row, col)
+                write!(f, "{}", locale::format("error.out_of_bounds", &[("row", row), ("col", col)]))
+            }
+            GridError::RowOutOfBounds { row } => {
+                write!(f, "{}", locale::format("error.row_out_of_bounds", &[("row", row)]))
+            }
+            GridError::ColumnOutOfBounds { col } => {
+                write!(f, "{}", locale::format("error.col_out_of_bounds", &[("col", col)]))
             }
             GridError::CellNotEditable { row, col } => {
-                write!(f, "Cell ({}, {}) is not editable", row, col)
+                write!(f, "{}", locale::format("error.not_editable", &[("row", row), ("col", col)]))
             }
             GridError::InvalidRegex { pattern, error } => {
-                write!(f, "Invalid regex pattern '{}': {}", pattern, error)
+                write!(f, "{}", locale::format("error.invalid_regex", &[("pattern", pattern), ("error", error)]))
             }
             GridError::PasteFailed { reason } => {
-                write!(f, "Paste operation failed: {}", reason)
+                write!(f, "{}", locale::format("error.paste_failed", &[("reason", reason)]))
             }
             GridError::InvalidJson { error } => {
-                write!(f, "Invalid JSON data: {}", error)
+                write!(f, "{}", locale::format("error.invalid_json", &[("error", error)]))
             }
             GridError::RenderInitFailed { error } => {
-                write!(f, "Renderer initialization failed: {}", error)
+                write!(f, "{}", locale::format("error.render_init_failed", &[("error", error)]))
             }
             GridError::ShaderError { error } => {
-                write!(f, "Shader error: {}", error)
+                write!(f, "{}", locale::format("error.shader", &[("error", error)]))
+            }
+            GridError::VersionConflict { expected, current } => {
+                write!(f, "{}", locale::format("error.version_conflict", &[("expected", expected), ("current", current)]))
             }
             GridError::OperationError { message } => {
                 write!(f, "{}", message)
@@ -62,10 +82,58 @@ impl fmt::Display for GridError {
 
 impl std::error::Error for GridError {}
 
-// Conversion to JsValue for WASM bindings
+impl GridError {
+    /// Error for an unrecognized option value, e.g. an unknown renderer name
+    pub fn unknown_value(name: &str, value: &str, expected: &[&str]) -> Self {
+        GridError::OperationError {
+            message: locale::format("error.unknown_value", &[("name", &name), ("value", &value), ("expected", &expected.join(", "))]),
+        }
+    }
+
+    /// Stable identifier of the error kind (the `code` of the JS error)
+    pub fn code(&self) -> &'static str {
+        match self {
+            GridError::OutOfBounds { .. } => "out_of_bounds",
+            GridError::RowOutOfBounds { .. } => "row_out_of_bounds",
+            GridError::ColumnOutOfBounds { .. } => "column_out_of_bounds",
+            GridError::CellNotEditable { .. } => "not_editable",
+            GridError::InvalidRegex { .. } => "invalid_regex",
+            GridError::PasteFailed { .. } => "paste_failed",
+            GridError::InvalidJson { .. } => "invalid_json",
+            GridError::RenderInitFailed { .. } => "render_init_failed",
+            GridError::ShaderError { .. } => "shader_error",
+            GridError::VersionConflict { .. } => "version_conflict",
+            GridError::OperationError { .. } => "operation_failed",
+        }
+    }
+
+    /// Details of the error (the `context` of the JS error), e.g. {"row": 5, "col": 2}
+    pub fn context(&self) -> serde_json::Value {
+        match self {
+            GridError::OutOfBounds { row, col } | GridError::CellNotEditable { row, col } => serde_json::json!({ "row": row, "col": col }),
+            GridError::RowOutOfBounds { row } => serde_json::json!({ "row": row }),
+            GridError::ColumnOutOfBounds { col } => serde_json::json!({ "col": col }),
+            GridError::InvalidRegex { pattern, error } => serde_json::json!({ "pattern": pattern, "error": error }),
+            GridError::PasteFailed { reason } => serde_json::json!({ "reason": reason }),
+            GridError::InvalidJson { error } | GridError::RenderInitFailed { error } | GridError::ShaderError { error } => {
+                serde_json::json!({ "error": error })
+            }
+            GridError::VersionConflict { expected, current } => serde_json::json!({ "expected": expected, "current": current }),
+            GridError::OperationError { .. } => serde_json::json!({}),
+        }
+    }
+}
+
+// Conversion to JsValue for WASM bindings: a JS Error named "GridError" with
+// `code` and `context` properties next to the (localized) message
 impl From<GridError> for JsValue {
     fn from(err: GridError) -> Self {
-        JsValue::from_str(&err.to_string())
+        let error = js_sys::Error::new(&err.to_string());
+        error.set_name("GridError");
+        let context = js_sys::JSON::parse(&err.context().to_string()).unwrap_or(JsValue::NULL);
+        let _ = js_sys::Reflect::set(&error, &JsValue::from_str("code"), &JsValue::from_str(err.code()));
+        let _ = js_sys::Reflect::set(&error, &JsValue::from_str("context"), &context);
+        error.into()
     }
 }
 
@@ -82,3 +150,24 @@ impl From<&str> for GridError {
         GridError::OperationError { message: message.to_string() }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_code_and_context() {
+        let error = GridError::ColumnOutOfBounds { col: 7 };
+        assert_eq!(error.code(), "column_out_of_bounds");
+        assert_eq!(error.context(), serde_json::json!({ "col": 7 }));
+        assert_eq!(error.to_string(), "Column 7 is out of range");
+
+        let error = GridError::OutOfBounds { row: 1, col: 2 };
+        assert_eq!((error.code(), error.context()), ("out_of_bounds", serde_json::json!({ "row": 1, "col": 2 })));
+        assert_eq!(GridError::from("failed").code(), "operation_failed");
+        assert_eq!(
+            GridError::unknown_value("density", "huge", &["compact", "standard"]).to_string(),
+            "Unknown density 'huge' (expected one of: compact, standard)"
+        );
+    }
+}
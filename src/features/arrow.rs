@@ -0,0 +1,201 @@
+use crate::core::{CellValue, DataType, Grid};
+use crate::features::locale;
+use crate::GridError;
+use arrow_array::cast::AsArray;
+use arrow_array::types::Float64Type;
+use arrow_array::{Array, ArrayRef, BooleanArray, Float64Array, RecordBatch, StringArray};
+use arrow_cast::cast;
+use arrow_cast::display::{ArrayFormatter, FormatOptions};
+use arrow_ipc::reader::{FileReader, StreamReader};
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::{ArrowError, DataType as ArrowType, Field, Schema, SchemaRef, TimeUnit};
+use std::io::Cursor;
+use std::sync::Arc;
+
+/// Magic bytes at the start of the Arrow IPC file format (the stream format has none)
+const FILE_MAGIC: &[u8] = b"ARROW1";
+
+fn arrow_error(e: ArrowError) -> GridError {
+    GridError::OperationError { message: locale::format("error.arrow", &[("error", &e)]) }
+}
+
+/// Grid data type for an Arrow column type (integers, floats and decimals are numbers;
+/// dates and timestamps are dates; everything else is shown as text)
+pub fn grid_type(arrow_type: &ArrowType) -> DataType {
+    match arrow_type {
+        t if t.is_numeric() => DataType::Number,
+        ArrowType::Boolean => DataType::Boolean,
+        ArrowType::Date32 | ArrowType::Date64 | ArrowType::Timestamp(..) => DataType::Date,
+        _ => DataType::Text,
+    }
+}
+
+/// Read Arrow IPC data in the stream or file format
+pub fn read_ipc(bytes: &[u8]) -> Result<(SchemaRef, Vec<RecordBatch>), GridError> {
+    if bytes.starts_with(FILE_MAGIC) {
+        let reader = FileReader::try_new(Cursor::new(bytes), None).map_err(arrow_error)?;
+        let schema = reader.schema();
+        let batches = reader.collect::<Result<Vec<_>, _>>().map_err(arrow_error)?;
+        Ok((schema, batches))
+    } else {
+        let reader = StreamReader::try_new(Cursor::new(bytes), None).map_err(arrow_error)?;
+        let schema = reader.schema();
+        let batches = reader.collect::<Result<Vec<_>, _>>().map_err(arrow_error)?;
+        Ok((schema, batches))
+    }
+}
+
+/// Replace the grid contents with Arrow IPC data: one grid column per field, named after
+/// it and typed by `grid_type`. Returns the schema that was loaded.
+pub fn load_ipc(grid: &mut Grid, bytes: &[u8]) -> Result<SchemaRef, GridError> {
+    let (schema, batches) = read_ipc(bytes)?;
+    let rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+
+    grid.clear();
+    grid.resize(rows.max(1), schema.fields().len().max(1));
+    for (col, field) in schema.fields().iter().enumerate() {
+        let config = &mut grid.column_configs[col];
+        config.display_name = field.name().clone();
+        config.internal_name = field.name().clone();
+        config.data_type = grid_type(field.data_type());
+    }
+
+    let mut first_row = 0;
+    for batch in &batches {
+        for (col, array) in batch.columns().iter().enumerate() {
+            for (offset, value) in column_values(array)?.into_iter().enumerate() {
+                if !value.is_empty() {
+                    grid.set_value(first_row + offset, col, value);
+                }
+            }
+        }
+        first_row += batch.num_rows();
+    }
+    Ok(schema)
+}
+
+/// Cell values of an Arrow array (nulls are empty cells)
+fn column_values(array: &ArrayRef) -> Result<Vec<CellValue>, GridError> {
+    let data_type = array.data_type();
+    if data_type.is_numeric() {
+        let numbers = cast(array, &ArrowType::Float64).map_err(arrow_error)?;
+        return Ok(numbers.as_primitive::<Float64Type>().iter().map(|n| n.map_or(CellValue::Empty, CellValue::Number)).collect());
+    }
+    match data_type {
+        ArrowType::Boolean => Ok(array.as_boolean().iter().map(|b| b.map_or(CellValue::Empty, CellValue::Boolean)).collect()),
+        ArrowType::Utf8 | ArrowType::LargeUtf8 | ArrowType::Utf8View => {
+            let strings = cast(array, &ArrowType::Utf8).map_err(arrow_error)?;
+            Ok(strings.as_string::<i32>().iter().map(|s| s.map_or(CellValue::Empty, |s| CellValue::Text(s.to_string()))).collect())
+        }
+        _ => {
+            // Dates, timestamps and other types use Arrow's display format
+            let wrap = if grid_type(data_type) == DataType::Date { CellValue::Date } else { CellValue::Text };
+            let formatter = ArrayFormatter::try_new(array.as_ref(), &FormatOptions::default()).map_err(arrow_error)?;
+            Ok((0..array.len())
+                .map(|i| if array.is_null(i) { CellValue::Empty } else { wrap(formatter.value(i).to_string()) })
+                .collect())
+        }
+    }
+}
+
+/// Write every row of the grid as Arrow IPC stream data. Columns are typed from their
+/// grid data type (Float64, Boolean, Date32 or Timestamp, Utf8); a column holding values
+/// that do not fit its type is written as Utf8 so nothing is lost.
+pub fn write_ipc(grid: &Grid) -> Result<Vec<u8>, GridError> {
+    let mut fields = Vec::with_capacity(grid.col_count());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(grid.col_count());
+    for col in 0..grid.col_count() {
+        let values: Vec<CellValue> = (0..grid.row_count()).map(|row| grid.get_value(row, col)).collect();
+        let data_type = grid.get_column_config(col).map_or(DataType::Text, |c| c.data_type.clone());
+        let array = typed_column(&values, &data_type)?;
+        let name = grid.get_column_config(col).map_or_else(|| format!("col_{}", col), |c| c.display_name.clone());
+        fields.push(Field::new(name, array.data_type().clone(), true));
+        columns.push(array);
+    }
+
+    let schema = Arc::new(Schema::new(fields));
+    let batch = RecordBatch::try_new(schema.clone(), columns).map_err(arrow_error)?;
+    let mut writer = StreamWriter::try_new(Vec::new(), &schema).map_err(arrow_error)?;
+    writer.write(&batch).map_err(arrow_error)?;
+    writer.finish().map_err(arrow_error)?;
+    writer.into_inner().map_err(arrow_error)
+}
+
+/// Arrow array for a column of cell values, falling back to text when a value does not fit
+fn typed_column(values: &[CellValue], data_type: &DataType) -> Result<ArrayRef, GridError> {
+    let text: Vec<Option<String>> = values.iter().map(|v| (!v.is_empty()).then(|| v.to_string())).collect();
+    let strings: ArrayRef = Arc::new(StringArray::from(text));
+
+    match data_type {
+        DataType::Number => {
+            let numbers: Option<Vec<Option<f64>>> = values
+                .iter()
+                .map(|v| match v {
+                    CellValue::Empty => Some(None),
+                    CellValue::Number(n) => Some(Some(*n)),
+                    _ => None,
+                })
+                .collect();
+            Ok(numbers.map_or(strings, |n| Arc::new(Float64Array::from(n))))
+        }
+        DataType::Boolean => {
+            let booleans: Option<Vec<Option<bool>>> = values
+                .iter()
+                .map(|v| match v {
+                    CellValue::Empty => Some(None),
+                    CellValue::Boolean(b) => Some(Some(*b)),
+                    _ => None,
+                })
+                .collect();
+            Ok(booleans.map_or(strings, |b| Arc::new(BooleanArray::from(b))))
+        }
+        DataType::Date => {
+            let has_time = values.iter().any(|v| v.to_string().trim().contains([' ', 'T']));
+            let target = if has_time { ArrowType::Timestamp(TimeUnit::Second, None) } else { ArrowType::Date32 };
+            // Values that do not parse become null, which shows in the null count
+            match cast(&strings, &target) {
+                Ok(dates) if dates.null_count() == strings.null_count() => Ok(dates),
+                _ => Ok(strings),
+            }
+        }
+        DataType::Text => Ok(strings),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arrow_round_trip() {
+        let mut grid = Grid::new(3, 4);
+        let types = [DataType::Number, DataType::Text, DataType::Boolean, DataType::Date];
+        for (col, data_type) in types.into_iter().enumerate() {
+            grid.column_configs[col].data_type = data_type;
+        }
+        grid.column_configs[0].display_name = "amount".to_string();
+        grid.set_value(0, 0, CellValue::Number(1.5));
+        grid.set_value(2, 0, CellValue::Number(-3.0));
+        grid.set_value(0, 1, CellValue::Text("héllo".to_string()));
+        grid.set_value(1, 2, CellValue::Boolean(true));
+        grid.set_value(0, 3, CellValue::Date("2024-02-29".to_string()));
+
+        let bytes = write_ipc(&grid).unwrap();
+        let mut loaded = Grid::new(1, 1);
+        let schema = load_ipc(&mut loaded, &bytes).unwrap();
+        assert_eq!(schema.field(0).name(), "amount");
+        assert_eq!(schema.field(3).data_type(), &ArrowType::Date32);
+        assert_eq!((loaded.row_count(), loaded.col_count()), (3, 4));
+        for row in 0..3 {
+            for col in 0..4 {
+                assert_eq!(loaded.get_value(row, col), grid.get_value(row, col), "cell ({}, {})", row, col);
+            }
+        }
+
+        // A number column holding text is written as text rather than dropping the value
+        grid.set_value(1, 0, CellValue::Text("n/a".to_string()));
+        let (schema, _) = read_ipc(&write_ipc(&grid).unwrap()).unwrap();
+        assert_eq!(schema.field(0).data_type(), &ArrowType::Utf8);
+        assert!(read_ipc(b"not arrow").is_err());
+    }
+}
@@ -0,0 +1,89 @@
+use crate::core::Grid;
+use std::collections::HashMap;
+
+/// Distinct values of one column, sorted by their lowercase form for prefix lookups
+struct ColumnIndex {
+    revision: u64,
+    values: Vec<(String, String, usize)>, // (lowercase, value, occurrences)
+}
+
+impl ColumnIndex {
+    fn build(grid: &Grid, col: usize) -> Self {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for row in 0..grid.row_count() {
+            let value = grid.get_value(row, col);
+            if !value.is_empty() {
+                *counts.entry(value.to_string()).or_default() += 1;
+            }
+        }
+        let mut values: Vec<(String, String, usize)> =
+            counts.into_iter().map(|(value, count)| (value.to_lowercase(), value, count)).collect();
+        values.sort();
+        Self { revision: grid.revision(), values }
+    }
+}
+
+/// Edit suggestions from the values already in a column
+/// Each column's index is built on first use and rebuilt once the column's data changed
+#[derive(Default)]
+pub struct Autocomplete {
+    pub complete_on_tab: bool, // Tab in the in-grid editor accepts the first suggestion
+    indexes: HashMap<usize, ColumnIndex>,
+}
+
+impl Autocomplete {
+    pub fn new(complete_on_tab: bool) -> Self {
+        Self { complete_on_tab, indexes: HashMap::new() }
+    }
+
+    /// Distinct values of `col` starting with `prefix` (case-insensitive), most frequent
+    /// first, then alphabetical. A value equal to the prefix is not suggested.
+    pub fn suggestions(&mut self, grid: &Grid, col: usize, prefix: &str, limit: usize) -> Vec<String> {
+        if prefix.is_empty() || limit == 0 || col >= grid.col_count() {
+            return Vec::new();
+        }
+        let index = self.indexes.entry(col).or_insert_with(|| ColumnIndex::build(grid, col));
+        if grid.columns_changed_since(&[col], index.revision) {
+            *index = ColumnIndex::build(grid, col);
+        }
+
+        let key = prefix.to_lowercase();
+        let start = index.values.partition_point(|(lower, _, _)| lower.as_str() < key.as_str());
+        let mut matches: Vec<&(String, String, usize)> = index.values[start..]
+            .iter()
+            .take_while(|(lower, _, _)| lower.starts_with(&key))
+            .filter(|(_, value, _)| value != prefix)
+            .collect();
+        matches.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+        matches.into_iter().take(limit).map(|(_, value, _)| value.clone()).collect()
+    }
+
+    /// Drop every index (e.g. after the grid was replaced)
+    pub fn clear(&mut self) {
+        self.indexes.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::CellValue;
+
+    #[test]
+    fn test_suggestions_follow_column_values() {
+        let mut grid = Grid::new(5, 1);
+        for (row, text) in ["Tokyo", "Toronto", "tokyo", "Osaka", "Tokyo"].iter().enumerate() {
+            grid.set_value(row, 0, CellValue::Text(text.to_string()));
+        }
+        let mut autocomplete = Autocomplete::new(false);
+
+        assert_eq!(autocomplete.suggestions(&grid, 0, "to", 10), vec!["Tokyo", "tokyo", "Toronto"]);
+        assert_eq!(autocomplete.suggestions(&grid, 0, "to", 1), vec!["Tokyo"]);
+        assert_eq!(autocomplete.suggestions(&grid, 0, "Tokyo", 10), vec!["tokyo"]);
+
+        // The index is rebuilt after the column changes
+        grid.set_value(3, 0, CellValue::Text("Toyama".to_string()));
+        assert_eq!(autocomplete.suggestions(&grid, 0, "toy", 10), vec!["Toyama"]);
+        assert!(autocomplete.suggestions(&grid, 0, "osa", 10).is_empty());
+    }
+}
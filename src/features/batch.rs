@@ -0,0 +1,138 @@
+use crate::core::{CellValue, Grid};
+use crate::features::locale;
+
+/// Maximum number of skipped entries listed in a batch report
+const MAX_REPORTED_ERRORS: usize = 20;
+
+/// Entry of a batch load that could not be applied
+#[derive(Clone, Debug, PartialEq)]
+pub struct SkippedEntry {
+    pub index: usize, // Position in the batch array
+    pub row: Option<usize>,
+    pub col: Option<usize>,
+    pub reason: String,
+}
+
+/// Outcome of a batch load (set_cells_batch / load_data_json)
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    pub applied: usize,
+    pub skipped: usize,
+    pub rejected: bool, // Strict mode refused the whole batch
+    errors: Vec<SkippedEntry>,
+}
+
+impl BatchReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a skipped entry (only the first MAX_REPORTED_ERRORS are kept)
+    pub fn skip(&mut self, entry: SkippedEntry) {
+        self.skipped += 1;
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(entry);
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let errors: Vec<serde_json::Value> = self
+            .errors
+            .iter()
+            .map(|e| serde_json::json!({ "index": e.index, "row": e.row, "col": e.col, "reason": e.reason }))
+            .collect();
+        serde_json::json!({
+            "applied": self.applied,
+            "skipped": self.skipped,
+            "rejected": self.rejected,
+            "errors": errors,
+        })
+    }
+}
+
+/// Check that a cell lies inside the grid
+fn check_bounds(grid: &Grid, index: usize, row: usize, col: usize) -> Result<(), SkippedEntry> {
+    if row >= grid.row_count() || col >= grid.col_count() {
+        return Err(SkippedEntry {
+            index,
+            row: Some(row),
+            col: Some(col),
+            reason: locale::format("error.out_of_bounds", &[("row", &row), ("col", &col)]),
+        });
+    }
+    Ok(())
+}
+
+/// Parse a set_cells_batch entry: [row, col, value_type, value_data]
+/// value_type: 0=empty, 1=text, 2=number, 3=boolean
+pub fn parse_cells_batch_entry(grid: &Grid, index: usize, entry: &[String]) -> Result<(usize, usize, CellValue), SkippedEntry> {
+    let fail = |row, col, reason: String| SkippedEntry { index, row, col, reason };
+    if entry.len() < 4 {
+        return Err(fail(None, None, locale::text("batch.expected_entry")));
+    }
+
+    let row = entry[0].parse::<usize>().map_err(|_| fail(None, None, locale::format("batch.invalid_row", &[("row", &entry[0])])))?;
+    let col = entry[1].parse::<usize>().map_err(|_| fail(Some(row), None, locale::format("batch.invalid_column", &[("col", &entry[1])])))?;
+    check_bounds(grid, index, row, col)?;
+
+    let value_data = &entry[3];
+    let value = match entry[2].as_str() {
+        "0" => CellValue::Empty,
+        "1" => CellValue::Text(value_data.clone()),
+        "2" => CellValue::Number(
+            value_data
+                .parse::<f64>()
+                .map_err(|_| fail(Some(row), Some(col), locale::format("batch.invalid_number", &[("value", &value_data)])))?,
+        ),
+        "3" => CellValue::Boolean(value_data == "true" || value_data == "1"),
+        other => return Err(fail(Some(row), Some(col), locale::format("batch.value_type", &[("type", &other)]))),
+    };
+    Ok((row, col, value))
+}
+
+/// Parse the position of a load_data_json entry: {"row": 0, "col": 0, "value": ...}
+pub fn parse_json_entry_position(grid: &Grid, index: usize, entry: &serde_json::Value) -> Result<(usize, usize), SkippedEntry> {
+    let fail = |row, col, reason: String| SkippedEntry { index, row, col, reason };
+    if !entry.is_object() {
+        return Err(fail(None, None, locale::text("batch.expected_object")));
+    }
+    let row = entry["row"].as_u64().ok_or_else(|| fail(None, None, locale::format("batch.invalid_row", &[("row", &entry["row"])])))? as usize;
+    let col = entry["col"].as_u64().ok_or_else(|| fail(Some(row), None, locale::format("batch.invalid_column", &[("col", &entry["col"])])))? as usize;
+    check_bounds(grid, index, row, col)?;
+
+    match &entry["value"] {
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+            Err(fail(Some(row), Some(col), locale::text("batch.unsupported_value")))
+        }
+        _ => Ok((row, col)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_batch_entries() {
+        let grid = Grid::new(2, 2);
+        let entry = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(parse_cells_batch_entry(&grid, 0, &entry(&["1", "0", "2", "4.5"])).unwrap(), (1, 0, CellValue::Number(4.5)));
+        let err = parse_cells_batch_entry(&grid, 3, &entry(&["2", "0", "1", "x"])).unwrap_err();
+        assert_eq!((err.index, err.row, err.col), (3, Some(2), Some(0)));
+        assert!(parse_cells_batch_entry(&grid, 0, &entry(&["0", "0", "2", "abc"])).is_err());
+        assert!(parse_cells_batch_entry(&grid, 0, &entry(&["0", "0", "7", ""])).is_err());
+        assert!(parse_cells_batch_entry(&grid, 0, &entry(&["a", "0", "1", ""])).is_err());
+
+        let json: serde_json::Value = serde_json::json!([{"row": 1, "col": 1, "value": 1}, {"row": 0, "value": 1}, {"row": 0, "col": 0, "value": [1]}]);
+        assert_eq!(parse_json_entry_position(&grid, 0, &json[0]).unwrap(), (1, 1));
+        assert!(parse_json_entry_position(&grid, 1, &json[1]).is_err());
+        assert!(parse_json_entry_position(&grid, 2, &json[2]).is_err());
+
+        let mut report = BatchReport::new();
+        for index in 0..30 {
+            report.skip(SkippedEntry { index, row: None, col: None, reason: String::new() });
+        }
+        assert_eq!((report.skipped, report.errors.len()), (30, MAX_REPORTED_ERRORS));
+    }
+}
@@ -1,6 +1,221 @@
-use crate::core::{cell::CellValue, Grid};
+use super::snapshot;
+use crate::core::{cell::{CellValue, DataType}, Grid, NumberFormat};
+use crate::features::locale;
 use crate::GridError;
+use js_sys::{Array, Object, Reflect};
+use serde_json::{json, Map, Value};
 use std::collections::HashSet;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Blob, BlobPropertyBag, Clipboard, ClipboardItem};
+
+/// Format tag and version of structured clipboard payloads (copy_selected_cells_structured)
+const STRUCTURED_FORMAT: &str = "datagrid5/cells";
+const STRUCTURED_VERSION: u64 = 1;
+
+/// Largest block (rows × cols) accepted from a structured payload, which may come from
+/// anywhere on the clipboard
+const MAX_STRUCTURED_CELLS: usize = 1_000_000;
+
+/// What to do when a paste would write over non-empty or read-only cells
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PastePolicy {
+    Overwrite, // Replace non-empty cells (read-only cells are always left alone)
+    Skip,      // Keep existing values; only fill empty cells
+    Prompt,    // Apply nothing and report the conflicts to the host
+}
+
+impl PastePolicy {
+    /// Parse policy name ("overwrite", "skip", "prompt")
+    pub fn parse(policy: &str) -> Result<Self, GridError> {
+        match policy.to_lowercase().as_str() {
+            "overwrite" => Ok(PastePolicy::Overwrite),
+            "skip" => Ok(PastePolicy::Skip),
+            "prompt" => Ok(PastePolicy::Prompt),
+            other => Err(GridError::unknown_value("paste_policy", other, &["overwrite", "skip", "prompt"])),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PastePolicy::Overwrite => "overwrite",
+            PastePolicy::Skip => "skip",
+            PastePolicy::Prompt => "prompt",
+        }
+    }
+}
+
+/// Which text copy operations export for each cell
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CopyFormat {
+    Raw,     // Stored values (`to_string()`), e.g. "1234.5"
+    Display, // What the grid shows, with the column's number format, e.g. "$1,234.50"
+}
+
+impl CopyFormat {
+    /// Parse format name ("raw", "display")
+    pub fn parse(format: &str) -> Result<Self, GridError> {
+        match format.to_lowercase().as_str() {
+            "raw" => Ok(CopyFormat::Raw),
+            "display" => Ok(CopyFormat::Display),
+            other => Err(GridError::unknown_value("copy_format", other, &["raw", "display"])),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CopyFormat::Raw => "raw",
+            CopyFormat::Display => "display",
+        }
+    }
+
+    /// Text of a cell in this format
+    pub fn cell_text(&self, grid: &Grid, row: usize, col: usize) -> String {
+        match self {
+            CopyFormat::Raw => grid.get_value_string(row, col),
+            CopyFormat::Display => grid.get_display_string(row, col),
+        }
+    }
+}
+
+/// Which part of the copied cells a paste writes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PasteContent {
+    All,     // Values and styles
+    Values,  // Values only; existing styles are kept
+    Formats, // Styles only; existing values are kept
+}
+
+/// Paste special options, as in spreadsheets
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PasteSpecial {
+    pub content: PasteContent,
+    pub transpose: bool,   // Rows become columns
+    pub skip_blanks: bool, // Empty source cells leave the target untouched
+}
+
+impl Default for PasteSpecial {
+    fn default() -> Self {
+        Self { content: PasteContent::All, transpose: false, skip_blanks: false }
+    }
+}
+
+impl PasteSpecial {
+    /// Parse a comma-separated mode list ("values", "formats", "transpose", "skip_blanks"),
+    /// e.g. "values,transpose"
+    pub fn parse(mode: &str) -> Result<Self, GridError> {
+        let mut special = Self::default();
+        for part in mode.split(',').map(|p| p.trim().to_lowercase()).filter(|p| !p.is_empty()) {
+            match part.as_str() {
+                "all" => special.content = PasteContent::All,
+                "values" => special.content = PasteContent::Values,
+                "formats" | "styles" => special.content = PasteContent::Formats,
+                "transpose" => special.transpose = true,
+                "skip_blanks" => special.skip_blanks = true,
+                other => {
+                    return Err(GridError::unknown_value("paste_mode", other, &["values", "formats", "transpose", "skip_blanks"]))
+                }
+            }
+        }
+        Ok(special)
+    }
+
+    /// Transpose the block if requested (short rows are padded with empty cells)
+    pub fn arrange(&self, block: Vec<Vec<PastedCell>>) -> Vec<Vec<PastedCell>> {
+        if !self.transpose {
+            return block;
+        }
+
+        let width = block.iter().map(|row| row.len()).max().unwrap_or(0);
+        let mut transposed = vec![vec![PastedCell::default(); block.len()]; width];
+        for (row, cells) in block.into_iter().enumerate() {
+            for (col, cell) in cells.into_iter().enumerate() {
+                transposed[col][row] = cell;
+            }
+        }
+        transposed
+    }
+}
+
+/// A pasted value that would replace existing data or target a read-only cell
+#[derive(Clone, Debug)]
+pub struct PasteConflict {
+    pub row: usize,
+    pub col: usize,
+    pub read_only: bool,
+    pub old_value: CellValue,
+    pub new_value: CellValue,
+}
+
+impl PasteConflict {
+    /// Check whether writing `new_value` to (row, col) conflicts with the current contents
+    pub fn check(grid: &Grid, row: usize, col: usize, new_value: &CellValue) -> Option<Self> {
+        if row >= grid.row_count() || col >= grid.col_count() {
+            return None; // Cells added by auto-expansion are always empty and editable
+        }
+
+        let old_value = grid.get_value(row, col);
+        let read_only = !grid.is_cell_editable(row, col);
+        let overwrites = !matches!(old_value, CellValue::Empty) && old_value.to_string() != new_value.to_string();
+
+        if read_only || overwrites {
+            Some(Self { row, col, read_only, old_value, new_value: new_value.clone() })
+        } else {
+            None
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "row": self.row,
+            "col": self.col,
+            "reason": if self.read_only { "read_only" } else { "non_empty" },
+            "old_value": self.old_value.to_string(),
+            "new_value": self.new_value.to_string(),
+        })
+    }
+}
+
+/// A value (and optional basic style) to paste into one cell
+/// TSV pastes carry values only; HTML tables from spreadsheets also carry styles
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PastedCell {
+    pub value: CellValue,
+    pub bold: bool,
+    pub italic: bool,
+    pub bg_color: Option<u32>, // RGBA as u32
+    pub fg_color: Option<u32>,
+}
+
+impl PastedCell {
+    /// Cell holding a TSV field
+    pub fn from_text(text: &str) -> Self {
+        Self { value: ClipboardOps::parse_value(text), ..Self::default() }
+    }
+
+    /// Check if the cell carries any style worth keeping
+    pub fn has_style(&self) -> bool {
+        self.bold || self.italic || self.bg_color.is_some() || self.fg_color.is_some()
+    }
+
+    /// Style keys of a structured payload cell ({"bg", "fg", "bold", "italic"}, defaults omitted)
+    fn style_json(&self) -> Map<String, Value> {
+        let mut style = Map::new();
+        if let Some(bg) = self.bg_color {
+            style.insert("bg".to_string(), json!(bg));
+        }
+        if let Some(fg) = self.fg_color {
+            style.insert("fg".to_string(), json!(fg));
+        }
+        if self.bold {
+            style.insert("bold".to_string(), json!(true));
+        }
+        if self.italic {
+            style.insert("italic".to_string(), json!(true));
+        }
+        style
+    }
+}
 
 /// Clipboard operations for copying and pasting cells
 pub struct ClipboardOps;
@@ -10,6 +225,7 @@ impl ClipboardOps {
     pub fn copy_selected_cells(
         selected_cells: &HashSet<(usize, usize)>,
         grid: &Grid,
+        format: CopyFormat,
     ) -> String {
         if selected_cells.is_empty() {
             return String::new();
@@ -36,7 +252,7 @@ impl ClipboardOps {
         for row in *min_row..=*max_row {
             for col in *min_col..=*max_col {
                 if selected_cells.contains(&(row, col)) {
-                    let value = grid.get_value_string(row, col);
+                    let value = format.cell_text(grid, row, col);
                     result.push_str(&value);
                 } else {
                     // Empty cell in the rectangular selection
@@ -55,13 +271,177 @@ impl ClipboardOps {
         result
     }
 
+    /// Copy selected cells as an HTML table (for spreadsheets such as Excel/Sheets)
+    /// Uses the same bounding box as the TSV copy; unselected cells become empty <td>s
+    pub fn copy_selected_cells_html(
+        selected_cells: &HashSet<(usize, usize)>,
+        grid: &Grid,
+        format: CopyFormat,
+    ) -> String {
+        if selected_cells.is_empty() {
+            return String::new();
+        }
+
+        let min_row = selected_cells.iter().map(|(r, _)| *r).min().unwrap();
+        let max_row = selected_cells.iter().map(|(r, _)| *r).max().unwrap();
+        let min_col = selected_cells.iter().map(|(_, c)| *c).min().unwrap();
+        let max_col = selected_cells.iter().map(|(_, c)| *c).max().unwrap();
+
+        let mut result = String::from("<meta charset=\"utf-8\"><table><tbody>");
+        for row in min_row..=max_row {
+            result.push_str("<tr>");
+            for col in min_col..=max_col {
+                // Section separators become cell borders
+                let borders: Vec<String> = [("right", grid.col_section_break(col)), ("bottom", grid.row_section_break(row))]
+                    .into_iter()
+                    .filter_map(|(side, section_break)| section_break.map(|b| format!("border-{}:{}", side, b.css())))
+                    .collect();
+                if borders.is_empty() {
+                    result.push_str("<td>");
+                } else {
+                    result.push_str(&format!("<td style=\"{}\">", borders.join(";")));
+                }
+                if selected_cells.contains(&(row, col)) {
+                    result.push_str(&Self::escape_html(&format.cell_text(grid, row, col)));
+                }
+                result.push_str("</td>");
+            }
+            result.push_str("</tr>");
+        }
+        result.push_str("</tbody></table>");
+
+        result
+    }
+
+    /// Escape text for use inside an HTML table cell
+    fn escape_html(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for ch in text.chars() {
+            match ch {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\n' => escaped.push_str("<br>"),
+                _ => escaped.push(ch),
+            }
+        }
+        escaped
+    }
+
+    /// Copy selected cells as a structured payload that keeps value types and styles:
+    /// {"format": "datagrid5/cells", "version": 1, "rows": R, "cols": C,
+    ///  "columns": [{"data_type", "number_format"}, ...],
+    ///  "cells": [[row_offset, col_offset, value, style?], ...]}
+    /// Offsets are relative to the selection's bounding box; only selected cells with a value
+    /// or style are listed (dates are {"date": ...}, as in serialize_state)
+    pub fn copy_selected_cells_structured(selected_cells: &HashSet<(usize, usize)>, grid: &Grid) -> Value {
+        if selected_cells.is_empty() {
+            return Value::Null;
+        }
+
+        let min_row = selected_cells.iter().map(|(r, _)| *r).min().unwrap();
+        let max_row = selected_cells.iter().map(|(r, _)| *r).max().unwrap();
+        let min_col = selected_cells.iter().map(|(_, c)| *c).min().unwrap();
+        let max_col = selected_cells.iter().map(|(_, c)| *c).max().unwrap();
+
+        let columns: Vec<Value> = (min_col..=max_col)
+            .map(|col| {
+                let config = grid.column_configs.get(col);
+                json!({
+                    "data_type": config.map(|c| c.data_type.as_str()),
+                    "number_format": config.and_then(|c| c.number_format.as_ref()).map(NumberFormat::to_json),
+                })
+            })
+            .collect();
+
+        let mut selected: Vec<(usize, usize)> = selected_cells.iter().copied().collect();
+        selected.sort_unstable();
+        let cells: Vec<Value> = selected
+            .into_iter()
+            .filter_map(|(row, col)| {
+                let cell = grid.get_cell(row, col)?;
+                let pasted = PastedCell {
+                    value: cell.value.clone(),
+                    bold: cell.font_bold,
+                    italic: cell.font_italic,
+                    bg_color: cell.bg_color,
+                    fg_color: cell.fg_color,
+                };
+                if pasted.value.is_empty() && !pasted.has_style() {
+                    return None;
+                }
+                let mut entry = vec![json!(row - min_row), json!(col - min_col), snapshot::value_to_json(&pasted.value)];
+                let style = pasted.style_json();
+                if !style.is_empty() {
+                    entry.push(Value::Object(style));
+                }
+                Some(Value::Array(entry))
+            })
+            .collect();
+
+        json!({
+            "format": STRUCTURED_FORMAT,
+            "version": STRUCTURED_VERSION,
+            "rows": max_row - min_row + 1,
+            "cols": max_col - min_col + 1,
+            "columns": columns,
+            "cells": cells,
+        })
+    }
+
+    /// Parse a structured payload into a block of cells (positions not listed are empty)
+    /// Text values are converted to the data type declared for their column in "columns"
+    pub fn parse_structured(payload: &str) -> Result<Vec<Vec<PastedCell>>, GridError> {
+        let invalid = |reason: &str| GridError::PasteFailed { reason: locale::format("error.structured_clipboard", &[("error", &reason)]) };
+
+        let payload: Value = serde_json::from_str(payload).map_err(|e| invalid(&e.to_string()))?;
+        if payload["format"].as_str() != Some(STRUCTURED_FORMAT) {
+            return Err(invalid("missing \"format\": \"datagrid5/cells\""));
+        }
+        if payload["version"].as_u64().is_some_and(|v| v > STRUCTURED_VERSION) {
+            return Err(invalid("unsupported version"));
+        }
+        let rows = payload["rows"].as_u64().ok_or_else(|| invalid("missing rows"))? as usize;
+        let cols = payload["cols"].as_u64().ok_or_else(|| invalid("missing cols"))? as usize;
+        if rows.checked_mul(cols).is_none_or(|cells| cells > MAX_STRUCTURED_CELLS) {
+            return Err(invalid(&format!("more than {} cells", MAX_STRUCTURED_CELLS)));
+        }
+        let types: Vec<Option<DataType>> = (0..cols)
+            .map(|col| payload["columns"][col]["data_type"].as_str().map(DataType::parse))
+            .collect();
+
+        let mut block = vec![vec![PastedCell::default(); cols]; rows];
+        for entry in payload["cells"].as_array().into_iter().flatten() {
+            let (Some(row), Some(col)) = (entry[0].as_u64(), entry[1].as_u64()) else { continue };
+            let (row, col) = (row as usize, col as usize);
+            let Some(value) = snapshot::value_from_json(&entry[2]) else { continue };
+            if row >= rows || col >= cols {
+                continue;
+            }
+            let value = match (value, &types[col]) {
+                (CellValue::Text(text), Some(data_type)) => data_type.convert_text(&text),
+                (value, _) => value,
+            };
+            let style = &entry[3];
+            block[row][col] = PastedCell {
+                value,
+                bold: style["bold"].as_bool().unwrap_or(false),
+                italic: style["italic"].as_bool().unwrap_or(false),
+                bg_color: style["bg"].as_u64().map(|c| c as u32),
+                fg_color: style["fg"].as_u64().map(|c| c as u32),
+            };
+        }
+        Ok(block)
+    }
+
     /// Cut selected cells (copy and then clear)
     pub fn cut_selected_cells(
         selected_cells: &HashSet<(usize, usize)>,
         grid: &mut Grid,
     ) -> String {
         // First copy the cells
-        let clipboard_text = Self::copy_selected_cells(selected_cells, grid);
+        let clipboard_text = Self::copy_selected_cells(selected_cells, grid, CopyFormat::Raw);
 
         // Then clear all selected cells
         let cells_to_clear: Vec<(usize, usize)> = selected_cells.iter().copied().collect();
@@ -72,6 +452,19 @@ impl ClipboardOps {
         clipboard_text
     }
 
+    /// Convert a pasted TSV field to a cell value (number, boolean or text)
+    pub fn parse_value(value: &str) -> CellValue {
+        if value.is_empty() {
+            CellValue::Empty
+        } else if let Ok(num) = value.parse::<f64>() {
+            CellValue::Number(num)
+        } else if value == "true" || value == "false" {
+            CellValue::Boolean(value == "true")
+        } else {
+            CellValue::Text(value.to_string())
+        }
+    }
+
     /// Paste cells from TSV (Tab-Separated Values) format
     pub fn paste_cells(
         tsv_text: String,
@@ -98,7 +491,7 @@ impl ClipboardOps {
             cells[0]
         } else {
             return Err(GridError::PasteFailed {
-                reason: "No cell selected for paste".to_string(),
+                reason: locale::text("error.no_paste_target"),
             });
         };
 
@@ -117,21 +510,189 @@ impl ClipboardOps {
                     break; // Don't paste beyond grid bounds
                 }
 
-                // Create cell value
-                let cell_value = if value.is_empty() {
-                    CellValue::Empty
-                } else if let Ok(num) = value.parse::<f64>() {
-                    CellValue::Number(num)
-                } else if *value == "true" || *value == "false" {
-                    CellValue::Boolean(*value == "true")
-                } else {
-                    CellValue::Text(value.to_string())
-                };
-
-                grid.set_value(target_row, target_col, cell_value);
+                grid.set_value(target_row, target_col, Self::parse_value(value));
             }
         }
 
         Ok(())
     }
 }
+
+/// Get the browser's async Clipboard API
+fn system_clipboard() -> Result<Clipboard, GridError> {
+    let window = web_sys::window().ok_or_else(|| GridError::OperationError {
+        message: locale::text("error.no_window"),
+    })?;
+    Ok(window.navigator().clipboard())
+}
+
+/// Build a Blob holding `text` with the given MIME type
+fn text_blob(text: &str, mime_type: &str) -> Result<JsValue, JsValue> {
+    let options = BlobPropertyBag::new();
+    options.set_type(mime_type);
+    Blob::new_with_str_sequence_and_options(&Array::of1(&JsValue::from_str(text)), &options).map(JsValue::from)
+}
+
+/// Write TSV (`text/plain`) and an HTML table (`text/html`) to the system clipboard
+/// Falls back to plain text where ClipboardItem is not supported
+pub async fn write_system_clipboard(tsv: String, html: String) -> Result<(), JsValue> {
+    let clipboard = system_clipboard()?;
+
+    let item = (|| -> Result<ClipboardItem, JsValue> {
+        let items = Object::new();
+        Reflect::set(&items, &JsValue::from_str("text/plain"), &text_blob(&tsv, "text/plain")?)?;
+        Reflect::set(&items, &JsValue::from_str("text/html"), &text_blob(&html, "text/html")?)?;
+        ClipboardItem::new_with_record_from_str_to_blob_promise(&items)
+    })();
+
+    if let Ok(item) = item {
+        if JsFuture::from(clipboard.write(&Array::of1(&item))).await.is_ok() {
+            return Ok(());
+        }
+    }
+
+    JsFuture::from(clipboard.write_text(&tsv)).await.map(|_| ())
+}
+
+/// Write plain text to the system clipboard (e.g. text selected in the native editor)
+pub async fn write_system_clipboard_text(text: String) -> Result<(), JsValue> {
+    JsFuture::from(system_clipboard()?.write_text(&text)).await.map(|_| ())
+}
+
+/// Read the system clipboard as (plain text, HTML if present)
+/// Falls back to plain text where reading ClipboardItems is not supported or not allowed
+pub async fn read_system_clipboard() -> Result<(String, Option<String>), JsValue> {
+    let clipboard = system_clipboard()?;
+
+    if let Ok(items) = JsFuture::from(clipboard.read()).await {
+        let mut text = None;
+        let mut html = None;
+        for item in Array::from(&items).iter() {
+            let item: ClipboardItem = item.unchecked_into();
+            let types = item.types();
+            if html.is_none() && types.includes(&JsValue::from_str("text/html"), 0) {
+                html = read_clipboard_item(&item, "text/html").await;
+            }
+            if text.is_none() && types.includes(&JsValue::from_str("text/plain"), 0) {
+                text = read_clipboard_item(&item, "text/plain").await;
+            }
+        }
+        if text.is_some() || html.is_some() {
+            return Ok((text.unwrap_or_default(), html));
+        }
+    }
+
+    let text = JsFuture::from(clipboard.read_text()).await?;
+    Ok((text.as_string().unwrap_or_default(), None))
+}
+
+/// Read one MIME type of a ClipboardItem as text
+async fn read_clipboard_item(item: &ClipboardItem, mime_type: &str) -> Option<String> {
+    let blob: Blob = JsFuture::from(item.get_type(mime_type)).await.ok()?.unchecked_into();
+    JsFuture::from(blob.text()).await.ok()?.as_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paste_conflicts() {
+        let mut grid = Grid::new(2, 2);
+        grid.set_value(0, 0, CellValue::Text("keep".to_string()));
+        grid.set_value(0, 1, CellValue::Number(5.0));
+        grid.set_column_editable(1, false);
+
+        let new_value = ClipboardOps::parse_value("new");
+        let conflict = PasteConflict::check(&grid, 0, 0, &new_value).unwrap();
+        assert!(!conflict.read_only);
+        assert_eq!(conflict.to_json()["reason"], "non_empty");
+
+        // Same value is not a conflict; read-only always is
+        assert!(PasteConflict::check(&grid, 0, 0, &ClipboardOps::parse_value("keep")).is_none());
+        assert!(PasteConflict::check(&grid, 1, 1, &new_value).unwrap().read_only);
+        assert!(PasteConflict::check(&grid, 1, 0, &new_value).is_none());
+        assert!(PasteConflict::check(&grid, 5, 0, &new_value).is_none());
+
+        assert_eq!(PastePolicy::parse("Skip").unwrap(), PastePolicy::Skip);
+        assert!(PastePolicy::parse("merge").is_err());
+    }
+
+    #[test]
+    fn test_paste_special_modes() {
+        let special = PasteSpecial::parse("values, transpose").unwrap();
+        assert_eq!(special.content, PasteContent::Values);
+        assert!(special.transpose && !special.skip_blanks);
+        assert_eq!(PasteSpecial::parse("").unwrap(), PasteSpecial::default());
+        assert!(PasteSpecial::parse("values,merge").is_err());
+
+        let block = vec![
+            vec![PastedCell::from_text("a"), PastedCell::from_text("b")],
+            vec![PastedCell::from_text("1")],
+        ];
+        let transposed = special.arrange(block);
+        assert_eq!(transposed.len(), 2);
+        assert_eq!(transposed[0][1].value, CellValue::Number(1.0));
+        assert_eq!(transposed[1][0].value, CellValue::Text("b".to_string()));
+        assert_eq!(transposed[1][1], PastedCell::default());
+    }
+
+    #[test]
+    fn test_structured_round_trip() {
+        let mut grid = Grid::new(3, 3);
+        grid.set_value(0, 0, CellValue::Date("2024-01-31".to_string()));
+        grid.set_value(1, 1, CellValue::Text("42".to_string()));
+        let mut styled = grid.get_cell(1, 1).cloned().unwrap();
+        styled.font_bold = true;
+        styled.bg_color = Some(0xff0000ff);
+        grid.set_cell(1, 1, styled);
+
+        let selected: HashSet<(usize, usize)> = [(0, 0), (0, 1), (1, 0), (1, 1)].into_iter().collect();
+        let payload = ClipboardOps::copy_selected_cells_structured(&selected, &grid);
+        assert_eq!(payload["cells"].as_array().unwrap().len(), 2);
+
+        let block = ClipboardOps::parse_structured(&payload.to_string()).unwrap();
+        assert_eq!(block.len(), 2);
+        assert_eq!(block[0][0].value, CellValue::Date("2024-01-31".to_string()));
+        assert_eq!(block[0][1], PastedCell::default());
+        // Text stays text (a TSV paste would turn "42" into a number)
+        assert_eq!(block[1][1].value, CellValue::Text("42".to_string()));
+        assert!(block[1][1].bold);
+        assert_eq!(block[1][1].bg_color, Some(0xff0000ff));
+
+        assert!(ClipboardOps::parse_structured("{\"rows\": 1}").is_err());
+
+        // Declared column types apply; oversized blocks are refused before allocating
+        let typed = r#"{"format":"datagrid5/cells","rows":1,"cols":2,
+            "columns":[{"data_type":"number"},{"data_type":"date"}],"cells":[[0,0,"7"],[0,1,"2024-02-01"]]}"#;
+        let block = ClipboardOps::parse_structured(typed).unwrap();
+        assert_eq!(block[0][0].value, CellValue::Number(7.0));
+        assert_eq!(block[0][1].value, CellValue::Date("2024-02-01".to_string()));
+        let huge = r#"{"format":"datagrid5/cells","rows":100000000,"cols":100000000,"cells":[]}"#;
+        assert!(ClipboardOps::parse_structured(huge).is_err());
+    }
+
+    #[test]
+    fn test_copy_selected_cells_html() {
+        let mut grid = Grid::new(2, 2);
+        grid.set_value(0, 0, CellValue::Text("a<b".to_string()));
+        grid.set_value(1, 1, CellValue::Number(2.0));
+
+        let selected: HashSet<(usize, usize)> = [(0, 0), (1, 1)].into_iter().collect();
+        assert_eq!(
+            ClipboardOps::copy_selected_cells_html(&selected, &grid, CopyFormat::Raw),
+            "<meta charset=\"utf-8\"><table><tbody><tr><td>a&lt;b</td><td></td></tr><tr><td></td><td>2</td></tr></tbody></table>"
+        );
+        assert_eq!(ClipboardOps::copy_selected_cells(&selected, &grid, CopyFormat::Raw), "a<b\t\n\t2");
+
+        // Section separators are exported as cell borders
+        grid.col_section_breaks.insert(0, crate::core::SectionBreak::Thick);
+        assert!(ClipboardOps::copy_selected_cells_html(&selected, &grid, CopyFormat::Raw)
+            .starts_with("<meta charset=\"utf-8\"><table><tbody><tr><td style=\"border-right:3px solid #404040\">a&lt;b</td>"));
+
+        // Display format uses the column's number format
+        grid.set_column_number_format(1, Some(NumberFormat { decimals: Some(1), ..NumberFormat::default() }));
+        assert_eq!(ClipboardOps::copy_selected_cells(&selected, &grid, CopyFormat::Display), "a<b\t\n\t2.0");
+        assert_eq!(ClipboardOps::copy_selected_cells(&selected, &grid, CopyFormat::Raw), "a<b\t\n\t2");
+    }
+}
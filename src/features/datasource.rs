@@ -0,0 +1,321 @@
+use js_sys::Function;
+use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
+
+/// Rows requested per fetch_rows call
+pub const DEFAULT_BLOCK_SIZE: usize = 100;
+/// Fetched blocks kept in the grid (older ones are dropped when the cache is full)
+pub const DEFAULT_CACHE_BLOCKS: usize = 20;
+/// Most blocks prefetched ahead of the scroll direction
+pub const DEFAULT_PREFETCH_BLOCKS: usize = 4;
+/// How far ahead (ms of scrolling at the current speed) prefetching tries to stay
+const PREFETCH_LOOKAHEAD_MS: f64 = 1000.0;
+/// Scroll samples further apart than this restart the velocity estimate
+const VELOCITY_RESET_MS: f64 = 300.0;
+
+/// Hit/miss counters of the block cache
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,           // Blocks already loaded when they scrolled into view
+    pub misses: u64,         // Blocks that scrolled into view before their rows arrived
+    pub prefetched: u64,     // Blocks fetched ahead of time
+    pub prefetch_hits: u64,  // Prefetched blocks that were later shown
+}
+
+/// Server-side data source: the grid holds only the blocks of rows around the viewport
+/// and asks the host for more through `fetch_rows(start, count)` as the view moves
+pub struct VirtualSource {
+    pub fetch_rows: Function,
+    pub block_size: usize,
+    pub cache_blocks: usize,
+    loaded: HashMap<usize, u64>, // Block -> last sync in which it was wanted (LRU eviction)
+    pending: HashSet<usize>,     // Blocks requested but not yet delivered
+    failed: HashSet<usize>,      // Blocks whose fetch failed (retried by refresh)
+    generation: u64,             // Bumped by reset; responses to older requests are ignored
+    clock: u64,
+    pub prefetch_blocks: usize,          // Most blocks prefetched ahead (0 = no prefetching)
+    pub prefetch_scheduled: bool,        // An idle callback is waiting to prefetch
+    prefetched: HashSet<usize>,          // Prefetched blocks not shown yet
+    visible_blocks: Option<(usize, usize)>, // Blocks on screen at the last track_view
+    last_sample: Option<(f64, usize)>,   // (time, first visible row) at the last track_view
+    velocity: f64,                       // Rows per ms (negative when scrolling up)
+    stats: CacheStats,
+}
+
+impl VirtualSource {
+    pub fn new(fetch_rows: Function, block_size: usize, cache_blocks: usize) -> Self {
+        Self {
+            fetch_rows,
+            block_size: block_size.max(1),
+            cache_blocks: cache_blocks.max(1),
+            loaded: HashMap::new(),
+            pending: HashSet::new(),
+            failed: HashSet::new(),
+            generation: 0,
+            clock: 0,
+            prefetch_blocks: DEFAULT_PREFETCH_BLOCKS,
+            prefetch_scheduled: false,
+            prefetched: HashSet::new(),
+            visible_blocks: None,
+            last_sample: None,
+            velocity: 0.0,
+            stats: CacheStats::default(),
+        }
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Blocks covering rows first_row..=last_row
+    fn blocks(&self, first_row: usize, last_row: usize) -> RangeInclusive<usize> {
+        first_row / self.block_size..=last_row / self.block_size
+    }
+
+    /// First row and row count of a block (the last block may be short)
+    pub fn block_rows(&self, block: usize, total_rows: usize) -> (usize, usize) {
+        let start = block * self.block_size;
+        (start, self.block_size.min(total_rows.saturating_sub(start)))
+    }
+
+    /// Mark the blocks covering row ranges (first, last) as in use and return the ones to
+    /// fetch (the returned blocks are recorded as pending)
+    pub fn request(&mut self, ranges: &[(usize, usize)], total_rows: usize) -> Vec<usize> {
+        if total_rows == 0 {
+            return Vec::new();
+        }
+        self.clock += 1;
+        let mut wanted = Vec::new();
+        let blocks = ranges.iter().filter(|(first, _)| *first < total_rows).flat_map(|&(first, last)| self.blocks(first, last.min(total_rows - 1)));
+        for block in blocks.collect::<Vec<_>>() {
+            if let Some(used) = self.loaded.get_mut(&block) {
+                *used = self.clock;
+            } else if !self.pending.contains(&block) && !self.failed.contains(&block) {
+                self.pending.insert(block);
+                wanted.push(block);
+            }
+        }
+        wanted
+    }
+
+    pub fn is_row_loaded(&self, row: usize) -> bool {
+        self.loaded.contains_key(&(row / self.block_size))
+    }
+
+    /// True while the row's block has been requested but has not arrived
+    pub fn is_row_pending(&self, row: usize) -> bool {
+        self.pending.contains(&(row / self.block_size))
+    }
+
+    /// Follow the visible rows: update the scroll velocity and count blocks that came into
+    /// view as cache hits (already loaded) or misses
+    pub fn track_view(&mut self, first_row: usize, last_row: usize, now: f64) {
+        match self.last_sample {
+            Some((time, row)) if now > time && now - time < VELOCITY_RESET_MS => {
+                let sample = (first_row as f64 - row as f64) / (now - time);
+                self.velocity = 0.5 * self.velocity + 0.5 * sample;
+            }
+            Some((time, _)) if now == time => {}
+            _ => self.velocity = 0.0,
+        }
+        if self.last_sample.is_none_or(|(_, row)| row != first_row) || self.velocity != 0.0 {
+            self.last_sample = Some((now, first_row));
+        }
+
+        let blocks = (first_row / self.block_size, last_row / self.block_size);
+        let previous = self.visible_blocks.replace(blocks);
+        for block in blocks.0..=blocks.1 {
+            if previous.is_some_and(|(first, last)| (first..=last).contains(&block)) {
+                continue;
+            }
+            if self.loaded.contains_key(&block) {
+                self.stats.hits += 1;
+                if self.prefetched.remove(&block) {
+                    self.stats.prefetch_hits += 1;
+                }
+            } else {
+                self.stats.misses += 1;
+            }
+        }
+    }
+
+    /// Scroll velocity in rows per ms (0 when the view is still)
+    pub fn velocity(&self) -> f64 {
+        self.velocity
+    }
+
+    /// Blocks to fetch ahead of the view (recorded as pending): in the scroll direction as
+    /// far as the current speed covers in a second (at least one block, at most
+    /// prefetch_blocks), plus one block the other way. Nothing while other fetches are
+    /// still pending or when prefetching is off.
+    pub fn prefetch(&mut self, first_row: usize, last_row: usize, total_rows: usize) -> Vec<usize> {
+        if self.prefetch_blocks == 0 || total_rows == 0 || !self.pending.is_empty() {
+            return Vec::new();
+        }
+        // Stay well inside the cache so prefetched blocks do not push out the visible ones
+        let limit = self.prefetch_blocks.min(self.cache_blocks / 2).max(1);
+        let speed_blocks = (self.velocity.abs() * PREFETCH_LOOKAHEAD_MS / self.block_size as f64).ceil() as usize;
+        let ahead = speed_blocks.clamp(1, limit);
+        let last_block = (total_rows - 1) / self.block_size;
+        // Blocks right next to the view are already requested by sync (one block margin)
+        let (first_block, view_last_block) = (first_row / self.block_size, last_row.min(total_rows - 1) / self.block_size);
+        let (down, up) = if self.velocity < 0.0 { (1, ahead) } else { (ahead, 1) };
+        let below = (view_last_block + 2..=view_last_block + 1 + down).filter(|&b| b <= last_block);
+        let above = (1..=up).filter_map(|i| first_block.checked_sub(1 + i));
+
+        let mut blocks = Vec::new();
+        for block in below.chain(above) {
+            if !self.loaded.contains_key(&block) && !self.pending.contains(&block) && !self.failed.contains(&block) {
+                self.pending.insert(block);
+                self.prefetched.insert(block);
+                blocks.push(block);
+            }
+        }
+        self.stats.prefetched += blocks.len() as u64;
+        blocks
+    }
+
+    pub fn stats(&self) -> &CacheStats {
+        &self.stats
+    }
+
+    /// Cache statistics: hits, misses, hit_rate, prefetched, prefetch_hits, the cache
+    /// state and the scroll velocity (rows per second)
+    pub fn stats_json(&self) -> serde_json::Value {
+        let shown = self.stats.hits + self.stats.misses;
+        serde_json::json!({
+            "hits": self.stats.hits,
+            "misses": self.stats.misses,
+            "hit_rate": if shown > 0 { self.stats.hits as f64 / shown as f64 } else { 1.0 },
+            "prefetched": self.stats.prefetched,
+            "prefetch_hits": self.stats.prefetch_hits,
+            "prefetch_blocks": self.prefetch_blocks,
+            "loaded_blocks": self.loaded.len(),
+            "pending_blocks": self.pending.len(),
+            "velocity": self.velocity * 1000.0,
+        })
+    }
+
+    /// Reset the hit/miss counters
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+
+    /// Record a delivered block; false when the response belongs to an earlier generation
+    pub fn complete(&mut self, generation: u64, block: usize) -> bool {
+        if generation != self.generation {
+            return false;
+        }
+        self.pending.remove(&block);
+        self.loaded.insert(block, self.clock);
+        true
+    }
+
+    /// Record a failed fetch (not retried until the next reset)
+    pub fn fail(&mut self, generation: u64, block: usize) {
+        if generation == self.generation && self.pending.remove(&block) {
+            self.prefetched.remove(&block);
+            self.failed.insert(block);
+        }
+    }
+
+    /// Remove the least recently used blocks beyond the cache size and return them
+    /// (blocks wanted by the last request are always kept)
+    pub fn evict(&mut self) -> Vec<usize> {
+        let excess = self.loaded.len().saturating_sub(self.cache_blocks);
+        let mut candidates: Vec<(u64, usize)> = self
+            .loaded
+            .iter()
+            .filter(|(_, &used)| used < self.clock)
+            .map(|(&block, &used)| (used, block))
+            .collect();
+        candidates.sort_unstable();
+
+        let evicted: Vec<usize> = candidates.into_iter().take(excess).map(|(_, block)| block).collect();
+        for block in &evicted {
+            self.loaded.remove(block);
+            self.prefetched.remove(block);
+        }
+        evicted
+    }
+
+    /// Forget every block (e.g. after the server data changed); pending responses are ignored
+    pub fn reset(&mut self) {
+        self.generation += 1;
+        self.loaded.clear();
+        self.pending.clear();
+        self.failed.clear();
+        self.prefetched.clear();
+        self.visible_blocks = None;
+    }
+
+    pub fn to_json(&self, total_rows: usize) -> serde_json::Value {
+        serde_json::json!({
+            "total_rows": total_rows,
+            "block_size": self.block_size,
+            "cache_blocks": self.cache_blocks,
+            "loaded_blocks": self.loaded.len(),
+            "pending_blocks": self.pending.len(),
+            "failed_blocks": self.failed.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen::JsCast;
+
+    #[test]
+    fn test_virtual_source_blocks() {
+        let mut source = VirtualSource::new(wasm_bindgen::JsValue::UNDEFINED.unchecked_into(), 10, 2);
+        assert_eq!(source.request(&[(5, 24)], 1000), vec![0, 1, 2]);
+        assert_eq!(source.request(&[(5, 24), (0, 3)], 1000), Vec::<usize>::new()); // Already pending
+        assert_eq!(source.block_rows(99, 995), (990, 5));
+
+        let generation = source.generation();
+        assert!(source.complete(generation, 0) && source.complete(generation, 1));
+        source.fail(generation, 2);
+        assert!(source.is_row_loaded(15) && !source.is_row_loaded(25));
+        assert!(!source.is_row_pending(25));
+
+        // Scrolling away: the old blocks become eviction candidates
+        assert_eq!(source.request(&[(500, 509)], 1000), vec![50]);
+        source.complete(generation, 50);
+        assert_eq!(source.evict().len(), 1);
+        assert!(source.is_row_loaded(500));
+
+        source.reset();
+        assert!(!source.complete(generation, 3));
+        assert!(!source.is_row_loaded(500));
+        assert_eq!(source.request(&[(20, 20)], 1000), vec![2]); // Failed blocks are retried after a reset
+    }
+
+    #[test]
+    fn test_virtual_prefetch() {
+        let mut source = VirtualSource::new(wasm_bindgen::JsValue::UNDEFINED.unchecked_into(), 10, 20);
+        let generation = source.generation();
+        source.track_view(0, 9, 0.0);
+        assert_eq!(source.stats().misses, 1);
+        for block in source.request(&[(0, 19)], 1000) {
+            source.complete(generation, block);
+        }
+
+        // Still: one block beyond the sync margin each way (none above row 0)
+        assert_eq!(source.prefetch(0, 9, 1000), vec![2]);
+        assert!(source.prefetch(0, 9, 1000).is_empty()); // Waits for pending fetches
+        source.complete(generation, 2);
+
+        // Scrolling down fast enough to cover the prefetch limit of 4 blocks per second
+        source.track_view(3, 12, 100.0);
+        source.track_view(9, 18, 200.0);
+        assert!(source.velocity() > 0.0);
+        assert_eq!(source.prefetch(9, 18, 1000), vec![3, 4, 5, 6]);
+        for block in [3, 4, 5, 6] {
+            source.complete(generation, block);
+        }
+        source.track_view(20, 29, 300.0);
+        let stats = source.stats().clone();
+        assert_eq!((stats.hits, stats.misses, stats.prefetched, stats.prefetch_hits), (2, 1, 5, 1));
+    }
+}
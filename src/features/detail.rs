@@ -0,0 +1,127 @@
+use crate::core::{Grid, Viewport};
+use js_sys::Function;
+use std::collections::HashMap;
+use wasm_bindgen::JsValue;
+
+/// Canvas rectangle (x, y, width, height) in device pixels
+pub type PanelRect = [f32; 4];
+
+/// Master-detail panels: tells the host where each expanded row's detail region is on
+/// the canvas so it can position a DOM element over it. The callback only runs when a
+/// panel moves, is scrolled in or out of view, or is collapsed.
+#[derive(Default)]
+pub struct DetailPanels {
+    callback: Option<Function>,
+    reported: HashMap<usize, Option<PanelRect>>, // Row ID -> last visible part sent (None = hidden)
+}
+
+impl DetailPanels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set (Some) or remove (None) the callback; all panels are reported again
+    pub fn set_callback(&mut self, callback: Option<Function>) {
+        self.callback = callback;
+        self.reported.clear();
+    }
+
+    /// Report panels whose position or visibility changed since the last call
+    pub fn sync(&mut self, grid: &Grid, viewport: &Viewport) {
+        let Some(callback) = &self.callback else {
+            return;
+        };
+
+        let mut current: HashMap<usize, Option<PanelRect>> = HashMap::new();
+        for row in grid.detail_rows() {
+            let Some(id) = grid.row_id(row) else {
+                continue;
+            };
+            let (rect, clip) = panel_rect(grid, viewport, row);
+            current.insert(id, clip);
+            if self.reported.get(&id) == Some(&clip) {
+                continue;
+            }
+            let zoom = viewport.zoom;
+            let scaled = |r: PanelRect| serde_json::json!({ "x": r[0] * zoom, "y": r[1] * zoom, "width": r[2] * zoom, "height": r[3] * zoom });
+            let info = serde_json::json!({
+                "row": row,
+                "row_id": id,
+                "expanded": true,
+                "visible": clip.is_some(),
+                "rect": scaled(rect),
+                "clip": clip.map(scaled),
+            });
+            call(callback, &info);
+        }
+
+        for id in self.reported.keys().filter(|id| !current.contains_key(id)) {
+            call(callback, &serde_json::json!({ "row_id": id, "expanded": false, "visible": false }));
+        }
+        self.reported = current;
+    }
+}
+
+/// Detail region of an expanded row: the full rectangle below the row's cells and its
+/// part visible in the row's pane (None when scrolled out of view)
+pub fn panel_rect(grid: &Grid, viewport: &Viewport, row: usize) -> (PanelRect, Option<PanelRect>) {
+    let header_offset_x = if grid.show_headers { grid.row_header_width } else { 0.0 };
+    let header_offset_y = if grid.show_headers { grid.col_header_height } else { 0.0 };
+    let frozen = row < grid.frozen_rows;
+
+    let scroll_y = if frozen { 0.0 } else { viewport.scroll_y };
+    let y = header_offset_y + (grid.row_y_position(row) - scroll_y) as f32 + grid.row_height(row);
+    let rect = [header_offset_x, y, (viewport.canvas_width - header_offset_x).max(0.0), grid.detail_height(row)];
+
+    let pane_top = if frozen {
+        header_offset_y
+    } else {
+        header_offset_y + grid.row_y_position(grid.frozen_rows.min(grid.row_count())) as f32
+    };
+    let pane_bottom = viewport.canvas_height - grid.footer_height;
+    let (top, bottom) = (rect[1].max(pane_top), (rect[1] + rect[3]).min(pane_bottom));
+    let clip = (bottom > top && rect[2] > 0.0).then_some([rect[0], top, rect[2], bottom - top]);
+    (rect, clip)
+}
+
+/// Call the detail callback, logging errors thrown by the host
+fn call(callback: &Function, info: &serde_json::Value) {
+    let arg = js_sys::JSON::parse(&info.to_string()).unwrap_or(JsValue::NULL);
+    if let Err(e) = callback.call1(&JsValue::NULL, &arg) {
+        log::error!("Row detail callback failed: {:?}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detail_panel_reserves_space() {
+        let mut grid = Grid::new(10, 2);
+        let mut viewport = Viewport::new(400.0, 200.0);
+        let (row_height, header) = (grid.row_height(0), grid.col_header_height);
+
+        grid.set_row_detail(1, Some(80.0));
+        assert_eq!(grid.row_y_position(2), (2.0 * row_height + 80.0) as f64);
+        assert_eq!(grid.total_height(), (10.0 * row_height + 80.0) as f64);
+
+        // Clicks on the panel hit no cell; the row below starts after it
+        let panel_y = header + 2.0 * row_height + 10.0;
+        assert_eq!(viewport.canvas_to_cell(grid.row_header_width + 5.0, panel_y, &grid), None);
+        assert_eq!(viewport.canvas_to_cell(grid.row_header_width + 5.0, panel_y + 75.0, &grid), Some((2, 0)));
+
+        let (rect, clip) = panel_rect(&grid, &viewport, 1);
+        assert_eq!(rect, [grid.row_header_width, header + 2.0 * row_height, 400.0 - grid.row_header_width, 80.0]);
+        assert_eq!(clip, Some(rect));
+
+        viewport.set_scroll(0.0, (2.0 * row_height + 80.0) as f64, &grid);
+        assert_eq!(panel_rect(&grid, &viewport, 1).1, None);
+
+        // Panels follow their row when rows are deleted above it, and go away with it
+        grid.delete_row(0);
+        assert_eq!(grid.detail_rows(), vec![0]);
+        grid.delete_row(0);
+        assert!(grid.detail_rows().is_empty());
+    }
+}
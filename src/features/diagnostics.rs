@@ -0,0 +1,103 @@
+use crate::GridError;
+use log::{LevelFilter, Log, Metadata, Record};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+/// Number of recent log lines kept for dump_diagnostics_json()
+const MAX_LOG_LINES: usize = 50;
+
+thread_local! {
+    static RECENT_LOG: RefCell<VecDeque<String>> = RefCell::new(VecDeque::with_capacity(MAX_LOG_LINES));
+}
+
+/// Logger that writes to the browser console (via console_log) and keeps the
+/// most recent lines for diagnostics dumps
+struct DiagnosticLogger;
+
+static LOGGER: DiagnosticLogger = DiagnosticLogger;
+
+impl Log for DiagnosticLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        console_log::log(record);
+        let timestamp = String::from(js_sys::Date::new_0().to_iso_string());
+        push_log_line(format!("{} {:<5} {}", timestamp, record.level(), record.args()));
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install the console + diagnostics logger
+pub fn init_logger(level: LevelFilter) -> Result<(), log::SetLoggerError> {
+    log::set_logger(&LOGGER)?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+/// Parse a log level name: off, error, warn, info or debug
+pub fn parse_log_level(level: &str) -> Result<LevelFilter, GridError> {
+    match level {
+        "off" => Ok(LevelFilter::Off),
+        "error" => Ok(LevelFilter::Error),
+        "warn" => Ok(LevelFilter::Warn),
+        "info" => Ok(LevelFilter::Info),
+        "debug" => Ok(LevelFilter::Debug),
+        _ => Err(GridError::unknown_value("log_level", level, &["off", "error", "warn", "info", "debug"])),
+    }
+}
+
+/// Name of the current log level (trace is reported as debug)
+pub fn log_level_name() -> &'static str {
+    match log::max_level() {
+        LevelFilter::Off => "off",
+        LevelFilter::Error => "error",
+        LevelFilter::Warn => "warn",
+        LevelFilter::Info => "info",
+        LevelFilter::Debug | LevelFilter::Trace => "debug",
+    }
+}
+
+/// Record a log line, dropping the oldest one when the buffer is full
+pub fn push_log_line(line: String) {
+    RECENT_LOG.with(|log| {
+        let mut log = log.borrow_mut();
+        if log.len() >= MAX_LOG_LINES {
+            log.pop_front();
+        }
+        log.push_back(line);
+    });
+}
+
+/// Get the most recent log lines (oldest first)
+pub fn recent_log_lines() -> Vec<String> {
+    RECENT_LOG.with(|log| log.borrow().iter().cloned().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_log_is_bounded() {
+        for i in 0..MAX_LOG_LINES + 5 {
+            push_log_line(format!("line {}", i));
+        }
+        let lines = recent_log_lines();
+        assert_eq!(lines.len(), MAX_LOG_LINES);
+        assert_eq!(lines[0], "line 5");
+        assert_eq!(lines[MAX_LOG_LINES - 1], format!("line {}", MAX_LOG_LINES + 4));
+    }
+
+    #[test]
+    fn test_parse_log_level() {
+        assert_eq!(parse_log_level("off").unwrap(), LevelFilter::Off);
+        assert_eq!(parse_log_level("debug").unwrap(), LevelFilter::Debug);
+        assert!(parse_log_level("trace").is_err());
+    }
+}
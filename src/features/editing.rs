@@ -1,10 +1,217 @@
-use crate::core::{Grid, Viewport};
+use crate::core::{CellValue, DataType, Grid, RuleViolation, Viewport};
+use crate::features::{filter, locale};
 use web_sys::MouseEvent;
 
+/// Severity of a problem found in an in-progress edit
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DraftStatus {
+    Ok,
+    Warning, // Would be accepted, but probably not as intended
+    Error,   // Breaks the column's validation or would be rejected on commit
+}
+
+impl DraftStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DraftStatus::Ok => "ok",
+            DraftStatus::Warning => "warning",
+            DraftStatus::Error => "error",
+        }
+    }
+}
+
+/// Check a value against the column's validation pattern and rules
+/// Returns the error message, or None if the value is valid
+pub fn validate_value(grid: &Grid, col: usize, text: &str) -> Option<String> {
+    if let Some(rule) = grid.get_column_rule(col) {
+        if let Some(violation) = rule.check(text) {
+            if let Some(message) = &rule.message {
+                return Some(message.clone());
+            }
+            return Some(match violation {
+                RuleViolation::Required => locale::text("validation.required"),
+                RuleViolation::Min(min) => locale::format("validation.min", &[("min", &min)]),
+                RuleViolation::Max(max) => locale::format("validation.max", &[("max", &max)]),
+                RuleViolation::MaxLength(max) => locale::format("validation.max_length", &[("max", &max)]),
+                RuleViolation::MinDate(date) => locale::format("validation.min_date", &[("date", &date)]),
+                RuleViolation::MaxDate(date) => locale::format("validation.max_date", &[("date", &date)]),
+                RuleViolation::NotAllowed => locale::text("validation.not_allowed"),
+            });
+        }
+    }
+
+    if text.is_empty() {
+        return None;
+    }
+    let (pattern, message) = grid.get_column_validation(col)?;
+    // Patterns that do not compile here (JS-only syntax) are left to the host
+    regex::Regex::new(&pattern).is_ok_and(|re| !re.is_match(text)).then_some(message)
+}
+
+/// Check editor content against the cell's editability, the column's validation pattern
+/// and rules, unique constraint and data type, without changing the grid
+/// Returns the most severe problem found (empty drafts are Ok unless a value is required)
+pub fn validate_draft(grid: &Grid, row: usize, col: usize, draft: &str) -> (DraftStatus, String) {
+    if !grid.is_cell_editable(row, col) {
+        return (DraftStatus::Error, locale::format("error.not_editable", &[("row", &row), ("col", &col)]));
+    }
+    if let Some(message) = validate_value(grid, col, draft) {
+        return (DraftStatus::Error, message);
+    }
+    if draft.is_empty() {
+        return (DraftStatus::Ok, String::new());
+    }
+
+    if grid.is_column_unique(col) {
+        if let Some(other) = grid.find_duplicate_row(col, &CellValue::Text(draft.to_string()), &[row]) {
+            let status = if grid.unique_strict { DraftStatus::Error } else { DraftStatus::Warning };
+            return (status, locale::format("validation.duplicate", &[("row", &grid.row_label(other))]));
+        }
+    }
+
+    let data_type = grid.get_column_config(col).map(|config| config.data_type.clone());
+    let type_key = match data_type {
+        Some(DataType::Number) if draft.trim().parse::<f64>().is_err() => Some("validation.number"),
+        Some(DataType::Date) if filter::date_key(draft).is_none() => Some("validation.date"),
+        Some(DataType::Boolean) if !["true", "false", "1", "0"].contains(&draft.trim().to_lowercase().as_str()) => {
+            Some("validation.boolean")
+        }
+        _ => None,
+    };
+    match type_key {
+        Some(key) => (DraftStatus::Warning, locale::text(key)),
+        None => (DraftStatus::Ok, String::new()),
+    }
+}
+
+/// Text, caret and selection of the in-grid (native) editor
+/// Positions are char indices; the selection runs between `anchor` and `caret`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextBuffer {
+    pub text: String,
+    pub caret: usize,
+    pub anchor: usize,
+}
+
+/// What the host has to do after a key was given to the native editor
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EditorKey {
+    Handled,   // The buffer changed (or the caret moved)
+    Commit,    // Enter / Tab: write the text into the cell
+    CommitAll, // Ctrl+Enter: write the text into every selected cell
+    Cancel,    // Escape: discard the text
+    Copy,      // Ctrl+C: copy the selected text
+    Cut,       // Ctrl+X: copy the selected text, then remove it
+    Paste,     // Ctrl+V: read the clipboard and insert it
+    Ignored,   // Not an editor key
+}
+
+impl TextBuffer {
+    /// Buffer holding `text` with the caret at the end
+    pub fn new(text: &str) -> Self {
+        let len = text.chars().count();
+        Self { text: text.to_string(), caret: len, anchor: len }
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    /// Byte offset of a char index
+    fn byte_at(&self, index: usize) -> usize {
+        self.text.char_indices().nth(index).map_or(self.text.len(), |(i, _)| i)
+    }
+
+    /// Selected char range (start, end); empty when nothing is selected
+    pub fn selection(&self) -> (usize, usize) {
+        (self.caret.min(self.anchor), self.caret.max(self.anchor))
+    }
+
+    pub fn has_selection(&self) -> bool {
+        self.caret != self.anchor
+    }
+
+    pub fn selected_text(&self) -> String {
+        let (start, end) = self.selection();
+        self.text[self.byte_at(start)..self.byte_at(end)].to_string()
+    }
+
+    /// Text before the caret (for measuring the caret position)
+    pub fn text_before(&self, index: usize) -> &str {
+        &self.text[..self.byte_at(index)]
+    }
+
+    /// Replace the selection with `text` (line breaks become spaces) and put the caret after it
+    pub fn insert(&mut self, text: &str) {
+        let text = text.replace("\r\n", " ").replace(['\r', '\n', '\t'], " ");
+        let (start, end) = self.selection();
+        let (from, to) = (self.byte_at(start), self.byte_at(end));
+        self.text.replace_range(from..to, &text);
+        self.caret = start + text.chars().count();
+        self.anchor = self.caret;
+    }
+
+    /// Remove the selection, or the char before (`forward` false) or after the caret
+    pub fn delete(&mut self, forward: bool) {
+        if !self.has_selection() {
+            if forward && self.caret < self.len() {
+                self.anchor = self.caret + 1;
+            } else if !forward && self.caret > 0 {
+                self.anchor = self.caret - 1;
+            }
+        }
+        self.insert("");
+    }
+
+    /// Move the caret to `index`, extending the selection when `extend` is set
+    pub fn move_to(&mut self, index: usize, extend: bool) {
+        self.caret = index.min(self.len());
+        if !extend {
+            self.anchor = self.caret;
+        }
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = 0;
+        self.caret = self.len();
+    }
+
+    /// Apply an editing key (KeyboardEvent.key); printable keys are typed
+    pub fn handle_key(&mut self, key: &str, ctrl: bool, shift: bool) -> EditorKey {
+        match key {
+            "Enter" if ctrl => return EditorKey::CommitAll,
+            "Enter" | "Tab" => return EditorKey::Commit,
+            "Escape" => return EditorKey::Cancel,
+            "Backspace" => self.delete(false),
+            "Delete" => self.delete(true),
+            "ArrowLeft" if !shift && self.has_selection() => self.move_to(self.selection().0, false),
+            "ArrowRight" if !shift && self.has_selection() => self.move_to(self.selection().1, false),
+            "ArrowLeft" => self.move_to(self.caret.saturating_sub(1), shift),
+            "ArrowRight" => self.move_to(self.caret + 1, shift),
+            "Home" | "ArrowUp" => self.move_to(0, shift),
+            "End" | "ArrowDown" => self.move_to(self.len(), shift),
+            _ if ctrl => match key.to_lowercase().as_str() {
+                "a" => self.select_all(),
+                "c" => return EditorKey::Copy,
+                "x" if self.has_selection() => return EditorKey::Cut,
+                "x" => {}
+                "v" => return EditorKey::Paste,
+                _ => return EditorKey::Ignored,
+            },
+            _ if key.chars().count() == 1 => self.insert(key),
+            _ => return EditorKey::Ignored,
+        }
+        EditorKey::Handled
+    }
+}
+
 /// Cell editing functionality for DataGrid
 pub struct EditingState {
     pub is_editing: bool,
     pub editing_cell: Option<(usize, usize)>,
+    pub native: bool,               // Edit inside the grid instead of an external <input>
+    pub buffer: Option<TextBuffer>, // Native editor content while editing
+    pub original_value: Option<CellValue>, // Cell value when the edit started (None for ghost cells)
 }
 
 impl Default for EditingState {
@@ -12,6 +219,9 @@ impl Default for EditingState {
         Self {
             is_editing: false,
             editing_cell: None,
+            native: false,
+            buffer: None,
+            original_value: None,
         }
     }
 }
@@ -23,38 +233,71 @@ impl EditingState {
 
     /// Start editing a cell
     pub fn start_edit(&mut self, row: usize, col: usize, grid: &Grid) -> bool {
-        // Check if cell is valid
-        if row >= grid.row_count() || col >= grid.col_count() {
+        // Check if cell is valid (ghost row/column cells can be edited to add data)
+        if (row >= grid.row_count() || col >= grid.col_count()) && !grid.is_ghost_cell(row, col) {
             return false;
         }
 
-        // Check if column is editable
-        if !grid.is_column_editable(col) {
-            log::debug!("Column {} is read-only", col);
+        // Check the grid, column, cell and editable rules
+        if !grid.is_cell_editable(row, col) {
+            log::debug!("Cell ({}, {}) is read-only", row, col);
             return false;
         }
 
-        // Check if cell is editable
-        if let Some(cell) = grid.get_cell(row, col) {
-            if !cell.editable {
-                return false;
-            }
-        }
-
         self.is_editing = true;
         self.editing_cell = Some((row, col));
+        self.original_value = (!grid.is_ghost_cell(row, col)).then(|| grid.get_value(row, col));
+        if self.native {
+            let text = if grid.is_ghost_cell(row, col) { String::new() } else { grid.get_value(row, col).to_string() };
+            self.buffer = Some(TextBuffer::new(&text));
+        }
 
         log::debug!("Started editing cell: ({}, {})", row, col);
         true
     }
 
+    /// Start editing a cell with its content replaced by `text` (type-to-edit)
+    /// The grid keeps its value until the edit is committed
+    pub fn start_edit_replacing(&mut self, row: usize, col: usize, text: &str, grid: &Grid) -> bool {
+        if !self.start_edit(row, col, grid) {
+            return false;
+        }
+        if let Some(buffer) = self.buffer.as_mut() {
+            *buffer = TextBuffer::new(text);
+        }
+        true
+    }
+
     /// End editing mode
     pub fn end_edit(&mut self) {
         self.is_editing = false;
         self.editing_cell = None;
+        self.buffer = None;
+        self.original_value = None;
         log::debug!("Ended editing");
     }
 
+    /// End editing and put back the value the cell had when the edit started
+    /// (undoing values written while editing). Nothing is recorded for undo.
+    /// Returns the restored cell if its value had changed.
+    pub fn cancel_edit(&mut self, grid: &mut Grid) -> Option<(usize, usize)> {
+        let restored = match (self.editing_cell, self.original_value.take()) {
+            (Some((row, col)), Some(original)) if grid.get_value(row, col) != original => {
+                grid.set_value(row, col, original.clone());
+                // Back to the value before the first edit: no longer modified
+                if let Some(cell) = grid.get_cell_mut(row, col) {
+                    if cell.original.as_ref() == Some(&original) {
+                        cell.clear_modified();
+                    }
+                }
+                Some((row, col))
+            }
+            _ => None,
+        };
+        self.end_edit();
+        restored
+    }
+
     /// Check if currently editing
     pub fn is_editing(&self) -> bool {
         self.is_editing
@@ -78,7 +321,7 @@ impl EditingState {
     pub fn get_cell_edit_rect(&self, row: usize, col: usize, grid: &Grid, viewport: &Viewport) -> Vec<f32> {
         // Account for header offsets
         let x = grid.row_header_width + grid.col_x_position(col) - viewport.scroll_x;
-        let y = grid.col_header_height + grid.row_y_position(row) - viewport.scroll_y;
+        let y = grid.col_header_height + (grid.row_y_position(row) - viewport.scroll_y) as f32;
         let width = grid.col_width(col);
         let height = grid.row_height(row);
 
@@ -100,3 +343,72 @@ impl EditingState {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_draft() {
+        let mut grid = Grid::new(3, 2);
+        grid.column_configs[0].data_type = DataType::Number;
+        grid.set_column_validation(1, "^[A-Z]{3}$".to_string(), "Use a 3-letter code".to_string());
+        grid.set_column_unique(1, true);
+        grid.set_value(0, 1, CellValue::Text("ABC".to_string()));
+
+        assert_eq!(validate_draft(&grid, 1, 0, "12.5").0, DraftStatus::Ok);
+        assert_eq!(validate_draft(&grid, 1, 0, "12,5").0, DraftStatus::Warning);
+        assert_eq!(validate_draft(&grid, 1, 1, "abc"), (DraftStatus::Error, "Use a 3-letter code".to_string()));
+        assert_eq!(validate_draft(&grid, 1, 1, "ABC"), (DraftStatus::Warning, "Duplicates row 1".to_string()));
+        assert_eq!(validate_draft(&grid, 0, 1, "ABC").0, DraftStatus::Ok); // Its own value
+
+        grid.unique_strict = true;
+        assert_eq!(validate_draft(&grid, 1, 1, "ABC").0, DraftStatus::Error);
+        grid.readonly = true;
+        assert_eq!(validate_draft(&grid, 1, 0, "").0, DraftStatus::Error);
+    }
+
+    #[test]
+    fn test_text_buffer_keys() {
+        let mut buffer = TextBuffer::new("héllo");
+        assert_eq!((buffer.caret, buffer.has_selection()), (5, false));
+        buffer.handle_key("ArrowLeft", false, true);
+        buffer.handle_key("ArrowLeft", false, true);
+        assert_eq!(buffer.selected_text(), "lo");
+        buffer.handle_key("p", false, false);
+        assert_eq!((buffer.text.as_str(), buffer.caret), ("hélp", 4));
+
+        buffer.handle_key("Home", false, false);
+        buffer.handle_key("Delete", false, false);
+        buffer.handle_key("End", false, false);
+        buffer.handle_key("Backspace", false, false);
+        assert_eq!(buffer.text, "él");
+
+        buffer.insert("a\nb");
+        assert_eq!(buffer.text, "éla b");
+        buffer.handle_key("a", true, false);
+        assert_eq!(buffer.handle_key("x", true, false), EditorKey::Cut);
+        assert_eq!(buffer.handle_key("Enter", false, false), EditorKey::Commit);
+        assert_eq!(buffer.handle_key("Enter", true, false), EditorKey::CommitAll);
+        assert_eq!(buffer.handle_key("F5", false, false), EditorKey::Ignored);
+    }
+
+    #[test]
+    fn test_cancel_edit_restores_value() {
+        let mut grid = Grid::new(2, 1);
+        grid.set_value(0, 0, CellValue::Text("a".to_string()));
+        let mut editing = EditingState::new();
+
+        assert!(editing.start_edit(0, 0, &grid));
+        editing.update_cell_value(0, 0, "b".to_string(), &mut grid);
+        grid.mark_modified(0, 0, CellValue::Text("a".to_string()));
+        assert_eq!(editing.cancel_edit(&mut grid), Some((0, 0)));
+        assert_eq!(grid.get_value(0, 0), CellValue::Text("a".to_string()));
+        assert!(!grid.get_cell(0, 0).unwrap().modified);
+        assert!(!editing.is_editing());
+
+        // Nothing to restore when the value did not change
+        assert!(editing.start_edit(1, 0, &grid));
+        assert_eq!(editing.cancel_edit(&mut grid), None);
+    }
+}
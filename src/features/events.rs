@@ -0,0 +1,74 @@
+use serde_json::{json, Value};
+
+/// Maximum number of queued events kept when the host does not poll
+const MAX_QUEUED_EVENTS: usize = 1000;
+
+/// Queue of grid events waiting to be polled by the host
+///
+/// Events are plain JSON objects with a "type" field, e.g.
+/// `{"type": "column_data_changed", "col": 2, "ranges": [[0, 4], [10, 10]]}`
+#[derive(Default)]
+pub struct EventQueue {
+    events: Vec<Value>,
+}
+
+impl EventQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue an event, dropping the oldest events when the queue is full
+    pub fn push(&mut self, event_type: &str, mut detail: Value) {
+        if let Value::Object(obj) = &mut detail {
+            obj.insert("type".to_string(), Value::String(event_type.to_string()));
+        } else {
+            detail = json!({ "type": event_type, "value": detail });
+        }
+
+        if self.events.len() >= MAX_QUEUED_EVENTS {
+            self.events.remove(0);
+        }
+        self.events.push(detail);
+    }
+
+    /// Queue column_data_changed events for (col, inclusive row ranges) pairs
+    pub fn push_column_changes(&mut self, changes: Vec<(usize, Vec<(usize, usize)>)>) {
+        for (col, ranges) in changes {
+            let ranges: Vec<[usize; 2]> = ranges.into_iter().map(|(start, end)| [start, end]).collect();
+            self.push("column_data_changed", json!({ "col": col, "ranges": ranges }));
+        }
+    }
+
+    /// Check if there are queued events
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Number of queued events
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Remove all queued events and return them as a JSON array string
+    pub fn drain_json(&mut self) -> String {
+        let events = std::mem::take(&mut self.events);
+        serde_json::to_string(&events).unwrap_or_else(|_| "[]".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_change_events() {
+        let mut queue = EventQueue::new();
+        queue.push_column_changes(vec![(2, vec![(0, 4), (10, 10)])]);
+        assert!(!queue.is_empty());
+        assert_eq!(
+            queue.drain_json(),
+            r#"[{"col":2,"ranges":[[0,4],[10,10]],"type":"column_data_changed"}]"#
+        );
+        assert!(queue.is_empty());
+    }
+}
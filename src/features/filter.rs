@@ -0,0 +1,548 @@
+use crate::core::{CellValue, DataType, Grid};
+use crate::features::locale;
+use crate::GridError;
+use serde_json::{json, Value};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet};
+
+/// Comparison operator in a filter expression
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    fn matches(&self, ordering: Ordering) -> bool {
+        match self {
+            CompareOp::Eq => ordering == Ordering::Equal,
+            CompareOp::Ne => ordering != Ordering::Equal,
+            CompareOp::Lt => ordering == Ordering::Less,
+            CompareOp::Le => ordering != Ordering::Greater,
+            CompareOp::Gt => ordering == Ordering::Greater,
+            CompareOp::Ge => ordering != Ordering::Less,
+        }
+    }
+}
+
+/// Text function in a filter expression (case-insensitive)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextOp {
+    Contains,
+    StartsWith,
+    EndsWith,
+}
+
+/// Parsed filter expression
+#[derive(Clone, Debug, PartialEq)]
+pub enum FilterNode {
+    Or(Vec<FilterNode>),
+    And(Vec<FilterNode>),
+    Not(Box<FilterNode>),
+    Compare(CompareOp, String),
+    Text(TextOp, String),
+    Empty,
+}
+
+/// Filter expression on a column, kept with its source text so it can be shown and re-applied
+/// Syntax: comparisons (`> 100`, `<= 2024-03-31`, `= 'foo'`, `!= bar`), functions
+/// (`contains('x')`, `startswith('x')`, `endswith('x')`, `empty()`), combined with
+/// AND / OR / NOT and parentheses. A bare value means `= value`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilterExpression {
+    pub source: String,
+    pub node: FilterNode,
+}
+
+impl FilterExpression {
+    pub fn parse(source: &str) -> Result<Self, GridError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let node = parser.parse_or()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(syntax_error(&format!("unexpected {}", token.describe())));
+        }
+        Ok(Self { source: source.trim().to_string(), node })
+    }
+
+    /// Check if a value passes the filter; `data_type` is the column's type
+    pub fn matches(&self, value: &CellValue, data_type: &DataType) -> bool {
+        eval(&self.node, value, data_type)
+    }
+}
+
+/// Condition of a column filter
+#[derive(Clone, Debug, PartialEq)]
+pub enum FilterCondition {
+    Expression(FilterExpression),
+    Contains(String),  // Case-insensitive substring
+    NonEmpty,
+    Values(Vec<Value>), // Value list (as returned by get_column_unique_values)
+    Equals(CellValue),
+    NotEquals(CellValue),
+}
+
+impl FilterCondition {
+    pub fn matches(&self, value: &CellValue, data_type: &DataType) -> bool {
+        match self {
+            FilterCondition::Expression(expression) => expression.matches(value, data_type),
+            FilterCondition::Contains(text) => value.to_string().to_lowercase().contains(&text.to_lowercase()),
+            FilterCondition::NonEmpty => !value.is_empty(),
+            FilterCondition::Values(values) => values.iter().any(|v| value.matches_json(v)),
+            FilterCondition::Equals(target) => Grid::compare_values(value, target) == Ordering::Equal,
+            FilterCondition::NotEquals(target) => Grid::compare_values(value, target) != Ordering::Equal,
+        }
+    }
+
+    /// Parse {"expression": ...}, {"contains": ...}, {"values": [...]}, {"equals": ...},
+    /// {"not_equals": ...} or {"non_empty": true}
+    pub fn from_json(json: &Value) -> Result<Self, GridError> {
+        if let Some(expression) = json["expression"].as_str() {
+            Ok(FilterCondition::Expression(FilterExpression::parse(expression)?))
+        } else if let Some(text) = json["contains"].as_str() {
+            Ok(FilterCondition::Contains(text.to_string()))
+        } else if let Some(values) = json["values"].as_array() {
+            Ok(FilterCondition::Values(values.clone()))
+        } else if let Some(value) = json.get("equals") {
+            Ok(FilterCondition::Equals(CellValue::from_json(value)))
+        } else if let Some(value) = json.get("not_equals") {
+            Ok(FilterCondition::NotEquals(CellValue::from_json(value)))
+        } else if json["non_empty"].as_bool() == Some(true) {
+            Ok(FilterCondition::NonEmpty)
+        } else {
+            Err(GridError::OperationError {
+                message: locale::text("error.filter_condition"),
+            })
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        match self {
+            FilterCondition::Expression(expression) => json!({ "expression": expression.source }),
+            FilterCondition::Contains(text) => json!({ "contains": text }),
+            FilterCondition::NonEmpty => json!({ "non_empty": true }),
+            FilterCondition::Values(values) => json!({ "values": values }),
+            FilterCondition::Equals(value) => json!({ "equals": value.to_json() }),
+            FilterCondition::NotEquals(value) => json!({ "not_equals": value.to_json() }),
+        }
+    }
+}
+
+/// Active filter of one column
+/// Filters in the same OR group are combined with OR; groups and ungrouped filters with AND
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnFilter {
+    pub condition: FilterCondition,
+    pub group: Option<String>,
+}
+
+impl ColumnFilter {
+    /// Parse a condition (see FilterCondition::from_json) with an optional "group" name
+    pub fn from_json(json: &Value) -> Result<Self, GridError> {
+        let group = match &json["group"] {
+            Value::String(group) => Some(group.clone()),
+            Value::Number(group) => Some(group.to_string()),
+            _ => None,
+        };
+        Ok(Self { condition: FilterCondition::from_json(json)?, group })
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut json = self.condition.to_json();
+        json["group"] = json!(self.group);
+        json
+    }
+}
+
+/// Active filters, at most one per column
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ColumnFilters {
+    filters: BTreeMap<usize, ColumnFilter>,
+}
+
+impl ColumnFilters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a column's filter, replacing its previous one
+    pub fn set(&mut self, col: usize, filter: ColumnFilter) {
+        self.filters.insert(col, filter);
+    }
+
+    /// Set a column's condition, keeping its OR group
+    pub fn set_condition(&mut self, col: usize, condition: FilterCondition) {
+        let group = self.filters.get(&col).and_then(|f| f.group.clone());
+        self.filters.insert(col, ColumnFilter { condition, group });
+    }
+
+    pub fn remove(&mut self, col: usize) -> bool {
+        self.filters.remove(&col).is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.filters.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    pub fn get(&self, col: usize) -> Option<&ColumnFilter> {
+        self.filters.get(&col)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &ColumnFilter)> {
+        self.filters.iter().map(|(col, filter)| (*col, filter))
+    }
+
+    /// Check if a row passes the filters (columns outside the grid are ignored)
+    pub fn row_passes(&self, grid: &Grid, row: usize) -> bool {
+        let mut groups: BTreeMap<&str, bool> = BTreeMap::new();
+        for (&col, filter) in self.filters.iter().filter(|(&col, _)| col < grid.col_count()) {
+            let passes = filter.condition.matches(&grid.get_value(row, col), &grid.column_configs[col].data_type);
+            match &filter.group {
+                Some(group) => *groups.entry(group.as_str()).or_insert(false) |= passes,
+                None if !passes => return false,
+                None => {}
+            }
+        }
+        groups.values().all(|&passes| passes)
+    }
+
+    /// Rows failing the filters
+    pub fn hidden_rows(&self, grid: &Grid) -> HashSet<usize> {
+        (0..grid.row_count()).filter(|&row| !self.row_passes(grid, row)).collect()
+    }
+}
+
+fn syntax_error(message: &str) -> GridError {
+    GridError::OperationError { message: locale::format("error.filter_expression", &[("error", &message)]) }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Op(CompareOp),
+    Word(String),   // Unquoted value or function name
+    Quoted(String), // Quoted string
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::LParen => "'('".to_string(),
+            Token::RParen => "')'".to_string(),
+            Token::And => "AND".to_string(),
+            Token::Or => "OR".to_string(),
+            Token::Not => "NOT".to_string(),
+            Token::Op(_) => "operator".to_string(),
+            Token::Word(word) => format!("'{}'", word),
+            Token::Quoted(text) => format!("'{}'", text),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, GridError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '&' if next == Some('&') => {
+                tokens.push(Token::And);
+                i += 2;
+            }
+            '|' if next == Some('|') => {
+                tokens.push(Token::Or);
+                i += 2;
+            }
+            '!' if next == Some('=') => {
+                tokens.push(Token::Op(CompareOp::Ne));
+                i += 2;
+            }
+            '!' => {
+                tokens.push(Token::Not);
+                i += 1;
+            }
+            '<' | '>' | '=' => {
+                let (op, len) = match (c, next) {
+                    ('<', Some('=')) => (CompareOp::Le, 2),
+                    ('<', Some('>')) => (CompareOp::Ne, 2),
+                    ('<', _) => (CompareOp::Lt, 1),
+                    ('>', Some('=')) => (CompareOp::Ge, 2),
+                    ('>', _) => (CompareOp::Gt, 1),
+                    ('=', Some('=')) => (CompareOp::Eq, 2),
+                    _ => (CompareOp::Eq, 1),
+                };
+                tokens.push(Token::Op(op));
+                i += len;
+            }
+            '\'' | '"' => {
+                // Quoted string; a doubled quote stands for the quote itself
+                let mut text = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(syntax_error("unterminated string")),
+                        Some(&q) if q == c && chars.get(i + 1) == Some(&c) => {
+                            text.push(c);
+                            i += 2;
+                        }
+                        Some(&q) if q == c => {
+                            i += 1;
+                            break;
+                        }
+                        Some(&ch) => {
+                            text.push(ch);
+                            i += 1;
+                        }
+                    }
+                }
+                tokens.push(Token::Quoted(text));
+            }
+            _ => {
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() && !"()<>=!'\"".contains(chars[i]) {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(match word.to_uppercase().as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Word(word),
+                });
+            }
+        }
+    }
+
+    if tokens.is_empty() {
+        return Err(syntax_error("empty expression"));
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), GridError> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(syntax_error(&format!("expected {} but found {}", expected.describe(), token.describe()))),
+            None => Err(syntax_error(&format!("expected {} at end", expected.describe()))),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<FilterNode, GridError> {
+        let mut terms = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            terms.push(self.parse_and()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { FilterNode::Or(terms) })
+    }
+
+    fn parse_and(&mut self) -> Result<FilterNode, GridError> {
+        let mut factors = vec![self.parse_factor()?];
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            factors.push(self.parse_factor()?);
+        }
+        Ok(if factors.len() == 1 { factors.remove(0) } else { FilterNode::And(factors) })
+    }
+
+    fn parse_factor(&mut self) -> Result<FilterNode, GridError> {
+        match self.next() {
+            Some(Token::Not) => Ok(FilterNode::Not(Box::new(self.parse_factor()?))),
+            Some(Token::LParen) => {
+                let node = self.parse_or()?;
+                self.expect(Token::RParen)?;
+                Ok(node)
+            }
+            Some(Token::Op(op)) => Ok(FilterNode::Compare(op, self.parse_value()?)),
+            Some(Token::Word(word)) if self.peek() == Some(&Token::LParen) => {
+                self.pos += 1;
+                let node = match word.to_lowercase().as_str() {
+                    "contains" => FilterNode::Text(TextOp::Contains, self.parse_value()?),
+                    "startswith" => FilterNode::Text(TextOp::StartsWith, self.parse_value()?),
+                    "endswith" => FilterNode::Text(TextOp::EndsWith, self.parse_value()?),
+                    "empty" | "blank" => FilterNode::Empty,
+                    _ => return Err(syntax_error(&format!("unknown function '{}'", word))),
+                };
+                self.expect(Token::RParen)?;
+                Ok(node)
+            }
+            Some(Token::Word(word)) => Ok(FilterNode::Compare(CompareOp::Eq, word)),
+            Some(Token::Quoted(text)) => Ok(FilterNode::Compare(CompareOp::Eq, text)),
+            Some(token) => Err(syntax_error(&format!("unexpected {}", token.describe()))),
+            None => Err(syntax_error("unexpected end of expression")),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<String, GridError> {
+        match self.next() {
+            Some(Token::Word(word)) => Ok(word),
+            Some(Token::Quoted(text)) => Ok(text),
+            Some(token) => Err(syntax_error(&format!("expected a value but found {}", token.describe()))),
+            None => Err(syntax_error("expected a value at end")),
+        }
+    }
+}
+
+fn eval(node: &FilterNode, value: &CellValue, data_type: &DataType) -> bool {
+    match node {
+        FilterNode::Or(terms) => terms.iter().any(|t| eval(t, value, data_type)),
+        FilterNode::And(factors) => factors.iter().all(|f| eval(f, value, data_type)),
+        FilterNode::Not(inner) => !eval(inner, value, data_type),
+        FilterNode::Empty => value.is_empty() || value.to_string().trim().is_empty(),
+        FilterNode::Text(op, needle) => {
+            let text = value.to_string().to_lowercase();
+            let needle = needle.to_lowercase();
+            match op {
+                TextOp::Contains => text.contains(&needle),
+                TextOp::StartsWith => text.starts_with(&needle),
+                TextOp::EndsWith => text.ends_with(&needle),
+            }
+        }
+        FilterNode::Compare(op, literal) => match compare(value, literal, data_type) {
+            Some(ordering) => op.matches(ordering),
+            None => *op == CompareOp::Ne, // Incomparable (empty cell vs value): only != matches
+        },
+    }
+}
+
+/// Compare a cell value with a literal: numerically for numbers, by date for dates,
+/// otherwise as case-insensitive text. None if the cell is empty and the literal is not.
+fn compare(value: &CellValue, literal: &str, data_type: &DataType) -> Option<Ordering> {
+    let text = match value {
+        CellValue::Empty => return if literal.is_empty() { Some(Ordering::Equal) } else { None },
+        CellValue::Number(n) => {
+            if let Ok(m) = literal.trim().parse::<f64>() {
+                return n.partial_cmp(&m);
+            }
+            n.to_string()
+        }
+        CellValue::Boolean(b) => b.to_string(),
+        CellValue::Date(d) => d.clone(),
+        CellValue::Text(t) => t.clone(),
+    };
+
+    if matches!(value, CellValue::Date(_)) || *data_type == DataType::Date {
+        if let (Some(a), Some(b)) = (date_key(&text), date_key(literal)) {
+            return Some(a.cmp(&b));
+        }
+    }
+    if *data_type == DataType::Number {
+        if let (Ok(a), Ok(b)) = (text.trim().parse::<f64>(), literal.trim().parse::<f64>()) {
+            return a.partial_cmp(&b);
+        }
+    }
+    Some(text.to_lowercase().cmp(&literal.to_lowercase()))
+}
+
+/// Parse "YYYY-MM-DD" or "YYYY/MM/DD" (optionally followed by a time) into a sortable key
+pub(crate) fn date_key(text: &str) -> Option<(i32, u32, u32)> {
+    let date = text.trim().split(['T', ' ']).next()?;
+    let mut parts = date.split(['-', '/']);
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some((year, month, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(expression: &str, value: CellValue, data_type: DataType) -> bool {
+        FilterExpression::parse(expression).unwrap().matches(&value, &data_type)
+    }
+
+    #[test]
+    fn test_filter_expressions() {
+        let number = |n| CellValue::Number(n);
+        assert!(matches("> 100 AND <= 200", number(150.0), DataType::Number));
+        assert!(!matches("> 100 AND <= 200", number(250.0), DataType::Number));
+        assert!(matches("< 10 OR > 100", number(5.0), DataType::Number));
+        assert!(matches("> 9", CellValue::Text("10".to_string()), DataType::Number));
+        assert!(!matches("> 100", CellValue::Empty, DataType::Number));
+
+        let text = |s: &str| CellValue::Text(s.to_string());
+        assert!(matches("contains('foo') OR startswith('bar')", text("Barn"), DataType::Text));
+        assert!(!matches("contains('foo') OR startswith('bar')", text("crowbar"), DataType::Text));
+        assert!(matches("NOT (contains(x) OR empty())", text("abc"), DataType::Text));
+        assert!(matches("'it''s'", text("It's"), DataType::Text));
+
+        let date = |s: &str| CellValue::Date(s.to_string());
+        assert!(matches(">= 2024-01-01 AND < 2024/02/01", date("2024-01-31"), DataType::Date));
+        assert!(!matches("> 2024-1-5", date("2024-01-05"), DataType::Date));
+
+        assert!(FilterExpression::parse("> ").is_err());
+        assert!(FilterExpression::parse("(> 1").is_err());
+        assert!(FilterExpression::parse("between(1)").is_err());
+        assert!(FilterExpression::parse("> 1 2").is_err());
+    }
+
+    #[test]
+    fn test_column_filters_and_or_groups() {
+        let mut grid = Grid::new(4, 3);
+        for (row, (a, b, c)) in [("x", 1.0, "keep"), ("y", 2.0, "keep"), ("z", 3.0, "keep"), ("x", 4.0, "drop")].iter().enumerate() {
+            grid.set_value(row, 0, CellValue::Text(a.to_string()));
+            grid.set_value(row, 1, CellValue::Number(*b));
+            grid.set_value(row, 2, CellValue::Text(c.to_string()));
+        }
+
+        let mut filters = ColumnFilters::new();
+        filters.set(2, ColumnFilter::from_json(&json!({ "equals": "keep" })).unwrap());
+        filters.set(0, ColumnFilter::from_json(&json!({ "values": ["x"], "group": "g" })).unwrap());
+        filters.set(1, ColumnFilter::from_json(&json!({ "expression": ">= 3", "group": "g" })).unwrap());
+
+        // col2 = keep AND (col0 in [x] OR col1 >= 3)
+        assert_eq!(filters.hidden_rows(&grid), [1, 3].into_iter().collect());
+
+        filters.set_condition(0, FilterCondition::Contains("Y".to_string()));
+        assert_eq!(filters.get(0).unwrap().group.as_deref(), Some("g"));
+        assert_eq!(filters.hidden_rows(&grid), [0, 3].into_iter().collect());
+
+        filters.remove(2);
+        assert_eq!(filters.hidden_rows(&grid), [0].into_iter().collect());
+        assert!(ColumnFilter::from_json(&json!({ "group": "g" })).is_err());
+    }
+}
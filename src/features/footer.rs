@@ -0,0 +1,202 @@
+use crate::core::{CellValue, Grid, PreviewMode};
+use crate::features::locale;
+use crate::GridError;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Height of the aggregate footer band pinned to the bottom of the grid
+pub const FOOTER_BAND_HEIGHT: f32 = 26.0;
+
+/// Aggregate shown for a column in the footer band
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Aggregate {
+    Sum,
+    Avg,
+    Min,
+    Max,
+    Count,  // Non-empty values
+    Custom, // Total of the column's JS reducer (set_column_reducer)
+}
+
+impl Aggregate {
+    /// Parse aggregate name ("sum", "avg", "min", "max", "count", "custom")
+    pub fn parse(name: &str) -> Result<Self, GridError> {
+        match name.to_lowercase().as_str() {
+            "sum" => Ok(Aggregate::Sum),
+            "avg" | "average" | "mean" => Ok(Aggregate::Avg),
+            "min" => Ok(Aggregate::Min),
+            "max" => Ok(Aggregate::Max),
+            "count" => Ok(Aggregate::Count),
+            "custom" => Ok(Aggregate::Custom),
+            other => Err(GridError::unknown_value("aggregate", other, &["sum", "avg", "min", "max", "count", "custom"])),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Aggregate::Sum => "sum",
+            Aggregate::Avg => "avg",
+            Aggregate::Min => "min",
+            Aggregate::Max => "max",
+            Aggregate::Count => "count",
+            Aggregate::Custom => "custom",
+        }
+    }
+
+    /// Short label drawn in front of the value (localizable as "footer.<name>")
+    pub fn label(&self) -> String {
+        match self {
+            Aggregate::Custom => String::new(),
+            aggregate => locale::text(&format!("footer.{}", aggregate.as_str())),
+        }
+    }
+
+    /// Aggregate the visible (not filtered or preview-hidden) values of a column
+    /// Sum/avg/min/max use the numbers only; None if there are none (or for Custom)
+    pub fn compute(&self, grid: &Grid, col: usize) -> Option<f64> {
+        self.compute_values((0..grid.row_count()).filter(|&row| !grid.is_row_hidden(row)).map(|row| grid.get_value(row, col)))
+    }
+
+    /// Aggregate the visible rows of `value_col`, bucketed by the display value of `group_col`
+    /// Groups are returned in order of first appearance as (key, row count, aggregate)
+    pub fn group(&self, grid: &Grid, group_col: usize, value_col: usize) -> Vec<(String, usize, Option<f64>)> {
+        let mut keys: Vec<String> = Vec::new();
+        let mut buckets: HashMap<String, Vec<CellValue>> = HashMap::new();
+        for row in (0..grid.row_count()).filter(|&row| !grid.is_row_hidden(row)) {
+            let key = grid.get_value(row, group_col).to_string();
+            let bucket = buckets.entry(key.clone()).or_insert_with(|| {
+                keys.push(key);
+                Vec::new()
+            });
+            bucket.push(grid.get_value(row, value_col));
+        }
+
+        keys.into_iter()
+            .map(|key| {
+                let values = buckets.remove(&key).unwrap_or_default();
+                let rows = values.len();
+                (key, rows, self.compute_values(values.into_iter()))
+            })
+            .collect()
+    }
+
+    /// Aggregate a set of values (see compute)
+    pub fn compute_values(&self, values: impl Iterator<Item = CellValue>) -> Option<f64> {
+        if *self == Aggregate::Count {
+            return Some(values.filter(|v| !v.is_empty()).count() as f64);
+        }
+
+        let numbers: Vec<f64> = values
+            .filter_map(|v| match v {
+                CellValue::Number(n) if n.is_finite() => Some(n),
+                _ => None,
+            })
+            .collect();
+        if numbers.is_empty() {
+            return None;
+        }
+        match self {
+            Aggregate::Sum => Some(numbers.iter().sum()),
+            Aggregate::Avg => Some(numbers.iter().sum::<f64>() / numbers.len() as f64),
+            Aggregate::Min => numbers.iter().copied().reduce(f64::min),
+            Aggregate::Max => numbers.iter().copied().reduce(f64::max),
+            Aggregate::Count | Aggregate::Custom => None,
+        }
+    }
+}
+
+/// Per-column footer aggregates with lazily computed values, dropped whenever the
+/// data, the filtered rows or the preview sampling change
+#[derive(Default)]
+pub struct FooterAggregates {
+    aggregates: BTreeMap<usize, Aggregate>,
+    values: HashMap<usize, Option<f64>>,
+    revision: u64,
+    filtered_rows: HashSet<usize>,
+    preview_mode: Option<PreviewMode>,
+}
+
+impl FooterAggregates {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set (Some) or remove (None) a column's aggregate
+    pub fn set(&mut self, col: usize, aggregate: Option<Aggregate>) {
+        match aggregate {
+            Some(aggregate) => self.aggregates.insert(col, aggregate),
+            None => self.aggregates.remove(&col),
+        };
+        self.values.remove(&col);
+    }
+
+    /// Get a column's aggregate
+    pub fn get(&self, col: usize) -> Option<Aggregate> {
+        self.aggregates.get(&col).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.aggregates.is_empty()
+    }
+
+    /// Get (computing if needed) the value of a column's built-in aggregate
+    pub fn value(&mut self, grid: &Grid, col: usize) -> Option<f64> {
+        if self.is_stale(grid) {
+            self.values.clear();
+            self.revision = grid.revision();
+            self.filtered_rows = grid.filtered_rows().clone();
+            self.preview_mode = grid.preview_mode;
+        }
+        let aggregate = self.get(col)?;
+        *self.values.entry(col).or_insert_with(|| aggregate.compute(grid, col))
+    }
+
+    /// Check if cached values are out of date
+    pub fn is_stale(&self, grid: &Grid) -> bool {
+        self.revision != grid.revision()
+            || self.preview_mode != grid.preview_mode
+            || &self.filtered_rows != grid.filtered_rows()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_footer_aggregates_skip_hidden_rows() {
+        let mut grid = Grid::new(4, 1);
+        grid.set_value(0, 0, CellValue::Number(4.0));
+        grid.set_value(1, 0, CellValue::Text("n/a".to_string()));
+        grid.set_value(2, 0, CellValue::Number(2.0));
+        grid.set_value(3, 0, CellValue::Number(9.0));
+
+        let mut footer = FooterAggregates::new();
+        footer.set(0, Some(Aggregate::Sum));
+        assert_eq!(footer.value(&grid, 0), Some(15.0));
+
+        grid.set_filtered_rows([3].into_iter().collect());
+        assert_eq!(footer.value(&grid, 0), Some(6.0));
+
+        footer.set(0, Some(Aggregate::Count));
+        assert_eq!(footer.value(&grid, 0), Some(3.0));
+        footer.set(0, Some(Aggregate::Avg));
+        assert_eq!(footer.value(&grid, 0), Some(3.0));
+        footer.set(0, Some(Aggregate::Max));
+        grid.set_value(2, 0, CellValue::Number(7.0));
+        assert_eq!(footer.value(&grid, 0), Some(7.0));
+    }
+
+    #[test]
+    fn test_group_aggregate() {
+        let mut grid = Grid::new(5, 2);
+        for (row, (city, n)) in [("b", 1.0), ("a", 2.0), ("b", 3.0), ("a", 4.0), ("c", 5.0)].into_iter().enumerate() {
+            grid.set_value(row, 0, CellValue::Text(city.to_string()));
+            grid.set_value(row, 1, CellValue::Number(n));
+        }
+        grid.set_filtered_rows([4].into_iter().collect());
+
+        let groups = Aggregate::Sum.group(&grid, 0, 1);
+        assert_eq!(groups, vec![("b".to_string(), 2, Some(4.0)), ("a".to_string(), 2, Some(6.0))]);
+        assert_eq!(Aggregate::Max.group(&grid, 0, 1)[1].2, Some(4.0));
+    }
+}
@@ -0,0 +1,93 @@
+use crate::GridError;
+use js_sys::Function;
+use serde_json::Value;
+use std::collections::HashMap;
+use wasm_bindgen::JsValue;
+
+/// Hook names accepted by set_operation_hook
+const HOOK_NAMES: [&str; 6] = ["before_sort", "after_sort", "before_filter", "after_filter", "before_edit", "before_change"];
+
+/// What a before_* hook decided
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HookDecision {
+    Proceed, // Run the operation as usual
+    Cancel,  // Skip the operation entirely (hook returned false)
+    Handled, // Host performs the operation (e.g. server-side): record the sort/filter state only
+}
+
+/// Synchronous JS callbacks around sort, filter and edit operations
+/// Sort/filter hooks receive the operation detail as a plain object; before_* hooks return
+/// false to cancel or "handled" to take the operation over. Edit hooks get positional
+/// arguments and can only veto (see `allows`)
+#[derive(Default)]
+pub struct OperationHooks {
+    hooks: HashMap<&'static str, Function>,
+}
+
+impl OperationHooks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (Some) or remove (None) a hook
+    pub fn set(&mut self, name: &str, callback: Option<Function>) -> Result<(), GridError> {
+        let Some(&name) = HOOK_NAMES.iter().find(|n| **n == name) else {
+            return Err(GridError::unknown_value("hook", name, &HOOK_NAMES));
+        };
+        match callback {
+            Some(callback) => self.hooks.insert(name, callback),
+            None => self.hooks.remove(name),
+        };
+        Ok(())
+    }
+
+    /// Call a hook with the operation detail and interpret its return value
+    /// A hook that throws is logged and treated as Proceed
+    pub fn call(&self, name: &str, detail: &Value) -> HookDecision {
+        let Some(callback) = self.hooks.get(name) else {
+            return HookDecision::Proceed;
+        };
+        let arg = js_sys::JSON::parse(&detail.to_string()).unwrap_or(JsValue::NULL);
+        match callback.call1(&JsValue::NULL, &arg) {
+            Ok(result) if result == JsValue::FALSE => HookDecision::Cancel,
+            Ok(result) if result.as_string().as_deref() == Some("handled") => HookDecision::Handled,
+            Ok(_) => HookDecision::Proceed,
+            Err(e) => {
+                log::error!("{} hook failed: {:?}", name, e);
+                HookDecision::Proceed
+            }
+        }
+    }
+
+    /// Ask the before_change hook whether (row, col) may take `new_value`
+    pub fn allows_change(&self, row: usize, col: usize, new_value: &str) -> bool {
+        self.allows(
+            "before_change",
+            &[JsValue::from(row as u32), JsValue::from(col as u32), JsValue::from_str(new_value)],
+        )
+    }
+
+    /// Call a veto hook (before_edit, before_change) with positional arguments
+    /// Returns false only if the hook returned false; a hook that throws is logged and allows
+    pub fn allows(&self, name: &str, args: &[JsValue]) -> bool {
+        let Some(callback) = self.hooks.get(name) else {
+            return true;
+        };
+        let args: js_sys::Array = args.iter().collect();
+        match callback.apply(&JsValue::NULL, &args) {
+            Ok(result) => result != JsValue::FALSE,
+            Err(e) => {
+                log::error!("{} hook failed: {:?}", name, e);
+                true
+            }
+        }
+    }
+}
+
+/// Current time in milliseconds for operation timings (0 outside a browser)
+pub fn now_ms() -> f64 {
+    web_sys::window()
+        .and_then(|w| w.performance())
+        .map(|p| p.now())
+        .unwrap_or(0.0)
+}
@@ -0,0 +1,405 @@
+use crate::features::clipboard::{ClipboardOps, PastedCell};
+use std::collections::HashMap;
+
+/// Style properties collected from `style` attributes, `<style>` class rules and tags
+#[derive(Clone, Copy, Debug, Default)]
+struct HtmlStyle {
+    bold: Option<bool>,
+    italic: Option<bool>,
+    bg_color: Option<u32>,
+    fg_color: Option<u32>,
+}
+
+impl HtmlStyle {
+    /// Parse CSS declarations ("font-weight:700; background:#FFFF00")
+    fn parse(css: &str) -> Self {
+        let mut style = Self::default();
+        for declaration in css.split(';') {
+            let Some((name, value)) = declaration.split_once(':') else { continue };
+            let value = value.trim().trim_end_matches("!important").trim();
+            match name.trim().to_lowercase().as_str() {
+                "font-weight" => {
+                    style.bold = Some(value == "bold" || value == "bolder" || value.parse::<u32>().is_ok_and(|w| w >= 600));
+                }
+                "font-style" => style.italic = Some(value == "italic" || value == "oblique"),
+                "background" | "background-color" => {
+                    style.bg_color = value.split_whitespace().find_map(parse_css_color).or(style.bg_color);
+                }
+                "color" => style.fg_color = parse_css_color(value).or(style.fg_color),
+                _ => {}
+            }
+        }
+        style
+    }
+
+    /// Overlay `other` on top of this style (later declarations win)
+    fn merge(&mut self, other: &HtmlStyle) {
+        self.bold = other.bold.or(self.bold);
+        self.italic = other.italic.or(self.italic);
+        self.bg_color = other.bg_color.or(self.bg_color);
+        self.fg_color = other.fg_color.or(self.fg_color);
+    }
+}
+
+/// Cell being read while walking the HTML
+struct OpenCell {
+    text: String,
+    style: HtmlStyle,
+    colspan: usize,
+    rowspan: usize,
+}
+
+/// Parse the first `<table>` in clipboard HTML into a rectangular block of cells
+/// Cells covered by colspan/rowspan are returned empty so the layout matches the source
+/// Returns None if the HTML contains no table
+pub fn parse_html_table(html: &str) -> Option<Vec<Vec<PastedCell>>> {
+    let lower = html.to_ascii_lowercase();
+    let table_start = lower.find("<table")?;
+    let classes = parse_class_rules(html, &lower);
+
+    // Rows of (cell, colspan, rowspan) in source order
+    let mut rows: Vec<Vec<(PastedCell, usize, usize)>> = Vec::new();
+    let mut cell: Option<OpenCell> = None;
+    let mut pos = table_start;
+
+    while pos < html.len() {
+        let Some(offset) = html[pos..].find('<') else {
+            if let Some(cell) = cell.as_mut() {
+                cell.text.push_str(&html[pos..]);
+            }
+            break;
+        };
+        if let Some(cell) = cell.as_mut() {
+            cell.text.push_str(&html[pos..pos + offset]);
+        }
+        pos += offset;
+
+        // Comments (Excel wraps conditional markup in them)
+        if html[pos..].starts_with("<!--") {
+            pos = html[pos..].find("-->").map_or(html.len(), |end| pos + end + 3);
+            continue;
+        }
+
+        let end = html[pos..].find('>').map_or(html.len(), |end| pos + end);
+        let tag = &html[pos + 1..end];
+        pos = (end + 1).min(html.len());
+
+        let closing = tag.starts_with('/');
+        let tag = tag.trim_start_matches('/');
+        let name_len = tag.find(|c: char| c.is_whitespace() || c == '/').unwrap_or(tag.len());
+        let name = tag[..name_len].to_ascii_lowercase();
+        let attrs = parse_attributes(&tag[name_len..]);
+
+        match (name.as_str(), closing) {
+            ("table", true) => break,
+            ("tr", false) => {
+                finish_cell(&mut cell, &mut rows);
+                rows.push(Vec::new());
+            }
+            ("td" | "th", false) => {
+                finish_cell(&mut cell, &mut rows);
+                let mut style = HtmlStyle::default();
+                if name == "th" {
+                    style.bold = Some(true);
+                }
+                if let Some(color) = attrs.get("bgcolor").and_then(|c| parse_css_color(c)) {
+                    style.bg_color = Some(color);
+                }
+                for class in attrs.get("class").map(|c| c.split_whitespace()).into_iter().flatten() {
+                    if let Some(class_style) = classes.get(class) {
+                        style.merge(class_style);
+                    }
+                }
+                if let Some(css) = attrs.get("style") {
+                    style.merge(&HtmlStyle::parse(css));
+                }
+                let span = |name: &str| attrs.get(name).and_then(|v| v.trim().parse::<usize>().ok()).unwrap_or(1).max(1);
+                cell = Some(OpenCell { text: String::new(), style, colspan: span("colspan"), rowspan: span("rowspan") });
+            }
+            ("td" | "th" | "tr", true) => finish_cell(&mut cell, &mut rows),
+            ("br", _) => {
+                if let Some(cell) = cell.as_mut() {
+                    cell.text.push('\n');
+                }
+            }
+            ("b" | "strong", false) => {
+                if let Some(cell) = cell.as_mut() {
+                    cell.style.bold = Some(true);
+                }
+            }
+            ("i" | "em", false) => {
+                if let Some(cell) = cell.as_mut() {
+                    cell.style.italic = Some(true);
+                }
+            }
+            ("font", false) => {
+                if let (Some(cell), Some(color)) = (cell.as_mut(), attrs.get("color").and_then(|c| parse_css_color(c))) {
+                    cell.style.fg_color = Some(color);
+                }
+            }
+            ("span" | "div" | "p", false) => {
+                if let (Some(cell), Some(css)) = (cell.as_mut(), attrs.get("style")) {
+                    cell.style.merge(&HtmlStyle::parse(css));
+                }
+            }
+            _ => {}
+        }
+    }
+    finish_cell(&mut cell, &mut rows);
+
+    // Lay the cells out on a grid, skipping positions covered by earlier spans
+    let mut layout: Vec<Vec<Option<PastedCell>>> = Vec::new();
+    for (row, cells) in rows.iter().enumerate() {
+        if layout.len() <= row {
+            layout.resize_with(row + 1, Vec::new);
+        }
+        let mut col = 0;
+        for (cell, colspan, rowspan) in cells {
+            while layout[row].get(col).is_some_and(|c| c.is_some()) {
+                col += 1;
+            }
+            for r in row..row + rowspan {
+                if layout.len() <= r {
+                    layout.resize_with(r + 1, Vec::new);
+                }
+                for c in col..col + colspan {
+                    if layout[r].len() <= c {
+                        layout[r].resize(c + 1, None);
+                    }
+                    layout[r][c] = Some(if r == row && c == col { cell.clone() } else { PastedCell::default() });
+                }
+            }
+            col += colspan;
+        }
+    }
+
+    let width = layout.iter().map(|row| row.len()).max().unwrap_or(0);
+    if width == 0 {
+        return None;
+    }
+    Some(
+        layout
+            .into_iter()
+            .map(|row| {
+                let mut row: Vec<PastedCell> = row.into_iter().map(Option::unwrap_or_default).collect();
+                row.resize(width, PastedCell::default());
+                row
+            })
+            .collect(),
+    )
+}
+
+/// Close the open cell (if any) and append it to the current row
+fn finish_cell(cell: &mut Option<OpenCell>, rows: &mut Vec<Vec<(PastedCell, usize, usize)>>) {
+    let Some(open) = cell.take() else { return };
+    if rows.is_empty() {
+        rows.push(Vec::new()); // <td> without a <tr>
+    }
+
+    let text = collapse_whitespace(&decode_entities(&open.text));
+    let parsed = PastedCell {
+        value: ClipboardOps::parse_value(&text),
+        bold: open.style.bold.unwrap_or(false),
+        italic: open.style.italic.unwrap_or(false),
+        bg_color: open.style.bg_color,
+        fg_color: open.style.fg_color,
+    };
+    rows.last_mut().unwrap().push((parsed, open.colspan, open.rowspan));
+}
+
+/// Collect `.class { ... }` rules from `<style>` blocks (Excel styles cells via classes)
+fn parse_class_rules(html: &str, lower: &str) -> HashMap<String, HtmlStyle> {
+    let mut classes = HashMap::new();
+    let mut pos = 0;
+
+    while let Some(start) = lower[pos..].find("<style") {
+        let body_start = lower[pos + start..].find('>').map_or(html.len(), |e| pos + start + e + 1);
+        let body_end = lower[body_start..].find("</style").map_or(html.len(), |e| body_start + e);
+        let css = html[body_start..body_end].replace("<!--", "").replace("-->", "");
+
+        for rule in css.split('}') {
+            let Some((selectors, body)) = rule.split_once('{') else { continue };
+            let style = HtmlStyle::parse(body);
+            for selector in selectors.split(',') {
+                if let Some(class) = selector.trim().strip_prefix('.') {
+                    classes.entry(class.to_string()).or_insert_with(HtmlStyle::default).merge(&style);
+                }
+            }
+        }
+        pos = body_end;
+    }
+
+    classes
+}
+
+/// Parse tag attributes (`name=value`, `name="value"`, `name='value'`) with lower-case names
+fn parse_attributes(text: &str) -> HashMap<String, String> {
+    let mut attrs = HashMap::new();
+    let mut rest = text.trim_start();
+
+    while !rest.is_empty() {
+        let name_end = rest.find(|c: char| c.is_whitespace() || c == '=' || c == '/').unwrap_or(rest.len());
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let mut value = String::new();
+        if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            let quote = after_eq.chars().next().filter(|c| *c == '"' || *c == '\'');
+            let (raw, remaining) = match quote {
+                Some(q) => {
+                    let body = &after_eq[1..];
+                    let end = body.find(q).unwrap_or(body.len());
+                    (&body[..end], body.get(end + 1..).unwrap_or(""))
+                }
+                None => {
+                    let end = after_eq.find(char::is_whitespace).unwrap_or(after_eq.len());
+                    (&after_eq[..end], &after_eq[end..])
+                }
+            };
+            value = decode_entities(raw);
+            rest = remaining.trim_start();
+        } else if name.is_empty() {
+            rest = rest.get(1..).unwrap_or("").trim_start(); // Stray '/'
+            continue;
+        }
+
+        if !name.is_empty() {
+            attrs.insert(name, value);
+        }
+    }
+
+    attrs
+}
+
+/// Decode the HTML character references that appear in spreadsheet output
+fn decode_entities(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(amp) = rest.find('&') {
+        result.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let ch = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse::<u32>().ok()))
+                    .and_then(char::from_u32),
+            };
+            ch.map(|ch| (ch, end))
+        });
+
+        match decoded {
+            Some((ch, end)) => {
+                result.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+
+    result
+}
+
+/// Collapse source whitespace the way a browser renders it, keeping `<br>` line breaks
+fn collapse_whitespace(text: &str) -> String {
+    text.split('\n')
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Parse a CSS color ("#RGB", "#RRGGBB", "rgb(r, g, b)", "rgba(r, g, b, a)", a few names)
+/// Returns RGBA as u32 (0xRRGGBBAA)
+fn parse_css_color(value: &str) -> Option<u32> {
+    let value = value.trim().to_lowercase();
+
+    if let Some(hex) = value.strip_prefix('#') {
+        let hex = match hex.len() {
+            3 => hex.chars().flat_map(|c| [c, c]).collect::<String>(),
+            6 => hex.to_string(),
+            _ => return None,
+        };
+        return u32::from_str_radix(&hex, 16).ok().map(|rgb| (rgb << 8) | 0xFF);
+    }
+
+    if let Some(args) = value.strip_prefix("rgba(").or_else(|| value.strip_prefix("rgb(")) {
+        let parts: Vec<f32> = args.trim_end_matches(')').split(',').filter_map(|p| p.trim().parse().ok()).collect();
+        if parts.len() < 3 {
+            return None;
+        }
+        let alpha = parts.get(3).copied().unwrap_or(1.0);
+        if alpha == 0.0 {
+            return None; // Transparent backgrounds carry no style
+        }
+        let [r, g, b] = [parts[0], parts[1], parts[2]].map(|c| c.clamp(0.0, 255.0) as u32);
+        let a = (alpha.clamp(0.0, 1.0) * 255.0).round() as u32;
+        return Some((r << 24) | (g << 16) | (b << 8) | a);
+    }
+
+    match value.as_str() {
+        "black" => Some(0x000000FF),
+        "white" => Some(0xFFFFFFFF),
+        "red" => Some(0xFF0000FF),
+        "green" => Some(0x008000FF),
+        "blue" => Some(0x0000FFFF),
+        "yellow" => Some(0xFFFF00FF),
+        "gray" | "grey" => Some(0x808080FF),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::cell::CellValue;
+
+    #[test]
+    fn test_parse_excel_table() {
+        let html = r#"<html><head><style><!--
+            .xl65 {font-weight:700; background:#FFFF00;}
+        --></style></head><body><table>
+            <tr><td class=xl65>Name</td><td colspan=2>Total &amp; tax</td></tr>
+            <tr><td rowspan="2" style="color:rgb(255, 0, 0)">a<br>b</td><td>1.5</td><td><b>x</b></td></tr>
+            <tr><td>2</td></tr>
+        </table></body></html>"#;
+
+        let cells = parse_html_table(html).unwrap();
+        assert_eq!(cells.len(), 3);
+        assert!(cells.iter().all(|row| row.len() == 3));
+
+        assert_eq!(cells[0][0].value, CellValue::Text("Name".to_string()));
+        assert!(cells[0][0].bold);
+        assert_eq!(cells[0][0].bg_color, Some(0xFFFF00FF));
+        assert_eq!(cells[0][1].value, CellValue::Text("Total & tax".to_string()));
+        assert_eq!(cells[0][2], PastedCell::default()); // Covered by colspan
+
+        assert_eq!(cells[1][0].value, CellValue::Text("a\nb".to_string()));
+        assert_eq!(cells[1][0].fg_color, Some(0xFF0000FF));
+        assert_eq!(cells[1][1].value, CellValue::Number(1.5));
+        assert!(cells[1][2].bold);
+
+        // Rowspan pushes the next row's first cell to the right
+        assert_eq!(cells[2][0], PastedCell::default());
+        assert_eq!(cells[2][1].value, CellValue::Number(2.0));
+
+        assert!(parse_html_table("<p>no table</p>").is_none());
+    }
+}
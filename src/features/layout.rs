@@ -0,0 +1,213 @@
+use crate::core::{Grid, WidthMode};
+use crate::features::locale;
+use crate::GridError;
+use serde_json::{json, Value};
+
+/// Version written by `Layout::to_json`
+const LAYOUT_VERSION: u64 = 1;
+
+/// A column's saved width, flex weight and visibility, keyed by its internal name
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnLayout {
+    pub name: String,
+    pub width: f32,
+    pub flex: Option<f32>, // Flex weight of a flex column (None: fixed width)
+    pub visible: bool,
+}
+
+/// User layout preferences (get_layout_json / apply_layout_json): column order, widths
+/// and visibility by internal name, row heights by row ID, frozen panes and sort keys.
+/// It holds no data; the column settings carry over to a different dataset, the row
+/// heights only to the same rows loaded in the same order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layout {
+    pub columns: Vec<ColumnLayout>,     // In display order
+    pub row_heights: Vec<(usize, f32)>, // (row ID, height) of rows with a non-default height
+    pub frozen_rows: usize,
+    pub frozen_cols: usize,
+    pub sort: Vec<(String, bool)>, // (internal name, ascending), by priority
+}
+
+impl Layout {
+    /// Capture the grid's current layout
+    pub fn capture(grid: &Grid) -> Self {
+        let name = |col: usize| grid.get_column_config(col).map_or_else(|| format!("col_{}", col), |c| c.internal_name.clone());
+        let default_height = grid.default_row_height();
+        let flex = |col: usize| {
+            grid.get_column_config(col).filter(|config| config.width_mode == WidthMode::Flex).map(|config| config.flex)
+        };
+        Self {
+            columns: (0..grid.col_count())
+                .map(|col| ColumnLayout {
+                    name: name(col),
+                    width: grid.base_col_width(col),
+                    flex: flex(col),
+                    visible: !grid.is_col_hidden(col),
+                })
+                .collect(),
+            row_heights: (0..grid.row_count())
+                .filter(|&row| grid.base_row_height(row) != default_height)
+                .filter_map(|row| Some((grid.row_id(row)?, grid.base_row_height(row))))
+                .collect(),
+            frozen_rows: grid.frozen_rows,
+            frozen_cols: grid.frozen_cols,
+            sort: grid.active_sort_keys().into_iter().map(|(col, ascending)| (name(col), ascending)).collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "version": LAYOUT_VERSION,
+            "columns": self.columns.iter()
+                .map(|c| json!({ "name": c.name, "width": c.width, "flex": c.flex, "visible": c.visible }))
+                .collect::<Vec<_>>(),
+            "row_heights": self.row_heights.iter().map(|&(row, height)| json!([row, height])).collect::<Vec<_>>(),
+            "frozen_rows": self.frozen_rows,
+            "frozen_cols": self.frozen_cols,
+            "sort": self.sort.iter().map(|(name, ascending)| json!([name, ascending])).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Parse a layout produced by `to_json`; missing parts are empty
+    pub fn from_json(value: &Value) -> Result<Self, GridError> {
+        let version = value["version"].as_u64().unwrap_or(0);
+        if version == 0 || version > LAYOUT_VERSION {
+            return Err(GridError::OperationError {
+                message: locale::format("error.layout_version", &[("version", &value["version"])]),
+            });
+        }
+        let columns = value["columns"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|c| {
+                Some(ColumnLayout {
+                    name: c["name"].as_str()?.to_string(),
+                    width: c["width"].as_f64()? as f32,
+                    flex: c["flex"].as_f64().and_then(|weight| WidthMode::flex_weight(weight).ok()),
+                    visible: c["visible"].as_bool().unwrap_or(true),
+                })
+            })
+            .collect();
+        let row_heights = value["row_heights"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| Some((entry[0].as_u64()? as usize, entry[1].as_f64()? as f32)))
+            .collect();
+        let sort = value["sort"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| Some((entry[0].as_str()?.to_string(), entry[1].as_bool().unwrap_or(true))))
+            .collect();
+        Ok(Self {
+            columns,
+            row_heights,
+            frozen_rows: value["frozen_rows"].as_u64().unwrap_or(0) as usize,
+            frozen_cols: value["frozen_cols"].as_u64().unwrap_or(0) as usize,
+            sort,
+        })
+    }
+
+    /// Current column at each position once the layout's order is applied: the saved
+    /// columns found in the grid fill the positions they occupy now, in saved order;
+    /// columns the layout does not know keep their positions
+    pub fn column_order(&self, grid: &Grid) -> Vec<usize> {
+        let mut used = vec![false; grid.col_count()];
+        let mut matched = Vec::new();
+        for saved in &self.columns {
+            if let Some(col) = (0..grid.col_count()).find(|&col| !used[col] && internal_name(grid, col) == Some(&saved.name)) {
+                used[col] = true;
+                matched.push(col);
+            }
+        }
+
+        let mut order: Vec<usize> = (0..grid.col_count()).collect();
+        let mut slots: Vec<usize> = matched.clone();
+        slots.sort_unstable();
+        for (slot, col) in slots.into_iter().zip(matched) {
+            order[slot] = col;
+        }
+        order
+    }
+
+    /// Saved settings of the column at `col` (first unused entry with its internal name)
+    pub fn column(&self, grid: &Grid, col: usize) -> Option<&ColumnLayout> {
+        let name = internal_name(grid, col)?;
+        let nth = (0..col).filter(|&c| internal_name(grid, c) == Some(name)).count();
+        self.columns.iter().filter(|c| &c.name == name).nth(nth)
+    }
+
+    /// Saved height of the row at `row` (None: default height)
+    pub fn row_height(&self, grid: &Grid, row: usize) -> Option<f32> {
+        let id = grid.row_id(row)?;
+        self.row_heights.iter().find(|&&(saved, _)| saved == id).map(|&(_, height)| height)
+    }
+
+    /// Sort keys as (col, ascending) for the grid's current columns (unknown names dropped)
+    pub fn sort_keys(&self, grid: &Grid) -> Vec<(usize, bool)> {
+        self.sort
+            .iter()
+            .filter_map(|(name, ascending)| {
+                (0..grid.col_count()).find(|&col| internal_name(grid, col) == Some(name)).map(|col| (col, *ascending))
+            })
+            .collect()
+    }
+}
+
+fn internal_name(grid: &Grid, col: usize) -> Option<&String> {
+    grid.get_column_config(col).map(|config| &config.internal_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::CellValue;
+
+    #[test]
+    fn test_layout_by_internal_name() {
+        let mut grid = Grid::new(3, 4);
+        for (col, name) in ["id", "name", "note", "price"].iter().enumerate() {
+            grid.column_configs[col].internal_name = name.to_string();
+        }
+        grid.set_col_width(1, 180.0);
+        grid.set_col_visible(2, false);
+        grid.column_configs[3].width_mode = WidthMode::Flex;
+        grid.column_configs[3].flex = 2.0;
+        grid.set_row_height(1, 40.0);
+        for (row, n) in [3.0, 1.0, 2.0].into_iter().enumerate() {
+            grid.set_value(row, 0, CellValue::Number(n));
+        }
+        grid.frozen_cols = 1;
+        grid.sort_by_column(3, false);
+
+        let layout = Layout::capture(&grid);
+        assert_eq!(Layout::from_json(&layout.to_json()).unwrap(), layout);
+        assert_eq!(layout.sort, vec![("price".to_string(), false)]);
+        assert_eq!(layout.columns[3].flex, Some(2.0));
+
+        // Row heights follow the row through the sort
+        let tall = grid.row_id(1).unwrap();
+        assert_eq!(layout.row_heights, vec![(tall, 40.0)]);
+        grid.sort_by_column(0, true);
+        assert_eq!(grid.row_index_by_id(tall), Some(0));
+        assert_eq!(layout.row_height(&grid, 0), Some(40.0));
+        assert_eq!(layout.row_height(&grid, 1), None);
+
+        // Another grid with the columns reordered and an extra one the layout does not know
+        let mut other = Grid::new(3, 5);
+        for (col, name) in ["price", "extra", "note", "name", "id"].iter().enumerate() {
+            other.column_configs[col].internal_name = name.to_string();
+        }
+        // Saved columns fill positions 0, 2, 3, 4 in saved order; "extra" stays at 1
+        assert_eq!(layout.column_order(&other), vec![4, 1, 3, 2, 0]);
+        assert_eq!(layout.column(&other, 3).unwrap().width, 180.0);
+        assert!(!layout.column(&other, 2).unwrap().visible);
+        assert!(layout.column(&other, 1).is_none());
+        assert_eq!(layout.column(&other, 0).unwrap().flex, Some(2.0));
+        assert_eq!(layout.sort_keys(&other), vec![(0, false)]);
+
+        assert!(Layout::from_json(&json!({ "version": 9 })).is_err());
+    }
+}
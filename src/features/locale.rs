@@ -0,0 +1,240 @@
+use crate::GridError;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Built-in user-visible strings (key, English default)
+/// Placeholders in braces are filled in by `format`
+const DEFAULT_STRINGS: &[(&str, &str)] = &[
+    // Canvas text
+    ("ghost_row", "+ Add row"),
+    ("pivot.grand_total", "Grand Total"),
+    ("pivot.blank", "(blank)"),
+    ("footer.sum", "Σ"),
+    ("footer.avg", "Avg"),
+    ("footer.min", "Min"),
+    ("footer.max", "Max"),
+    ("footer.count", "Count"),
+    // Context menu operations (get_operation_label)
+    ("operation.insert_row_above", "Insert row above"),
+    ("operation.insert_row_below", "Insert row below"),
+    ("operation.delete_row", "Delete row"),
+    ("operation.copy_row", "Copy row"),
+    ("operation.cut_row", "Cut row"),
+    ("operation.move_row_up", "Move row up"),
+    ("operation.move_row_down", "Move row down"),
+    ("operation.sort_ascending", "Sort ascending"),
+    ("operation.sort_descending", "Sort descending"),
+    ("operation.clear_sort", "Clear sort"),
+    ("operation.clear_filter", "Clear filter"),
+    ("operation.hide_column", "Hide column"),
+    ("operation.show_all_columns", "Show all columns"),
+    ("operation.auto_fit_column", "Auto-fit width"),
+    ("operation.insert_column_left", "Insert column left"),
+    ("operation.insert_column_right", "Insert column right"),
+    ("operation.delete_column", "Delete column"),
+    ("operation.move_column_left", "Move column left"),
+    ("operation.move_column_right", "Move column right"),
+    // Error messages
+    ("error.out_of_bounds", "Cell ({row}, {col}) is out of bounds"),
+    ("error.row_out_of_bounds", "Row {row} is out of range"),
+    ("error.col_out_of_bounds", "Column {col} is out of range"),
+    ("error.not_editable", "Cell ({row}, {col}) is not editable"),
+    ("error.change_rejected", "The change to cell ({row}, {col}) was rejected"),
+    ("error.invalid_regex", "Invalid regex pattern '{pattern}': {error}"),
+    ("error.paste_failed", "Paste operation failed: {reason}"),
+    ("error.invalid_json", "Invalid JSON data: {error}"),
+    ("error.render_init_failed", "Renderer initialization failed: {error}"),
+    ("error.shader", "Shader error: {error}"),
+    ("error.version_conflict", "Data changed since version {expected} (now {current})"),
+    ("error.delete_last_row", "Cannot delete the last row"),
+    ("error.move_first_row_up", "Cannot move first row up"),
+    ("error.move_last_row_down", "Cannot move last row down"),
+    ("error.delete_last_column", "Cannot delete the last column"),
+    ("error.move_first_column_left", "Cannot move first column left"),
+    ("error.move_last_column_right", "Cannot move last column right"),
+    ("error.unknown_operation", "Unknown operation: {operation}"),
+    ("error.not_rectangular", "Select a single rectangular range"),
+    ("error.transpose_no_room", "The transposed range does not fit in the grid"),
+    ("error.transpose_overwrite", "Transposing would overwrite cell ({row}, {col})"),
+    ("error.nothing_cut", "No cut cells to insert"),
+    ("error.shift_no_room", "Not enough room to shift cell ({row}, {col})"),
+    ("error.grid_busy", "The grid is busy: a callback cannot call back into it"),
+    ("error.pivot_read_only", "The pivot view is read-only; call clear_pivot() first"),
+    ("error.unknown_value", "Unknown {name} '{value}' (expected one of: {expected})"),
+    ("error.unknown_option", "Unknown option '{key}'"),
+    ("error.option_at_creation", "Option '{key}' can only be set when the grid is created"),
+    ("error.option_type", "Option '{key}' must be a {type}"),
+    ("error.options_object", "Options must be a JSON object"),
+    ("error.hover_style", "Hover style must be a JSON object"),
+    ("error.locale_object", "Locale strings must be a JSON object"),
+    ("error.locale_key", "Unknown locale string key '{key}'"),
+    ("error.locale_value", "Locale string '{key}' must be a string or null"),
+    ("error.cell_number", "Cell number must be finite, got {value}"),
+    ("error.iso_date", "Invalid ISO date '{value}' (expected YYYY-MM-DD[ HH:MM[:SS]])"),
+    ("error.border_side", "Border side must be 0-{max}, got {side}"),
+    ("error.flex_weight", "Flex weight must be a finite number above 0, got {weight}"),
+    ("error.detail_height", "Invalid detail height {height}"),
+    ("error.row_values", "Row values must be a JSON array or object"),
+    ("error.key_column", "Unknown key column {key}"),
+    ("error.indices_length", "Indices length {length} does not match row count {rows}"),
+    ("error.delete_every_column", "Cannot delete every column"),
+    ("error.editable_rules_array", "Editable rules must be a JSON array"),
+    ("error.editable_rule", "Invalid editable rule: {error}"),
+    ("error.validation_rule", "Invalid validation rule: {error}"),
+    ("error.filter_condition", "Filter needs one of expression, contains, values, equals, not_equals or non_empty"),
+    ("error.filter_expression", "Invalid filter expression: {error}"),
+    ("error.collator", "Failed to create Intl.Collator for locale {locale}: {error}"),
+    ("error.group_custom", "Custom aggregates cannot be grouped"),
+    ("error.pivot_field", "Unknown pivot field {field}"),
+    ("error.pivot_rows", "Pivot needs at least one row field"),
+    ("error.pivot_custom", "Custom aggregates cannot be pivoted"),
+    ("error.no_paste_target", "No cell selected for paste"),
+    ("error.paste_conflicts", "{count} cell(s) would be overwritten or are read-only"),
+    ("error.no_html_table", "Clipboard HTML contains no table"),
+    ("error.structured_clipboard", "Invalid structured clipboard data: {error}"),
+    ("error.grid_destroyed", "Grid was destroyed"),
+    ("error.no_window", "No browser window available"),
+    ("error.no_document", "No document available"),
+    ("error.element_not_found", "Element '{id}' not found"),
+    ("error.not_a_canvas", "Element '{id}' is not a canvas"),
+    ("error.create_canvas", "Failed to create a canvas"),
+    ("error.no_container", "The grid canvases are not inside a container element"),
+    ("error.no_canvas_context", "Canvas 2D context unavailable"),
+    ("error.dom", "Failed to {error}"),
+    ("error.watermark_content", "Watermark needs \"text\" or \"image\""),
+    ("error.watermark_size", "Watermark font_size and scale must be positive"),
+    ("error.export_region", "Export region start must not be after its end"),
+    ("error.export_too_large", "Export region is too large ({width} x {height} px, limit {limit})"),
+    ("error.page_size", "Page size needs \"size\" or \"width\" and \"height\""),
+    ("error.page_too_small", "Page ({width} x {height} px) is too small for the headers and frozen panes"),
+    ("error.no_page_layout", "No page layout (call get_page_count first)"),
+    ("error.page_out_of_range", "Page {page} is out of range ({count} pages)"),
+    ("error.page_layout_stale", "The grid changed since get_page_count; call it again"),
+    ("error.stream_virtual", "Streaming load is not available in virtual mode"),
+    ("error.stream_row", "Invalid JSON row: {error}"),
+    ("error.no_stream_load", "No streaming load in progress (call begin_stream_load)"),
+    ("error.delimiter", "Delimiter must be one character: '{delimiter}'"),
+    ("error.arrow_virtual", "Arrow load is not available in virtual mode"),
+    ("error.arrow", "Arrow error: {error}"),
+    ("error.state_version", "Unsupported grid state version: {version}"),
+    ("error.state_size", "Grid state has no grid size"),
+    ("error.layout_version", "Unsupported layout version: {version}"),
+    ("error.no_indexed_db", "IndexedDB is not available"),
+    ("error.time_travel_disabled", "Time travel is not enabled"),
+    ("error.no_checkpoint", "Checkpoint {id} does not exist"),
+    // Skipped entries of set_cells_batch and load_data_json
+    ("batch.expected_entry", "Expected [row, col, value_type, value_data]"),
+    ("batch.expected_object", "Expected an object with row, col and value"),
+    ("batch.invalid_row", "Invalid row {row}"),
+    ("batch.invalid_column", "Invalid column {col}"),
+    ("batch.invalid_number", "Invalid number '{value}'"),
+    ("batch.value_type", "Unknown value type '{type}' (expected 0-3)"),
+    ("batch.unsupported_value", "Unsupported value (expected string, number, boolean or null)"),
+    // Results of execute_row_operation / execute_column_operation
+    ("result.insert_row", "Inserted row at {row}"),
+    ("result.delete_row", "Deleted row {row}"),
+    ("result.move_row_up", "Moved row {row} up"),
+    ("result.move_row_down", "Moved row {row} down"),
+    ("result.sort_ascending", "Sorted column {col} ascending"),
+    ("result.sort_descending", "Sorted column {col} descending"),
+    ("result.clear_sort", "Cleared sort"),
+    ("result.clear_filter", "Cleared filter of column {col}"),
+    ("result.hide_column", "Hid column {col}"),
+    ("result.show_all_columns", "Showed {count} columns"),
+    ("result.auto_fit_column", "Auto-fit column {col}"),
+    ("result.insert_column", "Inserted column at {col}"),
+    ("result.delete_column", "Deleted column {col}"),
+    ("result.move_column_left", "Moved column {col} left"),
+    ("result.move_column_right", "Moved column {col} right"),
+    // Edit draft validation (validate_edit_draft)
+    ("validation.number", "Not a number; it will be stored as text"),
+    ("validation.date", "Not a date (YYYY-MM-DD); it will be stored as text"),
+    ("validation.boolean", "Not true/false; it will be stored as text"),
+    ("validation.duplicate", "Duplicates row {row}"),
+    // Column validation rules (set_column_rules_json)
+    ("validation.required", "A value is required"),
+    ("validation.min", "Must be at least {min}"),
+    ("validation.max", "Must be at most {max}"),
+    ("validation.max_length", "Must be at most {max} characters"),
+    ("validation.min_date", "Must be on or after {date}"),
+    ("validation.max_date", "Must be on or before {date}"),
+    ("validation.not_allowed", "Not one of the allowed values"),
+];
+
+thread_local! {
+    static OVERRIDES: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+/// Get a built-in string in the current locale (the key itself if unknown)
+pub fn text(key: &str) -> String {
+    OVERRIDES
+        .with(|overrides| overrides.borrow().get(key).cloned())
+        .or_else(|| DEFAULT_STRINGS.iter().find(|(k, _)| *k == key).map(|(_, text)| text.to_string()))
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Get a built-in string with its {name} placeholders replaced
+pub fn format(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter().fold(text(key), |text, (name, value)| text.replace(&format!("{{{}}}", name), &value.to_string()))
+}
+
+/// Override built-in strings from a JSON object of key -> text (null restores the default)
+/// Unknown keys or non-string values reject the whole table
+pub fn set_strings(strings: &serde_json::Value) -> Result<(), GridError> {
+    let Some(strings) = strings.as_object() else {
+        return Err(GridError::OperationError { message: text("error.locale_object") });
+    };
+    for (key, value) in strings {
+        if !DEFAULT_STRINGS.iter().any(|(k, _)| k == key) {
+            return Err(GridError::OperationError { message: format("error.locale_key", &[("key", key)]) });
+        }
+        if !value.is_string() && !value.is_null() {
+            return Err(GridError::OperationError { message: format("error.locale_value", &[("key", key)]) });
+        }
+    }
+
+    OVERRIDES.with(|overrides| {
+        let mut overrides = overrides.borrow_mut();
+        for (key, value) in strings {
+            match value.as_str() {
+                Some(text) => overrides.insert(key.clone(), text.to_string()),
+                None => overrides.remove(key),
+            };
+        }
+    });
+    Ok(())
+}
+
+/// Restore every built-in string to its English default
+pub fn reset() {
+    OVERRIDES.with(|overrides| overrides.borrow_mut().clear());
+}
+
+/// All built-in strings in the current locale as a JSON object
+pub fn to_json() -> serde_json::Value {
+    let strings: serde_json::Map<String, serde_json::Value> = DEFAULT_STRINGS
+        .iter()
+        .map(|(key, _)| (key.to_string(), serde_json::Value::String(text(key))))
+        .collect();
+    serde_json::Value::Object(strings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_overrides() {
+        assert_eq!(format("error.out_of_bounds", &[("row", &3), ("col", &4)]), "Cell (3, 4) is out of bounds");
+
+        set_strings(&serde_json::json!({ "error.out_of_bounds": "セル ({row}, {col}) は範囲外です" })).unwrap();
+        assert_eq!(format("error.out_of_bounds", &[("row", &3), ("col", &4)]), "セル (3, 4) は範囲外です");
+        assert!(set_strings(&serde_json::json!({ "no.such_key": "x" })).is_err());
+        assert!(set_strings(&serde_json::json!({ "ghost_row": 1 })).is_err());
+
+        set_strings(&serde_json::json!({ "error.out_of_bounds": null })).unwrap();
+        assert_eq!(text("error.out_of_bounds"), "Cell ({row}, {col}) is out of bounds");
+        reset();
+    }
+}
@@ -1,12 +1,41 @@
 // Feature modules for DataGrid functionality
 // This module contains specialized functionality extracted from lib.rs
 
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod autocomplete;
+pub mod batch;
 pub mod clipboard;
+pub mod datasource;
+pub mod detail;
+pub mod diagnostics;
 pub mod editing;
+pub mod events;
+pub mod filter;
+pub mod footer;
+pub mod hooks;
+pub mod html_table;
+pub mod layout;
+pub mod locale;
+#[cfg(feature = "persistence")]
+pub mod persistence;
+pub mod pivot;
+pub mod print;
+pub mod range_move;
 pub mod resize;
+pub mod row_animation;
 pub mod search;
 pub mod selection;
+pub mod series;
+pub mod snapshot;
+pub mod stream;
+pub mod summary;
+pub mod template;
+#[cfg(feature = "time-travel")]
+pub mod time_travel;
+pub mod totals;
 pub mod undo_redo;
+pub mod watermark;
 
 // Re-export commonly used types
 pub use undo_redo::{CellStyle, EditAction};
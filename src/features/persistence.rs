@@ -0,0 +1,106 @@
+use crate::core::Grid;
+use crate::features::locale;
+use crate::GridError;
+use js_sys::{Function, Promise};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{IdbDatabase, IdbOpenDbRequest, IdbRequest, IdbTransactionMode};
+
+/// IndexedDB database and object store holding persisted grids (one record per key)
+const DB_NAME: &str = "datagrid5";
+const STORE_NAME: &str = "grid_state";
+
+/// Auto-save settings and bookkeeping (enable_persistence)
+pub struct AutoSave {
+    pub key: String,
+    pub debounce_ms: u32,
+    pub saved: (u64, usize, usize), // (revision, frozen_rows, frozen_cols) at the last save
+    pub timeout_id: Option<i32>,    // Pending debounced save
+}
+
+impl AutoSave {
+    pub fn new(key: String, debounce_ms: u32, grid: &Grid) -> Self {
+        Self { key, debounce_ms, saved: Self::signature(grid), timeout_id: None }
+    }
+
+    /// What auto-save compares to detect unsaved changes
+    pub fn signature(grid: &Grid) -> (u64, usize, usize) {
+        (grid.revision(), grid.frozen_rows, grid.frozen_cols)
+    }
+
+    /// Cancel a pending debounced save
+    pub fn cancel(&mut self) {
+        if let (Some(id), Some(window)) = (self.timeout_id.take(), web_sys::window()) {
+            window.clear_timeout_with_handle(id);
+        }
+    }
+}
+
+/// Wait for an IndexedDB request to succeed, resolving to its result
+async fn request_result(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let promise = Promise::new(&mut |resolve: Function, reject: Function| {
+        let target = request.clone();
+        let on_success = Closure::once_into_js(move || {
+            let _ = resolve.call1(&JsValue::NULL, &target.result().unwrap_or(JsValue::UNDEFINED));
+        });
+        let target = request.clone();
+        let on_error = Closure::once_into_js(move || {
+            let error = target.error().ok().flatten().map(JsValue::from).unwrap_or(JsValue::UNDEFINED);
+            let _ = reject.call1(&JsValue::NULL, &error);
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    JsFuture::from(promise).await
+}
+
+/// Open the database, creating the object store on first use
+async fn open_database() -> Result<IdbDatabase, JsValue> {
+    let window = web_sys::window().ok_or_else(|| GridError::from(locale::text("error.no_window")))?;
+    let factory = window.indexed_db()?.ok_or_else(|| GridError::from(locale::text("error.no_indexed_db")))?;
+    let request: IdbOpenDbRequest = factory.open_with_u32(DB_NAME, 1)?;
+
+    let on_upgrade = Closure::once_into_js({
+        let request = request.clone();
+        move || {
+            if let Ok(db) = request.result().and_then(|db| db.dyn_into::<IdbDatabase>()) {
+                if !db.object_store_names().contains(STORE_NAME) {
+                    let _ = db.create_object_store(STORE_NAME);
+                }
+            }
+        }
+    });
+    request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+
+    request_result(&request).await?.dyn_into::<IdbDatabase>()
+}
+
+/// Store a JSON state string under `key`
+pub async fn save_to_indexed_db(key: String, state: String) -> Result<(), JsValue> {
+    let db = open_database().await?;
+    let transaction = db.transaction_with_str_and_mode(STORE_NAME, IdbTransactionMode::Readwrite)?;
+    let store = transaction.object_store(STORE_NAME)?;
+    request_result(&store.put_with_key(&JsValue::from_str(&state), &JsValue::from_str(&key))?).await?;
+    db.close();
+    Ok(())
+}
+
+/// Load the JSON state string stored under `key` (None if nothing was saved)
+pub async fn load_from_indexed_db(key: String) -> Result<Option<String>, JsValue> {
+    let db = open_database().await?;
+    let transaction = db.transaction_with_str(STORE_NAME)?;
+    let store = transaction.object_store(STORE_NAME)?;
+    let value = request_result(&store.get(&JsValue::from_str(&key))?).await?;
+    db.close();
+    Ok(value.as_string())
+}
+
+/// Delete the state stored under `key`
+pub async fn delete_from_indexed_db(key: String) -> Result<(), JsValue> {
+    let db = open_database().await?;
+    let transaction = db.transaction_with_str_and_mode(STORE_NAME, IdbTransactionMode::Readwrite)?;
+    let store = transaction.object_store(STORE_NAME)?;
+    request_result(&store.delete(&JsValue::from_str(&key))?).await?;
+    db.close();
+    Ok(())
+}
@@ -0,0 +1,426 @@
+use crate::core::{Cell, CellValue, ColumnConfig, DataType, Grid};
+use crate::features::filter::ColumnFilters;
+use crate::features::footer::{Aggregate, FooterAggregates};
+use crate::features::locale;
+use crate::features::totals::ColumnTotals;
+use crate::features::undo_redo::UndoRedoState;
+use crate::GridError;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/// Indentation per row dimension level in the label column
+const LEVEL_INDENT: &str = "    ";
+
+/// Fields of a pivot table (column indices of the flat grid)
+#[derive(Clone, Debug, PartialEq)]
+pub struct PivotConfig {
+    pub rows: Vec<usize>,    // Row dimensions, outermost first
+    pub columns: Vec<usize>, // Column dimensions, outermost first (may be empty)
+    pub value: usize,        // Aggregated column
+    pub aggregate: Aggregate,
+}
+
+impl PivotConfig {
+    /// Parse {"rows": [..], "columns": [..], "value": v, "aggregate": "sum"}
+    /// Fields are column indices or column names (internal or display)
+    pub fn from_json(config: &serde_json::Value, grid: &Grid) -> Result<Self, GridError> {
+        let field = |value: &serde_json::Value| -> Result<usize, GridError> {
+            let col = match value {
+                serde_json::Value::Number(n) => n.as_u64().map(|n| n as usize),
+                serde_json::Value::String(name) => grid
+                    .get_column_by_name(name)
+                    .or_else(|| grid.column_configs.iter().position(|c| &c.display_name == name)),
+                _ => None,
+            };
+            col.filter(|&col| col < grid.col_count()).ok_or_else(|| GridError::OperationError {
+                message: locale::format("error.pivot_field", &[("field", value)]),
+            })
+        };
+        let fields = |key: &str| -> Result<Vec<usize>, GridError> {
+            config[key].as_array().into_iter().flatten().map(field).collect()
+        };
+
+        let rows = fields("rows")?;
+        if rows.is_empty() {
+            return Err(GridError::OperationError { message: locale::text("error.pivot_rows") });
+        }
+        let aggregate = match config["aggregate"].as_str() {
+            Some(name) => Aggregate::parse(name)?,
+            None => Aggregate::Sum,
+        };
+        if aggregate == Aggregate::Custom {
+            return Err(GridError::OperationError { message: locale::text("error.pivot_custom") });
+        }
+
+        Ok(Self {
+            rows,
+            columns: fields("columns")?,
+            value: field(&config["value"])?,
+            aggregate,
+        })
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "rows": self.rows,
+            "columns": self.columns,
+            "value": self.value,
+            "aggregate": self.aggregate.as_str(),
+        })
+    }
+}
+
+/// The flat grid and its per-column state, parked while the pivot view is shown
+pub struct FlatState {
+    pub grid: Grid,
+    pub undo_redo: UndoRedoState,
+    pub column_filters: ColumnFilters,
+    pub footer: FooterAggregates,
+    pub column_totals: ColumnTotals,
+}
+
+impl FlatState {
+    /// A grid with empty undo history, filters, footer aggregates and totals
+    pub fn new(grid: Grid) -> Self {
+        Self {
+            grid,
+            undo_redo: UndoRedoState::default(),
+            column_filters: ColumnFilters::new(),
+            footer: FooterAggregates::new(),
+            column_totals: ColumnTotals::new(),
+        }
+    }
+}
+
+/// Pivot view of the flat grid with expandable row and column dimensions
+/// Nodes are dimension paths: a prefix of the row (or column) field values
+pub struct PivotView {
+    pub config: PivotConfig,
+    pub flat: FlatState,
+    collapsed_rows: HashSet<Vec<String>>,
+    collapsed_cols: HashSet<Vec<String>>,
+    row_nodes: Vec<Vec<String>>, // Path of each pivot row (the grand total row has an empty path)
+    col_nodes: Vec<Vec<String>>, // Path of each value column, from column 1 on (grand total: empty path)
+}
+
+impl PivotView {
+    pub fn new(config: PivotConfig, flat: FlatState) -> Self {
+        Self {
+            config,
+            flat,
+            collapsed_rows: HashSet::new(),
+            collapsed_cols: HashSet::new(),
+            row_nodes: Vec::new(),
+            col_nodes: Vec::new(),
+        }
+    }
+
+    /// Build the pivot grid from the visible rows of the flat grid
+    /// Column 0 holds the indented row labels; the last row and column are grand totals
+    pub fn build(&mut self) -> Grid {
+        let flat = &self.flat.grid;
+        let rows: Vec<usize> = (0..flat.row_count()).filter(|&row| !flat.is_row_hidden(row)).collect();
+        let path = |row: usize, fields: &[usize]| -> Vec<String> {
+            fields.iter().map(|&col| flat.get_value(row, col).to_string()).collect()
+        };
+
+        let row_paths: Vec<Vec<String>> = rows.iter().map(|&row| path(row, &self.config.rows)).collect();
+        let col_paths: Vec<Vec<String>> = rows.iter().map(|&row| path(row, &self.config.columns)).collect();
+        self.row_nodes = visible_nodes(&row_paths, &self.collapsed_rows, true);
+        self.row_nodes.push(Vec::new());
+        self.col_nodes = visible_nodes(&col_paths, &self.collapsed_cols, false);
+        self.col_nodes.push(Vec::new());
+
+        // Bucket every value under each row node and column node containing its row
+        let row_index: HashMap<&[String], usize> = self.row_nodes.iter().enumerate().map(|(i, p)| (p.as_slice(), i)).collect();
+        let col_index: HashMap<&[String], usize> = self.col_nodes.iter().enumerate().map(|(i, p)| (p.as_slice(), i)).collect();
+        let mut buckets: HashMap<(usize, usize), Vec<CellValue>> = HashMap::new();
+        for (i, &row) in rows.iter().enumerate() {
+            let value = flat.get_value(row, self.config.value);
+            let row_hits: Vec<usize> = (0..=row_paths[i].len()).filter_map(|d| row_index.get(&row_paths[i][..d]).copied()).collect();
+            let col_hits: Vec<usize> = (0..=col_paths[i].len()).filter_map(|d| col_index.get(&col_paths[i][..d]).copied()).collect();
+            for &r in &row_hits {
+                for &c in &col_hits {
+                    buckets.entry((r, c)).or_default().push(value.clone());
+                }
+            }
+        }
+
+        let mut grid = Grid::new(self.row_nodes.len(), self.col_nodes.len() + 1);
+        grid.set_density(flat.density);
+        grid.set_header_row_height(flat.header_row_height);
+        grid.row_header_width = flat.row_header_width;
+        grid.show_headers = flat.show_headers;
+        grid.show_grid_lines = flat.show_grid_lines;
+        grid.alternate_row_colors = flat.alternate_row_colors;
+        grid.readonly = true;
+        grid.frozen_cols = 1;
+
+        let field_name = |col: usize| flat.get_column_config(col).map(|c| c.display_name.clone()).unwrap_or_default();
+        let label_header = self.config.rows.iter().map(|&col| field_name(col)).collect::<Vec<_>>().join(" / ");
+        let mut label_config = ColumnConfig::new(label_header, "pivot_label".to_string());
+        label_config.editable = false;
+        label_config.sortable = false;
+        label_config.width = 180.0;
+        grid.set_column_config(0, label_config);
+
+        let value_format = flat.get_column_config(self.config.value).and_then(|c| c.number_format.clone());
+        for (i, node) in self.col_nodes.iter().enumerate() {
+            let mut config = ColumnConfig::new(self.col_label(node), format!("pivot_{}", i));
+            config.data_type = DataType::Number;
+            config.editable = false;
+            config.sortable = false;
+            if self.config.aggregate != Aggregate::Count {
+                config.number_format = value_format.clone();
+            }
+            grid.set_column_config(i + 1, config);
+        }
+        self.add_column_groups(&mut grid);
+
+        for (r, node) in self.row_nodes.iter().enumerate() {
+            let total = node.len() < self.config.rows.len();
+            let mut label = Cell::new(CellValue::Text(self.row_label(node)));
+            label.editable = false;
+            label.font_bold = total;
+            grid.set_cell(r, 0, label);
+            for c in 0..self.col_nodes.len() {
+                let value = buckets.remove(&(r, c)).and_then(|values| self.config.aggregate.compute_values(values.into_iter()));
+                let Some(value) = value else {
+                    continue;
+                };
+                let mut cell = Cell::new(CellValue::Number(value));
+                cell.editable = false;
+                cell.font_bold = total || self.col_nodes[c].is_empty();
+                grid.set_cell(r, c + 1, cell);
+            }
+        }
+        grid
+    }
+
+    /// Expand or collapse the row group shown in a pivot row
+    /// Returns false for leaf rows and the grand total row
+    pub fn toggle_row(&mut self, row: usize) -> bool {
+        match self.row_nodes.get(row) {
+            Some(node) if !node.is_empty() && node.len() < self.config.rows.len() => {
+                toggle(&mut self.collapsed_rows, node.clone());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Expand a collapsed pivot column, or collapse the group containing it
+    /// Returns false when there is nothing to toggle (top-level leaf or grand total column)
+    pub fn toggle_column(&mut self, col: usize) -> bool {
+        let Some(node) = col.checked_sub(1).and_then(|i| self.col_nodes.get(i)) else {
+            return false;
+        };
+        if node.is_empty() {
+            return false;
+        }
+        if self.collapsed_cols.contains(node) {
+            self.collapsed_cols.remove(node);
+            return true;
+        }
+        if node.len() < 2 {
+            return false;
+        }
+        self.collapsed_cols.insert(node[..node.len() - 1].to_vec());
+        true
+    }
+
+    /// Expand (or collapse to the outermost level) every row and column group
+    pub fn set_all_expanded(&mut self, expanded: bool) {
+        self.collapsed_rows.clear();
+        self.collapsed_cols.clear();
+        if expanded {
+            return;
+        }
+        for node in &self.row_nodes {
+            if !node.is_empty() {
+                self.collapsed_rows.insert(node[..1].to_vec());
+            }
+        }
+        for node in &self.col_nodes {
+            if node.len() > 1 {
+                self.collapsed_cols.insert(node[..1].to_vec());
+            }
+        }
+    }
+
+    /// Describe a pivot row: {"path": [...], "level", "group", "expanded"}
+    pub fn row_info(&self, row: usize) -> Option<serde_json::Value> {
+        let node = self.row_nodes.get(row)?;
+        let group = !node.is_empty() && node.len() < self.config.rows.len();
+        Some(serde_json::json!({
+            "path": node,
+            "level": node.len(),
+            "group": group,
+            "expanded": group && !self.collapsed_rows.contains(node),
+        }))
+    }
+
+    /// Label column text: indented value with an expand marker on groups
+    fn row_label(&self, node: &[String]) -> String {
+        let Some(key) = node.last() else {
+            return locale::text("pivot.grand_total");
+        };
+        let marker = match (node.len() < self.config.rows.len(), self.collapsed_rows.contains(node)) {
+            (false, _) => "",
+            (true, true) => "▸ ",
+            (true, false) => "▾ ",
+        };
+        format!("{}{}{}", LEVEL_INDENT.repeat(node.len() - 1), marker, key_label(key))
+    }
+
+    /// Column header text: the innermost value, marked when the column is a collapsed group
+    fn col_label(&self, node: &[String]) -> String {
+        match node.last() {
+            None => locale::text("pivot.grand_total"),
+            Some(key) if node.len() < self.config.columns.len() => format!("▸ {}", key_label(key)),
+            Some(key) => key_label(key),
+        }
+    }
+
+    /// Header groups for the outer column dimension values above expanded columns
+    fn add_column_groups(&self, grid: &mut Grid) {
+        for level in 0..self.config.columns.len().saturating_sub(1) {
+            let mut start = 0;
+            while start < self.col_nodes.len() {
+                let node = &self.col_nodes[start];
+                if node.len() <= level + 1 {
+                    start += 1;
+                    continue;
+                }
+                let prefix = &node[..=level];
+                let mut end = start;
+                while end + 1 < self.col_nodes.len() && self.col_nodes[end + 1].len() > level + 1 && &self.col_nodes[end + 1][..=level] == prefix {
+                    end += 1;
+                }
+                grid.add_column_group(format!("▾ {}", key_label(&prefix[level])), start + 1, end + 1, level);
+                start = end + 1;
+            }
+        }
+    }
+}
+
+/// Display text of a dimension value
+fn key_label(key: &str) -> String {
+    if key.is_empty() {
+        locale::text("pivot.blank")
+    } else {
+        key.to_string()
+    }
+}
+
+fn toggle(set: &mut HashSet<Vec<String>>, node: Vec<String>) {
+    if !set.remove(&node) {
+        set.insert(node);
+    }
+}
+
+/// Order dimension values: numbers first (numerically), then text
+/// (a total order, so mixed numeric and text values sort consistently)
+fn compare_keys(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(x), Ok(y)) => x.total_cmp(&y),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+/// Nodes shown for a dimension, in depth-first order
+/// With `groups`, every expanded group is listed before its children (row layout);
+/// otherwise only leaves and collapsed groups are (column layout)
+fn visible_nodes(paths: &[Vec<String>], collapsed: &HashSet<Vec<String>>, groups: bool) -> Vec<Vec<String>> {
+    let mut unique: Vec<&Vec<String>> = paths.iter().collect::<HashSet<_>>().into_iter().collect();
+    unique.sort_by(|a, b| a.iter().zip(b.iter()).map(|(x, y)| compare_keys(x, y)).find(|o| o.is_ne()).unwrap_or_else(|| a.cmp(b)));
+
+    let mut nodes: Vec<Vec<String>> = Vec::new();
+    let mut seen: HashSet<&[String]> = HashSet::new();
+    for path in unique {
+        for depth in 1..=path.len() {
+            let prefix = &path[..depth];
+            let is_collapsed = collapsed.contains(&prefix.to_vec());
+            if seen.insert(prefix) && (groups || depth == path.len() || is_collapsed) {
+                nodes.push(prefix.to_vec());
+            }
+            if is_collapsed {
+                break;
+            }
+        }
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sales_grid() -> Grid {
+        let mut grid = Grid::new(5, 4);
+        let rows = [("East", "A", "2023", 10.0), ("East", "B", "2024", 20.0), ("West", "A", "2023", 5.0), ("West", "A", "2024", 7.0), ("East", "A", "2024", 1.0)];
+        for (row, (region, product, year, amount)) in rows.into_iter().enumerate() {
+            grid.set_value(row, 0, CellValue::Text(region.to_string()));
+            grid.set_value(row, 1, CellValue::Text(product.to_string()));
+            grid.set_value(row, 2, CellValue::Text(year.to_string()));
+            grid.set_value(row, 3, CellValue::Number(amount));
+        }
+        grid
+    }
+
+    #[test]
+    fn test_pivot_layout_and_totals() {
+        let flat = FlatState::new(sales_grid());
+        let config = PivotConfig { rows: vec![0, 1], columns: vec![2], value: 3, aggregate: Aggregate::Sum };
+        let mut view = PivotView::new(config, flat);
+        let grid = view.build();
+
+        // East, East/A, East/B, West, West/A, Grand Total x label, 2023, 2024, Grand Total
+        assert_eq!((grid.row_count(), grid.col_count()), (6, 4));
+        assert_eq!(grid.get_value(0, 0), CellValue::Text("▾ East".to_string()));
+        assert_eq!(grid.get_value(1, 0), CellValue::Text("    A".to_string()));
+        assert_eq!(grid.get_value(0, 3), CellValue::Number(31.0));
+        assert_eq!(grid.get_value(1, 2), CellValue::Number(1.0));
+        assert_eq!(grid.get_value(2, 1), CellValue::Empty);
+        assert_eq!(grid.get_value(5, 1), CellValue::Number(15.0));
+        assert_eq!(grid.get_value(5, 3), CellValue::Number(43.0));
+
+        assert!(view.toggle_row(0));
+        assert!(!view.toggle_row(1));
+        let grid = view.build();
+        assert_eq!(grid.row_count(), 4);
+        assert_eq!(grid.get_value(0, 0), CellValue::Text("▸ East".to_string()));
+        assert_eq!(grid.get_value(1, 0), CellValue::Text("▾ West".to_string()));
+    }
+
+    #[test]
+    fn test_pivot_column_groups_collapse() {
+        let flat = FlatState::new(sales_grid());
+        let config = PivotConfig { rows: vec![0], columns: vec![2, 1], value: 3, aggregate: Aggregate::Count };
+        let mut view = PivotView::new(config, flat);
+        let grid = view.build();
+        // 2023/A, 2024/A, 2024/B, Grand Total
+        assert_eq!(grid.col_count(), 5);
+        assert_eq!(grid.column_groups.len(), 2);
+        assert_eq!(grid.get_value(0, 2), CellValue::Number(1.0));
+
+        assert!(view.toggle_column(2));
+        let grid = view.build();
+        assert_eq!(grid.col_count(), 4);
+        assert_eq!(grid.get_column_config(2).unwrap().display_name, "▸ 2024");
+        assert_eq!(grid.get_value(0, 2), CellValue::Number(2.0));
+        assert!(view.toggle_column(2));
+        assert_eq!(view.build().col_count(), 5);
+    }
+
+    #[test]
+    fn test_compare_keys_orders_numbers_before_text() {
+        let mut keys = vec!["b", "10", "", "2", "a", "1e1", "-1"];
+        keys.sort_by(|a, b| compare_keys(a, b));
+        assert_eq!(keys, vec!["-1", "2", "10", "1e1", "", "a", "b"]);
+        assert_eq!(compare_keys("2", "a"), compare_keys("3", "a"));
+        assert_eq!(compare_keys("a", "2"), Ordering::Greater);
+    }
+}
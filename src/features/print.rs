@@ -0,0 +1,199 @@
+use crate::core::Grid;
+use crate::features::locale;
+use crate::GridError;
+
+/// CSS pixels per inch used for the named paper sizes
+const CSS_DPI: f32 = 96.0;
+
+/// Order in which page tiles are numbered when the grid is split both ways
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PageOrder {
+    DownThenAcross, // All row bands of the first column band, then the next column band
+    AcrossThenDown, // All column bands of the first row band, then the next row band
+}
+
+impl PageOrder {
+    pub fn parse(order: &str) -> Result<Self, GridError> {
+        match order {
+            "down" => Ok(PageOrder::DownThenAcross),
+            "across" => Ok(PageOrder::AcrossThenDown),
+            _ => Err(GridError::unknown_value("page_order", order, &["down", "across"])),
+        }
+    }
+}
+
+/// Page size and what each page repeats
+#[derive(Clone, Debug)]
+pub struct PageSetup {
+    pub width: f32,  // Printable width in grid pixels
+    pub height: f32, // Printable height in grid pixels
+    pub headers: bool,
+    pub repeat_frozen: bool, // Frozen rows/columns are drawn on every page
+    pub order: PageOrder,
+    pub watermark: bool, // Draw the grid's watermark on the pages
+}
+
+impl PageSetup {
+    /// Parse {"width", "height"} in pixels or {"size": "a4" | "a3" | "letter" | "legal",
+    /// "orientation": "portrait" | "landscape"}, plus optional "margin" (pixels taken off
+    /// every side), "headers", "repeat_frozen", "order" and "watermark"
+    pub fn from_json(options: &serde_json::Value) -> Result<Self, GridError> {
+        let (mut width, mut height) = match options["size"].as_str() {
+            Some(size) => {
+                let (w, h) = match size {
+                    "a4" => (8.27, 11.69),
+                    "a3" => (11.69, 16.54),
+                    "letter" => (8.5, 11.0),
+                    "legal" => (8.5, 14.0),
+                    _ => return Err(GridError::unknown_value("page_size", size, &["a4", "a3", "letter", "legal"])),
+                };
+                (w * CSS_DPI, h * CSS_DPI)
+            }
+            None => match (options["width"].as_f64(), options["height"].as_f64()) {
+                (Some(w), Some(h)) => (w as f32, h as f32),
+                _ => return Err(GridError::OperationError { message: locale::text("error.page_size") }),
+            },
+        };
+        match options["orientation"].as_str() {
+            None | Some("portrait") => {}
+            Some("landscape") => (width, height) = (height, width),
+            Some(other) => return Err(GridError::unknown_value("orientation", other, &["portrait", "landscape"])),
+        }
+        let margin = options["margin"].as_f64().unwrap_or(0.0).max(0.0) as f32;
+        let order = match options["order"].as_str() {
+            Some(order) => PageOrder::parse(order)?,
+            None => PageOrder::DownThenAcross,
+        };
+        Ok(Self {
+            width: width - 2.0 * margin,
+            height: height - 2.0 * margin,
+            headers: options["headers"].as_bool().unwrap_or(true),
+            repeat_frozen: options["repeat_frozen"].as_bool().unwrap_or(true),
+            order,
+            watermark: options["watermark"].as_bool().unwrap_or(false),
+        })
+    }
+}
+
+/// One page: the header area and repeated frozen panes, then a band of body rows and columns
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PageTile {
+    pub rows: (usize, usize), // First and last body row (inclusive)
+    pub cols: (usize, usize), // First and last body column (inclusive)
+    pub width: f32,           // Drawn size in grid pixels (at most the page size)
+    pub height: f32,
+}
+
+/// The grid split into page-sized tiles (computed by paginate)
+#[derive(Clone, Debug)]
+pub struct PageLayout {
+    pub setup: PageSetup,
+    pub frozen_rows: usize, // Rows repeated at the top of every page
+    pub frozen_cols: usize, // Columns repeated at the left of every page
+    row_bands: Vec<(usize, usize, f32)>, // (first, last, height)
+    col_bands: Vec<(usize, usize, f32)>, // (first, last, width)
+    fixed: (f32, f32),                   // Width and height of the headers and frozen panes
+}
+
+impl PageLayout {
+    pub fn page_count(&self) -> usize {
+        self.row_bands.len() * self.col_bands.len()
+    }
+
+    /// Tile of page n (0-based)
+    pub fn page(&self, n: usize) -> Option<PageTile> {
+        if n >= self.page_count() {
+            return None;
+        }
+        let (row_band, col_band) = match self.setup.order {
+            PageOrder::DownThenAcross => (n % self.row_bands.len(), n / self.row_bands.len()),
+            PageOrder::AcrossThenDown => (n / self.col_bands.len(), n % self.col_bands.len()),
+        };
+        let (first_row, last_row, height) = self.row_bands[row_band];
+        let (first_col, last_col, width) = self.col_bands[col_band];
+        Some(PageTile {
+            rows: (first_row, last_row),
+            cols: (first_col, last_col),
+            width: self.fixed.0 + width,
+            height: self.fixed.1 + height,
+        })
+    }
+}
+
+/// Split items start..count into bands that fit in `available` (an item larger than the
+/// space gets a band of its own and is cut off; zero-size items join the current band)
+fn bands(start: usize, count: usize, available: f32, size: impl Fn(usize) -> f32) -> Vec<(usize, usize, f32)> {
+    let mut bands = Vec::new();
+    let mut band: Option<(usize, usize, f32)> = None;
+    for i in start..count {
+        let item = size(i);
+        band = match band {
+            Some((first, _, used)) if used + item <= available || item == 0.0 => Some((first, i, used + item)),
+            Some(full) => {
+                bands.push(full);
+                Some((i, i, item))
+            }
+            None => Some((i, i, item)),
+        };
+    }
+    bands.extend(band.map(|(first, last, used)| (first, last, used.min(available))));
+    bands
+}
+
+/// Split the grid into pages. Frozen rows and columns are repeated on every page when
+/// setup.repeat_frozen is set (unless they fill the whole grid); the footer is not printed.
+pub fn paginate(grid: &Grid, setup: PageSetup) -> Result<PageLayout, GridError> {
+    let frozen_rows = if setup.repeat_frozen && grid.frozen_rows < grid.row_count() { grid.frozen_rows } else { 0 };
+    let frozen_cols = if setup.repeat_frozen && grid.frozen_cols < grid.col_count() { grid.frozen_cols } else { 0 };
+    let (header_width, header_height) = if setup.headers { (grid.row_header_width, grid.col_header_height) } else { (0.0, 0.0) };
+    let fixed = (header_width + grid.col_x_position(frozen_cols), header_height + grid.row_y_position(frozen_rows) as f32);
+
+    let (available_width, available_height) = (setup.width - fixed.0, setup.height - fixed.1);
+    if available_width <= 0.0 || available_height <= 0.0 {
+        return Err(GridError::OperationError {
+            message: locale::format("error.page_too_small", &[("width", &setup.width), ("height", &setup.height)]),
+        });
+    }
+
+    Ok(PageLayout {
+        row_bands: bands(frozen_rows, grid.row_count(), available_height, |row| grid.row_height(row)),
+        col_bands: bands(frozen_cols, grid.col_count(), available_width, |col| grid.col_width(col)),
+        setup,
+        frozen_rows,
+        frozen_cols,
+        fixed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginate() {
+        // 100 rows of 25px and 10 columns of 100px under 30px headers with a 60px row header
+        let mut grid = Grid::new(100, 10);
+        for col in 0..10 {
+            grid.set_col_width(col, 100.0);
+        }
+        grid.frozen_rows = 1;
+        grid.frozen_cols = 1;
+        let setup = PageSetup::from_json(&serde_json::json!({ "width": 460, "height": 555, "order": "across" })).unwrap();
+        let layout = paginate(&grid, setup).unwrap();
+
+        // Body per page: 500px of rows (20 rows) and 300px of columns (3 columns)
+        assert_eq!(layout.page_count(), 5 * 3);
+        let first = layout.page(0).unwrap();
+        assert_eq!((first.rows, first.cols), ((1, 20), (1, 3)));
+        assert_eq!((first.width, first.height), (460.0, 555.0));
+        assert_eq!(layout.page(1).unwrap().cols, (4, 6));
+        let last = layout.page(14).unwrap();
+        assert_eq!((last.rows, last.cols), ((81, 99), (7, 9)));
+        assert!(layout.page(15).is_none());
+
+        let landscape = PageSetup::from_json(&serde_json::json!({ "size": "a4", "orientation": "landscape" })).unwrap();
+        assert!(landscape.width > landscape.height);
+        let tiny = PageSetup::from_json(&serde_json::json!({ "width": 100, "height": 100 })).unwrap();
+        assert!(paginate(&grid, tiny).is_err());
+    }
+}
@@ -0,0 +1,255 @@
+use crate::core::{Cell, CellValue, Grid};
+use crate::GridError;
+use std::collections::BTreeMap;
+
+/// Width (px) of the band just outside the selection outline that grabs it
+const BORDER_GRAB_WIDTH: f32 = 3.0;
+
+/// Rectangle of cells (min_row, min_col, max_row, max_col)
+pub type CellRange = (usize, usize, usize, usize);
+
+/// A selection being dragged to a new location by its border
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeMove {
+    pub source: CellRange,
+    pub grab: (usize, usize),   // Grabbed cell, relative to the source's top-left
+    pub target: (usize, usize), // Top-left of the drop position
+    pub copy: bool,             // Ctrl+drag copies instead of moving
+}
+
+impl RangeMove {
+    /// Start dragging `source`, grabbed at cell (row, col)
+    pub fn new(source: CellRange, row: usize, col: usize, copy: bool) -> Self {
+        let grab = (row.saturating_sub(source.0), col.saturating_sub(source.1));
+        Self { source, grab, target: (source.0, source.1), copy }
+    }
+
+    /// Follow the pointer over cell (row, col), keeping the range inside the grid
+    /// Returns true if the drop position changed
+    pub fn drag_to(&mut self, row: usize, col: usize, row_count: usize, col_count: usize) -> bool {
+        let (height, width) = self.size();
+        let target = (
+            row.saturating_sub(self.grab.0).min(row_count.saturating_sub(height)),
+            col.saturating_sub(self.grab.1).min(col_count.saturating_sub(width)),
+        );
+        let changed = target != self.target;
+        self.target = target;
+        changed
+    }
+
+    /// (rows, cols) of the dragged range
+    pub fn size(&self) -> (usize, usize) {
+        (self.source.2 - self.source.0 + 1, self.source.3 - self.source.1 + 1)
+    }
+
+    /// Range the cells land in
+    pub fn target_range(&self) -> CellRange {
+        let (height, width) = self.size();
+        (self.target.0, self.target.1, self.target.0 + height - 1, self.target.1 + width - 1)
+    }
+}
+
+/// Check if canvas point (x, y) is on the outline of `rect` (x, y, width, height)
+/// Only the edge itself and a thin band outside it count, so clicks inside the
+/// selection still select cells
+pub fn on_outline(rect: (f32, f32, f32, f32), x: f32, y: f32) -> bool {
+    let (left, top, right, bottom) = (rect.0, rect.1, rect.0 + rect.2, rect.1 + rect.3);
+    let in_band = x >= left - BORDER_GRAB_WIDTH
+        && x <= right + BORDER_GRAB_WIDTH
+        && y >= top - BORDER_GRAB_WIDTH
+        && y <= bottom + BORDER_GRAB_WIDTH;
+    let inside = x > left && x < right && y > top && y < bottom;
+    in_band && !inside
+}
+
+/// Cells written by moving (or copying) `source` so its top-left lands on `target`
+/// Values and styles travel; each written cell keeps its own editability and
+/// modified tracking. Source cells outside the target are emptied when moving.
+pub fn plan_move(grid: &Grid, source: CellRange, target: (usize, usize), copy: bool) -> Vec<(usize, usize, Cell)> {
+    let (r1, c1, r2, c2) = source;
+    if target == (r1, c1) {
+        return Vec::new();
+    }
+    let to_target = |row: usize, col: usize| (target.0 + row - r1, target.1 + col - c1);
+    let in_target = |row: usize, col: usize| {
+        (target.0..=target.0 + r2 - r1).contains(&row) && (target.1..=target.1 + c2 - c1).contains(&col)
+    };
+    let replace = |row: usize, col: usize, content: Cell| {
+        let old = grid.get_cell(row, col).cloned().unwrap_or_default();
+        let mut cell = Cell { editable: old.editable, modified: old.modified, original: old.original, ..content };
+        if cell.value != old.value {
+            cell.mark_modified(old.value);
+        }
+        cell
+    };
+
+    let mut writes = Vec::new();
+    for row in r1..=r2 {
+        for col in c1..=c2 {
+            if !copy && !in_target(row, col) {
+                writes.push((row, col, replace(row, col, Cell::new(CellValue::Empty))));
+            }
+            let (to_row, to_col) = to_target(row, col);
+            let content = grid.get_cell(row, col).cloned().unwrap_or_default();
+            writes.push((to_row, to_col, replace(to_row, to_col, content)));
+        }
+    }
+    writes
+}
+
+/// New contents by (row, col); None removes the cell
+pub type ShiftedCells = BTreeMap<(usize, usize), Option<Cell>>;
+
+/// Direction the surrounding cells move when cells are inserted or deleted
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ShiftAxis {
+    Vertical,   // Insert shifts cells down, delete shifts them up
+    Horizontal, // Insert shifts cells right, delete shifts them left
+}
+
+impl ShiftAxis {
+    /// Parse the shift of insert_cells ("down" or "right")
+    pub fn parse_insert(shift: &str) -> Result<Self, GridError> {
+        match shift.to_lowercase().as_str() {
+            "down" => Ok(ShiftAxis::Vertical),
+            "right" => Ok(ShiftAxis::Horizontal),
+            other => Err(GridError::unknown_value("insert_shift", other, &["down", "right"])),
+        }
+    }
+
+    /// Parse the shift of delete_cells ("up" or "left")
+    pub fn parse_delete(shift: &str) -> Result<Self, GridError> {
+        match shift.to_lowercase().as_str() {
+            "up" => Ok(ShiftAxis::Vertical),
+            "left" => Ok(ShiftAxis::Horizontal),
+            other => Err(GridError::unknown_value("delete_shift", other, &["up", "left"])),
+        }
+    }
+}
+
+/// Rows (vertical) or columns (horizontal) the grid needs so that inserting `range`
+/// pushes no non-empty cell off its end
+pub fn insert_extent(grid: &Grid, range: CellRange, axis: ShiftAxis) -> usize {
+    let (r1, c1, r2, c2) = range;
+    match axis {
+        ShiftAxis::Vertical => (r1..grid.row_count())
+            .rev()
+            .find(|&row| (c1..=c2).any(|col| !grid.get_value(row, col).is_empty()))
+            .map_or(0, |row| row + 1 + (r2 - r1 + 1)),
+        ShiftAxis::Horizontal => (c1..grid.col_count())
+            .rev()
+            .find(|&col| (r1..=r2).any(|row| !grid.get_value(row, col).is_empty()))
+            .map_or(0, |col| col + 1 + (c2 - c1 + 1)),
+    }
+}
+
+/// New contents of every cell that changes when `range` is inserted (empty cells open
+/// up, the cells from it onwards shift away) or deleted (the cells after it shift in).
+/// Whole cells (values, styles and flags) move; None leaves no cell behind.
+/// Fails with the first non-empty cell an insert would push off the grid.
+pub fn plan_shift(
+    grid: &Grid,
+    range: CellRange,
+    axis: ShiftAxis,
+    insert: bool,
+) -> Result<ShiftedCells, (usize, usize)> {
+    let (r1, c1, r2, c2) = range;
+    // Work along the shifted axis: `lane` crosses it, `pos` runs along it
+    let (lanes, start, count, end) = match axis {
+        ShiftAxis::Vertical => (c1..=c2, r1, r2 - r1 + 1, grid.row_count()),
+        ShiftAxis::Horizontal => (r1..=r2, c1, c2 - c1 + 1, grid.col_count()),
+    };
+    let cell_at = |lane: usize, pos: usize| match axis {
+        ShiftAxis::Vertical => (pos, lane),
+        ShiftAxis::Horizontal => (lane, pos),
+    };
+
+    let mut writes = BTreeMap::new();
+    for lane in lanes {
+        if insert {
+            if let Some(pos) = (end.saturating_sub(count).max(start)..end).find(|&pos| {
+                let (row, col) = cell_at(lane, pos);
+                !grid.get_value(row, col).is_empty()
+            }) {
+                return Err(cell_at(lane, pos));
+            }
+        }
+        for pos in start..end {
+            let from = if insert { pos.checked_sub(count).filter(|&from| from >= start) } else { Some(pos + count).filter(|&from| from < end) };
+            let (row, col) = cell_at(lane, pos);
+            let cell = from.and_then(|from| {
+                let (from_row, from_col) = cell_at(lane, from);
+                grid.get_cell(from_row, from_col).cloned()
+            });
+            if cell.is_some() || grid.get_cell(row, col).is_some() {
+                writes.insert((row, col), cell);
+            }
+        }
+    }
+    Ok(writes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_and_copy_range() {
+        let mut grid = Grid::new(4, 4);
+        grid.set_value(0, 0, CellValue::Text("a".to_string()));
+        grid.set_value(0, 1, CellValue::Text("b".to_string()));
+        grid.get_cell_mut(0, 1).unwrap().font_bold = true;
+
+        // Dragging by the right cell keeps the range inside the grid
+        let mut drag = RangeMove::new((0, 0, 0, 1), 0, 1, false);
+        assert!(drag.drag_to(2, 3, 4, 4));
+        assert_eq!(drag.target_range(), (2, 2, 2, 3));
+
+        // Moving one column right: "a" lands on the old "b" and (0, 0) is emptied
+        let writes = plan_move(&grid, (0, 0, 0, 1), (0, 1), false);
+        let cell = |row, col| writes.iter().rev().find(|w| (w.0, w.1) == (row, col)).map(|w| &w.2).unwrap();
+        assert_eq!(cell(0, 0).value, CellValue::Empty);
+        assert_eq!(cell(0, 1).value, CellValue::Text("a".to_string()));
+        assert!(!cell(0, 1).font_bold);
+        assert!(cell(0, 2).font_bold);
+
+        // Copying leaves the source alone
+        let writes = plan_move(&grid, (0, 0, 0, 1), (3, 0), true);
+        assert_eq!(writes.len(), 2);
+        assert!(writes.iter().all(|w| w.0 == 3));
+
+        assert!(on_outline((10.0, 10.0, 50.0, 20.0), 8.0, 20.0));
+        assert!(on_outline((10.0, 10.0, 50.0, 20.0), 30.0, 30.0));
+        assert!(!on_outline((10.0, 10.0, 50.0, 20.0), 12.0, 20.0));
+        assert!(!on_outline((10.0, 10.0, 50.0, 20.0), 30.0, 20.0));
+        assert!(!on_outline((10.0, 10.0, 50.0, 20.0), 30.0, 34.0));
+    }
+
+    #[test]
+    fn test_shift_cells() {
+        let mut grid = Grid::new(4, 2);
+        for row in 0..3 {
+            grid.set_value(row, 0, CellValue::Number(row as f64));
+        }
+        let value = |writes: &ShiftedCells, row, col| {
+            writes.get(&(row, col)).map(|cell| cell.as_ref().map(|c| c.value.clone()))
+        };
+
+        // Inserting one cell at (1, 0) pushes rows 1-2 of column 0 down; column 1 is untouched
+        assert_eq!(insert_extent(&grid, (1, 0, 1, 0), ShiftAxis::Vertical), 4);
+        let writes = plan_shift(&grid, (1, 0, 1, 0), ShiftAxis::Vertical, true).unwrap();
+        assert_eq!(value(&writes, 1, 0), Some(None));
+        assert_eq!(value(&writes, 3, 0), Some(Some(CellValue::Number(2.0))));
+        assert!(writes.keys().all(|&(_, col)| col == 0));
+
+        // Two cells would push the last value off the grid
+        assert_eq!(plan_shift(&grid, (1, 0, 2, 0), ShiftAxis::Vertical, true).err(), Some((2, 0)));
+
+        // Deleting (0, 0) shifts the column up
+        let writes = plan_shift(&grid, (0, 0, 0, 0), ShiftAxis::Vertical, false).unwrap();
+        assert_eq!(value(&writes, 0, 0), Some(Some(CellValue::Number(1.0))));
+        assert_eq!(value(&writes, 2, 0), Some(None));
+        assert_eq!(ShiftAxis::parse_delete("left").ok(), Some(ShiftAxis::Horizontal));
+        assert!(ShiftAxis::parse_insert("up").is_err());
+    }
+}
@@ -1,7 +1,59 @@
 use crate::core::{Grid, Viewport};
+use crate::GridError;
 
 const RESIZE_HANDLE_WIDTH: f32 = 5.0;
 
+/// Rows measured per column by AutoFitMode::Sample
+const AUTO_FIT_SAMPLE_ROWS: usize = 200;
+
+/// Column widths computed after loading data (auto_fit_on_load option)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AutoFitMode {
+    None,   // Keep the configured widths
+    Header, // Fit the header text only
+    Sample, // Fit the header and up to AUTO_FIT_SAMPLE_ROWS rows spread over the data
+    Full,   // Fit the header and every row
+}
+
+impl AutoFitMode {
+    pub fn parse(mode: &str) -> Result<Self, GridError> {
+        match mode.to_lowercase().as_str() {
+            "none" | "" => Ok(AutoFitMode::None),
+            "header" => Ok(AutoFitMode::Header),
+            "sample" => Ok(AutoFitMode::Sample),
+            "full" => Ok(AutoFitMode::Full),
+            other => Err(GridError::unknown_value("auto_fit_mode", other, &["header", "sample", "full", "none"])),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AutoFitMode::None => "none",
+            AutoFitMode::Header => "header",
+            AutoFitMode::Sample => "sample",
+            AutoFitMode::Full => "full",
+        }
+    }
+
+    /// Rows to measure in a grid of `row_count` rows
+    pub fn sample_rows(&self, row_count: usize) -> Vec<usize> {
+        match self {
+            AutoFitMode::None | AutoFitMode::Header => Vec::new(),
+            AutoFitMode::Sample if row_count > AUTO_FIT_SAMPLE_ROWS => {
+                (0..AUTO_FIT_SAMPLE_ROWS).map(|i| i * row_count / AUTO_FIT_SAMPLE_ROWS).collect()
+            }
+            AutoFitMode::Sample | AutoFitMode::Full => (0..row_count).collect(),
+        }
+    }
+}
+
+/// Column or row whose edge is under the pointer
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResizeHandle {
+    Column(usize),
+    Row(usize),
+}
+
 /// Column/Row resizing functionality for DataGrid
 pub struct ResizeState {
     pub is_resizing: bool,
@@ -31,6 +83,16 @@ impl ResizeState {
     /// Check if mouse is over a resize handle
     /// Returns: "col" for column resize, "row" for row resize, "none" otherwise
     pub fn check_resize_handle(&self, x: f32, y: f32, grid: &Grid, viewport: &Viewport) -> String {
+        match Self::handle_at(x, y, grid, viewport) {
+            Some(ResizeHandle::Column(_)) => "col".to_string(),
+            Some(ResizeHandle::Row(_)) => "row".to_string(),
+            None => "none".to_string(),
+        }
+    }
+
+    /// Resize handle (column right edge in the header, row bottom edge in the row header)
+    /// under canvas point (x, y); columns and rows that are not resizable have none
+    pub fn handle_at(x: f32, y: f32, grid: &Grid, viewport: &Viewport) -> Option<ResizeHandle> {
         // Column resize: only detect in column header area
         if y < grid.col_header_height {
             // Convert canvas x to grid x, considering scroll offset
@@ -40,7 +102,7 @@ impl ResizeState {
             let first_col = viewport.first_visible_col;
             let last_col = viewport.last_visible_col.min(grid.col_count().saturating_sub(1));
 
-            for col in first_col..=last_col {
+            for col in (first_col..=last_col).filter(|&col| grid.is_col_resizable(col)) {
                 let col_grid_x = grid.col_x_position(col);
                 let col_width = grid.col_width(col);
                 let col_right_edge = col_grid_x + col_width;
@@ -50,36 +112,32 @@ impl ResizeState {
 
                 // Check if mouse is near the right edge on canvas
                 if (x - canvas_edge_x).abs() < RESIZE_HANDLE_WIDTH && canvas_edge_x > grid.row_header_width {
-                    return "col".to_string();
+                    return Some(ResizeHandle::Column(col));
                 }
             }
         }
 
         // Row resize: only detect in row header area
         if x < grid.row_header_width {
-            // Convert canvas y to grid y, considering scroll offset
-            let _grid_y = y - grid.col_header_height + viewport.scroll_y;
-
             // Check visible rows only
             let first_row = viewport.first_visible_row;
             let last_row = viewport.last_visible_row.min(grid.row_count().saturating_sub(1));
 
-            for row in first_row..=last_row {
+            for row in (first_row..=last_row).filter(|&row| grid.row_limits(row).resizable) {
                 let row_grid_y = grid.row_y_position(row);
                 let row_height = grid.row_height(row);
-                let row_bottom_edge = row_grid_y + row_height;
 
                 // Calculate canvas position of the bottom edge
-                let canvas_edge_y = row_bottom_edge - viewport.scroll_y + grid.col_header_height;
+                let canvas_edge_y = (row_grid_y - viewport.scroll_y) as f32 + row_height + grid.col_header_height;
 
                 // Check if mouse is near the bottom edge on canvas
                 if (y - canvas_edge_y).abs() < RESIZE_HANDLE_WIDTH && canvas_edge_y > grid.col_header_height {
-                    return "row".to_string();
+                    return Some(ResizeHandle::Row(row));
                 }
             }
         }
 
-        "none".to_string()
+        None
     }
 
     /// Start column or row resize
@@ -89,7 +147,7 @@ impl ResizeState {
             let first_col = viewport.first_visible_col;
             let last_col = viewport.last_visible_col.min(grid.col_count().saturating_sub(1));
 
-            for col in first_col..=last_col {
+            for col in (first_col..=last_col).filter(|&col| grid.is_col_resizable(col)) {
                 let col_grid_x = grid.col_x_position(col);
                 let col_width = grid.col_width(col);
                 let col_right_edge = col_grid_x + col_width;
@@ -111,13 +169,12 @@ impl ResizeState {
             let first_row = viewport.first_visible_row;
             let last_row = viewport.last_visible_row.min(grid.row_count().saturating_sub(1));
 
-            for row in first_row..=last_row {
+            for row in (first_row..=last_row).filter(|&row| grid.row_limits(row).resizable) {
                 let row_grid_y = grid.row_y_position(row);
                 let row_height = grid.row_height(row);
-                let row_bottom_edge = row_grid_y + row_height;
 
                 // Calculate canvas position of the bottom edge
-                let canvas_edge_y = row_bottom_edge - viewport.scroll_y + grid.col_header_height;
+                let canvas_edge_y = (row_grid_y - viewport.scroll_y) as f32 + row_height + grid.col_header_height;
 
                 if (y - canvas_edge_y).abs() < RESIZE_HANDLE_WIDTH && canvas_edge_y > grid.col_header_height {
                     self.is_resizing = true;
@@ -141,11 +198,13 @@ impl ResizeState {
 
         if let Some(col) = self.resizing_column {
             let delta = x - self.resize_start_pos;
-            let new_width = (self.resize_start_size + delta).max(30.0); // Minimum 30px
+            let (min, max) = grid.col_width_limits(col);
+            let new_width = (self.resize_start_size + delta).max(30.0).clamp(min, max); // Minimum 30px
             grid.set_col_width(col, new_width);
         } else if let Some(row) = self.resizing_row {
             let delta = y - self.resize_start_pos;
-            let new_height = (self.resize_start_size + delta).max(20.0); // Minimum 20px
+            let (min, max) = grid.row_limits(row).height_limits();
+            let new_height = (self.resize_start_size + delta).max(20.0).clamp(min, max); // Minimum 20px
             grid.set_row_height(row, new_height);
         }
     }
@@ -167,3 +226,44 @@ impl ResizeState {
         self.is_resizing
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::RowLimits;
+
+    #[test]
+    fn test_resize_limits() {
+        let mut grid = Grid::new(2, 2);
+        grid.column_configs[0].max_width = Some(150.0);
+        grid.set_row_limits(0, RowLimits { min_height: Some(40.0), ..Default::default() });
+
+        // Dragging stops at the column's max width and the row's min height
+        let mut resize = ResizeState { is_resizing: true, resizing_column: Some(0), resize_start_size: 100.0, ..ResizeState::new() };
+        resize.update_resize(500.0, 0.0, &mut grid);
+        assert_eq!(grid.col_width(0), 150.0);
+        let mut resize = ResizeState { is_resizing: true, resizing_row: Some(0), resize_start_size: 25.0, ..ResizeState::new() };
+        resize.update_resize(0.0, -100.0, &mut grid);
+        assert_eq!(grid.row_height(0), 40.0);
+
+        grid.column_configs[1].resizable = false;
+        assert!(grid.is_col_resizable(0) && !grid.is_col_resizable(1));
+    }
+
+    #[test]
+    fn test_handle_at() {
+        let mut grid = Grid::new(3, 3);
+        let mut viewport = Viewport::new(800.0, 600.0);
+        viewport.update_visible_range(&grid);
+        let col_edge = grid.row_header_width + grid.col_width(0);
+        let row_edge = grid.col_header_height + grid.row_height(0);
+
+        assert_eq!(ResizeState::handle_at(col_edge, 5.0, &grid, &viewport), Some(ResizeHandle::Column(0)));
+        assert_eq!(ResizeState::handle_at(5.0, row_edge, &grid, &viewport), Some(ResizeHandle::Row(0)));
+        assert_eq!(ResizeState::handle_at(col_edge, row_edge + 10.0, &grid, &viewport), None); // Inside a cell
+
+        // A column that is not resizable has no handle
+        grid.column_configs[0].resizable = false;
+        assert_eq!(ResizeState::handle_at(col_edge, 5.0, &grid, &viewport), None);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::core::Grid;
+use std::collections::HashSet;
+
+/// Check the user's reduced-motion preference (prefers-reduced-motion: reduce)
+pub fn prefers_reduced_motion() -> bool {
+    web_sys::window()
+        .and_then(|window| window.match_media("(prefers-reduced-motion: reduce)").ok().flatten())
+        .is_some_and(|query| query.matches())
+}
+
+/// Default length of a row height transition
+pub const DEFAULT_ROW_ANIMATION_MS: f64 = 150.0;
+
+/// A row easing its extent change back to 0
+struct Transition {
+    row_id: usize,
+    from: f32,
+    start_time: Option<f64>, // Set by the first step, like scroll animations
+}
+
+/// Height transitions for rows that were inserted, deleted or had their detail panel
+/// expanded or collapsed: the change in vertical space is applied at once and then
+/// eased out through Grid::set_row_transition, so the rows below slide into place
+pub struct RowAnimation {
+    pub duration: f64, // Milliseconds; 0 turns animations off
+    transitions: Vec<Transition>,
+}
+
+impl RowAnimation {
+    pub fn new(duration: f64) -> Self {
+        Self { duration, transitions: Vec::new() }
+    }
+
+    /// Start (or extend) transitions for `(row_id, shift)` pairs: each row's extent is off by
+    /// `shift` pixels from its final value (negative = still to grow, positive = still to shrink)
+    /// Shifts for the same row add up
+    pub fn start_many(&mut self, grid: &mut Grid, shifts: &[(usize, f32)]) {
+        let mut started = HashSet::new();
+        for &(row_id, shift) in shifts {
+            grid.set_row_transition(row_id, grid.row_transition_by_id(row_id) + shift);
+            started.insert(row_id);
+        }
+        self.transitions.retain(|t| !started.contains(&t.row_id));
+        for row_id in started {
+            let from = grid.row_transition_by_id(row_id);
+            if from.abs() < 0.5 {
+                grid.set_row_transition(row_id, 0.0);
+            } else {
+                self.transitions.push(Transition { row_id, from, start_time: None });
+            }
+        }
+    }
+
+    /// Advance every transition; returns true while any is still running
+    pub fn step(&mut self, grid: &mut Grid, time: f64) -> bool {
+        let duration = self.duration.max(1.0);
+        self.transitions.retain_mut(|transition| {
+            let start = *transition.start_time.get_or_insert(time);
+            let t = ((time - start) / duration).clamp(0.0, 1.0) as f32;
+            let eased = 1.0 - (1.0 - t).powi(3); // ease-out cubic
+            let shift = if t >= 1.0 { 0.0 } else { transition.from * (1.0 - eased) };
+            grid.set_row_transition(transition.row_id, shift);
+            t < 1.0
+        });
+        !self.transitions.is_empty()
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.transitions.is_empty()
+    }
+
+    /// Jump every transition to its end
+    pub fn finish(&mut self, grid: &mut Grid) {
+        for transition in self.transitions.drain(..) {
+            grid.set_row_transition(transition.row_id, 0.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_transition_eases_out() {
+        let mut grid = Grid::new(3, 1);
+        let height = grid.row_height(1);
+        let id = grid.row_id(1).unwrap();
+        let mut animation = RowAnimation::new(100.0);
+
+        // An inserted row starts collapsed and grows back to its height
+        animation.start_many(&mut grid, &[(id, -height)]);
+        assert_eq!(grid.row_height(1), 0.0);
+        assert_eq!(grid.row_y_position(2), height as f64);
+        assert!(animation.step(&mut grid, 1000.0));
+        assert!(animation.step(&mut grid, 1050.0));
+        let halfway = grid.row_height(1);
+        assert!(halfway > height / 2.0 && halfway < height);
+
+        assert!(!animation.step(&mut grid, 1100.0));
+        assert_eq!(grid.row_height(1), height);
+        assert_eq!(grid.total_height(), 3.0 * height as f64);
+
+        // A deleted row leaves a gap below the row above it
+        animation.start_many(&mut grid, &[(id, height)]);
+        assert_eq!(grid.row_extent(1), 2.0 * height);
+        assert_eq!(grid.total_height(), 4.0 * height as f64);
+        animation.finish(&mut grid);
+        assert_eq!(grid.total_height(), 3.0 * height as f64);
+
+        // A batch adds up shifts for the same row (two rows deleted below it)
+        let first = grid.row_id(0).unwrap();
+        animation.start_many(&mut grid, &[(first, height), (first, height), (id, -height)]);
+        assert_eq!(grid.row_extent(0), 3.0 * height);
+        assert_eq!(grid.row_height(1), 0.0);
+        assert!(animation.is_active());
+        animation.finish(&mut grid);
+        assert_eq!(grid.total_height(), 3.0 * height as f64);
+    }
+}
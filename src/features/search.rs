@@ -1,24 +1,136 @@
 use crate::core::{cell::CellValue, Grid, Viewport};
 use crate::GridError;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::ops::Index;
+
+/// Search matches: an ordered list for next/previous navigation plus a column bitmap
+/// per matching row, so highlight checks while rendering are O(1) per visible cell
+#[derive(Default)]
+pub struct SearchResults {
+    ordered: Vec<(usize, usize)>,
+    rows: HashMap<usize, Vec<u64>>, // Row -> bitmap of matching columns
+}
+
+impl SearchResults {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a match (kept in insertion order for navigation; duplicates are ignored)
+    pub fn push(&mut self, cell: (usize, usize)) {
+        let (row, col) = cell;
+        let bits = self.rows.entry(row).or_default();
+        let (word, bit) = (col / 64, 1u64 << (col % 64));
+        if bits.len() <= word {
+            bits.resize(word + 1, 0);
+        }
+        if bits[word] & bit == 0 {
+            bits[word] |= bit;
+            self.ordered.push(cell);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.ordered.clear();
+        self.rows.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.ordered.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ordered.is_empty()
+    }
+
+    /// Position of a match in navigation order
+    pub fn position(&self, cell: (usize, usize)) -> Option<usize> {
+        if !self.contains(&cell) {
+            return None;
+        }
+        self.ordered.iter().position(|c| *c == cell)
+    }
+
+    /// Check if a cell is a match
+    pub fn contains(&self, cell: &(usize, usize)) -> bool {
+        let (row, col) = *cell;
+        self.rows
+            .get(&row)
+            .and_then(|bits| bits.get(col / 64))
+            .is_some_and(|word| word & (1u64 << (col % 64)) != 0)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, (usize, usize)> {
+        self.ordered.iter()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.ordered.capacity()
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.ordered.reserve(additional);
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.ordered.shrink_to_fit();
+        self.rows.shrink_to_fit();
+    }
+
+    /// Approximate heap size in bytes (for memory statistics)
+    pub fn memory_bytes(&self) -> usize {
+        self.ordered.len() * std::mem::size_of::<(usize, usize)>()
+            + self.rows.values().map(|bits| std::mem::size_of::<usize>() + bits.len() * 8).sum::<usize>()
+    }
+}
+
+impl Index<usize> for SearchResults {
+    type Output = (usize, usize);
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.ordered[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a SearchResults {
+    type Item = &'a (usize, usize);
+    type IntoIter = std::slice::Iter<'a, (usize, usize)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ordered.iter()
+    }
+}
+
+/// What produced the current search results (re-run after the data is replaced)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SearchKind {
+    Text,
+    Regex,
+    Modified,   // find_modified_cells
+    Duplicates, // find_duplicate_rows (key columns in SearchState::duplicate_keys)
+}
 
 /// Search and replace functionality for DataGrid
 pub struct SearchState {
     pub search_query: String,
-    pub search_results: Vec<(usize, usize)>,
+    pub search_results: SearchResults,
     pub current_search_index: Option<usize>,
     pub search_case_sensitive: bool,
     pub search_whole_word: bool,
+    pub search_kind: Option<SearchKind>,
+    pub duplicate_keys: Vec<usize>, // Key columns of the last find_duplicate_rows
 }
 
 impl Default for SearchState {
     fn default() -> Self {
         Self {
             search_query: String::new(),
-            search_results: Vec::new(),
+            search_results: SearchResults::new(),
             current_search_index: None,
             search_case_sensitive: false,
             search_whole_word: false,
+            search_kind: None,
+            duplicate_keys: Vec::new(),
         }
     }
 }
@@ -43,6 +155,7 @@ impl SearchState {
     ) -> usize {
         self.search_case_sensitive = case_sensitive;
         self.search_whole_word = whole_word;
+        self.search_kind = Some(SearchKind::Text);
         self.search_query = if case_sensitive {
             query.clone()
         } else {
@@ -148,6 +261,7 @@ impl SearchState {
         self.search_query = pattern;
         self.search_case_sensitive = case_sensitive;
         self.search_whole_word = false; // Not applicable for regex
+        self.search_kind = Some(SearchKind::Regex);
         self.search_results.clear();
         self.current_search_index = None;
 
@@ -175,11 +289,81 @@ impl SearchState {
         Regex::new(pattern).is_ok()
     }
 
+    /// Find all modified (edited) cells
+    pub fn find_modified_cells(&mut self, grid: &Grid) -> usize {
+        self.search_kind = Some(SearchKind::Modified);
+        self.search_results.clear();
+        self.current_search_index = None;
+
+        let mut cells: Vec<(usize, usize)> = grid.iter_cells().filter(|(_, cell)| cell.modified).map(|(pos, _)| pos).collect();
+        cells.sort_unstable();
+        for cell in cells {
+            self.search_results.push(cell);
+        }
+
+        if !self.search_results.is_empty() {
+            self.current_search_index = Some(0);
+        }
+        self.search_results.len()
+    }
+
+    /// Find rows whose values in `key_cols` repeat an earlier row; the key cells of every
+    /// row in a duplicate group (the first occurrence included) become the results
+    /// Returns the number of rows highlighted
+    pub fn find_duplicate_rows(&mut self, grid: &Grid, key_cols: &[usize]) -> usize {
+        self.search_kind = Some(SearchKind::Duplicates);
+        self.duplicate_keys = key_cols.to_vec();
+        self.search_results.clear();
+        self.current_search_index = None;
+
+        let mut rows: Vec<usize> = duplicate_row_groups(grid, key_cols).into_iter().flatten().collect();
+        rows.sort_unstable();
+        for &row in &rows {
+            for &col in key_cols {
+                self.search_results.push((row, col));
+            }
+        }
+
+        if !self.search_results.is_empty() {
+            self.current_search_index = Some(0);
+        }
+        rows.len()
+    }
+
+    /// Re-run the last search against the current data (e.g. after a state restore)
+    /// The current match is kept if that cell still matches
+    pub fn rerun(&mut self, grid: &Grid) -> usize {
+        let current = self.current_search_index.filter(|&idx| idx < self.search_results.len()).map(|idx| self.search_results[idx]);
+        match self.search_kind {
+            Some(SearchKind::Text) => {
+                self.search_text_with_options(self.search_query.clone(), self.search_case_sensitive, self.search_whole_word, grid);
+            }
+            Some(SearchKind::Regex) => {
+                if self.search_regex(self.search_query.clone(), self.search_case_sensitive, grid).is_err() {
+                    self.clear_search();
+                }
+            }
+            Some(SearchKind::Modified) => {
+                self.find_modified_cells(grid);
+            }
+            Some(SearchKind::Duplicates) => {
+                let key_cols: Vec<usize> = self.duplicate_keys.iter().copied().filter(|&col| col < grid.col_count()).collect();
+                self.find_duplicate_rows(grid, &key_cols);
+            }
+            None => return 0,
+        }
+        if let Some(idx) = current.and_then(|cell| self.search_results.position(cell)) {
+            self.current_search_index = Some(idx);
+        }
+        self.search_results.len()
+    }
+
     /// Clear search results
     pub fn clear_search(&mut self) {
         self.search_query.clear();
         self.search_results.clear();
         self.current_search_index = None;
+        self.search_kind = None;
     }
 
     /// Get search result count
@@ -318,8 +502,8 @@ pub fn ensure_cell_visible(row: usize, col: usize, grid: &Grid, viewport: &mut V
     // Check vertical visibility
     if cell_y < scroll_y {
         scroll_y = cell_y;
-    } else if cell_y + cell_height > scroll_y + viewport.canvas_height {
-        scroll_y = cell_y + cell_height - viewport.canvas_height;
+    } else if cell_y + cell_height as f64 > scroll_y + viewport.canvas_height as f64 {
+        scroll_y = cell_y + (cell_height - viewport.canvas_height) as f64;
     }
 
     // Update scroll if changed
@@ -328,3 +512,155 @@ pub fn ensure_cell_visible(row: usize, col: usize, grid: &Grid, viewport: &mut V
         viewport.update_visible_range(grid);
     }
 }
+
+/// A column whose display or internal name matches a find_column query
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnMatch {
+    pub col: usize,
+    pub score: f64, // 1.0 exact, 0.9 prefix, 0.75 substring, below 0.6 fuzzy
+}
+
+/// Find columns by display or internal name (case-insensitive), best matches first
+/// With `fuzzy`, names containing the query's characters in order also match
+/// ("cstnm" finds "customer_name"), scored by how tightly the characters cluster
+pub fn find_columns(grid: &Grid, query: &str, fuzzy: bool) -> Vec<ColumnMatch> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<ColumnMatch> = grid
+        .column_configs
+        .iter()
+        .enumerate()
+        .take(grid.col_count())
+        .filter_map(|(col, config)| {
+            let score = [&config.display_name, &config.internal_name]
+                .iter()
+                .filter_map(|name| name_score(&name.to_lowercase(), &query, fuzzy))
+                .fold(None, |best: Option<f64>, score| Some(best.map_or(score, |b| b.max(score))))?;
+            Some(ColumnMatch { col, score })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal).then(a.col.cmp(&b.col)));
+    matches
+}
+
+/// Score one lowercase name against a lowercase query
+fn name_score(name: &str, query: &str, fuzzy: bool) -> Option<f64> {
+    if name == query {
+        return Some(1.0);
+    }
+    if name.starts_with(query) {
+        return Some(0.9);
+    }
+    if name.contains(query) {
+        return Some(0.75);
+    }
+    if !fuzzy {
+        return None;
+    }
+
+    // Subsequence match: score by query length over the span the match covers
+    let name: Vec<char> = name.chars().collect();
+    let mut pos = 0;
+    let mut first = None;
+    for ch in query.chars() {
+        let offset = name[pos..].iter().position(|c| *c == ch)?;
+        first.get_or_insert(pos + offset);
+        pos += offset + 1;
+    }
+    let span = pos - first.unwrap_or(0);
+    Some(0.6 * query.chars().count() as f64 / span as f64)
+}
+
+/// Groups of rows with equal values in `key_cols`, each in row order (first occurrence
+/// first); only groups of two or more rows. Rows whose key cells are all empty are skipped.
+pub fn duplicate_row_groups(grid: &Grid, key_cols: &[usize]) -> Vec<Vec<usize>> {
+    let mut groups: HashMap<Vec<String>, Vec<usize>> = HashMap::new();
+    for row in 0..grid.row_count() {
+        let key: Vec<String> = key_cols.iter().map(|&col| grid.get_value_string(row, col)).collect();
+        if key.iter().all(String::is_empty) {
+            continue;
+        }
+        groups.entry(key).or_default().push(row);
+    }
+    let mut groups: Vec<Vec<usize>> = groups.into_values().filter(|rows| rows.len() > 1).collect();
+    groups.sort_unstable_by_key(|rows| rows[0]);
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ColumnConfig;
+
+    #[test]
+    fn test_search_results_bitmap() {
+        let mut results = SearchResults::new();
+        for cell in [(5, 2), (1, 130), (5, 2), (5, 64)] {
+            results.push(cell);
+        }
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[1], (1, 130));
+        assert!(results.contains(&(5, 64)) && results.contains(&(1, 130)));
+        assert!(!results.contains(&(5, 0)) && !results.contains(&(1, 2)) && !results.contains(&(2, 2)));
+
+        results.clear();
+        assert!(results.is_empty() && !results.contains(&(5, 2)));
+    }
+
+    #[test]
+    fn test_rerun_search_keeps_current_match() {
+        let mut grid = Grid::new(4, 1);
+        for (row, text) in ["apple", "pear", "apple pie", "plum"].into_iter().enumerate() {
+            grid.set_value(row, 0, CellValue::Text(text.to_string()));
+        }
+        let mut search = SearchState::new();
+        assert_eq!(search.search_text("APPLE".to_string(), &grid), 2);
+        search.search_next();
+
+        // Data replaced: a new match appears before the current one
+        grid.set_value(1, 0, CellValue::Text("apple tart".to_string()));
+        assert_eq!(search.rerun(&grid), 3);
+        assert_eq!(search.current_search_index, Some(2));
+        assert_eq!(search.search_results[2], (2, 0));
+
+        search.clear_search();
+        assert_eq!(search.rerun(&grid), 0);
+    }
+
+    #[test]
+    fn test_find_columns() {
+        let mut grid = Grid::new(1, 4);
+        let names = [("Customer Name", "customer_name"), ("Name", "name"), ("Created", "created_at"), ("Total", "total")];
+        for (col, (display, internal)) in names.iter().enumerate() {
+            grid.set_column_config(col, ColumnConfig::new(display.to_string(), internal.to_string()));
+        }
+
+        let cols = |matches: Vec<ColumnMatch>| matches.iter().map(|m| m.col).collect::<Vec<_>>();
+        assert_eq!(cols(find_columns(&grid, "name", false)), vec![1, 0]);
+        assert_eq!(cols(find_columns(&grid, "CREATED_AT", false)), vec![2]);
+        assert!(find_columns(&grid, "cstnm", false).is_empty());
+        assert_eq!(cols(find_columns(&grid, "cstnm", true)), vec![0]);
+        assert!(find_columns(&grid, " ", true).is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_rows() {
+        let mut grid = Grid::new(5, 2);
+        for (row, (name, city)) in [("a", "x"), ("b", "x"), ("a", "x"), ("", ""), ("a", "y")].into_iter().enumerate() {
+            grid.set_value(row, 0, CellValue::Text(name.to_string()));
+            grid.set_value(row, 1, CellValue::Text(city.to_string()));
+        }
+
+        assert_eq!(duplicate_row_groups(&grid, &[0, 1]), vec![vec![0, 2]]);
+        assert_eq!(duplicate_row_groups(&grid, &[0]), vec![vec![0, 2, 4]]);
+        assert_eq!(duplicate_row_groups(&grid, &[1]), vec![vec![0, 1, 2]]);
+
+        let mut search = SearchState::new();
+        assert_eq!(search.find_duplicate_rows(&grid, &[0, 1]), 2);
+        assert_eq!(search.search_results.iter().copied().collect::<Vec<_>>(), vec![(0, 0), (0, 1), (2, 0), (2, 1)]);
+    }
+}
@@ -1,9 +1,18 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+
+/// Header holding keyboard focus
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HeaderFocus {
+    Column(usize),
+    Row(usize),
+}
 
 /// Cell selection functionality for DataGrid
 pub struct SelectionState {
-    pub selected_cells: HashSet<(usize, usize)>,
+    pub selected_cells: HashSet<(usize, usize)>, // Change through the methods below so revision moves
     pub selection_anchor: Option<(usize, usize)>,
+    pub header_focus: Option<HeaderFocus>, // Keyboard focus in the column/row headers
+    revision: u64, // Incremented when the selected cells change
 }
 
 impl Default for SelectionState {
@@ -11,6 +20,8 @@ impl Default for SelectionState {
         Self {
             selected_cells: HashSet::new(),
             selection_anchor: None,
+            header_focus: None,
+            revision: 0,
         }
     }
 }
@@ -20,6 +31,16 @@ impl SelectionState {
         Self::default()
     }
 
+    /// Counter incremented whenever the selected cells change (cheap to compare per frame)
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Rows and columns containing the selection or the anchor cell (for header highlighting)
+    pub fn active_rows_and_cols(&self) -> (HashSet<usize>, HashSet<usize>) {
+        self.selected_cells.iter().chain(self.selection_anchor.iter()).map(|&(row, col)| (row, col)).unzip()
+    }
+
     /// Check if a cell is selected
     pub fn is_selected(&self, row: usize, col: usize) -> bool {
         self.selected_cells.contains(&(row, col))
@@ -27,16 +48,19 @@ impl SelectionState {
 
     /// Select a single cell (clears previous selection)
     pub fn select_single_cell(&mut self, row: usize, col: usize) {
+        self.revision += 1;
         // Clear all previous selections
         self.selected_cells.clear();
 
         // Add new selection
         self.selected_cells.insert((row, col));
         self.selection_anchor = Some((row, col));
+        self.header_focus = None;
     }
 
     /// Toggle cell selection (add/remove from selection)
     pub fn toggle_cell_selection(&mut self, row: usize, col: usize) {
+        self.revision += 1;
         if self.selected_cells.contains(&(row, col)) {
             // Remove from selection
             self.selected_cells.remove(&(row, col));
@@ -54,6 +78,7 @@ impl SelectionState {
     /// Select range from anchor to target cell
     pub fn select_range(&mut self, target_row: usize, target_col: usize, row_count: usize, col_count: usize) {
         if let Some((anchor_row, anchor_col)) = self.selection_anchor {
+            self.revision += 1;
             // Clear previous selection
             self.selected_cells.clear();
 
@@ -77,8 +102,19 @@ impl SelectionState {
         }
     }
 
+    /// Bounds (min_row, min_col, max_row, max_col) of the selection when it fills a rectangle
+    pub fn rectangle(&self) -> Option<(usize, usize, usize, usize)> {
+        let min_row = self.selected_cells.iter().map(|&(row, _)| row).min()?;
+        let max_row = self.selected_cells.iter().map(|&(row, _)| row).max()?;
+        let min_col = self.selected_cells.iter().map(|&(_, col)| col).min()?;
+        let max_col = self.selected_cells.iter().map(|&(_, col)| col).max()?;
+        let area = (max_row - min_row + 1) * (max_col - min_col + 1);
+        (self.selected_cells.len() == area).then_some((min_row, min_col, max_row, max_col))
+    }
+
     /// Clear all selections
     pub fn clear_selection(&mut self) {
+        self.revision += 1;
         self.selected_cells.clear();
     }
 
@@ -98,6 +134,7 @@ impl SelectionState {
 
     /// Select all cells (Ctrl+A)
     pub fn select_all(&mut self, row_count: usize, col_count: usize) {
+        self.revision += 1;
         self.selected_cells.clear();
 
         for row in 0..row_count {
@@ -115,6 +152,7 @@ impl SelectionState {
         if row >= row_count {
             return;
         }
+        self.revision += 1;
 
         self.selected_cells.clear();
 
@@ -125,11 +163,57 @@ impl SelectionState {
         self.selection_anchor = Some((row, 0));
     }
 
+    /// Columns whose every row is selected, in order
+    pub fn selected_columns(&self, row_count: usize) -> Vec<usize> {
+        if row_count == 0 {
+            return Vec::new();
+        }
+        let mut counts: HashMap<usize, usize> = HashMap::new();
+        for &(_, col) in &self.selected_cells {
+            *counts.entry(col).or_default() += 1;
+        }
+        let mut cols: Vec<usize> = counts.into_iter().filter(|&(_, n)| n == row_count).map(|(col, _)| col).collect();
+        cols.sort_unstable();
+        cols
+    }
+
+    /// Add a whole column to the selection, or remove it if it is already fully selected
+    /// (Ctrl+click on a column header)
+    pub fn toggle_col(&mut self, col: usize, row_count: usize, col_count: usize) {
+        if col >= col_count {
+            return;
+        }
+        self.revision += 1;
+        if self.selected_columns(row_count).contains(&col) {
+            self.selected_cells.retain(|&(_, c)| c != col);
+        } else {
+            self.selected_cells.extend((0..row_count).map(|row| (row, col)));
+        }
+        self.selection_anchor = Some((0, col));
+        self.header_focus = None;
+    }
+
+    /// Select whole columns from the anchor's column to target_col (Shift+click on a
+    /// column header); the anchor stays put so the range can be adjusted
+    pub fn select_col_range(&mut self, target_col: usize, row_count: usize, col_count: usize) {
+        let Some((_, anchor_col)) = self.selection_anchor else {
+            self.select_col(target_col, row_count, col_count);
+            return;
+        };
+        self.revision += 1;
+        self.selected_cells.clear();
+        for col in anchor_col.min(target_col)..=anchor_col.max(target_col).min(col_count.saturating_sub(1)) {
+            self.selected_cells.extend((0..row_count).map(|row| (row, col)));
+        }
+        self.header_focus = None;
+    }
+
     /// Select entire column
     pub fn select_col(&mut self, col: usize, row_count: usize, col_count: usize) {
         if col >= col_count {
             return;
         }
+        self.revision += 1;
 
         self.selected_cells.clear();
 
@@ -140,3 +224,46 @@ impl SelectionState {
         self.selection_anchor = Some((0, col));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_multi_select() {
+        let mut selection = SelectionState::new();
+        selection.select_col(1, 4, 6);
+        selection.toggle_col(4, 4, 6);
+        assert_eq!(selection.selected_columns(4), vec![1, 4]);
+
+        // Shift extends from the last clicked column; a partial column does not count
+        selection.select_col_range(2, 4, 6);
+        assert_eq!(selection.selected_columns(4), vec![2, 3, 4]);
+        selection.toggle_col(3, 4, 6);
+        selection.selected_cells.remove(&(0, 2));
+        assert_eq!(selection.selected_columns(4), vec![4]);
+    }
+
+    #[test]
+    fn test_rectangle() {
+        let mut selection = SelectionState::new();
+        assert_eq!(selection.rectangle(), None);
+        selection.select_single_cell(3, 1);
+        selection.select_range(1, 2, 10, 10);
+        assert_eq!(selection.rectangle(), Some((1, 1, 3, 2)));
+        selection.toggle_cell_selection(2, 2);
+        assert_eq!(selection.rectangle(), None);
+    }
+
+    #[test]
+    fn test_revision_follows_changes() {
+        let mut selection = SelectionState::new();
+        selection.select_range(1, 1, 10, 10); // No anchor: selects the cell
+        let before = selection.revision();
+        selection.select_single_cell(0, 1); // Same count, different cell
+        assert!(selection.revision() > before);
+        let before = selection.revision();
+        selection.select_col(20, 4, 6); // Out of range: nothing changes
+        assert_eq!(selection.revision(), before);
+    }
+}
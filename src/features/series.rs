@@ -0,0 +1,131 @@
+use crate::core::{CellValue, Grid, Viewport};
+use crate::features::selection::SelectionState;
+use crate::GridError;
+use serde_json::{json, Value};
+
+/// Which rows are included when extracting chart series
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SeriesScope {
+    All,       // Every row not hidden by filters/preview
+    Visible,   // Rows currently inside the viewport
+    Selection, // Rows containing at least one selected cell
+}
+
+impl SeriesScope {
+    /// Parse scope name ("all", "visible", "selection"); empty string means "all"
+    pub fn parse(scope: &str) -> Result<Self, GridError> {
+        match scope.to_lowercase().as_str() {
+            "" | "all" => Ok(SeriesScope::All),
+            "visible" => Ok(SeriesScope::Visible),
+            "selection" | "selected" => Ok(SeriesScope::Selection),
+            other => Err(GridError::unknown_value("series_scope", other, &["all", "visible", "selection"])),
+        }
+    }
+}
+
+/// Get the rows included in a scope, in display (sorted) order, skipping hidden rows
+pub fn series_rows(
+    grid: &Grid,
+    viewport: &Viewport,
+    selection: &SelectionState,
+    scope: SeriesScope,
+) -> Vec<usize> {
+    let row_count = grid.row_count();
+    if row_count == 0 {
+        return Vec::new();
+    }
+
+    let candidates: Vec<usize> = match scope {
+        SeriesScope::All => (0..row_count).collect(),
+        SeriesScope::Visible => {
+            let last = viewport.last_visible_row.min(row_count - 1);
+            (viewport.first_visible_row..=last).collect()
+        }
+        SeriesScope::Selection => {
+            let mut rows: Vec<usize> = selection.selected_cells.iter().map(|(row, _)| *row).collect();
+            rows.sort_unstable();
+            rows.dedup();
+            rows
+        }
+    };
+
+    candidates
+        .into_iter()
+        .filter(|row| *row < row_count && !grid.is_row_hidden(*row))
+        .collect()
+}
+
+/// Convert a cell value to a number for plotting
+/// Numeric text is parsed, booleans map to 1/0, anything else is None
+pub fn value_as_f64(value: &CellValue) -> Option<f64> {
+    match value {
+        CellValue::Number(n) => Some(*n),
+        CellValue::Boolean(b) => Some(if *b { 1.0 } else { 0.0 }),
+        CellValue::Text(s) => s.trim().parse::<f64>().ok(),
+        CellValue::Date(_) | CellValue::Empty => None,
+    }
+}
+
+/// Build aligned x/y series as JSON:
+/// {"rows": [...], "x": [...], "series": [{"col": 1, "name": "Sales", "values": [...]}]}
+/// X values keep their native type (labels, numbers, dates); y values are numbers or null
+pub fn series_json(grid: &Grid, rows: &[usize], x_col: usize, y_cols: &[usize]) -> Value {
+    let x: Vec<Value> = rows
+        .iter()
+        .map(|row| match grid.get_value(*row, x_col) {
+            CellValue::Number(n) => json!(n),
+            CellValue::Boolean(b) => json!(b),
+            CellValue::Text(s) | CellValue::Date(s) => json!(s),
+            CellValue::Empty => Value::Null,
+        })
+        .collect();
+
+    let series: Vec<Value> = y_cols
+        .iter()
+        .map(|col| {
+            let values: Vec<Option<f64>> = rows
+                .iter()
+                .map(|row| value_as_f64(&grid.get_value(*row, *col)))
+                .collect();
+            let name = grid
+                .get_column_config(*col)
+                .map(|c| c.display_name.clone())
+                .unwrap_or_else(|| Grid::column_index_to_letter(*col));
+
+            json!({ "col": col, "name": name, "values": values })
+        })
+        .collect();
+
+    json!({ "rows": rows, "x": x, "series": series })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_series_json() {
+        let mut grid = Grid::new(3, 2);
+        grid.set_value(0, 0, CellValue::Text("Jan".to_string()));
+        grid.set_value(0, 1, CellValue::Number(10.0));
+        grid.set_value(1, 0, CellValue::Text("Feb".to_string()));
+        grid.set_value(1, 1, CellValue::Text("n/a".to_string()));
+        grid.set_value(2, 0, CellValue::Text("Mar".to_string()));
+        grid.set_value(2, 1, CellValue::Text(" 7.5".to_string()));
+
+        let viewport = Viewport::new(800.0, 600.0);
+        let rows = series_rows(&grid, &viewport, &SelectionState::new(), SeriesScope::All);
+        assert_eq!(rows, vec![0, 1, 2]);
+
+        let json = series_json(&grid, &rows, 0, &[1]);
+        assert_eq!(json["x"], json!(["Jan", "Feb", "Mar"]));
+        assert_eq!(json["series"][0]["values"], json!([10.0, null, 7.5]));
+    }
+
+    #[test]
+    fn test_series_scope_parse() {
+        assert_eq!(SeriesScope::parse("").unwrap(), SeriesScope::All);
+        assert_eq!(SeriesScope::parse("Selection").unwrap(), SeriesScope::Selection);
+        assert!(SeriesScope::parse("page").is_err());
+    }
+}
@@ -0,0 +1,411 @@
+use crate::core::cell::CellBorder;
+use crate::core::{Cell, CellBorders, CellValue, Collation, ColumnConfig, DataType, Grid, NumberFormat, RowLimits, SortState, ValidationRule, WidthMode};
+use crate::features::locale;
+use crate::GridError;
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+
+/// Version of the snapshot JSON layout
+/// Version 1 (layout and values only) is still accepted by restore_state
+const STATE_VERSION: u64 = 2;
+
+/// Capture the grid's full state: size, column widths, row heights and IDs, frozen panes,
+/// column configs, header groups, sort and filter state, grid options, and cell
+/// values with styles, edit flags and borders
+/// Row heights are stored only where they differ from the default
+pub fn capture_state(grid: &Grid) -> Value {
+    let default_height = grid.default_row_height();
+    let row_heights: Vec<Value> = (0..grid.row_count())
+        .filter(|&row| grid.base_row_height(row) != default_height)
+        .map(|row| json!([row, grid.base_row_height(row)]))
+        .collect();
+
+    let mut cells: Vec<((usize, usize), Value)> = grid
+        .iter_cells()
+        .filter_map(|(pos, cell)| {
+            let style = cell_style_json(cell);
+            if cell.value.is_empty() && style.is_empty() {
+                return None;
+            }
+            let mut entry = vec![json!(pos.0), json!(pos.1), value_to_json(&cell.value)];
+            if !style.is_empty() {
+                entry.push(Value::Object(style));
+            }
+            Some((pos, Value::Array(entry)))
+        })
+        .collect();
+    cells.sort_by_key(|(pos, _)| *pos);
+
+    let mut borders: Vec<((usize, usize), Value)> = grid
+        .iter_borders()
+        .map(|(pos, borders)| (pos, json!([pos.0, pos.1, borders_to_json(borders)])))
+        .collect();
+    borders.sort_by_key(|(pos, _)| *pos);
+
+    let mut filtered_rows: Vec<usize> = grid.filtered_rows().iter().copied().collect();
+    filtered_rows.sort_unstable();
+
+    json!({
+        "version": STATE_VERSION,
+        "rows": grid.row_count(),
+        "cols": grid.col_count(),
+        "frozen_rows": grid.frozen_rows,
+        "frozen_cols": grid.frozen_cols,
+        "col_widths": (0..grid.col_count()).map(|col| grid.base_col_width(col)).collect::<Vec<_>>(),
+        "row_heights": row_heights,
+        "row_ids": (0..grid.row_count()).filter_map(|row| grid.row_id(row)).collect::<Vec<_>>(),
+        "readonly_rows": grid.readonly_rows(),
+        "hidden_rows": grid.hidden_rows(),
+        "row_limits": grid.limited_rows().into_iter()
+            .map(|(row, limits)| json!([row, limits.min_height, limits.max_height, limits.resizable]))
+            .collect::<Vec<_>>(),
+        "columns": grid.column_configs.iter().map(column_to_json).collect::<Vec<_>>(),
+        "header": {
+            "row_height": grid.header_row_height,
+            "groups": grid.column_groups.iter()
+                .map(|g| json!([g.label, g.start_col, g.end_col, g.level]))
+                .collect::<Vec<_>>(),
+        },
+        "sort": grid.sort_state().to_json(),
+        "filtered_rows": filtered_rows,
+        "options": {
+            "readonly": grid.readonly,
+            "unique_strict": grid.unique_strict,
+            "show_grid_lines": grid.show_grid_lines,
+            "alternate_row_colors": grid.alternate_row_colors,
+        },
+        "cells": cells.into_iter().map(|(_, entry)| entry).collect::<Vec<_>>(),
+        "borders": borders.into_iter().map(|(_, entry)| entry).collect::<Vec<_>>(),
+    })
+}
+
+/// Replace the grid's state with one produced by `capture_state`
+/// Parts missing from the state (e.g. in version 1) are reset to defaults
+pub fn restore_state(grid: &mut Grid, state: &Value) -> Result<(), GridError> {
+    let version = state["version"].as_u64().unwrap_or(0);
+    if version == 0 || version > STATE_VERSION {
+        return Err(GridError::OperationError {
+            message: locale::format("error.state_version", &[("version", &state["version"])]),
+        });
+    }
+    let (Some(rows), Some(cols)) = (state["rows"].as_u64(), state["cols"].as_u64()) else {
+        return Err(GridError::OperationError { message: locale::text("error.state_size") });
+    };
+
+    grid.clear();
+    grid.resize(rows as usize, cols as usize);
+    grid.frozen_rows = (state["frozen_rows"].as_u64().unwrap_or(0) as usize).min(grid.row_count());
+    grid.frozen_cols = (state["frozen_cols"].as_u64().unwrap_or(0) as usize).min(grid.col_count());
+
+    let default_height = grid.default_row_height();
+    for row in 0..grid.row_count() {
+        if grid.row_height(row) != default_height {
+            grid.set_row_height(row, default_height);
+        }
+    }
+    for entry in state["row_heights"].as_array().into_iter().flatten() {
+        if let (Some(row), Some(height)) = (entry[0].as_u64(), entry[1].as_f64()) {
+            grid.set_row_height(row as usize, height as f32);
+        }
+    }
+    if let Some(ids) = state["row_ids"].as_array() {
+        grid.set_row_ids(ids.iter().filter_map(|id| id.as_u64().map(|id| id as usize)).collect());
+    }
+    for row in state["readonly_rows"].as_array().into_iter().flatten().filter_map(|row| row.as_u64()) {
+        grid.set_row_readonly(row as usize, true);
+    }
+    for row in state["hidden_rows"].as_array().into_iter().flatten().filter_map(|row| row.as_u64()) {
+        grid.set_row_visible(row as usize, false);
+    }
+    for entry in state["row_limits"].as_array().into_iter().flatten() {
+        if let Some(row) = entry[0].as_u64() {
+            let limits = RowLimits {
+                min_height: entry[1].as_f64().map(|h| h as f32),
+                max_height: entry[2].as_f64().map(|h| h as f32),
+                resizable: entry[3].as_bool().unwrap_or(true),
+            };
+            grid.set_row_limits(row as usize, limits);
+        }
+    }
+    for (col, width) in state["col_widths"].as_array().into_iter().flatten().enumerate() {
+        if let Some(width) = width.as_f64() {
+            grid.set_col_width(col, width as f32);
+        }
+    }
+
+    for col in 0..grid.col_count() {
+        grid.column_configs[col] = match state["columns"].get(col) {
+            Some(config) => column_from_json(config, col),
+            None => ColumnConfig::new(Grid::get_col_name(col), format!("col_{}", col)),
+        };
+    }
+
+    grid.clear_column_groups();
+    if let Some(height) = state["header"]["row_height"].as_f64() {
+        grid.set_header_row_height(height as f32);
+    }
+    for group in state["header"]["groups"].as_array().into_iter().flatten() {
+        if let (Some(label), Some(start), Some(end), Some(level)) =
+            (group[0].as_str(), group[1].as_u64(), group[2].as_u64(), group[3].as_u64())
+        {
+            grid.add_column_group(label.to_string(), start as usize, end as usize, level as usize);
+        }
+    }
+
+    let sort = &state["sort"];
+    grid.set_sort_state(&SortState {
+        column: sort["column"].as_u64().map(|c| c as usize),
+        ascending: sort["ascending"].as_bool().unwrap_or(true),
+        columns: sort["columns"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|key| Some((key[0].as_u64()? as usize, key[1].as_bool()?)))
+            .collect(),
+    });
+    let filtered_rows: HashSet<usize> = state["filtered_rows"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|row| row.as_u64().map(|r| r as usize))
+        .filter(|&row| row < grid.row_count())
+        .collect();
+    grid.set_filtered_rows(filtered_rows);
+
+    let options = &state["options"];
+    grid.readonly = options["readonly"].as_bool().unwrap_or(false);
+    grid.unique_strict = options["unique_strict"].as_bool().unwrap_or(false);
+    grid.show_grid_lines = options["show_grid_lines"].as_bool().unwrap_or(true);
+    grid.alternate_row_colors = options["alternate_row_colors"].as_bool().unwrap_or(false);
+
+    for entry in state["cells"].as_array().into_iter().flatten() {
+        let (Some(row), Some(col)) = (entry[0].as_u64(), entry[1].as_u64()) else { continue };
+        let Some(value) = value_from_json(&entry[2]) else { continue };
+        let mut cell = Cell::new(value);
+        apply_cell_style(&mut cell, &entry[3]);
+        grid.set_cell(row as usize, col as usize, cell);
+    }
+
+    for entry in state["borders"].as_array().into_iter().flatten() {
+        let (Some(row), Some(col)) = (entry[0].as_u64(), entry[1].as_u64()) else { continue };
+        let (row, col) = (row as usize, col as usize);
+        if row < grid.row_count() && col < grid.col_count() {
+            grid.set_cell_borders_at(row, col, borders_from_json(&entry[2]));
+        }
+    }
+
+    Ok(())
+}
+
+/// Encode a cell value (dates as {"date": ...} to tell them apart from text)
+pub fn value_to_json(value: &CellValue) -> Value {
+    match value {
+        CellValue::Empty => Value::Null,
+        CellValue::Text(text) => json!(text),
+        CellValue::Number(n) => json!(n),
+        CellValue::Boolean(b) => json!(b),
+        CellValue::Date(date) => json!({ "date": date }),
+    }
+}
+
+/// Decode a value written by `value_to_json`
+pub fn value_from_json(value: &Value) -> Option<CellValue> {
+    match value {
+        Value::Null => Some(CellValue::Empty),
+        Value::String(text) => Some(CellValue::Text(text.clone())),
+        Value::Number(n) => Some(CellValue::Number(n.as_f64().unwrap_or(0.0))),
+        Value::Bool(b) => Some(CellValue::Boolean(*b)),
+        Value::Object(obj) => obj.get("date").and_then(|d| d.as_str()).map(|date| CellValue::Date(date.to_string())),
+        _ => None,
+    }
+}
+
+/// Non-default style and edit flags of a cell
+fn cell_style_json(cell: &Cell) -> Map<String, Value> {
+    let mut style = Map::new();
+    if let Some(bg) = cell.bg_color {
+        style.insert("bg".to_string(), json!(bg));
+    }
+    if let Some(fg) = cell.fg_color {
+        style.insert("fg".to_string(), json!(fg));
+    }
+    if cell.font_bold {
+        style.insert("bold".to_string(), json!(true));
+    }
+    if cell.font_italic {
+        style.insert("italic".to_string(), json!(true));
+    }
+    if !cell.editable {
+        style.insert("editable".to_string(), json!(false));
+    }
+    if cell.modified {
+        style.insert("modified".to_string(), json!(true));
+        if let Some(original) = &cell.original {
+            style.insert("original".to_string(), value_to_json(original));
+        }
+    }
+    style
+}
+
+/// Apply a style written by `cell_style_json` (missing keys keep the defaults)
+fn apply_cell_style(cell: &mut Cell, style: &Value) {
+    cell.bg_color = style["bg"].as_u64().map(|c| c as u32);
+    cell.fg_color = style["fg"].as_u64().map(|c| c as u32);
+    cell.font_bold = style["bold"].as_bool().unwrap_or(false);
+    cell.font_italic = style["italic"].as_bool().unwrap_or(false);
+    cell.editable = style["editable"].as_bool().unwrap_or(true);
+    cell.modified = style["modified"].as_bool().unwrap_or(false);
+    cell.original = if cell.modified { style.get("original").and_then(value_from_json) } else { None };
+}
+
+fn borders_to_json(borders: &CellBorders) -> Value {
+    let side = |border: &Option<CellBorder>| border.as_ref().map(|b| json!([b.color, b.width]));
+    json!({
+        "top": side(&borders.top),
+        "right": side(&borders.right),
+        "bottom": side(&borders.bottom),
+        "left": side(&borders.left),
+    })
+}
+
+fn borders_from_json(value: &Value) -> CellBorders {
+    let side = |side: &Value| -> Option<CellBorder> {
+        Some(CellBorder { color: side[0].as_u64()? as u32, width: side[1].as_f64()? as f32 })
+    };
+    CellBorders {
+        top: side(&value["top"]),
+        right: side(&value["right"]),
+        bottom: side(&value["bottom"]),
+        left: side(&value["left"]),
+    }
+}
+
+fn column_to_json(config: &ColumnConfig) -> Value {
+    json!({
+        "display_name": config.display_name,
+        "internal_name": config.internal_name,
+        "width": config.width,
+        "data_type": config.data_type.as_str(),
+        "editable": config.editable,
+        "visible": config.visible,
+        "sortable": config.sortable,
+        "filterable": config.filterable,
+        "validation_pattern": config.validation_pattern,
+        "validation_message": config.validation_message,
+        "validation_rule": config.validation_rule.as_ref().map(ValidationRule::to_json),
+        "unique": config.unique,
+        "number_format": config.number_format.as_ref().map(NumberFormat::to_json),
+        "padding": config.padding,
+        "collation": config.collation.as_str(),
+        "collation_locale": config.collation_locale,
+        "sort_order": config.sort_order,
+        "width_mode": config.width_mode.as_str(),
+        "flex": config.flex,
+        "min_width": config.min_width,
+        "max_width": config.max_width,
+        "resizable": config.resizable,
+    })
+}
+
+fn column_from_json(value: &Value, col: usize) -> ColumnConfig {
+    let mut config = ColumnConfig::new(
+        value["display_name"].as_str().map(str::to_string).unwrap_or_else(|| Grid::get_col_name(col)),
+        value["internal_name"].as_str().map(str::to_string).unwrap_or_else(|| format!("col_{}", col)),
+    );
+    if let Some(width) = value["width"].as_f64() {
+        config.width = width as f32;
+    }
+    config.data_type = DataType::parse(value["data_type"].as_str().unwrap_or("text"));
+    config.editable = value["editable"].as_bool().unwrap_or(true);
+    config.visible = value["visible"].as_bool().unwrap_or(true);
+    config.sortable = value["sortable"].as_bool().unwrap_or(true);
+    config.filterable = value["filterable"].as_bool().unwrap_or(true);
+    config.validation_pattern = value["validation_pattern"].as_str().map(str::to_string);
+    if let Some(message) = value["validation_message"].as_str() {
+        config.validation_message = message.to_string();
+    }
+    config.validation_rule = value.get("validation_rule").and_then(|rule| ValidationRule::from_json(rule).ok());
+    config.unique = value["unique"].as_bool().unwrap_or(false);
+    config.number_format = value.get("number_format").filter(|f| f.is_object()).map(NumberFormat::from_json);
+    config.padding = value["padding"].as_f64().map(|p| p as f32);
+    config.collation = value["collation"].as_str().and_then(|c| Collation::parse(c).ok()).unwrap_or_default();
+    config.collation_locale = value["collation_locale"].as_str().map(str::to_string);
+    config.sort_order = value["sort_order"]
+        .as_array()
+        .map(|values| values.iter().filter_map(|v| v.as_str().map(str::to_string)).collect());
+    config.width_mode = value["width_mode"].as_str().and_then(|m| WidthMode::parse(m).ok()).unwrap_or(WidthMode::Fixed);
+    config.flex = value["flex"].as_f64().and_then(|weight| WidthMode::flex_weight(weight).ok()).unwrap_or(1.0);
+    config.min_width = value["min_width"].as_f64().map(|w| w as f32);
+    config.max_width = value["max_width"].as_f64().map(|w| w as f32);
+    config.resizable = value["resizable"].as_bool().unwrap_or(true);
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_restore_round_trip() {
+        let mut grid = Grid::new(4, 3);
+        grid.set_value(0, 0, CellValue::Text("name".to_string()));
+        grid.set_value(1, 1, CellValue::Number(2.5));
+        grid.set_value(2, 2, CellValue::Date("2024-01-31".to_string()));
+        grid.set_value(3, 0, CellValue::Boolean(true));
+        grid.set_col_width(1, 180.0);
+        grid.set_row_height(2, 40.0);
+        grid.frozen_rows = 1;
+
+        let state = capture_state(&grid);
+        assert_eq!(state["row_heights"], json!([[2, 40.0]]));
+
+        let mut restored = Grid::new(1, 1);
+        restore_state(&mut restored, &state).unwrap();
+        assert_eq!(restored.row_count(), 4);
+        assert_eq!(restored.col_width(1), 180.0);
+        assert_eq!(restored.row_height(2), 40.0);
+        assert_eq!(restored.frozen_rows, 1);
+        assert_eq!(restored.get_value(1, 1), CellValue::Number(2.5));
+        assert_eq!(restored.get_value(2, 2), CellValue::Date("2024-01-31".to_string()));
+        assert_eq!(restored.get_value(3, 0), CellValue::Boolean(true));
+
+        assert!(restore_state(&mut restored, &json!({ "version": 99 })).is_err());
+    }
+
+    #[test]
+    fn test_round_trip_styles_columns_and_view_state() {
+        let mut grid = Grid::new(3, 2);
+        grid.set_value(0, 0, CellValue::Number(2.0));
+        grid.set_value(1, 0, CellValue::Number(1.0));
+        grid.get_cell_mut(1, 0).unwrap().font_bold = true;
+        grid.set_cell(2, 1, Cell { bg_color: Some(0xff0000ff), ..Cell::empty() });
+        grid.set_cell_borders_at(0, 1, CellBorders { top: Some(CellBorder { color: 0x000000ff, width: 2.0 }), ..Default::default() });
+        grid.column_configs[0].data_type = DataType::Number;
+        grid.set_column_number_format(0, Some(NumberFormat::for_locale("de-DE")));
+        grid.add_column_group("Group".to_string(), 0, 1, 0);
+        grid.sort_by_column(0, true);
+        grid.set_filtered_rows([2].into_iter().collect());
+        grid.set_row_visible(1, false);
+        grid.set_col_visible(1, false);
+        grid.set_row_limits(2, RowLimits { max_height: Some(40.0), resizable: false, ..Default::default() });
+        grid.column_configs[0].resizable = false;
+
+        let state = capture_state(&grid);
+        let mut restored = Grid::new(1, 1);
+        restore_state(&mut restored, &state).unwrap();
+
+        assert!(restored.get_cell(0, 0).unwrap().font_bold);
+        assert_eq!(restored.get_cell(2, 1).unwrap().bg_color, Some(0xff0000ff));
+        assert_eq!(restored.get_cell_borders(0, 1).unwrap().top.as_ref().unwrap().width, 2.0);
+        assert_eq!(restored.column_configs[0].data_type, DataType::Number);
+        assert_eq!(restored.column_configs[0].number_format, Some(NumberFormat::for_locale("de-DE")));
+        assert_eq!(restored.header_levels, 2);
+        assert_eq!(restored.sort_state(), grid.sort_state());
+        assert!(restored.is_row_filtered(2));
+        assert_eq!(restored.hidden_rows(), vec![1]);
+        assert_eq!(restored.row_limits(2).max_height, Some(40.0));
+        assert!(!restored.is_col_resizable(0));
+        assert_eq!(restored.base_col_width(1), grid.base_col_width(1)); // Hidden, width kept
+        assert_eq!(capture_state(&restored), state);
+    }
+}
@@ -0,0 +1,337 @@
+use crate::features::batch::BatchReport;
+use crate::features::locale;
+use crate::GridError;
+
+/// Text format of a streamed load
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StreamFormat {
+    Csv,       // RFC 4180: quoted fields may contain delimiters, quotes ("") and line breaks
+    Tsv,       // Same rules with a tab delimiter
+    JsonLines, // One JSON array or object per line
+    Json,      // A single JSON array of row arrays or objects
+}
+
+impl StreamFormat {
+    pub fn parse(format: &str) -> Result<Self, GridError> {
+        match format {
+            "csv" => Ok(StreamFormat::Csv),
+            "tsv" => Ok(StreamFormat::Tsv),
+            "jsonl" | "ndjson" => Ok(StreamFormat::JsonLines),
+            "json" => Ok(StreamFormat::Json),
+            _ => Err(GridError::unknown_value("stream_format", format, &["csv", "tsv", "jsonl", "json"])),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StreamFormat::Csv => "csv",
+            StreamFormat::Tsv => "tsv",
+            StreamFormat::JsonLines => "jsonl",
+            StreamFormat::Json => "json",
+        }
+    }
+}
+
+/// One record (row) produced by the parser
+#[derive(Debug, PartialEq)]
+pub enum StreamRecord {
+    Fields(Vec<String>),     // CSV/TSV fields
+    Json(serde_json::Value), // JSON row (array or object)
+    Invalid(String),         // JSON row that failed to parse
+}
+
+/// Incremental parser: chunks may end anywhere, including inside a quoted field, a JSON
+/// string or a multi-byte UTF-8 character; the unfinished part is carried to the next chunk
+#[derive(Debug)]
+pub struct StreamParser {
+    format: StreamFormat,
+    delimiter: char,
+    carry: Vec<u8>,  // Incomplete UTF-8 sequence at the end of the last chunk
+    started: bool,   // Past the byte order mark check
+    // CSV/TSV state
+    field: String,
+    record: Vec<String>,
+    in_quotes: bool,
+    quote_seen: bool, // Quote inside a quoted field: an escaped quote or the closing one
+    skip_lf: bool,    // Last record ended with CR (a following LF belongs to it)
+    // JSON state
+    item: String,
+    depth: usize, // Bracket depth inside the current item
+    in_string: bool,
+    escaped: bool,
+    array_open: bool,
+    array_closed: bool,
+}
+
+impl StreamParser {
+    pub fn new(format: StreamFormat, delimiter: Option<char>) -> Self {
+        let default_delimiter = if format == StreamFormat::Tsv { '\t' } else { ',' };
+        Self {
+            format,
+            delimiter: delimiter.unwrap_or(default_delimiter),
+            carry: Vec::new(),
+            started: false,
+            field: String::new(),
+            record: Vec::new(),
+            in_quotes: false,
+            quote_seen: false,
+            skip_lf: false,
+            item: String::new(),
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            array_open: false,
+            array_closed: false,
+        }
+    }
+
+    pub fn format(&self) -> StreamFormat {
+        self.format
+    }
+
+    /// Parse a chunk of bytes and return the records it completed
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<StreamRecord> {
+        self.carry.extend_from_slice(chunk);
+        let complete = self.carry.len() - incomplete_utf8_tail(&self.carry);
+        let text = String::from_utf8_lossy(&self.carry[..complete]).into_owned();
+        self.carry.drain(..complete);
+        self.process(&text)
+    }
+
+    fn process(&mut self, text: &str) -> Vec<StreamRecord> {
+        let mut chars = text.chars().peekable();
+        if !self.started && chars.peek().is_some() {
+            self.started = true;
+            chars.next_if_eq(&'\u{feff}');
+        }
+
+        let mut records = Vec::new();
+        for c in chars {
+            let record = match self.format {
+                StreamFormat::Csv | StreamFormat::Tsv => self.csv_char(c).map(StreamRecord::Fields),
+                StreamFormat::JsonLines => self.json_line_char(c),
+                StreamFormat::Json => self.json_array_char(c),
+            };
+            records.extend(record);
+        }
+        records
+    }
+
+    /// Flush the last record at the end of the stream
+    pub fn finish(&mut self) -> Vec<StreamRecord> {
+        // A truncated character at the very end becomes a replacement character
+        let tail = std::mem::take(&mut self.carry);
+        let mut records = self.process(&String::from_utf8_lossy(&tail));
+
+        match self.format {
+            StreamFormat::Csv | StreamFormat::Tsv => {
+                if !self.field.is_empty() || !self.record.is_empty() || self.in_quotes {
+                    records.extend(self.end_record().map(StreamRecord::Fields));
+                }
+                self.in_quotes = false;
+                self.quote_seen = false;
+            }
+            StreamFormat::JsonLines => records.extend(self.end_json_item()),
+            StreamFormat::Json => {
+                if !self.item.trim().is_empty() || (self.array_open && !self.array_closed) {
+                    records.push(StreamRecord::Invalid("Unexpected end of JSON data".to_string()));
+                }
+                self.item.clear();
+            }
+        }
+        records
+    }
+
+    fn csv_char(&mut self, c: char) -> Option<Vec<String>> {
+        if self.in_quotes {
+            if self.quote_seen {
+                self.quote_seen = false;
+                if c == '"' {
+                    self.field.push('"');
+                    return None;
+                }
+                self.in_quotes = false; // Closing quote: handle c as unquoted text below
+            } else {
+                if c == '"' {
+                    self.quote_seen = true;
+                } else {
+                    self.field.push(c);
+                }
+                return None;
+            }
+        }
+
+        if std::mem::take(&mut self.skip_lf) && c == '\n' {
+            return None;
+        }
+        match c {
+            '"' if self.field.is_empty() => self.in_quotes = true,
+            '\r' => {
+                self.skip_lf = true;
+                return self.end_record();
+            }
+            '\n' => return self.end_record(),
+            c if c == self.delimiter => self.record.push(std::mem::take(&mut self.field)),
+            c => self.field.push(c),
+        }
+        None
+    }
+
+    /// Complete the current CSV record (blank lines produce none)
+    fn end_record(&mut self) -> Option<Vec<String>> {
+        self.record.push(std::mem::take(&mut self.field));
+        let record = std::mem::take(&mut self.record);
+        (record.len() > 1 || !record[0].is_empty()).then_some(record)
+    }
+
+    fn json_line_char(&mut self, c: char) -> Option<StreamRecord> {
+        if c == '\n' {
+            return self.end_json_item();
+        }
+        self.item.push(c);
+        None
+    }
+
+    fn json_array_char(&mut self, c: char) -> Option<StreamRecord> {
+        if !self.array_open {
+            if c == '[' {
+                self.array_open = true;
+            } else if !c.is_whitespace() && !self.array_closed {
+                self.array_closed = true; // Report once, then ignore the rest
+                return Some(StreamRecord::Invalid("JSON data must be an array of rows".to_string()));
+            }
+            return None;
+        }
+        if self.array_closed {
+            return None;
+        }
+
+        if self.in_string {
+            self.item.push(c);
+            if self.escaped {
+                self.escaped = false;
+            } else if c == '\\' {
+                self.escaped = true;
+            } else if c == '"' {
+                self.in_string = false;
+            }
+            return None;
+        }
+
+        match c {
+            ',' | ']' if self.depth == 0 => {
+                self.array_closed = c == ']';
+                return self.end_json_item();
+            }
+            '"' => self.in_string = true,
+            '[' | '{' => self.depth += 1,
+            ']' | '}' => self.depth = self.depth.saturating_sub(1),
+            _ => {}
+        }
+        self.item.push(c);
+        None
+    }
+
+    /// Parse the collected JSON text of one row (whitespace-only items produce none)
+    fn end_json_item(&mut self) -> Option<StreamRecord> {
+        let item = std::mem::take(&mut self.item);
+        let item = item.trim();
+        if item.is_empty() {
+            return None;
+        }
+        Some(match serde_json::from_str(item) {
+            Ok(value) => StreamRecord::Json(value),
+            Err(e) => StreamRecord::Invalid(locale::format("error.stream_row", &[("error", &e)])),
+        })
+    }
+}
+
+/// Number of trailing bytes that start a UTF-8 character not yet complete
+fn incomplete_utf8_tail(bytes: &[u8]) -> usize {
+    for back in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - back];
+        if byte & 0xC0 != 0x80 {
+            let needed = match byte {
+                0xC0..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF7 => 4,
+                _ => 1,
+            };
+            return if needed > back { back } else { 0 };
+        }
+    }
+    0
+}
+
+/// A streamed load in progress (begin_stream_load .. end_stream_load)
+pub struct StreamLoad {
+    pub parser: StreamParser,
+    pub header: bool,               // First CSV record names the columns
+    pub total_bytes: Option<usize>, // Expected size, when the host knows it (for the progress ratio)
+    pub bytes_received: usize,
+    pub records: usize,     // Records parsed (the index used in the report)
+    pub rows_loaded: usize, // Grid rows filled
+    pub report: BatchReport,
+}
+
+impl StreamLoad {
+    pub fn new(parser: StreamParser, header: bool, total_bytes: Option<usize>) -> Self {
+        Self { parser, header, total_bytes, bytes_received: 0, records: 0, rows_loaded: 0, report: BatchReport::new() }
+    }
+
+    /// Progress info: bytes and rows so far, and the completed ratio when the size is known
+    pub fn progress_json(&self) -> serde_json::Value {
+        let ratio = self
+            .total_bytes
+            .filter(|&total| total > 0)
+            .map(|total| (self.bytes_received as f64 / total as f64).min(1.0));
+        serde_json::json!({
+            "format": self.parser.format().as_str(),
+            "bytes_received": self.bytes_received,
+            "total_bytes": self.total_bytes,
+            "progress": ratio,
+            "rows_loaded": self.rows_loaded,
+            "skipped": self.report.skipped,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(values: &[&str]) -> StreamRecord {
+        StreamRecord::Fields(values.iter().map(|v| v.to_string()).collect())
+    }
+
+    #[test]
+    fn test_stream_parser_chunk_boundaries() {
+        // Quoted field with an escaped quote, a delimiter and a line break, and a
+        // multi-byte character, split across chunks at awkward points
+        let data = "\u{feff}name,note\r\n\"Smith, J\",\"said \"\"hi\"\"\nbye\"\r\nÉric,plain\n\n".as_bytes();
+        let mut parser = StreamParser::new(StreamFormat::Csv, None);
+        let mut records = Vec::new();
+        for chunk in data.chunks(3) {
+            records.extend(parser.feed(chunk));
+        }
+        records.extend(parser.finish());
+        assert_eq!(
+            records,
+            vec![fields(&["name", "note"]), fields(&["Smith, J", "said \"hi\"\nbye"]), fields(&["Éric", "plain"])]
+        );
+
+        // Last record without a trailing newline
+        let mut parser = StreamParser::new(StreamFormat::Tsv, None);
+        assert_eq!(parser.feed(b"a\tb\nc"), vec![fields(&["a", "b"])]);
+        assert_eq!(parser.finish(), vec![fields(&["c"])]);
+
+        let mut parser = StreamParser::new(StreamFormat::Json, None);
+        let mut records = parser.feed(br#"[[1, "a,]"], {"x": [2"#);
+        records.extend(parser.feed(br#", 3]}, oops ]"#));
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0], StreamRecord::Json(serde_json::json!([1, "a,]"])));
+        assert_eq!(records[1], StreamRecord::Json(serde_json::json!({ "x": [2, 3] })));
+        assert!(matches!(records[2], StreamRecord::Invalid(_)));
+        assert!(parser.finish().is_empty());
+    }
+}
@@ -0,0 +1,291 @@
+use crate::core::{CellValue, DataType, Grid};
+use crate::GridError;
+use std::collections::HashMap;
+
+/// Height of the summary band drawn under the column headers
+pub const SUMMARY_BAND_HEIGHT: f32 = 22.0;
+
+/// Number of histogram bins in a sparkline
+const SPARKLINE_BINS: usize = 16;
+
+/// Number of most frequent values reported for text columns
+const TOP_VALUES: usize = 10;
+
+/// What the summary band under the column headers shows
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SummaryMode {
+    Off,
+    Sparkline, // Distribution histogram of the column's numbers
+    Stats,     // "min – max · avg" text
+}
+
+impl SummaryMode {
+    /// Parse mode name ("off", "sparkline", "stats")
+    pub fn parse(mode: &str) -> Result<Self, GridError> {
+        match mode.to_lowercase().as_str() {
+            "off" | "none" | "" => Ok(SummaryMode::Off),
+            "sparkline" => Ok(SummaryMode::Sparkline),
+            "stats" => Ok(SummaryMode::Stats),
+            other => Err(GridError::unknown_value("summary_mode", other, &["off", "sparkline", "stats"])),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SummaryMode::Off => "off",
+            SummaryMode::Sparkline => "sparkline",
+            SummaryMode::Stats => "stats",
+        }
+    }
+}
+
+/// Profile of a numeric column
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnSummary {
+    pub count: usize, // Numeric values
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub bins: Vec<u32>, // Histogram from min to max
+}
+
+impl ColumnSummary {
+    /// Summarize a column, or None if it is not numeric
+    /// A column is numeric if its data type is Number, or if most non-empty values are numbers
+    pub fn compute(grid: &Grid, col: usize) -> Option<Self> {
+        let mut numbers = Vec::new();
+        let mut non_empty = 0;
+        for row in 0..grid.row_count() {
+            match grid.get_value(row, col) {
+                CellValue::Empty => {}
+                CellValue::Number(n) if n.is_finite() => {
+                    numbers.push(n);
+                    non_empty += 1;
+                }
+                _ => non_empty += 1,
+            }
+        }
+
+        let typed_numeric = grid.get_column_config(col).is_some_and(|c| c.data_type == DataType::Number);
+        if numbers.is_empty() || (!typed_numeric && numbers.len() * 2 < non_empty) {
+            return None;
+        }
+
+        let min = numbers.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let mean = numbers.iter().sum::<f64>() / numbers.len() as f64;
+
+        let mut bins = vec![0u32; SPARKLINE_BINS];
+        let range = max - min;
+        for n in numbers.iter() {
+            let bin = if range > 0.0 { (((n - min) / range) * SPARKLINE_BINS as f64) as usize } else { 0 };
+            bins[bin.min(SPARKLINE_BINS - 1)] += 1;
+        }
+
+        Some(Self { count: numbers.len(), min, max, mean, bins })
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "count": self.count,
+            "min": self.min,
+            "max": self.max,
+            "mean": self.mean,
+            "bins": self.bins,
+        })
+    }
+}
+
+/// Descriptive statistics of a column
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnStats {
+    Numeric {
+        count: usize,       // Numeric values
+        empty_count: usize,
+        other_count: usize, // Non-empty values that are not numbers
+        sum: f64,
+        min: f64,
+        max: f64,
+        mean: f64,
+        median: f64,
+        stddev: f64,        // Sample standard deviation (0 for a single value)
+    },
+    Text {
+        count: usize, // Non-empty values
+        empty_count: usize,
+        distinct_count: usize,
+        top_values: Vec<(String, usize)>, // Most frequent values with their counts
+    },
+}
+
+impl ColumnStats {
+    /// Compute statistics over all rows of a column
+    /// Numeric columns (see ColumnSummary::compute) get number statistics, others value counts
+    pub fn compute(grid: &Grid, col: usize) -> Self {
+        let mut numbers = Vec::new();
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let mut empty_count = 0;
+        for row in 0..grid.row_count() {
+            let value = grid.get_value(row, col);
+            match &value {
+                CellValue::Empty => empty_count += 1,
+                CellValue::Number(n) if n.is_finite() => numbers.push(*n),
+                _ => {}
+            }
+            if !value.is_empty() {
+                *counts.entry(value.to_string()).or_default() += 1;
+            }
+        }
+        let non_empty = grid.row_count() - empty_count;
+
+        let typed_numeric = grid.get_column_config(col).is_some_and(|c| c.data_type == DataType::Number);
+        if numbers.is_empty() || (!typed_numeric && numbers.len() * 2 < non_empty) {
+            let mut top_values: Vec<(String, usize)> = counts.into_iter().collect();
+            let distinct_count = top_values.len();
+            top_values.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            top_values.truncate(TOP_VALUES);
+            return ColumnStats::Text { count: non_empty, empty_count, distinct_count, top_values };
+        }
+
+        numbers.sort_by(f64::total_cmp);
+        let count = numbers.len();
+        let sum: f64 = numbers.iter().sum();
+        let mean = sum / count as f64;
+        let median = if count % 2 == 1 {
+            numbers[count / 2]
+        } else {
+            (numbers[count / 2 - 1] + numbers[count / 2]) / 2.0
+        };
+        let stddev = if count > 1 {
+            (numbers.iter().map(|n| (n - mean).powi(2)).sum::<f64>() / (count - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+
+        ColumnStats::Numeric {
+            count,
+            empty_count,
+            other_count: non_empty - count,
+            sum,
+            min: numbers[0],
+            max: numbers[count - 1],
+            mean,
+            median,
+            stddev,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            ColumnStats::Numeric { count, empty_count, other_count, sum, min, max, mean, median, stddev } => {
+                serde_json::json!({
+                    "type": "numeric",
+                    "count": count,
+                    "empty_count": empty_count,
+                    "other_count": other_count,
+                    "sum": sum,
+                    "min": min,
+                    "max": max,
+                    "mean": mean,
+                    "median": median,
+                    "stddev": stddev,
+                })
+            }
+            ColumnStats::Text { count, empty_count, distinct_count, top_values } => {
+                let top_values: Vec<serde_json::Value> = top_values
+                    .iter()
+                    .map(|(value, count)| serde_json::json!({ "value": value, "count": count }))
+                    .collect();
+                serde_json::json!({
+                    "type": "text",
+                    "count": count,
+                    "empty_count": empty_count,
+                    "distinct_count": distinct_count,
+                    "top_values": top_values,
+                })
+            }
+        }
+    }
+}
+
+/// Lazily computed column summaries, dropped whenever the grid data changes
+#[derive(Default)]
+pub struct SummaryCache {
+    revision: u64,
+    summaries: HashMap<usize, Option<ColumnSummary>>,
+}
+
+impl SummaryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get (computing if needed) the summary of a column
+    pub fn get(&mut self, grid: &Grid, col: usize) -> Option<&ColumnSummary> {
+        if self.revision != grid.revision() {
+            self.summaries.clear();
+            self.revision = grid.revision();
+        }
+        self.summaries.entry(col).or_insert_with(|| ColumnSummary::compute(grid, col)).as_ref()
+    }
+
+    /// Check if cached summaries are out of date
+    pub fn is_stale(&self, grid: &Grid) -> bool {
+        self.revision != grid.revision()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_summary() {
+        let mut grid = Grid::new(5, 2);
+        for row in 0..4 {
+            grid.set_value(row, 0, CellValue::Number(row as f64 * 10.0));
+        }
+        grid.set_value(4, 0, CellValue::Text("n/a".to_string()));
+        grid.set_value(0, 1, CellValue::Text("a".to_string()));
+        grid.set_value(1, 1, CellValue::Text("b".to_string()));
+        grid.set_value(2, 1, CellValue::Number(1.0));
+
+        let mut cache = SummaryCache::new();
+        let summary = cache.get(&grid, 0).unwrap().clone();
+        assert_eq!((summary.count, summary.min, summary.max, summary.mean), (4, 0.0, 30.0, 15.0));
+        assert_eq!(summary.bins.iter().sum::<u32>(), 4);
+        assert_eq!(summary.bins[SPARKLINE_BINS - 1], 1);
+
+        // Mostly text: not numeric
+        assert!(cache.get(&grid, 1).is_none());
+
+        grid.set_value(0, 0, CellValue::Number(-10.0));
+        assert!(cache.is_stale(&grid));
+        assert_eq!(cache.get(&grid, 0).unwrap().min, -10.0);
+    }
+
+    #[test]
+    fn test_column_stats() {
+        let mut grid = Grid::new(6, 2);
+        for (row, n) in [4.0, 1.0, 3.0, 2.0].into_iter().enumerate() {
+            grid.set_value(row, 0, CellValue::Number(n));
+        }
+        grid.set_value(4, 0, CellValue::Text("n/a".to_string()));
+        for (row, text) in ["b", "a", "b", "c", "b"].into_iter().enumerate() {
+            grid.set_value(row, 1, CellValue::Text(text.to_string()));
+        }
+
+        let ColumnStats::Numeric { count, empty_count, other_count, median, stddev, .. } = ColumnStats::compute(&grid, 0) else {
+            panic!("expected numeric stats");
+        };
+        assert_eq!((count, empty_count, other_count, median), (4, 1, 1, 2.5));
+        assert!((stddev - 1.2910).abs() < 1e-4);
+
+        let ColumnStats::Text { count, empty_count, distinct_count, top_values } = ColumnStats::compute(&grid, 1) else {
+            panic!("expected text stats");
+        };
+        assert_eq!((count, empty_count, distinct_count), (5, 1, 3));
+        assert_eq!(top_values[0], ("b".to_string(), 3));
+        assert_eq!(top_values[1], ("a".to_string(), 1));
+    }
+}
@@ -0,0 +1,296 @@
+use crate::core::{Cell, CellValue, Grid};
+use crate::GridError;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// A single cell inside a template block
+#[derive(Clone, Debug)]
+pub struct TemplateCell {
+    pub value: CellValue,
+    pub bg_color: Option<u32>,
+    pub fg_color: Option<u32>,
+    pub font_bold: Option<bool>,
+    pub font_italic: Option<bool>,
+}
+
+impl TemplateCell {
+    /// Parse a template cell from JSON
+    /// Accepts a plain value ("text", 42, true, null) or an object:
+    /// {"value": "=A1*2", "type": "date", "bg_color": 0xFFEEEEFF, "fg_color": ..., "bold": true, "italic": false}
+    fn from_json(value: &serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Object(obj) => {
+                let raw = obj.get("value").unwrap_or(&serde_json::Value::Null);
+                let cell_value = match (obj.get("type").and_then(|t| t.as_str()), raw) {
+                    (Some("date"), serde_json::Value::String(s)) => CellValue::Date(s.clone()),
+                    _ => Self::value_from_json(raw),
+                };
+
+                Self {
+                    value: cell_value,
+                    bg_color: obj.get("bg_color").and_then(|v| v.as_u64()).map(|v| v as u32),
+                    fg_color: obj.get("fg_color").and_then(|v| v.as_u64()).map(|v| v as u32),
+                    font_bold: obj.get("bold").and_then(|v| v.as_bool()),
+                    font_italic: obj.get("italic").and_then(|v| v.as_bool()),
+                }
+            }
+            other => Self {
+                value: Self::value_from_json(other),
+                bg_color: None,
+                fg_color: None,
+                font_bold: None,
+                font_italic: None,
+            },
+        }
+    }
+
+    fn value_from_json(value: &serde_json::Value) -> CellValue {
+        match value {
+            serde_json::Value::String(s) => CellValue::Text(s.clone()),
+            serde_json::Value::Number(n) => n.as_f64().map(CellValue::Number).unwrap_or(CellValue::Empty),
+            serde_json::Value::Bool(b) => CellValue::Boolean(*b),
+            _ => CellValue::Empty,
+        }
+    }
+}
+
+/// Repeating block of cells used to fill a range (e.g. a weekly schedule block)
+///
+/// Text values support placeholders which are expanded per target cell:
+/// - `{row}`   1-based row number of the target cell
+/// - `{col}`   column letter of the target cell
+/// - `{block}` 1-based index of the vertical repetition
+///
+/// Text values starting with `=` are treated as formulas: A1-style references
+/// are shifted by the block offset on each repetition unless anchored with `$`.
+pub struct CellTemplate {
+    pub cells: Vec<Vec<TemplateCell>>,
+    width: usize,
+}
+
+impl CellTemplate {
+    /// Parse template JSON: either `[[cell, ...], ...]` or `{"cells": [[cell, ...], ...]}`
+    pub fn from_json(template_json: &str) -> Result<Self, GridError> {
+        let parsed: serde_json::Value = serde_json::from_str(template_json)
+            .map_err(|e| GridError::InvalidJson { error: e.to_string() })?;
+
+        let rows = match &parsed {
+            serde_json::Value::Array(rows) => rows,
+            serde_json::Value::Object(obj) => obj
+                .get("cells")
+                .and_then(|c| c.as_array())
+                .ok_or_else(|| GridError::InvalidJson { error: "Template must contain a \"cells\" array".to_string() })?,
+            _ => {
+                return Err(GridError::InvalidJson {
+                    error: "Template must be an array of rows".to_string(),
+                })
+            }
+        };
+
+        let cells: Vec<Vec<TemplateCell>> = rows
+            .iter()
+            .map(|row| match row {
+                serde_json::Value::Array(values) => values.iter().map(TemplateCell::from_json).collect(),
+                single => vec![TemplateCell::from_json(single)],
+            })
+            .collect();
+
+        let width = cells.iter().map(|r| r.len()).max().unwrap_or(0);
+        if cells.is_empty() || width == 0 {
+            return Err(GridError::InvalidJson {
+                error: "Template is empty".to_string(),
+            });
+        }
+
+        Ok(Self { cells, width })
+    }
+
+    /// Number of rows in one repetition of the template
+    pub fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Number of columns in one repetition of the template
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Build the cell written at (row, col) for a range starting at (start_row, start_col)
+    /// Returns None when the template has no cell at that position (ragged rows)
+    pub fn cell_at(
+        &self,
+        start_row: usize,
+        start_col: usize,
+        row: usize,
+        col: usize,
+        existing: Option<&Cell>,
+    ) -> Option<Cell> {
+        let rel_row = row - start_row;
+        let rel_col = col - start_col;
+        let block_row = rel_row / self.height();
+        let block_col = rel_col / self.width();
+
+        let template_cell = self.cells[rel_row % self.height()].get(rel_col % self.width())?;
+
+        let value = match &template_cell.value {
+            CellValue::Text(text) => {
+                let expanded = text
+                    .replace("{row}", &(row + 1).to_string())
+                    .replace("{col}", &Grid::column_index_to_letter(col))
+                    .replace("{block}", &(block_row + 1).to_string());
+
+                if expanded.starts_with('=') {
+                    CellValue::Text(Self::shift_references(
+                        &expanded,
+                        (block_row * self.height()) as isize,
+                        (block_col * self.width()) as isize,
+                    ))
+                } else {
+                    CellValue::Text(expanded)
+                }
+            }
+            other => other.clone(),
+        };
+
+        let mut cell = existing.cloned().unwrap_or_default();
+        let old_value = std::mem::replace(&mut cell.value, value);
+        cell.mark_modified(old_value);
+        if template_cell.bg_color.is_some() {
+            cell.bg_color = template_cell.bg_color;
+        }
+        if template_cell.fg_color.is_some() {
+            cell.fg_color = template_cell.fg_color;
+        }
+        if let Some(bold) = template_cell.font_bold {
+            cell.font_bold = bold;
+        }
+        if let Some(italic) = template_cell.font_italic {
+            cell.font_italic = italic;
+        }
+
+        Some(cell)
+    }
+
+    /// Write the template over the range (start_row, start_col)..=(end_row, end_col)
+    /// Cells that are not editable (read-only grid, column, row or cell, editable rules
+    /// or the editable callback) or that `allow` rejects keep their content.
+    /// Returns (row, col, old, new) for every written cell
+    pub fn fill(
+        &self,
+        grid: &mut Grid,
+        (start_row, start_col, end_row, end_col): (usize, usize, usize, usize),
+        mut allow: impl FnMut(usize, usize, &Cell) -> bool,
+    ) -> Vec<(usize, usize, Option<Cell>, Option<Cell>)> {
+        let mut changed_cells = Vec::new();
+        for row in start_row..=end_row {
+            for col in start_col..=end_col {
+                if !grid.is_cell_editable(row, col) {
+                    continue;
+                }
+                let old_cell = grid.get_cell(row, col).cloned();
+                if let Some(new_cell) = self.cell_at(start_row, start_col, row, col, old_cell.as_ref()) {
+                    if !allow(row, col, &new_cell) {
+                        continue;
+                    }
+                    grid.set_cell(row, col, new_cell.clone());
+                    changed_cells.push((row, col, old_cell, Some(new_cell)));
+                }
+            }
+        }
+        changed_cells
+    }
+
+    /// Shift relative A1-style references in a formula by the given row/column offsets
+    /// References anchored with `$` (e.g. `$A$1`, `A$1`) keep the anchored part unchanged
+    /// Names that only look like references (LOG10(, ATAN2(, XA1) are left alone
+    pub fn shift_references(formula: &str, row_offset: isize, col_offset: isize) -> String {
+        static REFERENCE: OnceLock<Regex> = OnceLock::new();
+        let re = REFERENCE.get_or_init(|| Regex::new(r"(\$?)([A-Z]{1,3})(\$?)([0-9]+)").unwrap());
+        let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+        re.replace_all(formula, |caps: &regex::Captures| {
+            // The regex crate has no lookaround, so check the neighbours here: a match
+            // inside a longer name or followed by "(" is not a reference
+            let matched = caps.get(0).unwrap();
+            let before = formula[..matched.start()].chars().next_back();
+            let after = formula[matched.end()..].chars().next();
+            if before.is_some_and(is_name_char) || after.is_some_and(|c| is_name_char(c) || c == '(') {
+                return matched.as_str().to_string();
+            }
+            let col_abs = &caps[1] == "$";
+            let row_abs = &caps[3] == "$";
+
+            let col_index = Self::letter_to_column_index(&caps[2]) as isize;
+            let row_number = caps[4].parse::<isize>().unwrap_or(1);
+
+            let new_col = if col_abs { col_index } else { (col_index + col_offset).max(0) };
+            let new_row = if row_abs { row_number } else { (row_number + row_offset).max(1) };
+
+            format!(
+                "{}{}{}{}",
+                &caps[1],
+                Grid::column_index_to_letter(new_col as usize),
+                &caps[3],
+                new_row
+            )
+        })
+        .into_owned()
+    }
+
+    /// Convert column letter to index (A -> 0, B -> 1, ..., AA -> 26)
+    fn letter_to_column_index(letters: &str) -> usize {
+        letters
+            .bytes()
+            .fold(0, |acc, b| acc * 26 + (b - b'A' + 1) as usize)
+            - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shift_references() {
+        assert_eq!(CellTemplate::shift_references("=A1+B2", 3, 0), "=A4+B5");
+        assert_eq!(CellTemplate::shift_references("=$A$1+A$1+$A1", 2, 1), "=$A$1+B$1+$A3");
+        assert_eq!(CellTemplate::shift_references("=SUM(Z1:Z3)", 0, 1), "=SUM(AA1:AA3)");
+        assert_eq!(CellTemplate::shift_references("=LOG10(A1)+ATAN2(B1,C1)", 1, 0), "=LOG10(A2)+ATAN2(B2,C2)");
+    }
+
+    #[test]
+    fn test_template_repeats_with_placeholders() {
+        let template = CellTemplate::from_json(r#"[["Day {block}", "=B1*2"], [{"value": 5, "bold": true}]]"#).unwrap();
+        assert_eq!(template.height(), 2);
+        assert_eq!(template.width(), 2);
+
+        let cell = template.cell_at(0, 0, 2, 0, None).unwrap();
+        assert_eq!(cell.value, CellValue::Text("Day 2".to_string()));
+
+        let formula = template.cell_at(0, 0, 2, 1, None).unwrap();
+        assert_eq!(formula.value, CellValue::Text("=B3*2".to_string()));
+
+        let styled = template.cell_at(0, 0, 1, 0, None).unwrap();
+        assert_eq!(styled.value, CellValue::Number(5.0));
+        assert!(styled.font_bold);
+
+        // Ragged row has no second column
+        assert!(template.cell_at(0, 0, 1, 1, None).is_none());
+    }
+
+    #[test]
+    fn test_fill_skips_locked_cells() {
+        let mut grid = Grid::new(3, 2);
+        grid.set_row_readonly(1, true);
+        grid.set_cell_editable(2, 1, false);
+
+        let template = CellTemplate::from_json(r#"[["x"]]"#).unwrap();
+        let changed = template.fill(&mut grid, (0, 0, 2, 1), |_, col, _| col == 0);
+        assert_eq!(changed.len(), 2);
+
+        assert_eq!(grid.get_value_string(0, 1), "");
+        assert_eq!(grid.get_value_string(1, 0), "");
+        assert_eq!(grid.get_value_string(2, 0), "x");
+        assert_eq!(grid.get_value_string(2, 1), "");
+    }
+}
@@ -0,0 +1,193 @@
+use crate::core::Grid;
+use crate::features::snapshot;
+use crate::features::EditAction;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+
+/// Most commands kept; older ones are dropped with the checkpoints that precede them
+const MAX_COMMANDS: usize = 10_000;
+
+/// A recorded change: an edit, undo or redo, numbered in the order it happened
+#[derive(Clone, Debug)]
+pub struct Command {
+    pub seq: usize,
+    pub time: f64, // Milliseconds since the epoch
+    pub detail: Value,
+}
+
+/// Full grid state (as serialize_state) after the commands numbered below `seq`
+#[derive(Clone, Debug)]
+pub struct Checkpoint {
+    pub id: usize,
+    pub seq: usize,
+    pub time: f64,
+    pub state: Value,
+}
+
+/// Recorder for time-travel debugging: the command stream plus a checkpoint every
+/// `interval` commands, so any reported state can be restored and the commands that
+/// led to it replayed by hand
+pub struct TimeTravel {
+    pub interval: usize,        // Commands between automatic checkpoints
+    pub max_checkpoints: usize, // Oldest checkpoints (and their commands) are dropped beyond this
+    commands: VecDeque<Command>,
+    checkpoints: VecDeque<Checkpoint>,
+    next_seq: usize,
+    next_id: usize,
+    since_checkpoint: usize, // Commands recorded after the last checkpoint
+}
+
+impl TimeTravel {
+    pub fn new(interval: usize, max_checkpoints: usize) -> Self {
+        Self {
+            interval: interval.max(1),
+            max_checkpoints: max_checkpoints.max(1),
+            commands: VecDeque::new(),
+            checkpoints: VecDeque::new(),
+            next_seq: 0,
+            next_id: 0,
+            since_checkpoint: 0,
+        }
+    }
+
+    /// Append a command to the stream
+    pub fn record(&mut self, detail: Value, time: f64) {
+        if self.commands.len() >= MAX_COMMANDS {
+            self.commands.pop_front();
+        }
+        self.commands.push_back(Command { seq: self.next_seq, time, detail });
+        self.next_seq += 1;
+        self.since_checkpoint += 1;
+    }
+
+    /// True when enough commands were recorded since the last checkpoint (or there is none)
+    pub fn needs_checkpoint(&self) -> bool {
+        self.checkpoints.is_empty() || self.since_checkpoint >= self.interval
+    }
+
+    /// Save the grid state as a checkpoint and return its ID
+    pub fn checkpoint(&mut self, grid: &Grid, time: f64) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.checkpoints.push_back(Checkpoint { id, seq: self.next_seq, time, state: snapshot::capture_state(grid) });
+        self.since_checkpoint = 0;
+
+        if self.checkpoints.len() > self.max_checkpoints {
+            self.checkpoints.pop_front();
+            let oldest = self.checkpoints.front().map_or(0, |c| c.seq);
+            while self.commands.front().is_some_and(|c| c.seq < oldest) {
+                self.commands.pop_front();
+            }
+        }
+        id
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Checkpoint> {
+        self.checkpoints.iter().find(|c| c.id == id)
+    }
+
+    /// Commands recorded after a checkpoint, up to the next one
+    pub fn commands_after(&self, id: usize) -> Vec<Value> {
+        let Some(index) = self.checkpoints.iter().position(|c| c.id == id) else {
+            return Vec::new();
+        };
+        let from = self.checkpoints[index].seq;
+        let to = self.checkpoints.get(index + 1).map_or(usize::MAX, |c| c.seq);
+        self.commands.iter().filter(|c| c.seq >= from && c.seq < to).map(command_json).collect()
+    }
+
+    /// Checkpoints (without their state) and the recorded commands, oldest first
+    pub fn timeline_json(&self) -> Value {
+        json!({
+            "interval": self.interval,
+            "checkpoints": self.checkpoints.iter()
+                .map(|c| json!({ "id": c.id, "seq": c.seq, "time": c.time }))
+                .collect::<Vec<_>>(),
+            "commands": self.commands.iter().map(command_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+fn command_json(command: &Command) -> Value {
+    let mut entry = json!({ "seq": command.seq, "time": command.time });
+    if let (Value::Object(entry), Value::Object(detail)) = (&mut entry, &command.detail) {
+        entry.extend(detail.clone());
+    }
+    entry
+}
+
+impl EditAction {
+    /// Short JSON description of the action for the command stream (no cell contents
+    /// beyond single-cell edits, so recording stays cheap)
+    pub fn describe(&self) -> Value {
+        match self {
+            EditAction::SetValue { row, col, old_value, new_value } => json!({
+                "command": "set_value", "row": row, "col": col,
+                "old": old_value.to_string(), "new": new_value.to_string(),
+            }),
+            EditAction::InsertRow { index, .. } => json!({ "command": "insert_row", "index": index }),
+            EditAction::DeleteRow { index, .. } => json!({ "command": "delete_row", "index": index }),
+            EditAction::InsertColumn { index, .. } => json!({ "command": "insert_column", "index": index }),
+            EditAction::DeleteColumn { index, .. } => json!({ "command": "delete_column", "index": index }),
+            EditAction::DeleteRows { rows } => {
+                json!({ "command": "delete_rows", "rows": rows.iter().map(|(row, _, _)| row).collect::<Vec<_>>() })
+            }
+            EditAction::ClearCells { cells } => json!({ "command": "clear_cells", "cells": cells.len() }),
+            EditAction::SetMultipleCells { cells } => json!({ "command": "set_cells", "cells": cells.len() }),
+            EditAction::SetStyle { row, col, .. } => json!({ "command": "set_style", "row": row, "col": col }),
+            EditAction::ReplaceCells { cells } => json!({ "command": "replace_cells", "cells": cells.len() }),
+            EditAction::ExpandGrid { new_rows, new_cols, action, .. } => json!({
+                "command": "expand_grid", "rows": new_rows, "cols": new_cols, "action": action.describe(),
+            }),
+            EditAction::Composite { actions } => {
+                json!({ "command": "composite", "actions": actions.iter().map(EditAction::describe).collect::<Vec<_>>() })
+            }
+            EditAction::SortRows { order, new_sort, .. } => {
+                json!({ "command": "sort", "sort": new_sort.to_json(), "reordered": !order.is_empty() })
+            }
+            EditAction::ResizeColumn { col, new_width, .. } => json!({ "command": "resize_column", "col": col, "width": new_width }),
+            EditAction::ResizeRow { row, new_height, .. } => json!({ "command": "resize_row", "row": row, "height": new_height }),
+            EditAction::MoveColumn { from, to } => json!({ "command": "move_column", "from": from, "to": to }),
+            EditAction::SetFrozen { new_frozen, .. } => json!({ "command": "set_frozen", "rows": new_frozen.0, "cols": new_frozen.1 }),
+            EditAction::SetFilter { new_rows, .. } => json!({ "command": "filter", "hidden_rows": new_rows.len() }),
+            EditAction::SetColumnVisibility { cols, visible } => json!({ "command": "column_visibility", "cols": cols, "visible": visible }),
+            EditAction::SetRowVisibility { rows, visible } => json!({ "command": "row_visibility", "rows": rows, "visible": visible }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::CellValue;
+
+    #[test]
+    fn test_time_travel_checkpoints() {
+        let mut grid = Grid::new(3, 2);
+        let mut recorder = TimeTravel::new(2, 2);
+        assert!(recorder.needs_checkpoint());
+        assert_eq!(recorder.checkpoint(&grid, 0.0), 0);
+
+        for (row, time) in [(0, 1.0), (1, 2.0), (2, 3.0)] {
+            let action = EditAction::SetValue { row, col: 0, old_value: CellValue::Empty, new_value: CellValue::Number(row as f64) };
+            grid.set_value(row, 0, CellValue::Number(row as f64));
+            recorder.record(action.describe(), time);
+            if recorder.needs_checkpoint() {
+                recorder.checkpoint(&grid, time);
+            }
+        }
+        // Checkpoint 1 came after two commands; the third follows it
+        let first = recorder.get(1).unwrap();
+        assert_eq!(first.seq, 2);
+        assert_eq!(recorder.commands_after(0).len(), 2);
+        let after = recorder.commands_after(1);
+        assert_eq!((after.len(), &after[0]["command"], &after[0]["row"]), (1, &json!("set_value"), &json!(2)));
+
+        // A third checkpoint drops the oldest one and the commands before checkpoint 1
+        recorder.checkpoint(&grid, 4.0);
+        assert!(recorder.get(0).is_none());
+        let timeline = recorder.timeline_json();
+        assert_eq!(timeline["checkpoints"].as_array().unwrap().len(), 2);
+        assert_eq!(timeline["commands"].as_array().unwrap().len(), 1);
+    }
+}
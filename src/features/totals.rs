@@ -0,0 +1,156 @@
+use crate::core::{CellValue, Grid};
+use js_sys::{Array, Function};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use wasm_bindgen::JsValue;
+
+/// A JS reducer computing a column total
+struct ColumnReducer {
+    reduce: Function,         // (values, col) => total, over the visible non-empty values
+    update: Option<Function>, // (total, old_value, new_value, col) => total; undefined = recompute
+    total: Option<Value>,     // None until first computed
+    values: Vec<CellValue>,   // Column values at the last computation (for incremental updates)
+}
+
+/// Custom column totals computed by JS reducers and kept up to date as cells change
+/// Edits are applied through the reducer's update hook when it has one; row inserts,
+/// deletes, sorts and filter changes recompute the totals from scratch
+#[derive(Default)]
+pub struct ColumnTotals {
+    reducers: BTreeMap<usize, ColumnReducer>,
+    revision: u64,                 // Grid revision at the last refresh
+    filtered_rows: HashSet<usize>, // Rows hidden at the last refresh
+}
+
+impl ColumnTotals {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (Some) or remove (None) a column's reducer and optional update hook
+    pub fn set(&mut self, col: usize, reducer: Option<(Function, Option<Function>)>) {
+        match reducer {
+            Some((reduce, update)) => {
+                self.reducers.insert(col, ColumnReducer { reduce, update, total: None, values: Vec::new() });
+            }
+            None => {
+                self.reducers.remove(&col);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reducers.is_empty()
+    }
+
+    /// Check if a column has a reducer
+    pub fn contains(&self, col: usize) -> bool {
+        self.reducers.contains_key(&col)
+    }
+
+    /// Last computed total of a column (null if the reducer failed)
+    pub fn get(&self, col: usize) -> Option<&Value> {
+        self.reducers.get(&col).and_then(|r| r.total.as_ref())
+    }
+
+    /// Computed totals as {"col": total, ...}
+    pub fn to_json(&self) -> Value {
+        let totals: serde_json::Map<String, Value> = self
+            .reducers
+            .iter()
+            .filter_map(|(col, r)| Some((col.to_string(), r.total.clone()?)))
+            .collect();
+        Value::Object(totals)
+    }
+
+    /// Bring the totals up to date with the grid
+    /// Returns the columns whose total changed
+    pub fn refresh(&mut self, grid: &Grid) -> Vec<usize> {
+        let recompute_all = grid.structure_changed_since(self.revision) || grid.filtered_rows() != &self.filtered_rows;
+        let mut changed = Vec::new();
+
+        for (&col, reducer) in self.reducers.iter_mut() {
+            let full = recompute_all || reducer.total.is_none() || reducer.values.len() != grid.row_count();
+            if !full && !grid.columns_changed_since(&[col], self.revision) {
+                continue;
+            }
+
+            let values: Vec<CellValue> = (0..grid.row_count()).map(|row| grid.get_value(row, col)).collect();
+            let total = match (full, reducer.update.as_ref(), reducer.total.as_ref()) {
+                (false, Some(update), Some(total)) => {
+                    Self::apply_updates(grid, col, update, total, &reducer.values, &values)
+                        .unwrap_or_else(|| Self::reduce(grid, col, &reducer.reduce, &values))
+                }
+                _ => Self::reduce(grid, col, &reducer.reduce, &values),
+            };
+
+            reducer.values = values;
+            if reducer.total.as_ref() != Some(&total) {
+                reducer.total = Some(total);
+                changed.push(col);
+            }
+        }
+
+        self.revision = grid.revision();
+        self.filtered_rows = grid.filtered_rows().clone();
+        changed
+    }
+
+    /// Call the reducer over the visible non-empty values of a column
+    fn reduce(grid: &Grid, col: usize, reduce: &Function, values: &[CellValue]) -> Value {
+        let array = Array::new();
+        for (row, value) in values.iter().enumerate() {
+            if !value.is_empty() && !grid.is_row_hidden(row) {
+                array.push(&value.to_js_value());
+            }
+        }
+        match reduce.call2(&JsValue::NULL, &array, &JsValue::from_f64(col as f64)) {
+            Ok(total) => js_to_json(&total),
+            Err(e) => {
+                log::error!("Total reducer for column {} failed: {:?}", col, e);
+                Value::Null
+            }
+        }
+    }
+
+    /// Feed each changed visible cell to the update hook
+    /// Returns None if the hook asked for a full recompute (returned undefined) or threw
+    fn apply_updates(
+        grid: &Grid,
+        col: usize,
+        update: &Function,
+        total: &Value,
+        old_values: &[CellValue],
+        new_values: &[CellValue],
+    ) -> Option<Value> {
+        let mut total = json_to_js(total);
+        for (row, (old, new)) in old_values.iter().zip(new_values).enumerate() {
+            if old == new || grid.is_row_hidden(row) {
+                continue;
+            }
+            let args = Array::of4(&total, &old.to_js_value(), &new.to_js_value(), &JsValue::from_f64(col as f64));
+            total = match update.apply(&JsValue::NULL, &args) {
+                Ok(next) if !next.is_undefined() => next,
+                Ok(_) => return None,
+                Err(e) => {
+                    log::error!("Total update hook for column {} failed: {:?}", col, e);
+                    return None;
+                }
+            };
+        }
+        Some(js_to_json(&total))
+    }
+}
+
+/// Convert a reducer result to JSON (undefined and unserializable values become null)
+fn js_to_json(value: &JsValue) -> Value {
+    js_sys::JSON::stringify(value)
+        .ok()
+        .and_then(|json| json.as_string())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or(Value::Null)
+}
+
+fn json_to_js(value: &Value) -> JsValue {
+    js_sys::JSON::parse(&value.to_string()).unwrap_or(JsValue::NULL)
+}
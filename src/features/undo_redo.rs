@@ -1,4 +1,6 @@
-use crate::core::{cell::CellValue, Cell, Grid, Viewport};
+use crate::core::{cell::CellValue, Cell, Grid, SortState, Viewport};
+use crate::features::filter::ColumnFilters;
+use std::collections::HashSet;
 
 /// Cell style information for undo/redo
 #[derive(Clone, Debug)]
@@ -9,6 +11,9 @@ pub struct CellStyle {
     pub font_italic: bool,
 }
 
+/// Cells of one row as (col, cell)
+pub type RowCells = Vec<(usize, Cell)>;
+
 /// Action that can be undone/redone
 #[derive(Clone)]
 pub enum EditAction {
@@ -20,11 +25,13 @@ pub enum EditAction {
     },
     InsertRow {
         index: usize,
+        row_id: Option<usize>, // Stable ID of the row, kept when redo inserts it again
         // Store all cells in the row before deletion (for redo of delete)
         cells: Vec<(usize, Cell)>, // (col, cell)
     },
     DeleteRow {
         index: usize,
+        row_id: Option<usize>, // Stable ID of the row, kept when undo inserts it again
         // Store all cells in the row for undo
         cells: Vec<(usize, Cell)>, // (col, cell)
     },
@@ -40,7 +47,7 @@ pub enum EditAction {
     },
     DeleteRows {
         // Store multiple rows for bulk deletion undo
-        rows: Vec<(usize, Vec<(usize, Cell)>)>, // (row_index, cells)
+        rows: Vec<(usize, Option<usize>, RowCells)>, // (row_index, row_id, cells), bottom to top
     },
     ClearCells {
         // Store multiple cell values for bulk clear/delete undo
@@ -56,12 +63,108 @@ pub enum EditAction {
         old_style: CellStyle,
         new_style: CellStyle,
     },
+    ReplaceCells {
+        // Store whole cells (value + style) for operations that touch both, e.g. templates
+        cells: Vec<(usize, usize, Option<Cell>, Option<Cell>)>, // (row, col, old_cell, new_cell)
+    },
+    ExpandGrid {
+        // Grid grown to fit pasted/filled data; `action` holds the cell changes made after growing
+        old_rows: usize,
+        old_cols: usize,
+        new_rows: usize,
+        new_cols: usize,
+        action: Box<EditAction>,
+    },
+    Composite {
+        // Several actions recorded by one user gesture, undone/redone as a single step
+        actions: Vec<EditAction>,
+    },
+    SortRows {
+        // Rows reordered by a sort (new row i = old row order[i]; empty = sort state only)
+        order: Vec<usize>,
+        old_sort: SortState,
+        new_sort: SortState,
+    },
+    ResizeColumn {
+        col: usize,
+        old_width: f32,
+        new_width: f32,
+    },
+    ResizeRow {
+        row: usize,
+        old_height: f32,
+        new_height: f32,
+    },
+    MoveColumn {
+        from: usize,
+        to: usize,
+    },
+    SetFrozen {
+        old_frozen: (usize, usize), // (rows, cols)
+        new_frozen: (usize, usize),
+    },
+    SetFilter {
+        // Rows hidden by filters, and the filter definitions, before and after the change
+        old_rows: HashSet<usize>,
+        new_rows: HashSet<usize>,
+        old_filters: ColumnFilters,
+        new_filters: ColumnFilters,
+    },
+    SetColumnVisibility {
+        cols: Vec<usize>, // Columns whose visibility changed
+        visible: bool,    // Visibility after the change
+    },
+    SetRowVisibility {
+        rows: Vec<usize>, // Rows whose visibility changed
+        visible: bool,    // Visibility after the change
+    },
+}
+
+impl EditAction {
+    /// Cells whose value the action sets in place (row and column inserts/deletes move
+    /// cells rather than change them)
+    pub fn value_cells(&self) -> Vec<(usize, usize)> {
+        match self {
+            EditAction::SetValue { row, col, .. } => vec![(*row, *col)],
+            EditAction::ClearCells { cells } => cells.iter().map(|(row, col, _)| (*row, *col)).collect(),
+            EditAction::SetMultipleCells { cells } => cells.iter().map(|(row, col, _, _)| (*row, *col)).collect(),
+            EditAction::ReplaceCells { cells } => cells.iter().map(|(row, col, _, _)| (*row, *col)).collect(),
+            EditAction::ExpandGrid { action, .. } => action.value_cells(),
+            EditAction::Composite { actions } => actions.iter().flat_map(EditAction::value_cells).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Filter definitions in effect once the action is undone (`undone`) or redone
+    /// (None if the action does not change filters); the grid only holds the hidden rows
+    pub fn filters(&self, undone: bool) -> Option<&ColumnFilters> {
+        match self {
+            EditAction::SetFilter { old_filters, new_filters, .. } => Some(if undone { old_filters } else { new_filters }),
+            EditAction::ExpandGrid { action, .. } => action.filters(undone),
+            EditAction::Composite { actions } if undone => actions.iter().find_map(|action| action.filters(true)),
+            EditAction::Composite { actions } => actions.iter().rev().find_map(|action| action.filters(false)),
+            _ => None,
+        }
+    }
+}
+
+/// Where a cell edit started: counts the actions recorded since, and keeps the redo stack
+/// the first of them cleared, so a cancelled edit can take its writes back
+struct EditMark {
+    recorded: usize,
+    redo: Option<Vec<EditAction>>,
 }
 
 /// Undo/Redo functionality for DataGrid
 pub struct UndoRedoState {
     pub undo_stack: Vec<EditAction>,
     pub redo_stack: Vec<EditAction>,
+    group: Vec<EditAction>, // Actions recorded since begin_group()
+    group_depth: usize,     // Nesting level of open groups (0 = none)
+    suspend_depth: usize,   // Nesting level of suspend() calls (0 = recording)
+    edit_mark: Option<EditMark>, // Set while a cell edit is in progress
+    #[cfg(feature = "time-travel")]
+    pub journal: Option<Vec<serde_json::Value>>, // Commands for time-travel debugging, when recording
 }
 
 impl Default for UndoRedoState {
@@ -69,6 +172,12 @@ impl Default for UndoRedoState {
         Self {
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            group: Vec::new(),
+            group_depth: 0,
+            suspend_depth: 0,
+            edit_mark: None,
+            #[cfg(feature = "time-travel")]
+            journal: None,
         }
     }
 }
@@ -78,68 +187,54 @@ impl UndoRedoState {
         Self::default()
     }
 
+    /// Remember where a cell edit starts (see discard_cell_edits)
+    pub fn mark_edit_start(&mut self) {
+        self.edit_mark = Some(EditMark { recorded: 0, redo: None });
+    }
+
+    /// Forget the edit start once the edit is over
+    pub fn end_edit_mark(&mut self) {
+        self.edit_mark = None;
+    }
+
+    /// Drop the SetValue entries for (row, col) recorded since mark_edit_start, from an
+    /// open group as well as the stack (values written during an edit that was then
+    /// cancelled). If nothing else was recorded, the redo stack they cleared comes back.
+    pub fn discard_cell_edits(&mut self, row: usize, col: usize) {
+        let Some(mark) = self.edit_mark.take() else {
+            return;
+        };
+        let is_cell_edit =
+            |action: Option<&EditAction>| matches!(action, Some(EditAction::SetValue { row: r, col: c, .. }) if (*r, *c) == (row, col));
+        let mut left = mark.recorded;
+        // An open group holds the newest actions
+        while left > 0 && is_cell_edit(self.group.last()) {
+            self.group.pop();
+            left -= 1;
+        }
+        while left > 0 && self.group.is_empty() && is_cell_edit(self.undo_stack.last()) {
+            self.undo_stack.pop();
+            left -= 1;
+        }
+        if left == 0 {
+            if let Some(redo) = mark.redo {
+                self.redo_stack = redo;
+            }
+        }
+    }
+
     /// Perform undo operation
+    /// An open group is closed first, so its actions are undone together
+    /// Refused while recording is suspended
     pub fn undo(&mut self, grid: &mut Grid, viewport: &mut Viewport) -> bool {
+        if self.is_suspended() {
+            return false;
+        }
+        self.close_groups();
         if let Some(action) = self.undo_stack.pop() {
-            match &action {
-                EditAction::SetValue { row, col, old_value, new_value } => {
-                    log::info!("[UndoRedo] Undoing SetValue: ({}, {}) \"{}\" <- \"{}\"",
-                               row, col, old_value.to_string(), new_value.to_string());
-                    // Restore old value without recording undo
-                    grid.set_value(*row, *col, old_value.clone());
-                }
-                EditAction::InsertRow { index, cells: _ } => {
-                    // Undo insert by deleting the row
-                    grid.delete_row(*index);
-                    viewport.update_visible_range(grid);
-                }
-                EditAction::DeleteRow { index, cells } => {
-                    // Undo delete by inserting the row back
-                    grid.insert_row(*index);
-                    grid.restore_row_cells(*index, cells);
-                    viewport.update_visible_range(grid);
-                }
-                EditAction::InsertColumn { index, cells: _ } => {
-                    // Undo insert by deleting the column
-                    grid.delete_column(*index);
-                    viewport.update_visible_range(grid);
-                }
-                EditAction::DeleteColumn { index, cells } => {
-                    // Undo delete by inserting the column back
-                    grid.insert_column(*index);
-                    grid.restore_column_cells(*index, cells);
-                    viewport.update_visible_range(grid);
-                }
-                EditAction::DeleteRows { rows } => {
-                    // Undo bulk delete by inserting rows back in reverse order
-                    for (index, cells) in rows.iter() {
-                        grid.insert_row(*index);
-                        grid.restore_row_cells(*index, cells);
-                    }
-                    viewport.update_visible_range(grid);
-                }
-                EditAction::ClearCells { cells } => {
-                    // Restore all cleared cell values
-                    for (row, col, old_value) in cells.iter() {
-                        grid.set_value(*row, *col, old_value.clone());
-                    }
-                }
-                EditAction::SetMultipleCells { cells } => {
-                    // Restore all old cell values
-                    for (row, col, old_value, _new_value) in cells.iter() {
-                        grid.set_value(*row, *col, old_value.clone());
-                    }
-                }
-                EditAction::SetStyle { row, col, old_style, new_style: _ } => {
-                    // Restore old style
-                    if let Some(cell) = grid.get_cell_mut(*row, *col) {
-                        cell.bg_color = old_style.bg_color;
-                        cell.fg_color = old_style.fg_color;
-                        cell.font_bold = old_style.font_bold;
-                        cell.font_italic = old_style.font_italic;
-                    }
-                }
-            }
+            Self::undo_action(&action, grid, viewport);
+            #[cfg(feature = "time-travel")]
+            self.journal_command("undo", &action);
 
             // Move action to redo stack
             self.redo_stack.push(action);
@@ -150,88 +245,363 @@ impl UndoRedoState {
     }
 
     /// Perform redo operation
+    /// Refused while recording is suspended
     pub fn redo(&mut self, grid: &mut Grid, viewport: &mut Viewport) -> bool {
+        if self.is_suspended() {
+            return false;
+        }
+        self.close_groups();
         if let Some(action) = self.redo_stack.pop() {
-            match &action {
-                EditAction::SetValue { row, col, old_value, new_value } => {
-                    log::info!("[UndoRedo] Redoing SetValue: ({}, {}) \"{}\" -> \"{}\"",
-                               row, col, old_value.to_string(), new_value.to_string());
-                    // Re-apply new value without recording undo
-                    grid.set_value(*row, *col, new_value.clone());
-                }
-                EditAction::InsertRow { index, cells } => {
-                    // Redo insert
-                    grid.insert_row(*index);
+            Self::redo_action(&action, grid, viewport);
+            #[cfg(feature = "time-travel")]
+            self.journal_command("redo", &action);
+
+            // Move action back to undo stack
+            self.undo_stack.push(action);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Revert a single action
+    fn undo_action(action: &EditAction, grid: &mut Grid, viewport: &mut Viewport) {
+        match action {
+            EditAction::SetValue { row, col, old_value, new_value } => {
+                log::debug!("[UndoRedo] Undoing SetValue: ({}, {}) \"{}\" <- \"{}\"",
+                           row, col, old_value.to_string(), new_value.to_string());
+                // Restore old value without recording undo
+                grid.set_value(*row, *col, old_value.clone());
+            }
+            EditAction::InsertRow { index, .. } => {
+                // Undo insert by deleting the row
+                grid.delete_row(*index);
+                viewport.update_visible_range(grid);
+            }
+            EditAction::DeleteRow { index, row_id, cells } => {
+                // Undo delete by inserting the row back
+                grid.insert_row_with_id(*index, *row_id);
+                grid.restore_row_cells(*index, cells);
+                viewport.update_visible_range(grid);
+            }
+            EditAction::InsertColumn { index, cells: _ } => {
+                // Undo insert by deleting the column
+                grid.delete_column(*index);
+                viewport.update_visible_range(grid);
+            }
+            EditAction::DeleteColumn { index, cells } => {
+                // Undo delete by inserting the column back
+                grid.insert_column(*index);
+                grid.restore_column_cells(*index, cells);
+                viewport.update_visible_range(grid);
+            }
+            EditAction::DeleteRows { rows } => {
+                // Undo bulk delete by inserting rows back in reverse order (top to bottom)
+                for (index, row_id, cells) in rows.iter().rev() {
+                    grid.insert_row_with_id(*index, *row_id);
                     grid.restore_row_cells(*index, cells);
-                    viewport.update_visible_range(grid);
                 }
-                EditAction::DeleteRow { index, cells: _ } => {
-                    // Redo delete
-                    grid.delete_row(*index);
-                    viewport.update_visible_range(grid);
+                viewport.update_visible_range(grid);
+            }
+            EditAction::ClearCells { cells } => {
+                // Restore all cleared cell values
+                for (row, col, old_value) in cells.iter() {
+                    grid.set_value(*row, *col, old_value.clone());
                 }
-                EditAction::InsertColumn { index, cells } => {
-                    // Redo insert
-                    grid.insert_column(*index);
-                    grid.restore_column_cells(*index, cells);
-                    viewport.update_visible_range(grid);
+            }
+            EditAction::SetMultipleCells { cells } => {
+                // Restore all old cell values
+                for (row, col, old_value, _new_value) in cells.iter() {
+                    grid.set_value(*row, *col, old_value.clone());
                 }
-                EditAction::DeleteColumn { index, cells: _ } => {
-                    // Redo delete
-                    grid.delete_column(*index);
-                    viewport.update_visible_range(grid);
+            }
+            EditAction::SetStyle { row, col, old_style, new_style: _ } => {
+                // Restore old style
+                if let Some(cell) = grid.get_cell_mut(*row, *col) {
+                    cell.bg_color = old_style.bg_color;
+                    cell.fg_color = old_style.fg_color;
+                    cell.font_bold = old_style.font_bold;
+                    cell.font_italic = old_style.font_italic;
                 }
-                EditAction::DeleteRows { rows } => {
-                    // Redo bulk delete from bottom to top to avoid index shifting
-                    let mut sorted_indices: Vec<usize> = rows.iter().map(|(idx, _)| *idx).collect();
-                    sorted_indices.sort_unstable();
-                    sorted_indices.reverse();
-                    for index in sorted_indices {
-                        grid.delete_row(index);
-                    }
-                    viewport.update_visible_range(grid);
+            }
+            EditAction::ReplaceCells { cells } => {
+                // Restore old cells (or remove cells that did not exist before)
+                for (row, col, old_cell, _new_cell) in cells.iter() {
+                    Self::put_cell(grid, *row, *col, old_cell);
                 }
-                EditAction::ClearCells { cells } => {
-                    // Re-clear all cells
-                    for (row, col, _old_value) in cells.iter() {
-                        grid.set_value(*row, *col, CellValue::Empty);
-                    }
+            }
+            EditAction::ExpandGrid { old_rows, old_cols, action, .. } => {
+                // Revert the cell changes, then shrink back to the original size
+                Self::undo_action(action, grid, viewport);
+                grid.resize(*old_rows, *old_cols);
+                viewport.update_visible_range(grid);
+            }
+            EditAction::Composite { actions } => {
+                // Revert the grouped actions in reverse order
+                for action in actions.iter().rev() {
+                    Self::undo_action(action, grid, viewport);
                 }
-                EditAction::SetMultipleCells { cells } => {
-                    // Re-apply all new cell values
-                    for (row, col, _old_value, new_value) in cells.iter() {
-                        grid.set_value(*row, *col, new_value.clone());
-                    }
+            }
+            EditAction::SortRows { order, old_sort, .. } => {
+                grid.unpermute_rows(order);
+                grid.set_sort_state(old_sort);
+                viewport.update_visible_range(grid);
+            }
+            EditAction::ResizeColumn { col, old_width, .. } => {
+                grid.set_col_width(*col, *old_width);
+                viewport.update_visible_range(grid);
+            }
+            EditAction::SetColumnVisibility { cols, visible } => {
+                for &col in cols {
+                    grid.set_col_visible(col, !visible);
                 }
-                EditAction::SetStyle { row, col, old_style: _, new_style } => {
-                    // Re-apply new style
-                    if let Some(cell) = grid.get_cell_mut(*row, *col) {
-                        cell.bg_color = new_style.bg_color;
-                        cell.fg_color = new_style.fg_color;
-                        cell.font_bold = new_style.font_bold;
-                        cell.font_italic = new_style.font_italic;
-                    }
+                viewport.update_visible_range(grid);
+            }
+            EditAction::SetRowVisibility { rows, visible } => {
+                for &row in rows {
+                    grid.set_row_visible(row, !visible);
                 }
+                viewport.update_visible_range(grid);
+            }
+            EditAction::ResizeRow { row, old_height, .. } => {
+                grid.set_row_height(*row, *old_height);
+                viewport.update_visible_range(grid);
             }
+            EditAction::MoveColumn { from, to } => {
+                grid.move_column(*to, *from);
+            }
+            EditAction::SetFrozen { old_frozen, .. } => {
+                (grid.frozen_rows, grid.frozen_cols) = *old_frozen;
+            }
+            EditAction::SetFilter { old_rows, .. } => {
+                grid.set_filtered_rows(old_rows.clone());
+                viewport.update_visible_range(grid);
+            }
+        }
+    }
 
-            // Move action back to undo stack
-            self.undo_stack.push(action);
-            true
-        } else {
-            false
+    /// Re-apply a single action
+    fn redo_action(action: &EditAction, grid: &mut Grid, viewport: &mut Viewport) {
+        match action {
+            EditAction::SetValue { row, col, old_value, new_value } => {
+                log::debug!("[UndoRedo] Redoing SetValue: ({}, {}) \"{}\" -> \"{}\"",
+                           row, col, old_value.to_string(), new_value.to_string());
+                // Re-apply new value without recording undo
+                grid.set_value(*row, *col, new_value.clone());
+            }
+            EditAction::InsertRow { index, row_id, cells } => {
+                // Redo insert
+                grid.insert_row_with_id(*index, *row_id);
+                grid.restore_row_cells(*index, cells);
+                viewport.update_visible_range(grid);
+            }
+            EditAction::DeleteRow { index, .. } => {
+                // Redo delete
+                grid.delete_row(*index);
+                viewport.update_visible_range(grid);
+            }
+            EditAction::InsertColumn { index, cells } => {
+                // Redo insert
+                grid.insert_column(*index);
+                grid.restore_column_cells(*index, cells);
+                viewport.update_visible_range(grid);
+            }
+            EditAction::DeleteColumn { index, cells: _ } => {
+                // Redo delete
+                grid.delete_column(*index);
+                viewport.update_visible_range(grid);
+            }
+            EditAction::DeleteRows { rows } => {
+                // Redo bulk delete from bottom to top to avoid index shifting
+                let mut sorted_indices: Vec<usize> = rows.iter().map(|(idx, _, _)| *idx).collect();
+                sorted_indices.sort_unstable();
+                sorted_indices.reverse();
+                for index in sorted_indices {
+                    grid.delete_row(index);
+                }
+                viewport.update_visible_range(grid);
+            }
+            EditAction::ClearCells { cells } => {
+                // Re-clear all cells
+                for (row, col, _old_value) in cells.iter() {
+                    grid.set_value(*row, *col, CellValue::Empty);
+                }
+            }
+            EditAction::SetMultipleCells { cells } => {
+                // Re-apply all new cell values
+                for (row, col, _old_value, new_value) in cells.iter() {
+                    grid.set_value(*row, *col, new_value.clone());
+                }
+            }
+            EditAction::SetStyle { row, col, old_style: _, new_style } => {
+                // Re-apply new style
+                if let Some(cell) = grid.get_cell_mut(*row, *col) {
+                    cell.bg_color = new_style.bg_color;
+                    cell.fg_color = new_style.fg_color;
+                    cell.font_bold = new_style.font_bold;
+                    cell.font_italic = new_style.font_italic;
+                }
+            }
+            EditAction::ReplaceCells { cells } => {
+                // Re-apply new cells
+                for (row, col, _old_cell, new_cell) in cells.iter() {
+                    Self::put_cell(grid, *row, *col, new_cell);
+                }
+            }
+            EditAction::ExpandGrid { new_rows, new_cols, action, .. } => {
+                // Grow again, then re-apply the cell changes
+                grid.resize(*new_rows, *new_cols);
+                Self::redo_action(action, grid, viewport);
+                viewport.update_visible_range(grid);
+            }
+            EditAction::Composite { actions } => {
+                // Re-apply the grouped actions in their original order
+                for action in actions.iter() {
+                    Self::redo_action(action, grid, viewport);
+                }
+            }
+            EditAction::SortRows { order, new_sort, .. } => {
+                grid.permute_rows(order);
+                grid.set_sort_state(new_sort);
+                viewport.update_visible_range(grid);
+            }
+            EditAction::ResizeColumn { col, new_width, .. } => {
+                grid.set_col_width(*col, *new_width);
+                viewport.update_visible_range(grid);
+            }
+            EditAction::SetColumnVisibility { cols, visible } => {
+                for &col in cols {
+                    grid.set_col_visible(col, *visible);
+                }
+                viewport.update_visible_range(grid);
+            }
+            EditAction::SetRowVisibility { rows, visible } => {
+                for &row in rows {
+                    grid.set_row_visible(row, *visible);
+                }
+                viewport.update_visible_range(grid);
+            }
+            EditAction::ResizeRow { row, new_height, .. } => {
+                grid.set_row_height(*row, *new_height);
+                viewport.update_visible_range(grid);
+            }
+            EditAction::MoveColumn { from, to } => {
+                grid.move_column(*from, *to);
+            }
+            EditAction::SetFrozen { new_frozen, .. } => {
+                (grid.frozen_rows, grid.frozen_cols) = *new_frozen;
+            }
+            EditAction::SetFilter { new_rows, .. } => {
+                grid.set_filtered_rows(new_rows.clone());
+                viewport.update_visible_range(grid);
+            }
         }
     }
 
-    /// Record an action for undo
+    /// Record an action for undo (added to the open group, if any)
+    /// While suspended the action is dropped; the redo stack is still cleared,
+    /// since redoing would overwrite the unrecorded change
     pub fn record_action(&mut self, action: EditAction) {
-        self.undo_stack.push(action);
-        // Clear redo stack when new action is recorded
-        self.redo_stack.clear();
+        #[cfg(feature = "time-travel")]
+        if let Some(journal) = &mut self.journal {
+            journal.push(action.describe());
+        }
+        if self.is_suspended() {
+            // Nothing is kept, so a suspended region never ends up half-recorded
+        } else {
+            if self.group_depth > 0 {
+                self.group.push(action);
+            } else {
+                self.undo_stack.push(action);
+            }
+            if let Some(mark) = &mut self.edit_mark {
+                mark.recorded += 1;
+            }
+        }
+        // Clear redo stack when new action is recorded (kept aside while editing, in
+        // case the edit is cancelled)
+        match &mut self.edit_mark {
+            Some(mark) if mark.redo.is_none() => mark.redo = Some(std::mem::take(&mut self.redo_stack)),
+            _ => self.redo_stack.clear(),
+        }
+    }
+
+    /// Start grouping actions into one undo step; groups may be nested
+    pub fn begin_group(&mut self) {
+        self.group_depth += 1;
+    }
+
+    /// Close the innermost group; closing the outermost one records the grouped actions
+    /// as a single step. Returns false if no group was open.
+    pub fn end_group(&mut self) -> bool {
+        if self.group_depth == 0 {
+            return false;
+        }
+        self.group_depth -= 1;
+        if self.group_depth == 0 {
+            let mut actions = std::mem::take(&mut self.group);
+            match actions.len() {
+                0 => {}
+                1 => self.undo_stack.push(actions.remove(0)),
+                _ => self.undo_stack.push(EditAction::Composite { actions }),
+            }
+        }
+        true
     }
 
-    /// Clear undo history
+    /// Check if a group is open
+    pub fn is_grouping(&self) -> bool {
+        self.group_depth > 0
+    }
+
+    /// Stop recording actions (e.g. for programmatic bulk loads); calls may be nested
+    /// Undo/redo are refused until the matching resume()
+    pub fn suspend(&mut self) {
+        self.suspend_depth += 1;
+    }
+
+    /// Undo the innermost suspend(); returns false if recording was not suspended
+    pub fn resume(&mut self) -> bool {
+        if self.suspend_depth == 0 {
+            return false;
+        }
+        self.suspend_depth -= 1;
+        true
+    }
+
+    /// Check if recording is suspended
+    pub fn is_suspended(&self) -> bool {
+        self.suspend_depth > 0
+    }
+
+    /// Close all open groups
+    fn close_groups(&mut self) {
+        while self.end_group() {}
+    }
+
+    /// Write a whole cell back to the grid, removing it when `cell` is None
+    fn put_cell(grid: &mut Grid, row: usize, col: usize, cell: &Option<Cell>) {
+        match cell {
+            Some(cell) => grid.set_cell(row, col, cell.clone()),
+            None => {
+                grid.remove_cell(row, col);
+            }
+        }
+    }
+
+    /// Clear undo history (including an open group's actions)
     pub fn clear_undo_history(&mut self) {
         self.undo_stack.clear();
+        self.group.clear();
+        self.edit_mark = None;
+    }
+
+    /// Add an undo or redo of `action` to the time-travel journal
+    #[cfg(feature = "time-travel")]
+    fn journal_command(&mut self, command: &str, action: &EditAction) {
+        if let Some(journal) = &mut self.journal {
+            journal.push(serde_json::json!({ "command": command, "action": action.describe() }));
+        }
     }
 
     /// Clear redo history
@@ -258,3 +628,154 @@ impl UndoRedoState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undo_group_is_one_step() {
+        let mut grid = Grid::new(3, 3);
+        let mut viewport = Viewport::new(800.0, 600.0);
+        let mut undo_redo = UndoRedoState::new();
+
+        undo_redo.begin_group();
+        for row in 0..3 {
+            grid.set_value(row, 0, CellValue::Number(row as f64));
+            undo_redo.record_action(EditAction::SetValue {
+                row,
+                col: 0,
+                old_value: CellValue::Empty,
+                new_value: CellValue::Number(row as f64),
+            });
+        }
+        undo_redo.begin_group(); // Nested groups join the outer one
+        assert!(undo_redo.end_group());
+        assert!(undo_redo.undo_stack.is_empty());
+        assert!(undo_redo.end_group());
+        assert!(!undo_redo.end_group());
+        assert_eq!(undo_redo.undo_stack.len(), 1);
+        assert_eq!(undo_redo.undo_stack[0].value_cells(), vec![(0, 0), (1, 0), (2, 0)]);
+
+        assert!(undo_redo.undo(&mut grid, &mut viewport));
+        assert!((0..3).all(|row| grid.get_value(row, 0).is_empty()));
+        assert!(undo_redo.redo(&mut grid, &mut viewport));
+        assert_eq!(grid.get_value(2, 0), CellValue::Number(2.0));
+    }
+
+    #[test]
+    fn test_suspended_actions_are_dropped() {
+        let mut grid = Grid::new(3, 3);
+        let mut viewport = Viewport::new(800.0, 600.0);
+        let mut undo_redo = UndoRedoState::new();
+        let set = |row: usize| EditAction::SetValue {
+            row,
+            col: 0,
+            old_value: CellValue::Empty,
+            new_value: CellValue::Number(1.0),
+        };
+
+        undo_redo.record_action(set(0));
+        undo_redo.begin_group();
+        undo_redo.record_action(set(1));
+        undo_redo.suspend();
+        undo_redo.suspend();
+        undo_redo.record_action(set(2)); // Not part of the open group
+        assert!(!undo_redo.undo(&mut grid, &mut viewport));
+        assert!(undo_redo.resume());
+        assert!(undo_redo.is_suspended());
+        assert!(undo_redo.resume());
+        assert!(!undo_redo.resume());
+        undo_redo.end_group();
+
+        assert_eq!(undo_redo.undo_stack.len(), 2);
+        assert!(matches!(undo_redo.undo_stack[1], EditAction::SetValue { row: 1, .. }));
+    }
+
+    #[test]
+    fn test_filter_definitions_follow_undo() {
+        let filter = |text: &str| {
+            let mut filters = ColumnFilters::new();
+            filters.set_condition(0, crate::features::filter::FilterCondition::Contains(text.to_string()));
+            filters
+        };
+        let set = |old: &str, new: &str| EditAction::SetFilter {
+            old_rows: HashSet::new(),
+            new_rows: HashSet::new(),
+            old_filters: filter(old),
+            new_filters: filter(new),
+        };
+        let action = EditAction::Composite { actions: vec![set("a", "b"), set("b", "c")] };
+        assert_eq!(action.filters(true), Some(&filter("a")));
+        assert_eq!(action.filters(false), Some(&filter("c")));
+        assert!(EditAction::MoveColumn { from: 0, to: 1 }.filters(true).is_none());
+    }
+
+    #[test]
+    fn test_discard_cell_edits_stops_at_mark() {
+        let mut undo_redo = UndoRedoState::new();
+        let set = |row: usize| EditAction::SetValue {
+            row,
+            col: 0,
+            old_value: CellValue::Empty,
+            new_value: CellValue::Number(1.0),
+        };
+
+        undo_redo.record_action(set(0));
+        undo_redo.redo_stack.push(set(2));
+        undo_redo.mark_edit_start();
+        undo_redo.record_action(set(0));
+        undo_redo.record_action(set(0));
+        undo_redo.discard_cell_edits(0, 0);
+        assert_eq!(undo_redo.undo_stack.len(), 1);
+        assert_eq!(undo_redo.redo_stack.len(), 1); // Cleared by the edit, back on cancel
+
+        // Entries in an open group go too
+        undo_redo.begin_group();
+        undo_redo.mark_edit_start();
+        undo_redo.record_action(set(0));
+        undo_redo.discard_cell_edits(0, 0);
+        assert!(undo_redo.end_group());
+        assert_eq!(undo_redo.undo_stack.len(), 1);
+
+        undo_redo.mark_edit_start();
+        undo_redo.record_action(set(0));
+        undo_redo.record_action(set(1));
+        undo_redo.discard_cell_edits(0, 0); // Another cell's entry on top
+        assert_eq!(undo_redo.undo_stack.len(), 3);
+        assert!(undo_redo.redo_stack.is_empty());
+    }
+
+    #[test]
+    fn test_undo_sort_and_layout() {
+        let mut grid = Grid::new(4, 2);
+        let mut viewport = Viewport::new(800.0, 600.0);
+        let mut undo_redo = UndoRedoState::new();
+        for (row, n) in [3.0, 1.0, 4.0, 2.0].iter().enumerate() {
+            grid.set_value(row, 0, CellValue::Number(*n));
+            grid.set_value(row, 1, CellValue::Text(format!("r{}", row)));
+        }
+
+        let old_sort = grid.sort_state();
+        let order = grid.sort_by_column(0, true).unwrap();
+        undo_redo.record_action(EditAction::SortRows { order, old_sort, new_sort: grid.sort_state() });
+        grid.set_col_width(1, 150.0);
+        undo_redo.record_action(EditAction::ResizeColumn { col: 1, old_width: 100.0, new_width: 150.0 });
+        grid.move_column(1, 0);
+        undo_redo.record_action(EditAction::MoveColumn { from: 1, to: 0 });
+        assert_eq!(grid.get_value(0, 0), CellValue::Text("r1".to_string()));
+        assert_eq!(grid.col_width(0), 150.0);
+
+        assert!(undo_redo.undo(&mut grid, &mut viewport));
+        assert!(undo_redo.undo(&mut grid, &mut viewport));
+        assert!(undo_redo.undo(&mut grid, &mut viewport));
+        assert_eq!(grid.col_width(1), 100.0);
+        assert_eq!(grid.sort_column, None);
+        let column: Vec<String> = (0..4).map(|row| grid.get_value_string(row, 1)).collect();
+        assert_eq!(column, ["r0", "r1", "r2", "r3"]);
+
+        assert!(undo_redo.redo(&mut grid, &mut viewport));
+        assert_eq!(grid.get_value(0, 0), CellValue::Number(1.0));
+        assert_eq!(grid.sort_column, Some(0));
+    }
+}
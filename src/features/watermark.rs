@@ -0,0 +1,79 @@
+use crate::features::locale;
+use crate::GridError;
+use web_sys::HtmlImageElement;
+
+/// Watermark drawn over the cell area beneath the cell text (e.g. "DRAFT")
+#[derive(Clone, Debug, PartialEq)]
+pub struct Watermark {
+    pub text: Option<String>,
+    pub image_url: Option<String>,
+    pub image: Option<HtmlImageElement>, // Loaded from image_url by the grid
+    pub font_size: f32,
+    pub color: String,
+    pub opacity: f32,
+    pub angle: f32, // Rotation in degrees (clockwise)
+    pub scale: f32, // Image size relative to its natural size
+    pub repeat: bool, // Tile across the cell area instead of one centered mark
+}
+
+impl Watermark {
+    /// Parse {"text" | "image", "font_size", "color", "opacity", "angle", "scale", "repeat"}
+    pub fn from_json(options: &serde_json::Value) -> Result<Self, GridError> {
+        let text = options["text"].as_str().filter(|t| !t.is_empty()).map(str::to_string);
+        let image_url = options["image"].as_str().filter(|u| !u.is_empty()).map(str::to_string);
+        if text.is_none() && image_url.is_none() {
+            return Err(GridError::OperationError { message: locale::text("error.watermark_content") });
+        }
+        let font_size = options["font_size"].as_f64().unwrap_or(72.0) as f32;
+        let scale = options["scale"].as_f64().unwrap_or(1.0) as f32;
+        if font_size <= 0.0 || scale <= 0.0 {
+            return Err(GridError::OperationError { message: locale::text("error.watermark_size") });
+        }
+        Ok(Self {
+            text,
+            image_url,
+            image: None,
+            font_size,
+            color: options["color"].as_str().unwrap_or("#888888").to_string(),
+            opacity: options["opacity"].as_f64().unwrap_or(0.15).clamp(0.0, 1.0) as f32,
+            angle: options["angle"].as_f64().unwrap_or(-30.0) as f32,
+            scale,
+            repeat: options["repeat"].as_bool().unwrap_or(false),
+        })
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "text": self.text,
+            "image": self.image_url,
+            "font_size": self.font_size,
+            "color": self.color,
+            "opacity": self.opacity,
+            "angle": self.angle,
+            "scale": self.scale,
+            "repeat": self.repeat,
+        })
+    }
+
+    /// True while the watermark image is still loading (a broken image counts as loaded)
+    pub fn is_loading(&self) -> bool {
+        self.image.as_ref().is_some_and(|image| !image.complete())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watermark_options() {
+        let watermark = Watermark::from_json(&serde_json::json!({ "text": "DRAFT", "opacity": 3 })).unwrap();
+        assert_eq!(watermark.opacity, 1.0);
+        assert_eq!((watermark.angle, watermark.font_size), (-30.0, 72.0));
+        assert!(!watermark.is_loading());
+        assert_eq!(Watermark::from_json(&watermark.to_json()).unwrap(), watermark);
+
+        assert!(Watermark::from_json(&serde_json::json!({ "opacity": 0.2 })).is_err());
+        assert!(Watermark::from_json(&serde_json::json!({ "text": "X", "font_size": 0 })).is_err());
+    }
+}
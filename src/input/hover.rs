@@ -0,0 +1,72 @@
+/// Detects the mouse pointer dwelling on one cell (for content previews)
+pub struct HoverTracker {
+    pub delay_ms: f64,               // Dwell time before a preview is requested (0 = disabled)
+    cell: Option<(usize, usize)>,    // Cell under the pointer
+    since: f64,                      // Time the pointer entered the cell
+    reported: bool,                  // Dwell already reported for this cell
+    previewed: bool,                 // Host was asked to preview this cell
+}
+
+impl HoverTracker {
+    pub fn new(delay_ms: f64) -> Self {
+        Self {
+            delay_ms,
+            cell: None,
+            since: 0.0,
+            reported: false,
+            previewed: false,
+        }
+    }
+
+    /// Pointer moved to a cell (None = off the cells) at `time` (ms)
+    /// Returns the previously hovered cell if it was previewed and the pointer left it
+    pub fn move_to(&mut self, cell: Option<(usize, usize)>, time: f64) -> Option<(usize, usize)> {
+        if cell == self.cell {
+            return None;
+        }
+        let left = self.cell.filter(|_| self.previewed);
+        self.cell = cell;
+        self.since = time;
+        self.reported = false;
+        self.previewed = false;
+        left
+    }
+
+    /// Remember that a preview was requested for the hovered cell
+    pub fn mark_previewed(&mut self) {
+        self.previewed = true;
+    }
+
+    /// Report the hovered cell once the pointer has stayed on it for the delay (call periodically)
+    pub fn check_dwell(&mut self, time: f64) -> Option<(usize, usize)> {
+        if self.delay_ms <= 0.0 || self.reported || time - self.since < self.delay_ms {
+            return None;
+        }
+        self.reported = true;
+        self.cell
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dwell_reported_once_per_cell() {
+        let mut hover = HoverTracker::new(500.0);
+        hover.move_to(Some((1, 2)), 1000.0);
+        assert_eq!(hover.check_dwell(1400.0), None);
+        assert_eq!(hover.check_dwell(1500.0), Some((1, 2)));
+        assert_eq!(hover.check_dwell(2000.0), None);
+        hover.mark_previewed();
+
+        // Moving within the cell keeps the dwell; leaving reports the cell
+        assert_eq!(hover.move_to(Some((1, 2)), 2100.0), None);
+        assert_eq!(hover.move_to(Some((1, 3)), 2200.0), Some((1, 2)));
+        assert_eq!(hover.move_to(None, 2300.0), None);
+
+        hover.delay_ms = 0.0;
+        hover.move_to(Some((0, 0)), 3000.0);
+        assert_eq!(hover.check_dwell(9000.0), None);
+    }
+}
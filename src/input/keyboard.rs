@@ -1,11 +1,11 @@
 /// Keyboard event handler for grid navigation
 pub struct KeyboardHandler {
-    // Currently not storing state, but could be extended
+    pub type_to_edit: bool, // Printable keys start editing the selected cell
 }
 
 impl KeyboardHandler {
     pub fn new() -> Self {
-        Self {}
+        Self { type_to_edit: true }
     }
 
     /// Handle keyboard event and return navigation command
@@ -40,7 +40,18 @@ impl KeyboardHandler {
             // F2 key for edit mode
             "F2" => Some(NavigationCommand::EditCell),
 
-            _ => None,
+            // An IME starting a composition (key "Process") starts editing with an empty
+            // editor; the composed text goes into the editor
+            "Process" if self.type_to_edit => Some(NavigationCommand::ComposeToEdit),
+
+            // Printable characters start editing, replacing the content
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) if self.type_to_edit && !ch.is_control() => Some(NavigationCommand::TypeToEdit(ch)),
+                    _ => None,
+                }
+            }
         }
     }
 
@@ -52,7 +63,8 @@ impl KeyboardHandler {
                 "End" => Some(NavigationCommand::DocumentEnd),
                 "z" | "Z" => Some(NavigationCommand::Undo),
                 "y" | "Y" => Some(NavigationCommand::Redo),
-                _ => self.handle_key(key),
+                // Other Ctrl+character shortcuts are left to the host
+                _ => self.handle_key(key).filter(|command| !command.is_typing()),
             }
         } else if shift {
             // Shift-only modifiers (none currently)
@@ -89,4 +101,34 @@ pub enum NavigationCommand {
     Undo,
     Redo,
     EditCell,
+    TypeToEdit(char),
+    ComposeToEdit,
+}
+
+impl NavigationCommand {
+    /// Check if the command comes from typing text (type-to-edit or an IME composition)
+    pub fn is_typing(&self) -> bool {
+        matches!(self, NavigationCommand::TypeToEdit(_) | NavigationCommand::ComposeToEdit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_to_edit_keys() {
+        let handler = KeyboardHandler::new();
+        assert_eq!(handler.handle_key("a"), Some(NavigationCommand::TypeToEdit('a')));
+        assert_eq!(handler.handle_key("é"), Some(NavigationCommand::TypeToEdit('é')));
+        assert_eq!(handler.handle_key("F2"), Some(NavigationCommand::EditCell));
+        assert_eq!(handler.handle_key("Shift"), None);
+        assert_eq!(handler.handle_key_with_modifiers("c", true, false), None);
+        assert_eq!(handler.handle_key_with_modifiers("A", false, true), Some(NavigationCommand::TypeToEdit('A')));
+        assert_eq!(handler.handle_key("Process"), Some(NavigationCommand::ComposeToEdit));
+
+        let handler = KeyboardHandler { type_to_edit: false };
+        assert_eq!(handler.handle_key("a"), None);
+        assert_eq!(handler.handle_key("Process"), None);
+    }
 }
@@ -0,0 +1,205 @@
+/// Velocity retained per 16ms frame during momentum scrolling
+const MOMENTUM_FRICTION: f32 = 0.95;
+
+/// Momentum stops below this velocity (px/ms)
+const MIN_MOMENTUM_VELOCITY: f32 = 0.02;
+
+/// Wheel momentum starts once no wheel event arrived for this long (ms)
+const WHEEL_IDLE_MS: f64 = 60.0;
+
+/// Wheel samples further apart than this (ms) start a new fling
+const WHEEL_SAMPLE_GAP_MS: f64 = 100.0;
+
+/// Minimum wheel velocity (px/ms) that continues scrolling after the wheel stops
+const MIN_WHEEL_FLING_VELOCITY: f32 = 0.5;
+
+/// Animated scroll towards a fixed position (smooth_scroll_to)
+#[derive(Clone, Copy, Debug)]
+struct ScrollAnimation {
+    from: (f32, f64),
+    to: (f32, f64),
+    duration: f64,
+    start_time: Option<f64>, // Set on the first step
+}
+
+/// Kinetic scrolling state stepped once per frame: momentum after a touch/wheel
+/// fling and eased scroll animations. Works in grid pixels.
+pub struct KineticScroller {
+    velocity_x: f32, // px/ms in scroll direction
+    velocity_y: f32,
+    momentum: bool,
+    last_step_time: Option<f64>,
+    last_wheel_time: Option<f64>,
+    animation: Option<ScrollAnimation>,
+}
+
+impl KineticScroller {
+    pub fn new() -> Self {
+        Self {
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            momentum: false,
+            last_step_time: None,
+            last_wheel_time: None,
+            animation: None,
+        }
+    }
+
+    /// Continue scrolling with the given velocity (px/ms), decelerating each frame
+    pub fn fling(&mut self, velocity_x: f32, velocity_y: f32) {
+        self.stop();
+        self.velocity_x = velocity_x;
+        self.velocity_y = velocity_y;
+        self.momentum = true;
+    }
+
+    /// Record a wheel scroll; once the wheel stops, a fast enough wheel keeps scrolling
+    pub fn wheel(&mut self, dx: f32, dy: f32, time: f64) {
+        let previous = self.last_wheel_time;
+        let (velocity_x, velocity_y) = (self.velocity_x, self.velocity_y);
+        self.stop();
+
+        if let Some(last) = previous {
+            let dt = (time - last) as f32;
+            if dt > 0.0 && (time - last) < WHEEL_SAMPLE_GAP_MS {
+                // Smooth velocity to avoid spikes from uneven event timing
+                self.velocity_x = 0.8 * (dx / dt) + 0.2 * velocity_x;
+                self.velocity_y = 0.8 * (dy / dt) + 0.2 * velocity_y;
+            }
+        }
+        self.last_wheel_time = Some(time);
+    }
+
+    /// Animate the scroll position from `from` to `to` over `duration_ms`
+    pub fn animate_to(&mut self, from: (f32, f64), to: (f32, f64), duration_ms: f64) {
+        self.stop();
+        self.animation = Some(ScrollAnimation { from, to, duration: duration_ms.max(1.0), start_time: None });
+    }
+
+    /// Advance one frame; returns the new scroll position, or None if nothing is moving
+    pub fn step(&mut self, time: f64, scroll_x: f32, scroll_y: f64) -> Option<(f32, f64)> {
+        if let Some(animation) = self.animation.as_mut() {
+            let start = *animation.start_time.get_or_insert(time);
+            let t = ((time - start) / animation.duration).clamp(0.0, 1.0) as f32;
+            let eased = 1.0 - (1.0 - t).powi(3); // ease-out cubic
+            let x = animation.from.0 + (animation.to.0 - animation.from.0) * eased;
+            let y = animation.from.1 + (animation.to.1 - animation.from.1) * eased as f64;
+            if t >= 1.0 {
+                self.animation = None;
+            }
+            return Some((x, y));
+        }
+
+        // Wheel stopped: keep going if it was fast enough
+        if let Some(last) = self.last_wheel_time {
+            if time - last < WHEEL_IDLE_MS {
+                return None;
+            }
+            self.last_wheel_time = None;
+            let fast = self.velocity_x.abs().max(self.velocity_y.abs()) >= MIN_WHEEL_FLING_VELOCITY;
+            if fast {
+                self.momentum = true;
+                self.last_step_time = Some(time);
+            } else {
+                self.stop();
+            }
+            return None;
+        }
+
+        if !self.momentum {
+            return None;
+        }
+
+        // First frame of a fling only records the time
+        let last = self.last_step_time.replace(time)?;
+        let dt = (time - last).max(0.0) as f32;
+
+        let dx = self.velocity_x * dt;
+        let dy = self.velocity_y * dt;
+
+        let decay = MOMENTUM_FRICTION.powf(dt / 16.0);
+        self.velocity_x *= decay;
+        self.velocity_y *= decay;
+
+        if self.velocity_x.abs() < MIN_MOMENTUM_VELOCITY && self.velocity_y.abs() < MIN_MOMENTUM_VELOCITY {
+            self.stop();
+        }
+
+        Some((scroll_x + dx, scroll_y + dy as f64))
+    }
+
+    /// Stop momentum and animations
+    pub fn stop(&mut self) {
+        self.velocity_x = 0.0;
+        self.velocity_y = 0.0;
+        self.momentum = false;
+        self.last_step_time = None;
+        self.last_wheel_time = None;
+        self.animation = None;
+    }
+
+    /// Check if momentum scrolling is in progress (not an animation)
+    pub fn is_momentum_active(&self) -> bool {
+        self.momentum
+    }
+
+    /// Check if anything is (or may start) moving
+    pub fn is_active(&self) -> bool {
+        self.momentum || self.animation.is_some() || self.last_wheel_time.is_some()
+    }
+}
+
+impl Default for KineticScroller {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fling_decelerates() {
+        let mut kinetic = KineticScroller::new();
+        kinetic.fling(0.0, 2.0);
+        assert_eq!(kinetic.step(0.0, 0.0, 100.0), None);
+
+        let (_, y1) = kinetic.step(16.0, 0.0, 100.0).unwrap();
+        assert_eq!(y1, 132.0);
+        let (_, y2) = kinetic.step(32.0, 0.0, y1).unwrap();
+        assert!(y2 - y1 < y1 - 100.0);
+
+        kinetic.stop();
+        assert!(!kinetic.is_active());
+    }
+
+    #[test]
+    fn test_wheel_fling_starts_when_wheel_stops() {
+        let mut kinetic = KineticScroller::new();
+        kinetic.wheel(0.0, 100.0, 0.0);
+        kinetic.wheel(0.0, 100.0, 20.0);
+        assert_eq!(kinetic.step(40.0, 0.0, 200.0), None); // Still wheeling
+        assert_eq!(kinetic.step(100.0, 0.0, 200.0), None); // Momentum starts
+        assert!(kinetic.is_momentum_active());
+        assert!(kinetic.step(116.0, 0.0, 200.0).unwrap().1 > 200.0);
+
+        // Slow wheel does not fling
+        kinetic.stop();
+        kinetic.wheel(0.0, 3.0, 0.0);
+        kinetic.wheel(0.0, 3.0, 50.0);
+        kinetic.step(200.0, 0.0, 0.0);
+        assert!(!kinetic.is_active());
+    }
+
+    #[test]
+    fn test_animate_to() {
+        let mut kinetic = KineticScroller::new();
+        kinetic.animate_to((0.0, 0.0), (100.0, 400.0), 200.0);
+        assert_eq!(kinetic.step(1000.0, 0.0, 0.0), Some((0.0, 0.0)));
+        let (x, y) = kinetic.step(1100.0, 0.0, 0.0).unwrap();
+        assert!(x > 50.0 && x < 100.0 && y > 200.0 && y < 400.0);
+        assert_eq!(kinetic.step(1200.0, x, y), Some((100.0, 400.0)));
+        assert!(!kinetic.is_active());
+    }
+}
@@ -0,0 +1,72 @@
+use crate::features::locale;
+use crate::GridError;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{AddEventListenerOptions, Element, Event, EventTarget, ResizeObserver};
+
+type Listener = Closure<dyn FnMut(Event)>;
+type ResizeCallback = Closure<dyn FnMut(js_sys::Array)>;
+
+fn dom_error(what: &str) -> GridError {
+    GridError::OperationError { message: locale::format("error.dom", &[("error", &what)]) }
+}
+
+/// DOM event listeners installed by attach_event_listeners
+///
+/// Every listener is removed (and its closure released) by `remove_all()` or when
+/// the set is dropped.
+#[derive(Default)]
+pub struct EventListeners {
+    listeners: Vec<(EventTarget, &'static str, Listener)>,
+    resize: Option<(ResizeObserver, ResizeCallback)>,
+}
+
+impl EventListeners {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Listen for `event_type` on `target`. Listeners are not passive, so handlers may
+    /// call preventDefault (wheel and touchmove would otherwise scroll the page).
+    pub fn add(&mut self, target: &EventTarget, event_type: &'static str, handler: impl FnMut(Event) + 'static) -> Result<(), GridError> {
+        let closure = Closure::wrap(Box::new(handler) as Box<dyn FnMut(Event)>);
+        let options = AddEventListenerOptions::new();
+        options.set_passive(false);
+        target
+            .add_event_listener_with_callback_and_add_event_listener_options(event_type, closure.as_ref().unchecked_ref(), &options)
+            .map_err(|_| dom_error(&format!("add the {} listener", event_type)))?;
+        self.listeners.push((target.clone(), event_type, closure));
+        Ok(())
+    }
+
+    /// Call `handler` with the new content size whenever `element` is resized
+    pub fn observe_resize(&mut self, element: &Element, mut handler: impl FnMut(f64, f64) + 'static) -> Result<(), GridError> {
+        let closure = Closure::wrap(Box::new(move |entries: js_sys::Array| {
+            // Only the last entry matters when several resizes were batched
+            if let Some(entry) = entries.iter().last().and_then(|e| e.dyn_into::<web_sys::ResizeObserverEntry>().ok()) {
+                let rect = entry.content_rect();
+                handler(rect.width(), rect.height());
+            }
+        }) as Box<dyn FnMut(js_sys::Array)>);
+        let observer = ResizeObserver::new(closure.as_ref().unchecked_ref()).map_err(|_| dom_error("create a ResizeObserver"))?;
+        observer.observe(element);
+        self.resize = Some((observer, closure));
+        Ok(())
+    }
+
+    /// Remove every listener and stop observing resizes
+    pub fn remove_all(&mut self) {
+        for (target, event_type, closure) in self.listeners.drain(..) {
+            let _ = target.remove_event_listener_with_callback(event_type, closure.as_ref().unchecked_ref());
+        }
+        if let Some((observer, _closure)) = self.resize.take() {
+            observer.disconnect();
+        }
+    }
+}
+
+impl Drop for EventListeners {
+    fn drop(&mut self) {
+        self.remove_all();
+    }
+}
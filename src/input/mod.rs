@@ -1,5 +1,13 @@
+pub mod hover;
 pub mod keyboard;
+pub mod kinetic;
+pub mod listeners;
 pub mod mouse;
+pub mod touch;
 
+pub use hover::HoverTracker;
 pub use keyboard::{KeyboardHandler, NavigationCommand};
+pub use kinetic::KineticScroller;
+pub use listeners::EventListeners;
 pub use mouse::MouseHandler;
+pub use touch::{TouchGesture, TouchHandler};
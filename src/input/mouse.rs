@@ -5,6 +5,8 @@ pub struct MouseHandler {
     pub last_x: f32,
     pub last_y: f32,
     pub selected_cell: Option<(usize, usize)>,
+    pub hovered_cell: Option<(usize, usize)>, // Cell under the pointer (hover highlight)
+    pub hover_events: bool,                   // Queue cell_hovered events when it changes
 }
 
 impl MouseHandler {
@@ -15,6 +17,8 @@ impl MouseHandler {
             last_x: 0.0,
             last_y: 0.0,
             selected_cell: None,
+            hovered_cell: None,
+            hover_events: false,
         }
     }
 
@@ -50,6 +54,14 @@ impl MouseHandler {
     pub fn select_cell(&mut self, row: usize, col: usize) {
         self.selected_cell = Some((row, col));
     }
+
+    /// Pointer moved over `cell` (None = off the cells)
+    /// Returns true if the hovered cell changed
+    pub fn hover(&mut self, cell: Option<(usize, usize)>) -> bool {
+        let changed = cell != self.hovered_cell;
+        self.hovered_cell = cell;
+        changed
+    }
 }
 
 impl Default for MouseHandler {
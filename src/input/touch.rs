@@ -0,0 +1,282 @@
+/// Movement (px) allowed before a touch stops being a tap/long-press
+const TAP_SLOP: f32 = 10.0;
+
+/// Hold time (ms) before a touch becomes a long-press
+const LONG_PRESS_MS: f64 = 500.0;
+
+/// Maximum time (ms) between taps for a double-tap
+const DOUBLE_TAP_MS: f64 = 300.0;
+
+/// Gesture recognized from touch input, in canvas coordinates
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TouchGesture {
+    None,
+    Pan { dx: f32, dy: f32 },          // Scroll content by (dx, dy)
+    Tap { x: f32, y: f32 },            // Quick touch without movement
+    DoubleTap { x: f32, y: f32 },      // Second tap on the same spot
+    LongPress { x: f32, y: f32 },      // Held in place (starts drag selection)
+    DragSelect { x: f32, y: f32 },     // Moved after a long-press
+    ContextMenu { x: f32, y: f32 },    // Released after a long-press without dragging
+    Pinch { x: f32, y: f32, scale: f32, dx: f32, dy: f32 }, // Two-finger zoom by scale around (x, y), plus scroll
+    Fling { vx: f32, vy: f32 },        // Released while panning: continue with momentum (px/ms)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TouchMode {
+    Idle,
+    Pending,    // Finger down, gesture not decided yet
+    Panning,    // One-finger pan
+    TwoFinger,  // Two-finger scroll
+    LongPress,  // Held in place
+    DragSelect, // Dragging after a long-press
+}
+
+/// Touch event handler: turns raw touch points into gestures
+pub struct TouchHandler {
+    mode: TouchMode,
+    start_x: f32,
+    start_y: f32,
+    start_time: f64,
+    last_x: f32,
+    last_y: f32,
+    last_time: f64,
+    velocity_x: f32, // px/ms in scroll direction
+    velocity_y: f32,
+    last_spread: f32, // Average finger distance from the center (0 = single touch)
+    last_tap: Option<(f32, f32, f64)>,
+}
+
+impl TouchHandler {
+    pub fn new() -> Self {
+        Self {
+            mode: TouchMode::Idle,
+            start_x: 0.0,
+            start_y: 0.0,
+            start_time: 0.0,
+            last_x: 0.0,
+            last_y: 0.0,
+            last_time: 0.0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            last_spread: 0.0,
+            last_tap: None,
+        }
+    }
+
+    /// Touch started; (x, y) is the touch point (or the center of all touches)
+    /// and spread the average distance of the touches from that center
+    pub fn touch_start(&mut self, x: f32, y: f32, spread: f32, touch_count: usize, time: f64) -> TouchGesture {
+        self.velocity_x = 0.0;
+        self.velocity_y = 0.0;
+        self.mode = if touch_count >= 2 { TouchMode::TwoFinger } else { TouchMode::Pending };
+        self.start_x = x;
+        self.start_y = y;
+        self.start_time = time;
+        self.last_x = x;
+        self.last_y = y;
+        self.last_time = time;
+        self.last_spread = spread;
+        TouchGesture::None
+    }
+
+    /// Touch moved
+    pub fn touch_move(&mut self, x: f32, y: f32, spread: f32, touch_count: usize, time: f64) -> TouchGesture {
+        match self.mode {
+            TouchMode::Idle => TouchGesture::None,
+            TouchMode::Pending => {
+                if touch_count >= 2 {
+                    // Second finger landed: switch to two-finger scroll from here
+                    self.mode = TouchMode::TwoFinger;
+                    self.last_x = x;
+                    self.last_y = y;
+                    self.last_time = time;
+                    self.last_spread = spread;
+                    TouchGesture::None
+                } else if self.distance_from_start(x, y) > TAP_SLOP {
+                    self.mode = TouchMode::Panning;
+                    self.pan_to(x, y, time)
+                } else {
+                    self.check_long_press(time)
+                }
+            }
+            TouchMode::Panning => self.pan_to(x, y, time),
+            TouchMode::TwoFinger => self.pinch_to(x, y, spread, time),
+            TouchMode::LongPress => {
+                if self.distance_from_start(x, y) > TAP_SLOP {
+                    self.mode = TouchMode::DragSelect;
+                    TouchGesture::DragSelect { x, y }
+                } else {
+                    TouchGesture::None
+                }
+            }
+            TouchMode::DragSelect => TouchGesture::DragSelect { x, y },
+        }
+    }
+
+    /// Touch ended; remaining_touches is the number of fingers still down
+    pub fn touch_end(&mut self, remaining_touches: usize, time: f64) -> TouchGesture {
+        if remaining_touches > 0 {
+            // Lifting one finger of a two-finger scroll: ignore the rest of the gesture
+            if self.mode == TouchMode::TwoFinger {
+                self.mode = TouchMode::Idle;
+            }
+            return TouchGesture::None;
+        }
+
+        let gesture = match self.mode {
+            TouchMode::Pending => {
+                if time - self.start_time >= LONG_PRESS_MS {
+                    TouchGesture::ContextMenu { x: self.start_x, y: self.start_y }
+                } else {
+                    self.tap(time)
+                }
+            }
+            TouchMode::Panning | TouchMode::TwoFinger => {
+                // Fling only if the finger was still moving when released
+                if time - self.last_time < 100.0 {
+                    TouchGesture::Fling { vx: self.velocity_x, vy: self.velocity_y }
+                } else {
+                    TouchGesture::None
+                }
+            }
+            TouchMode::LongPress => TouchGesture::ContextMenu { x: self.start_x, y: self.start_y },
+            TouchMode::DragSelect | TouchMode::Idle => TouchGesture::None,
+        };
+
+        self.mode = TouchMode::Idle;
+        gesture
+    }
+
+    /// Touch cancelled by the browser
+    pub fn touch_cancel(&mut self) {
+        self.mode = TouchMode::Idle;
+    }
+
+    /// Detect a long-press while the finger is held still (call periodically)
+    pub fn check_long_press(&mut self, time: f64) -> TouchGesture {
+        if self.mode == TouchMode::Pending && time - self.start_time >= LONG_PRESS_MS {
+            self.mode = TouchMode::LongPress;
+            TouchGesture::LongPress { x: self.start_x, y: self.start_y }
+        } else {
+            TouchGesture::None
+        }
+    }
+
+    /// Check if a touch gesture is in progress
+    pub fn is_active(&self) -> bool {
+        self.mode != TouchMode::Idle
+    }
+
+    fn distance_from_start(&self, x: f32, y: f32) -> f32 {
+        ((x - self.start_x).powi(2) + (y - self.start_y).powi(2)).sqrt()
+    }
+
+    fn pan_to(&mut self, x: f32, y: f32, time: f64) -> TouchGesture {
+        // Content scrolls opposite to finger movement
+        let dx = self.last_x - x;
+        let dy = self.last_y - y;
+
+        let dt = (time - self.last_time) as f32;
+        if dt > 0.0 {
+            // Smooth velocity to avoid spikes from uneven event timing
+            self.velocity_x = 0.8 * (dx / dt) + 0.2 * self.velocity_x;
+            self.velocity_y = 0.8 * (dy / dt) + 0.2 * self.velocity_y;
+        }
+
+        self.last_x = x;
+        self.last_y = y;
+        self.last_time = time;
+        TouchGesture::Pan { dx, dy }
+    }
+
+    fn pinch_to(&mut self, x: f32, y: f32, spread: f32, time: f64) -> TouchGesture {
+        let pan = self.pan_to(x, y, time);
+        let scale = if self.last_spread > 0.0 && spread > 0.0 { spread / self.last_spread } else { 1.0 };
+        self.last_spread = spread;
+
+        match pan {
+            TouchGesture::Pan { dx, dy } if scale != 1.0 => TouchGesture::Pinch { x, y, scale, dx, dy },
+            other => other,
+        }
+    }
+
+    fn tap(&mut self, time: f64) -> TouchGesture {
+        let (x, y) = (self.start_x, self.start_y);
+
+        if let Some((tap_x, tap_y, tap_time)) = self.last_tap.take() {
+            let near = (x - tap_x).abs() <= TAP_SLOP && (y - tap_y).abs() <= TAP_SLOP;
+            if near && time - tap_time <= DOUBLE_TAP_MS {
+                return TouchGesture::DoubleTap { x, y };
+            }
+        }
+
+        self.last_tap = Some((x, y, time));
+        TouchGesture::Tap { x, y }
+    }
+}
+
+impl Default for TouchHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tap_and_double_tap() {
+        let mut touch = TouchHandler::new();
+        touch.touch_start(50.0, 50.0, 0.0, 1, 0.0);
+        assert_eq!(touch.touch_end(0, 80.0), TouchGesture::Tap { x: 50.0, y: 50.0 });
+
+        touch.touch_start(52.0, 51.0, 0.0, 1, 200.0);
+        assert_eq!(touch.touch_end(0, 260.0), TouchGesture::DoubleTap { x: 52.0, y: 51.0 });
+    }
+
+    #[test]
+    fn test_pan_then_fling() {
+        let mut touch = TouchHandler::new();
+        touch.touch_start(100.0, 300.0, 0.0, 1, 0.0);
+        assert_eq!(touch.touch_move(100.0, 280.0, 0.0, 1, 16.0), TouchGesture::Pan { dx: 0.0, dy: 20.0 });
+        assert_eq!(touch.touch_move(100.0, 240.0, 0.0, 1, 32.0), TouchGesture::Pan { dx: 0.0, dy: 40.0 });
+        match touch.touch_end(0, 40.0) {
+            TouchGesture::Fling { vx, vy } => assert!(vx == 0.0 && vy > 0.0),
+            other => panic!("expected fling, got {:?}", other),
+        }
+
+        // Finger held still before release: no fling
+        touch.touch_start(100.0, 300.0, 0.0, 1, 100.0);
+        touch.touch_move(100.0, 200.0, 0.0, 1, 116.0);
+        assert_eq!(touch.touch_end(0, 400.0), TouchGesture::None);
+    }
+
+    #[test]
+    fn test_long_press_then_drag_select() {
+        let mut touch = TouchHandler::new();
+        touch.touch_start(10.0, 10.0, 0.0, 1, 0.0);
+        assert_eq!(touch.check_long_press(300.0), TouchGesture::None);
+        assert_eq!(touch.check_long_press(600.0), TouchGesture::LongPress { x: 10.0, y: 10.0 });
+        assert_eq!(touch.touch_move(80.0, 10.0, 0.0, 1, 700.0), TouchGesture::DragSelect { x: 80.0, y: 10.0 });
+        assert_eq!(touch.touch_end(0, 800.0), TouchGesture::None);
+
+        // Long-press released in place opens the context menu
+        touch.touch_start(10.0, 10.0, 0.0, 1, 1000.0);
+        touch.check_long_press(1600.0);
+        assert_eq!(touch.touch_end(0, 1700.0), TouchGesture::ContextMenu { x: 10.0, y: 10.0 });
+    }
+
+    #[test]
+    fn test_two_finger_pinch() {
+        let mut touch = TouchHandler::new();
+        touch.touch_start(100.0, 100.0, 50.0, 2, 0.0);
+        assert_eq!(
+            touch.touch_move(100.0, 100.0, 100.0, 2, 16.0),
+            TouchGesture::Pinch { x: 100.0, y: 100.0, scale: 2.0, dx: 0.0, dy: 0.0 }
+        );
+
+        // Constant spread is a plain two-finger scroll
+        assert_eq!(touch.touch_move(100.0, 90.0, 100.0, 2, 32.0), TouchGesture::Pan { dx: 0.0, dy: 10.0 });
+    }
+}
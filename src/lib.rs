@@ -4,41 +4,131 @@ mod features;
 mod input;
 mod renderer;
 
-use std::collections::HashSet;
+use datagrid5_macros::js_export;
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::{HashMap, HashSet};
+use std::rc::{Rc, Weak};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
-use web_sys::{HtmlCanvasElement, KeyboardEvent, MouseEvent, WheelEvent};
+use web_sys::{HtmlCanvasElement, HtmlImageElement, KeyboardEvent, MouseEvent, TouchEvent, WheelEvent};
 
-use core::{cell::CellBorder, Cell, CellBorders, CellValue, ColumnConfig, DataType, Grid, Viewport};
+use core::{cell::CellBorder, Cell, CellBorders, CellValue, Collation, ColumnConfig, DataType, Density, EditableRule, Grid, NumberFormat, PreviewMode, RowLimits, SectionBreak, SortClick, SortState, UniqueKey, ValidationRule, Viewport, WidthMode};
 pub use error::GridError;
 use features::{
-    editing::EditingState, resize::ResizeState, search::SearchState,
-    selection::SelectionState, undo_redo::UndoRedoState, EditAction, CellStyle,
+    autocomplete::Autocomplete, batch::{self, BatchReport, SkippedEntry}, clipboard::{self, ClipboardOps, CopyFormat, PasteConflict, PasteContent, PastePolicy, PasteSpecial, PastedCell}, datasource::{VirtualSource, DEFAULT_BLOCK_SIZE, DEFAULT_CACHE_BLOCKS}, detail::DetailPanels, html_table, editing::{self, EditingState, EditorKey, TextBuffer}, locale, pivot::{FlatState, PivotConfig, PivotView}, print::{self, PageLayout, PageSetup, PageTile}, range_move::{self, CellRange, RangeMove, ShiftAxis}, events::EventQueue, footer::{Aggregate, FooterAggregates, FOOTER_BAND_HEIGHT}, layout::Layout, filter::{ColumnFilter, ColumnFilters, FilterCondition, FilterExpression}, hooks::{self, HookDecision, OperationHooks}, resize::{AutoFitMode, ResizeHandle, ResizeState}, search::{self, SearchKind, SearchState}, stream::{StreamFormat, StreamLoad, StreamParser, StreamRecord},
+    row_animation::{self, RowAnimation, DEFAULT_ROW_ANIMATION_MS}, selection::{HeaderFocus, SelectionState}, series::{self, SeriesScope}, summary::{ColumnStats, ColumnSummary, SummaryCache, SummaryMode, SUMMARY_BAND_HEIGHT}, template::CellTemplate, totals::ColumnTotals, undo_redo::UndoRedoState, watermark::Watermark, EditAction, CellStyle,
 };
-use input::{KeyboardHandler, MouseHandler, NavigationCommand};
-use renderer::{TextRenderer, WebGLRenderer};
+#[cfg(feature = "persistence")]
+use features::persistence;
+#[cfg(feature = "time-travel")]
+use features::time_travel::TimeTravel;
+use input::{EventListeners, HoverTracker, KeyboardHandler, KineticScroller, MouseHandler, NavigationCommand, TouchGesture, TouchHandler};
+use renderer::{text::{HeaderHighlight, HoverStyle}, GridRenderer, RenderLoop, RenderState, TextRenderer};
+
+/// Maximum number of dirty cells redrawn individually before falling back to a full render
+const MAX_PARTIAL_RENDER_CELLS: usize = 500;
+
+/// Duration of the column header flash shown by goto_column
+const HEADER_FLASH_MS: f64 = 1200.0;
+
+/// Largest canvas side (device pixels) export_png draws a cell range on
+const MAX_EXPORT_PIXELS: f32 = 16_384.0;
+
+/// Default auto-expansion caps used when the "auto_expand" option omits max_rows/max_cols
+const DEFAULT_AUTO_EXPAND_ROWS: usize = 100_000;
+const DEFAULT_AUTO_EXPAND_COLS: usize = 1_000;
+
+/// Options update_options_json can change at runtime, with the JSON type each expects
+const LIVE_OPTIONS: &[(&str, &str)] = &[
+    ("readonly", "boolean"),
+    ("show_headers", "boolean"),
+    ("show_grid_lines", "boolean"),
+    ("enable_context_menu", "boolean"),
+    ("enable_row_selection", "boolean"),
+    ("enable_col_selection", "boolean"),
+    ("alternate_row_colors", "boolean"),
+    ("ghost_row", "boolean"),
+    ("ghost_column", "boolean"),
+    ("wheel_momentum", "boolean"),
+    ("strict_batch", "boolean"),
+    ("auto_refresh_filters", "boolean"),
+    ("auto_expand", "boolean"),
+    ("auto_text_contrast", "boolean"),
+    ("native_editing", "boolean"),
+    ("type_to_edit", "boolean"),
+    ("autocomplete_on_tab", "boolean"),
+    ("row_animation_ms", "number"),
+    ("frozen_rows", "count"),
+    ("frozen_cols", "count"),
+    ("row_label_start", "count"),
+    ("max_rows", "count"),
+    ("max_cols", "count"),
+    ("row_header_width", "number"),
+    ("col_header_height", "number"),
+    ("cell_padding", "number"),
+    ("content_preview_delay", "number"),
+    ("paste_policy", "string"),
+    ("copy_format", "string"),
+    ("density", "string"),
+    ("auto_fit_on_load", "string"),
+    ("column_summary", "string"),
+    ("log_level", "string"),
+];
+
+/// Options that size the grid or create its canvases, so they only apply in from_container
+const CREATION_OPTIONS: &[&str] = &["rows", "cols", "width", "height", "columns", "renderer"];
 
 // Use wee_alloc as the global allocator for smaller WASM size
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
-/// Main DataGrid control
-#[wasm_bindgen]
+/// Grid state behind the `DataGrid` JS class (see DataGridHandle)
 pub struct DataGrid {
     grid: Grid,
     viewport: Viewport,
-    webgl_renderer: WebGLRenderer,
+    renderer: GridRenderer,
     text_renderer: TextRenderer,
     mouse_handler: MouseHandler,
     keyboard_handler: KeyboardHandler,
+    touch_handler: TouchHandler,
+    hover: HoverTracker,
+    kinetic: KineticScroller,
+    wheel_momentum: bool,       // Continue scrolling after a fast wheel
     webgl_canvas: HtmlCanvasElement,
     text_canvas: HtmlCanvasElement,
     // Feature modules
     editing: EditingState,
     selection: SelectionState,
     resize: ResizeState,
+    range_move: Option<RangeMove>, // Selection being dragged by its border
+    cut_cells: Option<Vec<Vec<Option<Cell>>>>, // Last cut rectangle, for insert_cut_cells
     search: SearchState,
     undo_redo: UndoRedoState,
+    events: EventQueue,
+    paste_policy: PastePolicy,
+    copy_format: CopyFormat,
+    summary_mode: SummaryMode,  // Summary band under the column headers
+    summary_cache: SummaryCache, // Lazily computed column summaries
+    column_totals: ColumnTotals, // Custom column totals from JS reducers
+    footer: FooterAggregates,    // Aggregates shown in the footer band
+    auto_fit_on_load: AutoFitMode, // Column widths computed by load_data_json
+    strict_batch: bool,            // Reject a whole batch load if any entry is invalid
+    pivot: Option<PivotView>,      // Pivot view shown instead of the flat grid
+    details: DetailPanels,         // Master-detail panels positioned by the host
+    virtual_source: Option<VirtualSource>, // Server-side row provider (virtual data source mode)
+    infinite_scroll: Option<usize>, // Rows from the bottom that trigger need_more_rows (None = off)
+    awaiting_more_rows: Option<usize>, // Row count when need_more_rows fired; suppressed until it changes
+    stream_load: Option<StreamLoad>, // Chunked CSV/JSON load in progress
+    reported_scroll: (f32, f64), // Scroll position of the last scroll event
+    print_layout: Option<PageLayout>, // Pages computed by get_page_count (used by render_page)
+    column_filters: ColumnFilters,
+    auto_refresh_filters: bool, // Re-evaluate filters when data in a filtered column changes
+    filters_stale: bool,        // Filtered columns changed since the filters were last applied
+    filters_revision: u64,      // Grid revision when the filters were last checked
+    hooks: OperationHooks,
+    header_flash: Option<(usize, f64)>, // Column header highlighted by goto_column, and start time (ms)
+    row_animation: RowAnimation, // Height transitions of inserted/deleted rows and detail panels
+    autocomplete: Autocomplete,  // Edit suggestions from existing column values
     // Performance monitoring
     fps_samples: Vec<f64>,      // Store last N frame times
     last_frame_time: f64,       // Timestamp of last frame
@@ -47,9 +137,46 @@ pub struct DataGrid {
     // Differential rendering
     dirty_cells: HashSet<(usize, usize)>, // Cells that need re-rendering
     needs_full_render: bool,    // Flag to force full re-render
+    last_render_state: Option<RenderState>, // State at the last render (for partial renders)
+    render_loop: Option<RenderLoop>, // Built-in requestAnimationFrame loop
+    listeners: Option<EventListeners>, // DOM listeners installed by attach_event_listeners
+    this: Weak<RefCell<DataGrid>>, // Shared state holding this grid (set by DataGridHandle)
+    options: serde_json::Value, // Construction options (reported by dump_diagnostics_json)
+    #[cfg(feature = "persistence")]
+    auto_save: Option<persistence::AutoSave>, // IndexedDB auto-save (enable_persistence)
+    #[cfg(feature = "time-travel")]
+    time_travel: Option<TimeTravel>, // Checkpoints and command stream (enable_time_travel)
 }
 
-#[wasm_bindgen]
+/// The `DataGrid` class exported to JS
+/// The grid lives in an `Rc<RefCell<..>>`, so DOM listeners and other callbacks reach it
+/// through a `Weak` reference and a checked borrow. Its public methods are exported by
+/// `#[js_export]`; a call that finds the grid already borrowed (a JS callback re-entering
+/// the grid from one of its own calls) throws instead of aliasing it.
+#[wasm_bindgen(js_name = DataGrid)]
+pub struct DataGridHandle {
+    state: Rc<RefCell<DataGrid>>,
+}
+
+impl From<DataGrid> for DataGridHandle {
+    fn from(grid: DataGrid) -> Self {
+        let state = Rc::new(RefCell::new(grid));
+        state.borrow_mut().this = Rc::downgrade(&state);
+        Self { state }
+    }
+}
+
+impl DataGridHandle {
+    fn borrow_state(&self) -> Ref<'_, DataGrid> {
+        self.state.try_borrow().unwrap_or_else(|_| wasm_bindgen::throw_str(&locale::text("error.grid_busy")))
+    }
+
+    fn borrow_state_mut(&self) -> RefMut<'_, DataGrid> {
+        self.state.try_borrow_mut().unwrap_or_else(|_| wasm_bindgen::throw_str(&locale::text("error.grid_busy")))
+    }
+}
+
+#[js_export(DataGridHandle)]
 impl DataGrid {
     /// Create a new DataGrid from a container div ID with JSON options
     /// Creates canvases automatically inside the div
@@ -59,18 +186,18 @@ impl DataGrid {
         console_error_panic_hook::set_once();
 
         let document = web_sys::window()
-            .ok_or("No window")?
+            .ok_or(GridError::from(locale::text("error.no_window")))?
             .document()
-            .ok_or("No document")?;
+            .ok_or(GridError::from(locale::text("error.no_document")))?;
 
         // Get container div
         let container = document
             .get_element_by_id(container_id)
-            .ok_or_else(|| JsValue::from_str(&format!("Container '{}' not found", container_id)))?;
+            .ok_or_else(|| GridError::OperationError { message: locale::format("error.element_not_found", &[("id", &container_id)]) })?;
 
         // Parse options
         let options: serde_json::Value = serde_json::from_str(options_json)
-            .map_err(|e| JsValue::from_str(&format!("Invalid JSON options: {}", e)))?;
+            .map_err(|e| GridError::InvalidJson { error: format!("options: {}", e) })?;
 
         let rows = options["rows"].as_u64().unwrap_or(100) as usize;
         let cols = options["cols"].as_u64().unwrap_or(26) as usize;
@@ -87,9 +214,9 @@ impl DataGrid {
         // Create WebGL canvas
         let webgl_canvas = document
             .create_element("canvas")
-            .map_err(|_| "Failed to create WebGL canvas")?
+            .map_err(|_| GridError::from(locale::text("error.create_canvas")))?
             .dyn_into::<HtmlCanvasElement>()
-            .map_err(|_| "Failed to cast to canvas")?;
+            .map_err(|_| GridError::from(locale::text("error.create_canvas")))?;
 
         webgl_canvas.set_width(width);
         webgl_canvas.set_height(height);
@@ -98,9 +225,9 @@ impl DataGrid {
         // Create text overlay canvas
         let text_canvas = document
             .create_element("canvas")
-            .map_err(|_| "Failed to create text canvas")?
+            .map_err(|_| GridError::from(locale::text("error.create_canvas")))?
             .dyn_into::<HtmlCanvasElement>()
-            .map_err(|_| "Failed to cast to canvas")?;
+            .map_err(|_| GridError::from(locale::text("error.create_canvas")))?;
 
         text_canvas.set_width(width);
         text_canvas.set_height(height);
@@ -114,6 +241,7 @@ impl DataGrid {
         let canvas_height = height as f32;
 
         let mut grid = Grid::new(rows, cols);
+        let mut footer = FooterAggregates::new();
 
         // Parse column configurations
         if let Some(columns) = options["columns"].as_array() {
@@ -134,12 +262,7 @@ impl DataGrid {
 
                 let col_width = col_config["width"].as_f64().unwrap_or(100.0) as f32;
 
-                let data_type = match col_config["data_type"].as_str() {
-                    Some("number") => DataType::Number,
-                    Some("date") => DataType::Date,
-                    Some("boolean") => DataType::Boolean,
-                    _ => DataType::Text,
-                };
+                let data_type = DataType::parse(col_config["data_type"].as_str().unwrap_or("text"));
 
                 let editable = col_config["editable"].as_bool().unwrap_or(true);
                 let visible = col_config["visible"].as_bool().unwrap_or(true);
@@ -153,11 +276,35 @@ impl DataGrid {
                 config.visible = visible;
                 config.sortable = sortable;
                 config.filterable = filterable;
+                config.padding = col_config["padding"].as_f64().map(|p| p as f32);
+                if let Some(mode) = col_config["width_mode"].as_str() {
+                    config.width_mode = WidthMode::parse(mode)?;
+                }
+                config.flex = match col_config["flex"].as_f64() {
+                    Some(weight) => WidthMode::flex_weight(weight)?,
+                    None => 1.0,
+                };
+                config.min_width = col_config["min_width"].as_f64().map(|w| w as f32);
+                config.max_width = col_config["max_width"].as_f64().map(|w| w as f32);
+                config.resizable = col_config["resizable"].as_bool().unwrap_or(true);
+                if let Some(collation) = col_config["collation"].as_str() {
+                    config.collation = Collation::parse(collation)?;
+                }
+                config.collation_locale = col_config["collation_locale"].as_str().map(str::to_string);
+                config.sort_order = col_config["sort_order"].as_array().map(|values| values.iter().map(|v| CellValue::from_json(v).to_string()).collect());
+                match col_config["aggregate"].as_str() {
+                    None | Some("") | Some("none") => {}
+                    Some(aggregate) => footer.set(col_idx, Some(Aggregate::parse(aggregate)?)),
+                }
 
                 grid.set_column_config(col_idx, config);
             }
         }
 
+        if !footer.is_empty() {
+            grid.footer_height = FOOTER_BAND_HEIGHT;
+        }
+
         // Apply grid-wide options
         grid.frozen_rows = options["frozen_rows"].as_u64().unwrap_or(0) as usize;
         grid.frozen_cols = options["frozen_cols"].as_u64().unwrap_or(0) as usize;
@@ -168,6 +315,14 @@ impl DataGrid {
         grid.enable_row_selection = options["enable_row_selection"].as_bool().unwrap_or(true);
         grid.enable_col_selection = options["enable_col_selection"].as_bool().unwrap_or(true);
         grid.alternate_row_colors = options["alternate_row_colors"].as_bool().unwrap_or(false);
+        grid.show_ghost_row = options["ghost_row"].as_bool().unwrap_or(false);
+        grid.show_ghost_col = options["ghost_column"].as_bool().unwrap_or(false);
+        if options["auto_expand"].as_bool().unwrap_or(false) {
+            grid.auto_expand = Some((
+                options["max_rows"].as_u64().unwrap_or(DEFAULT_AUTO_EXPAND_ROWS as u64) as usize,
+                options["max_cols"].as_u64().unwrap_or(DEFAULT_AUTO_EXPAND_COLS as u64) as usize,
+            ));
+        }
 
         if let Some(row_header_width) = options["row_header_width"].as_f64() {
             grid.row_header_width = row_header_width as f32;
@@ -175,40 +330,124 @@ impl DataGrid {
         if let Some(col_header_height) = options["col_header_height"].as_f64() {
             grid.col_header_height = col_header_height as f32;
         }
+        if let Some(start) = options["row_label_start"].as_u64() {
+            grid.row_label_start = start as usize;
+        }
+
+        let paste_policy = match options["paste_policy"].as_str() {
+            Some(policy) => PastePolicy::parse(policy)?,
+            None => PastePolicy::Overwrite,
+        };
+        let copy_format = match options["copy_format"].as_str() {
+            Some(format) => CopyFormat::parse(format)?,
+            None => CopyFormat::Raw,
+        };
+        let density = match options["density"].as_str() {
+            Some(preset) => Density::parse(preset)?,
+            None => Density::Standard,
+        };
+        grid.set_density(density);
+        if let Some(level) = options["log_level"].as_str() {
+            log::set_max_level(features::diagnostics::parse_log_level(level)?);
+        }
+        if let Some(padding) = options["cell_padding"].as_f64() {
+            grid.cell_padding = padding.max(0.0) as f32;
+        }
+
+        let auto_fit_on_load = match options["auto_fit_on_load"].as_str() {
+            Some(mode) => AutoFitMode::parse(mode)?,
+            None => AutoFitMode::None,
+        };
+
+        let summary_mode = match options["column_summary"].as_str() {
+            Some(mode) => SummaryMode::parse(mode)?,
+            None => SummaryMode::Off,
+        };
+        if summary_mode != SummaryMode::Off {
+            grid.set_column_summary_height(SUMMARY_BAND_HEIGHT);
+        }
 
         let mut viewport = Viewport::new(canvas_width, canvas_height);
         viewport.update_visible_range(&grid);
 
-        let webgl_renderer = WebGLRenderer::new(&webgl_canvas)
+        let renderer = GridRenderer::new(&webgl_canvas, options["renderer"].as_str().unwrap_or("auto"))
             .map_err(|e: GridError| JsValue::from(e))?;
 
-        let text_renderer = TextRenderer::new(&text_canvas)
+        let mut text_renderer = TextRenderer::new(&text_canvas)
             .map_err(|e: GridError| JsValue::from(e))?;
+        text_renderer.set_font_size(density.font_size());
+        text_renderer.auto_text_contrast = options["auto_text_contrast"].as_bool().unwrap_or(true);
+        let mut editing = EditingState::new();
+        editing.native = options["native_editing"].as_bool().unwrap_or(false);
 
         let mouse_handler = MouseHandler::new();
-        let keyboard_handler = KeyboardHandler::new();
+        let mut keyboard_handler = KeyboardHandler::new();
+        keyboard_handler.type_to_edit = options["type_to_edit"].as_bool().unwrap_or(true);
 
-        Ok(DataGrid {
+        let mut data_grid = DataGrid {
             grid,
             viewport,
-            webgl_renderer,
+            renderer,
             text_renderer,
             mouse_handler,
             keyboard_handler,
+            touch_handler: TouchHandler::new(),
+            hover: HoverTracker::new(options["content_preview_delay"].as_f64().unwrap_or(0.0)),
+            kinetic: KineticScroller::new(),
+            wheel_momentum: options["wheel_momentum"].as_bool().unwrap_or(false),
             webgl_canvas,
             text_canvas,
-            editing: EditingState::new(),
+            editing,
             selection: SelectionState::new(),
             resize: ResizeState::new(),
+            range_move: None,
+            cut_cells: None,
             search: SearchState::new(),
             undo_redo: UndoRedoState::new(),
+            events: EventQueue::new(),
+            paste_policy,
+            copy_format,
+            summary_mode,
+            summary_cache: SummaryCache::new(),
+            column_totals: ColumnTotals::new(),
+            footer,
+            auto_fit_on_load,
+            strict_batch: options["strict_batch"].as_bool().unwrap_or(false),
+            pivot: None,
+            details: DetailPanels::new(),
+            virtual_source: None,
+            infinite_scroll: None,
+            awaiting_more_rows: None,
+            stream_load: None,
+            reported_scroll: (0.0, 0.0),
+            print_layout: None,
+            column_filters: ColumnFilters::new(),
+            auto_refresh_filters: options["auto_refresh_filters"].as_bool().unwrap_or(true),
+            filters_stale: false,
+            filters_revision: 0,
+            hooks: OperationHooks::new(),
+            header_flash: None,
+            row_animation: RowAnimation::new(options["row_animation_ms"].as_f64().unwrap_or(DEFAULT_ROW_ANIMATION_MS).max(0.0)),
+            autocomplete: Autocomplete::new(options["autocomplete_on_tab"].as_bool().unwrap_or(false)),
             fps_samples: Vec::new(),
             last_frame_time: 0.0,
             frame_count: 0,
             render_time_ms: 0.0,
             dirty_cells: HashSet::new(),
             needs_full_render: true,
-        })
+            last_render_state: None,
+            render_loop: None,
+            listeners: None,
+            this: Weak::new(),
+            options: options.clone(),
+            #[cfg(feature = "persistence")]
+            auto_save: None,
+            #[cfg(feature = "time-travel")]
+            time_travel: None,
+        };
+        // Flex columns share the width left over by the others
+        data_grid.apply_flex_widths();
+        Ok(data_grid)
     }
 
     /// Create a new DataGrid instance with two canvas IDs (WebGL and text overlay)
@@ -219,23 +458,23 @@ impl DataGrid {
         console_error_panic_hook::set_once();
 
         let document = web_sys::window()
-            .ok_or("No window")?
+            .ok_or(GridError::from(locale::text("error.no_window")))?
             .document()
-            .ok_or("No document")?;
+            .ok_or(GridError::from(locale::text("error.no_document")))?;
 
         // Get WebGL canvas
         let webgl_canvas = document
             .get_element_by_id(webgl_canvas_id)
-            .ok_or("WebGL canvas not found")?
+            .ok_or_else(|| GridError::from(locale::format("error.element_not_found", &[("id", &webgl_canvas_id)])))?
             .dyn_into::<HtmlCanvasElement>()
-            .map_err(|_| "WebGL element is not a canvas")?;
+            .map_err(|_| GridError::from(locale::format("error.not_a_canvas", &[("id", &webgl_canvas_id)])))?;
 
         // Get text overlay canvas
         let text_canvas = document
             .get_element_by_id(text_canvas_id)
-            .ok_or("Text canvas not found")?
+            .ok_or_else(|| GridError::from(locale::format("error.element_not_found", &[("id", &text_canvas_id)])))?
             .dyn_into::<HtmlCanvasElement>()
-            .map_err(|_| "Text element is not a canvas")?;
+            .map_err(|_| GridError::from(locale::format("error.not_a_canvas", &[("id", &text_canvas_id)])))?;
 
         let canvas_width = webgl_canvas.width() as f32;
         let canvas_height = webgl_canvas.height() as f32;
@@ -245,7 +484,7 @@ impl DataGrid {
         let mut viewport = Viewport::new(canvas_width, canvas_height);
         viewport.update_visible_range(&grid);
 
-        let webgl_renderer = WebGLRenderer::new(&webgl_canvas)
+        let renderer = GridRenderer::new(&webgl_canvas, "auto")
             .map_err(|e: GridError| JsValue::from(e))?;
 
         let text_renderer = TextRenderer::new(&text_canvas)
@@ -257,39 +496,379 @@ impl DataGrid {
         Ok(DataGrid {
             grid,
             viewport,
-            webgl_renderer,
+            renderer,
             text_renderer,
             mouse_handler,
             keyboard_handler,
+            touch_handler: TouchHandler::new(),
+            hover: HoverTracker::new(0.0),
+            kinetic: KineticScroller::new(),
+            wheel_momentum: false,
             webgl_canvas,
             text_canvas,
             editing: EditingState::new(),
             selection: SelectionState::new(),
             resize: ResizeState::new(),
+            range_move: None,
+            cut_cells: None,
             search: SearchState::new(),
             undo_redo: UndoRedoState::new(),
+            events: EventQueue::new(),
+            paste_policy: PastePolicy::Overwrite,
+            copy_format: CopyFormat::Raw,
+            summary_mode: SummaryMode::Off,
+            summary_cache: SummaryCache::new(),
+            column_totals: ColumnTotals::new(),
+            footer: FooterAggregates::new(),
+            auto_fit_on_load: AutoFitMode::None,
+            strict_batch: false,
+            pivot: None,
+            details: DetailPanels::new(),
+            virtual_source: None,
+            infinite_scroll: None,
+            awaiting_more_rows: None,
+            stream_load: None,
+            reported_scroll: (0.0, 0.0),
+            print_layout: None,
+            column_filters: ColumnFilters::new(),
+            auto_refresh_filters: true,
+            filters_stale: false,
+            filters_revision: 0,
+            hooks: OperationHooks::new(),
+            header_flash: None,
+            row_animation: RowAnimation::new(DEFAULT_ROW_ANIMATION_MS),
+            autocomplete: Autocomplete::new(false),
             fps_samples: Vec::new(),
             last_frame_time: 0.0,
             frame_count: 0,
             render_time_ms: 0.0,
             dirty_cells: HashSet::new(),
             needs_full_render: true, // Start with full render
+            last_render_state: None,
+            render_loop: None,
+            listeners: None,
+            this: Weak::new(),
+            options: serde_json::json!({}),
+            #[cfg(feature = "persistence")]
+            auto_save: None,
+            #[cfg(feature = "time-travel")]
+            time_travel: None,
         })
     }
 
     /// Render the grid
-    pub fn render(&self) {
-        // Render WebGL layer (grid lines and backgrounds)
-        self.webgl_renderer.render(&self.grid, &self.viewport, &self.selection);
+    /// Only dirty cells are redrawn when nothing else changed since the last render
+    /// (no scroll, resize, selection, search or structural change); otherwise the
+    /// whole grid is redrawn. Call mark_all_dirty() after changes made outside the API.
+    pub fn render(&mut self) {
+        self.sync_filters();
+        self.sync_column_totals();
+        self.sync_virtual_source();
+        self.check_infinite_scroll();
+        self.check_scroll_event();
+        if self.row_animation.is_active() {
+            self.row_animation.step(&mut self.grid, hooks::now_ms());
+            self.viewport.set_scroll(self.viewport.scroll_x, self.viewport.scroll_y, &self.grid);
+            self.viewport.update_visible_range(&self.grid);
+        }
+        let state = RenderState::capture(&self.grid, &self.viewport, &self.selection, &self.search);
+
+        let partial = !self.needs_full_render
+            && !self.dirty_cells.is_empty()
+            && (self.summary_mode == SummaryMode::Off || !self.summary_cache.is_stale(&self.grid))
+            && self.dirty_cells.len() <= MAX_PARTIAL_RENDER_CELLS
+            && self.grid.preview_mode.is_none()
+            && self.editing.buffer.is_none() // Nor the native editor
+            && self.last_render_state.as_ref() == Some(&state);
+
+        if partial {
+            let cells: Vec<(usize, usize)> = self.dirty_cells.iter().copied().collect();
+
+            self.renderer.render_cells(&self.grid, &self.viewport, &self.selection, &cells);
+            self.text_renderer.render_cells(
+                &self.grid,
+                &self.viewport,
+                &self.selection,
+                &self.search.search_results,
+                self.search.current_search_index,
+                &cells,
+            );
+        } else {
+            self.draw_full();
+        }
+        let placeholders = self.render_virtual_placeholders();
+        self.render_footer();
+        self.details.sync(&self.grid, &self.viewport);
 
-        // Render text layer on top with search highlight info
-        self.text_renderer.render_with_search(
-            &self.grid,
-            &self.viewport,
-            &self.selection,
-            &self.search.search_results,
-            self.search.current_search_index
-        );
+        self.dirty_cells.clear();
+        // Keep the placeholder shimmer animating, and redraw once a watermark image arrives
+        self.needs_full_render = placeholders
+            || self.text_renderer.watermark.as_ref().is_some_and(Watermark::is_loading)
+            || self.row_animation.is_active();
+        self.last_render_state = Some(state);
+
+        // Keep redrawing while a goto_column header flash fades out
+        if let Some((col, start)) = self.header_flash {
+            let elapsed = hooks::now_ms() - start;
+            if elapsed < HEADER_FLASH_MS {
+                let alpha = 1.0 - (elapsed / HEADER_FLASH_MS) as f32;
+                self.text_renderer.render_header_flash(&self.grid, &self.viewport, col, alpha);
+                self.needs_full_render = true;
+            } else {
+                self.header_flash = None;
+                self.needs_full_render = true;
+            }
+        }
+
+        #[cfg(feature = "persistence")]
+        self.schedule_auto_save();
+        #[cfg(feature = "time-travel")]
+        self.sync_time_travel();
+    }
+
+    /// Render only if something changed since the last render
+    /// (dirty cells, scroll, resize, selection, search or structural change)
+    /// Returns true if a render was performed
+    pub fn render_if_needed(&mut self) -> bool {
+        let changed = self.needs_full_render
+            || !self.dirty_cells.is_empty()
+            || self.last_render_state.as_ref()
+                != Some(&RenderState::capture(&self.grid, &self.viewport, &self.selection, &self.search));
+
+        if changed {
+            self.render();
+        }
+        changed
+    }
+
+    /// Start the built-in requestAnimationFrame render loop
+    /// Each frame calls render_if_needed(), so changes are coalesced into at most
+    /// one render per display frame and idle frames do no work.
+    /// Hosts no longer need to call render() themselves while the loop runs.
+    pub fn start_render_loop(&mut self) -> Result<(), JsValue> {
+        if self.render_loop.is_some() {
+            return Ok(());
+        }
+
+        // A frame that finds the grid busy is skipped; the next one picks the changes up
+        let this = self.this.clone();
+        let render_loop = RenderLoop::start(move |timestamp| {
+            DataGrid::with_grid(&this, |grid| {
+                grid.update_touch(timestamp);
+                grid.update_hover(timestamp);
+                grid.update_scroll(timestamp);
+                if grid.render_if_needed() {
+                    grid.update_fps(timestamp);
+                }
+            });
+        })?;
+
+        self.render_loop = Some(render_loop);
+        Ok(())
+    }
+
+    /// Stop the built-in render loop
+    pub fn stop_render_loop(&mut self) {
+        if let Some(mut render_loop) = self.render_loop.take() {
+            render_loop.stop();
+        }
+    }
+
+    /// Check if the built-in render loop is running
+    pub fn is_render_loop_running(&self) -> bool {
+        self.render_loop.is_some()
+    }
+
+    // ========== DOM Event Listeners API ==========
+
+    /// Wire mouse, wheel, keyboard, touch and resize handling to the grid's container
+    /// (the parent element of the canvases), so the host does not have to forward DOM
+    /// events to the handle_* methods. Results those methods would return are queued for
+    /// poll_events() instead: "context_menu" (right-click or long-press, when
+    /// enable_context_menu is on) and "edit_started" (double-click or double-tap).
+    /// The container is made focusable for keys, and the canvases follow its size.
+    /// Calling it again re-attaches; detach() removes every listener.
+    pub fn attach_event_listeners(&mut self) -> Result<(), JsValue> {
+        self.detach();
+        let container = self
+            .webgl_canvas
+            .parent_element()
+            .ok_or_else(|| GridError::from(locale::text("error.no_container")))?;
+        let window = web_sys::window().ok_or(GridError::from(locale::text("error.no_window")))?;
+        if !container.has_attribute("tabindex") {
+            container.set_attribute("tabindex", "0")?;
+        }
+
+        // Handlers reach the grid through the shared state; an event dispatched while the
+        // grid is busy (e.g. by a hook calling focus()) is ignored rather than aliasing it
+        let this = self.this.clone();
+        let mut listeners = EventListeners::new();
+        let target: &web_sys::EventTarget = container.as_ref();
+        let focus_target = container.clone().dyn_into::<web_sys::HtmlElement>().ok();
+        let from_canvas = |event: &web_sys::Event| event.target().is_some_and(|t| t.has_type::<HtmlCanvasElement>());
+
+        let grid_ref = this.clone();
+        listeners.add(target, "mousedown", move |event| {
+            let Ok(event) = event.dyn_into::<MouseEvent>() else { return };
+            if event.button() != 0 || !from_canvas(&event) {
+                return;
+            }
+            event.prevent_default(); // No text selection while dragging
+            if let Some(element) = &focus_target {
+                let _ = element.focus();
+            }
+            DataGrid::with_grid(&grid_ref, |grid| {
+                let (x, y) = grid.client_to_canvas(event.client_x(), event.client_y());
+                let ctrl = event.ctrl_key() || event.meta_key();
+                // Header and row edges resize; grabbing the selection's outline drags the range
+                let handle = grid.check_resize_handle(x, y);
+                if handle != "none" {
+                    grid.start_resize(x, y, &handle);
+                    return;
+                }
+                if !event.shift_key() && grid.start_range_move(x, y, ctrl) {
+                    return;
+                }
+                grid.handle_mouse_down_at_with_modifiers(x, y, event.shift_key(), ctrl);
+            });
+        })?;
+        let grid_ref = this.clone();
+        let cursor_target = container.clone().dyn_into::<web_sys::HtmlElement>().ok();
+        listeners.add(target, "mousemove", move |event| {
+            let Ok(event) = event.dyn_into::<MouseEvent>() else { return };
+            DataGrid::with_grid(&grid_ref, |grid| {
+                let (x, y) = grid.client_to_canvas(event.client_x(), event.client_y());
+                let ctrl = event.ctrl_key() || event.meta_key();
+                if grid.is_resizing() {
+                    grid.update_resize(x, y);
+                    grid.needs_full_render = true;
+                } else if grid.is_moving_range() {
+                    grid.update_range_move(x, y, ctrl);
+                } else {
+                    grid.mouse_move_at(x, y, event.time_stamp());
+                }
+                if let Some(element) = &cursor_target {
+                    let cursor = if grid.is_resizing() {
+                        if grid.resize.resizing_column.is_some() { "col-resize" } else { "row-resize" }
+                    } else {
+                        match grid.check_resize_handle(x, y).as_str() {
+                            "col" => "col-resize",
+                            "row" => "row-resize",
+                            _ if grid.is_moving_range() || grid.check_range_move_handle(x, y) => if ctrl { "copy" } else { "move" },
+                            _ => "",
+                        }
+                    };
+                    let _ = element.style().set_property("cursor", cursor);
+                }
+            });
+        })?;
+        // On the window, so a drag released outside the grid still ends
+        let grid_ref = this.clone();
+        listeners.add(window.as_ref(), "mouseup", move |_| {
+            DataGrid::with_grid(&grid_ref, |grid| {
+                if grid.is_resizing() {
+                    grid.end_resize();
+                    grid.needs_full_render = true;
+                }
+                grid.end_range_move();
+                grid.mouse_handler.mouse_up();
+            });
+        })?;
+        let grid_ref = this.clone();
+        listeners.add(target, "mouseleave", move |_| {
+            DataGrid::with_grid(&grid_ref, |grid| grid.handle_mouse_leave());
+        })?;
+        let grid_ref = this.clone();
+        listeners.add(target, "dblclick", move |event| {
+            let Ok(event) = event.dyn_into::<MouseEvent>() else { return };
+            if !from_canvas(&event) {
+                return;
+            }
+            DataGrid::with_grid(&grid_ref, |grid| {
+                let (x, y) = grid.client_to_canvas(event.client_x(), event.client_y());
+                if grid.handle_double_click_at(x, y).is_some() {
+                    grid.queue_edit_started();
+                }
+            });
+        })?;
+        let grid_ref = this.clone();
+        listeners.add(target, "wheel", move |event| {
+            let Ok(event) = event.dyn_into::<WheelEvent>() else { return };
+            event.prevent_default();
+            DataGrid::with_grid(&grid_ref, |grid| grid.handle_wheel(event));
+        })?;
+        let key_target = container.clone();
+        let grid_ref = this.clone();
+        listeners.add(target, "keydown", move |event| {
+            let Ok(event) = event.dyn_into::<KeyboardEvent>() else { return };
+            // Keys typed into inputs inside the container (e.g. a cell editor) are not ours
+            if event.target().and_then(|t| t.dyn_into::<web_sys::Element>().ok()).as_ref() != Some(&key_target) {
+                return;
+            }
+            let ctrl = event.ctrl_key() || event.meta_key();
+            let handled = DataGrid::with_grid(&grid_ref, |grid| grid.handle_keyboard_with_modifiers_key(&event.key(), ctrl, event.shift_key()));
+            if handled == Some(true) {
+                event.prevent_default();
+            }
+        })?;
+        let grid_ref = this.clone();
+        listeners.add(target, "contextmenu", move |event| {
+            let Ok(event) = event.dyn_into::<MouseEvent>() else { return };
+            DataGrid::with_grid(&grid_ref, |grid| {
+                if !grid.grid.enable_context_menu {
+                    return;
+                }
+                let (x, y) = grid.client_to_canvas(event.client_x(), event.client_y());
+                let (x, y) = grid.viewport.to_grid_pixels(x, y);
+                let info = grid.context_menu_info_at(x, y);
+                if let Ok(detail) = serde_json::from_str::<serde_json::Value>(&info) {
+                    event.prevent_default();
+                    grid.events.push("context_menu", detail);
+                }
+            });
+        })?;
+        // Touch listeners cancel the default action, so the page does not scroll and no
+        // emulated mouse events follow
+        for event_type in ["touchstart", "touchmove", "touchend", "touchcancel"] {
+            let grid_ref = this.clone();
+            listeners.add(target, event_type, move |event| {
+                let Ok(event) = event.dyn_into::<TouchEvent>() else { return };
+                event.prevent_default();
+                DataGrid::with_grid(&grid_ref, |grid| match event_type {
+                    "touchstart" => grid.handle_touch_start(event),
+                    "touchmove" => grid.handle_touch_move(event),
+                    _ => {
+                        let action = grid.handle_touch_end(event);
+                        grid.queue_touch_action(&action);
+                    }
+                });
+            })?;
+        }
+        listeners.observe_resize(&container, move |width, height| {
+            DataGrid::with_grid(&this, |grid| {
+                let (width, height) = (width.floor() as f32, height.floor() as f32);
+                let current = (grid.webgl_canvas.width() as f32, grid.webgl_canvas.height() as f32);
+                // A hidden container reports 0 x 0; keep the last size until it is shown again
+                if width > 0.0 && height > 0.0 && (width, height) != current {
+                    grid.resize(width, height);
+                }
+            });
+        })?;
+
+        self.listeners = Some(listeners);
+        Ok(())
+    }
+
+    /// Remove the listeners installed by attach_event_listeners (also done when the grid is freed)
+    pub fn detach(&mut self) {
+        if let Some(mut listeners) = self.listeners.take() {
+            listeners.remove_all();
+        }
+    }
+
+    /// Check if attach_event_listeners is active
+    pub fn is_attached(&self) -> bool {
+        self.listeners.is_some()
     }
 
     /// Resize the grid
@@ -299,44 +878,156 @@ impl DataGrid {
         self.text_canvas.set_width(width as u32);
         self.text_canvas.set_height(height as u32);
 
-        self.webgl_renderer.resize(width, height);
+        self.renderer.resize(width, height);
         self.viewport.resize(width, height);
+        self.apply_flex_widths();
         self.viewport.update_visible_range(&self.grid);
 
         // Mark for full re-render after resize
         self.needs_full_render = true;
     }
 
+    /// Get the active background renderer: "webgl", or "canvas2d" when WebGL is
+    /// unavailable or the `renderer: "canvas2d"` option was given
+    pub fn get_renderer(&self) -> String {
+        self.renderer.name().to_string()
+    }
+
     /// Handle mouse wheel event for scrolling
+    /// Ctrl+wheel (also sent by trackpad pinch) zooms around the pointer
     pub fn handle_wheel(&mut self, event: WheelEvent) {
         let delta_x = event.delta_x() as f32;
         let delta_y = event.delta_y() as f32;
 
-        self.viewport.scroll_by(delta_x, delta_y, &self.grid);
+        if event.ctrl_key() {
+            let factor = (-delta_y * 0.01).exp();
+            self.zoom_by(factor, event.offset_x() as f32, event.offset_y() as f32);
+            return;
+        }
+
+        let zoom = self.viewport.zoom;
+        self.viewport.scroll_by(delta_x / zoom, delta_y / zoom, &self.grid);
+        self.viewport.update_visible_range(&self.grid);
+
+        if self.wheel_momentum {
+            self.kinetic.wheel(delta_x / zoom, delta_y / zoom, event.time_stamp());
+        } else {
+            self.kinetic.stop();
+        }
+    }
+
+    /// Continue scrolling with deceleration after a fast wheel fling (default: false)
+    /// Off by default because trackpads already send their own inertial wheel events
+    pub fn set_wheel_momentum(&mut self, enabled: bool) {
+        self.wheel_momentum = enabled;
+        if !enabled {
+            self.kinetic.stop();
+        }
+    }
+
+    /// Check if wheel momentum is enabled
+    pub fn is_wheel_momentum_enabled(&self) -> bool {
+        self.wheel_momentum
+    }
+
+    /// Smoothly scroll so that (row, col) becomes visible, easing out over duration_ms
+    /// A duration of 0 jumps immediately. The animation is stepped by update_scroll()
+    /// (called automatically by the built-in render loop).
+    pub fn smooth_scroll_to(&mut self, row: usize, col: usize, duration_ms: f64) {
+        if row >= self.grid.row_count() || col >= self.grid.col_count() {
+            return;
+        }
+
+        let (target_x, target_y) = self.scroll_position_for_cell(row, col);
+        if duration_ms <= 0.0 {
+            self.kinetic.stop();
+            self.viewport.set_scroll(target_x, target_y, &self.grid);
+            self.viewport.update_visible_range(&self.grid);
+            return;
+        }
+
+        // Clamp the target so the animation does not ease towards an unreachable position
+        let mut target = self.viewport.clone();
+        target.set_scroll(target_x, target_y, &self.grid);
+        self.kinetic.animate_to(
+            (self.viewport.scroll_x, self.viewport.scroll_y),
+            (target.scroll_x, target.scroll_y),
+            duration_ms,
+        );
+    }
+
+    /// Advance kinetic scrolling (touch/wheel momentum) and smooth_scroll_to animations
+    /// Called automatically by the built-in render loop; call it every frame otherwise
+    /// Returns true if the scroll position changed
+    pub fn update_scroll(&mut self, timestamp: f64) -> bool {
+        let (scroll_x, scroll_y) = (self.viewport.scroll_x, self.viewport.scroll_y);
+        let Some((x, y)) = self.kinetic.step(timestamp, scroll_x, scroll_y) else {
+            return false;
+        };
+
+        self.viewport.set_scroll(x, y, &self.grid);
+        self.viewport.update_visible_range(&self.grid);
+
+        let moved = self.viewport.scroll_x != scroll_x || self.viewport.scroll_y != scroll_y;
+        if !moved && self.kinetic.is_momentum_active() {
+            // Reached the edge of the content
+            self.kinetic.stop();
+        }
+        moved
+    }
+
+    /// Check if momentum scrolling or a scroll animation is in progress
+    pub fn is_scroll_animating(&self) -> bool {
+        self.kinetic.is_active()
+    }
+
+    /// Set the zoom factor (1.0 = 100%, clamped to 0.25..4.0), keeping the top-left cell in place
+    /// Cell geometry, fonts and hit-testing all scale together
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.set_zoom_at(zoom, 0.0, 0.0);
+    }
+
+    /// Set the zoom factor, keeping the content under canvas point (x, y) in place
+    pub fn set_zoom_at(&mut self, zoom: f32, x: f32, y: f32) {
+        self.viewport.set_zoom(zoom, x, y, &self.grid);
         self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
+    }
+
+    /// Multiply the zoom factor by `factor` around canvas point (x, y) (e.g. for pinch gestures)
+    pub fn zoom_by(&mut self, factor: f32, x: f32, y: f32) {
+        self.set_zoom_at(self.viewport.zoom * factor, x, y);
+    }
+
+    /// Get the current zoom factor
+    pub fn get_zoom(&self) -> f32 {
+        self.viewport.zoom
     }
 
     /// Handle mouse down event with modifier keys
     pub fn handle_mouse_down_with_modifiers(&mut self, event: MouseEvent, shift: bool, ctrl: bool) {
-        let x = event.offset_x() as f32;
-        let y = event.offset_y() as f32;
+        let (x, y) = self.viewport.to_grid_pixels(event.offset_x() as f32, event.offset_y() as f32);
 
+        // A click inside the native editor moves its caret; anywhere else commits it
+        if self.native_editor_click(x, y) {
+            return;
+        }
         // If currently editing, commit the edit before processing the click
         if self.is_editing() {
             self.end_edit();
         }
 
-        // Check if clicked on column header (for sorting)
+        // Check if clicked on column header (sort or column selection, per the sort click policy)
         if let Some(col) = self.viewport.canvas_to_column_header(x, y, &self.grid) {
             log::debug!("Clicked column header: {}", col);
-            self.toggle_column_sort(col);
+            self.click_column_header(col, (x, y), shift, ctrl);
             return;
         }
 
         // Check if clicked on row header (already handled for row selection)
         if let Some(row) = self.viewport.canvas_to_row_header(x, y, &self.grid) {
             // Row header click - select entire row
-            self.select_row(row);
+            self.selection.select_row(row, self.grid.row_count(), self.grid.col_count());
             return;
         }
 
@@ -375,24 +1066,29 @@ impl DataGrid {
 
     /// Handle mouse down at coordinates with modifier keys (for JavaScript)
     pub fn handle_mouse_down_at_with_modifiers(&mut self, x: f32, y: f32, shift: bool, ctrl: bool) {
+        let (x, y) = self.viewport.to_grid_pixels(x, y);
         log::debug!("handle_mouse_down_at_with_modifiers: x={}, y={}", x, y);
 
+        // A click inside the native editor moves its caret; anywhere else commits it
+        if self.native_editor_click(x, y) {
+            return;
+        }
         // If currently editing, commit the edit before processing the click
         if self.is_editing() {
             self.end_edit();
         }
 
-        // Check if clicked on column header (for sorting)
+        // Check if clicked on column header (sort or column selection, per the sort click policy)
         if let Some(col) = self.viewport.canvas_to_column_header(x, y, &self.grid) {
             log::debug!("Clicked column header: {}", col);
-            self.toggle_column_sort(col);
+            self.click_column_header(col, (x, y), shift, ctrl);
             return;
         }
 
         // Check if clicked on row header (for row selection)
         if let Some(row) = self.viewport.canvas_to_row_header(x, y, &self.grid) {
             log::debug!("Clicked row header: {}", row);
-            self.select_row(row);
+            self.selection.select_row(row, self.grid.row_count(), self.grid.col_count());
             return;
         }
 
@@ -430,17 +1126,22 @@ impl DataGrid {
 
     /// Handle mouse down at specific coordinates
     pub fn handle_mouse_down_at(&mut self, x: f32, y: f32) {
+        let (x, y) = self.viewport.to_grid_pixels(x, y);
         log::debug!("handle_mouse_down_at called: x={}, y={}", x, y);
 
+        // A click inside the native editor moves its caret; anywhere else commits it
+        if self.native_editor_click(x, y) {
+            return;
+        }
         // If currently editing, commit the edit before processing the click
         if self.is_editing() {
             self.end_edit();
         }
 
-        // Check if clicked on column header (for sorting)
+        // Check if clicked on column header (sort or column selection, per the sort click policy)
         if let Some(col) = self.viewport.canvas_to_column_header(x, y, &self.grid) {
             log::debug!("Clicked column header: {}", col);
-            self.toggle_column_sort(col);
+            self.click_column_header(col, (x, y), false, false);
             return;
         }
 
@@ -448,7 +1149,7 @@ impl DataGrid {
         if let Some(row) = self.viewport.canvas_to_row_header(x, y, &self.grid) {
             log::debug!("Clicked row header: {}", row);
             // Row header click - select entire row
-            self.select_row(row);
+            self.selection.select_row(row, self.grid.row_count(), self.grid.col_count());
             return;
         }
 
@@ -480,8 +1181,12 @@ impl DataGrid {
 
     /// Handle mouse move event
     pub fn handle_mouse_move(&mut self, event: MouseEvent) {
-        let x = event.offset_x() as f32;
-        let y = event.offset_y() as f32;
+        self.mouse_move_at(event.offset_x() as f32, event.offset_y() as f32, event.time_stamp());
+    }
+
+    /// Helper: Extend a drag selection and track hover for a pointer at canvas point (x, y)
+    fn mouse_move_at(&mut self, x: f32, y: f32, time: f64) {
+        let (x, y) = self.viewport.to_grid_pixels(x, y);
 
         if self.mouse_handler.is_selecting {
             // Drag selection: extend range to current cell
@@ -491,37 +1196,194 @@ impl DataGrid {
                 self.mouse_handler.last_y = y;
             }
         }
+
+        let cell = self.viewport.canvas_to_cell(x, y, &self.grid);
+        self.track_hover(cell, time);
+    }
+
+    /// Handle mouseleave event (ends any content preview)
+    pub fn handle_mouse_leave(&mut self) {
+        self.track_hover(None, hooks::now_ms());
+    }
+
+    /// Check for the pointer dwelling on a truncated cell (called by the render loop;
+    /// call it periodically when driving rendering yourself)
+    /// @param timestamp - Current time in ms (performance.now() / requestAnimationFrame time)
+    /// Returns true if a "content_preview_requested" event was queued
+    pub fn update_hover(&mut self, timestamp: f64) -> bool {
+        let Some((row, col)) = self.hover.check_dwell(timestamp) else {
+            return false;
+        };
+        if self.is_editing() || self.mouse_handler.is_selecting || !self.is_cell_truncated(row, col) {
+            return false;
+        }
+
+        self.hover.mark_previewed();
+        let zoom = self.viewport.zoom;
+        let rect = self.viewport.cell_canvas_rect(row, col, &self.grid).map(|(x, y, w, h)| [x * zoom, y * zoom, w * zoom, h * zoom]);
+        self.events.push(
+            "content_preview_requested",
+            serde_json::json!({
+                "row": row,
+                "row_id": self.grid.row_id(row),
+                "col": col,
+                "text": self.grid.get_value_string(row, col),
+                "display_text": self.grid.get_display_string(row, col),
+                "rect": rect,
+            }),
+        );
+        true
+    }
+
+    /// Set how long (ms) the pointer must rest on a truncated cell before a
+    /// "content_preview_requested" event is queued (0 disables previews)
+    pub fn set_content_preview_delay(&mut self, delay_ms: f64) {
+        self.hover.delay_ms = delay_ms.max(0.0);
+    }
+
+    /// Get the content preview delay in ms (0 = disabled)
+    pub fn get_content_preview_delay(&self) -> f64 {
+        self.hover.delay_ms
+    }
+
+    /// Check if a cell's displayed text does not fit its column width (or spans several lines)
+    pub fn is_cell_truncated(&self, row: usize, col: usize) -> bool {
+        let text = self.grid.get_display_string(row, col);
+        if text.is_empty() {
+            return false;
+        }
+        let available = self.grid.col_width(col) - self.grid.cell_padding(col) * 2.0;
+        text.contains('\n') || self.text_renderer.measure_text(&text) > available
+    }
+
+    /// Helper: Follow the pointer for the hover highlight, cell_hovered events and dwell
+    /// detection; queues "content_preview_ended" when it leaves a cell whose preview was
+    /// requested
+    fn track_hover(&mut self, cell: Option<(usize, usize)>, time: f64) {
+        if self.mouse_handler.hover(cell) {
+            if self.text_renderer.hover_style.is_enabled() {
+                self.needs_full_render = true;
+            }
+            if self.mouse_handler.hover_events {
+                let zoom = self.viewport.zoom;
+                let rect = cell
+                    .and_then(|(row, col)| self.viewport.cell_canvas_rect(row, col, &self.grid))
+                    .map(|(x, y, w, h)| [x * zoom, y * zoom, w * zoom, h * zoom]);
+                self.events.push(
+                    "cell_hovered",
+                    serde_json::json!({
+                        "row": cell.map(|(row, _)| row),
+                        "row_id": cell.and_then(|(row, _)| self.grid.row_id(row)),
+                        "col": cell.map(|(_, col)| col),
+                        "rect": rect,
+                    }),
+                );
+            }
+        }
+        if let Some((row, col)) = self.hover.move_to(cell, time) {
+            self.events.push(
+                "content_preview_ended",
+                serde_json::json!({ "row": row, "row_id": self.grid.row_id(row), "col": col }),
+            );
+        }
+    }
+
+    /// Handle touchstart event (single-finger pan/tap/long-press, two-finger scroll)
+    /// Two-finger pinch zooms around the center of the fingers
+    pub fn handle_touch_start(&mut self, event: TouchEvent) {
+        if let Some((x, y, spread, count)) = self.touch_point(&event.touches()) {
+            self.touch_start(x, y, spread, count, event.time_stamp());
+        }
+    }
+
+    /// Handle touchmove event
+    pub fn handle_touch_move(&mut self, event: TouchEvent) {
+        if let Some((x, y, spread, count)) = self.touch_point(&event.touches()) {
+            self.touch_move(x, y, spread, count, event.time_stamp());
+        }
+    }
+
+    /// Handle touchend/touchcancel event
+    /// Returns the same JSON as handle_touch_end_at
+    pub fn handle_touch_end(&mut self, event: TouchEvent) -> String {
+        if event.type_() == "touchcancel" {
+            self.touch_handler.touch_cancel();
+            return String::new();
+        }
+        self.handle_touch_end_at(event.touches().length() as usize, event.time_stamp())
+    }
+
+    /// Handle touch start at canvas coordinates (for JavaScript)
+    /// @param x, y - Touch point (center of all touches for multi-touch)
+    /// @param touch_count - Number of fingers down
+    /// @param timestamp - Event time in ms (event.timeStamp)
+    pub fn handle_touch_start_at(&mut self, x: f32, y: f32, touch_count: usize, timestamp: f64) {
+        self.touch_start(x, y, 0.0, touch_count, timestamp);
+    }
+
+    /// Handle touch move at canvas coordinates (for JavaScript)
+    /// Pinch zoom needs finger distances: use handle_touch_move or zoom_by for that
+    pub fn handle_touch_move_at(&mut self, x: f32, y: f32, touch_count: usize, timestamp: f64) {
+        self.touch_move(x, y, 0.0, touch_count, timestamp);
+    }
+
+    /// Handle touch end (for JavaScript)
+    /// @param remaining_touches - Number of fingers still down
+    /// @param timestamp - Event time in ms (event.timeStamp)
+    /// Returns: JSON describing an action for the host, or empty string:
+    /// - {"action":"context_menu","type":"row"|"column"|"cell","row":N,"col":N} after a long-press
+    /// - {"action":"edit","row":N,"col":N} after a double-tap on an editable cell
+    pub fn handle_touch_end_at(&mut self, remaining_touches: usize, timestamp: f64) -> String {
+        let gesture = self.touch_handler.touch_end(remaining_touches, timestamp);
+        self.apply_touch_gesture(gesture)
+    }
+
+    /// Advance time-based touch behavior (long-press detection; momentum is stepped by update_scroll)
+    /// Called automatically by the built-in render loop; call it every frame otherwise
+    /// Returns true if the selection changed
+    pub fn update_touch(&mut self, timestamp: f64) -> bool {
+        let long_press = self.touch_handler.check_long_press(timestamp);
+        if long_press != TouchGesture::None {
+            self.apply_touch_gesture(long_press);
+            return true;
+        }
+
+        false
+    }
+
+    /// Check if a touch gesture or momentum panning is in progress
+    pub fn is_touch_active(&self) -> bool {
+        self.touch_handler.is_active() || self.kinetic.is_momentum_active()
     }
 
     /// Handle context menu (right-click) event
     /// Returns JSON with context info: {"type": "row"|"column"|"cell", "row": N, "col": N}
+    /// Column headers add the column's state for building a header menu: "internal_name",
+    /// "display_name", "sort" ({"ascending", "priority"} or null), "filter" (the column's
+    /// filter as in get_column_filters, or null), "sortable", "filterable", "hideable"
+    /// (visible and not the last visible column) and "groupable" (usable as a pivot row
+    /// group, i.e. a column of the flat grid)
     /// Returns empty string if not on grid
     pub fn handle_context_menu(&self, event: MouseEvent) -> String {
-        let x = event.offset_x() as f32;
-        let y = event.offset_y() as f32;
+        let (x, y) = self.viewport.to_grid_pixels(event.offset_x() as f32, event.offset_y() as f32);
+        self.context_menu_info_at(x, y)
+    }
 
+    /// Get context menu info at canvas coordinates (shared by right-click and long-press)
+    fn context_menu_info_at(&self, x: f32, y: f32) -> String {
         // Check if on row header
         if let Some(row) = self.viewport.canvas_to_row_header(x, y, &self.grid) {
-            return format!(
-                r#"{{"type":"row","row":{},"col":null}}"#,
-                row
-            );
+            return serde_json::json!({ "type": "row", "row": row, "row_id": self.grid.row_id(row), "col": null }).to_string();
         }
 
         // Check if on column header
         if let Some(col) = self.viewport.canvas_to_column_header(x, y, &self.grid) {
-            return format!(
-                r#"{{"type":"column","row":null,"col":{}}}"#,
-                col
-            );
+            return self.column_context_info(col).to_string();
         }
 
         // Check if on cell
         if let Some((row, col)) = self.viewport.canvas_to_cell(x, y, &self.grid) {
-            return format!(
-                r#"{{"type":"cell","row":{},"col":{}}}"#,
-                row, col
-            );
+            return serde_json::json!({ "type": "cell", "row": row, "row_id": self.grid.row_id(row), "col": col }).to_string();
         }
 
         // Not on grid
@@ -554,23 +1416,128 @@ impl DataGrid {
         operations
     }
 
+    /// Helper: Context menu info of a column header (see handle_context_menu)
+    fn column_context_info(&self, col: usize) -> serde_json::Value {
+        let config = self.grid.get_column_config(col);
+        let sort = self
+            .grid
+            .active_sort_keys()
+            .into_iter()
+            .enumerate()
+            .find(|&(_, (c, _))| c == col)
+            .map(|(priority, (_, ascending))| serde_json::json!({ "ascending": ascending, "priority": priority }));
+        serde_json::json!({
+            "type": "column",
+            "row": null,
+            "col": col,
+            "internal_name": config.map(|c| c.internal_name.clone()),
+            "display_name": config.map_or_else(|| Grid::get_col_name(col), |c| c.display_name.clone()),
+            "sort": sort,
+            "filter": self.column_filters.get(col).map(ColumnFilter::to_json),
+            "sortable": config.is_none_or(|c| c.sortable),
+            "filterable": config.is_none_or(|c| c.filterable),
+            "hideable": self.is_column_hideable(col),
+            "groupable": self.pivot.is_none(),
+        })
+    }
+
+    /// Helper: Check if hiding a column leaves another column visible
+    fn is_column_hideable(&self, col: usize) -> bool {
+        !self.grid.is_col_hidden(col) && (0..self.grid.col_count()).any(|c| c != col && !self.grid.is_col_hidden(c))
+    }
+
+    /// Get column operations for a header context menu
+    /// Returns the operations available for the given column (labels: get_operation_label)
+    pub fn get_column_context_operations(&self, col: usize) -> Vec<String> {
+        let Some(config) = self.grid.get_column_config(col) else {
+            return Vec::new();
+        };
+        let mut operations = Vec::new();
+
+        if config.sortable {
+            operations.push("sort_ascending".to_string());
+            operations.push("sort_descending".to_string());
+        }
+        if self.grid.active_sort_keys().iter().any(|&(c, _)| c == col) {
+            operations.push("clear_sort".to_string());
+        }
+        if self.column_filters.get(col).is_some() {
+            operations.push("clear_filter".to_string());
+        }
+
+        if self.is_column_hideable(col) {
+            operations.push("hide_column".to_string());
+        }
+        if !self.grid.hidden_columns().is_empty() {
+            operations.push("show_all_columns".to_string());
+        }
+        operations.push("auto_fit_column".to_string());
+
+        operations.push("insert_column_left".to_string());
+        operations.push("insert_column_right".to_string());
+        if self.grid.col_count() > 1 {
+            operations.push("delete_column".to_string());
+        }
+
+        if col > 0 {
+            operations.push("move_column_left".to_string());
+        }
+        if col < self.grid.col_count() - 1 {
+            operations.push("move_column_right".to_string());
+        }
+
+        operations
+    }
+
+    /// Get the display label of a context menu operation in the current locale
+    /// (e.g. "insert_row_above" -> "Insert row above")
+    pub fn get_operation_label(&self, operation: &str) -> String {
+        locale::text(&format!("operation.{}", operation))
+    }
+
+    /// Override built-in user-visible strings (canvas text, operation labels, error messages)
+    /// Takes a JSON object of key -> text; null restores a key's English default.
+    /// The table is shared by all grids on the page. See get_locale_strings_json() for the keys.
+    pub fn set_locale_strings_json(&mut self, strings_json: &str) -> Result<(), JsValue> {
+        let strings: serde_json::Value = serde_json::from_str(strings_json)
+            .map_err(|e| GridError::InvalidJson { error: format!("locale strings: {}", e) })?;
+        locale::set_strings(&strings)?;
+        self.refresh_pivot();
+        self.needs_full_render = true;
+        Ok(())
+    }
+
+    /// Get every built-in string key with its current text as a JSON object
+    pub fn get_locale_strings_json(&self) -> String {
+        locale::to_json().to_string()
+    }
+
+    /// Restore all built-in strings to English
+    pub fn reset_locale_strings(&mut self) {
+        locale::reset();
+        self.refresh_pivot();
+        self.needs_full_render = true;
+    }
+
     /// Execute row context menu operation
     pub fn execute_row_operation(&mut self, operation: &str, row: usize) -> Result<String, JsValue> {
+        self.check_flat()?;
+        self.check_row(row)?;
         match operation {
             "insert_row_above" => {
                 self.insert_row(row);
-                Ok(format!("Inserted row at {}", row))
+                Ok(locale::format("result.insert_row", &[("row", &row)]))
             }
             "insert_row_below" => {
                 self.insert_row(row + 1);
-                Ok(format!("Inserted row at {}", row + 1))
+                Ok(locale::format("result.insert_row", &[("row", &(row + 1))]))
             }
             "delete_row" => {
                 if self.grid.row_count() <= 1 {
-                    return Err(JsValue::from_str("Cannot delete the last row"));
+                    return Err(GridError::OperationError { message: locale::text("error.delete_last_row") }.into());
                 }
                 self.delete_row(row);
-                Ok(format!("Deleted row {}", row))
+                Ok(locale::format("result.delete_row", &[("row", &row)]))
             }
             "copy_row" => {
                 let mut cells = Vec::new();
@@ -590,19 +1557,79 @@ impl DataGrid {
             }
             "move_row_up" => {
                 if row == 0 {
-                    return Err(JsValue::from_str("Cannot move first row up"));
+                    return Err(GridError::OperationError { message: locale::text("error.move_first_row_up") }.into());
                 }
                 self.swap_rows(row, row - 1);
-                Ok(format!("Moved row {} up", row))
+                Ok(locale::format("result.move_row_up", &[("row", &row)]))
             }
             "move_row_down" => {
                 if row >= self.grid.row_count() - 1 {
-                    return Err(JsValue::from_str("Cannot move last row down"));
+                    return Err(GridError::OperationError { message: locale::text("error.move_last_row_down") }.into());
                 }
                 self.swap_rows(row, row + 1);
-                Ok(format!("Moved row {} down", row))
+                Ok(locale::format("result.move_row_down", &[("row", &row)]))
+            }
+            _ => Err(GridError::OperationError { message: locale::format("error.unknown_operation", &[("operation", &operation)]) }.into()),
+        }
+    }
+
+    /// Execute column context menu operation
+    pub fn execute_column_operation(&mut self, operation: &str, col: usize) -> Result<String, JsValue> {
+        self.check_flat()?;
+        self.check_col(col)?;
+        match operation {
+            "sort_ascending" | "sort_descending" => {
+                let ascending = operation == "sort_ascending";
+                self.sort_column(col, ascending);
+                Ok(locale::format(if ascending { "result.sort_ascending" } else { "result.sort_descending" }, &[("col", &col)]))
+            }
+            "clear_sort" => {
+                self.clear_multi_column_sort();
+                Ok(locale::text("result.clear_sort"))
+            }
+            "clear_filter" => {
+                self.remove_column_filter(col);
+                Ok(locale::format("result.clear_filter", &[("col", &col)]))
+            }
+            "hide_column" => {
+                self.set_column_visible_with_undo(col, false)?;
+                Ok(locale::format("result.hide_column", &[("col", &col)]))
+            }
+            "show_all_columns" => Ok(locale::format("result.show_all_columns", &[("count", &self.show_all_columns())])),
+            "auto_fit_column" => {
+                self.auto_fit_column(col)?;
+                Ok(locale::format("result.auto_fit_column", &[("col", &col)]))
+            }
+            "insert_column_left" => {
+                self.insert_column(col);
+                Ok(locale::format("result.insert_column", &[("col", &col)]))
+            }
+            "insert_column_right" => {
+                self.insert_column(col + 1);
+                Ok(locale::format("result.insert_column", &[("col", &(col + 1))]))
+            }
+            "delete_column" => {
+                if self.grid.col_count() <= 1 {
+                    return Err(GridError::OperationError { message: locale::text("error.delete_last_column") }.into());
+                }
+                self.delete_column(col);
+                Ok(locale::format("result.delete_column", &[("col", &col)]))
+            }
+            "move_column_left" => {
+                if col == 0 {
+                    return Err(GridError::OperationError { message: locale::text("error.move_first_column_left") }.into());
+                }
+                self.move_column(col, col - 1);
+                Ok(locale::format("result.move_column_left", &[("col", &col)]))
+            }
+            "move_column_right" => {
+                if col >= self.grid.col_count() - 1 {
+                    return Err(GridError::OperationError { message: locale::text("error.move_last_column_right") }.into());
+                }
+                self.move_column(col, col + 1);
+                Ok(locale::format("result.move_column_right", &[("col", &col)]))
             }
-            _ => Err(JsValue::from_str(&format!("Unknown operation: {}", operation)))
+            _ => Err(GridError::OperationError { message: locale::format("error.unknown_operation", &[("operation", &operation)]) }.into()),
         }
     }
 
@@ -623,25 +1650,105 @@ impl DataGrid {
     }
 
     /// Set cell value
-    pub fn set_cell_value(&mut self, row: usize, col: usize, value: &str) {
-        // Record old value for undo
-        let old_value = self.grid.get_value(row, col).clone();
-
+    /// Text that parses as a number (including "1e5" or "inf") is stored as a number;
+    /// use the typed setters (set_cell_text, set_cell_number, ...) to avoid the guessing
+    pub fn set_cell_value(&mut self, row: usize, col: usize, value: &str) -> Result<(), JsValue> {
+        self.check_flat()?;
+        self.check_cell(row, col)?;
         // Try to parse as number
         let new_value = if let Ok(num) = value.parse::<f64>() {
             CellValue::Number(num)
         } else {
             CellValue::Text(value.to_string())
         };
+        self.write_cell_value(row, col, new_value);
+        Ok(())
+    }
+
+    /// Set a cell to a number (NaN and infinities are rejected)
+    pub fn set_cell_number(&mut self, row: usize, col: usize, value: f64) -> Result<(), JsValue> {
+        if !value.is_finite() {
+            return Err(GridError::OperationError { message: locale::format("error.cell_number", &[("value", &value)]) }.into());
+        }
+        self.write_typed_value(row, col, CellValue::Number(value))
+    }
+
+    /// Set a cell to a boolean
+    pub fn set_cell_bool(&mut self, row: usize, col: usize, value: bool) -> Result<(), JsValue> {
+        self.write_typed_value(row, col, CellValue::Boolean(value))
+    }
+
+    /// Set a cell to a date: "YYYY-MM-DD", optionally with " HH:MM[:SS]" or "THH:MM[:SS]"
+    pub fn set_cell_date_iso(&mut self, row: usize, col: usize, value: &str) -> Result<(), JsValue> {
+        if !CellValue::is_iso_date(value) {
+            return Err(GridError::OperationError { message: locale::format("error.iso_date", &[("value", &value)]) }.into());
+        }
+        self.write_typed_value(row, col, CellValue::Date(value.to_string()))
+    }
+
+    /// Set a cell to text as is (no number parsing); an empty string clears the cell
+    pub fn set_cell_text(&mut self, row: usize, col: usize, value: &str) -> Result<(), JsValue> {
+        let value = if value.is_empty() { CellValue::Empty } else { CellValue::Text(value.to_string()) };
+        self.write_typed_value(row, col, value)
+    }
+
+    /// Helper: Bounds-checked write for the typed setters
+    fn write_typed_value(&mut self, row: usize, col: usize, value: CellValue) -> Result<(), JsValue> {
+        self.check_flat()?;
+        self.check_cell(row, col)?;
+        self.write_cell_value(row, col, value);
+        Ok(())
+    }
+
+    /// Helper: Error unless (row, col) is a cell of the grid
+    fn check_cell(&self, row: usize, col: usize) -> Result<(), GridError> {
+        if row >= self.grid.row_count() || col >= self.grid.col_count() {
+            return Err(GridError::OutOfBounds { row, col });
+        }
+        Ok(())
+    }
+
+    /// Helper: Error while the pivot view is shown: its grid is rebuilt from the parked
+    /// flat grid, so edits and structural changes to it would be lost
+    fn check_flat(&self) -> Result<(), GridError> {
+        if self.pivot.is_some() {
+            return Err(GridError::OperationError { message: locale::text("error.pivot_read_only") });
+        }
+        Ok(())
+    }
+
+    /// Helper: Grid holding the data: the parked flat grid while the pivot view is shown
+    fn data_grid(&self) -> &Grid {
+        self.pivot.as_ref().map_or(&self.grid, |view| &view.flat.grid)
+    }
+
+    /// Helper: Error unless the row exists
+    fn check_row(&self, row: usize) -> Result<(), GridError> {
+        if row >= self.grid.row_count() {
+            return Err(GridError::RowOutOfBounds { row });
+        }
+        Ok(())
+    }
+
+    /// Helper: Error unless the column exists
+    fn check_col(&self, col: usize) -> Result<(), GridError> {
+        if col >= self.grid.col_count() {
+            return Err(GridError::ColumnOutOfBounds { col });
+        }
+        Ok(())
+    }
+
+    /// Helper: Write a value, marking the cell modified and recording undo if it changed
+    fn write_cell_value(&mut self, row: usize, col: usize, new_value: CellValue) {
+        // Record old value for undo
+        let old_value = self.grid.get_value(row, col);
 
         // Only record if value actually changed
         if old_value != new_value {
             self.grid.set_value(row, col, new_value.clone());
 
             // Mark cell as modified
-            if let Some(cell) = self.grid.get_cell_mut(row, col) {
-                cell.modified = true;
-            }
+            self.grid.mark_modified(row, col, old_value.clone());
 
             // Mark cell as dirty for differential rendering
             self.dirty_cells.insert((row, col));
@@ -654,15 +1761,14 @@ impl DataGrid {
                 new_value,
             };
 
-            self.undo_redo.undo_stack.push(action);
-
-            // Clear redo stack on new edit
-            self.undo_redo.redo_stack.clear();
+            self.record_edit(action);
         }
     }
 
     /// Set cell value without recording to undo stack (useful for bulk data loading)
-    pub fn set_cell_value_no_undo(&mut self, row: usize, col: usize, value: &str) {
+    pub fn set_cell_value_no_undo(&mut self, row: usize, col: usize, value: &str) -> Result<(), JsValue> {
+        self.check_flat()?;
+        self.check_cell(row, col)?;
         // Try to parse as number
         let new_value = if let Ok(num) = value.parse::<f64>() {
             CellValue::Number(num)
@@ -670,16 +1776,17 @@ impl DataGrid {
             CellValue::Text(value.to_string())
         };
 
+        let old_value = self.grid.get_value(row, col);
         self.grid.set_value(row, col, new_value);
 
         // Mark cell as modified
-        if let Some(cell) = self.grid.get_cell_mut(row, col) {
-            cell.modified = true;
-        }
+        self.grid.mark_modified(row, col, old_value);
 
         // Mark cell as dirty for differential rendering
         self.dirty_cells.insert((row, col));
+        self.revalidate_cells([(row, col)]);
         // Note: We do NOT record to undo stack
+        Ok(())
     }
 
     /// Get cell value
@@ -687,6 +1794,52 @@ impl DataGrid {
         self.grid.get_value_string(row, col)
     }
 
+    /// Read a rectangular range of values in one call (corners inclusive, in any order)
+    /// Returns an array per row of typed values: numbers, booleans, strings (text and
+    /// dates) and null for empty cells. Use instead of get_cell_value loops for export/sync.
+    pub fn get_range_values(&self, r1: usize, c1: usize, r2: usize, c2: usize) -> Result<js_sys::Array, JsValue> {
+        let (rows, cols) = self.range_bounds(r1, c1, r2, c2)?;
+        Ok(rows
+            .map(|row| cols.clone().map(|col| self.grid.get_value(row, col).to_js_value()).collect::<js_sys::Array>())
+            .collect())
+    }
+
+    /// Read a rectangular range column by column (corners inclusive, in any order)
+    /// Returns an array per column: a Float64Array for number columns (empty and
+    /// non-numeric cells become NaN), otherwise an array of typed values as in get_range_values
+    pub fn get_range_columns(&self, r1: usize, c1: usize, r2: usize, c2: usize) -> Result<js_sys::Array, JsValue> {
+        let (rows, cols) = self.range_bounds(r1, c1, r2, c2)?;
+        Ok(cols
+            .map(|col| -> JsValue {
+                let numeric = self.grid.get_column_config(col).is_some_and(|config| config.data_type == DataType::Number);
+                if numeric {
+                    let values: Vec<f64> = rows
+                        .clone()
+                        .map(|row| series::value_as_f64(&self.grid.get_value(row, col)).unwrap_or(f64::NAN))
+                        .collect();
+                    js_sys::Float64Array::from(values.as_slice()).into()
+                } else {
+                    rows.clone().map(|row| self.grid.get_value(row, col).to_js_value()).collect::<js_sys::Array>().into()
+                }
+            })
+            .collect())
+    }
+
+    /// Helper: Row and column ranges of a rectangle given by two corners (checked)
+    fn range_bounds(
+        &self,
+        r1: usize,
+        c1: usize,
+        r2: usize,
+        c2: usize,
+    ) -> Result<(std::ops::RangeInclusive<usize>, std::ops::RangeInclusive<usize>), GridError> {
+        let (row, col) = (r1.max(r2), c1.max(c2));
+        if row >= self.grid.row_count() || col >= self.grid.col_count() {
+            return Err(GridError::OutOfBounds { row, col });
+        }
+        Ok((r1.min(r2)..=row, c1.min(c2)..=col))
+    }
+
     /// Get grid dimensions
     pub fn get_dimensions(&self) -> Vec<usize> {
         vec![self.grid.row_count(), self.grid.col_count()]
@@ -742,10 +1895,10 @@ impl DataGrid {
         let header_offset_y = if self.grid.show_headers { self.grid.col_header_height } else { 0.0 };
 
         let viewport_width = self.viewport.canvas_width - header_offset_x;
-        let viewport_height = self.viewport.canvas_height - header_offset_y;
+        let viewport_height = self.viewport.canvas_height - header_offset_y - self.grid.footer_height;
 
         let max_scroll_x = (self.grid.total_width() - viewport_width).max(0.0);
-        let max_scroll_y = (self.grid.total_height() - viewport_height).max(0.0);
+        let max_scroll_y = (self.grid.total_height() - viewport_height as f64).max(0.0);
 
         format!("[{},{}]", max_scroll_x, max_scroll_y)
     }
@@ -757,121 +1910,239 @@ impl DataGrid {
         let header_offset_y = if self.grid.show_headers { self.grid.col_header_height } else { 0.0 };
 
         let total_width = self.grid.total_width() + header_offset_x;
-        let total_height = self.grid.total_height() + header_offset_y;
+        let total_height = self.grid.total_height() + header_offset_y as f64;
 
         format!("[{},{}]", total_width, total_height)
     }
 
     /// Set scroll position
-    pub fn set_scroll(&mut self, x: f32, y: f32) {
+    pub fn set_scroll(&mut self, x: f32, y: f64) {
+        self.kinetic.stop();
         self.viewport.set_scroll(x, y, &self.grid);
         self.viewport.update_visible_range(&self.grid);
     }
 
+    /// Scroll by whole rows (negative = up), so the row n below the current top row of
+    /// the scrollable area becomes its top row
+    pub fn scroll_rows(&mut self, n: i32) {
+        let frozen_top = self.frozen_rows_height();
+        let top = self.grid.row_at_y(self.viewport.scroll_y + frozen_top as f64).unwrap_or(self.viewport.first_visible_row);
+        let target = (top as i64 + n as i64).max(0) as usize;
+        self.scroll_to_row(target.min(self.grid.row_count().saturating_sub(1)));
+    }
+
+    /// Scroll by whole pages (negative = up); a page is the height of the scrollable area
+    pub fn scroll_pages(&mut self, n: i32) {
+        let header_offset_y = if self.grid.show_headers { self.grid.col_header_height } else { 0.0 };
+        let page = (self.viewport.canvas_height - header_offset_y - self.grid.footer_height - self.frozen_rows_height()).max(0.0);
+        self.set_scroll(self.viewport.scroll_x, self.viewport.scroll_y + (n as f32 * page) as f64);
+    }
+
+    /// Scroll so that a row is the top row of the scrollable area (as far as the grid
+    /// allows); frozen rows are always visible and do not scroll
+    pub fn scroll_to_row(&mut self, row: usize) {
+        if row >= self.grid.row_count() || row < self.grid.frozen_rows {
+            return;
+        }
+        let y = self.grid.row_y_position(row) - self.frozen_rows_height() as f64;
+        self.set_scroll(self.viewport.scroll_x, y);
+    }
+
+    /// Scroll so that a column is the leftmost column of the scrollable area
+    pub fn scroll_to_col(&mut self, col: usize) {
+        if col >= self.grid.col_count() || col < self.grid.frozen_cols {
+            return;
+        }
+        let frozen_left = self.grid.col_x_position(self.grid.frozen_cols);
+        let x = self.grid.col_x_position(col) - frozen_left;
+        self.set_scroll(x, self.viewport.scroll_y);
+    }
+
+    /// Helper: Height of the frozen rows above the scrollable area
+    fn frozen_rows_height(&self) -> f32 {
+        self.grid.row_y_position(self.grid.frozen_rows.min(self.grid.row_count())) as f32
+    }
+
+    /// Helper: Queue a scroll event when the scroll position changed since the last render
+    /// (whatever moved it: wheel, touch, keyboard or the scroll APIs)
+    fn check_scroll_event(&mut self) {
+        let (x, y) = (self.viewport.scroll_x, self.viewport.scroll_y);
+        let (last_x, last_y) = self.reported_scroll;
+        if (x, y) == (last_x, last_y) {
+            return;
+        }
+        self.reported_scroll = (x, y);
+
+        let (delta_x, delta_y) = (x - last_x, y - last_y);
+        let (max_x, max_y) = self.viewport.max_scroll(&self.grid);
+        let (at_top, at_bottom, at_left, at_right) = (y <= 0.0, y >= max_y, x <= 0.0, x >= max_x);
+        let (direction, edge_reached) = if delta_y.abs() >= delta_x.abs() as f64 {
+            if delta_y > 0.0 { ("down", at_bottom.then_some("bottom")) } else { ("up", at_top.then_some("top")) }
+        } else if delta_x > 0.0 {
+            ("right", at_right.then_some("right"))
+        } else {
+            ("left", at_left.then_some("left"))
+        };
+        self.events.push(
+            "scroll",
+            serde_json::json!({
+                "x": x,
+                "y": y,
+                "delta_x": delta_x,
+                "delta_y": delta_y,
+                "direction": direction,
+                "edge_reached": edge_reached,
+                "at_top": at_top,
+                "at_bottom": at_bottom,
+                "at_left": at_left,
+                "at_right": at_right,
+                "first_visible_row": self.viewport.first_visible_row,
+                "last_visible_row": self.viewport.last_visible_row,
+            }),
+        );
+    }
+
     /// Set multiple cell values at once (for lazy loading/batch updates)
     /// Takes JSON array of [row, col, value_type, value_data]
     /// value_type: 0=empty, 1=text, 2=number, 3=boolean
     /// Example: "[[0, 0, 1, \"text\"], [1, 1, 2, \"123\"]]"
-    pub fn set_cells_batch(&mut self, cells_data_json: &str) -> Result<(), JsValue> {
+    /// Returns a load report: {"applied","skipped","rejected","errors":[{"index","row","col","reason"}]}
+    /// In strict mode an invalid entry rejects the whole batch (Err with the report)
+    /// With expected_version (from get_data_version), the batch is rejected with a
+    /// version_conflict error if the data changed since then
+    pub fn set_cells_batch(&mut self, cells_data_json: &str, expected_version: Option<f64>) -> Result<String, JsValue> {
+        self.check_flat()?;
+        self.check_data_version(expected_version)?;
         let cells_data: Vec<Vec<String>> = serde_json::from_str(cells_data_json)
-            .map_err(|e| JsValue::from_str(&format!("Invalid cells_data JSON: {}", e)))?;
-
-        for cell_data in cells_data {
-            if cell_data.len() < 4 {
-                continue;
-            }
-
-            let row = cell_data[0].parse::<usize>().unwrap_or(0);
-            let col = cell_data[1].parse::<usize>().unwrap_or(0);
-            let value_type = cell_data[2].parse::<u8>().unwrap_or(0);
-            let value_data = &cell_data[3];
-
-            if row >= self.grid.row_count() || col >= self.grid.col_count() {
-                continue;
+            .map_err(|e| GridError::InvalidJson { error: format!("cells_data: {}", e) })?;
+
+        let mut report = BatchReport::new();
+        let mut cells = Vec::with_capacity(cells_data.len());
+        for (index, cell_data) in cells_data.iter().enumerate() {
+            match batch::parse_cells_batch_entry(&self.grid, index, cell_data) {
+                Ok(cell) => cells.push(cell),
+                Err(skipped) => report.skip(skipped),
             }
+        }
+        self.check_batch(&mut report)?;
 
-            let cell_value = match value_type {
-                1 => CellValue::Text(value_data.clone()),
-                2 => CellValue::Number(value_data.parse::<f64>().unwrap_or(0.0)),
-                3 => CellValue::Boolean(value_data == "true" || value_data == "1"),
-                _ => CellValue::Empty,
-            };
-
+        for (row, col, cell_value) in cells {
             self.grid.set_value(row, col, cell_value);
             self.dirty_cells.insert((row, col));
+            self.revalidate_cells([(row, col)]);
+            report.applied += 1;
         }
 
+        Ok(report.to_json().to_string())
+    }
+
+    /// Helper: Log skipped batch entries and, in strict mode, reject the batch
+    fn check_batch(&self, report: &mut BatchReport) -> Result<(), JsValue> {
+        if report.skipped == 0 {
+            return Ok(());
+        }
+        log::warn!("Batch load: {} invalid entries{}", report.skipped, if self.strict_batch { " (rejected)" } else { "" });
+        if self.strict_batch {
+            report.rejected = true;
+            return Err(JsValue::from_str(&report.to_json().to_string()));
+        }
         Ok(())
     }
 
+    /// Get the data version: a counter incremented by every change to cell values or to
+    /// the rows and columns (edits, pastes, loads, undo, inserts, deletes, sorts). Pass it
+    /// as expected_version to a batch API to reject the batch if the data moved on meanwhile.
+    pub fn get_data_version(&self) -> f64 {
+        self.grid.data_version() as f64
+    }
+
+    /// Helper: Fail with VersionConflict when the data changed since `expected`
+    fn check_data_version(&self, expected: Option<f64>) -> Result<(), GridError> {
+        let current = self.grid.data_version();
+        match expected {
+            Some(expected) if expected != current as f64 => {
+                Err(GridError::VersionConflict { expected: expected.max(0.0) as u64, current })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Set whether batch loads (set_cells_batch, load_data_json) are all-or-nothing
+    pub fn set_strict_batch(&mut self, strict: bool) {
+        self.strict_batch = strict;
+    }
+
+    /// Check if batch loads are all-or-nothing
+    pub fn get_strict_batch(&self) -> bool {
+        self.strict_batch
+    }
+
     /// Load grid data from JSON
     /// Accepts JSON array: [{"row": 0, "col": 0, "value": "text"}, ...]
     /// Value can be string, number, boolean, date, or null (for empty)
     /// If column has data_type configured, value will be converted accordingly
-    pub fn load_data_json(&mut self, data_json: &str) -> Result<(), JsValue> {
+    /// Returns a load report like set_cells_batch (strict mode rejects the whole batch;
+    /// a stale expected_version rejects it with a version_conflict error)
+    pub fn load_data_json(&mut self, data_json: &str, expected_version: Option<f64>) -> Result<String, JsValue> {
+        self.check_flat()?;
+        self.check_data_version(expected_version)?;
         log::debug!("load_data_json called with {} bytes", data_json.len());
 
         let data: Vec<serde_json::Value> = serde_json::from_str(data_json)
-            .map_err(|e| JsValue::from_str(&format!("Invalid JSON data: {}", e)))?;
+            .map_err(|e| GridError::InvalidJson { error: format!("data: {}", e) })?;
 
         log::debug!("Parsed {} cell data entries", data.len());
 
-        let mut loaded_count = 0;
-        for cell_data in data {
-            let row = cell_data["row"].as_u64().unwrap_or(0) as usize;
-            let col = cell_data["col"].as_u64().unwrap_or(0) as usize;
-
-            if row >= self.grid.row_count() || col >= self.grid.col_count() {
-                log::debug!("Skipping cell ({}, {}) - out of bounds", row, col);
-                continue;
-            }
-
-            // Get column data type if configured
-            let expected_type = self.grid.get_column_config(col)
-                .map(|c| c.data_type.clone());
-
-            let cell_value = match &cell_data["value"] {
-                serde_json::Value::Null => CellValue::Empty,
-                serde_json::Value::String(s) => {
-                    // Convert based on column data type
-                    match expected_type {
-                        Some(DataType::Number) => {
-                            if let Ok(n) = s.parse::<f64>() {
-                                CellValue::Number(n)
-                            } else {
-                                CellValue::Text(s.clone())
-                            }
-                        }
-                        Some(DataType::Date) => CellValue::Date(s.clone()),
-                        Some(DataType::Boolean) => {
-                            CellValue::Boolean(s == "true" || s == "1")
-                        }
-                        _ => CellValue::Text(s.clone()),
-                    }
-                }
-                serde_json::Value::Number(n) => {
-                    if let Some(f) = n.as_f64() {
-                        CellValue::Number(f)
-                    } else {
-                        CellValue::Empty
-                    }
+        let mut report = BatchReport::new();
+        let mut cells = Vec::with_capacity(data.len());
+        for (index, cell_data) in data.iter().enumerate() {
+            match batch::parse_json_entry_position(&self.grid, index, cell_data) {
+                Ok((row, col)) => cells.push((row, col, self.json_to_cell_value(&cell_data["value"], col))),
+                Err(skipped) => {
+                    log::debug!("Skipping entry {}: {}", index, skipped.reason);
+                    report.skip(skipped);
                 }
-                serde_json::Value::Bool(b) => CellValue::Boolean(*b),
-                _ => CellValue::Empty,
-            };
-
-            self.grid.set_value(row, col, cell_value.clone());
-            self.dirty_cells.insert((row, col));
-            loaded_count += 1;
+            }
+        }
+        self.check_batch(&mut report)?;
 
-            if loaded_count <= 5 {
+        for (row, col, cell_value) in cells {
+            if report.applied < 5 {
                 log::debug!("Loaded cell ({}, {}): {:?}", row, col, cell_value);
             }
+            self.grid.set_value(row, col, cell_value);
+            self.dirty_cells.insert((row, col));
+            report.applied += 1;
         }
 
-        log::debug!("load_data_json completed. Loaded {} cells, {} dirty cells", loaded_count, self.dirty_cells.len());
+        log::debug!("load_data_json completed. Loaded {} cells, {} dirty cells", report.applied, self.dirty_cells.len());
 
-        Ok(())
+        self.fit_columns_on_load();
+        Ok(report.to_json().to_string())
+    }
+
+    /// Convert a JSON value to a cell value using the column's data type
+    fn json_to_cell_value(&self, value: &serde_json::Value, col: usize) -> CellValue {
+        // Get column data type if configured
+        let expected_type = self.grid.get_column_config(col)
+            .map(|c| c.data_type.clone());
+
+        match value {
+            serde_json::Value::Null => CellValue::Empty,
+            serde_json::Value::String(s) => {
+                // Convert based on column data type
+                expected_type.unwrap_or(DataType::Text).convert_text(s)
+            }
+            serde_json::Value::Number(n) => {
+                if let Some(f) = n.as_f64() {
+                    CellValue::Number(f)
+                } else {
+                    CellValue::Empty
+                }
+            }
+            serde_json::Value::Bool(b) => CellValue::Boolean(*b),
+            _ => CellValue::Empty,
+        }
     }
 
     /// Load data for a specific range (for lazy loading)
@@ -895,10 +2166,20 @@ impl DataGrid {
 
     /// Handle keyboard event
     pub fn handle_keyboard(&mut self, event: KeyboardEvent) -> bool {
-        let key = event.key();
+        let key = Self::event_key(&event);
+        // AltGr reports Ctrl+Alt on Windows but types characters
+        let alt_graph = event.get_modifier_state("AltGraph");
+        if let Some(handled) = self.handle_native_editor_key(&key, !alt_graph && (event.ctrl_key() || event.meta_key()), event.shift_key()) {
+            return handled;
+        }
+        if let Some(handled) = self.handle_header_key(&key, false, false) {
+            return handled;
+        }
 
-        // Get navigation command from key
-        if let Some(command) = self.keyboard_handler.handle_key(&key) {
+        // Get navigation command from key (no type-to-edit for shortcuts)
+        let shortcut = !alt_graph && (event.ctrl_key() || event.meta_key() || event.alt_key());
+        let command = self.keyboard_handler.handle_key(&key).filter(|c| !shortcut || !c.is_typing());
+        if let Some(command) = command {
             // Get current selected cell
             let current = self.mouse_handler.selected_cell;
 
@@ -966,40 +2247,7 @@ impl DataGrid {
                     Some((self.grid.row_count() - 1, self.grid.col_count() - 1))
                 }
                 NavigationCommand::Delete => {
-                    // Clear cell content for all selected cells
-                    if !self.selection.selected_cells.is_empty() {
-                        // Collect old values for undo
-                        let mut cells_to_clear = Vec::new();
-                        for (row, col) in self.selection.selected_cells.iter() {
-                            let old_value = self.grid.get_value(*row, *col);
-                            cells_to_clear.push((*row, *col, old_value));
-                        }
-
-                        // Clear all cells
-                        for (row, col, _) in &cells_to_clear {
-                            self.grid.set_value(*row, *col, CellValue::Empty);
-                        }
-
-                        // Record undo action
-                        let action = EditAction::ClearCells { cells: cells_to_clear };
-                        self.undo_redo.undo_stack.push(action);
-                        self.undo_redo.redo_stack.clear();
-
-                        log::debug!("Cleared {} cell(s)", self.selection.selected_cells.len());
-                        return true; // Force render
-                    } else if let Some((row, col)) = current {
-                        // Single cell clear
-                        let old_value = self.grid.get_value(row, col);
-                        self.grid.set_value(row, col, CellValue::Empty);
-
-                        // Record undo action
-                        let action = EditAction::ClearCells {
-                            cells: vec![(row, col, old_value)]
-                        };
-                        self.undo_redo.undo_stack.push(action);
-                        self.undo_redo.redo_stack.clear();
-
-                        log::debug!("Cleared cell: ({}, {})", row, col);
+                    if self.clear_for_delete_key(current) {
                         return true; // Force render
                     }
                     None
@@ -1033,9 +2281,15 @@ impl DataGrid {
                     })
                 }
                 NavigationCommand::Escape => {
-                    // If editing, cancel edit mode without moving
+                    // If editing, cancel edit mode (restoring the value) without moving
                     if self.is_editing() {
-                        self.end_edit();
+                        self.cancel_edit();
+                        return true; // Force render
+                    }
+                    // A range drag in progress is dropped where it started
+                    if self.is_moving_range() {
+                        self.cancel_range_move();
+                        return true;
                     }
                     None
                 }
@@ -1054,9 +2308,17 @@ impl DataGrid {
                     })
                 }
                 NavigationCommand::EditCell => {
-                    // Return true to signal JavaScript to start editing
-                    // JavaScript wrapper will handle the actual editing UI
-                    return true;
+                    // F2: edit keeping the content; the in-grid editor opens itself, an
+                    // external editor is opened by the host on true / "edit_started"
+                    return self.begin_keyboard_edit(None);
+                }
+                NavigationCommand::TypeToEdit(ch) => {
+                    // A printable key replaces the content (the host's editor shows "text")
+                    return self.begin_keyboard_edit(Some(&ch.to_string()));
+                }
+                NavigationCommand::ComposeToEdit => {
+                    // An IME composition replaces the content with what it commits
+                    return self.begin_keyboard_edit(Some(""));
                 }
             };
 
@@ -1080,8 +2342,15 @@ impl DataGrid {
 
     /// Handle keyboard event with modifier keys
     pub fn handle_keyboard_with_modifiers(&mut self, event: KeyboardEvent, ctrl: bool) -> bool {
-        let key = event.key();
+        let key = Self::event_key(&event);
+        let ctrl = ctrl && !event.get_modifier_state("AltGraph");
         let shift = event.shift_key();
+        if let Some(handled) = self.handle_native_editor_key(&key, ctrl, shift) {
+            return handled;
+        }
+        if let Some(handled) = self.handle_header_key(&key, ctrl, shift) {
+            return handled;
+        }
 
         // Get navigation command with modifiers
         if let Some(command) = self.keyboard_handler.handle_key_with_modifiers(&key, ctrl, shift) {
@@ -1151,10 +2420,7 @@ impl DataGrid {
                     Some((self.grid.row_count() - 1, self.grid.col_count() - 1))
                 }
                 NavigationCommand::Delete => {
-                    if let Some((row, col)) = current {
-                        self.grid.set_value(row, col, CellValue::Empty);
-                        log::debug!("Cleared cell: ({}, {})", row, col);
-                    }
+                    self.clear_for_delete_key(current);
                     None
                 }
                 NavigationCommand::Undo => {
@@ -1172,9 +2438,17 @@ impl DataGrid {
                     None
                 }
                 NavigationCommand::EditCell => {
-                    // Return true to signal JavaScript to start editing
-                    // JavaScript wrapper will handle the actual editing UI
-                    return true;
+                    // F2: edit keeping the content; the in-grid editor opens itself, an
+                    // external editor is opened by the host on true / "edit_started"
+                    return self.begin_keyboard_edit(None);
+                }
+                NavigationCommand::TypeToEdit(ch) => {
+                    // A printable key replaces the content (the host's editor shows "text")
+                    return self.begin_keyboard_edit(Some(&ch.to_string()));
+                }
+                NavigationCommand::ComposeToEdit => {
+                    // An IME composition replaces the content with what it commits
+                    return self.begin_keyboard_edit(Some(""));
                 }
                 NavigationCommand::Enter | NavigationCommand::Escape | NavigationCommand::Tab => {
                     None
@@ -1210,6 +2484,13 @@ impl DataGrid {
 
     /// Handle keyboard with key string and modifier flags (called from JavaScript)
     pub fn handle_keyboard_with_modifiers_key(&mut self, key: &str, ctrl: bool, shift: bool) -> bool {
+        if let Some(handled) = self.handle_native_editor_key(key, ctrl, shift) {
+            return handled;
+        }
+        if let Some(handled) = self.handle_header_key(key, ctrl, shift) {
+            return handled;
+        }
+
         // Get navigation command with modifiers
         if let Some(command) = self.keyboard_handler.handle_key_with_modifiers(key, ctrl, shift) {
             let current = self.mouse_handler.selected_cell;
@@ -1278,9 +2559,7 @@ impl DataGrid {
                     Some((self.grid.row_count() - 1, self.grid.col_count() - 1))
                 }
                 NavigationCommand::Delete => {
-                    if let Some((row, col)) = current {
-                        self.grid.set_value(row, col, CellValue::Empty);
-                    }
+                    self.clear_for_delete_key(current);
                     None
                 }
                 NavigationCommand::Undo => {
@@ -1298,9 +2577,17 @@ impl DataGrid {
                     None
                 }
                 NavigationCommand::EditCell => {
-                    // Return true to signal JavaScript to start editing
-                    // JavaScript wrapper will handle the actual editing UI
-                    return true;
+                    // F2: edit keeping the content; the in-grid editor opens itself, an
+                    // external editor is opened by the host on true / "edit_started"
+                    return self.begin_keyboard_edit(None);
+                }
+                NavigationCommand::TypeToEdit(ch) => {
+                    // A printable key replaces the content (the host's editor shows "text")
+                    return self.begin_keyboard_edit(Some(&ch.to_string()));
+                }
+                NavigationCommand::ComposeToEdit => {
+                    // An IME composition replaces the content with what it commits
+                    return self.begin_keyboard_edit(Some(""));
                 }
                 NavigationCommand::Enter | NavigationCommand::Escape | NavigationCommand::Tab => {
                     None
@@ -1333,8 +2620,133 @@ impl DataGrid {
         false
     }
 
+    /// Helper: Keyboard handling for header focus
+    /// Ctrl+ArrowUp on row 0 focuses the column header, Ctrl+ArrowLeft on column 0 the row header.
+    /// In a column header: Left/Right move, Enter toggles sort, Space selects the column,
+    /// Shift+Left/Right resize, Down/Escape return to the cells (row headers likewise).
+    /// Returns Some(render needed) if the key was consumed, None to continue normal handling.
+    fn handle_header_key(&mut self, key: &str, ctrl: bool, shift: bool) -> Option<bool> {
+        const RESIZE_STEP: f32 = 10.0;
+        let (rows, cols) = (self.grid.row_count(), self.grid.col_count());
+
+        match self.selection.header_focus {
+            None => {
+                if !ctrl || !self.grid.show_headers {
+                    return None;
+                }
+                let (row, col) = self.mouse_handler.selected_cell?;
+                let focus = match key {
+                    "ArrowUp" if row == 0 => HeaderFocus::Column(col),
+                    "ArrowLeft" if col == 0 => HeaderFocus::Row(row),
+                    _ => return None,
+                };
+                self.focus_header(focus);
+                Some(true)
+            }
+            Some(HeaderFocus::Column(col)) => {
+                match key {
+                    "ArrowLeft" | "ArrowRight" if shift => {
+                        let delta = if key == "ArrowLeft" { -RESIZE_STEP } else { RESIZE_STEP };
+                        self.set_col_width_with_undo(col, self.grid.col_width(col) + delta);
+                        self.viewport.update_visible_range(&self.grid);
+                    }
+                    "ArrowLeft" if col > 0 => self.focus_header(HeaderFocus::Column(col - 1)),
+                    "ArrowRight" if col + 1 < cols => self.focus_header(HeaderFocus::Column(col + 1)),
+                    "ArrowDown" | "Escape" => {
+                        self.selection.header_focus = None;
+                        if rows > 0 {
+                            let row = self.viewport.first_visible_row.min(rows - 1);
+                            self.select_single_cell(row, col);
+                            self.mouse_handler.select_cell(row, col);
+                        }
+                    }
+                    "Enter" => self.toggle_sort(col),
+                    " " | "Spacebar" => self.selection.select_col(col, rows, cols),
+                    "ArrowUp" | "ArrowLeft" | "ArrowRight" => return Some(false),
+                    _ => return None,
+                }
+                Some(true)
+            }
+            Some(HeaderFocus::Row(row)) => {
+                match key {
+                    "ArrowUp" | "ArrowDown" if shift => {
+                        let delta = if key == "ArrowUp" { -RESIZE_STEP } else { RESIZE_STEP };
+                        self.set_row_height_with_undo(row, self.grid.row_height(row) + delta);
+                        self.viewport.update_visible_range(&self.grid);
+                    }
+                    "ArrowUp" if row > 0 => self.focus_header(HeaderFocus::Row(row - 1)),
+                    "ArrowDown" if row + 1 < rows => self.focus_header(HeaderFocus::Row(row + 1)),
+                    "ArrowRight" | "Escape" => {
+                        self.selection.header_focus = None;
+                        if cols > 0 {
+                            let col = self.viewport.first_visible_col.min(cols - 1);
+                            self.select_single_cell(row, col);
+                            self.mouse_handler.select_cell(row, col);
+                        }
+                    }
+                    " " | "Spacebar" => self.selection.select_row(row, rows, cols),
+                    "ArrowUp" | "ArrowDown" | "ArrowLeft" | "Enter" => return Some(false),
+                    _ => return None,
+                }
+                Some(true)
+            }
+        }
+    }
+
+    /// Helper: Move keyboard focus to a header and scroll it into view along its axis
+    fn focus_header(&mut self, focus: HeaderFocus) {
+        self.selection.header_focus = Some(focus);
+        let (scroll_x, scroll_y) = match focus {
+            HeaderFocus::Column(col) => (self.scroll_position_for_cell(self.viewport.first_visible_row, col).0, self.viewport.scroll_y),
+            HeaderFocus::Row(row) => (self.viewport.scroll_x, self.scroll_position_for_cell(row, self.viewport.first_visible_col).1),
+        };
+        if scroll_x != self.viewport.scroll_x || scroll_y != self.viewport.scroll_y {
+            self.viewport.set_scroll(scroll_x, scroll_y, &self.grid);
+            self.viewport.update_visible_range(&self.grid);
+        }
+    }
+
+    /// Get the header holding keyboard focus as JSON: {"type":"column"|"row","index":n}
+    /// Returns an empty string when focus is in the cells
+    pub fn get_header_focus(&self) -> String {
+        match self.selection.header_focus {
+            Some(HeaderFocus::Column(col)) => serde_json::json!({"type": "column", "index": col}).to_string(),
+            Some(HeaderFocus::Row(row)) => serde_json::json!({"type": "row", "index": row}).to_string(),
+            None => String::new(),
+        }
+    }
+
+    /// Move keyboard focus to a column ("column") or row ("row") header
+    pub fn set_header_focus(&mut self, kind: &str, index: usize) -> Result<(), JsValue> {
+        let focus = match kind {
+            "column" if index < self.grid.col_count() => HeaderFocus::Column(index),
+            "row" if index < self.grid.row_count() => HeaderFocus::Row(index),
+            "column" => return Err(GridError::ColumnOutOfBounds { col: index }.into()),
+            "row" => return Err(GridError::RowOutOfBounds { row: index }.into()),
+            _ => return Err(GridError::unknown_value("header_type", kind, &["column", "row"]).into()),
+        };
+        self.focus_header(focus);
+        Ok(())
+    }
+
+    /// Return keyboard focus from the headers to the cells
+    pub fn clear_header_focus(&mut self) {
+        self.selection.header_focus = None;
+    }
+
     /// Ensure a cell is visible in the viewport
     fn ensure_cell_visible(&mut self, row: usize, col: usize) {
+        let (scroll_x, scroll_y) = self.scroll_position_for_cell(row, col);
+
+        // Update scroll if changed
+        if scroll_x != self.viewport.scroll_x || scroll_y != self.viewport.scroll_y {
+            self.viewport.set_scroll(scroll_x, scroll_y, &self.grid);
+            self.viewport.update_visible_range(&self.grid);
+        }
+    }
+
+    /// Helper: Closest scroll position at which (row, col) is fully visible
+    fn scroll_position_for_cell(&self, row: usize, col: usize) -> (f32, f64) {
         let cell_x = self.grid.col_x_position(col);
         let cell_y = self.grid.row_y_position(row);
         let cell_width = self.grid.col_width(col);
@@ -1349,7 +2761,7 @@ impl DataGrid {
 
         // Calculate visible area (excluding headers)
         let visible_width = self.viewport.canvas_width - row_header_width;
-        let visible_height = self.viewport.canvas_height - col_header_height;
+        let visible_height = self.viewport.canvas_height - col_header_height - self.grid.footer_height;
 
         // Check horizontal visibility
         // Cell position relative to visible area (after row header)
@@ -1365,33 +2777,54 @@ impl DataGrid {
 
         // Check vertical visibility
         // Cell position relative to visible area (after column header)
-        let cell_screen_y = cell_y - scroll_y;
+        let cell_screen_y = (cell_y - scroll_y) as f32;
 
         if cell_screen_y < 0.0 {
             // Cell is scrolled off to the top - align to top edge of visible area
             scroll_y = cell_y;
         } else if cell_screen_y + cell_height > visible_height {
             // Cell is scrolled off to the bottom - align to bottom edge of visible area
-            scroll_y = cell_y + cell_height - visible_height;
+            scroll_y = cell_y + (cell_height - visible_height) as f64;
         }
 
-        // Update scroll if changed
-        if scroll_x != self.viewport.scroll_x || scroll_y != self.viewport.scroll_y {
-            self.viewport.set_scroll(scroll_x, scroll_y, &self.grid);
-            self.viewport.update_visible_range(&self.grid);
-        }
+        (scroll_x, scroll_y)
     }
 
     /// Start editing a cell (called from JavaScript)
     pub fn start_edit(&mut self, row: usize, col: usize) -> bool {
+        if !self.allows_edit(row, col) {
+            return false;
+        }
         // Use the EditingState's start_edit method
-        self.editing.start_edit(row, col, &self.grid)
+        let started = self.editing.start_edit(row, col, &self.grid);
+        self.undo_redo.mark_edit_start();
+        started
     }
 
     /// End editing mode
     pub fn end_edit(&mut self) {
         // Use the EditingState's end_edit method
         self.editing.end_edit();
+        self.undo_redo.end_edit_mark();
+    }
+
+    /// Cancel editing, restoring the value the cell had when the edit started
+    /// Values written with update_cell_value while editing are put back without an
+    /// undo entry, and the entries they recorded are dropped. The in-grid editor is
+    /// closed like cancel_native_edit.
+    pub fn cancel_edit(&mut self) {
+        if self.editing.buffer.is_some() {
+            self.cancel_native_edit();
+            return;
+        }
+        if let Some((row, col)) = self.editing.editing_cell() {
+            self.undo_redo.discard_cell_edits(row, col);
+        }
+        if let Some(cell) = self.editing.cancel_edit(&mut self.grid) {
+            self.dirty_cells.insert(cell);
+            self.revalidate_cells([cell]);
+            self.needs_full_render = true;
+        }
     }
 
     /// Check if currently editing
@@ -1400,1328 +2833,5926 @@ impl DataGrid {
         self.editing.is_editing()
     }
 
-    /// Update cell value during editing
-    pub fn update_cell_value(&mut self, row: usize, col: usize, value: String) {
-        // Record old value for undo
-        let old_value = self.grid.get_value(row, col);
-        let new_value = CellValue::Text(value.clone());
-
-        log::info!("[UndoRedo] Recording cell edit: ({}, {}) \"{}\" -> \"{}\"",
-                   row, col,
-                   old_value.to_string(),
-                   new_value.to_string());
+    // ========== Native Editor API ==========
 
-        // Update the cell
-        self.editing.update_cell_value(row, col, value, &mut self.grid);
+    /// Edit cells inside the grid instead of an external <input> (also the
+    /// `native_editing` option). The editor keeps its own text, caret and selection,
+    /// is drawn over the cell on the text canvas and takes keys from the keyboard
+    /// handlers: Left/Right/Home/End (with Shift to select), Backspace/Delete, Ctrl+A/C/X/V,
+    /// Enter (commit and move down), Tab (commit and move right) and Escape (cancel).
+    /// Clicking in the cell moves the caret; clicking elsewhere commits.
+    /// Applies from the next start_edit; an edit in progress keeps its mode.
+    pub fn set_native_editing(&mut self, enabled: bool) {
+        self.editing.native = enabled;
+    }
 
-        // Record undo action
-        let action = EditAction::SetValue {
-            row,
-            col,
-            old_value: old_value.clone(),
-            new_value: new_value.clone(),
-        };
-        self.undo_redo.undo_stack.push(action);
-        self.undo_redo.redo_stack.clear();
+    /// Check if cells are edited with the in-grid editor
+    pub fn is_native_editing(&self) -> bool {
+        self.editing.native
+    }
 
-        log::info!("[UndoRedo] Undo stack size: {}, Can undo: {}",
-                   self.undo_redo.undo_stack.len(),
-                   !self.undo_redo.undo_stack.is_empty());
+    /// Start editing the selected cell when a printable key is pressed, replacing its
+    /// content (default: on; also the `type_to_edit` option). F2 always edits keeping the content.
+    pub fn set_type_to_edit(&mut self, enabled: bool) {
+        self.keyboard_handler.type_to_edit = enabled;
     }
 
-    /// Get cell position for editing (returns canvas coordinates)
-    pub fn get_cell_edit_rect(&self, row: usize, col: usize) -> Vec<f32> {
-        // Use the EditingState's get_cell_edit_rect method
-        self.editing.get_cell_edit_rect(row, col, &self.grid, &self.viewport)
+    /// Check if type-to-edit is on
+    pub fn is_type_to_edit_enabled(&self) -> bool {
+        self.keyboard_handler.type_to_edit
     }
 
-    /// Handle double-click for editing
-    pub fn handle_double_click(&mut self, event: MouseEvent) -> Option<Vec<usize>> {
-        let x = event.offset_x() as f32;
-        let y = event.offset_y() as f32;
+    /// Suggest values for the cell being edited: distinct values already in its column
+    /// that start with `prefix` (case-insensitive), most frequent first, at most `limit`.
+    /// Only text columns get suggestions; a value equal to the prefix is left out.
+    /// The column's index is built on first use and rebuilt after its data changes.
+    pub fn get_edit_suggestions(&mut self, prefix: &str, limit: usize) -> Vec<String> {
+        let Some((_, col)) = self.editing.editing_cell() else {
+            return Vec::new();
+        };
+        self.get_column_suggestions(col, prefix, limit)
+    }
 
-        // Get cell at click position
-        if let Some((row, col)) = self.viewport.canvas_to_cell(x, y, &self.grid) {
-            if self.start_edit(row, col) {
-                return Some(vec![row, col]);
-            }
+    /// Suggest values for any column (same rules as get_edit_suggestions)
+    pub fn get_column_suggestions(&mut self, col: usize, prefix: &str, limit: usize) -> Vec<String> {
+        if self.grid.get_column_config(col).is_none_or(|config| config.data_type != DataType::Text) {
+            return Vec::new();
         }
+        self.autocomplete.suggestions(&self.grid, col, prefix, limit)
+    }
 
-        None
+    /// Let Tab in the in-grid editor complete the text to the first suggestion before
+    /// committing (default: off; also the `autocomplete_on_tab` option)
+    pub fn set_autocomplete_on_tab(&mut self, enabled: bool) {
+        self.autocomplete.complete_on_tab = enabled;
     }
 
-    /// Handle double-click at specific canvas coordinates (for wrapper use)
-    pub fn handle_double_click_at(&mut self, x: f32, y: f32) -> Option<String> {
-        // Get cell at click position
-        if let Some((row, col)) = self.viewport.canvas_to_cell(x, y, &self.grid) {
-            if self.start_edit(row, col) {
-                return Some(format!("[{},{}]", row, col));
+    /// Check if Tab completes the in-grid editor's text
+    pub fn is_autocomplete_on_tab(&self) -> bool {
+        self.autocomplete.complete_on_tab
+    }
+
+    /// Helper: Replace the in-grid editor's text with its first suggestion, if any
+    fn accept_edit_suggestion(&mut self) {
+        let Some(prefix) = self.get_edit_text() else { return };
+        if let Some(suggestion) = self.get_edit_suggestions(&prefix, 1).pop() {
+            if let Some(buffer) = self.editing.buffer.as_mut() {
+                *buffer = TextBuffer::new(&suggestion);
             }
         }
+    }
 
-        None
+    /// Get the in-grid editor's text (None when no native edit is in progress)
+    pub fn get_edit_text(&self) -> Option<String> {
+        self.editing.buffer.as_ref().map(|buffer| buffer.text.clone())
     }
 
-    /// Check if mouse is over a resize handle
-    /// Returns: "col" for column resize, "row" for row resize, "none" otherwise
-    pub fn check_resize_handle(&self, x: f32, y: f32) -> String {
-        // Use ResizeState's check_resize_handle method
-        self.resize.check_resize_handle(x, y, &self.grid, &self.viewport)
+    /// Type text into the in-grid editor at the caret, replacing the selection
+    /// (for text the keyboard handlers do not see, e.g. IME composition results)
+    pub fn insert_edit_text(&mut self, text: &str) -> bool {
+        let Some(buffer) = self.editing.buffer.as_mut() else {
+            return false;
+        };
+        buffer.insert(text);
+        self.needs_full_render = true;
+        true
     }
 
-    /// Start column or row resize
-    pub fn start_resize(&mut self, x: f32, y: f32, resize_type: &str) -> bool {
-        // Use ResizeState's start_resize method with viewport information
-        self.resize.start_resize(x, y, resize_type, &self.grid, &self.viewport)
+    /// Write the in-grid editor's text into the cell and end editing
+    /// Queues "edit_ended" {row, row_id, col, committed}. Returns false (and keeps editing)
+    /// if no native edit is in progress or the value was rejected (strict unique column).
+    pub fn commit_native_edit(&mut self) -> bool {
+        self.finish_native_edit(true)
     }
 
-    /// Update resize during drag
-    pub fn update_resize(&mut self, x: f32, y: f32) {
-        // Use ResizeState's update_resize method
-        self.resize.update_resize(x, y, &mut self.grid);
+    /// End the in-grid edit without changing the cell (queues "edit_ended" with committed false)
+    pub fn cancel_native_edit(&mut self) {
+        self.finish_native_edit(false);
     }
 
-    /// End resize
-    pub fn end_resize(&mut self) {
-        // Use ResizeState's end_resize method
-        self.resize.end_resize();
+    /// Helper: End a native edit, writing the buffer into the cell when `commit` is set
+    fn finish_native_edit(&mut self, commit: bool) -> bool {
+        let (Some(buffer), Some((row, col))) = (self.editing.buffer.as_ref(), self.editing.editing_cell()) else {
+            return false;
+        };
+        let text = buffer.text.clone();
+        if commit {
+            if !self.update_cell_value(row, col, text) {
+                return false;
+            }
+            self.dirty_cells.insert((row, col));
+        }
+        self.end_edit();
+        self.events.push(
+            "edit_ended",
+            serde_json::json!({ "row": row, "row_id": self.grid.row_id(row), "col": col, "committed": commit }),
+        );
+        self.needs_full_render = true;
+        true
     }
 
-    /// Check if currently resizing
-    pub fn is_resizing(&self) -> bool {
-        self.resize.is_resizing
+    /// Helper: Give a key to the native editor while it is open
+    /// Returns Some(render needed) if the key was consumed, None to continue normal handling
+    fn handle_native_editor_key(&mut self, key: &str, ctrl: bool, shift: bool) -> Option<bool> {
+        let buffer = self.editing.buffer.as_mut()?;
+        match buffer.handle_key(key, ctrl, shift) {
+            EditorKey::Ignored => return Some(false),
+            EditorKey::Handled => {}
+            EditorKey::Cancel => self.cancel_native_edit(),
+            EditorKey::Copy | EditorKey::Cut => {
+                let text = buffer.selected_text();
+                if key.eq_ignore_ascii_case("x") {
+                    buffer.insert("");
+                }
+                wasm_bindgen_futures::spawn_local(async move {
+                    let _ = clipboard::write_system_clipboard_text(text).await;
+                });
+            }
+            EditorKey::Paste => {
+                let this = self.this.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let Ok((text, _)) = clipboard::read_system_clipboard().await else { return };
+                    DataGrid::with_grid(&this, |grid| grid.insert_edit_text(&text));
+                });
+            }
+            EditorKey::CommitAll => {
+                // Ctrl+Enter: the text goes into every selected cell; the selection stays
+                let text = buffer.text.clone();
+                let Some((row, col)) = self.editing.editing_cell() else { return Some(false) };
+                self.commit_edit_to_selection(text);
+                self.events.push(
+                    "edit_ended",
+                    serde_json::json!({ "row": row, "row_id": self.grid.row_id(row), "col": col, "committed": true }),
+                );
+            }
+            EditorKey::Commit => {
+                let Some((row, col)) = self.editing.editing_cell() else { return Some(false) };
+                if key == "Tab" && self.autocomplete.complete_on_tab {
+                    self.accept_edit_suggestion();
+                }
+                if self.commit_native_edit() {
+                    let next = if key == "Tab" {
+                        (col + 1 < self.grid.col_count()).then_some((row, col + 1))
+                    } else {
+                        (row + 1 < self.grid.row_count()).then_some((row + 1, col))
+                    };
+                    if let Some((row, col)) = next {
+                        self.select_single_cell(row, col);
+                        self.mouse_handler.select_cell(row, col);
+                        self.ensure_cell_visible(row, col);
+                    }
+                }
+            }
+        }
+        self.needs_full_render = true;
+        Some(true)
     }
 
-    /// Check if currently drag-selecting (for live selection preview)
-    pub fn is_selecting(&self) -> bool {
-        self.mouse_handler.is_selecting
+    /// Helper: Mouse down (grid pixels) while the native editor is open
+    /// Returns true if the click moved the caret inside the edited cell; a click
+    /// anywhere else commits the edit (or cancels it if the value was rejected)
+    fn native_editor_click(&mut self, x: f32, y: f32) -> bool {
+        let (Some(buffer), Some((row, col))) = (self.editing.buffer.as_ref(), self.editing.editing_cell()) else {
+            return false;
+        };
+        let rect = self.editing.get_cell_edit_rect(row, col, &self.grid, &self.viewport);
+        if x >= rect[0] && x < rect[0] + rect[2] && y >= rect[1] && y < rect[1] + rect[3] {
+            let caret = self.text_renderer.editor_caret_at(&self.grid, col, buffer, x - rect[0]);
+            if let Some(buffer) = self.editing.buffer.as_mut() {
+                buffer.move_to(caret, false);
+            }
+            self.needs_full_render = true;
+            return true;
+        }
+        if !self.commit_native_edit() {
+            self.cancel_native_edit();
+        }
+        false
     }
 
-    /// Select a single cell (clears previous selection)
-    fn select_single_cell(&mut self, row: usize, col: usize) {
-        self.selection.select_single_cell(row, col)
+    /// Write `value` into the edited cell and every visible selected cell at once (Ctrl+Enter
+    /// in spreadsheets) as one undo step, and end editing. Each cell goes through
+    /// update_cell_value, so locked cells, vetoed changes and rejected values are skipped.
+    /// The in-grid editor does this on Ctrl+Enter; hosts with their own editor call it
+    /// instead of update_cell_value. Returns the number of cells written.
+    pub fn commit_edit_to_selection(&mut self, value: String) -> usize {
+        let mut cells: Vec<(usize, usize)> = self.selection.selected_cells
+            .iter()
+            .copied()
+            .filter(|&(row, col)| !self.grid.is_row_hidden(row) && !self.grid.is_col_hidden(col))
+            .collect();
+        if let Some(cell) = self.editing.editing_cell().filter(|cell| !cells.contains(cell)) {
+            cells.push(cell);
+        }
+        cells.sort_unstable();
+        self.end_edit();
+
+        self.undo_redo.begin_group();
+        let mut written = 0;
+        for (row, col) in cells {
+            if self.update_cell_value(row, col, value.clone()) {
+                written += 1;
+            }
+        }
+        self.undo_redo.end_group();
+        self.needs_full_render = true;
+        written
     }
 
-    /// Toggle cell selection (add/remove from selection)
-    fn toggle_cell_selection(&mut self, row: usize, col: usize) {
-        self.selection.toggle_cell_selection(row, col)
+    /// Check in-progress editor content before it is committed, for inline feedback
+    /// Returns {"status": "ok" | "warning" | "error", "message": "..."}: errors break the
+    /// column's validation pattern, a strict unique constraint or the cell's editability;
+    /// warnings are values that would be accepted but stored differently than typed
+    /// (e.g. text in a number column) or duplicates in a non-strict unique column
+    pub fn validate_edit_draft(&self, row: usize, col: usize, draft_text: &str) -> Result<String, JsValue> {
+        if row >= self.grid.row_count() || col >= self.grid.col_count() {
+            return Err(GridError::OutOfBounds { row, col }.into());
+        }
+        let (status, message) = editing::validate_draft(&self.grid, row, col, draft_text);
+        Ok(serde_json::json!({ "status": status.as_str(), "message": message }).to_string())
     }
 
-    /// Select range from anchor to target cell
-    fn select_range(&mut self, target_row: usize, target_col: usize) {
-        self.selection.select_range(target_row, target_col, self.grid.row_count(), self.grid.col_count())
-    }
+    /// Update cell value during editing
+    /// Committing a value into the ghost row/column creates a real row/column
+    /// Returns false if the cell is not editable (see is_cell_editable) or the value was rejected
+    pub fn update_cell_value(&mut self, row: usize, col: usize, value: String) -> bool {
+        if !self.grid.is_cell_editable(row, col) || !self.allows_change(row, col, &value) {
+            return false;
+        }
 
-    /// Clear all selections
-    fn clear_selection(&mut self) {
-        self.selection.clear_selection()
-    }
+        // Column pattern and rules: reject the value in strict mode, otherwise mark the cell
+        let error = editing::validate_value(&self.grid, col, &value);
+        if let Some(message) = &error {
+            self.events.push(
+                "validation_failed",
+                serde_json::json!({
+                    "row": row,
+                    "row_id": self.grid.row_id(row),
+                    "col": col,
+                    "value": value,
+                    "message": message,
+                    "rejected": self.grid.validation_strict,
+                }),
+            );
+            if self.grid.validation_strict {
+                return false;
+            }
+        }
+        if self.grid.is_ghost_cell(row, col) {
+            self.commit_ghost_cell(row, col, value);
+            self.set_cell_validation_error(row, col, error);
+            return true;
+        }
 
-    /// Get selected cells as a JSON array of [row, col] pairs
-    /// Returns: "[[row1, col1], [row2, col2], ...]"
-    pub fn get_selected_cells(&self) -> String {
-        let cells: Vec<Vec<usize>> = self.selection.selected_cells
-            .iter()
-            .map(|(row, col)| vec![*row, *col])
-            .collect();
-        serde_json::to_string(&cells).unwrap_or_else(|_| "[]".to_string())
-    }
+        // Record old value for undo
+        let old_value = self.grid.get_value(row, col);
+        let new_value = CellValue::Text(value.clone());
 
-    /// Get the currently selected cell (first cell in selection)
-    /// Returns: "[row, col]" or null if no selection
-    pub fn get_selected_cell(&self) -> Option<String> {
-        self.selection.selected_cells
-            .iter()
-            .next()
-            .map(|(row, col)| format!("[{},{}]", row, col))
+        // Unique columns: flag duplicates, or reject them in strict mode
+        if self.grid.is_column_unique(col) && self.grid.find_duplicate_row(col, &new_value, &[row]).is_some() {
+            self.report_unique_violation(row, col, &value, "edit");
+            if self.grid.unique_strict {
+                return false;
+            }
+        }
+
+        log::debug!("[UndoRedo] Recording cell edit: ({}, {}) \"{}\" -> \"{}\"",
+                   row, col,
+                   old_value.to_string(),
+                   new_value.to_string());
+
+        // Update the cell
+        self.editing.update_cell_value(row, col, value, &mut self.grid);
+        if self.grid.get_value(row, col) != old_value {
+            self.grid.mark_modified(row, col, old_value.clone());
+        }
+
+        // Record undo action
+        let action = EditAction::SetValue {
+            row,
+            col,
+            old_value: old_value.clone(),
+            new_value: new_value.clone(),
+        };
+        self.undo_redo.record_action(action);
+        self.set_cell_validation_error(row, col, error);
+
+        log::debug!("[UndoRedo] Undo stack size: {}, Can undo: {}",
+                   self.undo_redo.undo_stack.len(),
+                   !self.undo_redo.undo_stack.is_empty());
+        true
     }
 
-    /// Get selection count
-    pub fn get_selection_count(&self) -> usize {
-        self.selection.selected_cells.len()
+    /// Helper: Key of a keyboard event, with keys pressed while an IME is composing
+    /// reported as "Process" (browsers differ in what they send)
+    fn event_key(event: &KeyboardEvent) -> String {
+        if event.is_composing() {
+            "Process".to_string()
+        } else {
+            event.key()
+        }
     }
 
-    /// Select a single cell and make it the active cell
-    pub fn select_cell(&mut self, row: usize, col: usize) {
-        // Select the cell
-        self.select_single_cell(row, col);
+    /// Helper: Client (viewport) coordinates to canvas coordinates
+    fn client_to_canvas(&self, client_x: i32, client_y: i32) -> (f32, f32) {
+        let rect = self.text_canvas.get_bounding_client_rect();
+        ((client_x as f64 - rect.left()) as f32, (client_y as f64 - rect.top()) as f32)
+    }
 
-        // Update mouse handler
-        self.mouse_handler.select_cell(row, col);
+    /// Helper: Run `f` on the grid from a DOM listener or another callback holding a weak
+    /// reference to the shared state. Returns None without calling `f` if the grid was freed
+    /// or is busy (the callback fired while one of the grid's own calls was running).
+    fn with_grid<R>(this: &Weak<RefCell<DataGrid>>, f: impl FnOnce(&mut DataGrid) -> R) -> Option<R> {
+        let state = this.upgrade()?;
+        let mut grid = state.try_borrow_mut().ok()?;
+        Some(f(&mut grid))
     }
 
-    /// Select all cells (Ctrl+A)
-    pub fn select_all(&mut self) {
-        self.selection.select_all(self.grid.row_count(), self.grid.col_count());
+    /// Helper: Queue "edit_started" for the cell an attached listener just opened for editing
+    fn queue_edit_started(&mut self) {
+        if let Some((row, col)) = self.editing.editing_cell() {
+            self.events.push("edit_started", serde_json::json!({ "row": row, "row_id": self.grid.row_id(row), "col": col }));
+        }
     }
 
-    /// Select entire row
-    pub fn select_row(&mut self, row: usize) {
-        self.selection.select_row(row, self.grid.row_count(), self.grid.col_count());
+    /// Helper: Start editing the selected cell from the keyboard: F2 keeps the content
+    /// (caret at the end), typed text replaces it (`text`: the character, or "" when an IME
+    /// starts composing)
+    /// Queues "edit_started" with "text" (null for F2) so an external editor can open the
+    /// same way; returns false if there is no selected cell or it is not editable
+    fn begin_keyboard_edit(&mut self, text: Option<&str>) -> bool {
+        let Some((row, col)) = self.mouse_handler.selected_cell.filter(|&(row, col)| self.allows_edit(row, col)) else {
+            return false;
+        };
+        let started = match text {
+            Some(text) => self.editing.start_edit_replacing(row, col, text, &self.grid),
+            None => self.editing.start_edit(row, col, &self.grid),
+        };
+        if started {
+            self.undo_redo.mark_edit_start();
+            self.events.push(
+                "edit_started",
+                serde_json::json!({ "row": row, "row_id": self.grid.row_id(row), "col": col, "text": text.map(String::from) }),
+            );
+            self.needs_full_render = true;
+        }
+        started
     }
 
-    /// Select entire column
-    pub fn select_col(&mut self, col: usize) {
-        self.selection.select_col(col, self.grid.row_count(), self.grid.col_count());
+    /// Helper: Queue the action returned by handle_touch_end (for attached listeners)
+    fn queue_touch_action(&mut self, action: &str) {
+        let Ok(serde_json::Value::Object(mut detail)) = serde_json::from_str(action) else { return };
+        match detail.remove("action").as_ref().and_then(|a| a.as_str()) {
+            Some("edit") => self.queue_edit_started(),
+            Some("context_menu") if self.grid.enable_context_menu => {
+                self.events.push("context_menu", serde_json::Value::Object(detail));
+            }
+            _ => {}
+        }
     }
 
-    /// Copy selected cells to TSV (Tab-Separated Values) format
-    /// Returns a string with cells separated by tabs and rows separated by newlines
-    pub fn copy_selected_cells(&self) -> String {
-        if self.selection.selected_cells.is_empty() {
-            return String::new();
+    /// Get the canvas-relative center of a touch list, the average distance of the
+    /// touches from that center (0 for a single touch) and the number of touches
+    fn touch_point(&self, touches: &web_sys::TouchList) -> Option<(f32, f32, f32, usize)> {
+        let count = touches.length();
+        if count == 0 {
+            return None;
         }
 
-        // Sort selected cells by row, then by column
-        let mut cells: Vec<(usize, usize)> = self.selection.selected_cells.iter().copied().collect();
-        cells.sort_by(|a, b| {
-            if a.0 == b.0 {
-                a.1.cmp(&b.1)
-            } else {
-                a.0.cmp(&b.0)
-            }
-        });
+        let rect = self.text_canvas.get_bounding_client_rect();
+        let points: Vec<(f64, f64)> = (0..count)
+            .filter_map(|i| touches.get(i))
+            .map(|touch| (touch.client_x() as f64 - rect.left(), touch.client_y() as f64 - rect.top()))
+            .collect();
+        if points.is_empty() {
+            return None;
+        }
 
-        // Find the bounding box
-        let min_row = cells.iter().map(|(r, _)| r).min().unwrap();
-        let max_row = cells.iter().map(|(r, _)| r).max().unwrap();
-        let min_col = cells.iter().map(|(_, c)| c).min().unwrap();
-        let max_col = cells.iter().map(|(_, c)| c).max().unwrap();
-
-        // Build TSV string
-        let mut result = String::new();
-        for row in *min_row..=*max_row {
-            for col in *min_col..=*max_col {
-                if self.selection.selected_cells.contains(&(row, col)) {
-                    let value = self.grid.get_value_string(row, col);
-                    result.push_str(&value);
-                } else {
-                    // Empty cell in the rectangular selection
-                    result.push_str("");
-                }
+        let n = points.len() as f64;
+        let center_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+        let center_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+        let spread = points
+            .iter()
+            .map(|p| ((p.0 - center_x).powi(2) + (p.1 - center_y).powi(2)).sqrt())
+            .sum::<f64>()
+            / n;
+
+        Some((center_x as f32, center_y as f32, spread as f32, points.len()))
+    }
 
-                if col < *max_col {
-                    result.push('\t');
+    /// Start a touch gesture at device canvas coordinates
+    fn touch_start(&mut self, x: f32, y: f32, spread: f32, touch_count: usize, timestamp: f64) {
+        if self.is_editing() {
+            self.end_edit();
+        }
+        // Any new touch stops momentum
+        self.kinetic.stop();
+        let gesture = self.touch_handler.touch_start(x, y, spread, touch_count, timestamp);
+        self.apply_touch_gesture(gesture);
+    }
+
+    /// Continue a touch gesture at device canvas coordinates
+    fn touch_move(&mut self, x: f32, y: f32, spread: f32, touch_count: usize, timestamp: f64) {
+        let gesture = self.touch_handler.touch_move(x, y, spread, touch_count, timestamp);
+        self.apply_touch_gesture(gesture);
+    }
+
+    /// Apply a recognized touch gesture; returns host action JSON (see handle_touch_end_at)
+    /// Gesture coordinates are device canvas pixels and are converted for the zoom level
+    fn apply_touch_gesture(&mut self, gesture: TouchGesture) -> String {
+        let zoom = self.viewport.zoom;
+        match gesture {
+            TouchGesture::None => String::new(),
+            TouchGesture::Pan { dx, dy } => {
+                self.viewport.scroll_by(dx / zoom, dy / zoom, &self.grid);
+                self.viewport.update_visible_range(&self.grid);
+                String::new()
+            }
+            TouchGesture::Fling { vx, vy } => {
+                self.kinetic.fling(vx / zoom, vy / zoom);
+                String::new()
+            }
+            TouchGesture::Pinch { x, y, scale, dx, dy } => {
+                self.viewport.scroll_by(dx / zoom, dy / zoom, &self.grid);
+                self.zoom_by(scale, x, y);
+                String::new()
+            }
+            TouchGesture::Tap { x, y } => {
+                // Same as a click (cell selection, header sort/row selection)
+                self.handle_mouse_down_at_with_modifiers(x, y, false, false);
+                self.mouse_handler.mouse_up();
+                String::new()
+            }
+            TouchGesture::DoubleTap { x, y } => {
+                self.mouse_handler.mouse_up();
+                self.handle_double_click_at(x, y)
+                    .and_then(|_| self.editing.editing_cell())
+                    .map(|(row, col)| format!(r#"{{"action":"edit","row":{},"col":{}}}"#, row, col))
+                    .unwrap_or_default()
+            }
+            TouchGesture::LongPress { x, y } => {
+                let (x, y) = self.viewport.to_grid_pixels(x, y);
+                // Select the pressed cell as the anchor for drag selection
+                if let Some((row, col)) = self.viewport.canvas_to_cell(x, y, &self.grid) {
+                    self.select_single_cell(row, col);
+                    self.mouse_handler.select_cell(row, col);
+                }
+                String::new()
+            }
+            TouchGesture::DragSelect { x, y } => {
+                let (x, y) = self.viewport.to_grid_pixels(x, y);
+                if let Some((row, col)) = self.viewport.canvas_to_cell(x, y, &self.grid) {
+                    self.select_range(row, col);
                 }
+                String::new()
             }
-            if row < *max_row {
-                result.push('\n');
+            TouchGesture::ContextMenu { x, y } => {
+                let (x, y) = self.viewport.to_grid_pixels(x, y);
+                let info = self.context_menu_info_at(x, y);
+                if info.is_empty() {
+                    info
+                } else {
+                    format!(r#"{{"action":"context_menu",{}"#, &info[1..])
+                }
             }
         }
-
-        result
     }
 
-    /// Cut selected cells (copy and then clear)
-    pub fn cut_selected_cells(&mut self) -> String {
-        // First copy the cells
-        let clipboard_text = self.copy_selected_cells();
-
-        // Then clear all selected cells
-        let cells_to_clear: Vec<(usize, usize)> = self.selection.selected_cells.iter().copied().collect();
-        for (row, col) in cells_to_clear {
-            self.grid.set_value(row, col, CellValue::Empty);
+    /// Turn a value typed into the ghost row/column into a real row/column
+    /// Queues a "row_added" or "column_added" event with "source": "ghost"
+    fn commit_ghost_cell(&mut self, row: usize, col: usize, value: String) {
+        // Nothing typed: leave the ghost entry as is
+        if value.is_empty() || self.editing.editing_cell() != Some((row, col)) {
+            return;
         }
 
-        clipboard_text
-    }
-
-    /// Paste cells from TSV (Tab-Separated Values) format
-    /// Pastes starting from the current focus cell
-    pub fn paste_cells(&mut self, tsv_text: String) -> Result<(), GridError> {
-        if tsv_text.is_empty() {
-            return Ok(());
+        let is_row = row == self.grid.row_count();
+        if is_row {
+            self.grid.insert_row(row);
+        } else {
+            self.grid.insert_column(col);
         }
 
-        // Determine starting position (focus cell or first selected cell)
-        let (start_row, start_col) = if let Some(anchor) = self.selection.selection_anchor {
-            anchor
-        } else if !self.selection.selected_cells.is_empty() {
-            let mut cells: Vec<(usize, usize)> = self.selection.selected_cells.iter().copied().collect();
-            cells.sort_by(|a, b| {
-                if a.0 == b.0 {
-                    a.1.cmp(&b.1)
-                } else {
-                    a.0.cmp(&b.0)
-                }
-            });
-            cells[0]
+        self.editing.update_cell_value(row, col, value, &mut self.grid);
+
+        // Record a single undo action: undoing removes the new row/column
+        let action = if is_row {
+            EditAction::InsertRow { index: row, row_id: self.grid.row_id(row), cells: self.grid.get_row_cells(row) }
         } else {
-            return Err(GridError::PasteFailed {
-                reason: "No cell selected for paste".to_string(),
-            });
+            EditAction::InsertColumn { index: col, cells: self.grid.get_column_cells(col) }
         };
+        self.undo_redo.record_action(action);
 
-        // Parse TSV and paste, recording old and new values for undo/redo
-        let mut changed_cells = Vec::new();
-        let lines: Vec<&str> = tsv_text.lines().collect();
-
-        for (row_offset, line) in lines.iter().enumerate() {
-            let target_row = start_row + row_offset;
-            if target_row >= self.grid.row_count() {
-                break; // Don't paste beyond grid bounds
-            }
+        if is_row {
+            self.events.push(
+                "row_added",
+                serde_json::json!({ "row": row, "row_id": self.grid.row_id(row), "source": "ghost" }),
+            );
+        } else {
+            self.events.push("column_added", serde_json::json!({ "col": col, "source": "ghost" }));
+        }
 
-            let values: Vec<&str> = line.split('\t').collect();
-            for (col_offset, value) in values.iter().enumerate() {
-                let target_col = start_col + col_offset;
-                if target_col >= self.grid.col_count() {
-                    break; // Don't paste beyond grid bounds
-                }
+        self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
+    }
 
-                // Record old value for undo
-                let old_value = self.grid.get_value(target_row, target_col);
+    /// Get cell position for editing (returns canvas coordinates)
+    pub fn get_cell_edit_rect(&self, row: usize, col: usize) -> Vec<f32> {
+        // Use the EditingState's get_cell_edit_rect method, scaled to device pixels for the overlay
+        let zoom = self.viewport.zoom;
+        self.editing
+            .get_cell_edit_rect(row, col, &self.grid, &self.viewport)
+            .into_iter()
+            .map(|v| v * zoom)
+            .collect()
+    }
 
-                // Create cell value
-                let new_value = if value.is_empty() {
-                    CellValue::Empty
-                } else if let Ok(num) = value.parse::<f64>() {
-                    CellValue::Number(num)
-                } else if *value == "true" || *value == "false" {
-                    CellValue::Boolean(*value == "true")
-                } else {
-                    CellValue::Text(value.to_string())
-                };
+    /// Handle double-click for editing
+    pub fn handle_double_click(&mut self, event: MouseEvent) -> Option<Vec<usize>> {
+        let (x, y) = self.viewport.to_grid_pixels(event.offset_x() as f32, event.offset_y() as f32);
+        if self.auto_fit_at_handle(x, y) {
+            return None;
+        }
 
-                self.grid.set_value(target_row, target_col, new_value.clone());
-                changed_cells.push((target_row, target_col, old_value, new_value));
+        // Get cell at click position (ghost row/column cells start an add)
+        let cell = self.viewport.canvas_to_cell(x, y, &self.grid)
+            .or_else(|| self.viewport.canvas_to_ghost_cell(x, y, &self.grid));
+        if let Some((row, col)) = cell {
+            // Pivot view: double-clicking a row label expands or collapses its group
+            if self.pivot.is_some() && col == 0 {
+                self.toggle_pivot_row(row);
+                return None;
+            }
+            if self.start_edit(row, col) {
+                return Some(vec![row, col]);
             }
         }
 
-        // Record undo action for all pasted cells
-        if !changed_cells.is_empty() {
-            let action = EditAction::SetMultipleCells { cells: changed_cells };
-            self.undo_redo.undo_stack.push(action);
-            self.undo_redo.redo_stack.clear();
+        None
+    }
+
+    /// Handle double-click at specific canvas coordinates (for wrapper use)
+    /// Double-clicking a column's resize handle auto-fits the column instead of editing
+    pub fn handle_double_click_at(&mut self, x: f32, y: f32) -> Option<String> {
+        let (x, y) = self.viewport.to_grid_pixels(x, y);
+        if self.auto_fit_at_handle(x, y) {
+            return None;
         }
 
-        Ok(())
+        // Get cell at click position (ghost row/column cells start an add)
+        let cell = self.viewport.canvas_to_cell(x, y, &self.grid)
+            .or_else(|| self.viewport.canvas_to_ghost_cell(x, y, &self.grid));
+        if let Some((row, col)) = cell {
+            // Pivot view: double-clicking a row label expands or collapses its group
+            if self.pivot.is_some() && col == 0 {
+                self.toggle_pivot_row(row);
+                return None;
+            }
+            if self.start_edit(row, col) {
+                return Some(format!("[{},{}]", row, col));
+            }
+        }
+
+        None
     }
 
-    /// Helper: Get current cell style (for undo tracking)
-    fn get_cell_style(&self, row: usize, col: usize) -> CellStyle {
-        if let Some(cell) = self.grid.get_cell(row, col) {
-            CellStyle {
-                bg_color: cell.bg_color,
-                fg_color: cell.fg_color,
-                font_bold: cell.font_bold,
-                font_italic: cell.font_italic,
-            }
-        } else {
-            CellStyle {
-                bg_color: None,
-                fg_color: None,
-                font_bold: false,
-                font_italic: false,
+    /// Helper: Auto-fit the column whose resize handle is at grid pixel (x, y), like
+    /// dragging it (a flex column becomes fixed). Row edges are consumed without a change:
+    /// cells do not wrap, so every row already fits its single line of text.
+    /// Returns true if (x, y) was on a resize handle
+    fn auto_fit_at_handle(&mut self, x: f32, y: f32) -> bool {
+        match ResizeState::handle_at(x, y, &self.grid, &self.viewport) {
+            Some(ResizeHandle::Column(col)) => {
+                let width = self.measure_column_width(col, 0..self.grid.row_count());
+                self.set_col_width_with_undo(col, width);
+                if let Some(config) = self.grid.column_configs.get_mut(col) {
+                    config.width_mode = WidthMode::Fixed;
+                }
+                self.after_column_layout_change();
+                true
             }
+            Some(ResizeHandle::Row(_)) => true,
+            None => false,
         }
     }
 
-    /// Set background color for a cell (RGBA as u32: 0xRRGGBBAA)
-    pub fn set_cell_bg_color(&mut self, row: usize, col: usize, color: u32) {
+    /// Check if mouse is over a resize handle
+    /// Returns: "col" for column resize, "row" for row resize, "none" otherwise
+    pub fn check_resize_handle(&self, x: f32, y: f32) -> String {
+        let (x, y) = self.viewport.to_grid_pixels(x, y);
+
+        // Use ResizeState's check_resize_handle method
+        self.resize.check_resize_handle(x, y, &self.grid, &self.viewport)
+    }
+
+    /// Start column or row resize
+    pub fn start_resize(&mut self, x: f32, y: f32, resize_type: &str) -> bool {
+        let (x, y) = self.viewport.to_grid_pixels(x, y);
+
+        // Use ResizeState's start_resize method with viewport information
+        self.resize.start_resize(x, y, resize_type, &self.grid, &self.viewport)
+    }
+
+    /// Update resize during drag
+    pub fn update_resize(&mut self, x: f32, y: f32) {
+        let (x, y) = self.viewport.to_grid_pixels(x, y);
+
+        // Use ResizeState's update_resize method
+        self.resize.update_resize(x, y, &mut self.grid);
+    }
+
+    /// End resize (the resize is recorded for undo)
+    pub fn end_resize(&mut self) {
+        let old_size = self.resize.resize_start_size;
+        if let Some(col) = self.resize.resizing_column {
+            let new_width = self.grid.col_width(col);
+            if new_width != old_size {
+                self.undo_redo.record_action(EditAction::ResizeColumn { col, old_width: old_size, new_width });
+                // A flex column sized by hand keeps that width; the other flex columns adapt
+                if let Some(config) = self.grid.column_configs.get_mut(col) {
+                    config.width_mode = WidthMode::Fixed;
+                }
+                self.apply_flex_widths();
+            }
+        } else if let Some(row) = self.resize.resizing_row {
+            let new_height = self.grid.row_height(row);
+            if new_height != old_size {
+                self.undo_redo.record_action(EditAction::ResizeRow { row, old_height: old_size, new_height });
+            }
+        }
+
+        // Use ResizeState's end_resize method
+        self.resize.end_resize();
+    }
+
+    /// Check if currently resizing
+    pub fn is_resizing(&self) -> bool {
+        self.resize.is_resizing
+    }
+
+    /// Check if currently drag-selecting (for live selection preview)
+    pub fn is_selecting(&self) -> bool {
+        self.mouse_handler.is_selecting
+    }
+
+    // ========== Range Move API ==========
+
+    /// Check if canvas point (x, y) is on the outline of the selected range, where a drag
+    /// moves the range (hosts can show a "move" cursor there)
+    pub fn check_range_move_handle(&self, x: f32, y: f32) -> bool {
+        let (x, y) = self.viewport.to_grid_pixels(x, y);
+        self.selection
+            .rectangle()
+            .and_then(|range| self.range_canvas_rect(range))
+            .is_some_and(|rect| range_move::on_outline(rect, x, y))
+    }
+
+    /// Start dragging the selected range by its outline; with `copy` (Ctrl) the drop copies
+    /// the cells instead of moving them. Returns false if (x, y) is not on the outline of a
+    /// rectangular selection (nothing starts)
+    pub fn start_range_move(&mut self, x: f32, y: f32, copy: bool) -> bool {
+        if !self.check_range_move_handle(x, y) || self.is_editing() {
+            return false;
+        }
+        let Some(range) = self.selection.rectangle() else {
+            return false;
+        };
+        // The grabbed cell is the one nearest to the pointer inside the range
+        let (gx, gy) = self.viewport.to_grid_pixels(x, y);
+        let (row, col) = self.viewport.canvas_to_cell(gx, gy, &self.grid).unwrap_or((range.0, range.1));
+        let (row, col) = (row.clamp(range.0, range.2), col.clamp(range.1, range.3));
+        self.range_move = Some(RangeMove::new(range, row, col, copy));
+        self.needs_full_render = true;
+        true
+    }
+
+    /// Follow the pointer during a range drag; the drop position is outlined at the next render
+    /// `copy` follows the Ctrl key, so it can be pressed or released mid-drag
+    pub fn update_range_move(&mut self, x: f32, y: f32, copy: bool) {
+        let (x, y) = self.viewport.to_grid_pixels(x, y);
+        let cell = self.viewport.canvas_to_cell(x, y, &self.grid);
+        let (rows, cols) = (self.grid.row_count(), self.grid.col_count());
+        if let Some(drag) = self.range_move.as_mut() {
+            drag.copy = copy;
+            if let Some((row, col)) = cell {
+                if drag.drag_to(row, col, rows, cols) {
+                    self.needs_full_render = true;
+                }
+            }
+        }
+    }
+
+    /// Drop the dragged range at its current position (see move_range/copy_range)
+    /// Returns true if cells changed; a rejected drop queues a "range_move_rejected"
+    /// event ({"message"}) and leaves the grid unchanged
+    pub fn end_range_move(&mut self) -> bool {
+        let Some(drag) = self.range_move.take() else {
+            return false;
+        };
+        self.needs_full_render = true;
+        match self.relocate_range(drag.source, drag.target, drag.copy) {
+            Ok(moved) => moved,
+            Err(error) => {
+                self.events.push("range_move_rejected", serde_json::json!({ "message": error.to_string() }));
+                false
+            }
+        }
+    }
+
+    /// Abandon a range drag without changing anything (e.g. on Escape)
+    pub fn cancel_range_move(&mut self) {
+        if self.range_move.take().is_some() {
+            self.needs_full_render = true;
+        }
+    }
+
+    /// Check if a selected range is being dragged
+    pub fn is_moving_range(&self) -> bool {
+        self.range_move.is_some()
+    }
+
+    /// Move the cells of rows r1..=r2, columns c1..=c2 so the top-left cell lands on
+    /// (to_row, to_col). Values and styles travel, overwriting the destination;
+    /// moved-from cells are emptied. Every changed cell must be editable, otherwise
+    /// nothing changes. Recorded as one undo action; the destination range is selected
+    /// afterwards. Returns true if cells changed
+    pub fn move_range(&mut self, r1: usize, c1: usize, r2: usize, c2: usize, to_row: usize, to_col: usize) -> Result<bool, GridError> {
+        let (rows, cols) = self.range_bounds(r1, c1, r2, c2)?;
+        self.relocate_range((*rows.start(), *cols.start(), *rows.end(), *cols.end()), (to_row, to_col), false)
+    }
+
+    /// Copy the cells of rows r1..=r2, columns c1..=c2 (values and styles) so the top-left
+    /// cell lands on (to_row, to_col); otherwise the same as move_range
+    pub fn copy_range(&mut self, r1: usize, c1: usize, r2: usize, c2: usize, to_row: usize, to_col: usize) -> Result<bool, GridError> {
+        let (rows, cols) = self.range_bounds(r1, c1, r2, c2)?;
+        self.relocate_range((*rows.start(), *cols.start(), *rows.end(), *cols.end()), (to_row, to_col), true)
+    }
+
+    /// Helper: Move or copy a checked range (move_range/copy_range and range drags)
+    fn relocate_range(&mut self, source: CellRange, to: (usize, usize), copy: bool) -> Result<bool, GridError> {
+        self.check_flat()?;
+        let (to_row, to_col) = to;
+        let (height, width) = (source.2 - source.0 + 1, source.3 - source.1 + 1);
+        self.check_cell(to_row + height - 1, to_col + width - 1)?;
+
+        let writes = range_move::plan_move(&self.grid, source, (to_row, to_col), copy);
+        if let Some(&(row, col, _)) = writes.iter().find(|(row, col, _)| !self.grid.is_cell_editable(*row, *col)) {
+            return Err(GridError::CellNotEditable { row, col });
+        }
+        self.check_changes_allowed(writes.iter().map(|(row, col, cell)| (*row, *col, Some(cell))))?;
+
+        let mut changed_cells = Vec::new();
+        for (row, col, cell) in writes {
+            let old_cell = self.grid.get_cell(row, col).cloned();
+            self.grid.set_cell(row, col, cell.clone());
+            changed_cells.push((row, col, old_cell, Some(cell)));
+        }
+        let moved = !changed_cells.is_empty();
+        if moved {
+            self.record_edit(EditAction::ReplaceCells { cells: changed_cells });
+        }
+
+        self.selection.select_single_cell(to_row, to_col);
+        self.select_range(to_row + height - 1, to_col + width - 1);
+        self.needs_full_render = true;
+        Ok(moved)
+    }
+
+    // ========== Cell Shift API ==========
+
+    /// Insert empty cells over rows r1..=r2, columns c1..=c2, shifting the cells from there
+    /// on down ("down", within those columns) or right ("right", within those rows)
+    /// Cells pushed past the end grow the grid within the auto-expansion caps; if they
+    /// still do not fit, or a shifted cell is read-only, nothing changes.
+    /// Recorded as one undo action; the inserted range is selected afterwards.
+    pub fn insert_cells(&mut self, r1: usize, c1: usize, r2: usize, c2: usize, shift: &str) -> Result<(), GridError> {
+        let axis = ShiftAxis::parse_insert(shift)?;
+        let (rows, cols) = self.range_bounds(r1, c1, r2, c2)?;
+        self.shift_cells((*rows.start(), *cols.start(), *rows.end(), *cols.end()), axis, true, Vec::new())
+    }
+
+    /// Delete the cells over rows r1..=r2, columns c1..=c2, shifting the cells after them
+    /// up ("up", within those columns) or left ("left", within those rows)
+    /// Nothing changes if a shifted cell is read-only. Recorded as one undo action.
+    pub fn delete_cells(&mut self, r1: usize, c1: usize, r2: usize, c2: usize, shift: &str) -> Result<(), GridError> {
+        let axis = ShiftAxis::parse_delete(shift)?;
+        let (rows, cols) = self.range_bounds(r1, c1, r2, c2)?;
+        self.shift_cells((*rows.start(), *cols.start(), *rows.end(), *cols.end()), axis, false, Vec::new())
+    }
+
+    /// Insert the cells of the last rectangular cut (cut_selected_cells/cut_to_clipboard)
+    /// with their top-left at (target_row, target_col), shifting the cells there down
+    /// ("down") or right ("right") instead of overwriting them. Values and styles are
+    /// inserted; the cut is used up. Otherwise the same as insert_cells.
+    pub fn insert_cut_cells(&mut self, target_row: usize, target_col: usize, shift: &str) -> Result<(), GridError> {
+        let axis = ShiftAxis::parse_insert(shift)?;
+        self.check_cell(target_row, target_col)?;
+        let cells = self.cut_cells.clone().ok_or_else(|| locale::text("error.nothing_cut"))?;
+        let (height, width) = (cells.len(), cells.first().map_or(0, Vec::len));
+        let range = (target_row, target_col, target_row + height - 1, target_col + width - 1);
+        self.shift_cells(range, axis, true, cells)?;
+        self.cut_cells = None;
+        Ok(())
+    }
+
+    /// Check if insert_cut_cells has cut cells to insert
+    pub fn has_cut_cells(&self) -> bool {
+        self.cut_cells.is_some()
+    }
+
+    /// Helper: Remember the selected rectangle's cells before a cut clears them
+    fn keep_cut_cells(&mut self) {
+        self.cut_cells = self.cut_snapshot();
+    }
+
+    /// Helper: Cells of the selected rectangle (None for other selections)
+    fn cut_snapshot(&self) -> Option<Vec<Vec<Option<Cell>>>> {
+        self.selection.rectangle().map(|(r1, c1, r2, c2)| {
+            (r1..=r2).map(|row| (c1..=c2).map(|col| self.grid.get_cell(row, col).cloned()).collect()).collect()
+        })
+    }
+
+    /// Helper: Insert or delete `range`, shifting the neighbouring cells along `axis`;
+    /// an insert then fills the opened range with `fill` (rows of cells)
+    fn shift_cells(&mut self, range: CellRange, axis: ShiftAxis, insert: bool, fill: Vec<Vec<Option<Cell>>>) -> Result<(), GridError> {
+        self.check_flat()?;
+        let (r1, c1, r2, c2) = range;
+        let old_size = if insert {
+            let extent = range_move::insert_extent(&self.grid, range, axis);
+            match axis {
+                ShiftAxis::Vertical => self.auto_expand_for(extent.max(r2 + 1), c2 + 1),
+                ShiftAxis::Horizontal => self.auto_expand_for(r2 + 1, extent.max(c2 + 1)),
+            }
+        } else {
+            None
+        };
+
+        let planned = self.check_cell(r2, c2).and_then(|_| {
+            let mut writes = range_move::plan_shift(&self.grid, range, axis, insert)
+                .map_err(|(row, col)| GridError::from(locale::format("error.shift_no_room", &[("row", &row), ("col", &col)])))?;
+            for (i, cells) in fill.into_iter().enumerate() {
+                for (j, cell) in cells.into_iter().enumerate() {
+                    writes.insert((r1 + i, c1 + j), cell);
+                }
+            }
+            if let Some(&(row, col)) = writes.keys().find(|&&(row, col)| !self.grid.is_cell_editable(row, col)) {
+                return Err(GridError::CellNotEditable { row, col });
+            }
+            self.check_changes_allowed(writes.iter().map(|(&(row, col), cell)| (row, col, cell.as_ref())))?;
+            Ok(writes)
+        });
+        let writes = match planned {
+            Ok(writes) => writes,
+            Err(error) => {
+                // Give back the rows/columns grown for this insert
+                if let Some((rows, cols)) = old_size {
+                    self.grid.resize(rows, cols);
+                    self.viewport.update_visible_range(&self.grid);
+                }
+                return Err(error);
+            }
+        };
+
+        let mut changed_cells = Vec::new();
+        for ((row, col), cell) in writes {
+            let old_cell = match &cell {
+                Some(cell) => {
+                    let old_cell = self.grid.get_cell(row, col).cloned();
+                    self.grid.set_cell(row, col, cell.clone());
+                    old_cell
+                }
+                None => self.grid.remove_cell(row, col),
+            };
+            changed_cells.push((row, col, old_cell, cell));
+        }
+        if !changed_cells.is_empty() || old_size.is_some() {
+            let action = self.with_expansion(old_size, EditAction::ReplaceCells { cells: changed_cells });
+            self.record_edit(action);
+        }
+
+        if insert {
+            self.selection.select_single_cell(r1, c1);
+            self.select_range(r2, c2);
+        }
+        self.needs_full_render = true;
+        Ok(())
+    }
+
+    /// Helper: Canvas rectangle (x, y, width, height) covered by a range of cells
+    /// (not clipped to the visible area; frozen rows/columns do not scroll)
+    fn range_canvas_rect(&self, range: CellRange) -> Option<(f32, f32, f32, f32)> {
+        let (r1, c1, r2, c2) = range;
+        if r2 >= self.grid.row_count() || c2 >= self.grid.col_count() {
+            return None;
+        }
+        let header_offset_x = if self.grid.show_headers { self.grid.row_header_width } else { 0.0 };
+        let header_offset_y = if self.grid.show_headers { self.grid.col_header_height } else { 0.0 };
+        let x = |col: usize, pos: f32| pos + header_offset_x - if col < self.grid.frozen_cols { 0.0 } else { self.viewport.scroll_x };
+        let y = |row: usize, pos: f64| (pos - if row < self.grid.frozen_rows { 0.0 } else { self.viewport.scroll_y }) as f32 + header_offset_y;
+
+        let left = x(c1, self.grid.col_x_position(c1));
+        let right = x(c2, self.grid.col_x_position(c2) + self.grid.col_width(c2));
+        let top = y(r1, self.grid.row_y_position(r1));
+        let bottom = y(r2, self.grid.row_y_position(r2) + self.grid.row_height(r2) as f64);
+        Some((left, top, right - left, bottom - top))
+    }
+
+    /// Select a single cell (clears previous selection)
+    fn select_single_cell(&mut self, row: usize, col: usize) {
+        self.selection.select_single_cell(row, col)
+    }
+
+    /// Toggle cell selection (add/remove from selection)
+    fn toggle_cell_selection(&mut self, row: usize, col: usize) {
+        self.selection.toggle_cell_selection(row, col)
+    }
+
+    /// Select range from anchor to target cell
+    fn select_range(&mut self, target_row: usize, target_col: usize) {
+        self.selection.select_range(target_row, target_col, self.grid.row_count(), self.grid.col_count())
+    }
+
+    /// Clear all selections
+    fn clear_selection(&mut self) {
+        self.selection.clear_selection()
+    }
+
+    /// Get selected cells as a JSON array of [row, col] pairs
+    /// Returns: "[[row1, col1], [row2, col2], ...]"
+    pub fn get_selected_cells(&self) -> String {
+        let cells: Vec<Vec<usize>> = self.selection.selected_cells
+            .iter()
+            .map(|(row, col)| vec![*row, *col])
+            .collect();
+        serde_json::to_string(&cells).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    /// Get the currently selected cell (first cell in selection)
+    /// Returns: "[row, col]" or null if no selection
+    pub fn get_selected_cell(&self) -> Option<String> {
+        self.selection.selected_cells
+            .iter()
+            .next()
+            .map(|(row, col)| format!("[{},{}]", row, col))
+    }
+
+    /// Get selection count
+    pub fn get_selection_count(&self) -> usize {
+        self.selection.selected_cells.len()
+    }
+
+    /// Select a single cell and make it the active cell
+    pub fn select_cell(&mut self, row: usize, col: usize) -> Result<(), JsValue> {
+        self.check_cell(row, col)?;
+        // Select the cell
+        self.select_single_cell(row, col);
+
+        // Update mouse handler
+        self.mouse_handler.select_cell(row, col);
+        Ok(())
+    }
+
+    /// Select all cells (Ctrl+A)
+    pub fn select_all(&mut self) {
+        self.selection.select_all(self.grid.row_count(), self.grid.col_count());
+    }
+
+    /// Select entire row
+    pub fn select_row(&mut self, row: usize) -> Result<(), JsValue> {
+        self.check_row(row)?;
+        self.selection.select_row(row, self.grid.row_count(), self.grid.col_count());
+        Ok(())
+    }
+
+    /// Select entire column
+    pub fn select_col(&mut self, col: usize) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        self.selection.select_col(col, self.grid.row_count(), self.grid.col_count());
+        Ok(())
+    }
+
+    /// Copy selected cells to TSV (Tab-Separated Values) format
+    /// Returns a string with cells separated by tabs and rows separated by newlines
+    /// Values are exported in the copy format (see set_copy_format)
+    pub fn copy_selected_cells(&self) -> String {
+        ClipboardOps::copy_selected_cells(&self.selection.selected_cells, &self.grid, self.copy_format)
+    }
+
+    /// Copy selected cells to TSV with an explicit format for this copy only
+    /// @param format - "raw" (stored values) or "display" (as shown, with number formats)
+    pub fn copy_selected_cells_as(&self, format: &str) -> Result<String, JsValue> {
+        let format = CopyFormat::parse(format)?;
+        Ok(ClipboardOps::copy_selected_cells(&self.selection.selected_cells, &self.grid, format))
+    }
+
+    /// Copy selected cells as an HTML table (what spreadsheets expect as text/html)
+    pub fn copy_selected_cells_html(&self) -> String {
+        ClipboardOps::copy_selected_cells_html(&self.selection.selected_cells, &self.grid, self.copy_format)
+    }
+
+    /// Copy selected cells as structured JSON with typed values, styles and the selection
+    /// layout, for lossless copy/paste between grids (see paste_structured)
+    /// Returns an empty string when nothing is selected
+    pub fn copy_selection_structured(&self) -> String {
+        match ClipboardOps::copy_selected_cells_structured(&self.selection.selected_cells, &self.grid) {
+            serde_json::Value::Null => String::new(),
+            payload => payload.to_string(),
+        }
+    }
+
+    /// Paste a payload from copy_selection_structured at the focus cell
+    /// Values keep their types (dates, booleans, numeric-looking text) and styles are
+    /// restored; cells that were not selected are pasted empty, as with TSV.
+    /// Uses the paste policy; returns the same JSON as paste_cells_with_policy
+    pub fn paste_structured(&mut self, json: &str) -> Result<String, JsValue> {
+        let block = ClipboardOps::parse_structured(json)?;
+        let (applied, pasted, conflicts) = self.paste_block(block, self.paste_policy, PasteSpecial::default())?;
+        let conflicts: Vec<serde_json::Value> = conflicts.iter().map(|c| c.to_json()).collect();
+
+        Ok(serde_json::json!({ "applied": applied, "pasted": pasted, "conflicts": conflicts }).to_string())
+    }
+
+    /// Cut selected cells (copy and then clear)
+    /// A rectangular selection is also kept for insert_cut_cells
+    pub fn cut_selected_cells(&mut self) -> String {
+        // First copy the cells
+        let clipboard_text = self.copy_selected_cells();
+        self.keep_cut_cells();
+
+        // Then clear all selected cells
+        self.clear_selected_cells_with_undo();
+
+        clipboard_text
+    }
+
+    /// Copy selected cells to the system clipboard as TSV (text/plain) and an HTML table (text/html)
+    /// Returns a Promise resolving to the copied TSV text
+    pub fn copy_to_clipboard(&self) -> js_sys::Promise {
+        self.write_selection_to_clipboard(self.copy_format)
+    }
+
+    /// Copy selected cells to the system clipboard with an explicit format for this copy only
+    /// @param format - "raw" (stored values) or "display" (as shown, with number formats)
+    pub fn copy_to_clipboard_as(&self, format: &str) -> Result<js_sys::Promise, JsValue> {
+        Ok(self.write_selection_to_clipboard(CopyFormat::parse(format)?))
+    }
+
+    /// Set the default format of copied values
+    /// @param format - "raw" (default, stored values) or "display" (as shown, with separators/units)
+    pub fn set_copy_format(&mut self, format: &str) -> Result<(), JsValue> {
+        self.copy_format = CopyFormat::parse(format)?;
+        Ok(())
+    }
+
+    /// Get the default copy format name
+    pub fn get_copy_format(&self) -> String {
+        self.copy_format.as_str().to_string()
+    }
+
+    /// Helper: Write the selection (TSV and HTML) to the system clipboard
+    fn write_selection_to_clipboard(&self, format: CopyFormat) -> js_sys::Promise {
+        let tsv = ClipboardOps::copy_selected_cells(&self.selection.selected_cells, &self.grid, format);
+        let html = ClipboardOps::copy_selected_cells_html(&self.selection.selected_cells, &self.grid, format);
+
+        wasm_bindgen_futures::future_to_promise(async move {
+            clipboard::write_system_clipboard(tsv.clone(), html).await?;
+            Ok(JsValue::from_str(&tsv))
+        })
+    }
+
+    /// Cut selected cells to the system clipboard (same formats as copy_to_clipboard)
+    /// Returns a Promise resolving to the TSV text. The cells are cleared (undoable) once the
+    /// clipboard write succeeds, so a rejected write loses nothing; if the data changed in
+    /// the meantime they are left as they are. A rectangular selection is also kept for
+    /// insert_cut_cells.
+    pub fn cut_to_clipboard(&mut self) -> js_sys::Promise {
+        let write = wasm_bindgen_futures::JsFuture::from(self.copy_to_clipboard());
+        let cells: Vec<(usize, usize)> = self.selection.selected_cells.iter().copied().collect();
+        let cut = self.cut_snapshot();
+        let version = self.grid.data_version();
+        let this = self.this.clone();
+
+        wasm_bindgen_futures::future_to_promise(async move {
+            let tsv = write.await?;
+            DataGrid::with_grid(&this, |grid| {
+                if grid.grid.data_version() == version {
+                    grid.cut_cells = cut;
+                    grid.clear_cells_with_undo(cells);
+                }
+            });
+            Ok(tsv)
+        })
+    }
+
+    /// Paste the system clipboard at the focus cell using the paste policy
+    /// An HTML table (text/html from Excel/Sheets) is preferred over the plain text
+    /// Returns a Promise resolving to the same JSON as paste_cells_with_policy, plus "format"
+    /// ("html" or "text") and "data" (the clipboard content, so a "prompt" conflict can be
+    /// resolved with paste_html_with_policy / paste_cells_with_policy)
+    pub fn paste_from_clipboard(&mut self) -> js_sys::Promise {
+        let this = self.this.clone();
+
+        wasm_bindgen_futures::future_to_promise(async move {
+            let (text, html) = clipboard::read_system_clipboard().await?;
+            DataGrid::with_grid(&this, |grid| grid.paste_clipboard_content(text, html))
+                .unwrap_or_else(|| Err(GridError::PasteFailed { reason: locale::text("error.grid_destroyed") }.into()))
+        })
+    }
+
+    /// Helper: Paste what paste_from_clipboard read and build its result JSON
+    fn paste_clipboard_content(&mut self, text: String, html: Option<String>) -> Result<JsValue, JsValue> {
+        // Prefer the HTML table (keeps spans and styles) over the TSV text
+        let (applied, pasted, conflicts, format, data) = match html.as_deref().and_then(html_table::parse_html_table) {
+            Some(block) => {
+                let (applied, pasted, conflicts) = self.paste_block(block, self.paste_policy, PasteSpecial::default())?;
+                (applied, pasted, conflicts, "html", html.unwrap_or_default())
+            }
+            None => {
+                let (applied, pasted, conflicts) = self.paste_tsv(&text, self.paste_policy)?;
+                (applied, pasted, conflicts, "text", text)
+            }
+        };
+        let conflicts: Vec<serde_json::Value> = conflicts.iter().map(|c| c.to_json()).collect();
+
+        Ok(JsValue::from_str(
+            &serde_json::json!({
+                "applied": applied,
+                "pasted": pasted,
+                "conflicts": conflicts,
+                "format": format,
+                "data": data,
+            })
+            .to_string(),
+        ))
+    }
+
+    /// Helper: Focus cell (selection anchor), or the top-left selected cell
+    fn focus_cell(&self) -> Option<(usize, usize)> {
+        self.selection
+            .selection_anchor
+            .or_else(|| self.selection.selected_cells.iter().copied().min())
+    }
+
+    /// Helper: Clear all selected cells, recording a single undo action
+    fn clear_selected_cells_with_undo(&mut self) {
+        let cells: Vec<(usize, usize)> = self.selection.selected_cells.iter().copied().collect();
+        self.clear_cells_with_undo(cells);
+    }
+
+    /// Helper: Delete key: clear the selected cells, or `current` when nothing is selected
+    /// Returns true if a cell was cleared
+    fn clear_for_delete_key(&mut self, current: Option<(usize, usize)>) -> bool {
+        let cells: Vec<(usize, usize)> = if self.selection.selected_cells.is_empty() {
+            current.into_iter().collect()
+        } else {
+            self.selection.selected_cells.iter().copied().collect()
+        };
+        let cleared = self.clear_cells_with_undo(cells);
+        log::debug!("Cleared {} cell(s)", cleared);
+        cleared > 0
+    }
+
+    /// Helper: Clear the editable, non-empty cells among `cells` (skipping any the
+    /// before_change hook vetoes), marking them modified and recording a single undo action
+    /// Returns the number of cleared cells
+    fn clear_cells_with_undo(&mut self, cells: Vec<(usize, usize)>) -> usize {
+        let mut cleared = Vec::new();
+        for (row, col) in cells {
+            let old_value = self.grid.get_value(row, col);
+            if !matches!(old_value, CellValue::Empty) && self.grid.is_cell_editable(row, col) && self.allows_change(row, col, "") {
+                self.grid.set_value(row, col, CellValue::Empty);
+                self.grid.mark_modified(row, col, old_value.clone());
+                self.dirty_cells.insert((row, col));
+                cleared.push((row, col, old_value));
+            }
+        }
+
+        let count = cleared.len();
+        if count > 0 {
+            self.record_edit(EditAction::ClearCells { cells: cleared });
+        }
+        count
+    }
+
+    /// Transpose the selected rectangular range in place (rows become columns)
+    /// The top-left corner stays put; values outside the original range that would be
+    /// overwritten, read-only cells that would change or a result that does not fit in the
+    /// grid reject the whole operation. Only values move; styles stay with their cells.
+    /// Recorded as a single undo action and the transposed range is selected afterwards.
+    pub fn transpose_selection(&mut self) -> Result<(), GridError> {
+        let (r1, c1, r2, c2) = self.selection.rectangle().ok_or_else(|| locale::text("error.not_rectangular"))?;
+        let (height, width) = (r2 - r1 + 1, c2 - c1 + 1);
+        if r1 + width > self.grid.row_count() || c1 + height > self.grid.col_count() {
+            return Err(locale::text("error.transpose_no_room").into());
+        }
+
+        let in_source = |row: usize, col: usize| (r1..=r2).contains(&row) && (c1..=c2).contains(&col);
+        let in_target = |row: usize, col: usize| row < r1 + width && col < c1 + height;
+        let target_cells = (r1..r1 + width).flat_map(|row| (c1..c1 + height).map(move |col| (row, col)));
+        let source_cells = (r1..=r2).flat_map(|row| (c1..=c2).map(move |col| (row, col)));
+
+        let mut writes = Vec::new();
+        for (row, col) in target_cells {
+            let value = self.grid.get_value(r1 + (col - c1), c1 + (row - r1));
+            if !in_source(row, col) && !self.grid.get_value(row, col).is_empty() {
+                return Err(locale::format("error.transpose_overwrite", &[("row", &row), ("col", &col)]).into());
+            }
+            writes.push((row, col, value));
+        }
+        writes.extend(source_cells.filter(|&(row, col)| !in_target(row, col)).map(|(row, col)| (row, col, CellValue::Empty)));
+        writes.retain(|(row, col, value)| self.grid.get_value(*row, *col) != *value);
+        if let Some(&(row, col, _)) = writes.iter().find(|(row, col, _)| !self.grid.is_cell_editable(*row, *col)) {
+            return Err(GridError::CellNotEditable { row, col });
+        }
+        if let Some(&(row, col, _)) = writes.iter().find(|(row, col, value)| !self.allows_change(*row, *col, &value.to_string())) {
+            return Err(locale::format("error.change_rejected", &[("row", &row), ("col", &col)]).into());
+        }
+
+        let mut changed_cells = Vec::new();
+        for (row, col, value) in writes {
+            let old_value = self.grid.get_value(row, col);
+            self.grid.set_value(row, col, value.clone());
+            self.grid.mark_modified(row, col, old_value.clone());
+            changed_cells.push((row, col, old_value, value));
+        }
+        if !changed_cells.is_empty() {
+            self.record_edit(EditAction::SetMultipleCells { cells: changed_cells });
+        }
+
+        self.selection.select_single_cell(r1, c1);
+        self.selection.select_range(r1 + width - 1, c1 + height - 1, self.grid.row_count(), self.grid.col_count());
+        self.needs_full_render = true;
+        Ok(())
+    }
+
+    /// Paste cells from TSV (Tab-Separated Values) format
+    /// Pastes starting from the current focus cell, using the paste policy (see set_paste_policy)
+    /// With the "prompt" policy nothing is pasted if any cell conflicts; use
+    /// paste_cells_with_policy to get the conflict list
+    pub fn paste_cells(&mut self, tsv_text: String) -> Result<(), GridError> {
+        let (applied, _, conflicts) = self.paste_tsv(&tsv_text, self.paste_policy)?;
+        if !applied {
+            return Err(GridError::PasteFailed {
+                reason: locale::format("error.paste_conflicts", &[("count", &conflicts.len())]),
+            });
+        }
+        Ok(())
+    }
+
+    /// Paste TSV with an explicit conflict policy ("overwrite", "skip" or "prompt")
+    /// Returns: {"applied": bool, "pasted": N, "conflicts": [{"row", "col", "reason", "old_value", "new_value"}]}
+    /// "reason" is "non_empty" or "read_only". With "prompt" and conflicts present nothing is
+    /// applied; call again with "overwrite" or "skip" once the user has decided.
+    pub fn paste_cells_with_policy(&mut self, tsv_text: String, policy: &str) -> Result<String, JsValue> {
+        let policy = PastePolicy::parse(policy)?;
+        let (applied, pasted, conflicts) = self.paste_tsv(&tsv_text, policy)?;
+        let conflicts: Vec<serde_json::Value> = conflicts.iter().map(|c| c.to_json()).collect();
+
+        Ok(serde_json::json!({ "applied": applied, "pasted": pasted, "conflicts": conflicts }).to_string())
+    }
+
+    /// Paste an HTML table (the text/html clipboard format of Excel and Google Sheets)
+    /// Keeps cell values, the colspan/rowspan layout (covered cells are pasted empty) and
+    /// basic styles (bold, italic, background and text color). Uses the paste policy.
+    pub fn paste_html(&mut self, html: String) -> Result<(), GridError> {
+        let block = Self::parse_html_block(&html)?;
+        let (applied, _, conflicts) = self.paste_block(block, self.paste_policy, PasteSpecial::default())?;
+        if !applied {
+            return Err(GridError::PasteFailed {
+                reason: locale::format("error.paste_conflicts", &[("count", &conflicts.len())]),
+            });
+        }
+        Ok(())
+    }
+
+    /// Paste an HTML table with an explicit conflict policy
+    /// Returns the same JSON as paste_cells_with_policy
+    pub fn paste_html_with_policy(&mut self, html: String, policy: &str) -> Result<String, JsValue> {
+        let policy = PastePolicy::parse(policy)?;
+        let block = Self::parse_html_block(&html)?;
+        let (applied, pasted, conflicts) = self.paste_block(block, policy, PasteSpecial::default())?;
+        let conflicts: Vec<serde_json::Value> = conflicts.iter().map(|c| c.to_json()).collect();
+
+        Ok(serde_json::json!({ "applied": applied, "pasted": pasted, "conflicts": conflicts }).to_string())
+    }
+
+    /// Paste special: paste TSV or an HTML table (detected from the content) with spreadsheet
+    /// paste modes, recorded as a single undo step
+    /// @param data - TSV text or clipboard HTML containing a <table>
+    /// @param mode - Comma-separated: "values" (values only), "formats" (styles only),
+    ///   "transpose", "skip_blanks" (empty source cells leave targets untouched); "" pastes all
+    /// Uses the paste policy; returns the same JSON as paste_cells_with_policy
+    pub fn paste_special(&mut self, data: String, mode: &str) -> Result<String, JsValue> {
+        let special = PasteSpecial::parse(mode)?;
+        let block = match html_table::parse_html_table(&data) {
+            Some(block) => block,
+            None => data.lines().map(|line| line.split('\t').map(PastedCell::from_text).collect()).collect(),
+        };
+        let (applied, pasted, conflicts) = self.paste_block(block, self.paste_policy, special)?;
+        let conflicts: Vec<serde_json::Value> = conflicts.iter().map(|c| c.to_json()).collect();
+
+        Ok(serde_json::json!({ "applied": applied, "pasted": pasted, "conflicts": conflicts }).to_string())
+    }
+
+    /// Helper: Parse pasted HTML into a block of cells
+    fn parse_html_block(html: &str) -> Result<Vec<Vec<PastedCell>>, GridError> {
+        html_table::parse_html_table(html).ok_or_else(|| GridError::PasteFailed {
+            reason: locale::text("error.no_html_table"),
+        })
+    }
+
+    /// Set the default policy for pasting over non-empty or read-only cells
+    /// @param policy - "overwrite" (default), "skip" or "prompt"
+    /// Read-only cells are never written, whatever the policy
+    pub fn set_paste_policy(&mut self, policy: &str) -> Result<(), JsValue> {
+        self.paste_policy = PastePolicy::parse(policy)?;
+        Ok(())
+    }
+
+    /// Get the current paste policy name
+    pub fn get_paste_policy(&self) -> String {
+        self.paste_policy.as_str().to_string()
+    }
+
+    /// Helper: Paste TSV at the focus cell according to `policy`
+    /// Returns (applied, pasted cell count, conflicts)
+    fn paste_tsv(&mut self, tsv_text: &str, policy: PastePolicy) -> Result<(bool, usize, Vec<PasteConflict>), GridError> {
+        let block: Vec<Vec<PastedCell>> = tsv_text
+            .lines()
+            .map(|line| line.split('\t').map(PastedCell::from_text).collect())
+            .collect();
+        self.paste_block(block, policy, PasteSpecial::default())
+    }
+
+    /// Helper: Paste a block of cells (values and optional styles) at the focus cell
+    /// `special` selects values/formats only, transpose and skip-blanks
+    /// Returns (applied, pasted cell count, conflicts)
+    fn paste_block(
+        &mut self,
+        block: Vec<Vec<PastedCell>>,
+        policy: PastePolicy,
+        special: PasteSpecial,
+    ) -> Result<(bool, usize, Vec<PasteConflict>), GridError> {
+        let block = special.arrange(block);
+        if block.is_empty() {
+            return Ok((true, 0, Vec::new()));
+        }
+
+        // Determine starting position (focus cell or first selected cell)
+        let (start_row, start_col) = self.focus_cell().ok_or_else(|| GridError::PasteFailed {
+            reason: locale::text("error.no_paste_target"),
+        })?;
+
+        // Work out which cells fit (after auto-expansion, if enabled)
+        let width = block.iter().map(|values| values.len()).max().unwrap_or(0);
+        let (rows, cols) = self.grid.auto_expand_size(start_row + block.len(), start_col + width);
+
+        let mut writes = Vec::new();
+        let mut dropped = 0;
+        for (row_offset, values) in block.into_iter().enumerate() {
+            let target_row = start_row + row_offset;
+            for (col_offset, cell) in values.into_iter().enumerate() {
+                let target_col = start_col + col_offset;
+                if special.skip_blanks && cell.value.is_empty() {
+                    continue;
+                }
+                if target_row >= rows || target_col >= cols {
+                    dropped += 1; // Don't paste beyond grid bounds
+                } else {
+                    writes.push((target_row, target_col, cell));
+                }
+            }
+        }
+
+        // Pasting formats only keeps values, so only read-only cells can conflict
+        let conflicts: Vec<PasteConflict> = writes
+            .iter()
+            .filter_map(|(row, col, cell)| match special.content {
+                PasteContent::Formats => PasteConflict::check(&self.grid, *row, *col, &self.grid.get_value(*row, *col)),
+                _ => PasteConflict::check(&self.grid, *row, *col, &cell.value),
+            })
+            .collect();
+        if policy == PastePolicy::Prompt && !conflicts.is_empty() {
+            return Ok((false, 0, conflicts));
+        }
+
+        if dropped > 0 {
+            self.report_truncated("paste", dropped);
+        }
+        let old_size = self.auto_expand_for(rows, cols);
+
+        let skipped: HashSet<(usize, usize)> = conflicts
+            .iter()
+            .filter(|c| c.read_only || policy == PastePolicy::Skip)
+            .map(|c| (c.row, c.col))
+            .collect();
+        // Cells vetoed by the before_change hook keep their value (formats leave values alone)
+        let mut writes: Vec<(usize, usize, PastedCell)> = writes
+            .into_iter()
+            .filter(|(row, col, cell)| {
+                !skipped.contains(&(*row, *col))
+                    && (special.content == PasteContent::Formats || self.allows_change(*row, *col, &cell.value.to_string()))
+            })
+            .collect();
+
+        // Unique columns: flag duplicates, or drop them in strict mode
+        let values: Vec<(usize, usize, CellValue)> = match special.content {
+            PasteContent::Formats => Vec::new(),
+            _ => writes.iter().map(|(r, c, cell)| (*r, *c, cell.value.clone())).collect(),
+        };
+        let duplicates = self.unique_duplicates(&values);
+        for &(row, col) in duplicates.iter() {
+            let value = values.iter().find(|w| w.0 == row && w.1 == col).map(|w| w.2.to_string()).unwrap_or_default();
+            self.report_unique_violation(row, col, &value, "paste");
+        }
+        if self.grid.unique_strict && !duplicates.is_empty() {
+            writes.retain(|(row, col, _)| !duplicates.contains(&(*row, *col)));
+        }
+
+        // Paste, recording old and new values (or whole cells when styles are pasted) for undo/redo
+        let pasted = writes.len();
+        let with_styles = match special.content {
+            PasteContent::All => writes.iter().any(|(_, _, cell)| cell.has_style()),
+            PasteContent::Values => false,
+            PasteContent::Formats => true,
+        };
+        let action = if with_styles {
+            let mut changed_cells = Vec::new();
+            for (target_row, target_col, pasted_cell) in writes {
+                let old_cell = self.grid.get_cell(target_row, target_col).cloned();
+                let mut new_cell = old_cell.clone().unwrap_or_default();
+                if special.content != PasteContent::Formats {
+                    let old_value = std::mem::replace(&mut new_cell.value, pasted_cell.value);
+                    if new_cell.value != old_value {
+                        new_cell.mark_modified(old_value);
+                    }
+                }
+                new_cell.font_bold = pasted_cell.bold;
+                new_cell.font_italic = pasted_cell.italic;
+                new_cell.bg_color = pasted_cell.bg_color;
+                new_cell.fg_color = pasted_cell.fg_color;
+                self.grid.set_cell(target_row, target_col, new_cell.clone());
+                changed_cells.push((target_row, target_col, old_cell, Some(new_cell)));
+            }
+            EditAction::ReplaceCells { cells: changed_cells }
+        } else {
+            let mut changed_cells = Vec::new();
+            for (target_row, target_col, pasted_cell) in writes {
+                // Record old value for undo
+                let old_value = self.grid.get_value(target_row, target_col);
+                self.grid.set_value(target_row, target_col, pasted_cell.value.clone());
+                if pasted_cell.value != old_value {
+                    self.grid.mark_modified(target_row, target_col, old_value.clone());
+                }
+                changed_cells.push((target_row, target_col, old_value, pasted_cell.value));
+            }
+            EditAction::SetMultipleCells { cells: changed_cells }
+        };
+
+        // Record undo action for all pasted cells (including the resize, if any)
+        if pasted > 0 {
+            self.needs_full_render = true;
+        }
+        if pasted > 0 || old_size.is_some() {
+            let action = self.with_expansion(old_size, action);
+            self.record_edit(action);
+        }
+
+        Ok((true, pasted, conflicts))
+    }
+
+    /// Helper: Grow the grid (within the auto-expansion caps) so that rows/cols needed fit
+    /// Returns the previous (rows, cols) when the grid was resized
+    fn auto_expand_for(&mut self, rows_needed: usize, cols_needed: usize) -> Option<(usize, usize)> {
+        let old_size = (self.grid.row_count(), self.grid.col_count());
+        let new_size = self.grid.auto_expand_size(rows_needed, cols_needed);
+        if new_size == old_size {
+            return None;
+        }
+
+        self.grid.resize(new_size.0, new_size.1);
+        self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
+        self.events.push(
+            "grid_expanded",
+            serde_json::json!({
+                "old_rows": old_size.0,
+                "old_cols": old_size.1,
+                "rows": new_size.0,
+                "cols": new_size.1,
+            }),
+        );
+        Some(old_size)
+    }
+
+    /// Helper: Wrap an edit action with the grid expansion that preceded it
+    fn with_expansion(&self, old_size: Option<(usize, usize)>, action: EditAction) -> EditAction {
+        match old_size {
+            Some((old_rows, old_cols)) => EditAction::ExpandGrid {
+                old_rows,
+                old_cols,
+                new_rows: self.grid.row_count(),
+                new_cols: self.grid.col_count(),
+                action: Box::new(action),
+            },
+            None => action,
+        }
+    }
+
+    /// Helper: Cells in `writes` whose value would repeat another value of a unique column
+    /// (existing rows not being overwritten, or an earlier write in the same batch)
+    fn unique_duplicates(&self, writes: &[(usize, usize, CellValue)]) -> Vec<(usize, usize)> {
+        let mut seen: HashMap<usize, HashSet<UniqueKey>> = HashMap::new();
+        let mut duplicates = Vec::new();
+
+        for (row, col, value) in writes {
+            if !self.grid.is_column_unique(*col) {
+                continue;
+            }
+
+            let keys = seen.entry(*col).or_insert_with(|| {
+                let targets: HashSet<usize> = writes.iter().filter(|w| w.1 == *col).map(|w| w.0).collect();
+                (0..self.grid.row_count())
+                    .filter(|r| !targets.contains(r))
+                    .filter_map(|r| UniqueKey::of(&self.grid.get_value(r, *col)))
+                    .collect()
+            });
+
+            if let Some(key) = self.grid.unique_key(*col, value) {
+                if !keys.insert(key) {
+                    duplicates.push((*row, *col));
+                }
+            }
+        }
+
+        duplicates
+    }
+
+    /// Helper: Queue a "uniqueness_violation" event (rejected in strict mode)
+    fn report_unique_violation(&mut self, row: usize, col: usize, value: &str, source: &str) {
+        self.events.push(
+            "uniqueness_violation",
+            serde_json::json!({
+                "row": row,
+                "row_id": self.grid.row_id(row),
+                "col": col,
+                "value": value,
+                "rejected": self.grid.unique_strict,
+                "source": source,
+            }),
+        );
+    }
+
+    /// Helper: Report cells dropped because they fell outside the grid
+    fn report_truncated(&mut self, source: &str, dropped_cells: usize) {
+        log::warn!("[{}] {} cell(s) outside the grid were dropped", source, dropped_cells);
+        self.events.push(
+            "data_truncated",
+            serde_json::json!({ "source": source, "dropped_cells": dropped_cells }),
+        );
+    }
+
+    /// Helper: Get current cell style (for undo tracking)
+    fn get_cell_style(&self, row: usize, col: usize) -> CellStyle {
+        if let Some(cell) = self.grid.get_cell(row, col) {
+            CellStyle {
+                bg_color: cell.bg_color,
+                fg_color: cell.fg_color,
+                font_bold: cell.font_bold,
+                font_italic: cell.font_italic,
+            }
+        } else {
+            CellStyle {
+                bg_color: None,
+                fg_color: None,
+                font_bold: false,
+                font_italic: false,
+            }
+        }
+    }
+
+    /// Set background color for a cell (RGBA as u32: 0xRRGGBBAA)
+    pub fn set_cell_bg_color(&mut self, row: usize, col: usize, color: u32) -> Result<(), JsValue> {
+        self.check_cell(row, col)?;
+        let old_style = self.get_cell_style(row, col);
+
+        if let Some(cell) = self.grid.get_cell_mut(row, col) {
+            cell.bg_color = Some(color);
+        } else {
+            // Create cell if it doesn't exist
+            let mut cell = Cell::empty();
+            cell.bg_color = Some(color);
+            self.grid.set_cell(row, col, cell);
+        }
+
+        self.mark_cell_dirty(row, col);
+
+        let new_style = self.get_cell_style(row, col);
+        let action = EditAction::SetStyle { row, col, old_style, new_style };
+        self.undo_redo.record_action(action);
+        Ok(())
+    }
+
+    /// Set background color for a cell without recording to undo stack (useful for bulk styling)
+    pub fn set_cell_bg_color_no_undo(&mut self, row: usize, col: usize, color: u32) -> Result<(), JsValue> {
+        self.check_cell(row, col)?;
+        if let Some(cell) = self.grid.get_cell_mut(row, col) {
+            cell.bg_color = Some(color);
+        } else {
+            // Create cell if it doesn't exist
+            let mut cell = Cell::empty();
+            cell.bg_color = Some(color);
+            self.grid.set_cell(row, col, cell);
+        }
+        self.mark_cell_dirty(row, col);
+        // Note: We do NOT record to undo stack
+        Ok(())
+    }
+
+    /// Set foreground (text) color for a cell (RGBA as u32: 0xRRGGBBAA)
+    pub fn set_cell_fg_color(&mut self, row: usize, col: usize, color: u32) -> Result<(), JsValue> {
+        self.check_cell(row, col)?;
+        let old_style = self.get_cell_style(row, col);
+
+        if let Some(cell) = self.grid.get_cell_mut(row, col) {
+            cell.fg_color = Some(color);
+        } else {
+            let mut cell = Cell::empty();
+            cell.fg_color = Some(color);
+            self.grid.set_cell(row, col, cell);
+        }
+
+        self.mark_cell_dirty(row, col);
+
+        let new_style = self.get_cell_style(row, col);
+        let action = EditAction::SetStyle { row, col, old_style, new_style };
+        self.undo_redo.record_action(action);
+        Ok(())
+    }
+
+    /// Set font style for a cell
+    pub fn set_cell_font_style(&mut self, row: usize, col: usize, bold: bool, italic: bool) -> Result<(), JsValue> {
+        self.check_cell(row, col)?;
+        let old_style = self.get_cell_style(row, col);
+
+        if let Some(cell) = self.grid.get_cell_mut(row, col) {
+            cell.font_bold = bold;
+            cell.font_italic = italic;
+        } else {
+            let mut cell = Cell::empty();
+            cell.font_bold = bold;
+            cell.font_italic = italic;
+            self.grid.set_cell(row, col, cell);
+        }
+
+        self.mark_cell_dirty(row, col);
+
+        let new_style = self.get_cell_style(row, col);
+        let action = EditAction::SetStyle { row, col, old_style, new_style };
+        self.undo_redo.record_action(action);
+        Ok(())
+    }
+
+    /// Clear background color for a cell
+    pub fn clear_cell_bg_color(&mut self, row: usize, col: usize) -> Result<(), JsValue> {
+        self.check_cell(row, col)?;
+        let old_style = self.get_cell_style(row, col);
+
+        if let Some(cell) = self.grid.get_cell_mut(row, col) {
+            cell.bg_color = None;
+        }
+
+        self.mark_cell_dirty(row, col);
+
+        let new_style = self.get_cell_style(row, col);
+        let action = EditAction::SetStyle { row, col, old_style, new_style };
+        self.undo_redo.record_action(action);
+        Ok(())
+    }
+
+    /// Clear foreground color for a cell
+    pub fn clear_cell_fg_color(&mut self, row: usize, col: usize) -> Result<(), JsValue> {
+        self.check_cell(row, col)?;
+        let old_style = self.get_cell_style(row, col);
+
+        if let Some(cell) = self.grid.get_cell_mut(row, col) {
+            cell.fg_color = None;
+        }
+
+        self.mark_cell_dirty(row, col);
+
+        let new_style = self.get_cell_style(row, col);
+        let action = EditAction::SetStyle { row, col, old_style, new_style };
+        self.undo_redo.record_action(action);
+        Ok(())
+    }
+
+    /// Set cell style (background, foreground, font) in one call
+    pub fn set_cell_style(
+        &mut self,
+        row: usize,
+        col: usize,
+        bg_color: Option<u32>,
+        fg_color: Option<u32>,
+        bold: bool,
+        italic: bool,
+    ) -> Result<(), JsValue> {
+        self.check_cell(row, col)?;
+        self.apply_cell_style(row, col, CellStyle { bg_color, fg_color, font_bold: bold, font_italic: italic });
+        Ok(())
+    }
+
+    /// Helper: Set the style of an existing cell (undoable)
+    fn apply_cell_style(&mut self, row: usize, col: usize, style: CellStyle) {
         let old_style = self.get_cell_style(row, col);
 
-        if let Some(cell) = self.grid.get_cell_mut(row, col) {
-            cell.bg_color = Some(color);
-        } else {
-            // Create cell if it doesn't exist
-            let mut cell = Cell::empty();
-            cell.bg_color = Some(color);
-            self.grid.set_cell(row, col, cell);
+        if let Some(cell) = self.grid.get_cell_mut(row, col) {
+            cell.bg_color = style.bg_color;
+            cell.fg_color = style.fg_color;
+            cell.font_bold = style.font_bold;
+            cell.font_italic = style.font_italic;
+        } else {
+            let mut cell = Cell::empty();
+            cell.bg_color = style.bg_color;
+            cell.fg_color = style.fg_color;
+            cell.font_bold = style.font_bold;
+            cell.font_italic = style.font_italic;
+            self.grid.set_cell(row, col, cell);
+        }
+
+        self.mark_cell_dirty(row, col);
+
+        let new_style = self.get_cell_style(row, col);
+        let action = EditAction::SetStyle { row, col, old_style, new_style };
+        self.undo_redo.record_action(action);
+    }
+
+    /// Set custom border for a cell (top, right, bottom, or left)
+    /// side: 0=top, 1=right, 2=bottom, 3=left
+    pub fn set_cell_border(&mut self, row: usize, col: usize, side: u8, color: u32, width: f32) -> Result<(), JsValue> {
+        self.check_cell(row, col)?;
+        // Get or create borders for this cell
+        let mut borders = self.grid.get_cell_borders(row, col)
+            .cloned()
+            .unwrap_or_default();
+
+        let border = Some(CellBorder { color, width });
+        match side {
+            0 => borders.top = border,
+            1 => borders.right = border,
+            2 => borders.bottom = border,
+            3 => borders.left = border,
+            _ => return Err(GridError::OperationError { message: locale::format("error.border_side", &[("max", &3), ("side", &side)]) }.into()),
+        }
+
+        self.grid.set_cell_borders_at(row, col, borders);
+        self.mark_cell_dirty(row, col);
+        Ok(())
+    }
+
+    /// Set all borders for a cell at once
+    pub fn set_cell_borders(&mut self, row: usize, col: usize, color: u32, width: f32) -> Result<(), JsValue> {
+        self.check_cell(row, col)?;
+        let border = Some(CellBorder { color, width });
+        let borders = CellBorders {
+            top: border.clone(),
+            right: border.clone(),
+            bottom: border.clone(),
+            left: border,
+        };
+        self.grid.set_cell_borders_at(row, col, borders);
+        self.mark_cell_dirty(row, col);
+        Ok(())
+    }
+
+    /// Clear border for a cell side
+    /// side: 0=top, 1=right, 2=bottom, 3=left, 4=all
+    pub fn clear_cell_border(&mut self, row: usize, col: usize, side: u8) -> Result<(), JsValue> {
+        self.check_cell(row, col)?;
+        if side > 4 {
+            return Err(GridError::OperationError { message: locale::format("error.border_side", &[("max", &4), ("side", &side)]) }.into());
+        }
+        if side == 4 {
+            // Clear all borders by removing the entry
+            self.grid.remove_cell_borders(row, col);
+        } else if let Some(borders) = self.grid.get_cell_borders(row, col) {
+            // Clone borders and modify
+            let mut new_borders = borders.clone();
+            match side {
+                0 => new_borders.top = None,
+                1 => new_borders.right = None,
+                2 => new_borders.bottom = None,
+                3 => new_borders.left = None,
+                _ => {}
+            }
+            // If all borders are None, remove the entry; otherwise update it
+            if new_borders.top.is_none() && new_borders.right.is_none()
+                && new_borders.bottom.is_none() && new_borders.left.is_none() {
+                self.grid.remove_cell_borders(row, col);
+            } else {
+                self.grid.set_cell_borders_at(row, col, new_borders);
+            }
+        }
+        self.mark_cell_dirty(row, col);
+        Ok(())
+    }
+
+    // ========== Template API ==========
+
+    /// Fill a range with a repeating template block (values, formulas and styles)
+    /// @param start_row, start_col, end_row, end_col - Target range (inclusive, clamped to grid bounds)
+    /// @param template_json - JSON array of rows, e.g. [["Mon", {"value": "=A1*2", "bold": true}], ...]
+    /// Text placeholders {row}, {col} and {block} are expanded per cell, and relative
+    /// references in formulas (text starting with "=") are shifted on each repetition.
+    /// Cells that are not editable (see is_cell_editable) are skipped.
+    /// The whole fill is recorded as a single undo action.
+    /// Returns the number of cells written
+    pub fn insert_template(
+        &mut self,
+        start_row: usize,
+        start_col: usize,
+        end_row: usize,
+        end_col: usize,
+        template_json: &str,
+    ) -> Result<usize, JsValue> {
+        let template = CellTemplate::from_json(template_json)?;
+
+        if start_row > end_row || start_col > end_col {
+            return Err(GridError::OutOfBounds { row: start_row, col: start_col }.into());
+        }
+
+        // Sizes past usize (u32 on wasm32) cannot be filled
+        let too_large = GridError::OutOfBounds { row: end_row, col: end_col };
+        let (Some(rows_needed), Some(cols_needed)) = (end_row.checked_add(1), end_col.checked_add(1)) else {
+            return Err(too_large.into());
+        };
+        let requested = (rows_needed - start_row).checked_mul(cols_needed - start_col).ok_or(too_large)?;
+
+        // Grow the grid to fit the range when auto-expansion is enabled, otherwise clamp
+        let old_size = self.auto_expand_for(rows_needed, cols_needed);
+        if self.grid.row_count() == 0 || self.grid.col_count() == 0 {
+            return Ok(0);
+        }
+
+        let end_row = end_row.min(self.grid.row_count() - 1);
+        let end_col = end_col.min(self.grid.col_count() - 1);
+        if start_row > end_row || start_col > end_col {
+            return Err(GridError::OutOfBounds { row: start_row, col: start_col }.into());
+        }
+
+        // Fits: the clamped range is no larger than the requested one
+        let filled = (end_row - start_row + 1) * (end_col - start_col + 1);
+        if filled < requested {
+            self.report_truncated("template", requested - filled);
+        }
+
+        // Cells locked by read-only flags, editable rules or the editable callback keep their
+        // content, as do cells vetoed by the before_change hook
+        let hooks = &self.hooks;
+        let changed_cells = template.fill(&mut self.grid, (start_row, start_col, end_row, end_col), |row, col, cell| {
+            hooks.allows_change(row, col, &cell.value.to_string())
+        });
+        self.dirty_cells.extend(changed_cells.iter().map(|(row, col, _, _)| (*row, *col)));
+
+        let count = changed_cells.len();
+        if count > 0 || old_size.is_some() {
+            let action = self.with_expansion(old_size, EditAction::ReplaceCells { cells: changed_cells });
+            self.record_edit(action);
+        }
+
+        Ok(count)
+    }
+
+    // ========== Column Navigation API ==========
+
+    /// Find columns by display or internal name (case-insensitive), best matches first
+    /// Exact names score 1.0, prefixes 0.9 and substrings 0.75; with `fuzzy`, names
+    /// containing the query's characters in order also match ("cstnm" finds "customer_name")
+    /// Returns: [{"col", "display_name", "internal_name", "score"}, ...]
+    pub fn find_column(&self, query: &str, fuzzy: bool) -> String {
+        let matches: Vec<serde_json::Value> = search::find_columns(&self.grid, query, fuzzy)
+            .into_iter()
+            .map(|m| {
+                let config = &self.grid.column_configs[m.col];
+                serde_json::json!({
+                    "col": m.col,
+                    "display_name": config.display_name,
+                    "internal_name": config.internal_name,
+                    "score": m.score,
+                })
+            })
+            .collect();
+        serde_json::Value::Array(matches).to_string()
+    }
+
+    /// Scroll a column into view (keeping the vertical position) and briefly flash its header
+    /// Returns false if the column does not exist
+    pub fn goto_column(&mut self, col: usize) -> bool {
+        if col >= self.grid.col_count() {
+            return false;
+        }
+
+        let (scroll_x, _) = self.scroll_position_for_cell(self.viewport.first_visible_row, col);
+        self.kinetic.stop();
+        self.viewport.set_scroll(scroll_x, self.viewport.scroll_y, &self.grid);
+        self.viewport.update_visible_range(&self.grid);
+
+        self.header_flash = Some((col, hooks::now_ms()));
+        self.needs_full_render = true;
+        true
+    }
+
+    // ========== Column Grouping API ==========
+
+    /// Add a column group for multi-level headers
+    /// @param label - Group label text
+    /// @param start_col - First column in group (0-indexed)
+    /// @param end_col - Last column in group (0-indexed, inclusive)
+    /// @param level - Header level (0 = top level, 1 = second level, etc.)
+    pub fn add_column_group(&mut self, label: String, start_col: usize, end_col: usize, level: usize) {
+        self.grid.add_column_group(label, start_col, end_col, level);
+    }
+
+    /// Clear all column groups (revert to single-level headers)
+    pub fn clear_column_groups(&mut self) {
+        self.grid.clear_column_groups();
+    }
+
+    /// Set the height of each header row (default: 30px)
+    pub fn set_header_row_height(&mut self, height: f32) {
+        self.grid.set_header_row_height(height);
+    }
+
+    /// Get the current number of header levels
+    pub fn get_header_levels(&self) -> usize {
+        self.grid.header_levels
+    }
+
+    /// Get total header height
+    pub fn get_header_height(&self) -> f32 {
+        self.grid.col_header_height
+    }
+
+    // ========== Header Highlight API ==========
+
+    /// Set how the row and column headers of the selection are highlighted, as spreadsheets do
+    /// @param mode - "tint" (default), "bold", "both" or "off"
+    pub fn set_active_header_highlight(&mut self, mode: &str) -> Result<(), JsValue> {
+        self.text_renderer.active_header_highlight = HeaderHighlight::parse(mode)?;
+        self.needs_full_render = true;
+        Ok(())
+    }
+
+    /// Get the header highlight mode ("tint", "bold", "both" or "off")
+    pub fn get_active_header_highlight(&self) -> String {
+        self.text_renderer.active_header_highlight.as_str().to_string()
+    }
+
+    /// Set the background of highlighted headers (CSS color, default: "#d3e3fd")
+    pub fn set_active_header_color(&mut self, color: String) {
+        self.text_renderer.active_header_bg = color;
+        self.needs_full_render = true;
+    }
+
+    // ========== Hover Highlight API ==========
+
+    /// Set how the cell under the mouse pointer is highlighted
+    /// @param style_json - {"row": bool, "column": bool, "cell": bool, "band_color": css,
+    ///   "outline_color": css}: row/column bands across the cell area and an outline around
+    ///   the cell (all off by default); missing keys are off or keep the default colors
+    pub fn set_hover_style(&mut self, style_json: &str) -> Result<(), JsValue> {
+        let json: serde_json::Value = serde_json::from_str(style_json)
+            .map_err(|e| GridError::InvalidJson { error: format!("hover style: {}", e) })?;
+        self.text_renderer.hover_style = HoverStyle::from_json(&json)?;
+        self.needs_full_render = true;
+        Ok(())
+    }
+
+    /// Get the hover highlight style as JSON
+    pub fn get_hover_style(&self) -> String {
+        self.text_renderer.hover_style.to_json().to_string()
+    }
+
+    /// Enable or disable cell_hovered events (e.g. for tooltips)
+    /// When enabled, poll_events() reports {"type": "cell_hovered", "row", "row_id", "col",
+    /// "rect"} each time the pointer moves to another cell; all fields are null when it
+    /// leaves the cells
+    pub fn set_cell_hover_events(&mut self, enabled: bool) {
+        self.mouse_handler.hover_events = enabled;
+    }
+
+    /// Check if cell_hovered events are enabled
+    pub fn is_cell_hover_events_enabled(&self) -> bool {
+        self.mouse_handler.hover_events
+    }
+
+    /// Get the cell under the mouse pointer as [row, col] (undefined when off the cells)
+    pub fn get_hovered_cell(&self) -> Option<Vec<usize>> {
+        self.mouse_handler.hovered_cell.map(|(row, col)| vec![row, col])
+    }
+
+    // ========== Text Contrast API ==========
+
+    /// Draw white text on dark cell backgrounds that have no text color of their own
+    /// (default: true). Light backgrounds keep the default text color; cells with an
+    /// explicit fg_color are never changed.
+    pub fn set_auto_text_contrast(&mut self, enabled: bool) {
+        self.text_renderer.auto_text_contrast = enabled;
+        self.needs_full_render = true;
+    }
+
+    /// Check if automatic text contrast is enabled
+    pub fn is_auto_text_contrast_enabled(&self) -> bool {
+        self.text_renderer.auto_text_contrast
+    }
+
+    // ========== Section Break API ==========
+
+    /// Draw a separator after a column to group sections of a wide table
+    /// @param style - "thin", "medium", "thick", "double", or "none" to hide the line
+    pub fn set_section_break_after_col(&mut self, col: usize, style: &str) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        self.grid.col_section_breaks.insert(col, SectionBreak::parse(style)?);
+        self.needs_full_render = true;
+        Ok(())
+    }
+
+    /// Draw a separator after a row (same styles as set_section_break_after_col)
+    pub fn set_section_break_after_row(&mut self, row: usize, style: &str) -> Result<(), JsValue> {
+        self.check_row(row)?;
+        self.grid.row_section_breaks.insert(row, SectionBreak::parse(style)?);
+        self.needs_full_render = true;
+        Ok(())
+    }
+
+    /// Restore the plain grid line after a column
+    pub fn clear_section_break_after_col(&mut self, col: usize) {
+        if self.grid.col_section_breaks.remove(&col).is_some() {
+            self.needs_full_render = true;
+        }
+    }
+
+    /// Restore the plain grid line after a row
+    pub fn clear_section_break_after_row(&mut self, row: usize) {
+        if self.grid.row_section_breaks.remove(&row).is_some() {
+            self.needs_full_render = true;
+        }
+    }
+
+    /// Remove all section separators
+    pub fn clear_section_breaks(&mut self) {
+        self.grid.col_section_breaks.clear();
+        self.grid.row_section_breaks.clear();
+        self.needs_full_render = true;
+    }
+
+    /// Get section separators as JSON: {"cols": {"2": "thick"}, "rows": {"9": "double"}}
+    pub fn get_section_breaks(&self) -> String {
+        let to_json = |breaks: &std::collections::BTreeMap<usize, SectionBreak>| -> serde_json::Map<String, serde_json::Value> {
+            breaks.iter().map(|(index, b)| (index.to_string(), serde_json::Value::from(b.as_str()))).collect()
+        };
+        serde_json::json!({
+            "cols": to_json(&self.grid.col_section_breaks),
+            "rows": to_json(&self.grid.row_section_breaks),
+        })
+        .to_string()
+    }
+
+    // ========== Column Validation API ==========
+
+    /// Set validation pattern for a column
+    /// @param col - Column index (0-based)
+    /// @param pattern - JavaScript regex pattern (e.g., "^[0-9]+$" for numbers only)
+    /// @param message - Error message to display when validation fails
+    pub fn set_column_validation(&mut self, col: usize, pattern: String, message: String) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        self.grid.set_column_validation(col, pattern, message);
+        Ok(())
+    }
+
+    /// Clear validation pattern for a column
+    pub fn clear_column_validation(&mut self, col: usize) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        self.grid.clear_column_validation(col);
+        Ok(())
+    }
+
+    /// Get validation pattern and message for a column
+    /// Returns JSON string: {"pattern": "regex", "message": "error msg"} or empty string if no validation
+    pub fn get_column_validation(&self, col: usize) -> String {
+        if let Some((pattern, message)) = self.grid.get_column_validation(col) {
+            return format!(r#"{{"pattern":"{}","message":"{}"}}"#,
+                         pattern.replace("\\", "\\\\").replace("\"", "\\\""),
+                         message.replace("\\", "\\\\").replace("\"", "\\\""));
+        }
+        String::new()
+    }
+
+    /// Set constraints checked on top of a column's validation pattern
+    /// @param rules_json - {"required": true, "min": 0, "max": 100, "max_length": 20,
+    ///   "min_date": "2024-01-01", "max_date": "2024-12-31", "allowed": ["A", "B"],
+    ///   "message": "..."}; all keys optional. "message" replaces the built-in messages.
+    ///   Ranges only apply to values that are numbers (dates) as typed.
+    pub fn set_column_rules_json(&mut self, col: usize, rules_json: &str) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        let json: serde_json::Value = serde_json::from_str(rules_json)
+            .map_err(|e| GridError::InvalidJson { error: format!("validation rules: {}", e) })?;
+        self.grid.set_column_rule(col, Some(ValidationRule::from_json(&json)?));
+        Ok(())
+    }
+
+    /// Get a column's validation rules as JSON, or empty string if none
+    pub fn get_column_rules_json(&self, col: usize) -> String {
+        self.grid.get_column_rule(col).map(|rule| rule.to_json().to_string()).unwrap_or_default()
+    }
+
+    /// Remove a column's validation rules (its pattern is kept)
+    pub fn clear_column_rules(&mut self, col: usize) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        self.grid.set_column_rule(col, None);
+        Ok(())
+    }
+
+    /// Check a cell's current value against its column's pattern and rules, marking
+    /// the cell with the error (or clearing its mark)
+    /// Returns the error message, or None if the value is valid
+    pub fn validate_cell(&mut self, row: usize, col: usize) -> Result<Option<String>, JsValue> {
+        if row >= self.grid.row_count() || col >= self.grid.col_count() {
+            return Err(GridError::OutOfBounds { row, col }.into());
+        }
+        let text = self.grid.get_value(row, col).to_string();
+        let error = editing::validate_value(&self.grid, col, &text);
+        self.set_cell_validation_error(row, col, error.clone());
+        Ok(error)
+    }
+
+    /// Reject edits that fail column validation instead of keeping them with an error
+    /// mark (default: false). Either way a "validation_failed" event is queued.
+    pub fn set_validation_strict(&mut self, strict: bool) {
+        self.grid.validation_strict = strict;
+    }
+
+    /// Check if edits failing validation are rejected
+    pub fn is_validation_strict(&self) -> bool {
+        self.grid.validation_strict
+    }
+
+    /// Get the cells marked with a validation error (for tooltips or an error list)
+    /// Returns JSON array: [{"row": 0, "row_id": 5, "col": 2, "message": "..."}, ...]
+    pub fn get_validation_errors_json(&self) -> String {
+        let errors: Vec<serde_json::Value> = self.grid.validation_errors()
+            .into_iter()
+            .map(|(row, col, message)| {
+                serde_json::json!({ "row": row, "row_id": self.grid.row_id(row), "col": col, "message": message })
+            })
+            .collect();
+        serde_json::Value::Array(errors).to_string()
+    }
+
+    /// Remove every validation error mark
+    pub fn clear_validation_errors(&mut self) {
+        for (row, col, _) in self.grid.validation_errors() {
+            self.grid.set_validation_error(row, col, None);
+        }
+        self.needs_full_render = true;
+    }
+
+    /// Set the color of the corner mark on cells with a validation error
+    /// (empty string: no mark). Default: "#d93025"
+    pub fn set_invalid_cell_color(&mut self, color: String) {
+        self.text_renderer.invalid_mark_color = color;
+        self.needs_full_render = true;
+    }
+
+    /// Helper: Record an edit for undo, re-checking the cells it wrote against validation
+    fn record_edit(&mut self, action: EditAction) {
+        self.revalidate_cells(action.value_cells());
+        self.undo_redo.record_action(action);
+    }
+
+    /// Helper: Re-check cells whose value changed outside the editor against their column's
+    /// pattern and rules, so their validation marks match the new values
+    fn revalidate_cells(&mut self, cells: impl IntoIterator<Item = (usize, usize)>) {
+        for (row, col) in cells {
+            if row < self.grid.row_count() && col < self.grid.col_count() {
+                let error = editing::validate_value(&self.grid, col, &self.grid.get_value_string(row, col));
+                self.set_cell_validation_error(row, col, error);
+            }
+        }
+    }
+
+    /// Helper: Set or clear a cell's validation error mark, redrawing the cell if it changed
+    fn set_cell_validation_error(&mut self, row: usize, col: usize, error: Option<String>) {
+        let current = self.grid.get_cell(row, col).and_then(|cell| cell.validation_error.clone());
+        if current != error {
+            self.grid.set_validation_error(row, col, error);
+            self.dirty_cells.insert((row, col));
+        }
+    }
+
+    // ========== Number Format API ==========
+
+    /// Set how numbers in a column are displayed (stored values are unchanged)
+    /// @param format_json - {"decimals": 2, "locale": "de-DE", "thousands_separator": ".",
+    ///   "decimal_separator": ",", "prefix": "$", "suffix": " kg"}; all keys optional.
+    ///   "locale" picks the separators; explicit separators override it
+    pub fn set_column_number_format(&mut self, col: usize, format_json: &str) -> Result<(), JsValue> {
+        let options: serde_json::Value = serde_json::from_str(format_json)
+            .map_err(|e| GridError::InvalidJson { error: format!("number format: {}", e) })?;
+
+        self.grid.set_column_number_format(col, Some(NumberFormat::from_json(&options)));
+        self.needs_full_render = true;
+        Ok(())
+    }
+
+    /// Remove the number format of a column (show raw values)
+    pub fn clear_column_number_format(&mut self, col: usize) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        self.grid.set_column_number_format(col, None);
+        self.needs_full_render = true;
+        Ok(())
+    }
+
+    /// Get the number format of a column as JSON, or empty string if none
+    pub fn get_column_number_format(&self, col: usize) -> String {
+        match self.grid.column_configs.get(col).and_then(|c| c.number_format.as_ref()) {
+            Some(format) => format.to_json().to_string(),
+            None => String::new(),
+        }
+    }
+
+    /// Get a cell's text as displayed (with the column's number format)
+    pub fn get_cell_display_value(&self, row: usize, col: usize) -> String {
+        self.grid.get_display_string(row, col)
+    }
+
+    // ========== Column Summary API ==========
+
+    /// Show a summary band under the column headers ("off", "sparkline" or "stats")
+    /// Numeric columns get a distribution sparkline or "min – max · avg"; summaries are
+    /// computed lazily for visible columns and cached until the data changes
+    pub fn set_column_summary(&mut self, mode: &str) -> Result<(), JsValue> {
+        let mode = SummaryMode::parse(mode)?;
+        let height = if mode == SummaryMode::Off { 0.0 } else { SUMMARY_BAND_HEIGHT };
+        self.summary_mode = mode;
+        self.grid.set_column_summary_height(height);
+        self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
+        Ok(())
+    }
+
+    /// Get the column summary mode ("off", "sparkline" or "stats")
+    pub fn get_column_summary_mode(&self) -> String {
+        self.summary_mode.as_str().to_string()
+    }
+
+    /// Get the summary of a numeric column as JSON: {"count","min","max","mean","bins"}
+    /// Returns an empty string for non-numeric columns
+    pub fn get_column_summary(&mut self, col: usize) -> String {
+        if col >= self.grid.col_count() {
+            return String::new();
+        }
+        match self.summary_cache.get(&self.grid, col) {
+            Some(summary) => summary.to_json().to_string(),
+            None => String::new(),
+        }
+    }
+
+    /// Get descriptive statistics of a column as JSON, computed over all rows
+    /// Numeric columns: {"type":"numeric","count","empty_count","other_count","sum","min",
+    /// "max","mean","median","stddev"} (sample standard deviation)
+    /// Other columns: {"type":"text","count","empty_count","distinct_count",
+    /// "top_values":[{"value","count"}, ...]} (10 most frequent values)
+    /// Returns an empty string for columns outside the grid
+    pub fn get_column_stats(&self, col: usize) -> String {
+        if col >= self.grid.col_count() {
+            return String::new();
+        }
+        ColumnStats::compute(&self.grid, col).to_json().to_string()
+    }
+
+    // ========== Column Totals API ==========
+
+    /// Register a JS reducer computing a custom total for a column (None removes it)
+    /// `reduce(values, col)` receives the visible non-empty values of the column and returns
+    /// the total (any JSON-serializable value). The optional `update(total, old_value,
+    /// new_value, col)` hook is called per edited cell to update the total incrementally;
+    /// returning undefined falls back to calling `reduce`. Row inserts/deletes, sorting and
+    /// filtering always call `reduce`. A "column_total_changed" event {col, total} is queued
+    /// whenever a total changes.
+    pub fn set_column_reducer(&mut self, col: usize, reduce: Option<js_sys::Function>, update: Option<js_sys::Function>) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        self.column_totals.set(col, reduce.map(|reduce| (reduce, update)));
+        self.sync_column_totals();
+        Ok(())
+    }
+
+    /// Check if a column has a total reducer
+    pub fn has_column_reducer(&self, col: usize) -> bool {
+        self.column_totals.contains(col)
+    }
+
+    /// Get a column's custom total as JSON (null if the reducer failed)
+    /// Returns an empty string if the column has no reducer
+    pub fn get_column_total(&mut self, col: usize) -> String {
+        self.sync_column_totals();
+        self.column_totals.get(col).map(|total| total.to_string()).unwrap_or_default()
+    }
+
+    /// Get all custom totals as JSON: {"<col>": total, ...}
+    pub fn get_column_totals(&mut self) -> String {
+        self.sync_column_totals();
+        self.column_totals.to_json().to_string()
+    }
+
+    /// Helper: Bring the custom totals up to date and queue change events
+    fn sync_column_totals(&mut self) {
+        if self.column_totals.is_empty() {
+            return;
+        }
+        for col in self.column_totals.refresh(&self.grid) {
+            let total = self.column_totals.get(col).cloned().unwrap_or(serde_json::Value::Null);
+            self.events.push("column_total_changed", serde_json::json!({ "col": col, "total": total }));
+        }
+    }
+
+    // ========== Footer Aggregates API ==========
+
+    /// Show an aggregate for a column in the footer band pinned below the rows:
+    /// "sum", "avg", "min", "max", "count", "custom" (the set_column_reducer total)
+    /// or "none" to remove it. Aggregates cover the visible rows and update after edits
+    /// and filtering; the band is shown while any column has an aggregate.
+    pub fn set_column_aggregate(&mut self, col: usize, aggregate: &str) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        let aggregate = match aggregate {
+            "" | "none" => None,
+            name => Some(Aggregate::parse(name)?),
+        };
+        self.footer.set(col, aggregate);
+
+        self.grid.footer_height = if self.footer.is_empty() { 0.0 } else { FOOTER_BAND_HEIGHT };
+        let (scroll_x, scroll_y) = (self.viewport.scroll_x, self.viewport.scroll_y);
+        self.viewport.set_scroll(scroll_x, scroll_y, &self.grid);
+        self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
+        Ok(())
+    }
+
+    /// Get a column's footer aggregate ("sum", "avg", "min", "max", "count", "custom" or "none")
+    pub fn get_column_aggregate(&self, col: usize) -> String {
+        self.footer.get(col).map_or("none", |aggregate| aggregate.as_str()).to_string()
+    }
+
+    /// Get the current value of a column's footer aggregate as JSON
+    /// (null when there are no numbers to aggregate; empty string without an aggregate)
+    pub fn get_column_aggregate_value(&mut self, col: usize) -> String {
+        self.aggregate_value(col).map(|value| value.to_string()).unwrap_or_default()
+    }
+
+    /// Group the visible rows by the display value of `group_col` and aggregate `value_col`
+    /// per group with "sum", "avg", "min", "max" or "count" (a one-level pivot)
+    /// Returns: [{"group":"Tokyo","rows":12,"value":3400}, ...] in order of first appearance
+    pub fn group_aggregate_json(&self, group_col: usize, value_col: usize, agg: &str) -> Result<String, JsValue> {
+        for col in [group_col, value_col] {
+            self.check_col(col)?;
+        }
+        let aggregate = Aggregate::parse(agg)?;
+        if aggregate == Aggregate::Custom {
+            return Err(GridError::OperationError { message: locale::text("error.group_custom") }.into());
+        }
+
+        let groups: Vec<serde_json::Value> = aggregate
+            .group(&self.grid, group_col, value_col)
+            .into_iter()
+            .map(|(group, rows, value)| serde_json::json!({ "group": group, "rows": rows, "value": value }))
+            .collect();
+        Ok(serde_json::Value::Array(groups).to_string())
+    }
+
+    /// Helper: Value of a column's footer aggregate (Custom reads the reducer total)
+    fn aggregate_value(&mut self, col: usize) -> Option<serde_json::Value> {
+        match self.footer.get(col)? {
+            Aggregate::Custom => {
+                self.sync_column_totals();
+                Some(self.column_totals.get(col).cloned().unwrap_or(serde_json::Value::Null))
+            }
+            _ => Some(serde_json::json!(self.footer.value(&self.grid, col))),
+        }
+    }
+
+    /// Helper: Draw the footer band for the visible columns with an aggregate
+    fn render_footer(&mut self) {
+        if self.grid.footer_height <= 0.0 {
+            return;
+        }
+
+        let mut cells = Vec::new();
+        for col in self.viewport.first_visible_col..=self.viewport.last_visible_col {
+            if col >= self.grid.col_count() || self.grid.col_width(col) <= 0.0 {
+                continue;
+            }
+            let (Some(aggregate), Some(value)) = (self.footer.get(col), self.aggregate_value(col)) else {
+                continue;
+            };
+            cells.push((col, aggregate.label(), self.format_aggregate(col, aggregate, &value)));
+        }
+        self.text_renderer.render_footer(&self.grid, &self.viewport, &cells);
+    }
+
+    /// Helper: Footer text for an aggregate value, numbers in the column's number format
+    /// (averages without a format are rounded to 2 decimals)
+    fn format_aggregate(&self, col: usize, aggregate: Aggregate, value: &serde_json::Value) -> String {
+        match value {
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::String(text) => text.clone(),
+            serde_json::Value::Number(n) => {
+                let n = n.as_f64().unwrap_or(0.0);
+                let format = self.grid.get_column_config(col).and_then(|c| c.number_format.clone());
+                match (format, aggregate) {
+                    (_, Aggregate::Count) => NumberFormat::default().format(n),
+                    (Some(format), _) => format.format(n),
+                    (None, Aggregate::Avg) => NumberFormat::default().format((n * 100.0).round() / 100.0),
+                    (None, _) => NumberFormat::default().format(n),
+                }
+            }
+            other => other.to_string(),
+        }
+    }
+
+    // ========== Pivot API ==========
+
+    /// Show a pivot table of the grid's visible rows instead of the flat grid
+    /// config: {"rows": [fields], "columns": [fields], "value": field, "aggregate": "sum"}
+    /// Fields are column indices or names; aggregate is "sum" (default), "avg", "min",
+    /// "max" or "count". Row groups get subtotals and the last row/column hold grand totals.
+    /// The pivot view is read-only; clear_pivot() brings back the flat grid unchanged.
+    pub fn set_pivot(&mut self, config_json: &str) -> Result<(), JsValue> {
+        let config: serde_json::Value = serde_json::from_str(config_json)
+            .map_err(|e| GridError::InvalidJson { error: format!("pivot config: {}", e) })?;
+        let flat_grid = self.pivot.as_ref().map_or(&self.grid, |view| &view.flat.grid);
+        let config = PivotConfig::from_json(&config, flat_grid)?;
+
+        if let Some(view) = &mut self.pivot {
+            view.config = config;
+            self.refresh_pivot();
+            return Ok(());
+        }
+
+        // Park the flat grid with its undo history, filters and totals
+        let mut flat = FlatState::new(Grid::new(0, 0));
+        self.swap_flat_state(&mut flat);
+        let mut view = PivotView::new(config, flat);
+        self.grid = view.build();
+        self.pivot = Some(view);
+        self.reset_after_grid_swap();
+        Ok(())
+    }
+
+    /// Return from the pivot view to the flat grid
+    pub fn clear_pivot(&mut self) {
+        let Some(mut view) = self.pivot.take() else {
+            return;
+        };
+        self.swap_flat_state(&mut view.flat);
+        self.reset_after_grid_swap();
+    }
+
+    /// Check if the pivot view is shown
+    pub fn is_pivot_active(&self) -> bool {
+        self.pivot.is_some()
+    }
+
+    /// Get the pivot configuration as JSON (column indices), or an empty string when off
+    pub fn get_pivot_config(&self) -> String {
+        self.pivot.as_ref().map(|view| view.config.to_json().to_string()).unwrap_or_default()
+    }
+
+    /// Expand or collapse the row group in a pivot row (also done by double-clicking its label)
+    /// Returns false if the row is not a group
+    pub fn toggle_pivot_row(&mut self, row: usize) -> bool {
+        let toggled = self.pivot.as_mut().is_some_and(|view| view.toggle_row(row));
+        if toggled {
+            let (rows, height) = (self.grid.row_count(), self.grid.total_height());
+            self.refresh_pivot();
+            if self.grid.row_count() > rows {
+                // Expanded: the new rows below the group row grow in
+                let added: Vec<(usize, f32)> = (row + 1..=row + self.grid.row_count() - rows)
+                    .map(|added| (added, -self.grid.row_extent(added)))
+                    .collect();
+                self.animate_row_extents(&added);
+            } else {
+                self.animate_row_extent(row, (height - self.grid.total_height()) as f32);
+            }
+        }
+        toggled
+    }
+
+    /// Expand a collapsed pivot column, or collapse the column group containing it
+    pub fn toggle_pivot_column(&mut self, col: usize) -> bool {
+        let toggled = self.pivot.as_mut().is_some_and(|view| view.toggle_column(col));
+        if toggled {
+            self.refresh_pivot();
+        }
+        toggled
+    }
+
+    /// Expand every pivot group, or collapse them to the outermost level
+    pub fn set_pivot_expanded(&mut self, expanded: bool) {
+        if let Some(view) = &mut self.pivot {
+            view.set_all_expanded(expanded);
+            self.refresh_pivot();
+        }
+    }
+
+    /// Describe a pivot row: {"path":["East","A"],"level":2,"group":false,"expanded":false}
+    /// (the grand total row has an empty path; empty string outside the pivot view)
+    pub fn get_pivot_row_info(&self, row: usize) -> String {
+        self.pivot
+            .as_ref()
+            .and_then(|view| view.row_info(row))
+            .map(|info| info.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Helper: Rebuild the pivot grid after its configuration or expansion changed
+    fn refresh_pivot(&mut self) {
+        let Some(view) = &mut self.pivot else {
+            return;
+        };
+        self.grid = view.build();
+        self.selection.clear_selection();
+        self.viewport.set_scroll(self.viewport.scroll_x, self.viewport.scroll_y, &self.grid);
+        self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
+    }
+
+    /// Helper: Exchange the grid and its per-column state with a parked flat state
+    fn swap_flat_state(&mut self, flat: &mut FlatState) {
+        self.row_animation.finish(&mut self.grid);
+        std::mem::swap(&mut self.grid, &mut flat.grid);
+        std::mem::swap(&mut self.undo_redo, &mut flat.undo_redo);
+        std::mem::swap(&mut self.column_filters, &mut flat.column_filters);
+        std::mem::swap(&mut self.footer, &mut flat.footer);
+        std::mem::swap(&mut self.column_totals, &mut flat.column_totals);
+    }
+
+    /// Helper: Reset view state referring to cells of the previous grid
+    fn reset_after_grid_swap(&mut self) {
+        self.editing.end_edit();
+        self.autocomplete.clear();
+        self.cut_cells = None;
+        self.selection.clear_selection();
+        self.mouse_handler.selected_cell = None;
+        self.clear_search();
+        self.summary_cache = SummaryCache::new();
+        self.filters_revision = self.grid.revision();
+        self.filters_stale = false;
+        self.apply_flex_widths();
+        self.viewport.set_scroll(0.0, 0.0, &self.grid);
+        self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
+    }
+
+    // ========== Live Options API ==========
+
+    /// Change construction options at runtime, e.g. {"show_headers": false, "frozen_rows": 1}
+    /// Accepts the grid-wide keys of from_container (headers, grid lines, alternate rows,
+    /// selection modes, frozen counts, header sizes, density, policies...); keys left out
+    /// keep their current value. Every value is checked before anything changes, so an
+    /// invalid option leaves the grid untouched. rows, cols, width, height, columns and
+    /// renderer only apply at creation (use resize/set_column_config instead).
+    pub fn update_options_json(&mut self, partial_options: &str) -> Result<(), JsValue> {
+        let options: serde_json::Value = serde_json::from_str(partial_options)
+            .map_err(|e| GridError::InvalidJson { error: format!("options: {}", e) })?;
+        let Some(entries) = options.as_object() else {
+            return Err(GridError::OperationError { message: locale::text("error.options_object") }.into());
+        };
+        for (key, value) in entries {
+            if CREATION_OPTIONS.contains(&key.as_str()) {
+                return Err(GridError::OperationError { message: locale::format("error.option_at_creation", &[("key", key)]) }.into());
+            }
+            let Some((_, kind)) = LIVE_OPTIONS.iter().find(|(name, _)| name == key) else {
+                return Err(GridError::OperationError { message: locale::format("error.unknown_option", &[("key", key)]) }.into());
+            };
+            let valid = match *kind {
+                "boolean" => value.is_boolean(),
+                "count" => value.is_u64(),
+                "number" => value.is_number(),
+                _ => value.is_string(),
+            };
+            if !valid {
+                return Err(GridError::OperationError { message: locale::format("error.option_type", &[("key", key), ("type", &kind)]) }.into());
+            }
+        }
+
+        let paste_policy = options["paste_policy"].as_str().map(PastePolicy::parse).transpose()?;
+        let copy_format = options["copy_format"].as_str().map(CopyFormat::parse).transpose()?;
+        let density = options["density"].as_str().map(Density::parse).transpose()?;
+        let auto_fit_on_load = options["auto_fit_on_load"].as_str().map(AutoFitMode::parse).transpose()?;
+        let summary_mode = options["column_summary"].as_str().map(SummaryMode::parse).transpose()?;
+        let log_level = options["log_level"].as_str().map(features::diagnostics::parse_log_level).transpose()?;
+
+        let grid = &mut self.grid;
+        let flags = [
+            ("readonly", &mut grid.readonly),
+            ("show_headers", &mut grid.show_headers),
+            ("show_grid_lines", &mut grid.show_grid_lines),
+            ("enable_context_menu", &mut grid.enable_context_menu),
+            ("enable_row_selection", &mut grid.enable_row_selection),
+            ("enable_col_selection", &mut grid.enable_col_selection),
+            ("alternate_row_colors", &mut grid.alternate_row_colors),
+            ("ghost_row", &mut grid.show_ghost_row),
+            ("ghost_column", &mut grid.show_ghost_col),
+            ("wheel_momentum", &mut self.wheel_momentum),
+            ("strict_batch", &mut self.strict_batch),
+            ("auto_refresh_filters", &mut self.auto_refresh_filters),
+            ("auto_text_contrast", &mut self.text_renderer.auto_text_contrast),
+            ("native_editing", &mut self.editing.native),
+            ("type_to_edit", &mut self.keyboard_handler.type_to_edit),
+            ("autocomplete_on_tab", &mut self.autocomplete.complete_on_tab),
+        ];
+        for (key, flag) in flags {
+            if let Some(value) = options[key].as_bool() {
+                *flag = value;
+            }
+        }
+        if !self.wheel_momentum {
+            self.kinetic.stop();
+        }
+
+        if let Some(rows) = options["frozen_rows"].as_u64() {
+            self.grid.frozen_rows = (rows as usize).min(self.grid.row_count());
+        }
+        if let Some(cols) = options["frozen_cols"].as_u64() {
+            self.grid.frozen_cols = (cols as usize).min(self.grid.col_count());
+        }
+        if let Some(start) = options["row_label_start"].as_u64() {
+            self.grid.row_label_start = start as usize;
+        }
+        if entries.contains_key("auto_expand") || entries.contains_key("max_rows") || entries.contains_key("max_cols") {
+            let (max_rows, max_cols) = self.grid.auto_expand.unwrap_or((DEFAULT_AUTO_EXPAND_ROWS, DEFAULT_AUTO_EXPAND_COLS));
+            let enabled = options["auto_expand"].as_bool().unwrap_or(self.grid.auto_expand.is_some());
+            self.grid.auto_expand = enabled.then(|| {
+                (
+                    options["max_rows"].as_u64().map_or(max_rows, |n| n as usize),
+                    options["max_cols"].as_u64().map_or(max_cols, |n| n as usize),
+                )
+            });
+        }
+
+        if let Some(width) = options["row_header_width"].as_f64() {
+            self.grid.row_header_width = width.max(0.0) as f32;
+        }
+        if let Some(height) = options["col_header_height"].as_f64() {
+            // The height covers every header level; the summary band stays on top of it
+            let levels = self.grid.header_levels.max(1) as f32;
+            self.grid.set_header_row_height(height.max(0.0) as f32 / levels);
+        }
+        if let Some(density) = density {
+            self.grid.set_density(density);
+            self.text_renderer.set_font_size(density.font_size());
+        }
+        if let Some(padding) = options["cell_padding"].as_f64() {
+            self.grid.cell_padding = padding.max(0.0) as f32;
+        }
+        if let Some(mode) = summary_mode {
+            self.summary_mode = mode;
+            self.grid.set_column_summary_height(if mode == SummaryMode::Off { 0.0 } else { SUMMARY_BAND_HEIGHT });
+        }
+        if let Some(duration) = options["row_animation_ms"].as_f64() {
+            self.set_row_animation_ms(duration);
+        }
+        if let Some(delay) = options["content_preview_delay"].as_f64() {
+            self.hover.delay_ms = delay.max(0.0);
+        }
+        if let Some(level) = log_level {
+            log::set_max_level(level);
+        }
+        if let Some(policy) = paste_policy {
+            self.paste_policy = policy;
+        }
+        if let Some(format) = copy_format {
+            self.copy_format = format;
+        }
+        if let Some(mode) = auto_fit_on_load {
+            self.auto_fit_on_load = mode;
+        }
+        if self.grid.readonly && self.editing.is_editing() {
+            self.editing.end_edit();
+        }
+
+        if let Some(current) = self.options.as_object_mut() {
+            current.extend(entries.clone());
+        }
+        // Header sizes, frozen panes and ghost cells change the scrollable extent
+        self.viewport.set_scroll(self.viewport.scroll_x, self.viewport.scroll_y, &self.grid);
+        self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
+        Ok(())
+    }
+
+    // ========== Density & Padding API ==========
+
+    /// Apply a density preset ("compact", "standard" or "comfortable"): sets the default
+    /// row height, cell padding and font size together. Rows with a custom height keep it.
+    pub fn set_density(&mut self, preset: &str) -> Result<(), JsValue> {
+        let density = Density::parse(preset)?;
+        self.grid.set_density(density);
+        self.text_renderer.set_font_size(density.font_size());
+        self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
+        Ok(())
+    }
+
+    /// Get the density preset ("compact", "standard" or "comfortable")
+    pub fn get_density(&self) -> String {
+        self.grid.density.as_str().to_string()
+    }
+
+    /// Set the horizontal text padding of all cells (columns with their own padding keep it)
+    pub fn set_cell_padding(&mut self, padding: f32) {
+        self.grid.cell_padding = padding.max(0.0);
+        self.needs_full_render = true;
+    }
+
+    /// Set a column's horizontal text padding (None = use the grid-wide cell padding)
+    pub fn set_column_padding(&mut self, col: usize, padding: Option<f32>) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        if let Some(config) = self.grid.column_configs.get_mut(col) {
+            config.padding = padding.map(|p| p.max(0.0));
+            self.needs_full_render = true;
+        }
+        Ok(())
+    }
+
+    /// Get the text padding used by a column's cells
+    pub fn get_column_padding(&self, col: usize) -> f32 {
+        self.grid.cell_padding(col)
+    }
+
+    /// Make a column flexible: it takes a share (by `weight`) of the canvas width left over
+    /// by the other columns, recomputed on resize(). None makes it fixed at its current width
+    /// The weight must be a finite number above 0
+    pub fn set_column_flex(&mut self, col: usize, weight: Option<f32>) -> Result<(), GridError> {
+        self.check_col(col)?;
+        let config = &mut self.grid.column_configs[col];
+        match weight {
+            Some(weight) => {
+                config.flex = WidthMode::flex_weight(weight as f64)?;
+                config.width_mode = WidthMode::Flex;
+            }
+            None => config.width_mode = WidthMode::Fixed,
+        }
+        self.after_column_layout_change();
+        Ok(())
+    }
+
+    /// Get a column's flex weight (None for a fixed-width column)
+    pub fn get_column_flex(&self, col: usize) -> Option<f32> {
+        self.grid
+            .get_column_config(col)
+            .filter(|config| config.width_mode == WidthMode::Flex)
+            .map(|config| config.flex)
+    }
+
+    /// Set the narrowest and widest width a column gets from flex layout, dragging its
+    /// header edge or auto-fit (None = no limit)
+    pub fn set_column_width_limits(&mut self, col: usize, min_width: Option<f32>, max_width: Option<f32>) -> Result<(), GridError> {
+        self.check_col(col)?;
+        let config = &mut self.grid.column_configs[col];
+        config.min_width = min_width;
+        config.max_width = max_width;
+        self.after_column_layout_change();
+        Ok(())
+    }
+
+    /// Get a column's width limits as {"min_width": px | null, "max_width": px | null}
+    pub fn get_column_width_limits(&self, col: usize) -> Result<String, GridError> {
+        self.check_col(col)?;
+        let config = &self.grid.column_configs[col];
+        Ok(serde_json::json!({ "min_width": config.min_width, "max_width": config.max_width }).to_string())
+    }
+
+    /// Allow or forbid resizing a column by dragging its header edge
+    /// (set_column_width and auto-fit still apply)
+    pub fn set_column_resizable(&mut self, col: usize, resizable: bool) -> Result<(), GridError> {
+        self.check_col(col)?;
+        self.grid.column_configs[col].resizable = resizable;
+        Ok(())
+    }
+
+    /// Check if a column can be resized by dragging
+    pub fn is_column_resizable(&self, col: usize) -> bool {
+        self.grid.is_col_resizable(col)
+    }
+
+    /// Set the lowest and highest height dragging a row's header edge can give it
+    /// (None = no limit); the limits follow the row through sorts and inserts
+    pub fn set_row_height_limits(&mut self, row: usize, min_height: Option<f32>, max_height: Option<f32>) -> Result<(), GridError> {
+        self.check_row(row)?;
+        let limits = RowLimits { min_height, max_height, ..self.grid.row_limits(row) };
+        self.grid.set_row_limits(row, limits);
+        Ok(())
+    }
+
+    /// Get a row's height limits as {"min_height": px | null, "max_height": px | null}
+    pub fn get_row_height_limits(&self, row: usize) -> Result<String, GridError> {
+        self.check_row(row)?;
+        let limits = self.grid.row_limits(row);
+        Ok(serde_json::json!({ "min_height": limits.min_height, "max_height": limits.max_height }).to_string())
+    }
+
+    /// Allow or forbid resizing a row by dragging its header edge
+    pub fn set_row_resizable(&mut self, row: usize, resizable: bool) -> Result<(), GridError> {
+        self.check_row(row)?;
+        let limits = RowLimits { resizable, ..self.grid.row_limits(row) };
+        self.grid.set_row_limits(row, limits);
+        Ok(())
+    }
+
+    /// Check if a row can be resized by dragging
+    pub fn is_row_resizable(&self, row: usize) -> bool {
+        self.grid.row_limits(row).resizable
+    }
+
+    /// Helper: Fit the flex columns into the canvas width beside the row headers
+    /// Returns true if a width changed
+    fn apply_flex_widths(&mut self) -> bool {
+        let header_width = if self.grid.show_headers { self.grid.row_header_width } else { 0.0 };
+        let available = self.viewport.canvas_width - header_width - self.grid.ghost_col_width();
+        let changed = self.grid.distribute_flex_widths(available);
+        if changed {
+            self.needs_full_render = true;
+        }
+        changed
+    }
+
+    // ========== Unique Constraint API ==========
+
+    /// Set how a column's text values are ordered when sorting
+    /// @param collation - "ordinal" (code point order, default), "case_insensitive",
+    ///   "natural" ("item2" < "item10", case-insensitive) or "locale" (Intl.Collator)
+    /// @param locale - Locale for "locale" collation (e.g. "de", "ja-JP"; None = browser default)
+    pub fn set_column_collation(&mut self, col: usize, collation: &str, locale: Option<String>) -> Result<(), JsValue> {
+        let collation = Collation::parse(collation)?;
+        self.grid.set_column_collation(col, collation, locale)?;
+        Ok(())
+    }
+
+    /// Get a column's collation as {"collation": "natural", "locale": null}
+    pub fn get_column_collation(&self, col: usize) -> String {
+        match self.grid.column_configs.get(col) {
+            Some(config) => serde_json::json!({
+                "collation": config.collation.as_str(),
+                "locale": config.collation_locale,
+            })
+            .to_string(),
+            None => String::new(),
+        }
+    }
+
+    /// Sort a column by a list of values instead of alphabetically, e.g.
+    /// ["Low", "Medium", "High"] or weekday names. Values are matched exactly against the
+    /// cell text; values not in the list sort after the listed ones.
+    /// @param order_json - JSON array of values, or "" / "null" to remove the list
+    pub fn set_column_sort_order(&mut self, col: usize, order_json: &str) -> Result<(), JsValue> {
+        let order = match order_json.trim() {
+            "" | "null" => None,
+            json => {
+                let values: Vec<serde_json::Value> = serde_json::from_str(json)
+                    .map_err(|e| GridError::InvalidJson { error: format!("sort order (expected an array): {}", e) })?;
+                Some(values.iter().map(|v| CellValue::from_json(v).to_string()).collect())
+            }
+        };
+        self.grid.set_column_sort_order(col, order);
+        Ok(())
+    }
+
+    /// Get a column's sort order list as a JSON array, or "null" if it has none
+    pub fn get_column_sort_order(&self, col: usize) -> String {
+        let order = self.grid.column_configs.get(col).and_then(|c| c.sort_order.as_ref());
+        serde_json::json!(order).to_string()
+    }
+
+    /// Sort a column with a JS comparison function instead of the built-in ordering
+    /// (pass null/undefined to remove it). The function is called as compare(a, b) with two
+    /// non-empty cell values (numbers, booleans or strings; dates as strings) and returns a
+    /// negative number, zero or a positive number like an Array.prototype.sort comparator.
+    /// Empty cells still sort last. Comparators are not saved by serialize_state.
+    pub fn set_column_comparator(&mut self, col: usize, compare: Option<js_sys::Function>) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        self.grid.set_column_comparator(col, compare);
+        Ok(())
+    }
+
+    /// Check if a column sorts with a JS comparison function
+    pub fn has_column_comparator(&self, col: usize) -> bool {
+        self.grid.column_configs.get(col).is_some_and(|c| c.comparator.is_some())
+    }
+
+    /// Require values in a column to be unique
+    /// Duplicates entered by edit or paste queue a "uniqueness_violation" event,
+    /// and are rejected when strict mode is on
+    pub fn set_column_unique(&mut self, col: usize, unique: bool) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        self.grid.set_column_unique(col, unique);
+        Ok(())
+    }
+
+    /// Check if a column requires unique values
+    pub fn is_column_unique(&self, col: usize) -> bool {
+        self.grid.is_column_unique(col)
+    }
+
+    /// Reject duplicate values in unique columns instead of only flagging them
+    pub fn set_unique_strict(&mut self, strict: bool) {
+        self.grid.unique_strict = strict;
+    }
+
+    /// Check if duplicate values in unique columns are rejected
+    pub fn is_unique_strict(&self) -> bool {
+        self.grid.unique_strict
+    }
+
+    /// Get the duplicate values currently in a column
+    /// Returns JSON array: [{"value": "a", "rows": [0, 3]}, ...]
+    pub fn get_uniqueness_violations(&self, col: usize) -> String {
+        let violations: Vec<serde_json::Value> = self.grid.uniqueness_violations(col)
+            .into_iter()
+            .map(|(value, rows)| serde_json::json!({ "value": value, "rows": rows }))
+            .collect();
+        serde_json::Value::Array(violations).to_string()
+    }
+
+    // ========== Column Editable Control API ==========
+
+    /// Set whether a column is editable
+    /// @param col - Column index (0-based)
+    /// @param editable - true: editable, false: read-only
+    pub fn set_column_editable(&mut self, col: usize, editable: bool) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        self.grid.set_column_editable(col, editable);
+        Ok(())
+    }
+
+    /// Check if a column is editable
+    pub fn is_column_editable(&self, col: usize) -> bool {
+        self.grid.is_column_editable(col)
+    }
+
+    /// Get editable status for all columns as JSON array
+    /// Returns: "[true, false, true, ...]"
+    pub fn get_all_column_editable_status(&self) -> String {
+        let status = self.grid.get_all_column_editable_status();
+        format!("[{}]", status.iter()
+            .map(|b| b.to_string())
+            .collect::<Vec<_>>()
+            .join(","))
+    }
+
+    /// Lock cells by row data, e.g. [{"column": "status", "equals": "locked"}]
+    /// Each rule tests one column ("column": index or internal name) with "equals",
+    /// "not_equals", "in" or "not_in" (null matches empty cells) and makes the matching
+    /// rows read-only, or only their "columns" when given. Replaces the previous rules;
+    /// "[]" removes them. Locked cells refuse editing, paste, Delete and cut.
+    pub fn set_editable_rule_json(&mut self, rules_json: &str) -> Result<(), JsValue> {
+        let rules: serde_json::Value = serde_json::from_str(rules_json)
+            .map_err(|e| GridError::InvalidJson { error: format!("editable rules: {}", e) })?;
+        let Some(rules) = rules.as_array() else {
+            return Err(GridError::OperationError { message: locale::text("error.editable_rules_array") }.into());
+        };
+        self.grid.editable_rules = rules
+            .iter()
+            .map(|rule| EditableRule::from_json(rule, &self.grid))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.needs_full_render = true;
+        Ok(())
+    }
+
+    /// Get the editable rules as JSON (columns as indices, conditions as "in"/"not_in")
+    pub fn get_editable_rule_json(&self) -> String {
+        serde_json::Value::Array(self.grid.editable_rules.iter().map(EditableRule::to_json).collect()).to_string()
+    }
+
+    /// Set (Some) or remove (None) a predicate deciding editability per cell
+    /// Called as callback(row, col) for cells that pass the column, cell and rule checks,
+    /// including for every visible cell when rendering, so keep it fast.
+    /// Returning false (or throwing) locks the cell.
+    pub fn set_editable_callback(&mut self, callback: Option<js_sys::Function>) {
+        self.grid.editable_callback = callback;
+        self.needs_full_render = true;
+    }
+
+    /// Check if a cell can be edited (read-only mode, column, row and cell flags, editable
+    /// rules and the editable callback)
+    pub fn is_cell_editable(&self, row: usize, col: usize) -> bool {
+        row < self.grid.row_count() && col < self.grid.col_count() && self.grid.is_cell_editable(row, col)
+    }
+
+    /// Make a single cell read-only (or editable again); the flag stays with the cell
+    /// when rows are sorted, and is kept by snapshots
+    pub fn set_cell_readonly(&mut self, row: usize, col: usize, readonly: bool) -> Result<(), JsValue> {
+        self.check_cell(row, col)?;
+        self.grid.set_cell_editable(row, col, !readonly);
+        self.dirty_cells.insert((row, col));
+        Ok(())
+    }
+
+    /// Check if a cell was made read-only with set_cell_readonly
+    pub fn is_cell_readonly(&self, row: usize, col: usize) -> bool {
+        self.grid.get_cell(row, col).is_some_and(|cell| !cell.editable)
+    }
+
+    /// Make every cell of a row read-only (or editable again); the flag follows the row
+    /// through sorts, inserts and deletes of other rows
+    pub fn set_row_readonly(&mut self, row: usize, readonly: bool) -> Result<(), JsValue> {
+        self.check_row(row)?;
+        self.grid.set_row_readonly(row, readonly);
+        self.needs_full_render = true;
+        Ok(())
+    }
+
+    /// Check if a row was made read-only with set_row_readonly
+    pub fn is_row_readonly(&self, row: usize) -> bool {
+        self.grid.is_row_readonly(row)
+    }
+
+    /// Get the rows made read-only with set_row_readonly as a JSON array of row indices
+    pub fn get_readonly_rows(&self) -> String {
+        serde_json::json!(self.grid.readonly_rows()).to_string()
+    }
+
+    /// Set the text color of cells that are not editable (empty string: no distinct color)
+    /// Not applied when the whole grid is read-only. Default: "#9e9e9e"
+    pub fn set_locked_cell_color(&mut self, color: String) {
+        self.text_renderer.locked_text_color = color;
+        self.needs_full_render = true;
+    }
+
+    /// Set column header name
+    /// @param col - Column index (0-based)
+    /// @param name - Header name to display
+    pub fn set_column_name(&mut self, col: usize, name: &str) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        if col < self.grid.column_configs.len() {
+            self.grid.column_configs[col].display_name = name.to_string();
+            self.needs_full_render = true;
+        }
+        Ok(())
+    }
+
+    /// Insert a row at the specified position
+    pub fn insert_row(&mut self, at_index: usize) {
+        if at_index > self.grid.row_count() || self.pivot.is_some() {
+            return;
+        }
+        self.grid.insert_row(at_index);
+        self.animate_row_extent(at_index, -self.grid.row_extent(at_index));
+
+        // Record action for undo (undoing InsertRow deletes the row, redo restores it with its ID)
+        let action = EditAction::InsertRow {
+            index: at_index,
+            row_id: self.grid.row_id(at_index),
+            cells: Vec::new(), // Empty row being inserted
+        };
+        self.undo_redo.record_action(action);
+
+        self.clear_selection();
+        self.viewport.update_visible_range(&self.grid);
+    }
+
+    /// Delete a row at the specified position
+    pub fn delete_row(&mut self, index: usize) {
+        if index >= self.grid.row_count() || self.grid.row_count() <= 1 || self.pivot.is_some() {
+            return;
+        }
+
+        // Save cells before deletion for undo
+        let cells = self.grid.get_row_cells(index);
+
+        let action = EditAction::DeleteRow {
+            index,
+            row_id: self.grid.row_id(index),
+            cells,
+        };
+        self.undo_redo.record_action(action);
+
+        let extent = self.grid.row_extent(index);
+        self.grid.delete_row(index);
+        if index > 0 {
+            // The gap closes below the row above
+            self.animate_row_extent(index - 1, extent);
+        }
+        self.clear_selection();
+        self.viewport.update_visible_range(&self.grid);
+    }
+
+    /// Insert a row at the position required by the active sort (binary search)
+    /// Appends when no sort is active
+    /// @param values_json - Row values as an array by column index (e.g. [1, "Alice", true])
+    ///                      or an object keyed by column internal name (e.g. {"id": 1, "name": "Alice"})
+    /// Returns: Index of the inserted row
+    pub fn insert_row_sorted(&mut self, values_json: &str) -> Result<usize, JsValue> {
+        self.check_flat()?;
+        let parsed: serde_json::Value = serde_json::from_str(values_json)
+            .map_err(|e| GridError::InvalidJson { error: format!("data: {}", e) })?;
+
+        let col_count = self.grid.col_count();
+        let values: Vec<CellValue> = match &parsed {
+            serde_json::Value::Array(items) => (0..col_count)
+                .map(|col| items.get(col).map(|v| self.json_to_cell_value(v, col)).unwrap_or(CellValue::Empty))
+                .collect(),
+            serde_json::Value::Object(obj) => (0..col_count)
+                .map(|col| {
+                    self.grid.get_column_config(col)
+                        .and_then(|c| obj.get(&c.internal_name))
+                        .map(|v| self.json_to_cell_value(v, col))
+                        .unwrap_or(CellValue::Empty)
+                })
+                .collect(),
+            _ => return Err(GridError::OperationError { message: locale::text("error.row_values") }.into()),
+        };
+
+        let index = self.grid.sorted_insert_position(&values);
+        self.grid.insert_row(index);
+        self.animate_row_extent(index, -self.grid.row_extent(index));
+
+        for (col, value) in values.into_iter().enumerate() {
+            if !matches!(value, CellValue::Empty) {
+                self.grid.set_value(index, col, value);
+            }
+        }
+
+        // Record action for undo (undoing InsertRow deletes the row, redo restores its cells)
+        let action = EditAction::InsertRow {
+            index,
+            row_id: self.grid.row_id(index),
+            cells: self.grid.get_row_cells(index),
+        };
+        self.undo_redo.record_action(action);
+
+        self.clear_selection();
+        self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
+
+        Ok(index)
+    }
+
+    /// Delete multiple rows at once
+    /// @param indices - JSON array of row indices to delete, e.g., "[0, 2, 5]"
+    pub fn delete_rows(&mut self, indices_json: String) -> Result<(), JsValue> {
+        self.check_flat()?;
+        // Parse indices
+        let indices: Vec<usize> = serde_json::from_str(&indices_json)
+            .map_err(|e| GridError::InvalidJson { error: format!("indices: {}", e) })?;
+
+        if indices.is_empty() {
+            return Ok(());
+        }
+
+        // Sort indices in descending order to delete from bottom to top
+        // This prevents index shifting issues
+        let mut sorted_indices = indices.clone();
+        sorted_indices.sort_unstable();
+        sorted_indices.reverse();
+
+        // Save all rows for undo
+        let mut deleted_rows = Vec::new();
+        for &index in &sorted_indices {
+            if index < self.grid.row_count() {
+                let cells = self.grid.get_row_cells(index);
+                deleted_rows.push((index, self.grid.row_id(index), cells));
+            }
+        }
+
+        // Record undo action
+        let action = EditAction::DeleteRows {
+            rows: deleted_rows.clone(),
+        };
+        self.undo_redo.record_action(action);
+
+        let mut removed: Vec<(usize, f32)> = sorted_indices
+            .iter()
+            .filter(|&&index| index < self.grid.row_count())
+            .map(|&index| (index, self.grid.row_extent(index)))
+            .collect();
+        removed.dedup_by_key(|(index, _)| *index);
+
+        // Delete rows from bottom to top
+        for &index in &sorted_indices {
+            if index < self.grid.row_count() {
+                self.grid.delete_row(index);
+            }
+        }
+
+        // Each gap closes below the row now above it (earlier deletes shift the rows up)
+        let gaps: Vec<(usize, f32)> = removed
+            .iter()
+            .rev()
+            .enumerate()
+            .filter(|&(deleted_above, &(index, _))| index > deleted_above)
+            .map(|(deleted_above, &(index, extent))| (index - deleted_above - 1, extent))
+            .collect();
+        self.animate_row_extents(&gaps);
+
+        self.clear_selection();
+        self.viewport.update_visible_range(&self.grid);
+
+        Ok(())
+    }
+
+    /// Get unique row indices from selected cells
+    /// Returns JSON array of row indices, e.g., "[0, 2, 5]"
+    pub fn get_selected_row_indices(&self) -> String {
+        let mut rows: Vec<usize> = self.selection.selected_cells
+            .iter()
+            .map(|(row, _)| *row)
+            .collect();
+
+        // Remove duplicates and sort
+        rows.sort_unstable();
+        rows.dedup();
+
+        serde_json::to_string(&rows).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    /// Set the number shown in the first row header (default 1; 0 makes the labels match
+    /// the 0-based indices used by the API)
+    pub fn set_row_label_start(&mut self, start: usize) {
+        self.grid.row_label_start = start;
+        self.needs_full_render = true;
+    }
+
+    /// Get the number shown in the first row header
+    pub fn get_row_label_start(&self) -> usize {
+        self.grid.row_label_start
+    }
+
+    /// Get the label shown in a row's header (empty for rows outside the grid)
+    pub fn get_row_label(&self, row: usize) -> String {
+        if row >= self.grid.row_count() {
+            return String::new();
+        }
+        self.grid.row_label(row)
+    }
+
+    /// Convert a row header label (what the user sees, e.g. "12") to the 0-based row index
+    /// used by the API; undefined if the label does not name a row
+    pub fn row_index_from_label(&self, label: &str) -> Option<usize> {
+        self.grid.row_index_from_label(label)
+    }
+
+    /// Get the label shown in a column's header (its display name)
+    pub fn get_column_label(&self, col: usize) -> String {
+        self.grid.get_column_config(col).map(|c| c.display_name.clone()).unwrap_or_default()
+    }
+
+    /// Convert a column label to the 0-based column index used by the API
+    /// Matches the display name, then the internal name (both case-insensitive), then a
+    /// spreadsheet letter ("A", "AB"); undefined if nothing matches
+    pub fn column_index_from_label(&self, label: &str) -> Option<usize> {
+        let label = label.trim();
+        let col_count = self.grid.col_count();
+        let find = |name: fn(&ColumnConfig) -> &str| {
+            (0..col_count).find(|&col| self.grid.get_column_config(col).is_some_and(|c| name(c).eq_ignore_ascii_case(label)))
+        };
+        find(|c| &c.display_name)
+            .or_else(|| find(|c| &c.internal_name))
+            .or_else(|| Grid::column_index_from_letter(label).filter(|&col| col < col_count))
+    }
+
+    /// Get the stable IDs of rows with selected cells, in row order
+    /// Returns JSON array of row IDs, e.g., "[12, 3, 40]"
+    pub fn get_selected_row_ids(&self) -> String {
+        let mut rows: Vec<usize> = self.selection.selected_cells.iter().map(|(row, _)| *row).collect();
+        rows.sort_unstable();
+        rows.dedup();
+
+        let ids: Vec<usize> = rows.into_iter().filter_map(|row| self.grid.row_id(row)).collect();
+        serde_json::to_string(&ids).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    /// Get the stable ID of a row
+    /// IDs are assigned when rows are created and stay with the row through sorting,
+    /// filtering and inserting or deleting other rows; a deleted row's ID is not reused
+    /// (undo restores it). Returns undefined for rows outside the grid.
+    pub fn get_row_id(&self, row_index: usize) -> Option<usize> {
+        self.grid.row_id(row_index)
+    }
+
+    /// Get the current index of the row with a stable ID (undefined if the row was deleted)
+    pub fn get_row_index_by_id(&self, id: usize) -> Option<usize> {
+        self.grid.row_index_by_id(id)
+    }
+
+    /// Insert a column at the specified position
+    pub fn insert_column(&mut self, at_index: usize) {
+        if self.pivot.is_some() {
+            return;
+        }
+        // Record action for undo (insert is opposite of delete, so we store as DeleteColumn)
+        let action = EditAction::DeleteColumn {
+            index: at_index,
+            cells: Vec::new(), // Empty column being inserted
+        };
+        self.undo_redo.record_action(action);
+
+        self.grid.insert_column(at_index);
+        self.clear_selection();
+        self.viewport.update_visible_range(&self.grid);
+    }
+
+    /// Delete a column at the specified position
+    pub fn delete_column(&mut self, index: usize) {
+        if self.pivot.is_some() {
+            return;
+        }
+        // Save cells before deletion for undo
+        let cells = self.grid.get_column_cells(index);
+
+        let action = EditAction::InsertColumn {
+            index,
+            cells,
+        };
+        self.undo_redo.record_action(action);
+
+        self.grid.delete_column(index);
+        self.clear_selection();
+        self.viewport.update_visible_range(&self.grid);
+    }
+
+    /// Delete all empty rows (rows with no non-empty cells)
+    pub fn delete_empty_rows(&mut self) -> usize {
+        if self.pivot.is_some() {
+            return 0;
+        }
+        let mut rows_to_delete = Vec::new();
+
+        // Find all empty rows
+        for row in 0..self.grid.row_count() {
+            let mut is_empty = true;
+            for col in 0..self.grid.col_count() {
+                if !self.grid.get_value(row, col).is_empty() {
+                    is_empty = false;
+                    break;
+                }
+            }
+            if is_empty {
+                rows_to_delete.push(row);
+            }
+        }
+
+        // Delete rows from bottom to top to maintain indices (undone as one step)
+        let count = rows_to_delete.len();
+        self.undo_redo.begin_group();
+        for row in rows_to_delete.iter().rev() {
+            self.delete_row(*row);
+        }
+        self.undo_redo.end_group();
+
+        self.clear_selection();
+        self.viewport.update_visible_range(&self.grid);
+        count
+    }
+
+    /// Check if a row is empty (all cells are empty)
+    pub fn is_row_empty(&self, row: usize) -> bool {
+        for col in 0..self.grid.col_count() {
+            if !self.grid.get_value(row, col).is_empty() {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Find all modified (edited) cells
+    pub fn find_modified_cells(&mut self) -> usize {
+        self.search.find_modified_cells(&self.grid);
+
+        if !self.search.search_results.is_empty() {
+            let (row, col) = self.search.search_results[0];
+            self.select_single_cell(row, col);
+            self.ensure_cell_visible(row, col);
+        }
+
+        self.search.search_results.len()
+    }
+
+    /// Highlight rows that repeat an earlier row's values in the key columns, as search
+    /// results (search_next/search_prev step through them; clear_search removes them)
+    /// @param key_cols_json - JSON array of column indices or internal names, e.g.
+    ///   '["email"]' or "[0, 2]"; "[]" compares whole rows
+    /// Every row of a duplicate group is marked, the first occurrence included; rows whose
+    /// key cells are all empty are ignored. Returns the number of rows highlighted.
+    pub fn highlight_duplicate_rows(&mut self, key_cols_json: &str) -> Result<usize, JsValue> {
+        let key_cols = self.parse_key_columns(key_cols_json)?;
+        let rows = self.search.find_duplicate_rows(&self.grid, &key_cols);
+
+        if !self.search.search_results.is_empty() {
+            let (row, col) = self.search.search_results[0];
+            self.select_single_cell(row, col);
+            self.ensure_cell_visible(row, col);
+        }
+        self.needs_full_render = true;
+        Ok(rows)
+    }
+
+    /// Delete every row that repeats an earlier row's values in the key columns, keeping
+    /// the first occurrence, as one undoable action (same key_cols_json as
+    /// highlight_duplicate_rows). Returns the number of rows deleted.
+    pub fn remove_duplicate_rows(&mut self, key_cols_json: &str) -> Result<usize, JsValue> {
+        self.check_flat()?;
+        let key_cols = self.parse_key_columns(key_cols_json)?;
+        let rows: Vec<usize> = search::duplicate_row_groups(&self.grid, &key_cols)
+            .into_iter()
+            .flat_map(|group| group.into_iter().skip(1))
+            .collect();
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        self.delete_rows(serde_json::json!(rows).to_string())?;
+        if self.search.search_kind == Some(SearchKind::Duplicates) {
+            self.search.rerun(&self.grid);
+        }
+        self.needs_full_render = true;
+        Ok(rows.len())
+    }
+
+    /// Helper: Columns of a JSON array of indices or internal names ("[]" = every column)
+    fn parse_key_columns(&self, key_cols_json: &str) -> Result<Vec<usize>, GridError> {
+        let keys: Vec<serde_json::Value> = serde_json::from_str(key_cols_json)
+            .map_err(|e| GridError::InvalidJson { error: format!("key columns: {}", e) })?;
+        if keys.is_empty() {
+            return Ok((0..self.grid.col_count()).collect());
+        }
+        keys.iter()
+            .map(|key| {
+                let col = match key {
+                    serde_json::Value::Number(n) => n.as_u64().map(|c| c as usize).filter(|&c| c < self.grid.col_count()),
+                    serde_json::Value::String(name) => self.grid.get_column_by_name(name),
+                    _ => None,
+                };
+                col.ok_or_else(|| GridError::OperationError { message: locale::format("error.key_column", &[("key", &key)]) })
+            })
+            .collect()
+    }
+
+    /// Clear modified flags from all cells (same as mark_saved)
+    pub fn clear_all_modified_flags(&mut self) {
+        self.grid.clear_modified();
+    }
+
+    /// Export edited cells as a JSON changeset for saving back to a server
+    /// Returns: [{"row","row_id","col","column_internal_name","old_value","new_value"}, ...] in row-major order,
+    /// where old_value is the value before the first edit since load (or the last mark_saved()).
+    /// Cells edited back to their original value are left out.
+    pub fn export_changes_json(&self) -> String {
+        let mut changes: Vec<((usize, usize), &Cell)> = self.grid
+            .iter_cells()
+            .filter(|(_, cell)| cell.modified && cell.original.as_ref() != Some(&cell.value))
+            .collect();
+        changes.sort_by_key(|(pos, _)| *pos);
+
+        let changes: Vec<serde_json::Value> = changes
+            .into_iter()
+            .map(|((row, col), cell)| {
+                serde_json::json!({
+                    "row": row,
+                    "row_id": self.grid.row_id(row),
+                    "col": col,
+                    "column_internal_name": self.grid.get_column_config(col).map(|c| c.internal_name.as_str()),
+                    "old_value": cell.original.as_ref().map(CellValue::to_json),
+                    "new_value": cell.value.to_json(),
+                })
+            })
+            .collect();
+        serde_json::Value::Array(changes).to_string()
+    }
+
+    /// Accept all edits as saved: clears modified flags and makes current values the
+    /// baseline for export_changes_json()
+    pub fn mark_saved(&mut self) {
+        self.grid.clear_modified();
+        self.needs_full_render = true;
+    }
+
+    /// Serialize the full grid state to a JSON string: values, cell styles and borders,
+    /// column configs, widths/heights, frozen panes, header groups, sort and filter state
+    pub fn serialize_state(&self) -> String {
+        features::snapshot::capture_state(self.data_grid()).to_string()
+    }
+
+    /// Replace the grid with a state produced by serialize_state()
+    /// Undo history and selection are cleared, since they refer to the replaced data
+    pub fn deserialize_state(&mut self, state_json: &str) -> Result<(), JsValue> {
+        self.check_flat()?;
+        let state: serde_json::Value = serde_json::from_str(state_json)
+            .map_err(|e| GridError::InvalidJson { error: format!("grid state: {}", e) })?;
+        features::snapshot::restore_state(&mut self.grid, &state)?;
+        self.undo_redo.clear_undo_history();
+        self.undo_redo.clear_redo_history();
+        self.selection.clear_selection();
+        self.reapply_derived_state();
+        Ok(())
+    }
+
+    /// Get the user's layout as JSON: column order, widths, flex weights and visibility
+    /// keyed by internal_name, row heights keyed by row ID, frozen panes and sort keys.
+    /// Unlike serialize_state it holds no data, so it can be saved as a preference and
+    /// applied to later sessions.
+    pub fn get_layout_json(&self) -> String {
+        Layout::capture(&self.grid).to_json().to_string()
+    }
+
+    /// Apply a layout from get_layout_json() (undone as one step)
+    /// Columns are matched by internal_name: saved columns missing from the grid are
+    /// ignored, and columns the layout does not know keep their position, width and
+    /// visibility. The saved sort is re-applied to the current rows, then the row heights.
+    /// Flex settings are applied directly and are not part of the undo step.
+    pub fn apply_layout_json(&mut self, layout_json: &str) -> Result<(), JsValue> {
+        self.check_flat()?;
+        let value: serde_json::Value = serde_json::from_str(layout_json)
+            .map_err(|e| GridError::InvalidJson { error: format!("layout: {}", e) })?;
+        let layout = Layout::from_json(&value)?;
+
+        self.undo_redo.begin_group();
+        // `current` tracks which original column sits at each position while moving
+        let mut current: Vec<usize> = (0..self.grid.col_count()).collect();
+        for (to, col) in layout.column_order(&self.grid).into_iter().enumerate() {
+            let from = current.iter().position(|&c| c == col).unwrap_or(to);
+            self.move_column(from, to);
+            let moved = current.remove(from);
+            current.insert(to, moved);
+        }
+        for col in 0..self.grid.col_count() {
+            if let Some(saved) = layout.column(&self.grid, col).cloned() {
+                let config = &mut self.grid.column_configs[col];
+                match saved.flex {
+                    Some(weight) => (config.width_mode, config.flex) = (WidthMode::Flex, weight),
+                    None => {
+                        config.width_mode = WidthMode::Fixed;
+                        self.set_col_width_with_undo(col, saved.width);
+                    }
+                }
+                self.set_column_visible_with_undo(col, saved.visible)?;
+            }
+        }
+        self.set_frozen_with_undo(layout.frozen_rows.min(self.grid.row_count()), layout.frozen_cols.min(self.grid.col_count()));
+
+        let keys = layout.sort_keys(&self.grid);
+        if keys != self.grid.active_sort_keys() {
+            let detail = serde_json::json!({ "action": "layout", "keys": keys });
+            let sorted = self.run_sort(detail, |grid| match keys.as_slice() {
+                [] => {
+                    grid.clear_multi_column_sort();
+                    None
+                }
+                [(col, ascending)] => grid.sort_by_column(*col, *ascending),
+                _ => {
+                    (grid.sort_column, grid.sort_ascending) = (Some(keys[0].0), keys[0].1);
+                    grid.sort_columns = keys.clone();
+                    grid.sort_by_multiple_columns()
+                }
+            });
+            if sorted {
+                self.clear_selection();
+            }
+        }
+
+        // Heights are keyed by row ID, so they land on the right rows after the sort
+        let default_height = self.grid.default_row_height();
+        for row in 0..self.grid.row_count() {
+            let height = layout.row_height(&self.grid, row).unwrap_or(default_height);
+            if self.grid.base_row_height(row) != height {
+                self.set_row_height_with_undo(row, height);
+            }
+        }
+        self.undo_redo.end_group();
+
+        self.after_column_layout_change();
+        Ok(())
+    }
+
+    /// Re-run the active column filters and the last search against the current data,
+    /// so filtered rows and search highlights match it (done automatically after
+    /// deserialize_state and restore_persisted). The current search match is kept if
+    /// it still matches. Without active column filters the restored filtered rows stay.
+    /// Returns: {"visible_rows": N, "search_results": N}
+    pub fn reapply_derived_state(&mut self) -> String {
+        if !self.column_filters.is_empty() {
+            self.apply_active_filters();
+        }
+        self.filters_revision = self.grid.revision();
+        self.filters_stale = false;
+        let search_results = self.search.rerun(&self.grid);
+        self.summary_cache = SummaryCache::new();
+
+        self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
+        serde_json::json!({
+            "visible_rows": self.grid.visible_row_count(),
+            "search_results": search_results,
+        })
+        .to_string()
+    }
+
+    /// Check if a cell is modified
+    pub fn is_cell_modified(&self, row: usize, col: usize) -> bool {
+        if let Some(cell) = self.grid.get_cell(row, col) {
+            cell.modified
+        } else {
+            false
+        }
+    }
+
+    /// Get count of modified cells
+    pub fn get_modified_cell_count(&self) -> usize {
+        let mut count = 0;
+        for row in 0..self.grid.row_count() {
+            for col in 0..self.grid.col_count() {
+                if let Some(cell) = self.grid.get_cell(row, col) {
+                    if cell.modified {
+                        count += 1;
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    /// Search for text in grid cells (case-insensitive by default)
+    pub fn search_text(&mut self, query: String) -> usize {
+        self.search_text_with_options(query, false, false)
+    }
+
+    /// Search for text with options
+    pub fn search_text_with_options(&mut self, query: String, case_sensitive: bool, whole_word: bool) -> usize {
+        self.search.search_case_sensitive = case_sensitive;
+        self.search.search_whole_word = whole_word;
+        self.search.search_kind = Some(SearchKind::Text);
+        self.search.search_query = if case_sensitive { query.clone() } else { query.to_lowercase() };
+        self.search.search_results.clear();
+        self.search.current_search_index = None;
+
+        if query.is_empty() {
+            return 0;
+        }
+
+        // Search through all cells
+        for row in 0..self.grid.row_count() {
+            for col in 0..self.grid.col_count() {
+                let cell_text = self.grid.get_value_string(row, col);
+                let search_text = if case_sensitive { cell_text.clone() } else { cell_text.to_lowercase() };
+
+                let is_match = if whole_word {
+                    // Whole word matching: split by whitespace and check for exact match
+                    search_text.split_whitespace().any(|word| word == self.search.search_query)
+                } else {
+                    // Substring matching
+                    search_text.contains(&self.search.search_query)
+                };
+
+                if is_match {
+                    self.search.search_results.push((row, col));
+                }
+            }
+        }
+
+        if !self.search.search_results.is_empty() {
+            self.search.current_search_index = Some(0);
+            let (row, col) = self.search.search_results[0];
+            self.select_single_cell(row, col);
+            self.ensure_cell_visible(row, col);
+        }
+
+        self.search.search_results.len()
+    }
+
+    /// Move to next search result
+    pub fn search_next(&mut self) -> bool {
+        if self.search.search_results.is_empty() {
+            return false;
+        }
+
+        if let Some(current_idx) = self.search.current_search_index {
+            let next_idx = (current_idx + 1) % self.search.search_results.len();
+            self.search.current_search_index = Some(next_idx);
+
+            let (row, col) = self.search.search_results[next_idx];
+            self.select_single_cell(row, col);
+            self.ensure_cell_visible(row, col);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Move to previous search result
+    pub fn search_prev(&mut self) -> bool {
+        if self.search.search_results.is_empty() {
+            return false;
+        }
+
+        if let Some(current_idx) = self.search.current_search_index {
+            let prev_idx = if current_idx == 0 {
+                self.search.search_results.len() - 1
+            } else {
+                current_idx - 1
+            };
+            self.search.current_search_index = Some(prev_idx);
+
+            let (row, col) = self.search.search_results[prev_idx];
+            self.select_single_cell(row, col);
+            self.ensure_cell_visible(row, col);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Search using regular expression
+    pub fn search_regex(&mut self, pattern: String, case_sensitive: bool) -> Result<usize, GridError> {
+        use regex::RegexBuilder;
+
+        // Build regex with case sensitivity option
+        let regex = match RegexBuilder::new(&pattern)
+            .case_insensitive(!case_sensitive)
+            .build()
+        {
+            Ok(re) => re,
+            Err(e) => {
+                return Err(GridError::InvalidRegex {
+                    pattern: pattern.clone(),
+                    error: e.to_string(),
+                })
+            }
+        };
+
+        self.search.search_query = pattern;
+        self.search.search_case_sensitive = case_sensitive;
+        self.search.search_whole_word = false; // Not applicable for regex
+        self.search.search_kind = Some(SearchKind::Regex);
+        self.search.search_results.clear();
+        self.search.current_search_index = None;
+
+        // Search through all cells
+        for row in 0..self.grid.row_count() {
+            for col in 0..self.grid.col_count() {
+                let cell_text = self.grid.get_value_string(row, col);
+
+                if regex.is_match(&cell_text) {
+                    self.search.search_results.push((row, col));
+                }
+            }
+        }
+
+        if !self.search.search_results.is_empty() {
+            self.search.current_search_index = Some(0);
+            let (row, col) = self.search.search_results[0];
+            self.select_single_cell(row, col);
+            self.ensure_cell_visible(row, col);
+        }
+
+        Ok(self.search.search_results.len())
+    }
+
+    /// Validate regex pattern without performing search
+    pub fn validate_regex_pattern(&self, pattern: String) -> bool {
+        use regex::Regex;
+        Regex::new(&pattern).is_ok()
+    }
+
+    /// Clear search results
+    pub fn clear_search(&mut self) {
+        self.search.clear_search();
+    }
+
+    /// Get search result count
+    pub fn get_search_result_count(&self) -> usize {
+        self.search.search_results.len()
+    }
+
+    /// Get current search index (1-based for display)
+    pub fn get_current_search_index(&self) -> i32 {
+        if let Some(idx) = self.search.current_search_index {
+            (idx + 1) as i32
+        } else {
+            -1
+        }
+    }
+
+    /// Check if a cell is a search result
+    pub fn is_search_result(&self, row: usize, col: usize) -> bool {
+        self.search.search_results.contains(&(row, col))
+    }
+
+    /// Check if a cell is the current (active) search result
+    pub fn is_current_search_result(&self, row: usize, col: usize) -> bool {
+        if let Some(idx) = self.search.current_search_index {
+            if idx < self.search.search_results.len() {
+                self.search.search_results[idx] == (row, col)
+            } else {
+                false
+            }
+        } else {
+            false
+        }
+    }
+
+    /// Replace current search result with new text
+    pub fn replace_current(&mut self, replacement: String) -> bool {
+        if let Some(idx) = self.search.current_search_index {
+            if idx < self.search.search_results.len() {
+                let (row, col) = self.search.search_results[idx];
+
+                // Parse replacement as number if possible
+                if let Ok(num) = replacement.parse::<f64>() {
+                    self.grid.set_value(row, col, CellValue::Number(num));
+                } else {
+                    self.grid.set_value(row, col, CellValue::Text(replacement));
+                }
+
+                // Move to next search result (or wrap around)
+                self.search_next();
+                true
+            } else {
+                false
+            }
+        } else {
+            false
+        }
+    }
+
+    /// Replace all search results with new text
+    pub fn replace_all(&mut self, replacement: String) -> usize {
+        let count = self.search.search_results.len();
+
+        // Replace all matching cells
+        for (row, col) in &self.search.search_results {
+            // Parse replacement as number if possible
+            if let Ok(num) = replacement.parse::<f64>() {
+                self.grid.set_value(*row, *col, CellValue::Number(num));
+            } else {
+                self.grid.set_value(*row, *col, CellValue::Text(replacement.clone()));
+            }
+        }
+
+        // Clear search results after replacing all
+        self.clear_search();
+        count
+    }
+
+    /// Replace in selection only
+    pub fn replace_in_selection(&mut self, search: String, replacement: String, case_sensitive: bool) -> usize {
+        let mut count = 0;
+        let search_str = if case_sensitive { search.clone() } else { search.to_lowercase() };
+
+        // Get list of selected cells
+        let selected: Vec<(usize, usize)> = self.selection.selected_cells.iter().cloned().collect();
+
+        for (row, col) in selected {
+            let cell_text = self.grid.get_value_string(row, col);
+            let search_text = if case_sensitive { cell_text.clone() } else { cell_text.to_lowercase() };
+
+            if search_text.contains(&search_str) {
+                // Parse replacement as number if possible
+                if let Ok(num) = replacement.parse::<f64>() {
+                    self.grid.set_value(row, col, CellValue::Number(num));
+                } else {
+                    self.grid.set_value(row, col, CellValue::Text(replacement.clone()));
+                }
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Sort by column (undoable)
+    pub fn sort_by_column(&mut self, col: usize, ascending: bool) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        self.sort_column(col, ascending);
+        Ok(())
+    }
+
+    /// Toggle sort on column (click column header)
+    pub fn toggle_column_sort(&mut self, col: usize) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        self.toggle_sort(col);
+        Ok(())
+    }
+
+    /// Helper: Sort by an existing column
+    fn sort_column(&mut self, col: usize, ascending: bool) {
+        let detail = serde_json::json!({ "action": "sort", "col": col, "ascending": ascending });
+        if self.run_sort(detail, |grid| grid.sort_by_column(col, ascending)) {
+            self.clear_selection();
+        }
+    }
+
+    /// Helper: Toggle the sort of an existing column
+    fn toggle_sort(&mut self, col: usize) {
+        // If already sorting by this column, toggle direction; otherwise sort ascending
+        if self.grid.sort_column == Some(col) {
+            self.sort_column(col, !self.grid.sort_ascending);
+        } else {
+            self.sort_column(col, true);
+        }
+    }
+
+    /// Set what a column header click does: "header" (anywhere sorts; default), "arrow"
+    /// (only the arrow zone at the header's right edge sorts, the rest selects the column)
+    /// or "none" (clicks select the column; sort through the API)
+    pub fn set_sort_click_policy(&mut self, policy: &str) -> Result<(), JsValue> {
+        self.grid.sort_click = SortClick::parse(policy)?;
+        self.needs_full_render = true;
+        Ok(())
+    }
+
+    /// Get the sort click policy ("header", "arrow" or "none")
+    pub fn get_sort_click_policy(&self) -> String {
+        self.grid.sort_click.as_str().to_string()
+    }
+
+    /// Set the width of the sort arrow zone used by the "arrow" policy (default: 20)
+    pub fn set_sort_arrow_zone_width(&mut self, width: f32) {
+        self.grid.sort_zone_width = width.max(0.0);
+        self.needs_full_render = true;
+    }
+
+    /// Show or hide a funnel button in the headers of filterable columns (default: off)
+    /// The button is highlighted while the column has a filter. Clicking it queues a
+    /// {"type": "filter_button", "col", "rect"} event instead of sorting or selecting, so
+    /// the host can open its filter UI under the button.
+    pub fn set_filter_buttons(&mut self, enabled: bool) {
+        self.grid.show_filter_buttons = enabled;
+        self.needs_full_render = true;
+    }
+
+    /// Check if filter buttons are shown
+    pub fn has_filter_buttons(&self) -> bool {
+        self.grid.show_filter_buttons
+    }
+
+    /// Hit-test a filter button at canvas coordinates
+    /// Returns {"type": "filter_button", "col": N, "rect": [x, y, width, height]} (canvas
+    /// pixels) or an empty string when (x, y) is not on a filter button
+    pub fn get_filter_button_at(&self, x: f32, y: f32) -> String {
+        let (x, y) = self.viewport.to_grid_pixels(x, y);
+        self.viewport
+            .canvas_to_filter_button(x, y, &self.grid)
+            .map(|col| self.filter_button_info(col).to_string())
+            .unwrap_or_default()
+    }
+
+    /// Helper: Filter button hit info of a column (see get_filter_button_at)
+    fn filter_button_info(&self, col: usize) -> serde_json::Value {
+        let zoom = self.viewport.zoom;
+        let rect = self.viewport.filter_button_rect(col, &self.grid).map(|(x, y, w, h)| [x * zoom, y * zoom, w * zoom, h * zoom]);
+        serde_json::json!({ "type": "filter_button", "col": col, "rect": rect })
+    }
+
+    /// Helper: Sort or select the column of a header click at canvas point `at`
+    /// Shift/Ctrl clicks never sort: they extend or toggle a multi-column selection.
+    /// A click on the column's filter button only queues a filter_button event.
+    fn click_column_header(&mut self, col: usize, at: (f32, f32), shift: bool, ctrl: bool) {
+        let (canvas_x, canvas_y) = at;
+        if self.viewport.canvas_to_filter_button(canvas_x, canvas_y, &self.grid) == Some(col) {
+            let info = self.filter_button_info(col);
+            self.events.push("filter_button", info);
+            return;
+        }
+        let (rows, cols) = (self.grid.row_count(), self.grid.col_count());
+        if shift {
+            self.selection.select_col_range(col, rows, cols);
+            return;
+        }
+        if ctrl {
+            self.selection.toggle_col(col, rows, cols);
+            return;
+        }
+        let sorts = match self.grid.sort_click {
+            SortClick::Header => true,
+            SortClick::Arrow => self.viewport.in_sort_zone(canvas_x, col, &self.grid),
+            SortClick::Off => false,
+        };
+        if sorts {
+            self.toggle_sort(col);
+        } else {
+            self.selection.select_col(col, rows, cols);
+        }
+    }
+
+    /// Get sort state for a column as JSON object
+    /// Returns: "{\"is_sorted\": true/false, \"is_ascending\": true/false}"
+    pub fn get_column_sort_state(&self, col: usize) -> String {
+        let (is_sorted, is_ascending) = if self.grid.sort_column == Some(col) {
+            (true, self.grid.sort_ascending)
+        } else {
+            (false, true)
+        };
+        format!(r#"{{"is_sorted":{},"is_ascending":{}}}"#, is_sorted, is_ascending)
+    }
+
+    /// Add column to multi-column sort (for Shift+Click)
+    pub fn add_multi_column_sort(&mut self, col: usize, ascending: bool) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        let detail = serde_json::json!({ "action": "add", "col": col, "ascending": ascending });
+        if self.run_sort(detail, |grid| grid.add_sort_column(col, ascending)) {
+            self.clear_selection();
+        }
+        Ok(())
+    }
+
+    /// Toggle column in multi-column sort
+    pub fn toggle_multi_column_sort(&mut self, col: usize) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        // Check if column is already in sort list
+        let existing = self.grid.sort_columns.iter()
+            .find(|(c, _)| *c == col)
+            .map(|(_, asc)| *asc);
+
+        match existing {
+            Some(true) => {
+                // Currently ascending, switch to descending
+                self.add_multi_column_sort(col, false)?;
+            }
+            Some(false) => {
+                // Currently descending, remove from sort
+                let detail = serde_json::json!({ "action": "remove", "col": col });
+                self.run_sort(detail, |grid| {
+                    grid.sort_columns.retain(|(c, _)| *c != col);
+                    if grid.sort_columns.is_empty() {
+                        grid.sort_column = None;
+                        None
+                    } else {
+                        grid.sort_by_multiple_columns()
+                    }
+                });
+            }
+            None => {
+                // Not in sort list, add as ascending
+                self.add_multi_column_sort(col, true)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Clear multi-column sort
+    pub fn clear_multi_column_sort(&mut self) {
+        let detail = serde_json::json!({ "action": "clear" });
+        self.run_sort(detail, |grid| {
+            grid.clear_multi_column_sort();
+            None
+        });
+    }
+
+    /// Helper: Run a sort through the before_sort/after_sort hooks and record it for undo
+    /// `sort` applies the sort and returns the new row order (None if rows did not move).
+    /// Returns false if a before_sort hook cancelled it. When the hook returns "handled",
+    /// the sort state (header indicators) is updated but rows stay in place (the host
+    /// sorts, e.g. on a server).
+    fn run_sort(&mut self, mut detail: serde_json::Value, sort: impl FnOnce(&mut Grid) -> Option<Vec<usize>>) -> bool {
+        detail["sort"] = self.grid.sort_state().to_json();
+        let decision = self.hooks.call("before_sort", &detail);
+        if decision == HookDecision::Cancel {
+            return false;
+        }
+
+        let start = hooks::now_ms();
+        let old_sort = self.grid.sort_state();
+        let mut order = sort(&mut self.grid);
+        if decision == HookDecision::Handled {
+            if let Some(order) = order.take() {
+                self.grid.unpermute_rows(&order);
+            }
+        }
+        self.record_sort(order, old_sort);
+        self.viewport.update_visible_range(&self.grid);
+
+        detail["sort"] = self.grid.sort_state().to_json();
+        detail["handled"] = serde_json::json!(decision == HookDecision::Handled);
+        detail["duration_ms"] = serde_json::json!(hooks::now_ms() - start);
+        self.hooks.call("after_sort", &detail);
+        self.events.push("after_sort", detail);
+        true
+    }
+
+    /// Register a synchronous hook around sorting and filtering
+    /// Names: "before_sort", "after_sort", "before_filter", "after_filter"; pass null to remove
+    /// ("before_edit" and "before_change" are also accepted, see on_before_edit/on_before_change).
+    /// Hooks get the operation as an object ({action, col, ...}); a before_* hook returns
+    /// false to cancel the operation or "handled" when the host performs it (e.g. server-side
+    /// sorting in virtual data mode), in which case only the sort/filter state is recorded.
+    /// after_* hooks also get "handled" and "duration_ms"; the same detail is queued as an
+    /// after_sort/after_filter event.
+    pub fn set_operation_hook(&mut self, name: &str, callback: Option<js_sys::Function>) -> Result<(), JsValue> {
+        self.hooks.set(name, callback)?;
+        Ok(())
+    }
+
+    /// Register a callback `(row, col) => boolean` asked before a cell starts editing
+    /// (double-click/tap, F2, type-to-edit, start_edit); returning false vetoes the edit.
+    /// Only asked for cells that are editable otherwise and only when an edit starts
+    /// (unlike set_editable_callback, which also locks cells for Delete, paste and display).
+    /// Pass null to remove.
+    pub fn on_before_edit(&mut self, callback: Option<js_sys::Function>) {
+        let _ = self.hooks.set("before_edit", callback);
+    }
+
+    /// Register a callback `(row, col, new_value) => boolean` asked before an edit is
+    /// committed (update_cell_value, the in-grid editor), a cell is cleared with Delete
+    /// (new_value "") or written by paste, templates, transpose, range moves and cell
+    /// shifts; returning false vetoes the change. Pass null to remove.
+    pub fn on_before_change(&mut self, callback: Option<js_sys::Function>) {
+        let _ = self.hooks.set("before_change", callback);
+    }
+
+    /// Helper: Ask the before_edit hook whether (row, col) may start editing
+    /// (not asked for cells the grid refuses anyway)
+    fn allows_edit(&self, row: usize, col: usize) -> bool {
+        !self.grid.is_cell_editable(row, col)
+            || self.hooks.allows("before_edit", &[JsValue::from(row as u32), JsValue::from(col as u32)])
+    }
+
+    /// Helper: Ask the before_change hook whether (row, col) may take `new_value`
+    fn allows_change(&self, row: usize, col: usize, new_value: &str) -> bool {
+        self.hooks.allows_change(row, col, new_value)
+    }
+
+    /// Helper: Ask the before_change hook about every cell an all-or-nothing operation
+    /// writes (None empties the cell); errors on the first veto
+    fn check_changes_allowed<'a>(&self, writes: impl IntoIterator<Item = (usize, usize, Option<&'a Cell>)>) -> Result<(), GridError> {
+        for (row, col, cell) in writes {
+            let new_value = cell.map(|cell| cell.value.to_string()).unwrap_or_default();
+            if !self.allows_change(row, col, &new_value) {
+                return Err(locale::format("error.change_rejected", &[("row", &row), ("col", &col)]).into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Helper: Record a sort for undo (`order` is the applied row order, if rows moved)
+    fn record_sort(&mut self, order: Option<Vec<usize>>, old_sort: SortState) {
+        let new_sort = self.grid.sort_state();
+        if order.is_none() && old_sort == new_sort {
+            return;
+        }
+        self.undo_redo.record_action(EditAction::SortRows {
+            order: order.unwrap_or_default(),
+            old_sort,
+            new_sort,
+        });
+    }
+
+    /// Get multi-column sort state as JSON array of [col, ascending] pairs
+    /// Returns: "[[col1, 1], [col2, 0], ...]" where 1=ascending, 0=descending
+    pub fn get_multi_column_sort_state(&self) -> String {
+        let state: Vec<Vec<u32>> = self.grid.sort_columns.iter()
+            .map(|(col, asc)| vec![*col as u32, if *asc { 1 } else { 0 }])
+            .collect();
+        serde_json::to_string(&state).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    /// Check if a column is in multi-column sort
+    /// Returns JSON: "{\"is_sorted\": bool, \"is_ascending\": bool, \"sort_priority\": number}"
+    pub fn get_column_multi_sort_state(&self, col: usize) -> String {
+        if let Some((priority, (_, ascending))) = self.grid.sort_columns.iter()
+            .enumerate()
+            .find(|(_, (c, _))| *c == col)
+        {
+            format!(r#"{{"is_sorted":true,"is_ascending":{},"sort_priority":{}}}"#, ascending, priority)
+        } else {
+            r#"{"is_sorted":false,"is_ascending":true,"sort_priority":-1}"#.to_string()
+        }
+    }
+
+    /// Freeze first N rows (undoable)
+    pub fn freeze_rows(&mut self, count: usize) {
+        self.set_frozen_with_undo(count.min(self.grid.row_count()), self.grid.frozen_cols);
+    }
+
+    /// Freeze first N columns (undoable)
+    pub fn freeze_cols(&mut self, count: usize) {
+        self.set_frozen_with_undo(self.grid.frozen_rows, count.min(self.grid.col_count()));
+    }
+
+    /// Get frozen row count
+    pub fn get_frozen_rows(&self) -> usize {
+        self.grid.frozen_rows
+    }
+
+    /// Set frozen row count (undoable)
+    pub fn set_frozen_rows(&mut self, count: usize) {
+        self.set_frozen_with_undo(count, self.grid.frozen_cols);
+    }
+
+    /// Get frozen column count
+    pub fn get_frozen_cols(&self) -> usize {
+        self.grid.frozen_cols
+    }
+
+    /// Set frozen column count (undoable)
+    pub fn set_frozen_cols(&mut self, count: usize) {
+        self.set_frozen_with_undo(self.grid.frozen_rows, count);
+    }
+
+    /// Helper: Change the frozen panes and record the change for undo
+    fn set_frozen_with_undo(&mut self, rows: usize, cols: usize) {
+        let old_frozen = (self.grid.frozen_rows, self.grid.frozen_cols);
+        if old_frozen == (rows, cols) {
+            return;
+        }
+        (self.grid.frozen_rows, self.grid.frozen_cols) = (rows, cols);
+        self.undo_redo.record_action(EditAction::SetFrozen { old_frozen, new_frozen: (rows, cols) });
+    }
+
+    /// Undo last edit action
+    pub fn undo(&mut self) -> bool {
+        log::debug!("[UndoRedo] Undo requested - stack size: {}", self.undo_redo.undo_stack.len());
+        let result = self.undo_redo.undo(&mut self.grid, &mut self.viewport);
+        if result {
+            let cells = self.undo_redo.redo_stack.last().map(EditAction::value_cells).unwrap_or_default();
+            self.revalidate_cells(cells);
+            self.restore_filters(true);
+        }
+        self.needs_full_render = true;
+        log::debug!("[UndoRedo] Undo completed: {}, remaining: {}", result, self.undo_redo.undo_stack.len());
+        result
+    }
+
+    /// Redo last undone action
+    pub fn redo(&mut self) -> bool {
+        log::debug!("[UndoRedo] Redo requested - stack size: {}", self.undo_redo.redo_stack.len());
+        let result = self.undo_redo.redo(&mut self.grid, &mut self.viewport);
+        if result {
+            let cells = self.undo_redo.undo_stack.last().map(EditAction::value_cells).unwrap_or_default();
+            self.revalidate_cells(cells);
+            self.restore_filters(false);
+        }
+        self.needs_full_render = true;
+        log::debug!("[UndoRedo] Redo completed: {}, remaining: {}", result, self.undo_redo.redo_stack.len());
+        result
+    }
+
+    /// Check if undo is available
+    pub fn can_undo(&self) -> bool {
+        !self.undo_redo.is_suspended() && !self.undo_redo.undo_stack.is_empty()
+    }
+
+    /// Check if redo is available
+    pub fn can_redo(&self) -> bool {
+        !self.undo_redo.is_suspended() && !self.undo_redo.redo_stack.is_empty()
+    }
+
+    /// Get undo stack size
+    pub fn get_undo_count(&self) -> usize {
+        self.undo_redo.undo_stack.len()
+    }
+
+    /// Get redo stack size
+    pub fn get_redo_count(&self) -> usize {
+        self.undo_redo.redo_stack.len()
+    }
+
+    /// Clear undo history (useful after initial data load)
+    pub fn clear_undo_history(&mut self) {
+        self.undo_redo.clear_undo_history();
+    }
+
+    /// Clear redo history
+    pub fn clear_redo_history(&mut self) {
+        self.undo_redo.clear_redo_history();
+    }
+
+    /// Start an undo group: edits until the matching end_undo_group() undo/redo as one step
+    /// Groups may be nested; only the outermost end_undo_group() records the step.
+    /// Calling undo()/redo() while a group is open closes it first.
+    pub fn begin_undo_group(&mut self) {
+        self.undo_redo.begin_group();
+    }
+
+    /// Close the innermost undo group (returns false if none was open)
+    pub fn end_undo_group(&mut self) -> bool {
+        self.undo_redo.end_group()
+    }
+
+    /// Check if an undo group is open
+    pub fn is_undo_group_open(&self) -> bool {
+        self.undo_redo.is_grouping()
+    }
+
+    /// Stop recording undo history, e.g. for programmatic bulk loads or derived-value
+    /// recomputation. Calls may be nested. Edits made until the matching resume_undo()
+    /// are not recorded (not even into an open undo group), and undo()/redo() are refused.
+    /// The redo history is dropped if anything is edited while suspended.
+    pub fn suspend_undo(&mut self) {
+        self.undo_redo.suspend();
+    }
+
+    /// Undo the innermost suspend_undo() (returns false if recording was not suspended)
+    pub fn resume_undo(&mut self) -> bool {
+        self.undo_redo.resume()
+    }
+
+    /// Check if undo recording is suspended
+    pub fn is_undo_suspended(&self) -> bool {
+        self.undo_redo.is_suspended()
+    }
+
+    /// Auto-fit column width to content
+    pub fn auto_fit_column(&mut self, col: usize) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        if col >= self.grid.col_count() {
+            return Ok(());
+        }
+
+        let optimal_width = self.measure_column_width(col, 0..self.grid.row_count());
+        self.set_col_width_with_undo(col, optimal_width);
+        self.viewport.update_visible_range(&self.grid);
+        Ok(())
+    }
+
+    /// Helper: Width that fits the column header and the given rows' text
+    fn measure_column_width(&self, col: usize, rows: impl IntoIterator<Item = usize>) -> f32 {
+        let padding = self.grid.cell_padding(col) * 2.0 + 10.0; // Padding on both sides
+        let min_width = 50.0;
+        let max_width = 400.0_f32;
+
+        // Measure the cells in this column
+        let mut max_text_width = 0.0_f32;
+
+        for row in rows {
+            let text = self.grid.get_display_string(row, col);
+            if !text.is_empty() {
+                let text_width = self.text_renderer.measure_text(&text);
+                max_text_width = max_text_width.max(text_width);
+            }
+        }
+
+        // Also measure column header
+        let header_text = match self.grid.column_configs.get(col) {
+            Some(config) => config.display_name.clone(),
+            None => Grid::get_col_name(col),
+        };
+        let header_width = self.text_renderer.measure_text(&header_text);
+        max_text_width = max_text_width.max(header_width);
+
+        // Calculate optimal width with padding, within the column's own limits
+        let (col_min, col_max) = self.grid.col_width_limits(col);
+        (max_text_width + padding).clamp(min_width, max_width).clamp(col_min, col_max)
+    }
+
+    /// Helper: Size columns after loading data according to the auto_fit_on_load mode
+    /// (not recorded for undo, like the load itself)
+    fn fit_columns_on_load(&mut self) {
+        if self.auto_fit_on_load == AutoFitMode::None {
+            return;
         }
 
-        let new_style = self.get_cell_style(row, col);
-        let action = EditAction::SetStyle { row, col, old_style, new_style };
-        self.undo_redo.undo_stack.push(action);
-        self.undo_redo.redo_stack.clear();
+        let rows = self.auto_fit_on_load.sample_rows(self.grid.row_count());
+        for col in 0..self.grid.col_count() {
+            let width = self.measure_column_width(col, rows.iter().copied());
+            self.grid.set_col_width(col, width);
+        }
+        self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
     }
 
-    /// Set background color for a cell without recording to undo stack (useful for bulk styling)
-    pub fn set_cell_bg_color_no_undo(&mut self, row: usize, col: usize, color: u32) {
-        if let Some(cell) = self.grid.get_cell_mut(row, col) {
-            cell.bg_color = Some(color);
-        } else {
-            // Create cell if it doesn't exist
-            let mut cell = Cell::empty();
-            cell.bg_color = Some(color);
-            self.grid.set_cell(row, col, cell);
+    /// Set how column widths are computed after load_data_json
+    /// @param mode - "none" (keep widths, default), "header" (fit header text),
+    ///   "sample" (header and up to 200 rows spread over the data) or "full" (every row)
+    pub fn set_auto_fit_on_load(&mut self, mode: &str) -> Result<(), JsValue> {
+        self.auto_fit_on_load = AutoFitMode::parse(mode)?;
+        Ok(())
+    }
+
+    /// Get the auto-fit mode used after loading data
+    pub fn get_auto_fit_on_load(&self) -> String {
+        self.auto_fit_on_load.as_str().to_string()
+    }
+
+    /// Auto-fit all columns to content (undone as one step)
+    pub fn auto_fit_all_columns(&mut self) {
+        self.undo_redo.begin_group();
+        for col in 0..self.grid.col_count() {
+            let optimal_width = self.measure_column_width(col, 0..self.grid.row_count());
+            self.set_col_width_with_undo(col, optimal_width);
         }
-        // Note: We do NOT record to undo stack
+        self.undo_redo.end_group();
+        self.viewport.update_visible_range(&self.grid);
     }
 
-    /// Set foreground (text) color for a cell (RGBA as u32: 0xRRGGBBAA)
-    pub fn set_cell_fg_color(&mut self, row: usize, col: usize, color: u32) {
-        let old_style = self.get_cell_style(row, col);
+    /// Set all columns to equal width (undone as one step)
+    pub fn set_all_columns_equal_width(&mut self, width: f32) {
+        self.undo_redo.begin_group();
+        for col in 0..self.grid.col_count() {
+            self.set_col_width_with_undo(col, width);
+        }
+        self.undo_redo.end_group();
+        self.viewport.update_visible_range(&self.grid);
+    }
 
-        if let Some(cell) = self.grid.get_cell_mut(row, col) {
-            cell.fg_color = Some(color);
-        } else {
-            let mut cell = Cell::empty();
-            cell.fg_color = Some(color);
-            self.grid.set_cell(row, col, cell);
+    /// Helper: Set a column width and record the change for undo
+    fn set_col_width_with_undo(&mut self, col: usize, width: f32) {
+        let old_width = self.grid.base_col_width(col);
+        self.grid.set_col_width(col, width);
+        let new_width = self.grid.base_col_width(col);
+        if new_width != old_width {
+            self.undo_redo.record_action(EditAction::ResizeColumn { col, old_width, new_width });
         }
+    }
 
-        let new_style = self.get_cell_style(row, col);
-        let action = EditAction::SetStyle { row, col, old_style, new_style };
-        self.undo_redo.undo_stack.push(action);
-        self.undo_redo.redo_stack.clear();
+    /// Helper: Set a row height and record the change for undo
+    fn set_row_height_with_undo(&mut self, row: usize, height: f32) {
+        let old_height = self.grid.base_row_height(row);
+        self.grid.set_row_height(row, height);
+        let new_height = self.grid.base_row_height(row);
+        if new_height != old_height {
+            self.undo_redo.record_action(EditAction::ResizeRow { row, old_height, new_height });
+        }
     }
 
-    /// Set font style for a cell
-    pub fn set_cell_font_style(&mut self, row: usize, col: usize, bold: bool, italic: bool) {
-        let old_style = self.get_cell_style(row, col);
+    /// Move a column (values, styles, width and config) to another position (undoable)
+    /// Returns false if either index is out of range
+    pub fn move_column(&mut self, from: usize, to: usize) -> bool {
+        if self.pivot.is_some() || !self.grid.move_column(from, to) {
+            return false;
+        }
+        if from != to {
+            self.undo_redo.record_action(EditAction::MoveColumn { from, to });
+            self.needs_full_render = true;
+        }
+        true
+    }
 
-        if let Some(cell) = self.grid.get_cell_mut(row, col) {
-            cell.font_bold = bold;
-            cell.font_italic = italic;
-        } else {
-            let mut cell = Cell::empty();
-            cell.font_bold = bold;
-            cell.font_italic = italic;
-            self.grid.set_cell(row, col, cell);
+    /// Filter column by text (case-insensitive contains)
+    /// Replaces the column's filter; filters on other columns stay active
+    pub fn filter_column_by_text(&mut self, col: usize, text: String) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        let detail = serde_json::json!({ "action": "text", "col": col, "text": text });
+        self.set_filter_condition(col, FilterCondition::Contains(text), detail);
+        Ok(())
+    }
+
+    /// Filter column by empty cells
+    pub fn filter_column_show_non_empty(&mut self, col: usize) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        let detail = serde_json::json!({ "action": "non_empty", "col": col });
+        self.set_filter_condition(col, FilterCondition::NonEmpty, detail);
+        Ok(())
+    }
+
+    /// Get a column's distinct values with occurrence counts, for checkbox filter menus
+    /// Returns: {"values": [{"value", "count"}, ...], "total": distinct count} in sort order,
+    /// with at most `limit` entries (0 = no limit). Empty cells are listed as null.
+    pub fn get_column_unique_values(&self, col: usize, limit: usize) -> String {
+        let counts = if col < self.grid.col_count() { self.grid.column_value_counts(col) } else { Vec::new() };
+        let total = counts.len();
+        let take = if limit == 0 { total } else { limit.min(total) };
+        let values: Vec<serde_json::Value> = counts
+            .into_iter()
+            .take(take)
+            .map(|(value, count)| serde_json::json!({ "value": value.to_json(), "count": count }))
+            .collect();
+        serde_json::json!({ "values": values, "total": total }).to_string()
+    }
+
+    /// Filter a column to rows whose value is in `values_json` (a JSON array of values as
+    /// returned by get_column_unique_values; null matches empty cells)
+    pub fn filter_column_by_values(&mut self, col: usize, values_json: &str) -> Result<(), JsValue> {
+        let values: Vec<serde_json::Value> = serde_json::from_str(values_json)
+            .map_err(|e| GridError::InvalidJson { error: format!("filter values: {}", e) })?;
+
+        let detail = serde_json::json!({ "action": "values", "col": col, "values": values });
+        self.set_filter_condition(col, FilterCondition::Values(values), detail);
+        Ok(())
+    }
+
+    /// Filter the active cell's column to rows with the same value ("Filter by cell value")
+    /// Returns false if no cell is selected
+    pub fn filter_by_selected_value(&mut self) -> bool {
+        self.filter_by_focus_value(true)
+    }
+
+    /// Hide rows whose value in the active cell's column equals the active cell's value
+    /// Returns false if no cell is selected
+    pub fn exclude_selected_value(&mut self) -> bool {
+        self.filter_by_focus_value(false)
+    }
+
+    /// Helper: Equality (keep_equal) or inequality filter on the focus cell's value
+    fn filter_by_focus_value(&mut self, keep_equal: bool) -> bool {
+        let Some((row, col)) = self.focus_cell() else {
+            return false;
+        };
+
+        let target = self.grid.get_value(row, col);
+        let detail = serde_json::json!({
+            "action": if keep_equal { "equal" } else { "not_equal" },
+            "col": col,
+            "value": target.to_json(),
+        });
+        let condition = if keep_equal { FilterCondition::Equals(target) } else { FilterCondition::NotEquals(target) };
+        self.set_filter_condition(col, condition, detail);
+        true
+    }
+
+    /// Filter a column with an expression, e.g. "> 100 AND <= 200" or
+    /// "contains('foo') OR startswith('bar')" (see features::filter for the syntax)
+    pub fn set_column_filter_expression(&mut self, col: usize, expression: &str) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        let expression = FilterExpression::parse(expression)?;
+        let detail = serde_json::json!({ "action": "expression", "col": col, "expression": expression.source });
+        self.set_filter_condition(col, FilterCondition::Expression(expression), detail);
+        Ok(())
+    }
+
+    /// Get a column's filter expression ('' if the column has no expression filter)
+    pub fn get_column_filter_expression(&self, col: usize) -> String {
+        match self.column_filters.get(col).map(|f| &f.condition) {
+            Some(FilterCondition::Expression(expression)) => expression.source.clone(),
+            _ => String::new(),
         }
+    }
 
-        let new_style = self.get_cell_style(row, col);
-        let action = EditAction::SetStyle { row, col, old_style, new_style };
-        self.undo_redo.undo_stack.push(action);
-        self.undo_redo.redo_stack.clear();
+    /// Remove a column's filter expression and re-apply the other filters
+    pub fn remove_column_filter_expression(&mut self, col: usize) {
+        if !self.get_column_filter_expression(col).is_empty() {
+            self.remove_column_filter(col);
+        }
     }
 
-    /// Clear background color for a cell
-    pub fn clear_cell_bg_color(&mut self, row: usize, col: usize) {
-        let old_style = self.get_cell_style(row, col);
+    /// Set a column's filter from JSON, replacing the column's previous filter:
+    /// {"expression": "> 5"}, {"contains": "abc"}, {"values": [...]}, {"equals": v},
+    /// {"not_equals": v} or {"non_empty": true}, with an optional "group" name.
+    /// Filters sharing a group are combined with OR; everything else is combined with AND.
+    pub fn set_column_filter(&mut self, col: usize, filter_json: &str) -> Result<(), JsValue> {
+        self.check_col(col)?;
+        let json: serde_json::Value = serde_json::from_str(filter_json)
+            .map_err(|e| GridError::InvalidJson { error: format!("filter: {}", e) })?;
+        let filter = ColumnFilter::from_json(&json)?;
+
+        let mut detail = filter.to_json();
+        detail["action"] = serde_json::json!("set");
+        detail["col"] = serde_json::json!(col);
+        self.run_filter(detail, |grid| {
+            grid.column_filters.set(col, filter);
+            grid.apply_active_filters();
+        });
+        Ok(())
+    }
 
-        if let Some(cell) = self.grid.get_cell_mut(row, col) {
-            cell.bg_color = None;
+    /// Put a column's filter into an OR group (None = combined with AND)
+    pub fn set_column_filter_group(&mut self, col: usize, group: Option<String>) {
+        let Some(filter) = self.column_filters.get(col) else {
+            return;
+        };
+        let filter = ColumnFilter { condition: filter.condition.clone(), group };
+        let detail = serde_json::json!({ "action": "group", "col": col, "group": filter.group });
+        self.run_filter(detail, |grid| {
+            grid.column_filters.set(col, filter);
+            grid.apply_active_filters();
+        });
+    }
+
+    /// Remove one column's filter; the other filters stay active
+    pub fn remove_column_filter(&mut self, col: usize) {
+        if self.column_filters.get(col).is_none() {
+            return;
         }
+        let detail = serde_json::json!({ "action": "remove", "col": col });
+        self.run_filter(detail, |grid| {
+            grid.column_filters.remove(col);
+            grid.apply_active_filters();
+        });
+    }
 
-        let new_style = self.get_cell_style(row, col);
-        let action = EditAction::SetStyle { row, col, old_style, new_style };
-        self.undo_redo.undo_stack.push(action);
-        self.undo_redo.redo_stack.clear();
+    /// List active filters as JSON: [{"col", "group", <condition>}, ...] in column order
+    pub fn get_column_filters(&self) -> String {
+        let filters: Vec<serde_json::Value> = self
+            .column_filters
+            .iter()
+            .map(|(col, filter)| {
+                let mut json = filter.to_json();
+                json["col"] = serde_json::json!(col);
+                json
+            })
+            .collect();
+        serde_json::Value::Array(filters).to_string()
     }
 
-    /// Clear foreground color for a cell
-    pub fn clear_cell_fg_color(&mut self, row: usize, col: usize) {
-        let old_style = self.get_cell_style(row, col);
+    /// Re-evaluate the active filters against the current data (recorded for undo)
+    pub fn refresh_filters(&mut self) {
+        let detail = serde_json::json!({ "action": "refresh" });
+        self.run_filter(detail, |grid| grid.apply_active_filters());
+    }
 
-        if let Some(cell) = self.grid.get_cell_mut(row, col) {
-            cell.fg_color = None;
+    /// Same as refresh_filters
+    pub fn reapply_column_filters(&mut self) {
+        self.refresh_filters();
+    }
+
+    /// Enable or disable automatic filter re-evaluation after edits
+    /// When disabled, edits in filtered columns leave the visible rows unchanged
+    /// until refresh_filters() is called (see has_stale_filters)
+    pub fn set_auto_refresh_filters(&mut self, enabled: bool) {
+        self.auto_refresh_filters = enabled;
+    }
+
+    /// Check if filters are re-evaluated automatically after edits
+    pub fn is_auto_refresh_filters(&self) -> bool {
+        self.auto_refresh_filters
+    }
+
+    /// Check if data in a filtered column changed since the filters were last applied
+    pub fn has_stale_filters(&mut self) -> bool {
+        self.sync_filters();
+        self.filters_stale
+    }
+
+    /// Helper: Mark the filters stale when data in a filtered column changed and, in
+    /// auto mode, re-evaluate them (deferred while a cell editor is open so the edited
+    /// row does not disappear mid-edit)
+    fn sync_filters(&mut self) {
+        let cols: Vec<usize> = self.column_filters.iter().map(|(col, _)| col).collect();
+        if !cols.is_empty() && self.grid.columns_changed_since(&cols, self.filters_revision) {
+            self.filters_stale = true;
+        }
+        self.filters_revision = self.grid.revision();
+        if !self.filters_stale || !self.auto_refresh_filters || self.editing.is_editing() {
+            return;
         }
 
-        let new_style = self.get_cell_style(row, col);
-        let action = EditAction::SetStyle { row, col, old_style, new_style };
-        self.undo_redo.undo_stack.push(action);
-        self.undo_redo.redo_stack.clear();
+        // Not recorded for undo: undoing the edit re-evaluates the filters again
+        let mut detail = serde_json::json!({ "action": "refresh", "auto": true });
+        let decision = self.hooks.call("before_filter", &detail);
+        self.filters_stale = false;
+        if decision == HookDecision::Cancel {
+            return;
+        }
+
+        let start = hooks::now_ms();
+        if decision == HookDecision::Proceed {
+            self.apply_active_filters();
+            self.viewport.update_visible_range(&self.grid);
+            self.needs_full_render = true;
+        }
+
+        detail["handled"] = serde_json::json!(decision == HookDecision::Handled);
+        detail["duration_ms"] = serde_json::json!(hooks::now_ms() - start);
+        detail["visible_rows"] = serde_json::json!(self.grid.visible_row_count());
+        self.hooks.call("after_filter", &detail);
+        self.events.push("after_filter", detail);
     }
 
-    /// Set cell style (background, foreground, font) in one call
-    pub fn set_cell_style(
-        &mut self,
-        row: usize,
-        col: usize,
-        bg_color: Option<u32>,
-        fg_color: Option<u32>,
-        bold: bool,
-        italic: bool,
-    ) {
-        let old_style = self.get_cell_style(row, col);
+    /// Helper: Replace a column's filter condition (keeping its group) and re-apply all filters
+    fn set_filter_condition(&mut self, col: usize, condition: FilterCondition, detail: serde_json::Value) {
+        self.run_filter(detail, |grid| {
+            grid.column_filters.set_condition(col, condition);
+            grid.apply_active_filters();
+        });
+    }
 
-        if let Some(cell) = self.grid.get_cell_mut(row, col) {
-            cell.bg_color = bg_color;
-            cell.fg_color = fg_color;
-            cell.font_bold = bold;
-            cell.font_italic = italic;
-        } else {
-            let mut cell = Cell::empty();
-            cell.bg_color = bg_color;
-            cell.fg_color = fg_color;
-            cell.font_bold = bold;
-            cell.font_italic = italic;
-            self.grid.set_cell(row, col, cell);
+    /// Helper: Hide the rows failing the active filters
+    fn apply_active_filters(&mut self) {
+        let hidden = self.column_filters.hidden_rows(&self.grid);
+        self.grid.set_filtered_rows(hidden);
+        self.filters_revision = self.grid.revision();
+        self.filters_stale = false;
+    }
+
+    /// Clear all column filters
+    pub fn clear_column_filters(&mut self) {
+        let detail = serde_json::json!({ "action": "clear" });
+        self.run_filter(detail, |grid| {
+            grid.column_filters.clear();
+            grid.grid.clear_filters();
+            grid.filters_stale = false;
+        });
+    }
+
+    /// Helper: Run a filter change through the before_filter/after_filter hooks and record
+    /// it for undo. Returns false if a before_filter hook cancelled it.
+    /// When the hook returns "handled", stored filter expressions are updated but the
+    /// visible rows are left as they were (the host filters, e.g. on a server).
+    fn run_filter(&mut self, mut detail: serde_json::Value, filter: impl FnOnce(&mut DataGrid)) -> bool {
+        let decision = self.hooks.call("before_filter", &detail);
+        if decision == HookDecision::Cancel {
+            return false;
         }
 
-        let new_style = self.get_cell_style(row, col);
-        let action = EditAction::SetStyle { row, col, old_style, new_style };
-        self.undo_redo.undo_stack.push(action);
-        self.undo_redo.redo_stack.clear();
+        let start = hooks::now_ms();
+        let old_rows = self.grid.filtered_rows().clone();
+        let old_filters = self.column_filters.clone();
+        filter(self);
+        if decision == HookDecision::Handled {
+            self.grid.set_filtered_rows(old_rows.clone());
+        }
+        self.record_filter_change(old_rows, old_filters);
+        self.viewport.update_visible_range(&self.grid);
+
+        detail["handled"] = serde_json::json!(decision == HookDecision::Handled);
+        detail["duration_ms"] = serde_json::json!(hooks::now_ms() - start);
+        detail["visible_rows"] = serde_json::json!(self.grid.visible_row_count());
+        self.hooks.call("after_filter", &detail);
+        self.events.push("after_filter", detail);
+        true
     }
 
-    /// Set custom border for a cell (top, right, bottom, or left)
-    /// side: 0=top, 1=right, 2=bottom, 3=left
-    pub fn set_cell_border(&mut self, row: usize, col: usize, side: u8, color: u32, width: f32) {
-        // Get or create borders for this cell
-        let mut borders = self.grid.get_cell_borders(row, col)
-            .cloned()
-            .unwrap_or_default();
+    /// Helper: Record a filter change (hidden rows and filters before the change) for undo
+    fn record_filter_change(&mut self, old_rows: HashSet<usize>, old_filters: ColumnFilters) {
+        if &old_rows != self.grid.filtered_rows() || old_filters != self.column_filters {
+            let new_rows = self.grid.filtered_rows().clone();
+            let new_filters = self.column_filters.clone();
+            self.undo_redo.record_action(EditAction::SetFilter { old_rows, new_rows, old_filters, new_filters });
+        }
+    }
 
-        let border = Some(CellBorder { color, width });
-        match side {
-            0 => borders.top = border,
-            1 => borders.right = border,
-            2 => borders.bottom = border,
-            3 => borders.left = border,
-            _ => {}
+    /// Helper: Put back the filter definitions of an undone or redone filter change
+    /// (the hidden rows were restored by the undo itself)
+    fn restore_filters(&mut self, undone: bool) {
+        let action = if undone { self.undo_redo.redo_stack.last() } else { self.undo_redo.undo_stack.last() };
+        if let Some(filters) = action.and_then(|action| action.filters(undone)).cloned() {
+            self.column_filters = filters;
+            self.filters_revision = self.grid.revision();
+            self.filters_stale = false;
         }
+    }
 
-        self.grid.set_cell_borders_at(row, col, borders);
+    /// Check if a row is filtered (hidden)
+    pub fn is_row_filtered(&self, row: usize) -> bool {
+        self.grid.is_row_filtered(row)
     }
 
-    /// Set all borders for a cell at once
-    pub fn set_cell_borders(&mut self, row: usize, col: usize, color: u32, width: f32) {
-        let border = Some(CellBorder { color, width });
-        let borders = CellBorders {
-            top: border.clone(),
-            right: border.clone(),
-            bottom: border.clone(),
-            left: border,
-        };
-        self.grid.set_cell_borders_at(row, col, borders);
+    /// Get visible row count
+    pub fn get_visible_row_count(&self) -> usize {
+        self.grid.visible_row_count()
     }
 
-    /// Clear border for a cell side
-    /// side: 0=top, 1=right, 2=bottom, 3=left, 4=all
-    pub fn clear_cell_border(&mut self, row: usize, col: usize, side: u8) {
-        if side == 4 {
-            // Clear all borders by removing the entry
-            self.grid.remove_cell_borders(row, col);
-        } else if let Some(borders) = self.grid.get_cell_borders(row, col) {
-            // Clone borders and modify
-            let mut new_borders = borders.clone();
-            match side {
-                0 => new_borders.top = None,
-                1 => new_borders.right = None,
-                2 => new_borders.bottom = None,
-                3 => new_borders.left = None,
-                _ => {}
-            }
-            // If all borders are None, remove the entry; otherwise update it
-            if new_borders.top.is_none() && new_borders.right.is_none()
-                && new_borders.bottom.is_none() && new_borders.left.is_none() {
-                self.grid.remove_cell_borders(row, col);
-            } else {
-                self.grid.set_cell_borders_at(row, col, new_borders);
-            }
+    // ========== Column Multi-Select API ==========
+
+    /// Columns whose every row is selected (Ctrl/Shift+click on column headers builds
+    /// such a selection), in ascending order
+    pub fn get_selected_columns(&self) -> Vec<usize> {
+        self.selection.selected_columns(self.grid.row_count())
+    }
+
+    /// Set the width of every selected column (undone as one step)
+    /// Returns the number of columns changed
+    pub fn set_selected_columns_width(&mut self, width: f32) -> usize {
+        let cols = self.get_selected_columns();
+        self.undo_redo.begin_group();
+        for &col in &cols {
+            self.set_col_width_with_undo(col, width);
         }
+        self.undo_redo.end_group();
+        self.after_column_layout_change();
+        cols.len()
     }
 
-    // ========== Column Grouping API ==========
+    /// Hide every selected column (data and widths are kept; undone as one step)
+    /// Returns the number of columns hidden
+    pub fn hide_selected_columns(&mut self) -> usize {
+        let cols: Vec<usize> = self.get_selected_columns().into_iter().filter(|&col| self.grid.set_col_visible(col, false)).collect();
+        if !cols.is_empty() {
+            self.undo_redo.record_action(EditAction::SetColumnVisibility { cols: cols.clone(), visible: false });
+            self.clear_selection();
+            self.after_column_layout_change();
+        }
+        cols.len()
+    }
 
-    /// Add a column group for multi-level headers
-    /// @param label - Group label text
-    /// @param start_col - First column in group (0-indexed)
-    /// @param end_col - Last column in group (0-indexed, inclusive)
-    /// @param level - Header level (0 = top level, 1 = second level, etc.)
-    pub fn add_column_group(&mut self, label: String, start_col: usize, end_col: usize, level: usize) {
-        self.grid.add_column_group(label, start_col, end_col, level);
+    /// Show every hidden column (undone as one step); returns the number shown
+    pub fn show_all_columns(&mut self) -> usize {
+        let cols: Vec<usize> = (0..self.grid.col_count()).filter(|&col| self.grid.set_col_visible(col, true)).collect();
+        if !cols.is_empty() {
+            self.undo_redo.record_action(EditAction::SetColumnVisibility { cols: cols.clone(), visible: true });
+            self.after_column_layout_change();
+        }
+        cols.len()
+    }
+
+    /// Hide a column: it collapses to zero width, keeping its data and width (undoable)
+    /// Returns false if it was already hidden
+    pub fn hide_column(&mut self, col: usize) -> Result<bool, GridError> {
+        self.set_column_visible_with_undo(col, false)
     }
 
-    /// Clear all column groups (revert to single-level headers)
-    pub fn clear_column_groups(&mut self) {
-        self.grid.clear_column_groups();
+    /// Show a hidden column at its previous width (undoable); returns false if it was not hidden
+    pub fn show_column(&mut self, col: usize) -> Result<bool, GridError> {
+        self.set_column_visible_with_undo(col, true)
     }
 
-    /// Set the height of each header row (default: 30px)
-    pub fn set_header_row_height(&mut self, height: f32) {
-        self.grid.set_header_row_height(height);
+    /// Check if a column is hidden
+    pub fn is_column_hidden(&self, col: usize) -> bool {
+        self.grid.is_col_hidden(col)
     }
 
-    /// Get the current number of header levels
-    pub fn get_header_levels(&self) -> usize {
-        self.grid.header_levels
+    /// Get the hidden columns, in ascending order
+    pub fn get_hidden_columns(&self) -> Vec<usize> {
+        self.grid.hidden_columns()
     }
 
-    /// Get total header height
-    pub fn get_header_height(&self) -> f32 {
-        self.grid.col_header_height
+    /// Hide a row: it collapses to zero height, keeping its data and height (undoable)
+    /// The row stays hidden through sorts, inserts and deletes of other rows
+    /// Returns false if it was already hidden
+    pub fn hide_row(&mut self, row: usize) -> Result<bool, GridError> {
+        self.set_row_visible_with_undo(row, false)
     }
 
-    // ========== Column Validation API ==========
+    /// Show a row hidden with hide_row (undoable); returns false if it was not hidden
+    pub fn show_row(&mut self, row: usize) -> Result<bool, GridError> {
+        self.set_row_visible_with_undo(row, true)
+    }
 
-    /// Set validation pattern for a column
-    /// @param col - Column index (0-based)
-    /// @param pattern - JavaScript regex pattern (e.g., "^[0-9]+$" for numbers only)
-    /// @param message - Error message to display when validation fails
-    pub fn set_column_validation(&mut self, col: usize, pattern: String, message: String) {
-        self.grid.set_column_validation(col, pattern, message);
+    /// Check if a row is hidden with hide_row (see is_row_filtered for filters)
+    pub fn is_row_hidden(&self, row: usize) -> bool {
+        self.grid.is_row_user_hidden(row)
     }
 
-    /// Clear validation pattern for a column
-    pub fn clear_column_validation(&mut self, col: usize) {
-        self.grid.clear_column_validation(col);
+    /// Get the rows hidden with hide_row, in ascending order
+    pub fn get_hidden_rows(&self) -> Vec<usize> {
+        self.grid.hidden_rows()
     }
 
-    /// Get validation pattern and message for a column
-    /// Returns JSON string: {"pattern": "regex", "message": "error msg"} or empty string if no validation
-    pub fn get_column_validation(&self, col: usize) -> String {
-        if let Some((pattern, message)) = self.grid.get_column_validation(col) {
-            return format!(r#"{{"pattern":"{}","message":"{}"}}"#,
-                         pattern.replace("\\", "\\\\").replace("\"", "\\\""),
-                         message.replace("\\", "\\\\").replace("\"", "\\\""));
+    /// Show every row hidden with hide_row (undone as one step); returns the number shown
+    pub fn show_all_rows(&mut self) -> usize {
+        let rows: Vec<usize> = self.grid.hidden_rows().into_iter().filter(|&row| self.grid.set_row_visible(row, true)).collect();
+        if !rows.is_empty() {
+            self.undo_redo.record_action(EditAction::SetRowVisibility { rows: rows.clone(), visible: true });
+            self.after_column_layout_change();
         }
-        String::new()
+        rows.len()
     }
 
-    // ========== Column Editable Control API ==========
-
-    /// Set whether a column is editable
-    /// @param col - Column index (0-based)
-    /// @param editable - true: editable, false: read-only
-    pub fn set_column_editable(&mut self, col: usize, editable: bool) {
-        self.grid.set_column_editable(col, editable);
+    /// Helper: Show or hide one column, recording the change for undo
+    fn set_column_visible_with_undo(&mut self, col: usize, visible: bool) -> Result<bool, GridError> {
+        self.check_col(col)?;
+        let changed = self.grid.set_col_visible(col, visible);
+        if changed {
+            self.undo_redo.record_action(EditAction::SetColumnVisibility { cols: vec![col], visible });
+            self.after_column_layout_change();
+        }
+        Ok(changed)
     }
 
-    /// Check if a column is editable
-    pub fn is_column_editable(&self, col: usize) -> bool {
-        self.grid.is_column_editable(col)
+    /// Helper: Show or hide one row, recording the change for undo
+    fn set_row_visible_with_undo(&mut self, row: usize, visible: bool) -> Result<bool, GridError> {
+        self.check_row(row)?;
+        let changed = self.grid.set_row_visible(row, visible);
+        if changed {
+            self.undo_redo.record_action(EditAction::SetRowVisibility { rows: vec![row], visible });
+            self.after_column_layout_change();
+        }
+        Ok(changed)
     }
 
-    /// Get editable status for all columns as JSON array
-    /// Returns: "[true, false, true, ...]"
-    pub fn get_all_column_editable_status(&self) -> String {
-        let status = self.grid.get_all_column_editable_status();
-        format!("[{}]", status.iter()
-            .map(|b| b.to_string())
-            .collect::<Vec<_>>()
-            .join(","))
+    /// Apply a cell style to every cell of the selected columns (undone as one step)
+    /// Returns the number of columns styled
+    pub fn set_selected_columns_style(&mut self, bg_color: Option<u32>, fg_color: Option<u32>, bold: bool, italic: bool) -> usize {
+        let cols = self.get_selected_columns();
+        self.undo_redo.begin_group();
+        for &col in &cols {
+            for row in 0..self.grid.row_count() {
+                self.apply_cell_style(row, col, CellStyle { bg_color, fg_color, font_bold: bold, font_italic: italic });
+            }
+        }
+        self.undo_redo.end_group();
+        self.needs_full_render = true;
+        cols.len()
     }
 
-    /// Set column header name
-    /// @param col - Column index (0-based)
-    /// @param name - Header name to display
-    pub fn set_column_name(&mut self, col: usize, name: &str) {
-        if col < self.grid.column_configs.len() {
-            self.grid.column_configs[col].display_name = name.to_string();
+    /// Delete every selected column (undone as one step); at least one column must remain
+    /// Returns the number of columns deleted
+    pub fn delete_selected_columns(&mut self) -> Result<usize, JsValue> {
+        self.check_flat()?;
+        let cols = self.get_selected_columns();
+        if !cols.is_empty() && cols.len() >= self.grid.col_count() {
+            return Err(GridError::OperationError { message: locale::text("error.delete_every_column") }.into());
         }
+        self.undo_redo.begin_group();
+        for &col in cols.iter().rev() {
+            self.delete_column(col);
+        }
+        self.undo_redo.end_group();
+        self.needs_full_render = true;
+        Ok(cols.len())
     }
 
-    /// Insert a row at the specified position
-    pub fn insert_row(&mut self, at_index: usize) {
-        // Record action for undo (insert is opposite of delete, so we store as DeleteRow)
-        let action = EditAction::DeleteRow {
-            index: at_index,
-            cells: Vec::new(), // Empty row being inserted
-        };
-        self.undo_redo.undo_stack.push(action);
-        self.undo_redo.redo_stack.clear();
-
-        self.grid.insert_row(at_index);
-        self.clear_selection();
+    /// Helper: Refresh scroll bounds and the visible range after column widths changed
+    fn after_column_layout_change(&mut self) {
+        self.apply_flex_widths();
+        let (scroll_x, scroll_y) = (self.viewport.scroll_x, self.viewport.scroll_y);
+        self.viewport.set_scroll(scroll_x, scroll_y, &self.grid);
         self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
     }
 
-    /// Delete a row at the specified position
-    pub fn delete_row(&mut self, index: usize) {
-        // Save cells before deletion for undo
-        let cells = self.grid.get_row_cells(index);
+    // ========== Watermark API ==========
 
-        let action = EditAction::InsertRow {
-            index,
-            cells,
-        };
-        self.undo_redo.undo_stack.push(action);
-        self.undo_redo.redo_stack.clear();
+    /// Show a watermark (e.g. "DRAFT" or "CONFIDENTIAL") across the cell area, beneath the
+    /// cell text. Exports (export_png, render_page) leave it out unless asked to include it.
+    /// @param watermark_json - {"text": "DRAFT"} or {"image": url}, with optional
+    ///   "font_size" (default: 72), "color" (default: "#888888"), "opacity" (0..1, default:
+    ///   0.15), "angle" (degrees clockwise, default: -30), "scale" (image size, default: 1)
+    ///   and "repeat" (tile the mark, default: false); "" removes the watermark
+    pub fn set_watermark_json(&mut self, watermark_json: &str) -> Result<(), JsValue> {
+        self.needs_full_render = true;
+        if watermark_json.trim().is_empty() {
+            self.text_renderer.watermark = None;
+            return Ok(());
+        }
+        let options: serde_json::Value = serde_json::from_str(watermark_json)
+            .map_err(|e| GridError::InvalidJson { error: format!("watermark: {}", e) })?;
+        let mut watermark = Watermark::from_json(&options)?;
+        if let Some(url) = &watermark.image_url {
+            // Drawn once loaded (render keeps redrawing while the image loads)
+            let image = HtmlImageElement::new()?;
+            // Anonymous CORS keeps a cross-origin mark from tainting the canvas (export_png)
+            image.set_cross_origin(Some("anonymous"));
+            image.set_src(url);
+            watermark.image = Some(image);
+        }
+        self.text_renderer.watermark = Some(watermark);
+        Ok(())
+    }
 
-        self.grid.delete_row(index);
-        self.clear_selection();
-        self.viewport.update_visible_range(&self.grid);
+    /// Get the watermark settings as JSON ("" when there is none)
+    pub fn get_watermark_json(&self) -> String {
+        self.text_renderer.watermark.as_ref().map_or_else(String::new, |watermark| watermark.to_json().to_string())
     }
 
-    /// Delete multiple rows at once
-    /// @param indices - JSON array of row indices to delete, e.g., "[0, 2, 5]"
-    pub fn delete_rows(&mut self, indices_json: String) -> Result<(), JsValue> {
-        // Parse indices
-        let indices: Vec<usize> = serde_json::from_str(&indices_json)
-            .map_err(|e| JsValue::from_str(&format!("Failed to parse indices: {}", e)))?;
+    // ========== Image Export API ==========
 
-        if indices.is_empty() {
-            return Ok(());
+    /// Export the grid as a PNG data URL (background and text layers composited on white)
+    /// @param region_json - "" for the current view, or
+    ///   {"start_row", "start_col", "end_row", "end_col", "headers": bool, "selection": bool}
+    ///   to draw a cell range of any position (end defaults to the last row/column).
+    ///   headers (default: true) includes the row/column headers; selection (default:
+    ///   false) keeps the selection highlight. Frozen panes and the footer are left out.
+    ///   "watermark": true includes the watermark; {"view": true, "watermark": true}
+    ///   exports the current view with it.
+    pub fn export_png(&mut self, region_json: &str) -> Result<String, JsValue> {
+        let region: serde_json::Value = if region_json.trim().is_empty() {
+            serde_json::json!({ "view": true })
+        } else {
+            serde_json::from_str(region_json).map_err(|e| GridError::InvalidJson { error: format!("export region: {}", e) })?
+        };
+        let include_watermark = region["watermark"].as_bool().unwrap_or(false);
+        if region["view"].as_bool().unwrap_or(false) {
+            return self.with_watermark(include_watermark, |grid| {
+                grid.needs_full_render = true;
+                grid.render();
+                grid.composite_png()
+            });
         }
 
-        // Sort indices in descending order to delete from bottom to top
-        // This prevents index shifting issues
-        let mut sorted_indices = indices.clone();
-        sorted_indices.sort_unstable();
-        sorted_indices.reverse();
-
-        // Save all rows for undo
-        let mut deleted_rows = Vec::new();
-        for &index in &sorted_indices {
-            if index < self.grid.row_count() {
-                let cells = self.grid.get_row_cells(index);
-                deleted_rows.push((index, cells));
+        let last_row = self.grid.row_count().saturating_sub(1);
+        let last_col = self.grid.col_count().saturating_sub(1);
+        let index = |key: &str, default: usize| region[key].as_u64().map_or(default, |n| n as usize);
+        let (start_row, start_col) = (index("start_row", 0), index("start_col", 0));
+        let (end_row, end_col) = (index("end_row", last_row), index("end_col", last_col));
+        if end_row > last_row || end_col > last_col {
+            return Err(GridError::OutOfBounds { row: end_row, col: end_col }.into());
+        }
+        if start_row > end_row || start_col > end_col {
+            return Err(GridError::OperationError { message: locale::text("error.export_region") }.into());
+        }
+        let headers = region["headers"].as_bool().unwrap_or(true);
+        let keep_selection = region["selection"].as_bool().unwrap_or(false);
+
+        let zoom = self.viewport.zoom;
+        let (header_width, header_height) = if headers { (self.grid.row_header_width, self.grid.col_header_height) } else { (0.0, 0.0) };
+        let (x, y) = (self.grid.col_x_position(start_col), self.grid.row_y_position(start_row));
+        let range_width = self.grid.col_x_position(end_col) + self.grid.col_width(end_col) - x;
+        let range_height = (self.grid.row_y_position(end_row) - y) as f32 + self.grid.row_height(end_row);
+        let (width, height) = ((header_width + range_width) * zoom, (header_height + range_height) * zoom);
+        if width > MAX_EXPORT_PIXELS || height > MAX_EXPORT_PIXELS {
+            return Err(GridError::OperationError {
+                message: locale::format("error.export_too_large", &[("width", &width), ("height", &height), ("limit", &MAX_EXPORT_PIXELS)]),
             }
+            .into());
         }
 
-        // Record undo action
-        let action = EditAction::DeleteRows {
-            rows: deleted_rows.clone(),
-        };
-        self.undo_redo.undo_stack.push(action);
-        self.undo_redo.redo_stack.clear();
+        self.with_watermark(include_watermark, |grid| {
+            grid.draw_offscreen_png((width, height), zoom, (0, 0), headers, (x, y), keep_selection)
+        })
+    }
 
-        // Delete rows from bottom to top
-        for &index in &sorted_indices {
-            if index < self.grid.row_count() {
-                self.grid.delete_row(index);
-            }
+    /// Helper: Run an export with or without the watermark (the on-screen view keeps it)
+    fn with_watermark<T>(&mut self, include: bool, export: impl FnOnce(&mut Self) -> T) -> T {
+        let hidden = if include { None } else { self.text_renderer.watermark.take() };
+        let result = export(self);
+        if hidden.is_some() {
+            self.text_renderer.watermark = hidden;
+            self.needs_full_render = true;
         }
+        result
+    }
 
-        self.clear_selection();
+    /// Helper: Lay the grid out on canvases of the given device size with the given frozen
+    /// panes, headers and scroll position, draw it, then put the view back (footer left out)
+    fn draw_offscreen_png(
+        &mut self,
+        size: (f32, f32),
+        zoom: f32,
+        frozen: (usize, usize),
+        headers: bool,
+        scroll: (f32, f64),
+        keep_selection: bool,
+    ) -> Result<String, JsValue> {
+        let saved_viewport = self.viewport.clone();
+        let saved_canvas = (self.webgl_canvas.width() as f32, self.webgl_canvas.height() as f32);
+        let saved_layout = (self.grid.frozen_rows, self.grid.frozen_cols, self.grid.show_headers, self.grid.footer_height);
+        let saved_selection = (!keep_selection).then(|| std::mem::take(&mut self.selection));
+        // The pointer's hover highlight belongs to the live view, not to exports
+        let saved_hover = self.mouse_handler.hovered_cell.take();
+        (self.grid.frozen_rows, self.grid.frozen_cols, self.grid.show_headers, self.grid.footer_height) = (frozen.0, frozen.1, headers, 0.0);
+
+        self.viewport.zoom = zoom;
+        self.resize(size.0, size.1);
+        self.viewport.set_scroll(scroll.0, scroll.1, &self.grid);
         self.viewport.update_visible_range(&self.grid);
+        self.draw_full();
+        let png = self.composite_png();
 
-        Ok(())
+        (self.grid.frozen_rows, self.grid.frozen_cols, self.grid.show_headers, self.grid.footer_height) = saved_layout;
+        if let Some(selection) = saved_selection {
+            self.selection = selection;
+        }
+        self.mouse_handler.hovered_cell = saved_hover;
+        self.resize(saved_canvas.0, saved_canvas.1);
+        self.viewport = saved_viewport;
+        self.last_render_state = None;
+        png
     }
 
-    /// Get unique row indices from selected cells
-    /// Returns JSON array of row indices, e.g., "[0, 2, 5]"
-    pub fn get_selected_row_indices(&self) -> String {
-        let mut rows: Vec<usize> = self.selection.selected_cells
-            .iter()
-            .map(|(row, _)| *row)
-            .collect();
+    /// Helper: Draw the background and text layers of the whole view
+    fn draw_full(&mut self) {
+        // Render background layer (grid lines and backgrounds; WebGL or Canvas 2D)
+        self.renderer.render(&self.grid, &self.viewport, &self.selection);
 
-        // Remove duplicates and sort
-        rows.sort_unstable();
-        rows.dedup();
+        // Render text layer on top with search highlight info
+        self.text_renderer.render_with_search(
+            &self.grid,
+            &self.viewport,
+            &self.selection,
+            &self.search.search_results,
+            self.search.current_search_index
+        );
+        if let Some((row, col)) = self.mouse_handler.hovered_cell.filter(|_| self.text_renderer.hover_style.is_enabled()) {
+            self.text_renderer.render_hover(&self.grid, &self.viewport, row, col);
+        }
 
-        serde_json::to_string(&rows).unwrap_or_else(|_| "[]".to_string())
+        if self.summary_mode != SummaryMode::Off && self.grid.show_headers {
+            let summaries: Vec<(usize, Option<ColumnSummary>)> = (self.viewport.first_visible_col
+                ..=self.viewport.last_visible_col)
+                .filter(|&col| col < self.grid.col_count() && self.grid.col_width(col) > 0.0)
+                .map(|col| (col, self.summary_cache.get(&self.grid, col).cloned()))
+                .collect();
+            self.text_renderer.render_column_summaries(&self.grid, &self.viewport, &summaries, self.summary_mode);
+        }
+        if let (Some(buffer), Some((row, col))) = (&self.editing.buffer, self.editing.editing_cell) {
+            let rect = self.editing.get_cell_edit_rect(row, col, &self.grid, &self.viewport);
+            self.text_renderer.render_editor(&self.grid, &self.viewport, col, &rect, buffer);
+        }
+        if self.grid.show_filter_buttons && self.grid.show_headers {
+            let filtered: HashSet<usize> = self.column_filters.iter().map(|(col, _)| col).collect();
+            self.text_renderer.render_filter_buttons(&self.grid, &self.viewport, &filtered);
+        }
+        if let Some(rect) = self.range_move.as_ref().and_then(|drag| self.range_canvas_rect(drag.target_range())) {
+            self.text_renderer.render_range_outline(&self.grid, &self.viewport, rect);
+        }
     }
 
-    /// Insert a column at the specified position
-    pub fn insert_column(&mut self, at_index: usize) {
-        // Record action for undo (insert is opposite of delete, so we store as DeleteColumn)
-        let action = EditAction::DeleteColumn {
-            index: at_index,
-            cells: Vec::new(), // Empty column being inserted
-        };
-        self.undo_redo.undo_stack.push(action);
-        self.undo_redo.redo_stack.clear();
-
-        self.grid.insert_column(at_index);
-        self.clear_selection();
-        self.viewport.update_visible_range(&self.grid);
+    /// Helper: Composite the background and text canvases into a PNG data URL
+    /// (must run in the same task as the drawing, before the WebGL buffer is presented)
+    fn composite_png(&self) -> Result<String, JsValue> {
+        let document = web_sys::window()
+            .and_then(|window| window.document())
+            .ok_or_else(|| GridError::OperationError { message: locale::text("error.no_document") })?;
+        let canvas = document.create_element("canvas")?.dyn_into::<HtmlCanvasElement>()?;
+        canvas.set_width(self.webgl_canvas.width());
+        canvas.set_height(self.webgl_canvas.height());
+        let context = canvas
+            .get_context("2d")?
+            .ok_or_else(|| GridError::OperationError { message: locale::text("error.no_canvas_context") })?
+            .dyn_into::<web_sys::CanvasRenderingContext2d>()?;
+
+        context.set_fill_style_str("#ffffff");
+        context.fill_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
+        context.draw_image_with_html_canvas_element(&self.webgl_canvas, 0.0, 0.0)?;
+        context.draw_image_with_html_canvas_element(&self.text_canvas, 0.0, 0.0)?;
+        canvas.to_data_url_with_type("image/png")
+    }
+
+    // ========== Print Layout API ==========
+
+    /// Split the grid into page-sized tiles for window.print() or PDF output and return
+    /// the number of pages. Every page repeats the headers and the frozen rows/columns.
+    /// @param page_size_json - {"width", "height"} in CSS pixels, or {"size": "a4" | "a3" |
+    ///   "letter" | "legal", "orientation": "portrait" | "landscape"}; optional "margin"
+    ///   (px per side), "headers" (default: true), "repeat_frozen" (default: true) and
+    ///   "order" ("down": down the rows first, default; "across": across the columns first);
+    ///   "watermark": true draws the watermark on every page
+    /// The layout is kept for render_page; call again after the data or sizes change.
+    pub fn get_page_count(&mut self, page_size_json: &str) -> Result<usize, JsValue> {
+        let options: serde_json::Value = serde_json::from_str(page_size_json)
+            .map_err(|e| GridError::InvalidJson { error: format!("page size: {}", e) })?;
+        let layout = print::paginate(&self.grid, PageSetup::from_json(&options)?)?;
+        let count = layout.page_count();
+        self.print_layout = Some(layout);
+        Ok(count)
+    }
+
+    /// Draw page n (0-based) of the get_page_count layout at 100% zoom and return it as a
+    /// PNG data URL (sized to the page's content, at most the page size). The selection
+    /// and footer are left out; the view is restored afterwards.
+    pub fn render_page(&mut self, n: usize) -> Result<String, JsValue> {
+        let (tile, frozen, headers) = self.page_tile(n)?;
+        let include_watermark = self.print_layout.as_ref().is_some_and(|layout| layout.setup.watermark);
+        let x = self.grid.col_x_position(tile.cols.0) - self.grid.col_x_position(frozen.1);
+        let y = self.grid.row_y_position(tile.rows.0) - self.grid.row_y_position(frozen.0);
+        self.with_watermark(include_watermark, |grid| {
+            grid.draw_offscreen_png((tile.width.ceil(), tile.height.ceil()), 1.0, frozen, headers, (x, y), false)
+        })
     }
 
-    /// Delete a column at the specified position
-    pub fn delete_column(&mut self, index: usize) {
-        // Save cells before deletion for undo
-        let cells = self.grid.get_column_cells(index);
+    /// Get what page n (0-based) shows as JSON:
+    /// {"page", "rows": [first, last], "cols": [first, last], "width", "height"}
+    /// (body rows and columns; the frozen ones are repeated on every page)
+    pub fn get_page_info(&self, n: usize) -> Result<String, JsValue> {
+        let (tile, _, _) = self.page_tile(n)?;
+        Ok(serde_json::json!({
+            "page": n,
+            "rows": [tile.rows.0, tile.rows.1],
+            "cols": [tile.cols.0, tile.cols.1],
+            "width": tile.width,
+            "height": tile.height,
+        })
+        .to_string())
+    }
 
-        let action = EditAction::InsertColumn {
-            index,
-            cells,
+    /// Helper: Tile of page n with the frozen panes and header setting it is drawn with
+    fn page_tile(&self, n: usize) -> Result<(PageTile, (usize, usize), bool), GridError> {
+        let Some(layout) = self.print_layout.as_ref() else {
+            return Err(GridError::OperationError { message: locale::text("error.no_page_layout") });
+        };
+        let Some(tile) = layout.page(n) else {
+            return Err(GridError::OperationError { message: locale::format("error.page_out_of_range", &[("page", &n), ("count", &layout.page_count())]) });
+        };
+        if tile.rows.1 >= self.grid.row_count() || tile.cols.1 >= self.grid.col_count() {
+            return Err(GridError::OperationError { message: locale::text("error.page_layout_stale") });
+        }
+        Ok((tile, (layout.frozen_rows, layout.frozen_cols), layout.setup.headers))
+    }
+
+    // ========== Streaming Load API ==========
+
+    /// Start loading large CSV/JSON data in chunks (feed_chunk, then end_stream_load),
+    /// so the host can hand over one piece per frame or per network read instead of
+    /// parsing everything in one load_data_json call. Existing rows are removed and the
+    /// grid grows as rows arrive (one empty row remains if none do); undo history and the
+    /// selection are reset.
+    /// @param format - "csv", "tsv", "jsonl" (one row per line) or "json" (array of rows);
+    ///   JSON rows are arrays in column order or objects keyed by column internal name
+    /// @param options_json - {"header": bool, "delimiter": ";", "total_bytes": n} (all optional, "" = defaults);
+    ///   header makes the first CSV row the column names, total_bytes enables the progress ratio
+    pub fn begin_stream_load(&mut self, format: &str, options_json: &str) -> Result<(), JsValue> {
+        self.check_flat()?;
+        if self.virtual_source.is_some() {
+            return Err(GridError::OperationError { message: locale::text("error.stream_virtual") }.into());
+        }
+        let format = StreamFormat::parse(format)?;
+        let options: serde_json::Value = if options_json.trim().is_empty() {
+            serde_json::json!({})
+        } else {
+            serde_json::from_str(options_json).map_err(|e| GridError::InvalidJson { error: format!("stream options: {}", e) })?
         };
-        self.undo_redo.undo_stack.push(action);
-        self.undo_redo.redo_stack.clear();
+        let delimiter = match options["delimiter"].as_str() {
+            Some(d) if d.chars().count() == 1 => d.chars().next(),
+            Some(d) => return Err(GridError::OperationError { message: locale::format("error.delimiter", &[("delimiter", &d)]) }.into()),
+            None => None,
+        };
+        let header = options["header"].as_bool().unwrap_or(false);
+        let total_bytes = options["total_bytes"].as_u64().map(|n| n as usize);
 
-        self.grid.delete_column(index);
-        self.clear_selection();
+        self.stream_load = Some(StreamLoad::new(StreamParser::new(format, delimiter), header, total_bytes));
+        self.grid.clear();
+        self.grid.resize(1, self.grid.col_count());
+        self.undo_redo.clear_undo_history();
+        self.undo_redo.clear_redo_history();
+        self.selection.clear_selection();
+        self.viewport.set_scroll(0.0, 0.0, &self.grid);
         self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
+        Ok(())
     }
 
-    /// Delete all empty rows (rows with no non-empty cells)
-    pub fn delete_empty_rows(&mut self) -> usize {
-        let mut rows_to_delete = Vec::new();
+    /// Parse the next chunk of the stream and add its complete rows to the grid.
+    /// A chunk may end anywhere (mid-row, mid-field or mid-character).
+    /// Also queues a stream_progress event.
+    /// Returns progress JSON: {"bytes_received", "total_bytes", "progress" (0..1 or null), "rows_loaded", "skipped"}
+    pub fn feed_chunk(&mut self, chunk: &[u8]) -> Result<String, JsValue> {
+        let Some(load) = self.stream_load.as_mut() else {
+            return Err(GridError::OperationError { message: locale::text("error.no_stream_load") }.into());
+        };
+        load.bytes_received += chunk.len();
+        let records = load.parser.feed(chunk);
+        self.apply_stream_records(records);
+
+        let progress = self.stream_load.as_ref().map(StreamLoad::progress_json).unwrap_or_default();
+        self.events.push("stream_progress", progress.clone());
+        Ok(progress.to_string())
+    }
+
+    /// Finish the stream: flush the last row, auto-fit columns and queue a
+    /// stream_load_complete event.
+    /// Returns report JSON: {"applied", "skipped", "errors", "rows", "cols", "bytes_received"}
+    /// where applied counts cells and errors lists rows that could not be read
+    pub fn end_stream_load(&mut self) -> Result<String, JsValue> {
+        let Some(load) = self.stream_load.as_mut() else {
+            return Err(GridError::OperationError { message: locale::text("error.no_stream_load") }.into());
+        };
+        let records = load.parser.finish();
+        self.apply_stream_records(records);
 
-        // Find all empty rows
-        for row in 0..self.grid.row_count() {
-            let mut is_empty = true;
-            for col in 0..self.grid.col_count() {
-                if !self.grid.get_value(row, col).is_empty() {
-                    is_empty = false;
-                    break;
-                }
-            }
-            if is_empty {
-                rows_to_delete.push(row);
-            }
-        }
+        let Some(load) = self.stream_load.take() else {
+            return Ok(String::new());
+        };
+        let mut report = load.report.to_json();
+        report["rows"] = serde_json::json!(self.grid.row_count());
+        report["cols"] = serde_json::json!(self.grid.col_count());
+        report["bytes_received"] = serde_json::json!(load.bytes_received);
+        log::debug!("Streaming load completed: {} rows, {} bytes", self.grid.row_count(), load.bytes_received);
 
-        // Delete rows from bottom to top to maintain indices
-        let count = rows_to_delete.len();
-        for row in rows_to_delete.iter().rev() {
-            self.delete_row(*row);
-        }
+        self.fit_columns_on_load();
+        self.events.push("stream_load_complete", report.clone());
+        Ok(report.to_string())
+    }
 
-        self.clear_selection();
-        self.viewport.update_visible_range(&self.grid);
-        count
+    /// Abandon a streaming load; rows added so far stay in the grid
+    pub fn cancel_stream_load(&mut self) {
+        self.stream_load = None;
     }
 
-    /// Check if a row is empty (all cells are empty)
-    pub fn is_row_empty(&self, row: usize) -> bool {
-        for col in 0..self.grid.col_count() {
-            if !self.grid.get_value(row, col).is_empty() {
-                return false;
-            }
-        }
-        true
+    /// Check if a streaming load is in progress
+    pub fn is_stream_loading(&self) -> bool {
+        self.stream_load.is_some()
     }
 
-    /// Find all modified (edited) cells
-    pub fn find_modified_cells(&mut self) -> usize {
-        self.search.search_results.clear();
-        self.search.current_search_index = None;
+    /// Helper: Append parsed stream records as grid rows (the first CSV record names the
+    /// columns when the load has a header)
+    fn apply_stream_records(&mut self, records: Vec<StreamRecord>) {
+        let Some(mut load) = self.stream_load.take() else {
+            return;
+        };
 
-        for row in 0..self.grid.row_count() {
-            for col in 0..self.grid.col_count() {
-                if let Some(cell) = self.grid.get_cell(row, col) {
-                    if cell.modified {
-                        self.search.search_results.push((row, col));
+        let mut rows: Vec<Vec<(usize, CellValue)>> = Vec::new();
+        for record in records {
+            let index = load.records;
+            load.records += 1;
+            let skipped = |reason: &str| SkippedEntry { index, row: None, col: None, reason: reason.to_string() };
+            match record {
+                StreamRecord::Fields(names) if load.header && index == 0 => {
+                    self.ensure_stream_columns(names.len());
+                    for (col, name) in names.into_iter().enumerate() {
+                        self.grid.column_configs[col].display_name = name;
                     }
                 }
-            }
-        }
-
-        if !self.search.search_results.is_empty() {
-            self.search.current_search_index = Some(0);
-            let (row, col) = self.search.search_results[0];
-            self.select_single_cell(row, col);
-            self.ensure_cell_visible(row, col);
-        }
-
-        self.search.search_results.len()
-    }
-
-    /// Clear modified flags from all cells
-    pub fn clear_all_modified_flags(&mut self) {
-        for row in 0..self.grid.row_count() {
-            for col in 0..self.grid.col_count() {
-                if let Some(cell) = self.grid.get_cell_mut(row, col) {
-                    cell.modified = false;
+                StreamRecord::Fields(fields) => {
+                    self.ensure_stream_columns(fields.len());
+                    rows.push(fields.iter().enumerate().map(|(col, text)| (col, self.stream_text_value(text, col))).collect());
                 }
+                StreamRecord::Json(serde_json::Value::Array(values)) => {
+                    self.ensure_stream_columns(values.len());
+                    rows.push(values.iter().enumerate().map(|(col, value)| (col, self.json_to_cell_value(value, col))).collect());
+                }
+                StreamRecord::Json(serde_json::Value::Object(map)) => {
+                    let mut cells = Vec::with_capacity(map.len());
+                    for (key, value) in &map {
+                        let col = self.stream_column_for_key(key);
+                        cells.push((col, self.json_to_cell_value(value, col)));
+                    }
+                    rows.push(cells);
+                }
+                StreamRecord::Json(_) => load.report.skip(skipped("Expected an array or object")),
+                StreamRecord::Invalid(reason) => load.report.skip(skipped(&reason)),
             }
         }
-    }
-
-    /// Check if a cell is modified
-    pub fn is_cell_modified(&self, row: usize, col: usize) -> bool {
-        if let Some(cell) = self.grid.get_cell(row, col) {
-            cell.modified
-        } else {
-            false
-        }
-    }
 
-    /// Get count of modified cells
-    pub fn get_modified_cell_count(&self) -> usize {
-        let mut count = 0;
-        for row in 0..self.grid.row_count() {
-            for col in 0..self.grid.col_count() {
-                if let Some(cell) = self.grid.get_cell(row, col) {
-                    if cell.modified {
-                        count += 1;
+        if !rows.is_empty() {
+            let first_row = load.rows_loaded;
+            load.rows_loaded += rows.len();
+            self.grid.resize(load.rows_loaded, self.grid.col_count());
+            for (offset, cells) in rows.into_iter().enumerate() {
+                for (col, value) in cells {
+                    if value != CellValue::Empty {
+                        self.grid.set_value(first_row + offset, col, value);
+                        load.report.applied += 1;
                     }
                 }
             }
+            self.viewport.update_visible_range(&self.grid);
+            self.needs_full_render = true;
         }
-        count
+        self.stream_load = Some(load);
     }
 
-    /// Search for text in grid cells (case-insensitive by default)
-    pub fn search_text(&mut self, query: String) -> usize {
-        self.search_text_with_options(query, false, false)
+    /// Helper: Grow the grid to at least `cols` columns for a streamed row
+    fn ensure_stream_columns(&mut self, cols: usize) {
+        if cols > self.grid.col_count() {
+            self.grid.resize(self.grid.row_count(), cols);
+            self.needs_full_render = true;
+        }
     }
 
-    /// Search for text with options
-    pub fn search_text_with_options(&mut self, query: String, case_sensitive: bool, whole_word: bool) -> usize {
-        self.search.search_case_sensitive = case_sensitive;
-        self.search.search_whole_word = whole_word;
-        self.search.search_query = if case_sensitive { query.clone() } else { query.to_lowercase() };
-        self.search.search_results.clear();
-        self.search.current_search_index = None;
-
-        if query.is_empty() {
-            return 0;
+    /// Helper: Column of a JSON object key (by internal name); unknown keys get a new column
+    fn stream_column_for_key(&mut self, key: &str) -> usize {
+        if let Some(col) = self.grid.get_column_by_name(key) {
+            return col;
         }
-
-        // Search through all cells
-        for row in 0..self.grid.row_count() {
-            for col in 0..self.grid.col_count() {
-                let cell_text = self.grid.get_value_string(row, col);
-                let search_text = if case_sensitive { cell_text.clone() } else { cell_text.to_lowercase() };
-
-                let is_match = if whole_word {
-                    // Whole word matching: split by whitespace and check for exact match
-                    search_text.split_whitespace().any(|word| word == self.search.search_query)
-                } else {
-                    // Substring matching
-                    search_text.contains(&self.search.search_query)
-                };
-
-                if is_match {
-                    self.search.search_results.push((row, col));
-                }
-            }
+        let col = self.grid.col_count();
+        self.ensure_stream_columns(col + 1);
+        let config = &mut self.grid.column_configs[col];
+        config.internal_name = key.to_string();
+        config.display_name = key.to_string();
+        col
+    }
+
+    /// Helper: Cell value of a CSV field; typed columns convert like load_data_json,
+    /// text columns detect numbers and booleans like paste
+    fn stream_text_value(&self, text: &str, col: usize) -> CellValue {
+        match self.grid.get_column_config(col).map(|c| &c.data_type) {
+            Some(DataType::Text) | None => ClipboardOps::parse_value(text),
+            Some(_) if text.is_empty() => CellValue::Empty,
+            Some(_) => self.json_to_cell_value(&serde_json::Value::String(text.to_string()), col),
         }
+    }
 
-        if !self.search.search_results.is_empty() {
-            self.search.current_search_index = Some(0);
-            let (row, col) = self.search.search_results[0];
-            self.select_single_cell(row, col);
-            self.ensure_cell_visible(row, col);
-        }
+    // ========== Infinite Scroll API ==========
 
-        self.search.search_results.len()
+    /// Fire a `need_more_rows` event when the view scrolls within threshold_rows of the
+    /// last row; the host then loads the next page with append_rows() and set_cells_batch()
+    pub fn set_infinite_scroll(&mut self, enabled: bool, threshold_rows: usize) {
+        self.infinite_scroll = enabled.then_some(threshold_rows);
+        self.awaiting_more_rows = None;
     }
 
-    /// Move to next search result
-    pub fn search_next(&mut self) -> bool {
-        if self.search.search_results.is_empty() {
-            return false;
-        }
+    /// Enable need_more_rows with the number of rows before the end that triggers it
+    /// (same as set_infinite_scroll(true, rows_before_end)); also applies in virtual mode,
+    /// where the end is the row count given to the data source
+    pub fn set_fetch_threshold(&mut self, rows_before_end: usize) {
+        self.set_infinite_scroll(true, rows_before_end);
+    }
 
-        if let Some(current_idx) = self.search.current_search_index {
-            let next_idx = (current_idx + 1) % self.search.search_results.len();
-            self.search.current_search_index = Some(next_idx);
+    /// Check if infinite scroll is enabled
+    pub fn is_infinite_scroll(&self) -> bool {
+        self.infinite_scroll.is_some()
+    }
 
-            let (row, col) = self.search.search_results[next_idx];
-            self.select_single_cell(row, col);
-            self.ensure_cell_visible(row, col);
-            true
-        } else {
-            false
+    /// Add empty rows at the bottom, keeping scroll position, selection and styles
+    /// (not recorded for undo, like loading data)
+    pub fn append_rows(&mut self, count: usize) {
+        if count == 0 || self.pivot.is_some() {
+            return;
         }
+        let old_rows = self.grid.row_count();
+        self.grid.resize(old_rows + count, self.grid.col_count());
+        self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
+        self.events.push("rows_appended", serde_json::json!({ "start": old_rows, "count": count }));
     }
 
-    /// Move to previous search result
-    pub fn search_prev(&mut self) -> bool {
-        if self.search.search_results.is_empty() {
-            return false;
+    /// Helper: Fire need_more_rows when the view nears the bottom, then hold it back until
+    /// the row count differs from the count it fired at (rows appended, loaded or deleted)
+    fn check_infinite_scroll(&mut self) {
+        let Some(threshold) = self.infinite_scroll else {
+            return;
+        };
+        let row_count = self.grid.row_count();
+        if self.awaiting_more_rows == Some(row_count) {
+            return;
         }
-
-        if let Some(current_idx) = self.search.current_search_index {
-            let prev_idx = if current_idx == 0 {
-                self.search.search_results.len() - 1
-            } else {
-                current_idx - 1
-            };
-            self.search.current_search_index = Some(prev_idx);
-
-            let (row, col) = self.search.search_results[prev_idx];
-            self.select_single_cell(row, col);
-            self.ensure_cell_visible(row, col);
-            true
-        } else {
-            false
+        self.awaiting_more_rows = None;
+        if self.viewport.last_visible_row + threshold + 1 >= row_count {
+            self.awaiting_more_rows = Some(row_count);
+            let mode = if self.virtual_source.is_some() { "virtual" } else { "append" };
+            self.events.push(
+                "need_more_rows",
+                serde_json::json!({ "row_count": row_count, "last_visible_row": self.viewport.last_visible_row, "mode": mode }),
+            );
         }
     }
 
-    /// Search using regular expression
-    pub fn search_regex(&mut self, pattern: String, case_sensitive: bool) -> Result<usize, GridError> {
-        use regex::RegexBuilder;
+    // ========== Virtual Data Source API ==========
 
-        // Build regex with case sensitivity option
-        let regex = match RegexBuilder::new(&pattern)
-            .case_insensitive(!case_sensitive)
-            .build()
-        {
-            Ok(re) => re,
-            Err(e) => {
-                return Err(GridError::InvalidRegex {
-                    pattern: pattern.clone(),
-                    error: e.to_string(),
-                })
-            }
-        };
+    /// Switch to a server-side data source: the grid keeps only the rows around the
+    /// viewport and calls `fetch_rows(start, count)` for the rest as the view moves.
+    /// fetch_rows returns (a Promise of) an array of rows, each an array of cell values
+    /// in column order; rows still loading are drawn as placeholders.
+    /// Existing cells are cleared and the grid is resized to total_rows.
+    /// @param block_size - Rows per fetch (0 = 100)
+    pub fn set_virtual_data_source(&mut self, fetch_rows: js_sys::Function, total_rows: usize, block_size: usize) {
+        let block_size = if block_size == 0 { DEFAULT_BLOCK_SIZE } else { block_size };
+        self.virtual_source = Some(VirtualSource::new(fetch_rows, block_size, DEFAULT_CACHE_BLOCKS));
+        self.grid.clear();
+        self.grid.resize(total_rows, self.grid.col_count());
+        self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
+    }
 
-        self.search.search_query = pattern;
-        self.search.search_case_sensitive = case_sensitive;
-        self.search.search_whole_word = false; // Not applicable for regex
-        self.search.search_results.clear();
-        self.search.current_search_index = None;
+    /// Leave virtual mode; rows loaded so far stay in the grid
+    pub fn clear_virtual_data_source(&mut self) {
+        if self.virtual_source.take().is_some() {
+            self.needs_full_render = true;
+        }
+    }
 
-        // Search through all cells
-        for row in 0..self.grid.row_count() {
-            for col in 0..self.grid.col_count() {
-                let cell_text = self.grid.get_value_string(row, col);
+    /// Check if a virtual data source is active
+    pub fn is_virtual_mode(&self) -> bool {
+        self.virtual_source.is_some()
+    }
 
-                if regex.is_match(&cell_text) {
-                    self.search.search_results.push((row, col));
-                }
-            }
+    /// Set how many fetched blocks are kept (default: 20); older blocks are dropped
+    /// and fetched again when they scroll back into view
+    pub fn set_virtual_cache_blocks(&mut self, blocks: usize) {
+        if let Some(source) = self.virtual_source.as_mut() {
+            source.cache_blocks = blocks.max(1);
         }
+    }
 
-        if !self.search.search_results.is_empty() {
-            self.search.current_search_index = Some(0);
-            let (row, col) = self.search.search_results[0];
-            self.select_single_cell(row, col);
-            self.ensure_cell_visible(row, col);
+    /// Change the total row count reported by the server (cached rows are kept)
+    pub fn set_virtual_row_count(&mut self, total_rows: usize) {
+        if self.virtual_source.is_none() || self.pivot.is_some() {
+            return;
         }
+        self.grid.resize(total_rows, self.grid.col_count());
+        let (scroll_x, scroll_y) = (self.viewport.scroll_x, self.viewport.scroll_y);
+        self.viewport.set_scroll(scroll_x, scroll_y, &self.grid);
+        self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
+    }
 
-        Ok(self.search.search_results.len())
+    /// Drop every cached row and fetch the visible ones again (e.g. after the server
+    /// data changed); responses to earlier requests are ignored
+    pub fn refresh_virtual_rows(&mut self) {
+        if let Some(source) = self.virtual_source.as_mut() {
+            source.reset();
+            self.grid.clear();
+            self.needs_full_render = true;
+        }
     }
 
-    /// Validate regex pattern without performing search
-    pub fn validate_regex_pattern(&self, pattern: String) -> bool {
-        use regex::Regex;
-        Regex::new(&pattern).is_ok()
+    /// Check if a row's data is available (always true outside virtual mode)
+    pub fn is_row_loaded(&self, row: usize) -> bool {
+        self.virtual_source.as_ref().is_none_or(|source| source.is_row_loaded(row))
     }
 
-    /// Clear search results
-    pub fn clear_search(&mut self) {
-        self.search.search_query.clear();
-        self.search.search_results.clear();
-        self.search.current_search_index = None;
+    /// Set how many blocks are prefetched ahead of the scroll direction while the browser
+    /// is idle (default: 4; 0 turns prefetching off). Faster scrolling prefetches further,
+    /// up to this many blocks (and at most half the cache).
+    pub fn set_virtual_prefetch_blocks(&mut self, blocks: usize) {
+        if let Some(source) = self.virtual_source.as_mut() {
+            source.prefetch_blocks = blocks;
+        }
     }
 
-    /// Get search result count
-    pub fn get_search_result_count(&self) -> usize {
-        self.search.search_results.len()
+    /// Get block cache statistics as JSON ('' outside virtual mode):
+    /// {"hits", "misses", "hit_rate", "prefetched", "prefetch_hits", "prefetch_blocks",
+    /// "loaded_blocks", "pending_blocks", "velocity"} (velocity in rows per second)
+    /// A hit is a block already loaded when it scrolled into view; a miss showed placeholders
+    pub fn get_cache_stats(&self) -> String {
+        self.virtual_source.as_ref().map(|source| source.stats_json().to_string()).unwrap_or_default()
     }
 
-    /// Get current search index (1-based for display)
-    pub fn get_current_search_index(&self) -> i32 {
-        if let Some(idx) = self.search.current_search_index {
-            (idx + 1) as i32
-        } else {
-            -1
+    /// Reset the counters reported by get_cache_stats
+    pub fn reset_cache_stats(&mut self) {
+        if let Some(source) = self.virtual_source.as_mut() {
+            source.reset_stats();
         }
     }
 
-    /// Check if a cell is a search result
-    pub fn is_search_result(&self, row: usize, col: usize) -> bool {
-        self.search.search_results.contains(&(row, col))
+    /// Get the virtual source state as JSON
+    /// {"total_rows", "block_size", "cache_blocks", "loaded_blocks", "pending_blocks", "failed_blocks"}
+    /// ('' outside virtual mode)
+    pub fn get_virtual_status(&self) -> String {
+        self.virtual_source
+            .as_ref()
+            .map(|source| source.to_json(self.grid.row_count()).to_string())
+            .unwrap_or_default()
     }
 
-    /// Check if a cell is the current (active) search result
-    pub fn is_current_search_result(&self, row: usize, col: usize) -> bool {
-        if let Some(idx) = self.search.current_search_index {
-            if idx < self.search.search_results.len() {
-                self.search.search_results[idx] == (row, col)
-            } else {
-                false
+    /// Helper: Fetch the blocks around the viewport that are not loaded yet and drop
+    /// the least recently used ones beyond the cache size
+    fn sync_virtual_source(&mut self) {
+        let total_rows = self.grid.row_count();
+        let frozen_rows = self.grid.frozen_rows.min(total_rows);
+        let Some(source) = self.virtual_source.as_mut() else {
+            return;
+        };
+
+        // Visible rows plus one block above and below, and the frozen rows
+        let (first, last) = (self.viewport.first_visible_row, self.viewport.last_visible_row);
+        source.track_view(first, last.min(total_rows.saturating_sub(1)), hooks::now_ms());
+        let mut ranges = vec![(first.saturating_sub(source.block_size), last + source.block_size)];
+        if frozen_rows > 0 {
+            ranges.push((0, frozen_rows - 1));
+        }
+        let wanted = source.request(&ranges, total_rows);
+
+        let cols = self.grid.col_count();
+        for block in source.evict() {
+            let (start, count) = source.block_rows(block, total_rows);
+            for row in start..start + count {
+                for col in 0..cols {
+                    self.grid.remove_cell(row, col);
+                }
             }
-        } else {
-            false
         }
-    }
 
-    /// Replace current search result with new text
-    pub fn replace_current(&mut self, replacement: String) -> bool {
-        if let Some(idx) = self.search.current_search_index {
-            if idx < self.search.search_results.len() {
-                let (row, col) = self.search.search_results[idx];
+        for block in wanted {
+            self.fetch_virtual_block(block);
+        }
+        self.schedule_virtual_prefetch();
+    }
 
-                // Parse replacement as number if possible
-                if let Ok(num) = replacement.parse::<f64>() {
-                    self.grid.set_value(row, col, CellValue::Number(num));
-                } else {
-                    self.grid.set_value(row, col, CellValue::Text(replacement));
+    /// Helper: Prefetch the blocks ahead of the scroll direction once the browser is idle
+    /// (requestIdleCallback, or a short timeout where it is not supported)
+    fn schedule_virtual_prefetch(&mut self) {
+        let Some(source) = self.virtual_source.as_ref() else { return };
+        if source.prefetch_blocks == 0 || source.prefetch_scheduled {
+            return;
+        }
+        let Some(window) = web_sys::window() else { return };
+
+        let this = self.this.clone();
+        let callback = Closure::once_into_js(move || {
+            DataGrid::with_grid(&this, |grid| {
+                let total_rows = grid.grid.row_count();
+                let (first, last) = (grid.viewport.first_visible_row, grid.viewport.last_visible_row);
+                let Some(source) = grid.virtual_source.as_mut() else { return };
+                source.prefetch_scheduled = false;
+                for block in source.prefetch(first, last, total_rows) {
+                    grid.fetch_virtual_block(block);
                 }
-
-                // Move to next search result (or wrap around)
-                self.search_next();
-                true
-            } else {
-                false
-            }
-        } else {
-            false
+            });
+        });
+        let scheduled = window
+            .request_idle_callback(callback.unchecked_ref())
+            .map(|_| ())
+            .or_else(|_| window.set_timeout_with_callback_and_timeout_and_arguments_0(callback.unchecked_ref(), 50).map(|_| ()))
+            .is_ok();
+        if let Some(source) = self.virtual_source.as_mut() {
+            source.prefetch_scheduled = scheduled;
         }
     }
 
-    /// Replace all search results with new text
-    pub fn replace_all(&mut self, replacement: String) -> usize {
-        let count = self.search.search_results.len();
+    /// Helper: Call fetch_rows for a block and load the rows when they arrive
+    fn fetch_virtual_block(&mut self, block: usize) {
+        let Some(source) = self.virtual_source.as_ref() else {
+            return;
+        };
+        let (start, count) = source.block_rows(block, self.grid.row_count());
+        let generation = source.generation();
+        let result = source.fetch_rows.call2(&JsValue::NULL, &JsValue::from(start as f64), &JsValue::from(count as f64));
+
+        let this = self.this.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let rows = match result {
+                Ok(value) => wasm_bindgen_futures::JsFuture::from(js_sys::Promise::resolve(&value)).await,
+                Err(e) => Err(e),
+            };
+            DataGrid::with_grid(&this, |grid| grid.load_virtual_block(generation, block, rows));
+        });
+    }
 
-        // Replace all matching cells
-        for (row, col) in &self.search.search_results {
-            // Parse replacement as number if possible
-            if let Ok(num) = replacement.parse::<f64>() {
-                self.grid.set_value(*row, *col, CellValue::Number(num));
-            } else {
-                self.grid.set_value(*row, *col, CellValue::Text(replacement.clone()));
+    /// Helper: Store a fetched block (ignored if the source was reset since the request)
+    fn load_virtual_block(&mut self, generation: u64, block: usize, rows: Result<JsValue, JsValue>) {
+        let total_rows = self.grid.row_count();
+        let Some(source) = self.virtual_source.as_mut() else {
+            return;
+        };
+        let (start, count) = source.block_rows(block, total_rows);
+
+        let rows = rows
+            .map_err(|e| e.as_string().unwrap_or_else(|| format!("{:?}", e)))
+            .and_then(|value| js_sys::JSON::stringify(&value).map_err(|_| "Rows are not serializable".to_string()))
+            .and_then(|json| {
+                serde_json::from_str::<Vec<Vec<serde_json::Value>>>(&String::from(json))
+                    .map_err(|e| format!("Expected an array of row arrays: {}", e))
+            });
+        let rows = match rows {
+            Ok(rows) => rows,
+            Err(reason) => {
+                log::warn!("[Virtual] fetch_rows({}, {}) failed: {}", start, count, reason);
+                source.fail(generation, block);
+                self.events.push("virtual_fetch_failed", serde_json::json!({ "start": start, "count": count, "reason": reason }));
+                return;
             }
+        };
+        if !source.complete(generation, block) {
+            return;
         }
 
-        // Clear search results after replacing all
-        self.clear_search();
-        count
+        for (offset, values) in rows.iter().take(count).enumerate() {
+            for (col, value) in values.iter().take(self.grid.col_count()).enumerate() {
+                let cell_value = self.json_to_cell_value(value, col);
+                if cell_value != CellValue::Empty {
+                    self.grid.set_value(start + offset, col, cell_value);
+                }
+            }
+        }
+        self.events.push("virtual_rows_loaded", serde_json::json!({ "start": start, "count": rows.len().min(count) }));
+        self.needs_full_render = true;
     }
 
-    /// Replace in selection only
-    pub fn replace_in_selection(&mut self, search: String, replacement: String, case_sensitive: bool) -> usize {
-        let mut count = 0;
-        let search_str = if case_sensitive { search.clone() } else { search.to_lowercase() };
-
-        // Get list of selected cells
-        let selected: Vec<(usize, usize)> = self.selection.selected_cells.iter().cloned().collect();
-
-        for (row, col) in selected {
-            let cell_text = self.grid.get_value_string(row, col);
-            let search_text = if case_sensitive { cell_text.clone() } else { cell_text.to_lowercase() };
+    /// Helper: Draw placeholders over visible rows whose block is still being fetched
+    /// Returns true if any were drawn
+    fn render_virtual_placeholders(&self) -> bool {
+        let Some(source) = self.virtual_source.as_ref() else {
+            return false;
+        };
+        let last_row = self.viewport.last_visible_row.min(self.grid.row_count().saturating_sub(1));
+        let frozen_rows = self.grid.frozen_rows.min(self.grid.row_count());
+        let rows: Vec<usize> = (0..frozen_rows)
+            .chain(self.viewport.first_visible_row.max(frozen_rows)..=last_row)
+            .filter(|&row| row < self.grid.row_count() && !self.grid.is_row_hidden(row) && source.is_row_pending(row))
+            .collect();
+        if rows.is_empty() {
+            return false;
+        }
 
-            if search_text.contains(&search_str) {
-                // Parse replacement as number if possible
-                if let Ok(num) = replacement.parse::<f64>() {
-                    self.grid.set_value(row, col, CellValue::Number(num));
-                } else {
-                    self.grid.set_value(row, col, CellValue::Text(replacement.clone()));
-                }
-                count += 1;
-            }
+        let phase = ((hooks::now_ms() % 1200.0) / 1200.0) as f32;
+        self.text_renderer.render_placeholder_rows(&self.grid, &self.viewport, &rows, phase);
+        true
+    }
+
+    // ========== Master-Detail API ==========
+
+    /// Expand a row into a detail panel: `height` pixels are reserved below the row and
+    /// the row detail callback is told where to place the host's DOM content
+    /// Panels follow their row through sorting and inserts; filtered rows hide them
+    pub fn expand_row_detail(&mut self, row: usize, height: f32) -> Result<(), JsValue> {
+        if row >= self.grid.row_count() {
+            return Err(GridError::OutOfBounds { row, col: 0 }.into());
         }
+        if !height.is_finite() || height <= 0.0 {
+            return Err(GridError::OperationError { message: locale::format("error.detail_height", &[("height", &height)]) }.into());
+        }
+        let extent = self.grid.row_extent(row);
+        self.grid.set_row_detail(row, Some(height));
+        self.animate_row_extent(row, extent - self.grid.row_extent(row));
+        self.after_detail_change();
+        Ok(())
+    }
 
-        count
+    /// Collapse a row's detail panel
+    pub fn collapse_row_detail(&mut self, row: usize) {
+        if self.is_row_detail_expanded(row) {
+            let extent = self.grid.row_extent(row);
+            self.grid.set_row_detail(row, None);
+            self.animate_row_extent(row, extent - self.grid.row_extent(row));
+            self.after_detail_change();
+        }
     }
 
-    /// Sort by column
-    pub fn sort_by_column(&mut self, col: usize, ascending: bool) {
-        self.grid.sort_by_column(col, ascending);
-        self.clear_selection();
-        self.viewport.update_visible_range(&self.grid);
+    /// Check if a row's detail panel is expanded
+    pub fn is_row_detail_expanded(&self, row: usize) -> bool {
+        self.grid.detail_rows().contains(&row)
     }
 
-    /// Toggle sort on column (click column header)
-    pub fn toggle_column_sort(&mut self, col: usize) {
-        // If already sorting by this column, toggle direction
-        if self.grid.sort_column == Some(col) {
-            self.sort_by_column(col, !self.grid.sort_ascending);
-        } else {
-            // Otherwise, sort ascending
-            self.sort_by_column(col, true);
-        }
+    /// Get the rows with an expanded detail panel
+    pub fn get_expanded_detail_rows(&self) -> Vec<usize> {
+        self.grid.detail_rows()
     }
 
-    /// Get sort state for a column as JSON object
-    /// Returns: "{\"is_sorted\": true/false, \"is_ascending\": true/false}"
-    pub fn get_column_sort_state(&self, col: usize) -> String {
-        let (is_sorted, is_ascending) = if self.grid.sort_column == Some(col) {
-            (true, self.grid.sort_ascending)
-        } else {
-            (false, true)
-        };
-        format!(r#"{{"is_sorted":{},"is_ascending":{}}}"#, is_sorted, is_ascending)
+    /// Set the callback that positions detail content (None removes it)
+    /// Called with {row, row_id, expanded: true, visible, rect: {x, y, width, height},
+    /// clip} when a panel is expanded, moves, or scrolls in or out of view, and with
+    /// {row_id, expanded: false} when it is collapsed or its row deleted.
+    /// rect is the whole panel and clip its visible part (null when scrolled out),
+    /// in canvas pixels like get_cell_rect.
+    pub fn set_row_detail_callback(&mut self, callback: Option<js_sys::Function>) {
+        self.details.set_callback(callback);
+        self.needs_full_render = true;
     }
 
-    /// Add column to multi-column sort (for Shift+Click)
-    pub fn add_multi_column_sort(&mut self, col: usize, ascending: bool) {
-        self.grid.add_sort_column(col, ascending);
-        self.clear_selection();
+    /// Helper: Keep the scroll position valid and redraw after panels change
+    fn after_detail_change(&mut self) {
+        let (scroll_x, scroll_y) = (self.viewport.scroll_x, self.viewport.scroll_y);
+        self.viewport.set_scroll(scroll_x, scroll_y, &self.grid);
         self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
     }
 
-    /// Toggle column in multi-column sort
-    pub fn toggle_multi_column_sort(&mut self, col: usize) {
-        // Check if column is already in sort list
-        let existing = self.grid.sort_columns.iter()
-            .find(|(c, _)| *c == col)
-            .map(|(_, asc)| *asc);
+    // ========== Row Animation API ==========
 
-        match existing {
-            Some(true) => {
-                // Currently ascending, switch to descending
-                self.add_multi_column_sort(col, false);
-            }
-            Some(false) => {
-                // Currently descending, remove from sort
-                self.grid.sort_columns.retain(|(c, _)| *c != col);
-                if self.grid.sort_columns.is_empty() {
-                    self.grid.sort_column = None;
-                } else {
-                    self.grid.sort_by_multiple_columns();
-                }
-                self.viewport.update_visible_range(&self.grid);
-            }
-            None => {
-                // Not in sort list, add as ascending
-                self.add_multi_column_sort(col, true);
-            }
+    /// Set the length of row height transitions in ms (0 turns them off; default 150,
+    /// also the `row_animation_ms` option). Expanding or collapsing a detail panel or
+    /// pivot group and inserting or deleting rows then slide the rows below into place
+    /// instead of jumping. Transitions run with the built-in render loop and are
+    /// skipped when the user prefers reduced motion.
+    pub fn set_row_animation_ms(&mut self, duration_ms: f64) {
+        self.row_animation.duration = duration_ms.max(0.0);
+        if self.row_animation.duration == 0.0 && self.row_animation.is_active() {
+            self.row_animation.finish(&mut self.grid);
+            self.after_detail_change();
         }
     }
 
-    /// Clear multi-column sort
-    pub fn clear_multi_column_sort(&mut self) {
-        self.grid.clear_multi_column_sort();
-        self.viewport.update_visible_range(&self.grid);
+    /// Get the length of row height transitions in ms (0 = off)
+    pub fn get_row_animation_ms(&self) -> f64 {
+        self.row_animation.duration
     }
 
-    /// Get multi-column sort state as JSON array of [col, ascending] pairs
-    /// Returns: "[[col1, 1], [col2, 0], ...]" where 1=ascending, 0=descending
-    pub fn get_multi_column_sort_state(&self) -> String {
-        let state: Vec<Vec<u32>> = self.grid.sort_columns.iter()
-            .map(|(col, asc)| vec![*col as u32, if *asc { 1 } else { 0 }])
-            .collect();
-        serde_json::to_string(&state).unwrap_or_else(|_| "[]".to_string())
+    /// Check if a row height transition is in progress
+    pub fn is_row_animating(&self) -> bool {
+        self.row_animation.is_active()
     }
 
-    /// Check if a column is in multi-column sort
-    /// Returns JSON: "{\"is_sorted\": bool, \"is_ascending\": bool, \"sort_priority\": number}"
-    pub fn get_column_multi_sort_state(&self, col: usize) -> String {
-        if let Some((priority, (_, ascending))) = self.grid.sort_columns.iter()
-            .enumerate()
-            .find(|(_, (c, _))| *c == col)
+    /// Helper: Ease a row's extent change (`shift` = old extent - new extent) when
+    /// transitions are on, the render loop drives frames and motion is not reduced
+    fn animate_row_extent(&mut self, row: usize, shift: f32) {
+        self.animate_row_extents(&[(row, shift)]);
+    }
+
+    /// Animate several rows' extent changes together (one reduced-motion check for the batch)
+    fn animate_row_extents(&mut self, shifts: &[(usize, f32)]) {
+        if shifts.iter().all(|&(_, shift)| shift == 0.0)
+            || self.row_animation.duration == 0.0
+            || self.render_loop.is_none()
+            || row_animation::prefers_reduced_motion()
         {
-            format!(r#"{{"is_sorted":true,"is_ascending":{},"sort_priority":{}}}"#, ascending, priority)
-        } else {
-            r#"{"is_sorted":false,"is_ascending":true,"sort_priority":-1}"#.to_string()
+            return;
+        }
+        let by_id: Vec<(usize, f32)> = shifts
+            .iter()
+            .filter(|&&(_, shift)| shift != 0.0)
+            .filter_map(|&(row, shift)| Some((self.grid.row_id(row)?, shift)))
+            .collect();
+        if !by_id.is_empty() {
+            self.row_animation.start_many(&mut self.grid, &by_id);
+            self.needs_full_render = true;
         }
     }
 
-    /// Freeze first N rows
-    pub fn freeze_rows(&mut self, count: usize) {
-        self.grid.frozen_rows = count.min(self.grid.row_count());
-    }
+    // ========== Ghost Row/Column API ==========
 
-    /// Freeze first N columns
-    pub fn freeze_cols(&mut self, count: usize) {
-        self.grid.frozen_cols = count.min(self.grid.col_count());
+    /// Show or hide the ghost row (trailing "+" row for quick data entry)
+    /// Editing a ghost cell and committing a value appends a real row
+    pub fn set_ghost_row(&mut self, enabled: bool) {
+        self.grid.show_ghost_row = enabled;
+        self.refresh_after_preview_change();
     }
 
-    /// Get frozen row count
-    pub fn get_frozen_rows(&self) -> usize {
-        self.grid.frozen_rows
+    /// Show or hide the ghost column (trailing "+" column for quick data entry)
+    /// Editing a ghost cell and committing a value appends a real column
+    pub fn set_ghost_column(&mut self, enabled: bool) {
+        self.grid.show_ghost_col = enabled;
+        self.refresh_after_preview_change();
     }
 
-    /// Set frozen row count
-    pub fn set_frozen_rows(&mut self, count: usize) {
-        self.grid.frozen_rows = count;
+    /// Check if the ghost row is shown
+    pub fn has_ghost_row(&self) -> bool {
+        self.grid.show_ghost_row
     }
 
-    /// Get frozen column count
-    pub fn get_frozen_cols(&self) -> usize {
-        self.grid.frozen_cols
+    /// Check if the ghost column is shown
+    pub fn has_ghost_column(&self) -> bool {
+        self.grid.show_ghost_col
     }
 
-    /// Set frozen column count
-    pub fn set_frozen_cols(&mut self, count: usize) {
-        self.grid.frozen_cols = count;
+    /// Check if (row, col) is a ghost cell (row == row_count or col == col_count)
+    pub fn is_ghost_cell(&self, row: usize, col: usize) -> bool {
+        self.grid.is_ghost_cell(row, col)
     }
 
-    /// Undo last edit action
-    pub fn undo(&mut self) -> bool {
-        log::info!("[UndoRedo] Undo requested - stack size: {}", self.undo_redo.undo_stack.len());
-        let result = self.undo_redo.undo(&mut self.grid, &mut self.viewport);
-        log::info!("[UndoRedo] Undo completed: {}, remaining: {}", result, self.undo_redo.undo_stack.len());
-        result
-    }
+    // ========== Auto-Expansion API ==========
 
-    /// Redo last undone action
-    pub fn redo(&mut self) -> bool {
-        log::info!("[UndoRedo] Redo requested - stack size: {}", self.undo_redo.redo_stack.len());
-        let result = self.undo_redo.redo(&mut self.grid, &mut self.viewport);
-        log::info!("[UndoRedo] Redo completed: {}, remaining: {}", result, self.undo_redo.redo_stack.len());
-        result
+    /// Grow the grid when a paste or template fill extends beyond its bounds
+    /// Growth is limited to max_rows x max_cols; data beyond the cap is dropped and
+    /// reported with a "data_truncated" event. Each resize queues a "grid_expanded"
+    /// event and is undone together with the paste/fill.
+    /// @param enabled - false restores the default (truncate at the grid bounds)
+    pub fn set_auto_expand(&mut self, enabled: bool, max_rows: usize, max_cols: usize) {
+        self.grid.auto_expand = if enabled { Some((max_rows, max_cols)) } else { None };
     }
 
-    /// Check if undo is available
-    pub fn can_undo(&self) -> bool {
-        !self.undo_redo.undo_stack.is_empty()
+    /// Check if auto-expansion is enabled
+    pub fn is_auto_expand_enabled(&self) -> bool {
+        self.grid.auto_expand.is_some()
     }
 
-    /// Check if redo is available
-    pub fn can_redo(&self) -> bool {
-        !self.undo_redo.redo_stack.is_empty()
-    }
+    // ========== Chart Data API ==========
 
-    /// Get undo stack size
-    pub fn get_undo_count(&self) -> usize {
-        self.undo_redo.undo_stack.len()
-    }
+    /// Extract aligned x/y series for charting libraries
+    /// Hidden (filtered) rows are skipped and rows follow the current sort order
+    /// @param x_col - Column used for x values (labels keep their type)
+    /// @param y_cols_json - JSON array of y columns, e.g. "[1, 2]"
+    /// @param scope - "all", "visible" (rows in viewport) or "selection" (rows with selected cells)
+    /// Returns: {"rows": [...], "x": [...], "series": [{"col": 1, "name": "Sales", "values": [10, null, ...]}]}
+    pub fn get_series_json(&self, x_col: usize, y_cols_json: &str, scope: &str) -> Result<String, JsValue> {
+        let y_cols: Vec<usize> = serde_json::from_str(y_cols_json)
+            .map_err(|e| GridError::InvalidJson { error: e.to_string() })?;
 
-    /// Get redo stack size
-    pub fn get_redo_count(&self) -> usize {
-        self.undo_redo.redo_stack.len()
-    }
+        let col_count = self.grid.col_count();
+        if let Some(&col) = std::iter::once(&x_col).chain(y_cols.iter()).find(|c| **c >= col_count) {
+            return Err(GridError::OutOfBounds { row: 0, col }.into());
+        }
 
-    /// Clear undo history (useful after initial data load)
-    pub fn clear_undo_history(&mut self) {
-        self.undo_redo.clear_undo_history();
-    }
+        let scope = SeriesScope::parse(scope)?;
+        let rows = series::series_rows(&self.grid, &self.viewport, &self.selection, scope);
+        let json = series::series_json(&self.grid, &rows, x_col, &y_cols);
 
-    /// Clear redo history
-    pub fn clear_redo_history(&mut self) {
-        self.undo_redo.clear_redo_history();
+        Ok(json.to_string())
     }
 
-    /// Auto-fit column width to content
-    pub fn auto_fit_column(&mut self, col: usize) {
+    /// Extract one column as a Float64Array for charting (non-numeric values become NaN)
+    /// @param col - Column index
+    /// @param scope - "all", "visible" or "selection" (same rows as get_series_json)
+    pub fn get_series_values(&self, col: usize, scope: &str) -> Result<Vec<f64>, JsValue> {
         if col >= self.grid.col_count() {
-            return;
+            return Err(GridError::OutOfBounds { row: 0, col }.into());
         }
 
-        let padding = 20.0; // Padding on both sides
-        let min_width = 50.0;
-        let max_width = 400.0_f32;
+        let scope = SeriesScope::parse(scope)?;
+        let rows = series::series_rows(&self.grid, &self.viewport, &self.selection, scope);
 
-        // Measure all cells in this column
-        let mut max_text_width = 0.0_f32;
+        Ok(rows
+            .iter()
+            .map(|row| series::value_as_f64(&self.grid.get_value(*row, col)).unwrap_or(f64::NAN))
+            .collect())
+    }
 
-        for row in 0..self.grid.row_count() {
-            let text = self.grid.get_value_string(row, col);
-            if !text.is_empty() {
-                let text_width = self.text_renderer.measure_text(&text);
-                max_text_width = max_text_width.max(text_width);
-            }
-        }
+    // ========== Event API ==========
 
-        // Also measure column header
-        let header_text = Grid::get_col_name(col);
-        let header_width = self.text_renderer.measure_text(&header_text);
-        max_text_width = max_text_width.max(header_width);
+    /// Enable or disable column_data_changed events
+    /// When enabled, every data change is tracked per column and reported by poll_events()
+    /// as {"type": "column_data_changed", "col": 2, "ranges": [[start_row, end_row], ...]}
+    /// (inclusive, coalesced row ranges) so derived computations can update incrementally
+    pub fn set_column_change_events(&mut self, enabled: bool) {
+        self.grid.set_column_change_tracking(enabled);
+    }
 
-        // Calculate optimal width with padding
-        let optimal_width = (max_text_width + padding).clamp(min_width, max_width);
+    /// Check if column_data_changed events are enabled
+    pub fn is_column_change_events_enabled(&self) -> bool {
+        self.grid.is_column_change_tracking()
+    }
 
-        self.grid.set_col_width(col, optimal_width);
-        self.viewport.update_visible_range(&self.grid);
+    /// Check if there are events waiting to be polled
+    pub fn has_pending_events(&self) -> bool {
+        !self.events.is_empty() || self.grid.has_column_changes()
     }
 
-    /// Auto-fit all columns to content
-    pub fn auto_fit_all_columns(&mut self) {
-        for col in 0..self.grid.col_count() {
-            self.auto_fit_column(col);
-        }
+    /// Take all pending events
+    /// Returns: JSON array of event objects, each with a "type" field
+    pub fn poll_events(&mut self) -> String {
+        let changes = self.grid.take_column_changes();
+        self.events.push_column_changes(changes);
+        self.events.drain_json()
     }
 
-    /// Set all columns to equal width
-    pub fn set_all_columns_equal_width(&mut self, width: f32) {
-        for col in 0..self.grid.col_count() {
-            self.grid.set_col_width(col, width);
-        }
-        self.viewport.update_visible_range(&self.grid);
+    // ========== Preview Mode API ==========
+
+    /// Enable row sampling preview: display only every n-th row (0, n, 2n, ...)
+    /// Hidden rows collapse to zero height; data is kept, so turning preview off
+    /// restores the full view without reloading. n <= 1 disables preview mode.
+    pub fn set_preview_mode(&mut self, n: usize) {
+        self.grid.preview_mode = if n > 1 { Some(PreviewMode::EveryNth(n)) } else { None };
+        self.refresh_after_preview_change();
     }
 
-    /// Filter column by text (case-insensitive contains)
-    pub fn filter_column_by_text(&mut self, col: usize, text: String) {
-        let filter_text = text.to_lowercase();
-        self.grid.apply_column_filter(col, |value| {
-            let cell_text = match value {
-                CellValue::Text(t) => t.to_lowercase(),
-                CellValue::Number(n) => n.to_string(),
-                CellValue::Boolean(b) => b.to_string(),
-                CellValue::Date(d) => d.to_lowercase(),
-                CellValue::Empty => String::new(),
-            };
-            cell_text.contains(&filter_text)
-        });
-        self.viewport.update_visible_range(&self.grid);
+    /// Enable preview of only the first n rows
+    pub fn set_preview_first_rows(&mut self, n: usize) {
+        self.grid.preview_mode = Some(PreviewMode::FirstN(n));
+        self.refresh_after_preview_change();
     }
 
-    /// Filter column by empty cells
-    pub fn filter_column_show_non_empty(&mut self, col: usize) {
-        self.grid.apply_column_filter(col, |value| {
-            !matches!(value, CellValue::Empty)
-        });
-        self.viewport.update_visible_range(&self.grid);
+    /// Disable preview mode and show all rows
+    pub fn clear_preview_mode(&mut self) {
+        self.grid.preview_mode = None;
+        self.refresh_after_preview_change();
     }
 
-    /// Clear all column filters
-    pub fn clear_column_filters(&mut self) {
-        self.grid.clear_filters();
-        self.viewport.update_visible_range(&self.grid);
+    /// Check if preview mode is active
+    pub fn is_preview_mode(&self) -> bool {
+        self.grid.preview_mode.is_some()
     }
 
-    /// Check if a row is filtered (hidden)
-    pub fn is_row_filtered(&self, row: usize) -> bool {
-        self.grid.is_row_filtered(row)
+    /// Get number of rows hidden by preview mode
+    pub fn get_preview_hidden_count(&self) -> usize {
+        self.grid.preview_hidden_count()
     }
 
-    /// Get visible row count
-    pub fn get_visible_row_count(&self) -> usize {
-        self.grid.visible_row_count()
+    /// Re-clamp scroll and visible range after preview mode (or a ghost row/column) changes
+    /// the row layout
+    fn refresh_after_preview_change(&mut self) {
+        self.viewport.set_scroll(self.viewport.scroll_x, self.viewport.scroll_y, &self.grid);
+        self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
     }
 }
 
@@ -2731,8 +8762,9 @@ pub fn init() {
     #[cfg(feature = "console_error_panic_hook")]
     console_error_panic_hook::set_once();
 
-    // Initialize console_log for proper logging
-    console_log::init_with_level(log::Level::Debug).expect("Failed to initialize logger");
+    // Log to the console, keeping recent lines for dump_diagnostics_json()
+    // at warn level; set_log_level turns on the per-click and per-edit debug output
+    features::diagnostics::init_logger(log::LevelFilter::Warn).expect("Failed to initialize logger");
 
     log::info!("DataGrid5 initialized");
 }
@@ -2770,7 +8802,7 @@ impl DataGrid {
     }
 }
 
-#[wasm_bindgen]
+#[js_export(DataGridHandle)]
 impl DataGrid {
     /// Start performance benchmark (returns start time)
     pub fn benchmark_start(&self) -> f64 {
@@ -2909,7 +8941,7 @@ impl DataGrid {
     pub fn get_memory_usage(&self) -> usize {
         let cell_count = self.grid.row_count() * self.grid.col_count();
         let selected_cells_mem = self.selection.selected_cells.len() * std::mem::size_of::<(usize, usize)>();
-        let search_results_mem = self.search.search_results.len() * std::mem::size_of::<(usize, usize)>();
+        let search_results_mem = self.search.search_results.memory_bytes();
         let undo_stack_mem = self.undo_redo.undo_stack.capacity() * std::mem::size_of::<EditAction>();
         let redo_stack_mem = self.undo_redo.redo_stack.capacity() * std::mem::size_of::<EditAction>();
         let dirty_cells_mem = self.dirty_cells.len() * std::mem::size_of::<(usize, usize)>();
@@ -2939,6 +8971,104 @@ impl DataGrid {
         }
     }
 
+    // ========== Logging API ==========
+
+    /// Set how much the grid logs to the console: "off", "error", "warn" (default), "info"
+    /// or "debug". The level is shared by every grid on the page; lines below it are
+    /// neither printed nor kept for dump_diagnostics_json().
+    pub fn set_log_level(&mut self, level: &str) -> Result<(), JsValue> {
+        log::set_max_level(features::diagnostics::parse_log_level(level)?);
+        Ok(())
+    }
+
+    /// Get the current log level name
+    pub fn get_log_level(&self) -> String {
+        features::diagnostics::log_level_name().to_string()
+    }
+
+    /// Dump the grid's state for bug reports as JSON: version, options, dimensions,
+    /// memory and performance stats, renderer info, active features, undo/search/
+    /// selection counts and the last 50 log lines. Cell values are not included.
+    pub fn dump_diagnostics_json(&self) -> String {
+        #[cfg(feature = "persistence")]
+        let persistence_enabled = self.auto_save.is_some();
+        #[cfg(not(feature = "persistence"))]
+        let persistence_enabled = false;
+
+        let compiled_features: Vec<&str> = [
+            ("console_error_panic_hook", cfg!(feature = "console_error_panic_hook")),
+            ("persistence", cfg!(feature = "persistence")),
+            ("time-travel", cfg!(feature = "time-travel")),
+        ]
+        .into_iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| name)
+        .collect();
+
+        serde_json::json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "user_agent": web_sys::window().and_then(|w| w.navigator().user_agent().ok()),
+            "options": self.options,
+            "dimensions": {
+                "rows": self.grid.row_count(),
+                "cols": self.grid.col_count(),
+                "stored_cells": self.grid.iter_cells().count(),
+                "visible_rows": self.grid.visible_row_count(),
+                "frozen": [self.grid.frozen_rows, self.grid.frozen_cols],
+                "canvas": [self.viewport.canvas_width, self.viewport.canvas_height],
+                "scroll": [self.viewport.scroll_x, self.viewport.scroll_y],
+                "zoom": self.viewport.zoom,
+            },
+            "memory": {
+                "estimated_bytes": self.get_memory_usage(),
+                "wasm_memory_bytes": wasm_bindgen::memory()
+                    .dyn_into::<js_sys::WebAssembly::Memory>()
+                    .ok()
+                    .and_then(|m| m.buffer().dyn_into::<js_sys::ArrayBuffer>().ok())
+                    .map(|b| b.byte_length()),
+            },
+            "performance": {
+                "fps": self.calculate_fps(),
+                "render_time_ms": self.render_time_ms,
+                "frame_count": self.frame_count,
+                "dirty_cells": self.dirty_cells.len(),
+            },
+            "renderer": self.renderer.renderer_info(),
+            "features": {
+                "compiled": compiled_features,
+                "render_loop": self.render_loop.is_some(),
+                "wheel_momentum": self.wheel_momentum,
+                "persistence": persistence_enabled,
+                "column_summary": self.summary_mode.as_str(),
+                "paste_policy": self.paste_policy.as_str(),
+                "copy_format": self.copy_format.as_str(),
+                "auto_expand": self.grid.auto_expand.is_some(),
+                "preview_mode": self.grid.preview_mode.is_some(),
+                "ghost_row": self.grid.show_ghost_row,
+                "ghost_col": self.grid.show_ghost_col,
+                "readonly": self.grid.readonly,
+                "unique_strict": self.grid.unique_strict,
+                "validation_strict": self.grid.validation_strict,
+                "header_levels": self.grid.header_levels,
+                "sorted": !self.grid.active_sort_keys().is_empty(),
+                "filtered_rows": self.grid.filtered_rows().len(),
+            },
+            "counts": {
+                "undo": self.undo_redo.undo_stack.len(),
+                "redo": self.undo_redo.redo_stack.len(),
+                "undo_group_open": self.undo_redo.is_grouping(),
+                "undo_suspended": self.undo_redo.is_suspended(),
+                "search_results": self.search.search_results.len(),
+                "selected_cells": self.selection.selected_cells.len(),
+                "modified_cells": self.grid.iter_cells().filter(|(_, cell)| cell.modified).count(),
+                "pending_events": self.events.len(),
+                "editing": self.editing.is_editing,
+            },
+            "recent_log": features::diagnostics::recent_log_lines(),
+        })
+        .to_string()
+    }
+
     // ============================================================================
     // Worker Thread Support for Background Data Processing
     // ============================================================================
@@ -3014,9 +9144,13 @@ impl DataGrid {
 
     /// Import processed data from worker thread
     /// Accepts JSON array: [{"row":0,"col":0,"value":"text","type":"text"}, ...]
-    pub fn import_worker_result(&mut self, result_json: &str) -> Result<usize, JsValue> {
+    /// Pass the get_data_version() taken at export time as expected_version to reject the
+    /// result (version_conflict error) if the grid was edited while the worker ran
+    pub fn import_worker_result(&mut self, result_json: &str, expected_version: Option<f64>) -> Result<usize, JsValue> {
+        self.check_flat()?;
+        self.check_data_version(expected_version)?;
         let data: Vec<serde_json::Value> = serde_json::from_str(result_json)
-            .map_err(|e| JsValue::from_str(&format!("Invalid JSON: {}", e)))?;
+            .map_err(|e| GridError::InvalidJson { error: format!("worker result: {}", e) })?;
 
         let mut updated_count = 0;
 
@@ -3071,9 +9205,9 @@ impl DataGrid {
     /// ascending_json: JSON array of booleans, e.g. "[true, false]"
     pub fn prepare_sort_data(&self, sort_columns_json: &str, ascending_json: &str) -> Result<String, JsValue> {
         let sort_columns: Vec<usize> = serde_json::from_str(sort_columns_json)
-            .map_err(|e| JsValue::from_str(&format!("Invalid sort_columns JSON: {}", e)))?;
+            .map_err(|e| GridError::InvalidJson { error: format!("sort_columns: {}", e) })?;
         let ascending: Vec<bool> = serde_json::from_str(ascending_json)
-            .map_err(|e| JsValue::from_str(&format!("Invalid ascending JSON: {}", e)))?;
+            .map_err(|e| GridError::InvalidJson { error: format!("ascending: {}", e) })?;
 
         Ok(format!(
             r#"{{"data":{},"sort_columns":{},"ascending":{}}}"#,
@@ -3085,16 +9219,17 @@ impl DataGrid {
 
     /// Apply sorted row indices from worker result
     /// Takes array of row indices representing the new order
-    pub fn apply_sorted_indices(&mut self, indices_json: &str) -> Result<(), JsValue> {
+    /// A stale expected_version (see import_worker_result) rejects it with a version_conflict error
+    pub fn apply_sorted_indices(&mut self, indices_json: &str, expected_version: Option<f64>) -> Result<(), JsValue> {
+        self.check_data_version(expected_version)?;
         let indices: Vec<usize> = serde_json::from_str(indices_json)
-            .map_err(|e| JsValue::from_str(&format!("Invalid indices JSON: {}", e)))?;
+            .map_err(|e| GridError::InvalidJson { error: format!("indices: {}", e) })?;
 
         if indices.len() != self.grid.row_count() {
-            return Err(JsValue::from_str(&format!(
-                "Indices length {} does not match row count {}",
-                indices.len(),
-                self.grid.row_count()
-            )));
+            return Err(GridError::OperationError {
+                message: locale::format("error.indices_length", &[("length", &indices.len()), ("rows", &self.grid.row_count())]),
+            }
+            .into());
         }
 
         // Create a copy of all rows
@@ -3122,6 +9257,287 @@ impl DataGrid {
     }
 }
 
+
+// ============================================================================
+// IndexedDB Persistence (feature "persistence")
+// ============================================================================
+
+#[cfg(feature = "persistence")]
+#[js_export(DataGridHandle)]
+impl DataGrid {
+    /// Auto-save layout (size, column widths, row heights, frozen panes) and cell values to
+    /// IndexedDB under `key`, once no change happened for `debounce_ms`
+    /// Changes are picked up when the grid renders (render() or the render loop)
+    pub fn enable_persistence(&mut self, key: String, debounce_ms: u32) {
+        self.disable_persistence();
+        self.auto_save = Some(persistence::AutoSave::new(key, debounce_ms, self.data_grid()));
+    }
+
+    /// Stop auto-saving (a pending save is cancelled; stored state is kept)
+    pub fn disable_persistence(&mut self) {
+        if let Some(mut auto_save) = self.auto_save.take() {
+            auto_save.cancel();
+        }
+    }
+
+    /// Check if auto-save is enabled
+    pub fn is_persistence_enabled(&self) -> bool {
+        self.auto_save.is_some()
+    }
+
+    /// Save the current state to IndexedDB under `key` now
+    /// Returns a Promise resolving when the state is stored
+    pub fn save_persisted(&mut self, key: String) -> js_sys::Promise {
+        let state = features::snapshot::capture_state(self.data_grid()).to_string();
+        let signature = persistence::AutoSave::signature(self.data_grid());
+        if let Some(auto_save) = self.auto_save.as_mut().filter(|a| a.key == key) {
+            auto_save.cancel();
+            auto_save.saved = signature;
+        }
+
+        wasm_bindgen_futures::future_to_promise(async move {
+            persistence::save_to_indexed_db(key, state).await?;
+            Ok(JsValue::TRUE)
+        })
+    }
+
+    /// Restore the state saved under `key` (call on startup, before enable_persistence)
+    /// Returns a Promise resolving to true if a state was restored, false if none was saved
+    /// Undo history is cleared, since it refers to the replaced data
+    pub fn restore_persisted(&mut self, key: String) -> js_sys::Promise {
+        let this = self.this.clone();
+
+        wasm_bindgen_futures::future_to_promise(async move {
+            let Some(state) = persistence::load_from_indexed_db(key).await? else {
+                return Ok(JsValue::FALSE);
+            };
+            let state: serde_json::Value = serde_json::from_str(&state)
+                .map_err(|e| GridError::InvalidJson { error: format!("persisted state: {}", e) })?;
+            DataGrid::with_grid(&this, |grid| grid.apply_persisted_state(&state))
+                .unwrap_or_else(|| Err(GridError::OperationError { message: locale::text("error.grid_destroyed") }))?;
+            Ok(JsValue::TRUE)
+        })
+    }
+
+    /// Helper: Replace the grid with a state restore_persisted loaded
+    fn apply_persisted_state(&mut self, state: &serde_json::Value) -> Result<(), GridError> {
+        self.check_flat()?;
+        features::snapshot::restore_state(&mut self.grid, state)?;
+        self.undo_redo.clear_undo_history();
+        self.undo_redo.clear_redo_history();
+        self.selection.clear_selection();
+        self.reapply_derived_state();
+        if let Some(auto_save) = self.auto_save.as_mut() {
+            auto_save.saved = persistence::AutoSave::signature(&self.grid);
+        }
+        Ok(())
+    }
+
+    /// Delete the state saved under `key`
+    pub fn clear_persisted(&mut self, key: String) -> js_sys::Promise {
+        wasm_bindgen_futures::future_to_promise(async move {
+            persistence::delete_from_indexed_db(key).await?;
+            Ok(JsValue::TRUE)
+        })
+    }
+}
+
+#[cfg(feature = "persistence")]
+impl DataGrid {
+    /// Helper: (Re)start the auto-save debounce timer when the grid changed since the last save
+    fn schedule_auto_save(&mut self) {
+        let signature = persistence::AutoSave::signature(self.data_grid());
+        let Some(auto_save) = self.auto_save.as_mut() else { return };
+        if auto_save.saved == signature {
+            return;
+        }
+        auto_save.saved = signature;
+        auto_save.cancel();
+
+        let this = self.this.clone();
+        let callback = Closure::once_into_js(move || {
+            let Some((key, state)) = DataGrid::with_grid(&this, |grid| {
+                let auto_save = grid.auto_save.as_mut()?;
+                auto_save.timeout_id = None;
+                Some((auto_save.key.clone(), features::snapshot::capture_state(grid.data_grid()).to_string()))
+            })
+            .flatten() else {
+                return;
+            };
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Err(e) = persistence::save_to_indexed_db(key, state).await {
+                    log::warn!("[Persistence] Auto-save failed: {:?}", e);
+                }
+            });
+        });
+
+        let Some(auto_save) = self.auto_save.as_mut() else { return };
+        auto_save.timeout_id = web_sys::window().and_then(|window| {
+            window
+                .set_timeout_with_callback_and_timeout_and_arguments_0(
+                    callback.unchecked_ref(),
+                    auto_save.debounce_ms as i32,
+                )
+                .ok()
+        });
+    }
+}
+
+// ============================================================================
+// Time-Travel Debugging (feature "time-travel")
+// ============================================================================
+
+#[cfg(feature = "time-travel")]
+#[js_export(DataGridHandle)]
+impl DataGrid {
+    /// Start recording edits, undo/redo, sorts and filters, with a full state checkpoint
+    /// every `interval` commands. Options: {"interval": 25, "max_checkpoints": 20}
+    /// ("" for the defaults). The first checkpoint is the current state.
+    pub fn enable_time_travel(&mut self, options_json: &str) -> Result<(), JsValue> {
+        let options: serde_json::Value = if options_json.trim().is_empty() {
+            serde_json::json!({})
+        } else {
+            serde_json::from_str(options_json).map_err(|e| GridError::InvalidJson { error: format!("time travel options: {}", e) })?
+        };
+        let interval = options["interval"].as_u64().unwrap_or(25) as usize;
+        let max_checkpoints = options["max_checkpoints"].as_u64().unwrap_or(20) as usize;
+
+        let mut recorder = TimeTravel::new(interval, max_checkpoints);
+        recorder.checkpoint(self.data_grid(), js_sys::Date::now());
+        self.time_travel = Some(recorder);
+        self.undo_redo.journal = Some(Vec::new());
+        Ok(())
+    }
+
+    /// Stop recording and drop the checkpoints and commands
+    pub fn disable_time_travel(&mut self) {
+        self.time_travel = None;
+        self.undo_redo.journal = None;
+    }
+
+    /// Check if time-travel recording is on
+    pub fn is_time_travel_enabled(&self) -> bool {
+        self.time_travel.is_some()
+    }
+
+    /// Save a checkpoint of the current state now and return its ID
+    pub fn time_travel_checkpoint(&mut self) -> Result<usize, JsValue> {
+        self.sync_time_travel();
+        let recorder = self.time_travel.as_mut().ok_or(GridError::from(locale::text("error.time_travel_disabled")))?;
+        let grid = self.pivot.as_ref().map_or(&self.grid, |view| &view.flat.grid);
+        Ok(recorder.checkpoint(grid, js_sys::Date::now()))
+    }
+
+    /// Get the recording: {"interval", "checkpoints": [{"id", "seq", "time"}],
+    /// "commands": [{"seq", "time", "command", ...}]}. A checkpoint holds the state after
+    /// the commands numbered below its seq.
+    pub fn get_time_travel_json(&mut self) -> Result<String, JsValue> {
+        self.sync_time_travel();
+        let recorder = self.time_travel.as_ref().ok_or(GridError::from(locale::text("error.time_travel_disabled")))?;
+        Ok(recorder.timeline_json().to_string())
+    }
+
+    /// Restore the grid to a checkpoint. The state being left is saved as a new checkpoint
+    /// first, so travel_to(present) returns to it. Undo history and selection are cleared,
+    /// as with deserialize_state. Returns {"id", "seq", "time", "present", "commands"} with
+    /// the commands recorded between this checkpoint and the next one.
+    pub fn travel_to(&mut self, checkpoint_id: usize) -> Result<String, JsValue> {
+        self.check_flat()?;
+        self.sync_time_travel();
+        let recorder = self.time_travel.as_mut().ok_or(GridError::from(locale::text("error.time_travel_disabled")))?;
+        let checkpoint = recorder.get(checkpoint_id).cloned().ok_or_else(|| GridError::OperationError {
+            message: locale::format("error.no_checkpoint", &[("id", &checkpoint_id)]),
+        })?;
+        let commands = recorder.commands_after(checkpoint_id);
+        let present = recorder.checkpoint(&self.grid, js_sys::Date::now());
+        recorder.record(serde_json::json!({ "command": "travel_to", "checkpoint": checkpoint_id }), js_sys::Date::now());
+
+        features::snapshot::restore_state(&mut self.grid, &checkpoint.state)?;
+        self.undo_redo.clear_undo_history();
+        self.undo_redo.clear_redo_history();
+        self.selection.clear_selection();
+        self.reapply_derived_state();
+        Ok(serde_json::json!({
+            "id": checkpoint.id,
+            "seq": checkpoint.seq,
+            "time": checkpoint.time,
+            "present": present,
+            "commands": commands,
+        })
+        .to_string())
+    }
+}
+
+#[cfg(feature = "time-travel")]
+impl DataGrid {
+    /// Helper: Move journaled commands into the recorder and checkpoint when one is due
+    fn sync_time_travel(&mut self) {
+        let Some(recorder) = self.time_travel.as_mut() else { return };
+        let commands = self.undo_redo.journal.as_mut().map(std::mem::take).unwrap_or_default();
+        if commands.is_empty() {
+            return;
+        }
+        let now = js_sys::Date::now();
+        for command in commands {
+            recorder.record(command, now);
+        }
+        if recorder.needs_checkpoint() {
+            let grid = self.pivot.as_ref().map_or(&self.grid, |view| &view.flat.grid);
+            recorder.checkpoint(grid, now);
+        }
+    }
+}
+
+// ============================================================================
+// Arrow IPC Interop (feature "arrow")
+// ============================================================================
+
+#[cfg(feature = "arrow")]
+#[js_export(DataGridHandle)]
+impl DataGrid {
+    /// Replace the grid with Arrow IPC data (stream or file format), e.g. a DuckDB-WASM
+    /// query result or an `apache-arrow` table serialized with `tableToIPC`.
+    /// Each field becomes a column named after it; integer, float and decimal fields are
+    /// numbers, dates and timestamps are dates, booleans stay booleans and other types are
+    /// shown as text. Undo history and the selection are reset.
+    /// Returns {"rows": N, "cols": N, "columns": [{"name", "arrow_type", "data_type"}]}
+    pub fn load_arrow(&mut self, bytes: &[u8]) -> Result<String, JsValue> {
+        self.check_flat()?;
+        if self.virtual_source.is_some() {
+            return Err(GridError::OperationError { message: locale::text("error.arrow_virtual") }.into());
+        }
+        let schema = features::arrow::load_ipc(&mut self.grid, bytes)?;
+
+        self.undo_redo.clear_undo_history();
+        self.undo_redo.clear_redo_history();
+        self.selection.clear_selection();
+        self.viewport.set_scroll(0.0, 0.0, &self.grid);
+        self.viewport.update_visible_range(&self.grid);
+        self.needs_full_render = true;
+        self.fit_columns_on_load();
+
+        let columns: Vec<serde_json::Value> = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                serde_json::json!({
+                    "name": field.name(),
+                    "arrow_type": field.data_type().to_string(),
+                    "data_type": features::arrow::grid_type(field.data_type()).as_str(),
+                })
+            })
+            .collect();
+        Ok(serde_json::json!({ "rows": self.grid.row_count(), "cols": self.grid.col_count(), "columns": columns }).to_string())
+    }
+
+    /// Export every row as Arrow IPC stream data (a Uint8Array for `tableFromIPC` or
+    /// DuckDB-WASM `insertArrowFromIPCStream`); columns are named by their header and typed
+    /// from their data type, falling back to Utf8 when a value does not fit
+    pub fn export_arrow(&self) -> Result<Vec<u8>, JsValue> {
+        Ok(features::arrow::write_ipc(&self.grid)?)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,70 @@
+use super::{Canvas2DRenderer, WebGLRenderer};
+use crate::core::{Grid, Viewport};
+use crate::features::selection::SelectionState;
+use crate::GridError;
+use web_sys::HtmlCanvasElement;
+
+/// Renderer for grid lines, backgrounds and borders (the layer under the text canvas)
+pub enum GridRenderer {
+    WebGL(WebGLRenderer),
+    Canvas2D(Canvas2DRenderer),
+}
+
+impl GridRenderer {
+    /// Create the renderer selected by the `renderer` option:
+    /// "auto" (WebGL, falling back to Canvas 2D when no WebGL context is available),
+    /// "webgl" (fail without WebGL) or "canvas2d"
+    pub fn new(canvas: &HtmlCanvasElement, renderer: &str) -> Result<Self, GridError> {
+        match renderer.to_lowercase().as_str() {
+            "auto" | "" => match WebGLRenderer::new(canvas) {
+                Ok(webgl) => Ok(GridRenderer::WebGL(webgl)),
+                Err(e) => {
+                    log::warn!("{}; falling back to the Canvas 2D renderer", e);
+                    Ok(GridRenderer::Canvas2D(Canvas2DRenderer::new(canvas)?))
+                }
+            },
+            "webgl" => Ok(GridRenderer::WebGL(WebGLRenderer::new(canvas)?)),
+            "canvas2d" | "canvas" => Ok(GridRenderer::Canvas2D(Canvas2DRenderer::new(canvas)?)),
+            other => Err(GridError::unknown_value("renderer", other, &["auto", "webgl", "canvas2d"])),
+        }
+    }
+
+    /// Name of the active renderer ("webgl" or "canvas2d")
+    pub fn name(&self) -> &'static str {
+        match self {
+            GridRenderer::WebGL(_) => "webgl",
+            GridRenderer::Canvas2D(_) => "canvas2d",
+        }
+    }
+
+    pub fn resize(&mut self, width: f32, height: f32) {
+        match self {
+            GridRenderer::WebGL(r) => r.resize(width, height),
+            GridRenderer::Canvas2D(r) => r.resize(width, height),
+        }
+    }
+
+    /// Describe the renderer for diagnostics
+    pub fn renderer_info(&self) -> serde_json::Value {
+        let mut info = match self {
+            GridRenderer::WebGL(r) => r.renderer_info(),
+            GridRenderer::Canvas2D(r) => r.renderer_info(),
+        };
+        info["backend"] = serde_json::json!(self.name());
+        info
+    }
+
+    pub fn render(&self, grid: &Grid, viewport: &Viewport, selection: &SelectionState) {
+        match self {
+            GridRenderer::WebGL(r) => r.render(grid, viewport, selection),
+            GridRenderer::Canvas2D(r) => r.render(grid, viewport, selection),
+        }
+    }
+
+    pub fn render_cells(&self, grid: &Grid, viewport: &Viewport, selection: &SelectionState, cells: &[(usize, usize)]) {
+        match self {
+            GridRenderer::WebGL(r) => r.render_cells(grid, viewport, selection, cells),
+            GridRenderer::Canvas2D(r) => r.render_cells(grid, viewport, selection, cells),
+        }
+    }
+}
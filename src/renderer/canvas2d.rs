@@ -0,0 +1,336 @@
+use super::text::u32_to_rgba_string;
+use crate::core::{Grid, SectionBreak, Viewport};
+use crate::features::selection::SelectionState;
+use crate::GridError;
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+/// Colors matching the WebGL renderer's output
+const BACKGROUND_COLOR: &str = "#ffffff";
+const GRID_LINE_COLOR: &str = "rgb(204, 204, 204)"; // Light gray
+const CELL_BORDER_COLOR: &str = "rgb(153, 153, 153)"; // Dark gray
+const SELECTION_COLOR: &str = "rgb(204, 230, 255)"; // Light blue selection
+const SECTION_BREAK_COLOR: &str = "rgb(64, 64, 64)"; // Near black
+
+/// Canvas 2D grid renderer: draws the same grid lines, backgrounds and borders as
+/// WebGLRenderer, for browsers and webviews where WebGL is unavailable
+pub struct Canvas2DRenderer {
+    context: CanvasRenderingContext2d,
+
+    // Canvas dimensions (device pixels)
+    canvas_width: f32,
+    canvas_height: f32,
+}
+
+impl Canvas2DRenderer {
+    /// Create a new Canvas 2D renderer
+    pub fn new(canvas: &HtmlCanvasElement) -> Result<Self, GridError> {
+        let context = canvas
+            .get_context("2d")
+            .map_err(|_| GridError::RenderInitFailed {
+                error: "Failed to get 2D context".to_string(),
+            })?
+            .ok_or_else(|| GridError::RenderInitFailed {
+                error: "2D context is None".to_string(),
+            })?
+            .dyn_into::<CanvasRenderingContext2d>()
+            .map_err(|_| GridError::RenderInitFailed {
+                error: "Failed to cast to CanvasRenderingContext2d".to_string(),
+            })?;
+
+        Ok(Self {
+            context,
+            canvas_width: canvas.width() as f32,
+            canvas_height: canvas.height() as f32,
+        })
+    }
+
+    /// Resize the renderer
+    pub fn resize(&mut self, width: f32, height: f32) {
+        self.canvas_width = width;
+        self.canvas_height = height;
+    }
+
+    /// Describe the renderer for diagnostics
+    pub fn renderer_info(&self) -> serde_json::Value {
+        serde_json::json!({
+            "canvas_width": self.canvas_width,
+            "canvas_height": self.canvas_height,
+        })
+    }
+
+    /// Clear the canvas
+    pub fn clear(&self) {
+        let _ = self.context.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        self.context.set_fill_style_str(BACKGROUND_COLOR);
+        self.context.fill_rect(0.0, 0.0, self.canvas_width as f64, self.canvas_height as f64);
+    }
+
+    /// Render the grid with freeze support (same regions and order as WebGLRenderer::render)
+    pub fn render(&self, grid: &Grid, viewport: &Viewport, selection: &SelectionState) {
+        self.clear();
+
+        let frozen_rows = grid.frozen_rows;
+        let frozen_cols = grid.frozen_cols;
+        let first_row = frozen_rows.max(viewport.first_visible_row);
+        let first_col = frozen_cols.max(viewport.first_visible_col);
+        let last_row = viewport.last_visible_row.min(grid.row_count().saturating_sub(1)) + 1;
+        let last_col = viewport.last_visible_col.min(grid.col_count().saturating_sub(1)) + 1;
+
+        // Region 1: Frozen rows × Frozen cols (top-left) - no scroll
+        if frozen_rows > 0 && frozen_cols > 0 {
+            self.translate_region(grid, viewport, 0, 0);
+            self.render_region(grid, selection, 0, frozen_rows, 0, frozen_cols);
+        }
+
+        // Region 2: Frozen rows × Scrollable cols (top-right) - horizontal scroll
+        if frozen_rows > 0 {
+            self.translate_region(grid, viewport, 0, first_col);
+            self.render_region(grid, selection, 0, frozen_rows, first_col, last_col);
+        }
+
+        // Region 3: Scrollable rows × Frozen cols (bottom-left) - vertical scroll
+        if frozen_cols > 0 {
+            self.translate_region(grid, viewport, first_row, 0);
+            self.render_region(grid, selection, first_row, last_row, 0, frozen_cols);
+        }
+
+        // Region 4: Scrollable rows × Scrollable cols (bottom-right) - both scroll
+        self.translate_region(grid, viewport, first_row, first_col);
+        self.render_region(grid, selection, first_row, last_row, first_col, last_col);
+
+        let _ = self.context.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+    }
+
+    /// Re-render only the given cells (differential rendering)
+    /// Each cell is cleared and redrawn inside a clip rectangle, like the WebGL scissor path
+    pub fn render_cells(
+        &self,
+        grid: &Grid,
+        viewport: &Viewport,
+        selection: &SelectionState,
+        cells: &[(usize, usize)],
+    ) {
+        for &(row, col) in cells {
+            if grid.is_row_hidden(row) {
+                continue;
+            }
+
+            let Some((x, y, width, height)) = viewport.cell_canvas_rect(row, col, grid) else {
+                continue;
+            };
+
+            // Include the 1px grid lines on the cell edges (clip is in device pixels)
+            let zoom = viewport.zoom;
+            let x1 = ((x - 1.0) * zoom).max(0.0).floor() as f64;
+            let y1 = ((y - 1.0) * zoom).max(0.0).floor() as f64;
+            let x2 = ((x + width + 1.0) * zoom).min(self.canvas_width).ceil() as f64;
+            let y2 = ((y + height + 1.0) * zoom).min(self.canvas_height).ceil() as f64;
+
+            self.context.save();
+            let _ = self.context.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+            self.context.begin_path();
+            self.context.rect(x1, y1, x2 - x1, y2 - y1);
+            self.context.clip();
+            self.context.set_fill_style_str(BACKGROUND_COLOR);
+            self.context.fill_rect(x1, y1, x2 - x1, y2 - y1);
+
+            self.translate_region(grid, viewport, row, col);
+            self.render_region(grid, selection, row, row + 1, col, col + 1);
+            self.context.restore();
+        }
+    }
+
+    /// Map grid pixels (offset by the translation) onto the zoomed canvas
+    /// Lines are kept 1 device pixel wide at any zoom
+    fn translate(&self, viewport: &Viewport, translate_x: f32, translate_y: f32) {
+        let zoom = viewport.zoom as f64;
+        self.context.set_line_width(1.0 / zoom);
+        let _ = self.context.set_transform(
+            zoom,
+            0.0,
+            0.0,
+            zoom,
+            translate_x as f64 * zoom,
+            translate_y as f64 * zoom,
+        );
+    }
+
+    /// Translate for a region starting at (row_start, col_start), like
+    /// WebGLRenderer::render_region: the region scrolls along the axes on which it starts
+    /// past the frozen rows/columns, and its coordinates are measured from its first row
+    fn translate_region(&self, grid: &Grid, viewport: &Viewport, row_start: usize, col_start: usize) {
+        let header_offset_x = if grid.show_headers { grid.row_header_width } else { 0.0 };
+        let header_offset_y = if grid.show_headers { grid.col_header_height } else { 0.0 };
+        let scroll_x = if col_start < grid.frozen_cols { 0.0 } else { viewport.scroll_x };
+        let scroll_y = if row_start < grid.frozen_rows { 0.0 } else { viewport.scroll_y };
+        let translate_y = (grid.row_y_position(row_start) - scroll_y) as f32 + header_offset_y;
+        self.translate(viewport, -scroll_x + header_offset_x, translate_y);
+    }
+
+    /// Render a specific region of the grid (see translate_region)
+    fn render_region(
+        &self,
+        grid: &Grid,
+        selection: &SelectionState,
+        row_start: usize,
+        row_end: usize,
+        col_start: usize,
+        col_end: usize,
+    ) {
+        if row_start >= row_end || col_start >= col_end {
+            return;
+        }
+
+        self.render_grid_lines_region(grid, row_start, row_end, col_start, col_end);
+        self.render_cell_backgrounds_region(grid, selection, row_start, row_end, col_start, col_end);
+        self.render_cell_borders_region(grid, row_start, row_end, col_start, col_end);
+        self.render_section_breaks_region(grid, row_start, row_end, col_start, col_end);
+    }
+
+    /// Render grid lines for a specific region
+    fn render_grid_lines_region(
+        &self,
+        grid: &Grid,
+        row_start: usize,
+        row_end: usize,
+        col_start: usize,
+        col_end: usize,
+    ) {
+        let line_width = self.context.line_width();
+        let x_start = grid.col_x_position(col_start) as f64;
+        let x_end = if col_end < grid.col_count() {
+            grid.col_x_position(col_end)
+        } else {
+            grid.total_width()
+        } as f64;
+        let origin = grid.row_y_position(row_start);
+        let y_start = 0.0;
+        let y_end = if row_end < grid.row_count() {
+            grid.row_y_position(row_end)
+        } else {
+            grid.total_height()
+        } - origin;
+
+        self.context.begin_path();
+
+        // Vertical lines (columns)
+        for col in col_start..=col_end.min(grid.col_count()) {
+            if col > 0 && grid.col_section_break(col - 1) == Some(SectionBreak::Hidden) {
+                continue;
+            }
+            let x = grid.col_x_position(col) as f64 + line_width / 2.0;
+            self.context.move_to(x, y_start);
+            self.context.line_to(x, y_end);
+        }
+
+        // Horizontal lines (rows)
+        for row in row_start..=row_end.min(grid.row_count()) {
+            if row > 0 && grid.row_section_break(row - 1) == Some(SectionBreak::Hidden) {
+                continue;
+            }
+            let y = grid.row_y_position(row) - origin + line_width / 2.0;
+            self.context.move_to(x_start, y);
+            self.context.line_to(x_end, y);
+        }
+
+        self.stroke(GRID_LINE_COLOR);
+    }
+
+    /// Render cell backgrounds for a specific region
+    fn render_cell_backgrounds_region(
+        &self,
+        grid: &Grid,
+        selection: &SelectionState,
+        row_start: usize,
+        row_end: usize,
+        col_start: usize,
+        col_end: usize,
+    ) {
+        let origin = grid.row_y_position(row_start);
+        for row in row_start..row_end.min(grid.row_count()) {
+            if grid.is_row_hidden(row) {
+                continue;
+            }
+
+            for col in col_start..col_end.min(grid.col_count()) {
+                // Cell color wins over the selection color, as in the WebGL renderer
+                let bg_color = match grid.get_cell(row, col).and_then(|cell| cell.bg_color) {
+                    Some(color) => u32_to_rgba_string(color),
+                    None if selection.is_selected(row, col) => SELECTION_COLOR.to_string(),
+                    None => continue,
+                };
+
+                self.context.set_fill_style_str(&bg_color);
+                self.context.fill_rect(
+                    grid.col_x_position(col) as f64,
+                    grid.row_y_position(row) - origin,
+                    grid.col_width(col) as f64,
+                    grid.row_height(row) as f64,
+                );
+            }
+        }
+    }
+
+    /// Render cell borders for a specific region
+    fn render_cell_borders_region(
+        &self,
+        grid: &Grid,
+        row_start: usize,
+        row_end: usize,
+        col_start: usize,
+        col_end: usize,
+    ) {
+        let offset = self.context.line_width() / 2.0;
+        let origin = grid.row_y_position(row_start);
+        self.context.begin_path();
+
+        for row in row_start..row_end.min(grid.row_count()) {
+            if grid.is_row_hidden(row) {
+                continue;
+            }
+
+            for col in col_start..col_end.min(grid.col_count()) {
+                let x = grid.col_x_position(col) as f64;
+                let y = grid.row_y_position(row) - origin;
+                let right = x + grid.col_width(col) as f64 + offset;
+                let bottom = y + grid.row_height(row) as f64 + offset;
+
+                // Right border
+                if grid.col_section_break(col) != Some(SectionBreak::Hidden) {
+                    self.context.move_to(right, y);
+                    self.context.line_to(right, bottom);
+                }
+
+                // Bottom border
+                if grid.row_section_break(row) != Some(SectionBreak::Hidden) {
+                    self.context.move_to(x, bottom);
+                    self.context.line_to(right, bottom);
+                }
+            }
+        }
+
+        self.stroke(CELL_BORDER_COLOR);
+    }
+
+    /// Render section separators for a specific region
+    fn render_section_breaks_region(
+        &self,
+        grid: &Grid,
+        row_start: usize,
+        row_end: usize,
+        col_start: usize,
+        col_end: usize,
+    ) {
+        self.context.set_fill_style_str(SECTION_BREAK_COLOR);
+        for [x, y, width, height] in grid.section_break_rects(row_start, row_end, col_start, col_end) {
+            self.context.fill_rect(x as f64, y as f64, width as f64, height as f64);
+        }
+    }
+
+    /// Stroke the current path
+    fn stroke(&self, color: &str) {
+        self.context.set_stroke_style_str(color);
+        self.context.stroke();
+    }
+}
@@ -1,7 +1,15 @@
+pub mod backend;
+pub mod canvas2d;
+pub mod render_loop;
+pub mod render_state;
 pub mod shader;
 pub mod text;
 pub mod webgl;
 
 // pub use shader::ShaderProgram;
+pub use backend::GridRenderer;
+pub use canvas2d::Canvas2DRenderer;
+pub use render_loop::RenderLoop;
+pub use render_state::RenderState;
 pub use text::TextRenderer;
 pub use webgl::WebGLRenderer;
@@ -0,0 +1,73 @@
+use crate::GridError;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+
+type FrameCallback = Rc<RefCell<Option<Closure<dyn FnMut(f64)>>>>;
+
+/// requestAnimationFrame-driven loop calling a frame handler once per display frame
+///
+/// The loop is stopped (pending frame cancelled, callback released) by `stop()` or
+/// when the loop is dropped.
+pub struct RenderLoop {
+    callback: FrameCallback,
+    frame_id: Rc<Cell<Option<i32>>>,
+}
+
+impl RenderLoop {
+    /// Start the loop; `on_frame` receives the rAF timestamp in ms
+    pub fn start(mut on_frame: impl FnMut(f64) + 'static) -> Result<Self, GridError> {
+        let window = web_sys::window().ok_or_else(|| GridError::RenderInitFailed {
+            error: "No window available for requestAnimationFrame".to_string(),
+        })?;
+
+        let callback: FrameCallback = Rc::new(RefCell::new(None));
+        let frame_id = Rc::new(Cell::new(None));
+
+        let next_callback = callback.clone();
+        let next_frame_id = frame_id.clone();
+        let next_window = window.clone();
+
+        *callback.borrow_mut() = Some(Closure::wrap(Box::new(move |timestamp: f64| {
+            on_frame(timestamp);
+
+            // Schedule the next frame unless the loop was stopped
+            if let Some(closure) = next_callback.borrow().as_ref() {
+                let id = next_window
+                    .request_animation_frame(closure.as_ref().unchecked_ref())
+                    .ok();
+                next_frame_id.set(id);
+            }
+        }) as Box<dyn FnMut(f64)>));
+
+        let id = callback
+            .borrow()
+            .as_ref()
+            .map(|closure| window.request_animation_frame(closure.as_ref().unchecked_ref()))
+            .transpose()
+            .map_err(|_| GridError::RenderInitFailed {
+                error: "requestAnimationFrame failed".to_string(),
+            })?;
+        frame_id.set(id);
+
+        Ok(Self { callback, frame_id })
+    }
+
+    /// Stop the loop
+    pub fn stop(&mut self) {
+        if let Some(id) = self.frame_id.take() {
+            if let Some(window) = web_sys::window() {
+                let _ = window.cancel_animation_frame(id);
+            }
+        }
+        // Drop the closure (breaks the self-reference cycle)
+        self.callback.borrow_mut().take();
+    }
+}
+
+impl Drop for RenderLoop {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,59 @@
+use crate::core::{Grid, Viewport};
+use crate::features::{search::SearchState, selection::{HeaderFocus, SelectionState}};
+
+/// Snapshot of everything that affects layout or whole-frame appearance
+///
+/// Differential rendering is only safe when the snapshot taken at the last render
+/// equals the current one; any difference (scroll, resize, selection, search,
+/// structure) forces a full render.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderState {
+    canvas_size: (f32, f32),
+    zoom: f32,
+    scroll: (f32, f64),
+    visible_range: (usize, usize, usize, usize),
+    dimensions: (usize, usize),
+    total_size: (f32, f64),
+    header_size: (bool, f32, f32),
+    frozen: (usize, usize),
+    visible_rows: usize,
+    sort: (Option<usize>, bool, usize),
+    column_groups: usize,
+    ghosts: (bool, bool),
+    selection: (u64, Option<(usize, usize)>, Option<HeaderFocus>),
+    search: (usize, Option<usize>),
+    preview: bool,
+}
+
+impl RenderState {
+    /// Capture the current render state
+    pub fn capture(
+        grid: &Grid,
+        viewport: &Viewport,
+        selection: &SelectionState,
+        search: &SearchState,
+    ) -> Self {
+        Self {
+            canvas_size: (viewport.canvas_width, viewport.canvas_height),
+            zoom: viewport.zoom,
+            scroll: (viewport.scroll_x, viewport.scroll_y),
+            visible_range: (
+                viewport.first_visible_row,
+                viewport.last_visible_row,
+                viewport.first_visible_col,
+                viewport.last_visible_col,
+            ),
+            dimensions: (grid.row_count(), grid.col_count()),
+            total_size: (grid.total_width(), grid.total_height()),
+            header_size: (grid.show_headers, grid.row_header_width, grid.col_header_height),
+            frozen: (grid.frozen_rows, grid.frozen_cols),
+            visible_rows: grid.visible_row_count(),
+            sort: (grid.sort_column, grid.sort_ascending, grid.sort_columns.len()),
+            column_groups: grid.column_groups.len(),
+            ghosts: (grid.show_ghost_row, grid.show_ghost_col),
+            selection: (selection.revision(), selection.selection_anchor, selection.header_focus),
+            search: (search.search_results.len(), search.current_search_index),
+            preview: grid.preview_mode.is_some(),
+        }
+    }
+}
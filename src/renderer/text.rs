@@ -1,11 +1,17 @@
-use crate::core::{Grid, Viewport};
-use crate::features::selection::SelectionState;
+use crate::core::{Grid, SortClick, Viewport};
+use crate::features::editing::TextBuffer;
+use crate::features::locale;
+use crate::features::selection::{HeaderFocus, SelectionState};
+use crate::features::search::SearchResults;
+use crate::features::summary::{ColumnSummary, SummaryMode};
+use crate::features::watermark::Watermark;
 use crate::GridError;
+use std::collections::HashSet;
 use wasm_bindgen::JsCast;
 use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
 
 /// Convert u32 RGBA color (0xRRGGBBAA) to CSS rgba() string
-fn u32_to_rgba_string(color: u32) -> String {
+pub(super) fn u32_to_rgba_string(color: u32) -> String {
     let r = ((color >> 24) & 0xFF) as u8;
     let g = ((color >> 16) & 0xFF) as u8;
     let b = ((color >> 8) & 0xFF) as u8;
@@ -13,6 +19,161 @@ fn u32_to_rgba_string(color: u32) -> String {
     format!("rgba({}, {}, {}, {})", r, g, b, a)
 }
 
+/// Text color that stays readable on a cell background: white when the background
+/// (blended over the white canvas) is dark, None when the default dark text already contrasts
+fn contrast_text_color(bg_color: u32) -> Option<&'static str> {
+    let alpha = (bg_color & 0xFF) as f32 / 255.0;
+    let channel = |shift: u32| {
+        // Blend over white, then linearize sRGB (WCAG relative luminance)
+        let c = (((bg_color >> shift) & 0xFF) as f32 * alpha + 255.0 * (1.0 - alpha)) / 255.0;
+        if c <= 0.039_28 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+    };
+    let luminance = 0.2126 * channel(24) + 0.7152 * channel(16) + 0.0722 * channel(8);
+    // Below this luminance white text has the higher contrast ratio
+    (luminance < 0.179).then_some("#FFFFFF")
+}
+
+/// Column header text: the name with the sort direction appended, unless the sort
+/// arrow is drawn in its own click zone (render_sort_zone)
+fn header_label(grid: &Grid, col: usize, name: String) -> String {
+    if grid.sort_click == SortClick::Arrow || grid.sort_column != Some(col) {
+        name
+    } else if grid.sort_ascending {
+        format!("{} ▲", name)
+    } else {
+        format!("{} ▼", name)
+    }
+}
+
+/// Header background and border colors
+const HEADER_BG: &str = "#f0f0f0";
+const HEADER_BORDER: &str = "#cccccc";
+/// Double line in the headers where hidden columns/rows collapse
+const HIDDEN_MARKER_COLOR: &str = "#5f6368";
+/// Arrow zone glyph of a sortable column that is not sorted (SortClick::Arrow)
+const SORT_ZONE_IDLE_COLOR: &str = "#a0a0a0";
+/// Funnel of a column with an active filter (filter buttons)
+const FILTER_ACTIVE_COLOR: &str = "#1a73e8";
+
+/// How the row/column headers of the selection are highlighted
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HeaderHighlight {
+    Off,
+    Tint,
+    Bold,
+    TintBold,
+}
+
+impl HeaderHighlight {
+    /// Parse mode name ("off", "tint", "bold", "both")
+    pub fn parse(mode: &str) -> Result<Self, GridError> {
+        match mode.to_lowercase().as_str() {
+            "off" | "none" => Ok(HeaderHighlight::Off),
+            "tint" => Ok(HeaderHighlight::Tint),
+            "bold" => Ok(HeaderHighlight::Bold),
+            "both" | "tint+bold" => Ok(HeaderHighlight::TintBold),
+            other => Err(GridError::unknown_value("header_highlight", other, &["off", "tint", "bold", "both"])),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HeaderHighlight::Off => "off",
+            HeaderHighlight::Tint => "tint",
+            HeaderHighlight::Bold => "bold",
+            HeaderHighlight::TintBold => "both",
+        }
+    }
+
+    fn tints(&self) -> bool {
+        matches!(self, HeaderHighlight::Tint | HeaderHighlight::TintBold)
+    }
+
+    fn bolds(&self) -> bool {
+        matches!(self, HeaderHighlight::Bold | HeaderHighlight::TintBold)
+    }
+}
+
+/// Highlight of the cell under the mouse pointer (set_hover_style)
+#[derive(Clone, Debug, PartialEq)]
+pub struct HoverStyle {
+    pub row: bool,             // Tint the hovered row
+    pub column: bool,          // Tint the hovered column
+    pub cell: bool,            // Outline the hovered cell
+    pub band_color: String,    // Row/column tint (translucent, drawn over the text)
+    pub outline_color: String, // Cell outline
+}
+
+impl Default for HoverStyle {
+    fn default() -> Self {
+        Self {
+            row: false,
+            column: false,
+            cell: false,
+            band_color: "rgba(26, 115, 232, 0.08)".to_string(),
+            outline_color: "#1a73e8".to_string(),
+        }
+    }
+}
+
+impl HoverStyle {
+    /// Parse {"row", "column", "cell", "band_color", "outline_color"}; missing keys keep
+    /// their defaults (off, with the default colors)
+    pub fn from_json(json: &serde_json::Value) -> Result<Self, GridError> {
+        if !json.is_object() {
+            return Err(GridError::OperationError { message: locale::text("error.hover_style") });
+        }
+        let default = Self::default();
+        let color = |key: &str, default: String| json[key].as_str().map_or(default, str::to_string);
+        Ok(Self {
+            row: json["row"].as_bool().unwrap_or(false),
+            column: json["column"].as_bool().unwrap_or(false),
+            cell: json["cell"].as_bool().unwrap_or(false),
+            band_color: color("band_color", default.band_color),
+            outline_color: color("outline_color", default.outline_color),
+        })
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "row": self.row,
+            "column": self.column,
+            "cell": self.cell,
+            "band_color": self.band_color,
+            "outline_color": self.outline_color,
+        })
+    }
+
+    /// Check if anything is drawn for the hovered cell
+    pub fn is_enabled(&self) -> bool {
+        self.row || self.column || self.cell
+    }
+}
+
+/// Header styling for one frame: the rows/columns of the selection and how to mark them
+struct HeaderPaint<'a> {
+    active_bg: Option<&'a str>,  // Background of highlighted headers (None = plain)
+    active_font: Option<String>, // Font of highlighted headers (None = regular)
+    active_rows: HashSet<usize>,
+    active_cols: HashSet<usize>,
+}
+
+impl HeaderPaint<'_> {
+    fn bg(&self, active: bool) -> &str {
+        match self.active_bg {
+            Some(bg) if active => bg,
+            _ => HEADER_BG,
+        }
+    }
+
+    fn font<'b>(&'b self, active: bool, regular: &'b str) -> &'b str {
+        match &self.active_font {
+            Some(font) if active => font,
+            _ => regular,
+        }
+    }
+}
+
 /// Font configuration for text rendering
 #[derive(Debug, Clone)]
 pub struct FontConfig {
@@ -44,6 +205,25 @@ pub struct TextRenderer {
 
     // Cached font string
     font_string: String,
+
+    // Highlight of the selection's row/column headers
+    pub active_header_highlight: HeaderHighlight,
+    pub active_header_bg: String,
+
+    // Pick white text on dark cell backgrounds that have no text color of their own
+    pub auto_text_contrast: bool,
+
+    // Text color of cells that are not editable (empty = not distinguished)
+    pub locked_text_color: String,
+
+    // Corner mark of cells with a validation error (empty = no mark)
+    pub invalid_mark_color: String,
+
+    // Row/column bands and outline of the cell under the pointer
+    pub hover_style: HoverStyle,
+
+    // Mark drawn beneath the cell text (set_watermark_json)
+    pub watermark: Option<Watermark>,
 }
 
 impl TextRenderer {
@@ -76,6 +256,13 @@ impl TextRenderer {
             selected_bg_color: "rgba(102, 126, 234, 0.2)".to_string(),
             selected_text_color: "#000000".to_string(),
             font_string,
+            active_header_highlight: HeaderHighlight::Tint,
+            active_header_bg: "#d3e3fd".to_string(),
+            auto_text_contrast: true,
+            locked_text_color: "#9e9e9e".to_string(),
+            invalid_mark_color: "#d93025".to_string(),
+            hover_style: HoverStyle::default(),
+            watermark: None,
         })
     }
 
@@ -91,14 +278,31 @@ impl TextRenderer {
         self.font_config = config;
     }
 
+    /// Change only the font size (e.g. for density presets)
+    pub fn set_font_size(&mut self, size: f32) {
+        let config = FontConfig { size, ..self.font_config.clone() };
+        self.set_font(config);
+    }
+
+    /// Current font size in pixels
+    pub fn font_size(&self) -> f32 {
+        self.font_config.size
+    }
+
     /// Clear the canvas
     pub fn clear(&self, width: f32, height: f32) {
         self.context.clear_rect(0.0, 0.0, width as f64, height as f64);
     }
 
+    /// Scale all drawing by the viewport zoom factor
+    fn apply_zoom(&self, viewport: &Viewport) {
+        let zoom = viewport.zoom as f64;
+        let _ = self.context.set_transform(zoom, 0.0, 0.0, zoom, 0.0, 0.0);
+    }
+
     /// Render all visible text in the grid
     pub fn render(&self, grid: &Grid, viewport: &Viewport, selection: &SelectionState) {
-        self.render_with_search(grid, viewport, selection, &[], None);
+        self.render_with_search(grid, viewport, selection, &SearchResults::new(), None);
     }
 
     pub fn render_with_search(
@@ -106,15 +310,24 @@ impl TextRenderer {
         grid: &Grid,
         viewport: &Viewport,
         selection: &SelectionState,
-        search_results: &[(usize, usize)],
+        search_results: &SearchResults,
         current_search_index: Option<usize>
     ) {
-        // Clear canvas first
+        // Draw in grid pixels scaled by the zoom factor
+        self.apply_zoom(viewport);
+
+        // Clear canvas first, then lay the watermark under everything drawn after it
         self.clear(viewport.canvas_width, viewport.canvas_height);
+        if let Some(watermark) = &self.watermark {
+            self.render_watermark(grid, viewport, watermark);
+        }
 
         // Render headers if enabled
         if grid.show_headers {
-            self.render_headers(grid, viewport);
+            self.render_headers(grid, viewport, selection);
+            if let Some(focus) = selection.header_focus {
+                self.render_header_focus(grid, viewport, focus);
+            }
         }
 
         let first_row = viewport.first_visible_row;
@@ -135,7 +348,7 @@ impl TextRenderer {
         // Region 1: Frozen rows × Frozen cols (top-left)
         if frozen_rows > 0 && frozen_cols > 0 {
             for row in 0..frozen_rows.min(grid.row_count()) {
-                if grid.is_row_filtered(row) { continue; }
+                if grid.is_row_hidden(row) { continue; }
                 for col in 0..frozen_cols.min(grid.col_count()) {
                     self.render_cell_with_search_frozen(grid, viewport, selection, row, col, search_results, current_search_index, true, true);
                 }
@@ -145,7 +358,7 @@ impl TextRenderer {
         // Region 2: Frozen rows × Scrollable cols (top-right)
         if frozen_rows > 0 {
             for row in 0..frozen_rows.min(grid.row_count()) {
-                if grid.is_row_filtered(row) { continue; }
+                if grid.is_row_hidden(row) { continue; }
                 for col in first_col.max(frozen_cols)..=last_col {
                     self.render_cell_with_search_frozen(grid, viewport, selection, row, col, search_results, current_search_index, true, false);
                 }
@@ -155,7 +368,7 @@ impl TextRenderer {
         // Region 3: Scrollable rows × Frozen cols (bottom-left)
         if frozen_cols > 0 {
             for row in first_row.max(frozen_rows)..=last_row {
-                if grid.is_row_filtered(row) { continue; }
+                if grid.is_row_hidden(row) { continue; }
                 for col in 0..frozen_cols.min(grid.col_count()) {
                     self.render_cell_with_search_frozen(grid, viewport, selection, row, col, search_results, current_search_index, false, true);
                 }
@@ -164,16 +377,165 @@ impl TextRenderer {
 
         // Region 4: Scrollable rows × Scrollable cols (bottom-right)
         for row in first_row.max(frozen_rows)..=last_row {
-            if grid.is_row_filtered(row) { continue; }
+            if grid.is_row_hidden(row) { continue; }
             for col in first_col.max(frozen_cols)..=last_col {
                 self.render_cell_with_search_frozen(grid, viewport, selection, row, col, search_results, current_search_index, false, false);
             }
         }
+
+        // Ghost row/column placeholders for quick add
+        if grid.show_ghost_row || grid.show_ghost_col {
+            self.render_ghost_entries(grid, viewport);
+        }
+
+        // Preview mode banner with hidden row count
+        if grid.preview_mode.is_some() {
+            self.render_preview_banner(grid, viewport);
+        }
+    }
+
+    /// Re-render only the given cells (differential rendering)
+    /// Each cell rectangle is cleared and redrawn under a clip so neighbouring cells,
+    /// headers and frozen panes are left untouched
+    pub fn render_cells(
+        &self,
+        grid: &Grid,
+        viewport: &Viewport,
+        selection: &SelectionState,
+        search_results: &SearchResults,
+        current_search_index: Option<usize>,
+        cells: &[(usize, usize)],
+    ) {
+        self.apply_zoom(viewport);
+
+        for &(row, col) in cells {
+            if grid.is_row_hidden(row) {
+                continue;
+            }
+
+            let Some((x, y, width, height)) = viewport.cell_canvas_rect(row, col, grid) else {
+                continue;
+            };
+
+            self.context.save();
+            self.context.begin_path();
+            self.context.rect(x as f64, y as f64, width as f64, height as f64);
+            self.context.clip();
+            self.context.clear_rect(x as f64, y as f64, width as f64, height as f64);
+            if let Some(watermark) = &self.watermark {
+                self.render_watermark(grid, viewport, watermark);
+            }
+
+            self.render_cell_with_search_frozen(
+                grid,
+                viewport,
+                selection,
+                row,
+                col,
+                search_results,
+                current_search_index,
+                row < grid.frozen_rows,
+                col < grid.frozen_cols,
+            );
+
+            self.context.restore();
+        }
+    }
+
+    /// Render the ghost row/column with a "+" affordance
+    fn render_ghost_entries(&self, grid: &Grid, viewport: &Viewport) {
+        let header_offset_x = if grid.show_headers { grid.row_header_width } else { 0.0 };
+        let header_offset_y = if grid.show_headers { grid.col_header_height } else { 0.0 };
+
+        let ghost_bg = "#F5F7FB";
+        let ghost_fg = "#8A94A6";
+
+        self.context.set_font(&self.font_string);
+
+        if grid.show_ghost_row {
+            let y = header_offset_y + (grid.total_height() - viewport.scroll_y) as f32;
+            let height = grid.ghost_row_height();
+            let x = header_offset_x;
+            let width = (grid.total_width() - viewport.scroll_x).min(viewport.canvas_width - header_offset_x);
+
+            if y < viewport.canvas_height && y + height > header_offset_y && width > 0.0 {
+                let top = y.max(header_offset_y);
+                self.context.set_fill_style_str(ghost_bg);
+                self.context.fill_rect(x as f64, top as f64, width as f64, (y + height - top - 1.0).max(0.0) as f64);
+
+                self.context.set_fill_style_str(ghost_fg);
+                let text_y = (y + height / 2.0) as f64;
+                if grid.show_headers && y >= header_offset_y {
+                    self.context.set_text_align("center");
+                    let _ = self.context.fill_text("+", (grid.row_header_width / 2.0) as f64, text_y);
+                    self.context.set_text_align("left");
+                }
+                if y >= header_offset_y {
+                    let _ = self.context.fill_text(&locale::text("ghost_row"), (x + 5.0) as f64, text_y);
+                }
+            }
+        }
+
+        if grid.show_ghost_col {
+            let x = header_offset_x + grid.total_width() - viewport.scroll_x;
+            let width = grid.ghost_col_width();
+            let y = header_offset_y;
+            let height = ((grid.total_height() - viewport.scroll_y) as f32).min(viewport.canvas_height - header_offset_y);
+
+            if x < viewport.canvas_width && x + width > header_offset_x && height > 0.0 {
+                let left = x.max(header_offset_x);
+                self.context.set_fill_style_str(ghost_bg);
+                self.context.fill_rect(left as f64, y as f64, (x + width - left - 1.0).max(0.0) as f64, height as f64);
+
+                if grid.show_headers && x >= header_offset_x {
+                    self.context.set_fill_style_str(ghost_fg);
+                    self.context.set_text_align("center");
+                    let _ = self.context.fill_text(
+                        "+",
+                        (x + width / 2.0) as f64,
+                        (grid.col_header_height / 2.0) as f64,
+                    );
+                    self.context.set_text_align("left");
+                }
+            }
+        }
+    }
+
+    /// Render the preview mode banner at the bottom of the canvas
+    fn render_preview_banner(&self, grid: &Grid, viewport: &Viewport) {
+        let hidden = grid.preview_hidden_count();
+        let total = grid.row_count();
+        let text = format!(
+            "Preview: showing {} of {} rows ({} hidden)",
+            total - hidden,
+            total,
+            hidden
+        );
+
+        let banner_height = 24.0;
+        let banner_y = viewport.canvas_height - banner_height;
+
+        self.context.set_fill_style_str("rgba(255, 243, 205, 0.95)");
+        self.context.fill_rect(0.0, banner_y as f64, viewport.canvas_width as f64, banner_height as f64);
+
+        self.context.set_stroke_style_str("#e0c068");
+        self.context.set_line_width(1.0);
+        self.context.stroke_rect(0.0, banner_y as f64, viewport.canvas_width as f64, banner_height as f64);
+
+        self.context.set_fill_style_str("#664d03");
+        self.context.set_font(&self.font_string);
+        self.context.set_text_align("center");
+        let _ = self.context.fill_text(
+            &text,
+            (viewport.canvas_width / 2.0) as f64,
+            (banner_y + banner_height / 2.0) as f64,
+        );
+        self.context.set_text_align("left");
     }
 
     /// Render a single cell's text
     fn render_cell(&self, grid: &Grid, viewport: &Viewport, row: usize, col: usize) {
-        self.render_cell_with_search(grid, viewport, row, col, &[], None);
+        self.render_cell_with_search(grid, viewport, row, col, &SearchResults::new(), None);
     }
 
     /// Render cell with frozen row/column support
@@ -184,15 +546,15 @@ impl TextRenderer {
         selection: &SelectionState,
         row: usize,
         col: usize,
-        search_results: &[(usize, usize)],
+        search_results: &SearchResults,
         current_search_index: Option<usize>,
         is_frozen_row: bool,
         is_frozen_col: bool,
     ) {
-        let text = grid.get_value_string(row, col);
-        if text.is_empty() {
+        if grid.is_col_hidden(col) {
             return;
         }
+        let text = grid.get_display_string(row, col);
 
         // Calculate cell position on canvas
         let grid_x = grid.col_x_position(col);
@@ -207,7 +569,7 @@ impl TextRenderer {
         let scroll_y = if is_frozen_row { 0.0 } else { viewport.scroll_y };
 
         let canvas_x = grid_x - scroll_x + header_offset_x;
-        let canvas_y = grid_y - scroll_y + header_offset_y;
+        let canvas_y = (grid_y - scroll_y) as f32 + header_offset_y;
 
         let width = grid.col_width(col);
         let height = grid.row_height(row);
@@ -219,6 +581,16 @@ impl TextRenderer {
             return;
         }
 
+        // Only the validation mark is drawn on empty cells (e.g. a required value)
+        let (clip_left, clip_top) = (
+            if is_frozen_col { canvas_x } else { canvas_x.max(header_offset_x) },
+            if is_frozen_row { canvas_y } else { canvas_y.max(header_offset_y) },
+        );
+        if text.is_empty() {
+            self.render_invalid_mark(grid, row, col, (canvas_x + width, canvas_y), (clip_left, clip_top));
+            return;
+        }
+
         // Get cell data
         let cell = grid.get_cell(row, col);
         let is_selected = selection.is_selected(row, col);
@@ -283,9 +655,14 @@ impl TextRenderer {
         }
 
         // Set text color
+        let bg_visible = !is_selected && !is_current_match && !is_search_match;
         let text_color = if let Some(cell) = cell {
             if let Some(fg_color) = cell.fg_color {
                 u32_to_rgba_string(fg_color)
+            } else if let Some(color) = self.auto_contrast_color(cell.bg_color, bg_visible) {
+                color.to_string()
+            } else if self.is_locked(grid, row, col) {
+                self.locked_text_color.clone()
             } else if is_selected {
                 self.selected_text_color.clone()
             } else if row == 0 {
@@ -317,7 +694,7 @@ impl TextRenderer {
         }
 
         // Draw text with padding and clipping
-        let padding = 5.0;
+        let padding = grid.cell_padding(col);
         let text_x = canvas_x + padding;
         let text_y = canvas_y + height / 2.0 + self.font_config.size / 3.0;
 
@@ -359,6 +736,8 @@ impl TextRenderer {
 
         // Reset font
         self.context.set_font(&self.font_string);
+
+        self.render_invalid_mark(grid, row, col, (canvas_x + width, canvas_y), (clip_left, clip_top));
     }
 
     /// Render a single cell's text with search highlighting
@@ -368,10 +747,13 @@ impl TextRenderer {
         viewport: &Viewport,
         row: usize,
         col: usize,
-        search_results: &[(usize, usize)],
+        search_results: &SearchResults,
         current_search_index: Option<usize>
     ) {
-        let text = grid.get_value_string(row, col);
+        if grid.is_col_hidden(col) {
+            return;
+        }
+        let text = grid.get_display_string(row, col);
         if text.is_empty() {
             return;
         }
@@ -385,7 +767,7 @@ impl TextRenderer {
         let header_offset_y = if grid.show_headers { grid.col_header_height } else { 0.0 };
 
         let canvas_x = grid_x - viewport.scroll_x + header_offset_x;
-        let canvas_y = grid_y - viewport.scroll_y + header_offset_y;
+        let canvas_y = (grid_y - viewport.scroll_y) as f32 + header_offset_y;
 
         let width = grid.col_width(col);
         let height = grid.row_height(row);
@@ -473,10 +855,15 @@ impl TextRenderer {
             );
         }
 
-        // Set text color (custom or default)
+        // Set text color (custom, contrasting with the background, or default)
+        let bg_visible = !is_current_match && !is_search_match;
         let text_color = if let Some(cell) = cell {
             if let Some(fg_color) = cell.fg_color {
                 u32_to_rgba_string(fg_color)
+            } else if let Some(color) = self.auto_contrast_color(cell.bg_color, bg_visible) {
+                color.to_string()
+            } else if self.is_locked(grid, row, col) {
+                self.locked_text_color.clone()
             } else if is_selected {
                 self.selected_text_color.clone()
             } else if row == 0 {
@@ -509,7 +896,7 @@ impl TextRenderer {
         }
 
         // Text padding
-        let padding = 4.0;
+        let padding = grid.cell_padding(col);
         let text_x = canvas_x + padding;
         let text_y = canvas_y + height / 2.0;
 
@@ -554,7 +941,7 @@ impl TextRenderer {
         let header_offset_y = if grid.show_headers { grid.col_header_height } else { 0.0 };
 
         let canvas_x = grid_x - viewport.scroll_x + header_offset_x;
-        let canvas_y = grid_y - viewport.scroll_y + header_offset_y;
+        let canvas_y = (grid_y - viewport.scroll_y) as f32 + header_offset_y;
 
         let width = grid.col_width(col);
         let height = grid.row_height(row);
@@ -593,39 +980,569 @@ impl TextRenderer {
         self.header_text_color = color;
     }
 
+    /// Readable text color for a cell background when automatic contrast applies
+    /// (the background is shown, not covered by a selection or search highlight)
+    fn auto_contrast_color(&self, bg_color: Option<u32>, bg_visible: bool) -> Option<&'static str> {
+        bg_color.filter(|_| self.auto_text_contrast && bg_visible).and_then(contrast_text_color)
+    }
+
+    /// Draw the validation error mark at a cell's top-right `corner`; nothing is drawn
+    /// left of or above `clip` (the header edges for scrolled cells)
+    fn render_invalid_mark(&self, grid: &Grid, row: usize, col: usize, corner: (f32, f32), clip: (f32, f32)) {
+        let ((right, top), (clip_left, clip_top)) = (corner, clip);
+        if self.invalid_mark_color.is_empty()
+            || grid.get_cell(row, col).is_none_or(|cell| cell.validation_error.is_none())
+        {
+            return;
+        }
+        let size = 6.0_f32.min(grid.col_width(col)).min(grid.row_height(row));
+        if right - size < clip_left || top < clip_top {
+            return;
+        }
+
+        self.context.begin_path();
+        self.context.move_to((right - size) as f64, top as f64);
+        self.context.line_to(right as f64, top as f64);
+        self.context.line_to(right as f64, (top + size) as f64);
+        self.context.close_path();
+        self.context.set_fill_style_str(&self.invalid_mark_color);
+        self.context.fill();
+    }
+
+    /// Check if a cell is shown as not editable (not when the whole grid is read-only)
+    fn is_locked(&self, grid: &Grid, row: usize, col: usize) -> bool {
+        !self.locked_text_color.is_empty() && !grid.readonly && !grid.is_cell_editable(row, col)
+    }
+
     /// Set selection colors
     pub fn set_selection_colors(&mut self, bg_color: String, text_color: String) {
         self.selected_bg_color = bg_color;
         self.selected_text_color = text_color;
     }
 
-    /// Render row and column headers
-    fn render_headers(&self, grid: &Grid, viewport: &Viewport) {
+    /// Render row and column headers; those of the selected rows and columns are highlighted
+    fn render_headers(&self, grid: &Grid, viewport: &Viewport, selection: &SelectionState) {
         let row_header_width = grid.row_header_width;
         let col_header_height = grid.col_header_height;
 
-        // Header background color
-        let header_bg = "#f0f0f0";
-        let header_border = "#cccccc";
+        let (active_rows, active_cols) = if self.active_header_highlight == HeaderHighlight::Off {
+            Default::default()
+        } else {
+            selection.active_rows_and_cols()
+        };
+        let paint = HeaderPaint {
+            active_bg: self.active_header_highlight.tints().then_some(self.active_header_bg.as_str()),
+            active_font: self.active_header_highlight.bolds().then(|| format!("bold {}px {}", self.font_config.size, self.font_config.family)),
+            active_rows,
+            active_cols,
+        };
 
         // Draw top-left corner cell (all-select button area)
-        self.context.set_fill_style_str(&header_bg);
+        self.context.set_fill_style_str(HEADER_BG);
         self.context.fill_rect(0.0, 0.0,
             row_header_width as f64,
             col_header_height as f64);
 
         // Border for corner
-        self.context.set_stroke_style_str(&header_border);
+        self.context.set_stroke_style_str(HEADER_BORDER);
         self.context.set_line_width(1.0);
         self.context.stroke_rect(0.0, 0.0,
             row_header_width as f64,
             col_header_height as f64);
 
-        // Render column headers
-        self.render_column_headers(grid, viewport, row_header_width, col_header_height, header_bg, header_border);
+        // Render column headers (above the summary band, if shown)
+        let label_height = col_header_height - grid.column_summary_height;
+        self.render_column_headers(grid, viewport, row_header_width, label_height, &paint);
 
         // Render row headers
-        self.render_row_headers(grid, viewport, row_header_width, col_header_height, header_bg, header_border);
+        self.render_row_headers(grid, viewport, row_header_width, col_header_height, &paint);
+        self.render_hidden_markers(grid, viewport, label_height);
+        self.context.set_font(&self.font_string);
+    }
+
+    /// Draw double lines in the headers where hidden columns or rows collapse
+    fn render_hidden_markers(&self, grid: &Grid, viewport: &Viewport, label_height: f32) {
+        let (row_header_width, col_header_height) = (grid.row_header_width, grid.col_header_height);
+        let hidden_cols = grid.hidden_columns();
+        let hidden_rows = grid.hidden_rows();
+        if hidden_cols.is_empty() && hidden_rows.is_empty() {
+            return;
+        }
+
+        self.context.save();
+        self.context.set_stroke_style_str(HIDDEN_MARKER_COLOR);
+        self.context.set_line_width(1.0);
+        self.context.begin_path();
+        // One marker per run of adjacent hidden columns/rows
+        for &col in hidden_cols.iter().filter(|&&col| col == 0 || !grid.is_col_hidden(col - 1)) {
+            let x = grid.col_x_position(col) - viewport.scroll_x + row_header_width;
+            if x >= row_header_width && x <= viewport.canvas_width {
+                for offset in [-1.5, 1.5] {
+                    self.context.move_to((x + offset) as f64, 0.0);
+                    self.context.line_to((x + offset) as f64, label_height as f64);
+                }
+            }
+        }
+        for &row in hidden_rows.iter().filter(|&&row| row == 0 || !grid.is_row_user_hidden(row - 1)) {
+            let y = (grid.row_y_position(row) - viewport.scroll_y) as f32 + col_header_height;
+            if y >= col_header_height && y <= viewport.canvas_height {
+                for offset in [-1.5, 1.5] {
+                    self.context.move_to(0.0, (y + offset) as f64);
+                    self.context.line_to(row_header_width as f64, (y + offset) as f64);
+                }
+            }
+        }
+        self.context.stroke();
+        self.context.restore();
+    }
+
+    /// Draw the keyboard focus ring around a column or row header
+    fn render_header_focus(&self, grid: &Grid, viewport: &Viewport, focus: HeaderFocus) {
+        let (x, y, width, height) = match focus {
+            HeaderFocus::Column(col) => {
+                // Ring around the bottom header level, above the summary band
+                let top = if grid.header_levels > 1 && !grid.column_groups.is_empty() {
+                    (grid.header_levels - 1) as f32 * grid.header_row_height
+                } else {
+                    0.0
+                };
+                let x = grid.col_x_position(col) - viewport.scroll_x + grid.row_header_width;
+                let bottom = grid.col_header_height - grid.column_summary_height;
+                (x, top, grid.col_width(col), bottom - top)
+            }
+            HeaderFocus::Row(row) => {
+                let y = (grid.row_y_position(row) - viewport.scroll_y) as f32 + grid.col_header_height;
+                (0.0, y, grid.row_header_width, grid.row_height(row))
+            }
+        };
+
+        self.context.set_stroke_style_str("#1a73e8");
+        self.context.set_line_width(2.0);
+        self.context.stroke_rect((x + 1.0) as f64, (y + 1.0) as f64, (width - 2.0) as f64, (height - 2.0) as f64);
+    }
+
+    /// Draw pulsing placeholder bars in the visible cells of rows still being fetched
+    /// `phase` (0..1) drives the shimmer
+    pub fn render_placeholder_rows(&self, grid: &Grid, viewport: &Viewport, rows: &[usize], phase: f32) {
+        self.apply_zoom(viewport);
+        let alpha = 0.05 + 0.07 * (0.5 + 0.5 * (phase * std::f32::consts::TAU).sin());
+        self.context.set_fill_style_str(&format!("rgba(0, 0, 0, {:.3})", alpha));
+
+        let last_col = viewport.last_visible_col.min(grid.col_count().saturating_sub(1));
+        let cols = (0..grid.frozen_cols.min(grid.col_count())).chain(viewport.first_visible_col.max(grid.frozen_cols)..=last_col);
+        for col in cols {
+            let padding = grid.cell_padding(col).max(4.0);
+            for &row in rows {
+                let Some((x, y, width, height)) = viewport.cell_canvas_rect(row, col, grid) else {
+                    continue;
+                };
+                let bar_height = (height * 0.4).min(10.0);
+                let bar_width = width - 2.0 * padding;
+                if bar_width > 0.0 {
+                    self.context.fill_rect((x + padding) as f64, (y + (height - bar_height) / 2.0) as f64, bar_width as f64, bar_height as f64);
+                }
+            }
+        }
+    }
+
+    /// Highlight a column header (goto_column flash); `alpha` fades the highlight out
+    pub fn render_header_flash(&self, grid: &Grid, viewport: &Viewport, col: usize, alpha: f32) {
+        if !grid.show_headers || col >= grid.col_count() {
+            return;
+        }
+
+        self.apply_zoom(viewport);
+        let x = grid.col_x_position(col) - viewport.scroll_x + grid.row_header_width;
+        let height = grid.col_header_height - grid.column_summary_height;
+
+        // Keep the highlight off the top-left corner cell
+        self.context.save();
+        self.context.begin_path();
+        self.context.rect(
+            grid.row_header_width as f64,
+            0.0,
+            (viewport.canvas_width - grid.row_header_width) as f64,
+            height as f64,
+        );
+        self.context.clip();
+        self.context.set_fill_style_str(&format!("rgba(255, 193, 7, {:.3})", 0.6 * alpha));
+        self.context.fill_rect(x as f64, 0.0, grid.col_width(col) as f64, height as f64);
+        self.context.restore();
+    }
+
+    /// Draw the hover highlight of cell (row, col): row and column bands across the cell
+    /// area and an outline around the cell, as enabled in `hover_style`
+    pub fn render_hover(&self, grid: &Grid, viewport: &Viewport, row: usize, col: usize) {
+        let style = &self.hover_style;
+        let Some((x, y, width, height)) = viewport.cell_canvas_rect(row, col, grid) else { return };
+        self.apply_zoom(viewport);
+        let header_offset_x = if grid.show_headers { grid.row_header_width } else { 0.0 };
+        let header_offset_y = if grid.show_headers { grid.col_header_height } else { 0.0 };
+
+        self.context.save();
+        self.context.set_fill_style_str(&style.band_color);
+        if style.row {
+            let right = (header_offset_x + grid.total_width() - viewport.scroll_x).min(viewport.canvas_width);
+            self.context.fill_rect(header_offset_x as f64, y as f64, (right - header_offset_x).max(0.0) as f64, height as f64);
+        }
+        if style.column {
+            let bottom = (header_offset_y + (grid.total_height() - viewport.scroll_y) as f32).min(viewport.canvas_height);
+            self.context.fill_rect(x as f64, header_offset_y as f64, width as f64, (bottom - header_offset_y).max(0.0) as f64);
+        }
+        if style.cell {
+            self.context.set_stroke_style_str(&style.outline_color);
+            self.context.set_line_width(1.0);
+            self.context.stroke_rect(x as f64 + 0.5, y as f64 + 0.5, (width - 1.0).max(0.0) as f64, (height - 1.0).max(0.0) as f64);
+        }
+        self.context.restore();
+    }
+
+    /// Draw the funnel buttons in the headers of filterable columns; columns in `filtered`
+    /// have an active filter and get a highlighted button
+    pub fn render_filter_buttons(&self, grid: &Grid, viewport: &Viewport, filtered: &HashSet<usize>) {
+        self.apply_zoom(viewport);
+        let last_col = viewport.last_visible_col.min(grid.col_count().saturating_sub(1));
+
+        // Keep the buttons off the row headers
+        self.context.save();
+        self.context.begin_path();
+        self.context.rect(
+            grid.row_header_width as f64,
+            0.0,
+            (viewport.canvas_width - grid.row_header_width) as f64,
+            grid.col_header_height as f64,
+        );
+        self.context.clip();
+        for col in viewport.first_visible_col..=last_col {
+            let Some((x, y, size, _)) = viewport.filter_button_rect(col, grid) else { continue };
+            let (x, y, size) = (x as f64, y as f64, size as f64);
+            let active = filtered.contains(&col);
+            if active {
+                self.context.set_fill_style_str(&self.active_header_bg);
+                self.context.fill_rect(x, y, size, size);
+            }
+
+            // Funnel: a triangle narrowing into a short stem
+            let (mid, inset) = (x + size / 2.0, size * 0.15);
+            self.context.begin_path();
+            self.context.move_to(x + inset, y + inset);
+            self.context.line_to(x + size - inset, y + inset);
+            self.context.line_to(mid + size * 0.1, y + size * 0.5);
+            self.context.line_to(mid + size * 0.1, y + size - inset);
+            self.context.line_to(mid - size * 0.1, y + size - inset - size * 0.1);
+            self.context.line_to(mid - size * 0.1, y + size * 0.5);
+            self.context.close_path();
+            self.context.set_fill_style_str(if active { FILTER_ACTIVE_COLOR } else { SORT_ZONE_IDLE_COLOR });
+            self.context.fill();
+        }
+        self.context.restore();
+    }
+
+    /// Outline where a dragged range would be dropped; `rect` is (x, y, width, height)
+    pub fn render_range_outline(&self, grid: &Grid, viewport: &Viewport, rect: (f32, f32, f32, f32)) {
+        self.apply_zoom(viewport);
+        let header_offset_x = if grid.show_headers { grid.row_header_width } else { 0.0 };
+        let header_offset_y = if grid.show_headers { grid.col_header_height } else { 0.0 };
+
+        // Keep the outline off the headers
+        self.context.save();
+        self.context.begin_path();
+        self.context.rect(
+            header_offset_x as f64,
+            header_offset_y as f64,
+            (viewport.canvas_width - header_offset_x) as f64,
+            (viewport.canvas_height - header_offset_y) as f64,
+        );
+        self.context.clip();
+        self.context.set_stroke_style_str("#5f6368");
+        self.context.set_line_width(3.0);
+        self.context.stroke_rect(rect.0 as f64, rect.1 as f64, rect.2 as f64, rect.3 as f64);
+        self.context.restore();
+    }
+
+    /// Horizontal scroll of the native editor's text that keeps the caret inside the cell
+    pub fn editor_scroll(&self, grid: &Grid, col: usize, buffer: &TextBuffer) -> f32 {
+        let visible = grid.col_width(col) - 2.0 * grid.cell_padding(col);
+        let caret_x = self.measure_text(buffer.text_before(buffer.caret));
+        (caret_x - visible).max(0.0)
+    }
+
+    /// Caret index nearest to `x` (grid pixels relative to the cell's left edge)
+    pub fn editor_caret_at(&self, grid: &Grid, col: usize, buffer: &TextBuffer, x: f32) -> usize {
+        let target = x - grid.cell_padding(col) + self.editor_scroll(grid, col, buffer);
+        let len = buffer.text.chars().count();
+        (0..=len)
+            .map(|index| (index, (self.measure_text(buffer.text_before(index)) - target).abs()))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(len, |(index, _)| index)
+    }
+
+    /// Draw the native editor over a cell: the buffer text, the selection and the caret
+    /// `rect` is the cell in grid pixels (x, y, width, height)
+    pub fn render_editor(&self, grid: &Grid, viewport: &Viewport, col: usize, rect: &[f32], buffer: &TextBuffer) {
+        let [x, y, width, height] = [rect[0] as f64, rect[1] as f64, rect[2] as f64, rect[3] as f64];
+        let padding = grid.cell_padding(col) as f64;
+        let scroll = self.editor_scroll(grid, col, buffer) as f64;
+        let text_x = x + padding - scroll;
+        let text_y = y + height / 2.0;
+        let line_height = self.font_height() as f64;
+        let offset = |index: usize| self.measure_text(buffer.text_before(index)) as f64;
+
+        self.apply_zoom(viewport);
+        self.context.save();
+        self.context.set_fill_style_str("#ffffff");
+        self.context.fill_rect(x, y, width, height);
+        self.context.begin_path();
+        self.context.rect(x + 1.0, y, width - 2.0, height);
+        self.context.clip();
+
+        if buffer.has_selection() {
+            let (start, end) = buffer.selection();
+            self.context.set_fill_style_str(&self.selected_bg_color);
+            self.context.fill_rect(text_x + offset(start), text_y - line_height / 2.0, offset(end) - offset(start), line_height);
+        }
+        self.context.set_fill_style_str(&self.text_color);
+        self.context.set_text_align("left");
+        let _ = self.context.fill_text(&buffer.text, text_x, text_y);
+
+        let caret_x = (text_x + offset(buffer.caret)).round() + 0.5;
+        self.context.set_stroke_style_str(&self.text_color);
+        self.context.set_line_width(1.0);
+        self.context.begin_path();
+        self.context.move_to(caret_x, text_y - line_height / 2.0);
+        self.context.line_to(caret_x, text_y + line_height / 2.0);
+        self.context.stroke();
+        self.context.restore();
+
+        self.context.set_stroke_style_str("#667eea");
+        self.context.set_line_width(2.0);
+        self.context.stroke_rect(x, y, width, height);
+    }
+
+    /// Draw the watermark across the cell area, before the cell text so the text stays on
+    /// top (the cell backgrounds are on the layer below and show through it)
+    fn render_watermark(&self, grid: &Grid, viewport: &Viewport, watermark: &Watermark) {
+        let (left, top) = if grid.show_headers { (grid.row_header_width, grid.col_header_height) } else { (0.0, 0.0) };
+        let width = viewport.canvas_width - left;
+        let height = viewport.canvas_height - top - grid.footer_height;
+        if width <= 0.0 || height <= 0.0 {
+            return;
+        }
+        let image = watermark.image.as_ref().filter(|image| image.complete() && image.natural_width() > 0);
+        let (mark_width, mark_height) = match (image, &watermark.text) {
+            (Some(image), _) => (image.natural_width() as f32 * watermark.scale, image.natural_height() as f32 * watermark.scale),
+            (None, Some(_)) => (0.0, watermark.font_size),
+            (None, None) => return,
+        };
+
+        self.apply_zoom(viewport);
+        self.context.save();
+        self.context.begin_path();
+        self.context.rect(left as f64, top as f64, width as f64, height as f64);
+        self.context.clip();
+        self.context.set_global_alpha(watermark.opacity as f64);
+        self.context.set_fill_style_str(&watermark.color);
+        self.context.set_font(&format!("700 {}px {}", watermark.font_size, self.font_config.family));
+        self.context.set_text_align("center");
+        self.context.set_text_baseline("middle");
+        let mark_width = match &watermark.text {
+            Some(text) if image.is_none() => self.context.measure_text(text).map_or(0.0, |m| m.width() as f32),
+            _ => mark_width,
+        };
+
+        let centers: Vec<(f32, f32)> = if watermark.repeat {
+            // Tiles spaced by the mark's diagonal so rotated marks never overlap
+            let step = (mark_width.hypot(mark_height) * 1.2).max(watermark.font_size);
+            let columns = (width / step).ceil() as usize + 1;
+            let rows = (height / step).ceil() as usize + 1;
+            (0..rows)
+                .flat_map(|r| (0..columns).map(move |c| (left + step * (c as f32 + if r % 2 == 0 { 0.25 } else { 0.75 }), top + step * (r as f32 + 0.5))))
+                .collect()
+        } else {
+            vec![(left + width / 2.0, top + height / 2.0)]
+        };
+
+        for (x, y) in centers {
+            self.context.save();
+            let _ = self.context.translate(x as f64, y as f64);
+            let _ = self.context.rotate((watermark.angle as f64).to_radians());
+            match (image, &watermark.text) {
+                (Some(image), _) => {
+                    let _ = self.context.draw_image_with_html_image_element_and_dw_and_dh(
+                        image,
+                        -mark_width as f64 / 2.0,
+                        -mark_height as f64 / 2.0,
+                        mark_width as f64,
+                        mark_height as f64,
+                    );
+                }
+                (None, Some(text)) => {
+                    let _ = self.context.fill_text(text, 0.0, 0.0);
+                }
+                (None, None) => {}
+            }
+            self.context.restore();
+        }
+        self.context.restore();
+    }
+
+    /// Render the summary band under the column headers: a histogram sparkline or
+    /// "min – max · avg" per numeric column (non-numeric columns are left blank)
+    pub fn render_column_summaries(
+        &self,
+        grid: &Grid,
+        viewport: &Viewport,
+        summaries: &[(usize, Option<ColumnSummary>)],
+        mode: SummaryMode,
+    ) {
+        let band_height = grid.column_summary_height;
+        if !grid.show_headers || band_height <= 0.0 || mode == SummaryMode::Off {
+            return;
+        }
+        let band_y = grid.col_header_height - band_height;
+        let row_header_width = grid.row_header_width;
+
+        self.context.save();
+        self.context.begin_path();
+        self.context.rect(
+            row_header_width as f64,
+            band_y as f64,
+            (viewport.canvas_width - row_header_width).max(0.0) as f64,
+            band_height as f64,
+        );
+        self.context.clip();
+
+        for (col, summary) in summaries {
+            let canvas_x = grid.col_x_position(*col) - viewport.scroll_x + row_header_width;
+            let width = grid.col_width(*col);
+            if canvas_x + width < row_header_width || canvas_x > viewport.canvas_width {
+                continue;
+            }
+
+            self.context.set_fill_style_str("#f7f7fa");
+            self.context.fill_rect(canvas_x as f64, band_y as f64, width as f64, band_height as f64);
+            self.context.set_stroke_style_str("#cccccc");
+            self.context.set_line_width(1.0);
+            self.context.stroke_rect(canvas_x as f64, band_y as f64, width as f64, band_height as f64);
+
+            let Some(summary) = summary else { continue };
+            match mode {
+                SummaryMode::Sparkline => {
+                    let max_bin = summary.bins.iter().copied().max().unwrap_or(0).max(1) as f32;
+                    let inner_x = canvas_x + 4.0;
+                    let inner_width = (width - 8.0).max(0.0);
+                    let inner_height = band_height - 6.0;
+                    let bar_width = inner_width / summary.bins.len() as f32;
+
+                    self.context.set_fill_style_str("rgba(102, 126, 234, 0.7)");
+                    for (i, count) in summary.bins.iter().enumerate() {
+                        if *count == 0 {
+                            continue;
+                        }
+                        let bar_height = (*count as f32 / max_bin * inner_height).max(1.0);
+                        self.context.fill_rect(
+                            (inner_x + i as f32 * bar_width) as f64,
+                            (band_y + 3.0 + inner_height - bar_height) as f64,
+                            (bar_width - 1.0).max(1.0) as f64,
+                            bar_height as f64,
+                        );
+                    }
+                }
+                SummaryMode::Stats => {
+                    let text = format!(
+                        "{} – {} · {}",
+                        Self::compact_number(summary.min),
+                        Self::compact_number(summary.max),
+                        Self::compact_number(summary.mean)
+                    );
+                    self.context.set_fill_style_str("#666666");
+                    self.context.set_font(&format!("{}px {}", (self.font_config.size - 2.0).max(8.0), self.font_config.family));
+                    self.context.set_text_align("center");
+                    let _ = self.context.fill_text(&text, (canvas_x + width / 2.0) as f64, (band_y + band_height / 2.0) as f64);
+                    self.context.set_text_align("left");
+                    self.context.set_font(&self.font_string);
+                }
+                SummaryMode::Off => {}
+            }
+        }
+
+        self.context.restore();
+    }
+
+    /// Render the aggregate footer band pinned to the bottom of the canvas
+    /// `cells` holds (col, label, value text) for the visible columns with an aggregate;
+    /// the label is drawn on the left and the value right-aligned
+    pub fn render_footer(&self, grid: &Grid, viewport: &Viewport, cells: &[(usize, String, String)]) {
+        let band_height = grid.footer_height;
+        if band_height <= 0.0 {
+            return;
+        }
+        self.apply_zoom(viewport);
+
+        let band_y = viewport.canvas_height - band_height;
+        let row_header_width = if grid.show_headers { grid.row_header_width } else { 0.0 };
+        let text_y = (band_y + band_height / 2.0) as f64;
+
+        // Band background with a separator line above it
+        self.context.set_fill_style_str("#f0f0f0");
+        self.context.fill_rect(0.0, band_y as f64, viewport.canvas_width as f64, band_height as f64);
+        self.context.set_stroke_style_str("#999999");
+        self.context.set_line_width(1.0);
+        self.context.begin_path();
+        self.context.move_to(0.0, band_y as f64 + 0.5);
+        self.context.line_to(viewport.canvas_width as f64, band_y as f64 + 0.5);
+        self.context.stroke();
+
+        self.context.save();
+        self.context.begin_path();
+        self.context.rect(
+            row_header_width as f64,
+            band_y as f64,
+            (viewport.canvas_width - row_header_width).max(0.0) as f64,
+            band_height as f64,
+        );
+        self.context.clip();
+
+        for (col, label, value) in cells {
+            let canvas_x = grid.col_x_position(*col) - viewport.scroll_x + row_header_width;
+            let width = grid.col_width(*col);
+            if canvas_x + width < row_header_width || canvas_x > viewport.canvas_width {
+                continue;
+            }
+            let padding = grid.cell_padding(*col);
+
+            self.context.set_stroke_style_str("#cccccc");
+            self.context.stroke_rect(canvas_x as f64, band_y as f64, width as f64, band_height as f64);
+
+            if !label.is_empty() {
+                self.context.set_fill_style_str("#888888");
+                self.context.set_font(&format!("{}px {}", (self.font_config.size - 2.0).max(8.0), self.font_config.family));
+                let _ = self.context.fill_text(label, (canvas_x + padding) as f64, text_y);
+            }
+            self.context.set_fill_style_str(&self.header_text_color);
+            self.context.set_font(&format!("bold {}", self.font_string));
+            self.context.set_text_align("right");
+            let _ = self.context.fill_text(value, (canvas_x + width - padding) as f64, text_y);
+            self.context.set_text_align("left");
+        }
+
+        self.context.set_font(&self.font_string);
+        self.context.restore();
+    }
+
+    /// Short number text for the summary band (at most 3 significant decimals, k/M suffixes)
+    fn compact_number(value: f64) -> String {
+        let abs = value.abs();
+        let (scaled, suffix) = if abs >= 1e6 {
+            (value / 1e6, "M")
+        } else if abs >= 1e4 {
+            (value / 1e3, "k")
+        } else {
+            (value, "")
+        };
+        let text = format!("{:.2}", scaled);
+        let text = text.trim_end_matches('0').trim_end_matches('.');
+        format!("{}{}", text, suffix)
     }
 
     /// Render column headers (A, B, C, ...) with optional multi-level grouping
@@ -635,18 +1552,17 @@ impl TextRenderer {
         viewport: &Viewport,
         row_header_width: f32,
         col_header_height: f32,
-        header_bg: &str,
-        header_border: &str,
+        paint: &HeaderPaint,
     ) {
         let first_col = viewport.first_visible_col;
         let last_col = viewport.last_visible_col.min(grid.col_count().saturating_sub(1));
 
         // If we have column groups, render multi-level headers
         if grid.header_levels > 1 && !grid.column_groups.is_empty() {
-            self.render_grouped_column_headers(grid, viewport, row_header_width, header_bg, header_border, first_col, last_col);
+            self.render_grouped_column_headers(grid, viewport, row_header_width, paint, first_col, last_col);
         } else {
             // Render simple single-level headers
-            self.render_simple_column_headers(grid, viewport, row_header_width, col_header_height, header_bg, header_border, first_col, last_col);
+            self.render_simple_column_headers(grid, viewport, row_header_width, col_header_height, paint, first_col, last_col);
         }
     }
 
@@ -657,8 +1573,7 @@ impl TextRenderer {
         viewport: &Viewport,
         row_header_width: f32,
         col_header_height: f32,
-        header_bg: &str,
-        header_border: &str,
+        paint: &HeaderPaint,
         first_col: usize,
         last_col: usize,
     ) {
@@ -686,7 +1601,8 @@ impl TextRenderer {
             }
 
             // Draw header background (clipped to not overlap top-left corner)
-            self.context.set_fill_style_str(&header_bg);
+            let active = paint.active_cols.contains(&col);
+            self.context.set_fill_style_str(paint.bg(active));
             self.context.fill_rect(
                 draw_x as f64,
                 0.0,
@@ -695,7 +1611,7 @@ impl TextRenderer {
             );
 
             // Draw header border (clipped to not overlap top-left corner)
-            self.context.set_stroke_style_str(&header_border);
+            self.context.set_stroke_style_str(HEADER_BORDER);
             self.context.set_line_width(1.0);
             self.context.stroke_rect(
                 draw_x as f64,
@@ -711,21 +1627,12 @@ impl TextRenderer {
                 Grid::get_col_name(col)
             };
 
-            // Add sort indicator if this column is sorted
-            let display_text = if grid.sort_column == Some(col) {
-                if grid.sort_ascending {
-                    format!("{} ▲", col_name)
-                } else {
-                    format!("{} ▼", col_name)
-                }
-            } else {
-                col_name
-            };
+            let display_text = header_label(grid, col, col_name);
 
             // Draw text - always centered in the original cell position, not the clipped area
             self.context.set_fill_style_str(&self.header_text_color);
             self.context.set_text_align("center");
-            self.context.set_font(&self.font_string);
+            self.context.set_font(paint.font(active, &self.font_string));
 
             // Text should be centered in the original cell, not the clipped visible area
             let text_x = canvas_x + width / 2.0;
@@ -745,6 +1652,7 @@ impl TextRenderer {
                 self.context.clip();
 
                 let _ = self.context.fill_text(&display_text, text_x as f64, text_y as f64);
+                self.render_sort_zone(grid, col, canvas_x + width, text_y);
 
                 self.context.restore();
             }
@@ -760,8 +1668,7 @@ impl TextRenderer {
         grid: &Grid,
         viewport: &Viewport,
         row_header_width: f32,
-        header_bg: &str,
-        header_border: &str,
+        paint: &HeaderPaint,
         first_col: usize,
         last_col: usize,
     ) {
@@ -813,7 +1720,7 @@ impl TextRenderer {
                 );
 
                 // Draw group border
-                self.context.set_stroke_style_str(&header_border);
+                self.context.set_stroke_style_str(HEADER_BORDER);
                 self.context.set_line_width(1.0);
                 self.context.stroke_rect(
                     visible_x as f64,
@@ -876,7 +1783,8 @@ impl TextRenderer {
             }
 
             // Draw header background (clipped)
-            self.context.set_fill_style_str(&header_bg);
+            let active = paint.active_cols.contains(&col);
+            self.context.set_fill_style_str(paint.bg(active));
             self.context.fill_rect(
                 draw_x as f64,
                 col_header_y as f64,
@@ -885,7 +1793,7 @@ impl TextRenderer {
             );
 
             // Draw header border (clipped)
-            self.context.set_stroke_style_str(&header_border);
+            self.context.set_stroke_style_str(HEADER_BORDER);
             self.context.set_line_width(1.0);
             self.context.stroke_rect(
                 draw_x as f64,
@@ -897,20 +1805,11 @@ impl TextRenderer {
             // Draw column name
             let col_name = Grid::get_col_name(col);
 
-            // Add sort indicator if this column is sorted
-            let display_text = if grid.sort_column == Some(col) {
-                if grid.sort_ascending {
-                    format!("{} ▲", col_name)
-                } else {
-                    format!("{} ▼", col_name)
-                }
-            } else {
-                col_name
-            };
+            let display_text = header_label(grid, col, col_name);
 
             self.context.set_fill_style_str(&self.header_text_color);
             self.context.set_text_align("center");
-            self.context.set_font(&self.font_string);
+            self.context.set_font(paint.font(active, &self.font_string));
 
             // Text should be centered in the original cell, not the clipped area
             let text_x = canvas_x + width / 2.0;
@@ -930,6 +1829,7 @@ impl TextRenderer {
                 self.context.clip();
 
                 let _ = self.context.fill_text(&display_text, text_x as f64, text_y as f64);
+                self.render_sort_zone(grid, col, canvas_x + width, text_y);
 
                 self.context.restore();
             }
@@ -939,6 +1839,23 @@ impl TextRenderer {
         }
     }
 
+    /// Draw the sort arrow zone at the right edge of a sortable column header
+    /// (SortClick::Arrow only); `right` is the header's right edge on the canvas
+    fn render_sort_zone(&self, grid: &Grid, col: usize, right: f32, text_y: f32) {
+        if grid.sort_click != SortClick::Arrow || !grid.get_column_config(col).is_some_and(|c| c.sortable) {
+            return;
+        }
+        let (glyph, color) = match grid.sort_column == Some(col) {
+            true if grid.sort_ascending => ("▲", self.header_text_color.as_str()),
+            true => ("▼", self.header_text_color.as_str()),
+            false => ("⇅", SORT_ZONE_IDLE_COLOR),
+        };
+        let zone = grid.sort_zone_width.min(grid.col_width(col));
+        self.context.set_fill_style_str(color);
+        self.context.set_text_align("center");
+        let _ = self.context.fill_text(glyph, (right - zone / 2.0) as f64, text_y as f64);
+    }
+
     /// Render row headers (1, 2, 3, ...)
     fn render_row_headers(
         &self,
@@ -946,15 +1863,14 @@ impl TextRenderer {
         viewport: &Viewport,
         row_header_width: f32,
         col_header_height: f32,
-        header_bg: &str,
-        header_border: &str,
+        paint: &HeaderPaint,
     ) {
         let first_row = viewport.first_visible_row;
         let last_row = viewport.last_visible_row.min(grid.row_count().saturating_sub(1));
 
         for row in first_row..=last_row {
             let grid_y = grid.row_y_position(row);
-            let canvas_y = grid_y - viewport.scroll_y + col_header_height;
+            let canvas_y = (grid_y - viewport.scroll_y) as f32 + col_header_height;
             let height = grid.row_height(row);
 
             // Skip if not visible
@@ -979,7 +1895,8 @@ impl TextRenderer {
             }
 
             // Draw header background (clipped)
-            self.context.set_fill_style_str(&header_bg);
+            let active = paint.active_rows.contains(&row);
+            self.context.set_fill_style_str(paint.bg(active));
             self.context.fill_rect(
                 0.0,
                 draw_y as f64,
@@ -988,7 +1905,7 @@ impl TextRenderer {
             );
 
             // Draw header border (clipped)
-            self.context.set_stroke_style_str(&header_border);
+            self.context.set_stroke_style_str(HEADER_BORDER);
             self.context.set_line_width(1.0);
             self.context.stroke_rect(
                 0.0,
@@ -997,11 +1914,11 @@ impl TextRenderer {
                 draw_height as f64,
             );
 
-            // Draw row number (1, 2, 3, ...) with clipping
-            let row_number = format!("{}", row + 1);
+            // Draw row number (1, 2, 3, ... or from row_label_start) with clipping
+            let row_number = grid.row_label(row);
             self.context.set_fill_style_str(&self.header_text_color);
             self.context.set_text_align("center");
-            self.context.set_font(&self.font_string);
+            self.context.set_font(paint.font(active, &self.font_string));
 
             let text_x = row_header_width / 2.0;
             let text_y = canvas_y + height / 2.0;
@@ -1029,3 +1946,28 @@ impl TextRenderer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contrast_text_color() {
+        assert_eq!(contrast_text_color(0x000080FF), Some("#FFFFFF")); // Navy
+        assert_eq!(contrast_text_color(0xC62828FF), Some("#FFFFFF")); // Dark red
+        assert_eq!(contrast_text_color(0xFFEB3BFF), None); // Yellow
+        assert_eq!(contrast_text_color(0xE3F2FDFF), None); // Light blue
+        assert_eq!(contrast_text_color(0x00000020), None); // Mostly transparent black shows as light gray
+    }
+
+    #[test]
+    fn test_hover_style_json() {
+        let style = HoverStyle::from_json(&serde_json::json!({ "row": true, "outline_color": "red" })).unwrap();
+        assert!(style.row && !style.column && !style.cell);
+        assert_eq!(style.outline_color, "red");
+        assert_eq!(style.band_color, HoverStyle::default().band_color);
+        assert_eq!(HoverStyle::from_json(&style.to_json()).unwrap(), style);
+        assert!(!HoverStyle::default().is_enabled());
+        assert!(HoverStyle::from_json(&serde_json::json!("row")).is_err());
+    }
+}
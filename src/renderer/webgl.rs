@@ -1,5 +1,5 @@
 use super::shader::ShaderProgram;
-use crate::core::{Grid, Viewport};
+use crate::core::{Grid, SectionBreak, Viewport};
 use crate::features::selection::SelectionState;
 use crate::GridError;
 use wasm_bindgen::JsCast;
@@ -30,8 +30,17 @@ pub struct WebGLRenderer {
 impl WebGLRenderer {
     /// Create a new WebGL renderer
     pub fn new(canvas: &HtmlCanvasElement) -> Result<Self, GridError> {
+        // Preserve the drawing buffer so differential renders can update
+        // individual cells without redrawing the whole frame
+        let context_options = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(
+            &context_options,
+            &"preserveDrawingBuffer".into(),
+            &wasm_bindgen::JsValue::TRUE,
+        );
+
         let context = canvas
-            .get_context("webgl")
+            .get_context_with_context_options("webgl", &context_options)
             .map_err(|_| GridError::RenderInitFailed {
                 error: "Failed to get WebGL context".to_string(),
             })?
@@ -104,6 +113,39 @@ impl WebGLRenderer {
             .viewport(0, 0, width as i32, height as i32);
     }
 
+    /// Describe the WebGL implementation (vendor, renderer, limits) for diagnostics
+    pub fn renderer_info(&self) -> serde_json::Value {
+        // From the WEBGL_debug_renderer_info extension
+        const UNMASKED_VENDOR_WEBGL: u32 = 0x9245;
+        const UNMASKED_RENDERER_WEBGL: u32 = 0x9246;
+
+        let gl = &self.context;
+        let parameter = |name: u32| -> serde_json::Value {
+            match gl.get_parameter(name) {
+                Ok(value) if value.is_string() => serde_json::json!(value.as_string()),
+                Ok(value) if value.as_f64().is_some() => serde_json::json!(value.as_f64()),
+                Ok(value) if value.is_instance_of::<js_sys::Int32Array>() => {
+                    serde_json::json!(js_sys::Int32Array::from(value).to_vec())
+                }
+                _ => serde_json::Value::Null,
+            }
+        };
+
+        let debug_info = gl.get_extension("WEBGL_debug_renderer_info").ok().flatten().is_some();
+        serde_json::json!({
+            "vendor": parameter(WebGlRenderingContext::VENDOR),
+            "renderer": parameter(WebGlRenderingContext::RENDERER),
+            "unmasked_vendor": if debug_info { parameter(UNMASKED_VENDOR_WEBGL) } else { serde_json::Value::Null },
+            "unmasked_renderer": if debug_info { parameter(UNMASKED_RENDERER_WEBGL) } else { serde_json::Value::Null },
+            "version": parameter(WebGlRenderingContext::VERSION),
+            "shading_language_version": parameter(WebGlRenderingContext::SHADING_LANGUAGE_VERSION),
+            "max_texture_size": parameter(WebGlRenderingContext::MAX_TEXTURE_SIZE),
+            "max_viewport_dims": parameter(WebGlRenderingContext::MAX_VIEWPORT_DIMS),
+            "max_vertex_attribs": parameter(WebGlRenderingContext::MAX_VERTEX_ATTRIBS),
+            "context_lost": gl.is_context_lost(),
+        })
+    }
+
     /// Clear the canvas
     pub fn clear(&self) {
         self.context.clear_color(1.0, 1.0, 1.0, 1.0);
@@ -117,12 +159,9 @@ impl WebGLRenderer {
         self.context.use_program(Some(&self.shader_program.program));
 
         // Set resolution uniform (same for all regions)
+        // Geometry is in grid pixels; the viewport's zoomed size scales it onto the canvas
         self.context
-            .uniform2f(Some(&self.u_resolution), self.canvas_width, self.canvas_height);
-
-        // Calculate header offset
-        let header_offset_x = if grid.show_headers { grid.row_header_width } else { 0.0 };
-        let header_offset_y = if grid.show_headers { grid.col_header_height } else { 0.0 };
+            .uniform2f(Some(&self.u_resolution), viewport.canvas_width, viewport.canvas_height);
 
         let frozen_rows = grid.frozen_rows;
         let frozen_cols = grid.frozen_cols;
@@ -135,21 +174,11 @@ impl WebGLRenderer {
 
         // Region 1: Frozen rows × Frozen cols (top-left) - no scroll
         if frozen_rows > 0 && frozen_cols > 0 {
-            self.context.uniform2f(
-                Some(&self.u_translation),
-                header_offset_x,
-                header_offset_y,
-            );
             self.render_region(grid, selection, viewport, 0, frozen_rows, 0, frozen_cols);
         }
 
         // Region 2: Frozen rows × Scrollable cols (top-right) - horizontal scroll
         if frozen_rows > 0 {
-            self.context.uniform2f(
-                Some(&self.u_translation),
-                -viewport.scroll_x + header_offset_x,
-                header_offset_y,
-            );
             self.render_region(
                 grid,
                 selection,
@@ -163,11 +192,6 @@ impl WebGLRenderer {
 
         // Region 3: Scrollable rows × Frozen cols (bottom-left) - vertical scroll
         if frozen_cols > 0 {
-            self.context.uniform2f(
-                Some(&self.u_translation),
-                header_offset_x,
-                -viewport.scroll_y + header_offset_y,
-            );
             self.render_region(
                 grid,
                 selection,
@@ -180,11 +204,6 @@ impl WebGLRenderer {
         }
 
         // Region 4: Scrollable rows × Scrollable cols (bottom-right) - both scroll
-        self.context.uniform2f(
-            Some(&self.u_translation),
-            -viewport.scroll_x + header_offset_x,
-            -viewport.scroll_y + header_offset_y,
-        );
         self.render_region(
             grid,
             selection,
@@ -198,12 +217,63 @@ impl WebGLRenderer {
         // Note: Text rendering will be done via Canvas 2D API overlay
     }
 
-    /// Render a specific region of the grid
+    /// Re-render only the given cells (differential rendering)
+    /// Each cell is cleared and redrawn inside a scissor rectangle so the rest of the
+    /// frame is left untouched. The viewport must be unchanged since the last full render.
+    pub fn render_cells(
+        &self,
+        grid: &Grid,
+        viewport: &Viewport,
+        selection: &SelectionState,
+        cells: &[(usize, usize)],
+    ) {
+        self.context.use_program(Some(&self.shader_program.program));
+        self.context
+            .uniform2f(Some(&self.u_resolution), viewport.canvas_width, viewport.canvas_height);
+
+        self.context.enable(WebGlRenderingContext::SCISSOR_TEST);
+        self.context.clear_color(1.0, 1.0, 1.0, 1.0);
+
+        for &(row, col) in cells {
+            if grid.is_row_hidden(row) {
+                continue;
+            }
+
+            let Some((x, y, width, height)) = viewport.cell_canvas_rect(row, col, grid) else {
+                continue;
+            };
+
+            // Include the 1px grid lines on the cell edges (scissor is in device pixels)
+            let zoom = viewport.zoom;
+            let x1 = ((x - 1.0) * zoom).max(0.0).floor();
+            let y1 = ((y - 1.0) * zoom).max(0.0).floor();
+            let x2 = ((x + width + 1.0) * zoom).min(self.canvas_width).ceil();
+            let y2 = ((y + height + 1.0) * zoom).min(self.canvas_height).ceil();
+
+            // Scissor box uses a bottom-left origin
+            self.context.scissor(
+                x1 as i32,
+                (self.canvas_height - y2) as i32,
+                (x2 - x1) as i32,
+                (y2 - y1) as i32,
+            );
+            self.context.clear(WebGlRenderingContext::COLOR_BUFFER_BIT);
+
+            self.render_region(grid, selection, viewport, row, row + 1, col, col + 1);
+        }
+
+        self.context.disable(WebGlRenderingContext::SCISSOR_TEST);
+    }
+
+    /// Render a specific region of the grid (the region scrolls along the axes on which it
+    /// starts past the frozen rows/columns)
+    /// Vertices are measured from the region's first row and the f64 distance to it is
+    /// folded into the translation, so rows far down a huge grid keep f32 precision
     fn render_region(
         &self,
         grid: &Grid,
         selection: &SelectionState,
-        _viewport: &Viewport,
+        viewport: &Viewport,
         row_start: usize,
         row_end: usize,
         col_start: usize,
@@ -213,6 +283,16 @@ impl WebGLRenderer {
             return;
         }
 
+        let header_offset_x = if grid.show_headers { grid.row_header_width } else { 0.0 };
+        let header_offset_y = if grid.show_headers { grid.col_header_height } else { 0.0 };
+        let scroll_x = if col_start < grid.frozen_cols { 0.0 } else { viewport.scroll_x };
+        let scroll_y = if row_start < grid.frozen_rows { 0.0 } else { viewport.scroll_y };
+        self.context.uniform2f(
+            Some(&self.u_translation),
+            -scroll_x + header_offset_x,
+            (grid.row_y_position(row_start) - scroll_y) as f32 + header_offset_y,
+        );
+
         // Render grid lines for this region
         self.render_grid_lines_region(grid, row_start, row_end, col_start, col_end);
 
@@ -221,6 +301,9 @@ impl WebGLRenderer {
 
         // Render cell borders for this region
         self.render_cell_borders_region(grid, row_start, row_end, col_start, col_end);
+
+        // Render section separators over the lines
+        self.render_section_breaks_region(grid, row_start, row_end, col_start, col_end);
     }
 
     /// Render grid lines for a specific region
@@ -244,15 +327,20 @@ impl WebGLRenderer {
         } else {
             grid.total_width()
         };
-        let y_start = grid.row_y_position(row_start);
+        let origin = grid.row_y_position(row_start);
+        let y_start = 0.0;
         let y_end = if row_end < grid.row_count() {
             grid.row_y_position(row_end)
         } else {
             grid.total_height()
         };
+        let y_end = (y_end - origin) as f32;
 
         // Vertical lines (columns)
         for col in col_start..=col_end.min(grid.col_count()) {
+            if col > 0 && grid.col_section_break(col - 1) == Some(SectionBreak::Hidden) {
+                continue;
+            }
             let x = grid.col_x_position(col);
 
             // Line from top to bottom of region
@@ -263,7 +351,10 @@ impl WebGLRenderer {
 
         // Horizontal lines (rows)
         for row in row_start..=row_end.min(grid.row_count()) {
-            let y = grid.row_y_position(row);
+            if row > 0 && grid.row_section_break(row - 1) == Some(SectionBreak::Hidden) {
+                continue;
+            }
+            let y = (grid.row_y_position(row) - origin) as f32;
 
             // Line from left to right of region
             positions.extend_from_slice(&[x_start, y, x_end, y]);
@@ -286,15 +377,16 @@ impl WebGLRenderer {
     ) {
         let mut positions: Vec<f32> = Vec::new();
         let mut colors: Vec<f32> = Vec::new();
+        let origin = grid.row_y_position(row_start);
 
         for row in row_start..row_end.min(grid.row_count()) {
-            if grid.is_row_filtered(row) {
+            if grid.is_row_hidden(row) {
                 continue;
             }
 
             for col in col_start..col_end.min(grid.col_count()) {
                 let x = grid.col_x_position(col);
-                let y = grid.row_y_position(row);
+                let y = (grid.row_y_position(row) - origin) as f32;
                 let width = grid.col_width(col);
                 let height = grid.row_height(row);
 
@@ -356,27 +448,32 @@ impl WebGLRenderer {
         let mut colors: Vec<f32> = Vec::new();
 
         let border_color = [0.6, 0.6, 0.6, 1.0]; // Dark gray
+        let origin = grid.row_y_position(row_start);
 
         for row in row_start..row_end.min(grid.row_count()) {
-            if grid.is_row_filtered(row) {
+            if grid.is_row_hidden(row) {
                 continue;
             }
 
             for col in col_start..col_end.min(grid.col_count()) {
                 let x = grid.col_x_position(col);
-                let y = grid.row_y_position(row);
+                let y = (grid.row_y_position(row) - origin) as f32;
                 let width = grid.col_width(col);
                 let height = grid.row_height(row);
 
                 // Right border
-                positions.extend_from_slice(&[x + width, y, x + width, y + height]);
-                colors.extend_from_slice(&border_color);
-                colors.extend_from_slice(&border_color);
+                if grid.col_section_break(col) != Some(SectionBreak::Hidden) {
+                    positions.extend_from_slice(&[x + width, y, x + width, y + height]);
+                    colors.extend_from_slice(&border_color);
+                    colors.extend_from_slice(&border_color);
+                }
 
                 // Bottom border
-                positions.extend_from_slice(&[x, y + height, x + width, y + height]);
-                colors.extend_from_slice(&border_color);
-                colors.extend_from_slice(&border_color);
+                if grid.row_section_break(row) != Some(SectionBreak::Hidden) {
+                    positions.extend_from_slice(&[x, y + height, x + width, y + height]);
+                    colors.extend_from_slice(&border_color);
+                    colors.extend_from_slice(&border_color);
+                }
             }
         }
 
@@ -385,6 +482,33 @@ impl WebGLRenderer {
         }
     }
 
+    /// Render section separators for a specific region (filled, since WebGL lines are 1px)
+    fn render_section_breaks_region(
+        &self,
+        grid: &Grid,
+        row_start: usize,
+        row_end: usize,
+        col_start: usize,
+        col_end: usize,
+    ) {
+        let mut positions: Vec<f32> = Vec::new();
+        let mut colors: Vec<f32> = Vec::new();
+
+        let break_color = [0.25, 0.25, 0.25, 1.0]; // Near black
+
+        for [x, y, width, height] in grid.section_break_rects(row_start, row_end, col_start, col_end) {
+            let (x2, y2) = (x + width, y + height);
+            positions.extend_from_slice(&[x, y, x2, y, x, y2, x, y2, x2, y, x2, y2]);
+            for _ in 0..6 {
+                colors.extend_from_slice(&break_color);
+            }
+        }
+
+        if !positions.is_empty() {
+            self.draw_triangles(&positions, &colors);
+        }
+    }
+
     /// Draw lines
     fn draw_lines(&self, positions: &[f32], colors: &[f32]) {
         if positions.is_empty() {
@@ -48,6 +48,7 @@ export class DataGridWrapper {
         this._onTextCanvasMouseDown = this._onTextCanvasMouseDown.bind(this);
         this._onTextCanvasMouseMove = this._onTextCanvasMouseMove.bind(this);
         this._onTextCanvasMouseUp = this._onTextCanvasMouseUp.bind(this);
+        this._onTextCanvasMouseLeave = this._onTextCanvasMouseLeave.bind(this);
         this._onTextCanvasDoubleClick = this._onTextCanvasDoubleClick.bind(this);
         this._onTextCanvasKeyDown = this._onTextCanvasKeyDown.bind(this);
         this._onTextCanvasWheel = this._onTextCanvasWheel.bind(this);
@@ -248,6 +249,7 @@ export class DataGridWrapper {
         this.textCanvas.addEventListener('mousedown', this._onTextCanvasMouseDown);
         this.textCanvas.addEventListener('mousemove', this._onTextCanvasMouseMove);
         this.textCanvas.addEventListener('mouseup', this._onTextCanvasMouseUp);
+        this.textCanvas.addEventListener('mouseleave', this._onTextCanvasMouseLeave);
 
         if (this.options.enableEditing) {
             this.textCanvas.addEventListener('dblclick', this._onTextCanvasDoubleClick);
@@ -283,6 +285,23 @@ export class DataGridWrapper {
             return;
         }
 
+        // Filter buttons let the application open its filter UI under the button
+        const filterButton = this.grid.get_filter_button_at(x, y);
+        if (filterButton) {
+            this.container.dispatchEvent(new CustomEvent('gridfilterbutton', {
+                detail: JSON.parse(filterButton)
+            }));
+            e.preventDefault();
+            return;
+        }
+
+        // Dragging the selection's outline moves the range (Ctrl/Cmd copies)
+        if (!e.shiftKey && this.grid.start_range_move(x, y, e.ctrlKey || e.metaKey)) {
+            e.preventDefault();
+            this.requestRender();
+            return;
+        }
+
         // Handle cell selection with modifiers
         this._log('calling handle_mouse_down_at_with_modifiers');
         this.grid.handle_mouse_down_at_with_modifiers(
@@ -305,6 +324,8 @@ export class DataGridWrapper {
             this.textCanvas.style.cursor = 'col-resize';
         } else if (resizeType === 'row') {
             this.textCanvas.style.cursor = 'row-resize';
+        } else if (this.grid.is_moving_range() || this.grid.check_range_move_handle(x, y)) {
+            this.textCanvas.style.cursor = (e.ctrlKey || e.metaKey) ? 'copy' : 'move';
         } else {
             this.textCanvas.style.cursor = 'cell';
         }
@@ -313,15 +334,25 @@ export class DataGridWrapper {
         if (this.grid.is_resizing()) {
             this.grid.update_resize(x, y);
             this.requestRender();
+        } else if (this.grid.is_moving_range()) {
+            this.grid.update_range_move(x, y, e.ctrlKey || e.metaKey);
+            this.requestRender();
         } else {
             this.grid.handle_mouse_move(e);
-            // Render if drag-selecting to show live selection preview
-            if (this.grid.is_selecting()) {
+            // Render if drag-selecting to show live selection preview, or if the hover highlight moved
+            if (this.grid.is_selecting() || this.grid.needs_full_render()) {
                 this.requestRender();
             }
         }
     }
 
+    _onTextCanvasMouseLeave() {
+        this.grid.handle_mouse_leave();
+        if (this.grid.needs_full_render()) {
+            this.requestRender(); // Clear the hover highlight
+        }
+    }
+
     _onTextCanvasMouseUp(e) {
         const rect = this.textCanvas.getBoundingClientRect();
         const x = e.clientX - rect.left;
@@ -331,6 +362,9 @@ export class DataGridWrapper {
             this.grid.end_resize();
             this.updateVirtualScrollSize();  // Update scroll size after resize
             this.requestRender();
+        } else if (this.grid.is_moving_range()) {
+            this.grid.end_range_move();
+            this.requestRender();
         } else {
             this.grid.handle_mouse_up(x, y);
             this.requestRender();
@@ -346,6 +380,8 @@ export class DataGridWrapper {
         if (cellInfo) {
             const [row, col] = JSON.parse(cellInfo);
             this.startCellEdit(row, col);
+        } else {
+            this.requestRender(); // A resize handle double-click may have auto-fit a column
         }
     }
 
@@ -355,8 +391,10 @@ export class DataGridWrapper {
             return;
         }
 
-        const isCtrl = e.ctrlKey || e.metaKey;
-        const isAlt = e.altKey;
+        // AltGr reports Ctrl+Alt on Windows but types characters
+        const altGraph = e.getModifierState && e.getModifierState('AltGraph');
+        const isCtrl = (e.ctrlKey || e.metaKey) && !altGraph;
+        const isAlt = e.altKey && !altGraph;
 
         // Handle clipboard operations
         if (isCtrl) {
@@ -378,8 +416,11 @@ export class DataGridWrapper {
             }
         }
 
+        // Keys on a focused header (Enter sorts, Space selects) go to the grid, not the editor
+        const headerFocused = this.grid.get_header_focus() !== '';
+
         // Handle F2 key to start editing
-        if (e.key === 'F2' && this.options.enableEditing) {
+        if (e.key === 'F2' && this.options.enableEditing && !headerFocused) {
             e.preventDefault();
             const selectedCell = this.getSelectedCell();
             if (selectedCell) {
@@ -389,9 +430,21 @@ export class DataGridWrapper {
             return;
         }
 
+        // An IME starting a composition opens an empty editor; the default action is kept
+        // so the composition continues in the focused editor
+        if (this.options.enableEditing && !headerFocused && (e.isComposing || e.key === 'Process')) {
+            const selectedCell = this.getSelectedCell();
+            if (selectedCell && this.editingRow === null) {
+                const [row, col] = selectedCell;
+                this.startCellEdit(row, col, '');
+                return;
+            }
+        }
+
         // Handle printable character input to start editing
         // Check if it's a printable character (single char, no modifiers except Shift)
         if (this.options.enableEditing &&
+            !headerFocused &&
             e.key.length === 1 &&
             !isCtrl &&
             !isAlt &&
@@ -415,6 +468,7 @@ export class DataGridWrapper {
         if (handled) {
             e.preventDefault();
             this.syncScrollPosition();
+            this.updateVirtualScrollSize();  // Keyboard resize on a header may change the size
             this.requestRender();
         }
     }
@@ -755,6 +809,29 @@ export class DataGridWrapper {
         }
     }
 
+    commitEditToSelection() {
+        if (!this.grid || this.editingRow === null || this.editingCol === null) return;
+
+        const row = this.editingRow;
+        const col = this.editingCol;
+        const oldValue = this.grid.get_cell_value(row, col);
+        const newValue = this.cellEditor.value;
+        this.editingRow = null;
+        this.editingCol = null;
+
+        // Writes every selected cell as one undo step and ends edit mode
+        const cells = this.grid.commit_edit_to_selection(newValue);
+
+        this.cellEditor.style.display = 'none';
+        this.cellEditor.value = '';
+        this.requestRender();
+
+        this.container.dispatchEvent(new CustomEvent('celleditend', {
+            detail: { row, col, oldValue, newValue, changed: cells > 0, saved: true, cells }
+        }));
+        this.textCanvas.focus();
+    }
+
     setupEditorEvents() {
         // Setup event listeners using bound methods (for proper cleanup)
         this.cellEditor.addEventListener('compositionstart', this._onEditorCompositionStart);
@@ -793,7 +870,11 @@ export class DataGridWrapper {
             return;
         }
 
-        if (e.key === 'Enter') {
+        if (e.key === 'Enter' && (e.ctrlKey || e.metaKey)) {
+            // Ctrl+Enter: write the value into every selected cell
+            e.preventDefault();
+            this.commitEditToSelection();
+        } else if (e.key === 'Enter') {
             e.preventDefault();
             this.endCellEdit(true, true, false);
         } else if (e.key === 'Tab') {
@@ -910,6 +991,7 @@ export class DataGridWrapper {
             this.textCanvas.removeEventListener('mousedown', this._onTextCanvasMouseDown);
             this.textCanvas.removeEventListener('mousemove', this._onTextCanvasMouseMove);
             this.textCanvas.removeEventListener('mouseup', this._onTextCanvasMouseUp);
+            this.textCanvas.removeEventListener('mouseleave', this._onTextCanvasMouseLeave);
             this.textCanvas.removeEventListener('dblclick', this._onTextCanvasDoubleClick);
             this.textCanvas.removeEventListener('keydown', this._onTextCanvasKeyDown);
             this.textCanvas.removeEventListener('wheel', this._onTextCanvasWheel);
@@ -1000,8 +1082,8 @@ export class DataGridWrapper {
             const tsvData = this.grid.copy_selected_cells();
 
             if (tsvData) {
-                // Copy to system clipboard
-                navigator.clipboard.writeText(tsvData).then(() => {
+                // Copy to system clipboard (TSV and HTML table)
+                this.grid.copy_to_clipboard().then(() => {
                     this._log('Copied to clipboard');
 
                     // Emit custom event
@@ -1025,13 +1107,14 @@ export class DataGridWrapper {
 
     handleCut() {
         try {
-            const tsvData = this.grid.cut_selected_cells();
+            const tsvData = this.grid.copy_selected_cells();
 
             if (tsvData) {
+                // Copy to system clipboard (TSV and HTML table) and clear the cells
+                const written = this.grid.cut_to_clipboard();
                 this.requestRender();
 
-                // Copy to system clipboard
-                navigator.clipboard.writeText(tsvData).then(() => {
+                written.then(() => {
                     this._log('Cut to clipboard');
 
                     // Emit custom event
@@ -1056,33 +1139,68 @@ export class DataGridWrapper {
     async handlePaste() {
         try {
             let tsvData;
+            let htmlData = null;
 
-            // Try to read from system clipboard
+            // Try to read from system clipboard (HTML table from Excel/Sheets first)
             try {
-                tsvData = await navigator.clipboard.readText();
+                for (const item of await navigator.clipboard.read()) {
+                    if (item.types.includes('text/html')) {
+                        htmlData = await (await item.getType('text/html')).text();
+                    }
+                    if (item.types.includes('text/plain')) {
+                        tsvData = await (await item.getType('text/plain')).text();
+                    }
+                }
             } catch (err) {
-                this._log('Cannot read from clipboard, using fallback');
-                // Fallback: use memory clipboard
-                tsvData = this.clipboardData || '';
+                try {
+                    tsvData = await navigator.clipboard.readText();
+                } catch (err) {
+                    this._log('Cannot read from clipboard, using fallback');
+                    // Fallback: use memory clipboard
+                    tsvData = this.clipboardData || '';
+                }
             }
 
-            if (tsvData) {
-                // Paste into grid
-                this.grid.paste_cells(tsvData);
-                this._log('Pasted from clipboard');
-
-                // Emit custom event
-                this.container.dispatchEvent(new CustomEvent('gridpaste', {
-                    detail: { data: tsvData }
-                }));
-
-                this.requestRender();
+            if (htmlData && /<table/i.test(htmlData)) {
+                this._pasteWithPolicy(htmlData, this.grid.get_paste_policy(), 'html');
+            } else if (tsvData) {
+                this._pasteWithPolicy(tsvData, this.grid.get_paste_policy());
             }
         } catch (err) {
             console.error('[Wrapper] Paste error:', err);
         }
     }
 
+    // Paste using a conflict policy; with "prompt", conflicts are reported through a
+    // 'gridpasteconflict' event whose detail.resolve('overwrite' | 'skip') completes the paste
+    // format: 'text' (TSV) or 'html' (table copied from a spreadsheet, keeps spans and styles)
+    _pasteWithPolicy(data, policy, format = 'text') {
+        const result = JSON.parse(format === 'html'
+            ? this.grid.paste_html_with_policy(data, policy)
+            : this.grid.paste_cells_with_policy(data, policy));
+
+        if (!result.applied) {
+            this.container.dispatchEvent(new CustomEvent('gridpasteconflict', {
+                detail: {
+                    data,
+                    format,
+                    conflicts: result.conflicts,
+                    resolve: (resolvedPolicy) => this._pasteWithPolicy(data, resolvedPolicy, format)
+                }
+            }));
+            return;
+        }
+
+        this._log('Pasted from clipboard');
+
+        // Emit custom event
+        this.container.dispatchEvent(new CustomEvent('gridpaste', {
+            detail: { data, format, pasted: result.pasted, conflicts: result.conflicts }
+        }));
+
+        this.requestRender();
+    }
+
     // Manual clipboard methods
     copy() {
         this.handleCopy();
@@ -1094,8 +1212,7 @@ export class DataGridWrapper {
 
     paste(tsvData) {
         if (tsvData) {
-            this.grid.paste_cells(tsvData);
-            this.requestRender();
+            this._pasteWithPolicy(tsvData, this.grid.get_paste_policy());
         } else {
             this.handlePaste();
         }
@@ -1133,4 +1250,14 @@ export class DataGridWrapper {
     clearRedoHistory() {
         this.grid.clear_redo_history();
     }
+
+    // Run fn without recording undo history; recording resumes even if fn throws
+    withUndoSuspended(fn) {
+        this.grid.suspend_undo();
+        try {
+            return fn();
+        } finally {
+            this.grid.resume_undo();
+        }
+    }
 }